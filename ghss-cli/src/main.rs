@@ -1,22 +1,58 @@
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{Context, bail};
-use clap::{Parser, ValueEnum};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use clap_verbosity_flag::{Verbosity, WarnLevel};
 use tracing_subscriber::{EnvFilter, fmt};
 
+use ghss::advisory::AdvisoryFilter;
+use ghss::baseline::Baseline;
+use ghss::checkpoint::Checkpoint;
+use ghss::config::{self, Config, Policy};
 use ghss::depth::DepthLimit;
+use ghss::duplicates;
+use ghss::epss::EpssClient;
+use ghss::explain;
+use ghss::finding::{self, Finding};
 use ghss::github::GitHubClient;
-use ghss::output::{self, AuditNode, OutputFormat};
+use ghss::ignore::{self, IgnoreList, InlineSuppressions};
+use ghss::knowledge::{self, KnowledgeBase};
+use ghss::lint::Linter;
+use ghss::location::SourceLocation;
+use ghss::output::{self, AuditNode, AuditReport, OutputFormat};
+use ghss::overrides::Overrides;
+use ghss::permissions;
 use ghss::pipeline::PipelineBuilder;
-use ghss::providers;
+use ghss::policy::{self, Decision};
+use ghss::providers::health::{self, HealthStatus};
+use ghss::providers::osv_db::{self, OsvDatabase};
+use ghss::providers::snyk::{SnykConfig, SnykProvider};
+use ghss::providers::{Providers, depsdev::DepsDevClient};
+use ghss::rules::Rules;
+use ghss::runtime;
+use ghss::scorecard::ScorecardClient;
+use ghss::signing::{self, ReportSignature};
+use ghss::stages::repository::DEFAULT_STALE_AFTER_MONTHS;
 use ghss::stages::{
-    AdvisoryStage, CompositeExpandStage, DependencyStage, RefResolveStage, ScanStage,
-    WorkflowExpandStage,
+    AdvisoryStage, CommitOwnershipStage, CompositeExpandStage, DependencyStage, EpssStage,
+    LicenseStage, ProjectInfoStage, ProvenanceStage, RecommendStage, RefResolveStage, ReleaseStage,
+    RepoHygieneStage, RepoMetadataStage, ReputationStage, ScanStage, ScorecardStage,
+    SignatureStage, TagMovementStage, TagProtectionStage, UpdateHygieneStage, WorkflowExpandStage,
+    updates,
 };
+use ghss::tag_history::TagHistory;
+use ghss::template::{self, TemplateProperties};
+use ghss::trust::TrustedOwners;
+use ghss::usage::{self, UsageQuery};
+use ghss::vex::{self, VexDocument};
 use ghss::walker::Walker;
 
-/// Output format for audit results.
+/// Output format for subcommand results.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "lower")]
 enum CliOutputFormat {
@@ -25,40 +61,148 @@ enum CliOutputFormat {
     Sarif,
 }
 
-impl From<CliOutputFormat> for OutputFormat {
-    fn from(value: CliOutputFormat) -> Self {
+/// Output format for the audit, which can also write OpenVEX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lower")]
+enum AuditOutputFormat {
+    Text,
+    Json,
+    Sarif,
+    OpenVex,
+}
+
+impl From<AuditOutputFormat> for OutputFormat {
+    fn from(value: AuditOutputFormat) -> Self {
         match value {
-            CliOutputFormat::Text => OutputFormat::Text,
-            CliOutputFormat::Json => OutputFormat::Json,
-            CliOutputFormat::Sarif => OutputFormat::Sarif,
+            AuditOutputFormat::Text => OutputFormat::Text,
+            AuditOutputFormat::Json => OutputFormat::Json,
+            AuditOutputFormat::Sarif => OutputFormat::Sarif,
+            AuditOutputFormat::OpenVex => OutputFormat::OpenVex,
         }
     }
 }
 
+/// The process exit status, the contract scripts and CI gates rely on.
+/// Kept in step with [`EXIT_CODES`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    /// Nothing failed.
+    Clean = 0,
+    /// Findings at or above a `--fail-on*` threshold, or a subcommand's
+    /// check failed.
+    Findings = 1,
+    /// Invalid arguments or input, or an error that stopped the run. clap
+    /// uses the same code for argument errors.
+    Usage = 2,
+    /// The run completed, but lookups failed, so results are incomplete.
+    StageErrors = 3,
+}
+
+/// The exit code contract, shown after `--help`.
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  clean: no findings at or above a --fail-on* threshold
+  1  findings at or above a --fail-on* threshold, or a subcommand's check failed
+  2  usage error: invalid arguments or input, or an error that stopped the run
+  3  scan completed with stage errors (with --fail-on errors or --strict), so results are incomplete";
+
+/// A condition that fails the run (`--fail-on`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lower")]
+enum FailOn {
+    /// An action referenced by tag or branch, as with --require-pinned
+    Unpinned,
+    /// A stage that failed for an action, leaving its results incomplete
+    Errors,
+}
+
 /// Audit GitHub Actions workflows for third-party action usage
 #[derive(Parser)]
-#[command(name = "ghss", version)]
+#[command(
+    name = "ghss",
+    version,
+    subcommand_negates_reqs = true,
+    after_help = EXIT_CODES
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to a GitHub Actions workflow YAML file
-    #[arg(short, long)]
-    file: PathBuf,
+    #[arg(short, long, required = true)]
+    file: Option<PathBuf>,
+
+    /// Config file (TOML, or YAML if named *.yml) of defaults for the scan,
+    /// GitHub token and service URLs, and a `[policy]` of fail thresholds,
+    /// pinning, trusted and forbidden owners and disabled rules; flags on the
+    /// command line win [default: each ghss.toml or .ghss.yml from the
+    /// workflow's directory up to the repository root, layered; else one in
+    /// the current directory or ~/.config/ghss] (see README)
+    #[arg(long, value_name = "PATH", env = "GHSS_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Don't look for a config file when --config is not given
+    #[arg(long, env = "GHSS_NO_CONFIG", conflicts_with = "config")]
+    no_config: bool,
 
-    /// Advisory provider to use (ghsa, osv, or all)
-    #[arg(long, default_value = "all")]
+    /// Profile in the config file's `[profiles]`, such as quick, deep or
+    /// ci, whose settings replace its `[scan]` and add to its `[policy]`
+    #[arg(
+        long,
+        env = "GHSS_PROFILE",
+        value_name = "NAME",
+        conflicts_with = "no_config"
+    )]
+    profile: Option<String>,
+
+    /// The `[policy]` of the config file, once loaded.
+    #[arg(skip)]
+    policy: Policy,
+
+    /// Advisory provider to use (ghsa, ghsa-graphql, osv, osv-offline, depsdev, or all)
+    #[arg(long, env = "GHSS_PROVIDER", default_value = "all", global = true)]
     provider: String,
 
-    /// Output format for results (text, json, sarif).
+    /// Also query Snyk for dependency advisories, using `SNYK_TOKEN` and
+    /// `SNYK_ORG_ID`
+    #[arg(long, env = "GHSS_SNYK", global = true)]
+    snyk: bool,
+
+    /// Seconds to wait for each advisory provider per action before
+    /// recording a timeout and keeping the other providers' results
+    #[arg(long, env = "GHSS_PROVIDER_TIMEOUT", value_name = "SECS", default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    provider_timeout: u64,
+
+    /// Keep advisories their source has withdrawn (dropped by default)
+    #[arg(long, env = "GHSS_INCLUDE_WITHDRAWN")]
+    include_withdrawn: bool,
+
+    /// Show each advisory's full description and reference links, not
+    /// just its summary (text and JSON output)
+    #[arg(long, env = "GHSS_DETAILS")]
+    details: bool,
+
+    /// Only report advisories published on or after this date
+    /// (YYYY-MM-DD or RFC 3339); advisories without a date are kept
+    #[arg(long, value_name = "DATE", value_parser = parse_since)]
+    since: Option<DateTime<Utc>>,
+
+    /// Output format for results (text, json, sarif, openvex).
     /// SARIF output expects --file to be a repo-relative path so the
     /// emitted artifactLocation is usable by GitHub Code Scanning.
-    #[arg(long, value_enum, default_value_t = CliOutputFormat::Text, conflicts_with = "json")]
-    format: CliOutputFormat,
+    #[arg(long, env = "GHSS_FORMAT", value_enum, default_value_t = AuditOutputFormat::Text, conflicts_with = "json")]
+    format: AuditOutputFormat,
 
     /// Deprecated: use --format json. Kept for back-compat with existing scripts.
     #[arg(long, hide = true)]
     json: bool,
 
+    /// Maximum number of actions audited at once
+    #[arg(long, env = "GHSS_CONCURRENCY", value_name = "N", default_value_t = 10, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    concurrency: usize,
+
     /// Recursive expansion depth for composite actions and reusable workflows (0 = no expansion, "unlimited" = full traversal)
-    #[arg(long, default_value = "0")]
+    #[arg(long, env = "GHSS_DEPTH", default_value = "0")]
     depth: DepthLimit,
 
     /// Select which root actions to audit (all, or 1-indexed ranges like 1-3,5)
@@ -66,41 +210,424 @@ struct Cli {
     select: Option<ghss::ActionSelection>,
 
     /// Scan action ecosystems and npm dependencies for known vulnerabilities
-    #[arg(long)]
+    #[arg(long, env = "GHSS_DEPS")]
     deps: bool,
 
-    /// Fail with exit code 2 if any advisory meets or exceeds this severity (critical, high, medium, low)
-    #[arg(long, value_name = "LEVEL")]
+    /// Fail with exit code 1 if any advisory or finding meets or exceeds this severity (critical, high, medium, low)
+    #[arg(long, env = "GHSS_FAIL_ON_SEVERITY", value_name = "LEVEL")]
     fail_on_severity: Option<ghss::advisory::Severity>,
 
+    /// Also fail on these conditions (comma-separated): `unpinned` action
+    /// references (exit code 1), stage `errors` (exit code 3)
+    #[arg(
+        long,
+        env = "GHSS_FAIL_ON",
+        value_enum,
+        value_delimiter = ',',
+        value_name = "CONDITION"
+    )]
+    fail_on: Vec<FailOn>,
+
+    /// Fail with exit code 1 if any third-party action is referenced by tag or
+    /// branch instead of a full commit SHA
+    #[arg(long, env = "GHSS_REQUIRE_PINNED")]
+    require_pinned: bool,
+
+    /// Fail with exit code 3 on anything that left the audit incomplete:
+    /// stage errors, as with --fail-on errors, and local actions that could
+    /// not be read, each listed on stderr
+    #[arg(long, env = "GHSS_STRICT")]
+    strict: bool,
+
+    /// Owner whose actions and reusable workflows are first-party, such as
+    /// your own organization: their advisories, unpinned refs and
+    /// `secrets: inherit` are not reported (repeatable or comma-separated)
+    #[arg(
+        long = "trusted-owner",
+        value_name = "OWNER",
+        env = "GHSS_TRUSTED_OWNERS",
+        value_delimiter = ','
+    )]
+    trusted_owners: Vec<String>,
+
     /// GitHub personal access token (or set `GITHUB_TOKEN` env var)
-    #[arg(long, env = "GITHUB_TOKEN")]
+    #[arg(long, env = "GITHUB_TOKEN", global = true)]
     github_token: Option<String>,
 
     /// GitHub App ID (alternative to --github-token)
-    #[arg(long, env = "GITHUB_APP_ID")]
+    #[arg(long, env = "GITHUB_APP_ID", global = true)]
     github_app_id: Option<u64>,
 
     /// GitHub App installation ID (alternative to --github-token)
-    #[arg(long, env = "GITHUB_APP_INSTALLATION_ID")]
+    #[arg(long, env = "GITHUB_APP_INSTALLATION_ID", global = true)]
     github_app_installation_id: Option<u64>,
 
     /// Path to GitHub App private key PEM file (alternative to --github-token)
-    #[arg(long, env = "GITHUB_APP_PRIVATE_KEY_PATH")]
+    #[arg(long, env = "GITHUB_APP_PRIVATE_KEY_PATH", global = true)]
     github_app_private_key_path: Option<PathBuf>,
 
+    /// Also print GitHub Actions workflow commands (::error file=...) to stderr
+    /// so findings show up as inline annotations when run inside Actions
+    #[arg(long, env = "GHSS_ANNOTATIONS")]
+    annotations: bool,
+
+    /// Workflow run to correlate with: marks which audited actions executed in it (requires --repo)
+    #[arg(long, value_name = "ID", requires = "repo")]
+    run_id: Option<u64>,
+
+    /// Repository (owner/name) that --run-id belongs to
+    #[arg(long, value_name = "OWNER/REPO", requires = "run_id")]
+    repo: Option<String>,
+
+    /// Also check each audited action's repository for Dependabot or Renovate
+    /// config that keeps its own action refs updated
+    #[arg(long, env = "GHSS_UPDATE_HYGIENE")]
+    update_hygiene: bool,
+
+    /// Also look up each action repository's license and OpenSSF Scorecard
+    /// score on deps.dev (no token needed)
+    #[arg(long, env = "GHSS_PROJECT_INFO")]
+    project_info: bool,
+
+    /// Also list each action repository's releases and report how far the
+    /// pinned version is behind the latest, flagging refs two or more majors
+    /// behind
+    #[arg(long, env = "GHSS_RELEASES")]
+    releases: bool,
+
+    /// Also find, for each action not pinned to a commit or with an
+    /// applicable advisory, the latest release with no known advisory and
+    /// its commit SHA, reported as `recommended_ref`
+    #[arg(long, env = "GHSS_RECOMMEND")]
+    recommend: bool,
+
+    /// Remember which commit each release tag resolved to in this JSON file,
+    /// and flag tags that have moved since an earlier scan or differ from
+    /// the commit their release was published from
+    #[arg(long, env = "GHSS_TAG_HISTORY", value_name = "PATH")]
+    tag_history: Option<PathBuf>,
+
+    /// Also read each action repository's metadata and flag archived or
+    /// disabled repositories
+    #[arg(long, env = "GHSS_REPO_METADATA")]
+    repo_metadata: bool,
+
+    /// With --repo-metadata, flag action repositories with no push or
+    /// release in this many months
+    #[arg(long, env = "GHSS_STALE_AFTER", value_name = "MONTHS", default_value_t = DEFAULT_STALE_AFTER_MONTHS)]
+    stale_after: u32,
+
+    /// Also report each action repository's stars, forks, contributor
+    /// count, and owner account age
+    #[arg(long, env = "GHSS_REPUTATION")]
+    reputation: bool,
+
+    /// Also fetch each action repository's OpenSSF Scorecard score and
+    /// failing checks (no token needed)
+    #[arg(long, env = "GHSS_SCORECARD")]
+    scorecard: bool,
+
+    /// Flag action repositories whose Scorecard score is below this, from 0
+    /// to 10 (implies --scorecard)
+    #[arg(long, env = "GHSS_MIN_SCORECARD", value_name = "SCORE", value_parser = parse_score)]
+    min_scorecard: Option<f64>,
+
+    /// Check the artifact attestations of each action's pinned release for
+    /// SLSA provenance built from the pinned commit
+    #[arg(long, env = "GHSS_PROVENANCE")]
+    provenance: bool,
+
+    /// Also report whether each action's commit, and its tag when
+    /// annotated, carries a verified GPG, SSH, or Sigstore signature
+    #[arg(long, env = "GHSS_SIGNATURES")]
+    signatures: bool,
+
+    /// Flag actions whose tag and commit both lack a verified signature
+    /// (implies --signatures)
+    #[arg(long, env = "GHSS_REQUIRE_SIGNED")]
+    require_signed: bool,
+
+    /// Also report each action repository's license as an SPDX identifier
+    #[arg(long, env = "GHSS_LICENSE")]
+    license: bool,
+
+    /// Flag actions under this license: an SPDX identifier, or a family
+    /// prefix like AGPL (repeatable; implies --license)
+    #[arg(
+        long = "deny-license",
+        env = "GHSS_DENY_LICENSE",
+        value_delimiter = ',',
+        value_name = "SPDX"
+    )]
+    deny_licenses: Vec<String>,
+
+    /// Also report whether each action's tag is kept in place by an
+    /// immutable release or a tag ruleset
+    #[arg(long, env = "GHSS_TAG_PROTECTION")]
+    tag_protection: bool,
+
+    /// Also report whether each action repository publishes a security
+    /// policy and has private vulnerability reporting enabled
+    #[arg(long, env = "GHSS_REPO_HYGIENE")]
+    repo_hygiene: bool,
+
+    /// Check that each commit SHA pin belongs to the named repository, on
+    /// its default branch or a tag, rather than to a fork
+    #[arg(long, env = "GHSS_VERIFY_PINS")]
+    verify_pins: bool,
+
+    /// Also look up FIRST EPSS exploit probabilities for advisories with a
+    /// CVE alias, including dependency advisories (no token needed)
+    #[arg(long, env = "GHSS_EPSS")]
+    epss: bool,
+
+    /// JSON file of action permissions and risky inputs, layered over the
+    /// bundled knowledge base (same format as ghss/data/popular-actions.json)
+    #[arg(long, value_name = "PATH", env = "GHSS_KNOWLEDGE_BASE")]
+    knowledge_base: Option<PathBuf>,
+
+    /// YAML file of `not_affected` dependency overrides: advisories that match
+    /// a package's version data but don't apply (see README)
+    #[arg(long, value_name = "PATH", env = "GHSS_OVERRIDES")]
+    overrides: Option<PathBuf>,
+
+    /// YAML file of custom rules checked against every audited action use
+    /// and reported as findings, and of built-in rules to disable (see README)
+    #[arg(long, value_name = "PATH", env = "GHSS_RULES")]
+    rules: Option<PathBuf>,
+
+    /// Action (`owner/repo[/path][@ref]`, `*` matches anything) or owner
+    /// that no workflow may use; each use is a critical `forbidden-action`
+    /// finding (repeatable)
+    #[arg(
+        long,
+        env = "GHSS_FORBID",
+        value_delimiter = ',',
+        value_name = "PATTERN"
+    )]
+    forbid: Vec<String>,
+
+    /// File of advisory IDs, rule IDs and actions to ignore [default:
+    /// .ghssignore at the repository root, if present] (see README)
+    #[arg(long, value_name = "PATH", env = "GHSS_IGNORE_FILE")]
+    ignore_file: Option<PathBuf>,
+
+    /// JSON report from an earlier run (--format json): advisories and
+    /// findings it already has are left out, so only new ones are reported
+    #[arg(long, env = "GHSS_BASELINE", value_name = "PATH")]
+    baseline: Option<PathBuf>,
+
+    /// Write the advisories suppressed by --overrides as an OpenVEX document
+    #[arg(long, value_name = "PATH", requires = "overrides")]
+    openvex_out: Option<PathBuf>,
+
+    /// OpenVEX document (path or URL) to apply to matching advisories.
+    /// not_affected and fixed statements keep them from failing the run.
+    /// Repeatable; later statements win.
+    #[arg(
+        long = "vex",
+        env = "GHSS_VEX",
+        value_delimiter = ',',
+        value_name = "FILE|URL"
+    )]
+    vex: Vec<String>,
+
+    /// Lower concurrency as the GitHub rate-limit budget runs low and raise it
+    /// again while there is headroom, instead of a fixed limit
+    #[arg(long, env = "GHSS_ADAPTIVE_CONCURRENCY")]
+    adaptive_concurrency: bool,
+
+    /// Save walk progress to this file so an interrupted scan can be
+    /// continued with --resume
+    #[arg(long, value_name = "PATH")]
+    checkpoint: Option<PathBuf>,
+
+    /// Continue the walk saved in this checkpoint instead of starting over.
+    /// Progress keeps being saved to --checkpoint, or to this file
+    #[arg(long, value_name = "PATH")]
+    resume: Option<PathBuf>,
+
+    /// Sign the emitted report with this ed25519 private key (PKCS#8 PEM)
+    #[arg(long, value_name = "PEM", requires = "signature")]
+    sign_key: Option<PathBuf>,
+
+    /// Where to write the detached report signature (requires --sign-key)
+    #[arg(long, value_name = "PATH", requires = "sign_key")]
+    signature: Option<PathBuf>,
+
     #[command(flatten)]
     verbosity: Verbosity<WarnLevel>,
 }
 
-#[tokio::main]
-async fn main() {
-    let mut args = Cli::parse();
+#[derive(Subcommand)]
+enum Command {
+    /// Verify a report against its detached signature
+    VerifyReport(VerifyReportArgs),
+    /// Find every workflow in an organization that uses an action
+    FindUsage(FindUsageArgs),
+    /// Find workflows copy-pasted across an organization's repositories
+    FindDuplicates(FindDuplicatesArgs),
+    /// Manage the offline OSV database used by `--provider osv-offline`
+    Db(DbArgs),
+    /// Inspect the advisory providers selected by --provider
+    Providers(ProvidersArgs),
+    /// Look up one advisory by GHSA or CVE ID and explain it in full
+    Explain(ExplainArgs),
+    /// Pin a workflow's tag and branch refs to the commit SHAs they point at
+    Pin(PinArgs),
+    /// Move actions with known advisories to the latest unaffected release,
+    /// pinned by SHA
+    Update(PinArgs),
+    /// Write a commented starter config file, and optionally a
+    /// .ghssignore, into the current repository
+    Init(InitArgs),
+}
+
+#[derive(Args)]
+struct VerifyReportArgs {
+    /// Path to the report that was signed
+    #[arg(long)]
+    report: PathBuf,
+
+    /// Path to the detached signature written by --signature
+    #[arg(long)]
+    signature: PathBuf,
+
+    /// Trusted ed25519 public key (SPKI PEM)
+    #[arg(long, value_name = "PEM")]
+    public_key: PathBuf,
+}
+
+#[derive(Args)]
+struct FindUsageArgs {
+    /// Action to search for (owner/repo, or owner/repo/path), at any ref
+    action: String,
+
+    /// Organization whose repositories are searched
+    #[arg(long)]
+    org: String,
+
+    /// Known-bad commit SHA (repeatable). Refs are resolved and any that
+    /// point at one are reported as compromised, with exit code 1.
+    #[arg(long = "sha", value_name = "SHA")]
+    shas: Vec<String>,
+
+    /// Output format (text or json)
+    #[arg(long, value_enum, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct FindDuplicatesArgs {
+    /// Organization whose repositories are searched
+    #[arg(long)]
+    org: String,
+
+    /// Output format (text or json)
+    #[arg(long, value_enum, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct ExplainArgs {
+    /// Advisory ID, e.g. GHSA-mcph-m25j-8j63 or CVE-2025-30066
+    id: String,
+
+    /// Output format (text or json)
+    #[arg(long, value_enum, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct PinArgs {
+    /// Workflow file to rewrite
+    #[arg(long, short)]
+    file: PathBuf,
+
+    /// Rewrite the file in place instead of printing a diff
+    #[arg(long)]
+    write: bool,
+}
+
+#[derive(Args)]
+struct InitArgs {
+    /// Directory to write to [default: the root of the git repository
+    /// containing the current directory, else the current directory]
+    #[arg(long, value_name = "PATH")]
+    dir: Option<PathBuf>,
+
+    /// Also write a starter .ghssignore
+    #[arg(long)]
+    ignore: bool,
+
+    /// Overwrite files that already exist
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct ProvidersArgs {
+    #[command(subcommand)]
+    command: ProvidersCommand,
+}
+
+#[derive(Subcommand)]
+enum ProvidersCommand {
+    /// Send each provider a known query and report reachability, auth, and
+    /// latency
+    Check(ProvidersCheckArgs),
+}
+
+#[derive(Args)]
+struct ProvidersCheckArgs {
+    /// Output format (text or json)
+    #[arg(long, value_enum, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct DbArgs {
+    #[command(subcommand)]
+    command: DbCommand,
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Download or refresh the OSV data dumps
+    Update(DbUpdateArgs),
+}
+
+#[derive(Args)]
+struct DbUpdateArgs {
+    /// Database directory [default: $GHSS_OSV_DB_DIR, else ~/.cache/ghss/osv]
+    #[arg(long, value_name = "PATH")]
+    dir: Option<PathBuf>,
+
+    /// OSV ecosystem to download, e.g. npm (repeatable). Defaults to GitHub
+    /// Actions and every ecosystem --deps queries.
+    #[arg(long = "ecosystem", value_name = "NAME")]
+    ecosystems: Vec<String>,
+}
+
+fn main() {
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // Before logging is set up, since the config can choose the format.
+    // `init` writes the config rather than reading it.
+    let config_paths = if matches!(args.command, Some(Command::Init(_))) {
+        vec![]
+    } else {
+        apply_config(&mut args, &matches).unwrap_or_else(|e| {
+            eprintln!("Error: {e:#}");
+            std::process::exit(Exit::Usage as i32);
+        })
+    };
 
     // Back-compat: --json overrides --format. clap's `conflicts_with` already
     // rejects passing both, so this only fires when only --json is set.
     if args.json {
-        args.format = CliOutputFormat::Json;
+        args.format = AuditOutputFormat::Json;
     }
 
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
@@ -115,9 +642,9 @@ async fn main() {
         .without_time();
 
     // Use JSON-formatted log output to stderr whenever the result format is
-    // machine-readable, so operators piping --format json or --format sarif
+    // machine-readable, so operators piping --format json, sarif or openvex
     // also get structured logs.
-    let structured_logs = matches!(args.format, CliOutputFormat::Json | CliOutputFormat::Sarif);
+    let structured_logs = args.format != AuditOutputFormat::Text;
     if structured_logs {
         base.json().init();
     } else {
@@ -125,53 +652,106 @@ async fn main() {
     }
 
     if args.json {
-        tracing::warn!("--json is deprecated; use --format json instead");
+        tracing::warn!(
+            "--json is deprecated; --format json replaces it, but prints an object \
+             (schema_version 2) whose `actions` is the array --json prints"
+        );
+    }
+
+    for path in &config_paths {
+        tracing::info!(path = %path.display(), "loaded config");
     }
 
-    match run(&args).await {
-        Ok(code) => std::process::exit(code),
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start the async runtime");
+    match runtime.block_on(run(&args)) {
+        Ok(code) => std::process::exit(code as i32),
         Err(e) => {
             eprintln!("Error: {e:#}");
-            std::process::exit(1);
+            std::process::exit(Exit::Usage as i32);
         }
     }
 }
 
-async fn run(args: &Cli) -> anyhow::Result<i32> {
-    if !args.file.exists() {
-        bail!("file not found: {}", args.file.display());
+async fn run(args: &Cli) -> anyhow::Result<Exit> {
+    match &args.command {
+        Some(Command::VerifyReport(verify)) => return verify_report(verify),
+        Some(Command::FindUsage(find)) => return find_usage(args, find).await,
+        Some(Command::FindDuplicates(find)) => return find_duplicates(args, find).await,
+        Some(Command::Db(DbArgs {
+            command: DbCommand::Update(update),
+        })) => return db_update(update).await,
+        Some(Command::Providers(ProvidersArgs {
+            command: ProvidersCommand::Check(check),
+        })) => return providers_check(args, check).await,
+        Some(Command::Explain(explain)) => return explain_advisory(args, explain).await,
+        Some(Command::Pin(pin)) => return pin_workflow(args, pin).await,
+        Some(Command::Update(update)) => return update_workflow(args, update).await,
+        Some(Command::Init(init)) => return init_repo(init),
+        None => {}
     }
 
-    let contents = std::fs::read_to_string(&args.file)?;
-    let actions = ghss::parse_actions(&contents)?;
-    let client = build_client(args)?;
+    // clap enforces --file whenever no subcommand is given.
+    let file = args.file.as_ref().context("--file is required")?;
+    if !file.exists() {
+        bail!("file not found: {}", file.display());
+    }
 
+    let contents = std::fs::read_to_string(file)?;
+    if template::is_template_path(file) {
+        check_template_properties(file);
+    }
+    // Local `./path` refs resolve against the repository root. Outside a
+    // `.github/workflows` layout, assume ghss runs from the root.
+    let root = repo_root(file).unwrap_or(std::path::Path::new("."));
+    let rules = load_rules(args)?;
+    let mut ignore_list = load_ignore_list(args, root)?;
+    let baseline = load_baseline(args)?;
+    let linter = Linter::default()
+        .with_disabled(rules.disabled())?
+        .with_disabled(&args.policy.disable)?;
+    let mut analysis =
+        ghss::analyze_workflow_in(&contents, &file.to_string_lossy(), root, &linter)?;
     // Filter root actions by --select
-    let actions = match &args.select {
-        Some(sel) => actions
-            .into_iter()
-            .enumerate()
-            .filter(|(i, _)| sel.includes(*i))
-            .map(|(_, a)| a)
-            .collect(),
-        None => actions,
-    };
+    if let Some(sel) = &args.select {
+        analysis.select(sel);
+    }
+    let actions = analysis.actions;
+    let local_errors = analysis.local_errors;
+    let client = build_client(args)?;
+
+    let mut vex_documents = Vec::new();
+    for source in &args.vex {
+        vex_documents.push(VexDocument::load(source).await?);
+    }
 
     let has_token = client.has_token();
-    let action_providers = providers::create_action_providers(&args.provider, &client)?;
-    let package_providers = providers::create_package_providers(&args.provider)?;
+    let providers = build_providers(args, &client)?;
 
+    let filter = AdvisoryFilter {
+        include_withdrawn: args.include_withdrawn,
+        published_since: args.since,
+    };
     let mut builder = PipelineBuilder::default()
+        .max_concurrency(args.concurrency)
         .stage(CompositeExpandStage::new(client.clone()))
-        .stage(WorkflowExpandStage::new(client.clone()))
+        .stage(
+            WorkflowExpandStage::new(client.clone())
+                .with_trusted_owners(args.trusted_owners.clone()),
+        )
         .stage(RefResolveStage::new(client.clone()))
-        .stage(AdvisoryStage::new(action_providers));
+        .stage(
+            AdvisoryStage::new(providers.action_providers())
+                .with_timeout(Duration::from_secs(args.provider_timeout))
+                .with_filter(filter),
+        );
 
     if args.deps {
         if has_token {
-            builder = builder
-                .stage(ScanStage::new(client.clone()))
-                .stage(DependencyStage::new(client.clone(), package_providers));
+            builder = builder.stage(ScanStage::new(client.clone())).stage(
+                DependencyStage::new(client.clone(), providers.package_providers())
+                    .with_overrides(Arc::new(load_overrides(args)?))
+                    .with_filter(filter),
+            );
         } else {
             tracing::warn!(
                 "--deps requires a GitHub token; skipping ecosystem scan and dependency audit"
@@ -179,16 +759,192 @@ async fn run(args: &Cli) -> anyhow::Result<i32> {
         }
     }
 
+    if args.update_hygiene {
+        builder = builder.stage(UpdateHygieneStage::new(client.clone()));
+    }
+
+    if args.project_info {
+        builder = builder.stage(ProjectInfoStage::new(DepsDevClient::new()));
+    }
+
+    if args.releases {
+        builder = builder.stage(ReleaseStage::new(client.clone()));
+    }
+
+    if args.recommend {
+        builder = builder.stage(RecommendStage::new(client.clone()));
+    }
+
+    if args.repo_metadata {
+        builder = builder
+            .stage(RepoMetadataStage::new(client.clone()).with_stale_after(args.stale_after));
+    }
+
+    if args.reputation {
+        builder = builder.stage(ReputationStage::new(client.clone()));
+    }
+
+    if args.scorecard || args.min_scorecard.is_some() {
+        builder = builder
+            .stage(ScorecardStage::new(ScorecardClient::new()).with_min_score(args.min_scorecard));
+    }
+
+    if args.provenance {
+        builder = builder.stage(ProvenanceStage::new(client.clone()));
+    }
+
+    if args.signatures || args.require_signed {
+        builder = builder
+            .stage(SignatureStage::new(client.clone()).with_require_signed(args.require_signed));
+    }
+
+    if args.license || !args.deny_licenses.is_empty() {
+        builder = builder
+            .stage(LicenseStage::new(client.clone()).with_denylist(args.deny_licenses.clone()));
+    }
+
+    if args.tag_protection {
+        builder = builder.stage(TagProtectionStage::new(client.clone()));
+    }
+
+    if args.repo_hygiene {
+        builder = builder.stage(RepoHygieneStage::new(client.clone()));
+    }
+
+    if args.verify_pins {
+        builder = builder.stage(CommitOwnershipStage::new(client.clone()));
+    }
+
+    let tag_history = match &args.tag_history {
+        Some(path) => Some(Arc::new(Mutex::new(TagHistory::load(path)?))),
+        None => None,
+    };
+    if let Some(history) = &tag_history {
+        builder = builder.stage(TagMovementStage::new(client.clone(), history.clone()));
+    }
+
+    if args.epss {
+        builder = builder.stage(EpssStage::new(EpssClient::new()));
+    }
+
     let pipeline = builder.build();
     let max_concurrency = pipeline.max_concurrency();
-    let walker = Walker::new(pipeline, args.depth.to_max_depth(), max_concurrency);
-    let nodes: Vec<AuditNode> = walker.walk(actions).await;
+    let mut walker = Walker::new(pipeline, args.depth.to_max_depth(), max_concurrency);
+    if args.adaptive_concurrency {
+        walker = walker.with_adaptive_concurrency(client.clone());
+    }
+    if let Some(path) = &args.resume {
+        let checkpoint = Checkpoint::load(path)?;
+        walker = walker.resume(checkpoint, &actions)?;
+    }
+    if let Some(path) = args.checkpoint.as_ref().or(args.resume.as_ref()) {
+        walker = walker.with_checkpoint(path);
+    }
+    let mut nodes: Vec<AuditNode> = walker.walk(actions).await;
+    if let (Some(path), Some(history)) = (&args.tag_history, &tag_history) {
+        history
+            .lock()
+            .expect("tag history lock poisoned")
+            .save(path)?;
+    }
+    if !args.details {
+        clear_advisory_details(&mut nodes);
+    }
+    load_knowledge_base(args)?.annotate(&mut nodes);
+    if !vex_documents.is_empty() {
+        let applied = vex::apply(&vex_documents, &mut nodes);
+        tracing::info!(applied, "applied VEX statements");
+    }
+
+    if let (Some(run_id), Some(repo)) = (args.run_id, &args.repo) {
+        let jobs = runtime::fetch_run_jobs(&client, repo, run_id).await?;
+        runtime::correlate(&mut nodes, run_id, &jobs);
+    }
+    let mut findings = analysis.findings;
+    findings.extend(permissions::check_permissions(
+        &contents,
+        &file.to_string_lossy(),
+        &nodes,
+    )?);
+    findings.extend(knowledge::check_inputs(&nodes));
+    findings.extend(rules.evaluate(&nodes, &file.to_string_lossy()));
+    findings.extend(policy::evaluate(&args.policy.checks, &nodes)?);
+    if let Some(root) = repo_root(file) {
+        findings.extend(check_local_update_hygiene(file, root));
+    }
+    let mut inline = InlineSuppressions::parse(&contents, &file.to_string_lossy())?;
+    let today = chrono::Utc::now().date_naive();
+    findings.extend(ignore_list.expire(today));
+    findings.extend(inline.expire(today));
+    linter.retain_enabled(&mut findings);
+    let trusted = TrustedOwners::new(&args.trusted_owners).apply(&mut nodes, &mut findings);
+    tracing::info!(
+        advisories = trusted.advisories,
+        findings = trusted.findings,
+        "left out trusted owners' advisories and pinning findings"
+    );
+    let ignored = ignore_list.apply(&mut nodes, &mut findings);
+    tracing::info!(
+        advisories = ignored.advisories,
+        findings = ignored.findings,
+        "applied ignore list"
+    );
+    let suppressed = inline.apply(&mut nodes, &mut findings);
+    tracing::info!(
+        advisories = suppressed.advisories,
+        findings = suppressed.findings,
+        "applied inline suppressions"
+    );
+    if let Some(baseline) = &baseline {
+        let known = baseline.apply(&mut nodes, &mut findings);
+        tracing::info!(
+            advisories = known.advisories,
+            findings = known.findings,
+            "left out findings already in the baseline"
+        );
+    }
+    let report = AuditReport::new(nodes, findings);
+
+    if let Some(path) = &args.openvex_out {
+        let vex = VexDocument::from_suppressions(&report.actions, chrono::Utc::now());
+        let json = serde_json::to_string_pretty(&vex)?;
+        std::fs::write(path, json + "\n")
+            .with_context(|| format!("failed to write OpenVEX document: {}", path.display()))?;
+    }
 
-    let formatter = output::formatter(OutputFormat::from(args.format), args.file.clone());
-    formatter
-        .write_results(&nodes, &mut std::io::stdout().lock())
-        .expect("failed to write output");
+    let formatter = output::formatter(OutputFormat::from(args.format), file.clone());
+    // The legacy --json flag keeps emitting the bare action array.
+    let write_output = |writer: &mut dyn std::io::Write| {
+        if args.json {
+            formatter.write_results(&report.actions, writer)
+        } else {
+            formatter.write_report(&report, writer)
+        }
+    };
+    match (&args.sign_key, &args.signature) {
+        (Some(key_path), Some(signature_path)) => {
+            let mut bytes = Vec::new();
+            write_output(&mut bytes).expect("failed to write output");
+            write_signature(&bytes, key_path, signature_path)?;
+            std::io::Write::write_all(&mut std::io::stdout().lock(), &bytes)?;
+        }
+        _ => write_output(&mut std::io::stdout().lock()).expect("failed to write output"),
+    }
+
+    if args.annotations {
+        output::annotations::write_annotations(
+            &report.actions,
+            &report.findings,
+            file,
+            &mut std::io::stderr().lock(),
+        )?;
+    }
+    let nodes = report.actions;
 
+    // Every failed condition is listed before exiting, so one run shows
+    // everything the policy rejects. Findings decide the exit code over
+    // stage errors.
+    let mut exit = Exit::Clean;
     if let Some(threshold) = args.fail_on_severity {
         let violations = output::collect_severity_violations(&nodes, threshold);
         if !violations.is_empty() {
@@ -203,11 +959,631 @@ async fn run(args: &Cli) -> anyhow::Result<i32> {
                 );
             }
             eprintln!();
-            return Ok(2);
+            exit = Exit::Findings;
+        }
+
+        let severe: Vec<&Finding> = report
+            .findings
+            .iter()
+            .filter(|f| f.severity >= threshold)
+            .collect();
+        if !severe.is_empty() {
+            eprintln!(
+                "\n{} finding(s) at or above {threshold} severity:\n",
+                severe.len()
+            );
+            for f in &severe {
+                print_failed_finding(f, true);
+            }
+            eprintln!();
+            exit = Exit::Findings;
+        }
+    }
+
+    if args.require_pinned || args.fail_on.contains(&FailOn::Unpinned) {
+        let unpinned: Vec<&Finding> = report
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == finding::UNPINNED_ACTION.id)
+            .collect();
+        if !unpinned.is_empty() {
+            eprintln!(
+                "\n{} action reference(s) not pinned to a commit SHA:\n",
+                unpinned.len()
+            );
+            for f in &unpinned {
+                print_failed_finding(f, false);
+            }
+            eprintln!();
+            exit = Exit::Findings;
+        }
+    }
+
+    let failed_checks: Vec<&Finding> = report
+        .findings
+        .iter()
+        .filter(|f| {
+            args.policy
+                .checks
+                .iter()
+                .any(|c| c.decision == Decision::Fail && c.id == f.rule_id)
+        })
+        .collect();
+    if !failed_checks.is_empty() {
+        eprintln!("\n{} policy check(s) failed:\n", failed_checks.len());
+        for f in &failed_checks {
+            print_failed_finding(f, true);
+        }
+        eprintln!();
+        exit = Exit::Findings;
+    }
+
+    if args.strict && !local_errors.is_empty() {
+        eprintln!(
+            "\n{} local action(s) could not be read, so the actions they use were not audited:\n",
+            local_errors.len()
+        );
+        for e in &local_errors {
+            eprintln!("  {}: {}", e.uses, e.message);
+        }
+        eprintln!();
+        if exit == Exit::Clean {
+            exit = Exit::StageErrors;
+        }
+    }
+
+    if args.strict || args.fail_on.contains(&FailOn::Errors) {
+        let errors = output::collect_stage_errors(&nodes);
+        if !errors.is_empty() {
+            eprintln!(
+                "\n{} stage error(s) left results incomplete:\n",
+                errors.len()
+            );
+            for (action, e) in &errors {
+                eprintln!("  {action} - {}: {}", e.stage, e.message);
+            }
+            eprintln!();
+            if exit == Exit::Clean {
+                exit = Exit::StageErrors;
+            }
+        }
+    }
+
+    Ok(exit)
+}
+
+/// One line of a `--fail-on*` summary: the finding's location and message,
+/// prefixed with its rule id when findings of several rules are listed.
+fn print_failed_finding(f: &Finding, with_rule: bool) {
+    let message = if with_rule {
+        format!("{} ({}): {}", f.rule_id, f.severity, f.message)
+    } else {
+        f.message.clone()
+    };
+    match &f.location {
+        Some(location) => eprintln!("  {location}: {message}"),
+        None => eprintln!("  {message}"),
+    }
+}
+
+fn load_knowledge_base(args: &Cli) -> anyhow::Result<KnowledgeBase> {
+    let mut kb = KnowledgeBase::bundled();
+    if let Some(path) = &args.knowledge_base {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read knowledge base: {}", path.display()))?;
+        kb.extend(KnowledgeBase::from_json(&json).with_context(|| format!("{}", path.display()))?);
+    }
+    Ok(kb)
+}
+
+fn load_overrides(args: &Cli) -> anyhow::Result<Overrides> {
+    let Some(path) = &args.overrides else {
+        return Ok(Overrides::default());
+    };
+    let yaml = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read overrides: {}", path.display()))?;
+    let overrides = Overrides::from_yaml(&yaml).with_context(|| format!("{}", path.display()))?;
+    tracing::info!(count = overrides.len(), "loaded dependency overrides");
+    Ok(overrides)
+}
+
+fn load_rules(args: &Cli) -> anyhow::Result<Rules> {
+    let rules = match &args.rules {
+        Some(path) => {
+            let yaml = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read rules: {}", path.display()))?;
+            let rules = Rules::from_yaml(&yaml).with_context(|| format!("{}", path.display()))?;
+            tracing::info!(count = rules.len(), "loaded custom rules");
+            rules
+        }
+        None => Rules::default(),
+    };
+    Ok(rules
+        .with_forbidden(args.policy.forbid.iter().cloned())
+        .with_forbidden(args.forbid.iter().cloned()))
+}
+
+/// Load `--config`, or the config files found by [`discover_config`], and
+/// merge them into `args`. A flag given on the command line or through its
+/// environment variable wins; lists are combined. Returns the files loaded.
+///
+/// This runs before the async runtime starts: `[urls]` are passed on as
+/// the `GHSS_*_URL` variables the clients read, and setting the environment
+/// is only sound while no other thread can be reading it.
+fn apply_config(args: &mut Cli, matches: &ArgMatches) -> anyhow::Result<Vec<PathBuf>> {
+    let paths = match &args.config {
+        Some(path) => vec![path.clone()],
+        None if args.no_config => return Ok(vec![]),
+        None => discover_config(args.file.as_deref()),
+    };
+    if paths.is_empty() {
+        if let Some(profile) = &args.profile {
+            bail!("--profile {profile}: no config file found");
+        }
+        return Ok(paths);
+    }
+    let source = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    // `--config` is the user's own; discovered files may come from the
+    // scanned repository.
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_layers(&paths)?,
+    };
+    if let Some(profile) = &args.profile {
+        config = config
+            .with_profile(profile)
+            .with_context(|| source.clone())?;
+    }
+    let unset = |id: &str| {
+        matches!(
+            matches.value_source(id),
+            None | Some(ValueSource::DefaultValue)
+        )
+    };
+
+    let scan = &config.scan;
+    if let Some(provider) = &scan.provider
+        && unset("provider")
+    {
+        args.provider = provider.clone();
+    }
+    if let Some(depth) = &scan.depth
+        && unset("depth")
+    {
+        args.depth = depth.clone();
+    }
+    if let Some(concurrency) = scan.concurrency
+        && unset("concurrency")
+    {
+        args.concurrency = concurrency;
+    }
+    if let Some(format) = &scan.format
+        && unset("format")
+        && !args.json
+    {
+        args.format = AuditOutputFormat::from_str(format, true)
+            .map_err(|e| anyhow::anyhow!("{source}: scan.format: {e}"))?;
+    }
+
+    let has_app = args.github_app_id.is_some()
+        || args.github_app_installation_id.is_some()
+        || args.github_app_private_key_path.is_some();
+    if args.github_token.is_none() && !has_app {
+        args.github_token = config.github.token().with_context(|| source.clone())?;
+    }
+
+    for (var, url) in config.urls.vars() {
+        if std::env::var_os(var).is_none() {
+            // SAFETY: no other thread has been started yet.
+            unsafe { std::env::set_var(var, url) };
+        }
+    }
+
+    let policy = config.policy;
+    if args.fail_on_severity.is_none() {
+        args.fail_on_severity = policy.fail_on_severity;
+    }
+    if policy.fail_on_errors && !args.fail_on.contains(&FailOn::Errors) {
+        args.fail_on.push(FailOn::Errors);
+    }
+    args.require_pinned |= policy.require_pinned;
+    args.trusted_owners
+        .extend(policy.trusted_owners.iter().cloned());
+    args.policy = policy;
+    Ok(paths)
+}
+
+/// The config files from the directory of `file` up to its repository
+/// root, layered in that order, or else the first config file in the
+/// current directory or the user config directory.
+fn discover_config(file: Option<&std::path::Path>) -> Vec<PathBuf> {
+    let cwd = std::path::Path::new(".");
+    if let Some(file) = file {
+        let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty());
+        let found = config::discover_upward(dir.unwrap_or(cwd), repo_root(file));
+        if !found.is_empty() {
+            return found;
+        }
+    }
+    config::discover(&[cwd]).into_iter().collect()
+}
+
+/// The `--ignore-file`, or else `.ghssignore` at the repository root when
+/// there is one.
+fn load_ignore_list(args: &Cli, root: &std::path::Path) -> anyhow::Result<IgnoreList> {
+    let path = match &args.ignore_file {
+        Some(path) => path.clone(),
+        None => root.join(ignore::FILE_NAME),
+    };
+    if args.ignore_file.is_none() && !path.exists() {
+        return Ok(IgnoreList::default());
+    }
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read ignore file: {}", path.display()))?;
+    let list = IgnoreList::parse(&text)
+        .with_context(|| format!("{}", path.display()))?
+        .with_file(path.to_string_lossy());
+    tracing::info!(count = list.len(), path = %path.display(), "loaded ignore list");
+    Ok(list)
+}
+
+fn load_baseline(args: &Cli) -> anyhow::Result<Option<Baseline>> {
+    let Some(path) = &args.baseline else {
+        return Ok(None);
+    };
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read baseline: {}", path.display()))?;
+    let baseline = Baseline::from_json(&json).with_context(|| format!("{}", path.display()))?;
+    Ok(Some(baseline))
+}
+
+/// Drop advisory descriptions and references, which providers always
+/// return but only `--details` shows.
+fn clear_advisory_details(nodes: &mut [AuditNode]) {
+    for node in nodes {
+        let entry = &mut node.entry;
+        let deps = entry.dep_vulnerabilities.iter_mut();
+        for adv in entry
+            .advisories
+            .iter_mut()
+            .chain(deps.flat_map(|dep| dep.advisories.iter_mut()))
+        {
+            adv.clear_details();
+        }
+        clear_advisory_details(&mut node.children);
+    }
+}
+
+/// Root of the repository a workflow under `.github/workflows` lives in.
+fn repo_root(file: &std::path::Path) -> Option<&std::path::Path> {
+    let workflows = file.parent()?;
+    let github = workflows.parent()?;
+    if workflows.file_name()? != "workflows" || github.file_name()? != ".github" {
+        return None;
+    }
+    github.parent()
+}
+
+/// Check that the repository at `root` has Dependabot or Renovate keeping
+/// its actions up to date.
+fn check_local_update_hygiene(file: &std::path::Path, root: &std::path::Path) -> Option<Finding> {
+    let hygiene = updates::check_local(root);
+    tracing::info!(%hygiene, "checked update config");
+    (!hygiene.covers_actions).then(|| {
+        Finding::new(&finding::NO_ACTION_UPDATES, hygiene.to_string()).with_location(Some(
+            SourceLocation::new(1, 1).with_file(file.to_string_lossy()),
+        ))
+    })
+}
+
+/// Starter workflows are audited like any other workflow; the companion
+/// `*.properties.json` is only checked so a missing one is noticed.
+fn check_template_properties(file: &std::path::Path) {
+    let Some(props_path) = template::properties_path(file) else {
+        return;
+    };
+    match std::fs::read_to_string(&props_path) {
+        Ok(json) => match TemplateProperties::from_json(&json) {
+            Ok(props) => tracing::info!(name = %props.name, "auditing workflow template"),
+            Err(e) => tracing::warn!("{}: {e:#}", props_path.display()),
+        },
+        Err(_) => tracing::warn!(
+            "workflow template has no {} companion; GitHub will not offer it",
+            props_path.display()
+        ),
+    }
+}
+
+fn write_signature(
+    report: &[u8],
+    key_path: &std::path::Path,
+    signature_path: &std::path::Path,
+) -> anyhow::Result<()> {
+    let pem = std::fs::read_to_string(key_path)
+        .with_context(|| format!("failed to read signing key: {}", key_path.display()))?;
+    let key = signing::load_signing_key(&pem)?;
+    let signature = signing::sign_report(report, &key);
+    let json = serde_json::to_string_pretty(&signature)?;
+    std::fs::write(signature_path, json + "\n")
+        .with_context(|| format!("failed to write signature: {}", signature_path.display()))
+}
+
+fn verify_report(args: &VerifyReportArgs) -> anyhow::Result<Exit> {
+    let report = std::fs::read(&args.report)
+        .with_context(|| format!("failed to read report: {}", args.report.display()))?;
+    let signature: ReportSignature = serde_json::from_str(
+        &std::fs::read_to_string(&args.signature)
+            .with_context(|| format!("failed to read signature: {}", args.signature.display()))?,
+    )
+    .context("signature file is not a valid report signature")?;
+    let pem = std::fs::read_to_string(&args.public_key)
+        .with_context(|| format!("failed to read public key: {}", args.public_key.display()))?;
+    let key = signing::load_verifying_key(&pem)?;
+
+    signing::verify_report(&report, &signature, &key)?;
+    println!(
+        "Signature OK: {} (sha256 {})",
+        args.report.display(),
+        signature.sha256
+    );
+    Ok(Exit::Clean)
+}
+
+async fn find_usage(args: &Cli, find: &FindUsageArgs) -> anyhow::Result<Exit> {
+    if find.format == CliOutputFormat::Sarif {
+        bail!("find-usage supports text and json output");
+    }
+    let query = UsageQuery::new(&find.action, &find.shas)?;
+    let client = build_client(args)?;
+    let usages = usage::find_usage(&client, &find.org, &query).await?;
+
+    let mut stdout = std::io::stdout().lock();
+    if find.format == CliOutputFormat::Json {
+        serde_json::to_writer_pretty(&mut stdout, &usages)?;
+        writeln!(stdout)?;
+    } else if usages.is_empty() {
+        writeln!(stdout, "no workflows in {} use {query}", find.org)?;
+    } else {
+        writeln!(stdout, "{} uses of {query} in {}:", usages.len(), find.org)?;
+        for u in &usages {
+            writeln!(stdout, "  {u}")?;
+        }
+    }
+
+    let compromised = usages
+        .iter()
+        .any(|u| u.exposure == usage::Exposure::Compromised);
+    Ok(if compromised {
+        Exit::Findings
+    } else {
+        Exit::Clean
+    })
+}
+
+async fn find_duplicates(args: &Cli, find: &FindDuplicatesArgs) -> anyhow::Result<Exit> {
+    if find.format == CliOutputFormat::Sarif {
+        bail!("find-duplicates supports text and json output");
+    }
+    let client = build_client(args)?;
+    let groups = duplicates::find_duplicates(&client, &find.org).await?;
+
+    let mut stdout = std::io::stdout().lock();
+    if find.format == CliOutputFormat::Json {
+        serde_json::to_writer_pretty(&mut stdout, &groups)?;
+        writeln!(stdout)?;
+    } else if groups.is_empty() {
+        writeln!(stdout, "no duplicated workflows in {}", find.org)?;
+    } else {
+        writeln!(
+            stdout,
+            "{} duplicated workflows in {}:",
+            groups.len(),
+            find.org
+        )?;
+        for group in &groups {
+            writeln!(stdout, "  {group}")?;
+        }
+    }
+    Ok(Exit::Clean)
+}
+
+async fn providers_check(args: &Cli, check: &ProvidersCheckArgs) -> anyhow::Result<Exit> {
+    if check.format == CliOutputFormat::Sarif {
+        bail!("providers check supports text and json output");
+    }
+    let client = build_client(args)?;
+    let providers = build_providers(args, &client)?;
+    let results = health::check(&providers, Duration::from_secs(args.provider_timeout)).await;
+
+    let mut stdout = std::io::stdout().lock();
+    if check.format == CliOutputFormat::Json {
+        serde_json::to_writer_pretty(&mut stdout, &results)?;
+        writeln!(stdout)?;
+    } else {
+        for result in &results {
+            writeln!(stdout, "{result}")?;
+        }
+        if let Some(limit) = client.rate_limit() {
+            writeln!(
+                stdout,
+                "GitHub rate limit ({}): {} of {} remaining",
+                limit.resource, limit.remaining, limit.limit
+            )?;
         }
     }
 
-    Ok(0)
+    let healthy = results.iter().all(|r| r.status == HealthStatus::Ok);
+    Ok(if healthy {
+        Exit::Clean
+    } else {
+        Exit::StageErrors
+    })
+}
+
+async fn explain_advisory(args: &Cli, explain: &ExplainArgs) -> anyhow::Result<Exit> {
+    if explain.format == CliOutputFormat::Sarif {
+        bail!("explain supports text and json output");
+    }
+    let client = build_client(args)?;
+    let providers = build_providers(args, &client)?;
+    let Some(explanation) = explain::explain(&providers, &explain.id).await? else {
+        let names: Vec<&str> = providers
+            .action_providers()
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        if names.is_empty() {
+            bail!("provider {} cannot look up advisories by ID", args.provider);
+        }
+        bail!("{} not found by {}", explain.id, names.join(", "));
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    if explain.format == CliOutputFormat::Json {
+        serde_json::to_writer_pretty(&mut stdout, &explanation)?;
+    } else {
+        write!(stdout, "{explanation}")?;
+    }
+    writeln!(stdout)?;
+    Ok(Exit::Clean)
+}
+
+async fn pin_workflow(args: &Cli, pin: &PinArgs) -> anyhow::Result<Exit> {
+    let contents = std::fs::read_to_string(&pin.file)
+        .with_context(|| format!("failed to read {}", pin.file.display()))?;
+    let file = pin.file.to_string_lossy();
+    let refs = ghss::pin::unpinned_refs(&contents, &file)?;
+    let client = build_client(args)?;
+    let (edits, failed) = ghss::pin::resolve_pins(&client, &refs).await;
+    for (action, e) in &failed {
+        eprintln!("could not resolve {action}: {e:#}");
+    }
+
+    write_edits(pin, &contents, &edits, "pinned")?;
+    Ok(if failed.is_empty() {
+        Exit::Clean
+    } else {
+        Exit::StageErrors
+    })
+}
+
+async fn update_workflow(args: &Cli, update: &PinArgs) -> anyhow::Result<Exit> {
+    let contents = std::fs::read_to_string(&update.file)
+        .with_context(|| format!("failed to read {}", update.file.display()))?;
+    let refs = ghss::pin::third_party_refs(&contents, &update.file.to_string_lossy())?;
+    let client = build_client(args)?;
+    let providers = build_providers(args, &client)?;
+    let pipeline = PipelineBuilder::default()
+        .max_concurrency(args.concurrency)
+        .stage(
+            AdvisoryStage::new(providers.action_providers())
+                .with_timeout(Duration::from_secs(args.provider_timeout)),
+        )
+        .stage(RecommendStage::new(client))
+        .build();
+    let max_concurrency = pipeline.max_concurrency();
+    let mut roots = refs.clone();
+    roots.sort();
+    roots.dedup();
+    let nodes = Walker::new(pipeline, Some(0), max_concurrency)
+        .walk(roots)
+        .await;
+
+    let plan = ghss::update::plan(&refs, &nodes);
+    for action in &plan.unfixed {
+        eprintln!("no fixed release found for {action}");
+    }
+    write_edits(update, &contents, &plan.edits, "updated")?;
+    Ok(if plan.unfixed.is_empty() {
+        Exit::Clean
+    } else {
+        Exit::Findings
+    })
+}
+
+/// Apply `edits` to the workflow: rewrite it with `--write`, otherwise
+/// print them as a diff.
+fn write_edits(
+    args: &PinArgs,
+    contents: &str,
+    edits: &[ghss::pin::Edit],
+    verb: &str,
+) -> anyhow::Result<()> {
+    let file = args.file.to_string_lossy();
+    let edited = ghss::pin::apply(contents, edits)?;
+    if args.write {
+        if !edits.is_empty() {
+            std::fs::write(&args.file, &edited)
+                .with_context(|| format!("failed to write {}", args.file.display()))?;
+        }
+        println!("{verb} {} action reference(s) in {file}", edits.len());
+    } else {
+        print!("{}", ghss::pin::diff(&file, contents, &edited));
+    }
+    Ok(())
+}
+
+/// Write the starter config, and the starter `.ghssignore` with `--ignore`.
+/// Nothing is written if any of them would replace an existing file,
+/// unless `--force` is given.
+fn init_repo(init: &InitArgs) -> anyhow::Result<Exit> {
+    let dir = match &init.dir {
+        Some(dir) => dir.clone(),
+        None => current_repo_root()?,
+    };
+    let mut files = vec![(dir.join(config::FILE_NAMES[0]), config::STARTER)];
+    if init.ignore {
+        files.push((dir.join(ignore::FILE_NAME), ignore::STARTER));
+    }
+    if !init.force {
+        let existing = config::FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .chain(init.ignore.then(|| dir.join(ignore::FILE_NAME)))
+            .find(|path| path.exists());
+        if let Some(path) = existing {
+            bail!(
+                "{} already exists; use --force to overwrite it",
+                path.display()
+            );
+        }
+    }
+    for (path, contents) in files {
+        std::fs::write(&path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+    Ok(Exit::Clean)
+}
+
+/// The git repository containing the current directory, else the current
+/// directory.
+fn current_repo_root() -> anyhow::Result<PathBuf> {
+    let cwd = std::env::current_dir().context("failed to read the current directory")?;
+    let root = cwd
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(&cwd);
+    Ok(root.to_path_buf())
+}
+
+async fn db_update(args: &DbUpdateArgs) -> anyhow::Result<Exit> {
+    let db = OsvDatabase::new(args.dir.clone().unwrap_or_else(OsvDatabase::default_dir));
+    let ecosystems: Vec<&str> = if args.ecosystems.is_empty() {
+        osv_db::DEFAULT_ECOSYSTEMS.to_vec()
+    } else {
+        args.ecosystems.iter().map(String::as_str).collect()
+    };
+    for ecosystem in ecosystems {
+        let count = db.update(ecosystem).await?;
+        println!("{ecosystem}: {count} advisories");
+    }
+    println!("OSV database updated in {}", db.dir().display());
+    Ok(Exit::Clean)
 }
 
 fn build_client(args: &Cli) -> anyhow::Result<GitHubClient> {
@@ -236,3 +1612,81 @@ fn build_client(args: &Cli) -> anyhow::Result<GitHubClient> {
         Ok(GitHubClient::new(args.github_token.clone()))
     }
 }
+
+/// The `--provider` selection, plus Snyk with `--snyk`.
+fn build_providers(args: &Cli, client: &GitHubClient) -> anyhow::Result<Providers> {
+    let providers = Providers::named(&args.provider, client)?;
+    if !args.snyk {
+        return Ok(providers);
+    }
+    let snyk = SnykProvider::new(SnykConfig::from_env()).context("--snyk")?;
+    Ok(providers.with_package_provider(Arc::new(snyk)))
+}
+
+/// `--min-scorecard` value: an OpenSSF Scorecard score from 0 to 10.
+fn parse_score(value: &str) -> Result<f64, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|score| (0.0..=10.0).contains(score))
+        .ok_or_else(|| format!("expected a score from 0 to 10, got {value:?}"))
+}
+
+/// `--since` value: a date (midnight UTC) or an RFC 3339 timestamp.
+fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is valid")
+            .and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| format!("expected YYYY-MM-DD or an RFC 3339 timestamp, got {value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Options that name a single run's inputs and outputs have no
+    /// `GHSS_<FLAG>` variable.
+    const PER_RUN: &[&str] = &[
+        "file",
+        "json",
+        "select",
+        "since",
+        "run-id",
+        "repo",
+        "checkpoint",
+        "resume",
+        "sign-key",
+        "signature",
+        "openvex-out",
+        "verbose",
+        "quiet",
+        "help",
+        "version",
+    ];
+
+    #[test]
+    fn each_option_reads_its_own_env_var() {
+        for arg in Cli::command().get_arguments() {
+            let Some(long) = arg.get_long() else {
+                continue;
+            };
+            let env = arg.get_env().map(|env| env.to_string_lossy().into_owned());
+            let expected = match long {
+                // Named before the convention, after GitHub's own variables.
+                "trusted-owner" => Some("GHSS_TRUSTED_OWNERS".to_string()),
+                "github-token" => Some("GITHUB_TOKEN".to_string()),
+                "github-app-id" | "github-app-installation-id" | "github-app-private-key-path" => {
+                    Some(long.to_uppercase().replace('-', "_"))
+                }
+                _ if PER_RUN.contains(&long) => None,
+                _ => Some(format!("GHSS_{}", long.to_uppercase().replace('-', "_"))),
+            };
+            assert_eq!(env, expected, "--{long}");
+        }
+    }
+}
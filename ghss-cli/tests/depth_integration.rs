@@ -1,6 +1,6 @@
 use std::process::Command;
 
-use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::matchers::{body_string_contains, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 fn fixture(name: &str) -> String {
@@ -8,6 +8,31 @@ fn fixture(name: &str) -> String {
     format!("{dir}/tests/fixtures/{name}")
 }
 
+/// Answer an OSV `querybatch` request. Packages named in `vulnerable` get
+/// the paired vulnerability ID; every other query gets no vulnerabilities.
+fn osv_batch(
+    vulnerable: &'static [(&'static str, &'static str)],
+) -> impl Fn(&wiremock::Request) -> ResponseTemplate {
+    move |request| {
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let results: Vec<serde_json::Value> = body["queries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|query| {
+                let name = query["package"]["name"].as_str().unwrap();
+                let vulns: Vec<serde_json::Value> = vulnerable
+                    .iter()
+                    .filter(|(package, _)| *package == name)
+                    .map(|(_, id)| serde_json::json!({"id": id}))
+                    .collect();
+                serde_json::json!({"vulns": vulns})
+            })
+            .collect();
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": results}))
+    }
+}
+
 fn ghss() -> Command {
     Command::new(env!("CARGO_BIN_EXE_ghss"))
 }
@@ -98,6 +123,13 @@ fn run_ghss_with_mock(server: &MockServer, args: &[&str]) -> std::process::Outpu
         .env("GHSS_API_BASE_URL", server.uri())
         .env("GHSS_RAW_BASE_URL", server.uri())
         .env("GHSS_OSV_BASE_URL", format!("{}/osv-query", server.uri()))
+        .env("GHSS_NPM_REGISTRY_URL", format!("{}/npm", server.uri()))
+        .env("GHSS_DEPSDEV_BASE_URL", format!("{}/depsdev", server.uri()))
+        .env("GHSS_EPSS_BASE_URL", format!("{}/epss", server.uri()))
+        .env(
+            "GHSS_SCORECARD_BASE_URL",
+            format!("{}/scorecard", server.uri()),
+        )
         .env_remove("GITHUB_TOKEN")
         .output()
         .expect("failed to execute")
@@ -109,6 +141,13 @@ fn run_ghss_with_mock_and_token(server: &MockServer, args: &[&str]) -> std::proc
         .env("GHSS_API_BASE_URL", server.uri())
         .env("GHSS_RAW_BASE_URL", server.uri())
         .env("GHSS_OSV_BASE_URL", format!("{}/osv-query", server.uri()))
+        .env("GHSS_NPM_REGISTRY_URL", format!("{}/npm", server.uri()))
+        .env("GHSS_DEPSDEV_BASE_URL", format!("{}/depsdev", server.uri()))
+        .env("GHSS_EPSS_BASE_URL", format!("{}/epss", server.uri()))
+        .env(
+            "GHSS_SCORECARD_BASE_URL",
+            format!("{}/scorecard", server.uri()),
+        )
         .env("GITHUB_TOKEN", "fake-token")
         .output()
         .expect("failed to execute")
@@ -134,6 +173,15 @@ fn stdout_of_mock(server: &MockServer, args: &[&str]) -> String {
     String::from_utf8(output.stdout).unwrap()
 }
 
+/// Top-level action lines of text output, stopping at the summary footer.
+fn action_lines(stdout: &str) -> Vec<&str> {
+    stdout
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .filter(|l| !l.starts_with(' '))
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Text output tests
 // ---------------------------------------------------------------------------
@@ -154,9 +202,8 @@ async fn depth_0_produces_flat_output() {
     );
 
     // Only root actions at column 0
-    let action_lines: Vec<&str> = stdout.lines().filter(|l| !l.starts_with(' ')).collect();
     assert_eq!(
-        action_lines,
+        action_lines(&stdout),
         vec!["test-org/composite-a@v1", "test-org/leaf-action@v1"],
         "depth 0 should only show root actions"
     );
@@ -252,6 +299,131 @@ async fn depth_unlimited_expands_full_tree() {
     );
 }
 
+#[tokio::test]
+async fn adaptive_concurrency_lowers_limit_on_low_headroom() {
+    let server = setup_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-limit", "5000")
+                .insert_header("x-ratelimit-remaining", "10")
+                .set_body_json(serde_json::json!([])),
+        )
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let output = run_ghss_with_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--provider",
+            "ghsa",
+            "--depth",
+            "unlimited",
+            "--adaptive-concurrency",
+            "-v",
+        ],
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stdout.contains("    test-org/deep-leaf@v1\n"),
+        "walk should still complete, got:\n{stdout}"
+    );
+    assert!(
+        stderr.contains("adjusted concurrency to rate-limit headroom"),
+        "expected concurrency to drop, got:\n{stderr}"
+    );
+}
+
+#[tokio::test]
+async fn resume_rebuilds_tree_without_network() {
+    let checkpoint = std::env::temp_dir().join(format!("ghss-resume-{}.json", std::process::id()));
+    let checkpoint_arg = checkpoint.to_str().unwrap();
+    let workflow = fixture("depth-test-workflow.yml");
+    let args = [
+        "--file",
+        &workflow,
+        "--provider",
+        "ghsa",
+        "--depth",
+        "unlimited",
+    ];
+
+    let server = setup_mock_server().await;
+    let mut first_args = args.to_vec();
+    first_args.extend(["--checkpoint", checkpoint_arg]);
+    let first = stdout_of_mock(&server, &first_args);
+
+    // Nothing is mounted: every node must come from the checkpoint.
+    let empty = MockServer::start().await;
+    let mut resume_args = args.to_vec();
+    resume_args.extend(["--resume", checkpoint_arg]);
+    let resumed = stdout_of_mock(&empty, &resume_args);
+    std::fs::remove_file(&checkpoint).unwrap();
+
+    assert!(resumed.contains("    test-org/deep-leaf@v1\n"), "{resumed}");
+    assert_eq!(action_lines(&first), action_lines(&resumed));
+    assert!(empty.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn custom_rules_report_findings() {
+    let server = setup_mock_server().await;
+    let stdout = stdout_of_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--provider",
+            "ghsa",
+            "--depth",
+            "unlimited",
+            "--format",
+            "json",
+            "--rules",
+            &fixture("custom-rules.yml"),
+        ],
+    );
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let findings: Vec<(&str, &str, &str)> = report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| {
+            (
+                f["rule_id"].as_str().unwrap(),
+                f["severity"].as_str().unwrap(),
+                f["message"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        findings,
+        [
+            (
+                "nested-tag",
+                "low",
+                "test-org/composite-b is pinned to a tag inside test-org/composite-a"
+            ),
+            (
+                "nested-tag",
+                "low",
+                "test-org/deep-leaf is pinned to a tag inside test-org/composite-b"
+            ),
+            (
+                "nested-tag",
+                "low",
+                "test-org/leaf-x is pinned to a tag inside test-org/composite-a"
+            ),
+        ]
+    );
+}
+
 // ---------------------------------------------------------------------------
 // JSON output tests
 // ---------------------------------------------------------------------------
@@ -413,6 +585,73 @@ async fn reusable_workflow_includes_job_level_refs() {
     );
 }
 
+#[tokio::test]
+async fn reusable_workflow_calls_are_checked_against_workflow_call() {
+    let server = setup_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/org/shared-workflows/v1/.github/workflows/deploy.yml",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "on:\n  workflow_call:\n    inputs:\n      environment:\n        type: string\n    secrets:\n      deploy-key:\n        required: true\njobs: {}\n",
+        ))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("reusable-workflow.yml"),
+        "--provider",
+        "ghsa",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "secrets-inherit (medium): job `call-ci` passes all of its secrets to \
+             org/shared-workflows/.github/workflows/ci.yml@main"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "workflow-call-mismatch (low): job `call-deploy` does not pass required secret \
+             `deploy-key` to org/shared-workflows/.github/workflows/deploy.yml@v1"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "org/shared-workflows/.github/workflows/ci.yml@main\n  secrets: all (inherit)\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "org/shared-workflows/.github/workflows/deploy.yml@v1\n  secrets: NPM_TOKEN\n"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let mut trusted_args = args.to_vec();
+    trusted_args.extend(["--trusted-owner", "Org"]);
+    let stdout = stdout_of_mock(&server, &trusted_args);
+    assert!(!stdout.contains("secrets-inherit"), "got:\n{stdout}");
+
+    let mut sarif_args = args.to_vec();
+    sarif_args.extend(["--format", "sarif"]);
+    let sarif: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &sarif_args)).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    let inherit = results
+        .iter()
+        .find(|r| r["ruleId"] == "ghss/secrets-inherit")
+        .expect("secrets-inherit result");
+    assert_eq!(
+        inherit["locations"][0]["physicalLocation"]["region"]["startLine"],
+        20
+    );
+}
+
 // ---------------------------------------------------------------------------
 // 2b: Local-only/docker workflow (empty output edge case)
 // ---------------------------------------------------------------------------
@@ -481,6 +720,12 @@ async fn setup_scan_mock_server() -> MockServer {
         .mount(&server)
         .await;
 
+    Mock::given(method("POST"))
+        .and(path("/osv-querybatch"))
+        .respond_with(osv_batch(&[]))
+        .mount(&server)
+        .await;
+
     server
 }
 
@@ -524,9 +769,8 @@ async fn select_filters_root_actions() {
     );
 
     // Only the first root action should appear
-    let action_lines: Vec<&str> = stdout.lines().filter(|l| !l.starts_with(' ')).collect();
     assert_eq!(
-        action_lines,
+        action_lines(&stdout),
         vec!["test-org/composite-a@v1"],
         "--select 1 should only include the first root action, got:\n{stdout}"
     );
@@ -644,6 +888,344 @@ async fn mocked_advisory_appears_in_text_output() {
     );
 }
 
+#[tokio::test]
+async fn fixed_version_is_suggested_as_upgrade() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                "ghsa_id": "GHSA-test-fix0-0001",
+                "summary": "Fixed in a patch release",
+                "severity": "medium",
+                "html_url": "https://github.com/advisories/GHSA-test-fix0-0001",
+                "vulnerabilities": [{
+                    "package": {"ecosystem": "actions", "name": "test-org/composite-a"},
+                    "vulnerable_version_range": ">= 1.0.0, <= 1.4.9",
+                    "first_patched_version": "1.5.0"
+                }]
+            }])),
+        )
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains("    affected: >= 1.0.0, <= 1.4.9\n    fix: upgrade to 1.5.0\n"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    assert_eq!(
+        parsed["actions"][0]["advisories"][0]["fixed_version"],
+        "1.5.0"
+    );
+}
+
+#[tokio::test]
+async fn ghsa_advisories_follow_link_pagination() {
+    use wiremock::matchers::query_param;
+
+    let server = MockServer::start().await;
+    let advisory = |id: &str| {
+        serde_json::json!({
+            "ghsa_id": id,
+            "summary": format!("{id} summary"),
+            "severity": "high",
+            "html_url": format!("https://github.com/advisories/{id}")
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .and(query_param("after", "cursor-2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([advisory("GHSA-page-two-0002")])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .and(query_param("affects", "test-org/composite-a"))
+        .and(query_param("per_page", "100"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "link",
+                    format!(
+                        r#"<{}/advisories?ecosystem=actions&affects=test-org/composite-a&per_page=100&after=cursor-2>; rel="next""#,
+                        server.uri()
+                    ),
+                )
+                .set_body_json(serde_json::json!([advisory("GHSA-page-one-0001")])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let stdout = stdout_of_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--provider",
+            "ghsa",
+            "--depth",
+            "0",
+        ],
+    );
+    assert!(
+        stdout.contains("GHSA-page-one-0001") && stdout.contains("GHSA-page-two-0002"),
+        "advisories from both pages should appear, got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn epss_scores_cve_aliases() {
+    use wiremock::matchers::query_param;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .and(query_param("affects", "test-org/composite-a"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                "ghsa_id": "GHSA-test-epss-0001",
+                "cve_id": "CVE-2024-0001",
+                "summary": "Exploited composite vulnerability",
+                "severity": "critical",
+                "html_url": "https://github.com/advisories/GHSA-test-epss-0001"
+            }])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/epss"))
+        .and(query_param("cve", "CVE-2024-0001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{"cve": "CVE-2024-0001", "epss": "0.42000", "percentile": "0.97500"}]
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--depth",
+        "0",
+        "--epss",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains("epss: 42.0% (percentile 97.5)"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let epss = &parsed["actions"][0]["advisories"][0]["epss"];
+    assert_eq!(epss["probability"], 0.42);
+    assert_eq!(epss["percentile"], 0.975);
+}
+
+#[tokio::test]
+async fn withdrawn_osv_advisories_are_dropped_unless_included() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/osv-query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "vulns": [
+                {"id": "GHSA-live-0001", "summary": "Still valid"},
+                {
+                    "id": "GHSA-gone-0002",
+                    "summary": "Withdrawn report",
+                    "withdrawn": "2024-03-05T10:00:00Z"
+                }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "osv",
+        "--depth",
+        "0",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(stdout.contains("GHSA-live-0001"), "got:\n{stdout}");
+    assert!(!stdout.contains("GHSA-gone-0002"), "got:\n{stdout}");
+
+    let mut include_args = args.to_vec();
+    include_args.push("--include-withdrawn");
+    let stdout = stdout_of_mock(&server, &include_args);
+    assert!(
+        stdout.contains("GHSA-gone-0002") && stdout.contains("withdrawn: 2024-03-05"),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn since_reports_only_newly_published_advisories() {
+    let server = MockServer::start().await;
+    let advisory = |id: &str, published: &str| {
+        serde_json::json!({
+            "ghsa_id": id,
+            "summary": format!("{id} summary"),
+            "severity": "high",
+            "html_url": format!("https://github.com/advisories/{id}"),
+            "published_at": published,
+            "updated_at": "2025-03-01T00:00:00Z"
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            advisory("GHSA-old0-0000-0001", "2024-06-01T09:00:00Z"),
+            advisory("GHSA-new0-0000-0002", "2025-02-20T09:00:00Z")
+        ])))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--depth",
+        "0",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains("published: 2024-06-01 (modified 2025-03-01)"),
+        "got:\n{stdout}"
+    );
+
+    let mut since_args = args.to_vec();
+    since_args.extend(["--since", "2025-01-01"]);
+    let stdout = stdout_of_mock(&server, &since_args);
+    assert!(stdout.contains("GHSA-new0-0000-0002"), "got:\n{stdout}");
+    assert!(!stdout.contains("GHSA-old0-0000-0001"), "got:\n{stdout}");
+
+    let output = run_ghss_with_mock(&server, &["--file", "x.yml", "--since", "last week"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("YYYY-MM-DD"));
+}
+
+#[tokio::test]
+async fn advisory_reported_by_both_providers_lists_both_sources() {
+    let server = setup_advisory_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/osv-query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "vulns": [{
+                "id": "GHSA-test-adv1-0001",
+                "aliases": ["CVE-2025-0001"],
+                "summary": "Test composite vulnerability"
+            }]
+        })))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--depth",
+        "0",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains("test-org/composite-a@v1\n  runtime: node20\n  GHSA-test-adv1-0001 (high): Test composite vulnerability\n    https://github.com/advisories/GHSA-test-adv1-0001\n    found by: GHSA, OSV\n"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let advisory = &parsed["actions"][0]["advisories"][0];
+    assert_eq!(advisory["sources"], serde_json::json!(["GHSA", "OSV"]));
+    assert_eq!(advisory["aliases"], serde_json::json!(["CVE-2025-0001"]));
+}
+
+#[tokio::test]
+async fn details_shows_description_and_references() {
+    let server = setup_advisory_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/osv-query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "vulns": [{
+                "id": "GHSA-test-adv1-0001",
+                "summary": "Test composite vulnerability",
+                "details": "Untrusted input reaches a shell.\n\nUpgrade to v2.",
+                "references": [
+                    {"type": "FIX", "url": "https://github.com/test-org/composite-a/commit/abc"}
+                ]
+            }]
+        })))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--depth",
+        "0",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(!stdout.contains("description:"), "got:\n{stdout}");
+    assert!(!stdout.contains("references:"), "got:\n{stdout}");
+
+    let mut details_args = args.to_vec();
+    details_args.push("--details");
+    let stdout = stdout_of_mock(&server, &details_args);
+    assert!(
+        stdout.contains("    affected: >= 1.0.0, < 2.0.0 (applies to this version)\n    fix: upgrade to 2.0.0\n    description:\n      Untrusted input reaches a shell.\n\n      Upgrade to v2.\n    references:\n      https://github.com/test-org/composite-a/commit/abc\n"),
+        "got:\n{stdout}"
+    );
+
+    details_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &details_args)).unwrap();
+    let advisory = &parsed["actions"][0]["advisories"][0];
+    assert_eq!(
+        advisory["description"],
+        "Untrusted input reaches a shell.\n\nUpgrade to v2."
+    );
+    assert_eq!(
+        advisory["references"],
+        serde_json::json!(["https://github.com/test-org/composite-a/commit/abc"])
+    );
+}
+
 #[tokio::test]
 async fn mocked_advisory_appears_in_json_output() {
     let server = setup_advisory_mock_server().await;
@@ -758,33 +1340,35 @@ async fn setup_deps_mock_server() -> MockServer {
         .mount(&server)
         .await;
 
-    // OSV query for lodash: return a vulnerability
+    // OSV batch: lodash has a vulnerability
     Mock::given(method("POST"))
-        .and(path("/osv-query"))
-        .and(body_string_contains("lodash"))
+        .and(path("/osv-querybatch"))
+        .respond_with(osv_batch(&[("lodash", "GHSA-dep-lodash-0001")]))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/osv-vulns/GHSA-dep-lodash-0001"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "vulns": [{
-                "id": "GHSA-dep-lodash-0001",
-                "summary": "Prototype pollution in lodash",
-                "references": [
-                    {"type": "ADVISORY", "url": "https://example.com/lodash-vuln"}
-                ],
-                "affected": [{
-                    "ranges": [{
-                        "type": "ECOSYSTEM",
-                        "events": [
-                            {"introduced": "0"},
-                            {"fixed": "4.17.21"}
-                        ]
-                    }]
-                }],
-                "database_specific": {"severity": "HIGH"}
-            }]
+            "id": "GHSA-dep-lodash-0001",
+            "summary": "Prototype pollution in lodash",
+            "references": [
+                {"type": "ADVISORY", "url": "https://example.com/lodash-vuln"}
+            ],
+            "affected": [{
+                "ranges": [{
+                    "type": "ECOSYSTEM",
+                    "events": [
+                        {"introduced": "0"},
+                        {"fixed": "4.17.21"}
+                    ]
+                }]
+            }],
+            "database_specific": {"severity": "HIGH"}
         })))
         .mount(&server)
         .await;
 
-    // OSV: return empty for everything else
+    // OSV single queries (actions): return empty
     Mock::given(method("POST"))
         .and(path("/osv-query"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
@@ -792,6 +1376,20 @@ async fn setup_deps_mock_server() -> MockServer {
         .mount(&server)
         .await;
 
+    // npm registry metadata for lodash: 4.x is an old major line
+    Mock::given(method("GET"))
+        .and(path("/npm/lodash"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "dist-tags": {"latest": "5.0.0"},
+            "time": {
+                "4.17.21": "2021-02-20T15:42:16.891Z",
+                "5.0.0": "2025-01-01T00:00:00.000Z"
+            },
+            "versions": {"4.17.21": {}, "5.0.0": {}}
+        })))
+        .mount(&server)
+        .await;
+
     server
 }
 
@@ -821,6 +1419,63 @@ async fn deps_flag_shows_dependency_vulnerability() {
         stdout.contains("GHSA-dep-lodash-0001"),
         "should show lodash advisory ID, got:\n{stdout}"
     );
+    assert!(
+        stdout.contains(
+            "registry: latest 5.0.0 (2025-01-01); 4.x unmaintained, newest 4.17.21 (2021-02-20)"
+        ),
+        "should show npm maintenance signals, got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn overrides_suppress_dependency_advisory_and_export_openvex() {
+    let server = setup_deps_mock_server().await;
+    let vex_path = std::env::temp_dir().join(format!("ghss-vex-{}.json", std::process::id()));
+    let output = run_ghss_with_mock_and_token(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--provider",
+            "all",
+            "--deps",
+            "--overrides",
+            &fixture("lodash-overrides.yml"),
+            "--openvex-out",
+            vex_path.to_str().unwrap(),
+            "--fail-on-severity",
+            "high",
+        ],
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "suppressed advisory should not fail the run, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("not affected: GHSA-dep-lodash-0001 (vulnerable_code_not_in_execute_path)"),
+        "should list the suppressed advisory, got:\n{stdout}"
+    );
+    assert!(
+        !stdout.contains("registry:"),
+        "should skip registry lookups for fully suppressed packages, got:\n{stdout}"
+    );
+
+    let vex: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&vex_path).unwrap()).unwrap();
+    std::fs::remove_file(&vex_path).ok();
+    assert_eq!(vex["@context"], "https://openvex.dev/ns/v0.2.0");
+    let statement = &vex["statements"][0];
+    assert_eq!(statement["vulnerability"]["name"], "GHSA-dep-lodash-0001");
+    assert_eq!(statement["products"][0]["@id"], "pkg:npm/lodash@4.17.20");
+    assert_eq!(statement["status"], "not_affected");
+    assert_eq!(
+        statement["impact_statement"],
+        "The action never merges untrusted objects."
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -828,7 +1483,7 @@ async fn deps_flag_shows_dependency_vulnerability() {
 // ---------------------------------------------------------------------------
 
 #[tokio::test]
-async fn fail_on_severity_exits_2_when_threshold_met() {
+async fn fail_on_severity_exits_1_when_threshold_met() {
     let server = setup_advisory_mock_server().await;
     let output = run_ghss_with_mock(
         &server,
@@ -842,8 +1497,8 @@ async fn fail_on_severity_exits_2_when_threshold_met() {
 
     assert_eq!(
         output.status.code(),
-        Some(2),
-        "should exit 2 when advisory meets threshold"
+        Some(1),
+        "should exit 1 when advisory meets threshold"
     );
 
     let stdout = String::from_utf8(output.stdout).unwrap();
@@ -859,6 +1514,93 @@ async fn fail_on_severity_exits_2_when_threshold_met() {
     );
 }
 
+#[tokio::test]
+async fn require_pinned_exits_1_for_tag_refs() {
+    let server = setup_mock_server().await;
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+    ];
+    let output = run_ghss_with_mock(&server, &args);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(
+            "unpinned-action (medium): test-org/composite-a is referenced by tag `v1`, not a full commit SHA"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let mut pinned_args = args.to_vec();
+    pinned_args.push("--require-pinned");
+    let output = run_ghss_with_mock(&server, &pinned_args);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("2 action reference(s) not pinned to a commit SHA"),
+        "got:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("depth-test-workflow.yml:"),
+        "should point at each reference, got:\n{stderr}"
+    );
+}
+
+#[tokio::test]
+async fn fail_on_errors_exits_3_when_a_stage_fails() {
+    // The mock server has no ref endpoints, so RefResolve fails for every action.
+    let server = setup_mock_server().await;
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+    ];
+    let output = run_ghss_with_mock(&server, &args);
+    assert_eq!(output.status.code(), Some(0));
+
+    let mut failing_args = args.to_vec();
+    failing_args.extend(["--fail-on", "errors"]);
+    let output = run_ghss_with_mock(&server, &failing_args);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("2 stage error(s) left results incomplete")
+            && stderr.contains(
+                "test-org/composite-a@v1 - RefResolve: ref 'v1' not found as tag or branch"
+            ),
+        "got:\n{stderr}"
+    );
+
+    let mut strict_args = args.to_vec();
+    strict_args.push("--strict");
+    let output = run_ghss_with_mock(&server, &strict_args);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("2 stage error(s) left results incomplete")
+    );
+
+    // Findings over the threshold take precedence over stage errors.
+    failing_args.extend(["--fail-on", "unpinned"]);
+    let output = run_ghss_with_mock(&server, &failing_args);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("2 action reference(s) not pinned to a commit SHA")
+            && stderr.contains("2 stage error(s) left results incomplete"),
+        "got:\n{stderr}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    assert_eq!(parsed["actions"][0]["errors"][0]["stage"], "RefResolve");
+}
+
 #[tokio::test]
 async fn fail_on_severity_exits_0_when_below_threshold() {
     let server = setup_advisory_mock_server().await;
@@ -880,6 +1622,209 @@ async fn fail_on_severity_exits_0_when_below_threshold() {
     );
 }
 
+#[tokio::test]
+async fn advisory_outside_pinned_version_does_not_fail() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                "ghsa_id": "GHSA-test-old0-0001",
+                "summary": "Fixed before v1",
+                "severity": "high",
+                "html_url": "https://github.com/advisories/GHSA-test-old0-0001",
+                "vulnerabilities": [{
+                    "package": {"ecosystem": "actions", "name": "test-org/composite-a"},
+                    "vulnerable_version_range": "< 1.0.0"
+                }]
+            }])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/osv-query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--fail-on-severity",
+        "high",
+    ];
+    let output = run_ghss_with_mock(&server, &args);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("    affected: < 1.0.0 (not applicable to this pinned version)\n"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    assert_eq!(parsed["actions"][0]["advisories"][0]["applicable"], false);
+
+    let mut sarif_args = args.to_vec();
+    sarif_args.extend(["--format", "sarif"]);
+    let sarif: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &sarif_args)).unwrap();
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["suppressions"][0]["justification"],
+        "pinned version is outside the affected range < 1.0.0"
+    );
+}
+
+#[tokio::test]
+async fn ignored_advisory_does_not_fail() {
+    let server = setup_advisory_mock_server().await;
+    let ignore_file = std::env::temp_dir().join(format!("ghss-ignore-{}", std::process::id()));
+    std::fs::write(&ignore_file, "GHSA-test-adv1-0001 reason=\"accepted\"\n").unwrap();
+    let output = run_ghss_with_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--ignore-file",
+            ignore_file.to_str().unwrap(),
+            "--fail-on-severity",
+            "high",
+        ],
+    );
+    std::fs::remove_file(&ignore_file).ok();
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ignored advisories should not fail the run, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("GHSA-test-adv1-0001"), "got:\n{stdout}");
+}
+
+#[tokio::test]
+async fn baseline_advisory_does_not_fail() {
+    let server = setup_advisory_mock_server().await;
+    let args = ["--file", &fixture("depth-test-workflow.yml")];
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let baseline = std::env::temp_dir().join(format!("ghss-baseline-{}.json", std::process::id()));
+    std::fs::write(&baseline, stdout_of_mock(&server, &json_args)).unwrap();
+
+    let mut failing_args = args.to_vec();
+    failing_args.extend(["--fail-on-severity", "high", "--baseline"]);
+    failing_args.push(baseline.to_str().unwrap());
+    let output = run_ghss_with_mock(&server, &failing_args);
+    std::fs::remove_file(&baseline).ok();
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "advisories in the baseline should not fail the run, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("GHSA-test-adv1-0001"), "got:\n{stdout}");
+}
+
+#[tokio::test]
+async fn openvex_format_states_each_action_advisory() {
+    let server = setup_advisory_mock_server().await;
+    let stdout = stdout_of_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--vex",
+            &fixture("test-org.vex.json"),
+            "--format",
+            "openvex",
+        ],
+    );
+    let vex: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(vex["@context"], "https://openvex.dev/ns/v0.2.0");
+    assert_eq!(vex["author"], "ghss");
+    let statements = vex["statements"].as_array().unwrap();
+    assert!(!statements.is_empty());
+
+    let status_of = |product: &str| {
+        statements
+            .iter()
+            .find(|s| s["products"][0]["@id"] == product)
+            .map(|s| s["status"].as_str().unwrap().to_string())
+    };
+    // The --vex statement is carried over; the rest are not yet known to be
+    // affected.
+    assert_eq!(
+        status_of("pkg:githubactions/test-org/composite-a@v1").as_deref(),
+        Some("not_affected"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        statements
+            .iter()
+            .filter(|s| s["status"] != "not_affected")
+            .all(|s| s["status"] == "under_investigation" || s["status"] == "affected"),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn vex_not_affected_statement_suppresses_failure() {
+    let server = setup_advisory_mock_server().await;
+    let output = run_ghss_with_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--vex",
+            &fixture("test-org.vex.json"),
+            "--fail-on-severity",
+            "high",
+        ],
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "not_affected advisories should not fail the run, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(
+            "vex: not_affected (vulnerable_code_not_in_execute_path): \
+             The affected input is never set by these actions."
+        ),
+        "should show the VEX status, got:\n{stdout}"
+    );
+
+    let sarif = stdout_of_mock(
+        &server,
+        &[
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--vex",
+            &fixture("test-org.vex.json"),
+            "--format",
+            "sarif",
+        ],
+    );
+    let sarif: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+    let results: Vec<&serde_json::Value> = sarif["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r["ruleId"] == "ghss/vulnerable-action")
+        .collect();
+    assert!(!results.is_empty());
+    for result in results {
+        assert_eq!(result["suppressions"][0]["kind"], "external");
+    }
+}
+
 #[tokio::test]
 async fn fail_on_severity_exits_0_without_flag() {
     let server = setup_advisory_mock_server().await;
@@ -911,8 +1856,8 @@ async fn fail_on_severity_checks_dependency_advisories() {
 
     assert_eq!(
         output.status.code(),
-        Some(2),
-        "should exit 2 when dependency advisory meets threshold, stderr: {}",
+        Some(1),
+        "should exit 1 when dependency advisory meets threshold, stderr: {}",
         String::from_utf8_lossy(&output.stderr)
     );
 
@@ -939,8 +1884,8 @@ async fn fail_on_severity_still_outputs_json() {
 
     assert_eq!(
         output.status.code(),
-        Some(2),
-        "should exit 2 when threshold met"
+        Some(1),
+        "should exit 1 when threshold met"
     );
 
     let stdout = String::from_utf8(output.stdout).unwrap();
@@ -1033,33 +1978,38 @@ async fn setup_go_deps_mock_server() -> MockServer {
         .mount(&server)
         .await;
 
-    // OSV query for gin-gonic/gin: return a vulnerability
+    // OSV batch: github.com/gin-gonic/gin has a vulnerability
     Mock::given(method("POST"))
-        .and(path("/osv-query"))
-        .and(body_string_contains("gin-gonic/gin"))
+        .and(path("/osv-querybatch"))
+        .respond_with(osv_batch(&[(
+            "github.com/gin-gonic/gin",
+            "GHSA-go-gin-0001",
+        )]))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/osv-vulns/GHSA-go-gin-0001"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "vulns": [{
-                "id": "GHSA-go-gin-0001",
-                "summary": "Test vulnerability in gin framework",
-                "references": [
-                    {"type": "ADVISORY", "url": "https://example.com/gin-vuln"}
-                ],
-                "affected": [{
-                    "ranges": [{
-                        "type": "ECOSYSTEM",
-                        "events": [
-                            {"introduced": "0"},
-                            {"fixed": "1.9.2"}
-                        ]
-                    }]
-                }],
-                "database_specific": {"severity": "HIGH"}
-            }]
+            "id": "GHSA-go-gin-0001",
+            "summary": "Test vulnerability in gin framework",
+            "references": [
+                {"type": "ADVISORY", "url": "https://example.com/gin-vuln"}
+            ],
+            "affected": [{
+                "ranges": [{
+                    "type": "ECOSYSTEM",
+                    "events": [
+                        {"introduced": "0"},
+                        {"fixed": "1.9.2"}
+                    ]
+                }]
+            }],
+            "database_specific": {"severity": "HIGH"}
         })))
         .mount(&server)
         .await;
 
-    // OSV: return empty for everything else
+    // OSV single queries (actions): return empty
     Mock::given(method("POST"))
         .and(path("/osv-query"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
@@ -1166,9 +2116,9 @@ async fn deps_flag_sends_correct_go_ecosystem_string() {
 
     // Only match OSV queries with correct "Go" ecosystem (capital G)
     let osv_mock = Mock::given(method("POST"))
-        .and(path("/osv-query"))
+        .and(path("/osv-querybatch"))
         .and(body_string_contains(r#""ecosystem":"Go""#))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .respond_with(osv_batch(&[]))
         .expect(1..)
         .mount_as_scoped(&server)
         .await;
@@ -1187,3 +2137,1890 @@ async fn deps_flag_sends_correct_go_ecosystem_string() {
     // Scoped mock verifies that at least one request matched "ecosystem":"Go"
     drop(osv_mock);
 }
+
+// ---------------------------------------------------------------------------
+// Workflow run correlation
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn run_id_marks_which_actions_executed() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/app/actions/runs/99/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "total_count": 1,
+            "jobs": [{
+                "name": "build",
+                "steps": [
+                    {
+                        "name": "Run test-org/composite-a@v1",
+                        "conclusion": "success",
+                        "started_at": "2026-01-01T00:00:00Z",
+                        "completed_at": "2026-01-01T00:00:12Z"
+                    },
+                    {"name": "Run test-org/leaf-action@v1", "conclusion": "skipped"}
+                ]
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--run-id",
+        "99",
+        "--repo",
+        "test-org/app",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/composite-a@v1\n  run 99: executed\n    build / Run test-org/composite-a@v1: success in 12s\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("test-org/leaf-action@v1\n  run 99: not executed\n"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let execution = &parsed["actions"][0]["execution"];
+    assert_eq!(execution["run_id"], 99);
+    assert_eq!(execution["steps"][0]["duration_secs"], 12);
+}
+
+// ---------------------------------------------------------------------------
+// Update hygiene
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn update_hygiene_reports_action_repo_config() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/test-org/composite-a/HEAD/.github/dependabot.yml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "version: 2\nupdates:\n  - package-ecosystem: github-actions\n    directory: /\n",
+        ))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--update-hygiene",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/composite-a@v1\n  updates: dependabot (.github/dependabot.yml), covers github-actions\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("test-org/leaf-action@v1\n  updates: no Dependabot or Renovate config\n"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let hygiene = &parsed["actions"][0]["update_hygiene"];
+    assert_eq!(hygiene["tool"], "dependabot");
+    assert_eq!(hygiene["covers_actions"], true);
+}
+
+#[tokio::test]
+async fn releases_report_lag_and_flag_old_majors() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/composite-a/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"tag_name": "v3.1.0", "draft": false, "prerelease": false},
+            {"tag_name": "v3.0.0", "draft": false, "prerelease": false},
+            {"tag_name": "v2.0.0", "draft": false, "prerelease": false},
+            {"tag_name": "v1.2.0", "draft": false, "prerelease": false}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"name": "v1"},
+            {"name": "v1.0.1"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--releases",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout
+            .contains("test-org/composite-a@v1\n  releases: 3 releases behind v3.1.0 (2 majors)\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("test-org/leaf-action@v1\n  releases: up to date with v1.0.1\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "outdated-major (medium): test-org/composite-a@v1 is 2 major versions behind the latest release v3.1.0"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let releases = &parsed["actions"][0]["releases"];
+    assert_eq!(releases["latest"], "v3.1.0");
+    assert_eq!(releases["behind"], 3);
+    assert_eq!(releases["majors_behind"], 2);
+    let findings = parsed["findings"].as_array().unwrap();
+    assert!(findings.iter().any(|f| f["rule_id"] == "outdated-major"));
+}
+
+#[tokio::test]
+async fn releases_flag_major_on_retired_node_runtime() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"tag_name": "v2.0.0", "draft": false, "prerelease": false},
+            {"tag_name": "v1.4.0", "draft": false, "prerelease": false}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/test-org/leaf-action/v1.4.0/action.yml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("name: Leaf\nruns:\n  using: node16\n  main: index.js\n"),
+        )
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--releases",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/leaf-action@v1\n  releases: 1 release behind v2.0.0 (1 major); this major runs on node16\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "outdated-major (medium): test-org/leaf-action@v1 is behind the latest release v2.0.0; its newest release on this major, v1.4.0, runs on node16"
+        ),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn recommend_pins_latest_release_outside_advisories() {
+    let server = setup_advisory_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/composite-a/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"tag_name": "v2.1.0-rc.1", "draft": false, "prerelease": true},
+            {"tag_name": "v2.0.0", "draft": false, "prerelease": false},
+            {"tag_name": "v1.9.0", "draft": false, "prerelease": false}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/composite-a/git/ref/tags/v2.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v2.0.0",
+            "object": {"type": "commit", "sha": "2222222222222222222222222222222222222222"}
+        })))
+        .mount(&server)
+        .await;
+    for listing in ["releases", "tags"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/leaf-action/{listing}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+    }
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--recommend",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/composite-a@v1\n  recommended: 2222222222222222222222222222222222222222 # v2.0.0\n"
+        ),
+        "got:\n{stdout}"
+    );
+    // No releases to recommend.
+    assert!(
+        !stdout.contains("test-org/leaf-action@v1\n  recommended:"),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let recommended = &parsed["actions"][0]["recommended_ref"];
+    assert_eq!(recommended["tag"], "v2.0.0");
+    assert_eq!(
+        recommended["sha"],
+        "2222222222222222222222222222222222222222"
+    );
+}
+
+#[tokio::test]
+async fn project_info_reports_license_and_scorecard() {
+    let server = setup_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path(
+            "/depsdev/v3/projects/github.com%2Ftest-org%2Fleaf-action",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "license": "Apache-2.0",
+            "scorecard": {"date": "2025-03-10T00:00:00Z", "overallScore": 5.4}
+        })))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--project-info",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/leaf-action@v1\n  project: license Apache-2.0, scorecard 5.4 (2025-03-10)\n"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    let leaf = parsed["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["repo"] == "leaf-action")
+        .unwrap();
+    assert_eq!(leaf["project"]["license"], "Apache-2.0");
+    assert_eq!(leaf["project"]["scorecard"], 5.4);
+    // composite-a is unknown to deps.dev.
+    assert!(parsed["actions"][0].get("project").is_none());
+}
+
+#[tokio::test]
+async fn osv_offline_provider_reads_downloaded_database() {
+    use std::io::Write;
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file(
+        "GHSA-offl-ine0-0001.json",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .unwrap();
+    let record = serde_json::json!({
+        "id": "GHSA-offl-ine0-0001",
+        "summary": "Offline composite vulnerability",
+        "affected": [{"package": {"ecosystem": "GitHub Actions", "name": "test-org/composite-a"}}],
+        "database_specific": {"severity": "HIGH"}
+    });
+    zip.write_all(record.to_string().as_bytes()).unwrap();
+    let dump = zip.finish().unwrap().into_inner();
+
+    let server = setup_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/dumps/GitHub%20Actions/all.zip"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(dump))
+        .mount(&server)
+        .await;
+
+    let dir = std::env::temp_dir().join(format!("ghss-osv-db-cli-{}", std::process::id()));
+    let update = ghss()
+        .args(["db", "update", "--ecosystem", "GitHub Actions", "--dir"])
+        .arg(&dir)
+        .env("GHSS_OSV_DUMP_URL", format!("{}/dumps", server.uri()))
+        .output()
+        .expect("failed to execute");
+    assert!(
+        update.status.success(),
+        "db update failed: {}",
+        String::from_utf8_lossy(&update.stderr)
+    );
+    assert!(String::from_utf8_lossy(&update.stdout).contains("GitHub Actions: 1 advisories"));
+
+    let output = ghss()
+        .args([
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--provider",
+            "osv-offline",
+        ])
+        .env("GHSS_API_BASE_URL", server.uri())
+        .env("GHSS_RAW_BASE_URL", server.uri())
+        .env("GHSS_OSV_BASE_URL", "http://127.0.0.1:1")
+        .env("GHSS_OSV_DB_DIR", &dir)
+        .env_remove("GITHUB_TOKEN")
+        .output()
+        .expect("failed to execute");
+    std::fs::remove_dir_all(&dir).ok();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("GHSA-offl-ine0-0001 (high): Offline composite vulnerability"),
+        "advisory should come from the offline database, got:\n{stdout}"
+    );
+}
+
+#[test]
+fn run_id_requires_repo() {
+    let output = ghss()
+        .args([
+            "--file",
+            &fixture("depth-test-workflow.yml"),
+            "--run-id",
+            "1",
+        ])
+        .output()
+        .expect("failed to execute");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--repo"), "got: {stderr}");
+}
+
+// ---------------------------------------------------------------------------
+// find-usage
+// ---------------------------------------------------------------------------
+
+const BAD_SHA: &str = "0e58ed8671d6b60d0890c21b07f8835ace038e67";
+
+async fn setup_find_usage_mock_server() -> MockServer {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/orgs/acme/repos"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"name": "app", "archived": false},
+            {"name": "site", "archived": false},
+            {"name": "legacy", "archived": true}
+        ])))
+        .mount(&server)
+        .await;
+
+    for (repo, workflow, yaml) in [
+        (
+            "app",
+            "ci.yml",
+            "jobs:\n  build:\n    steps:\n      - uses: tj-actions/changed-files@v35\n",
+        ),
+        (
+            "site",
+            "deploy.yml",
+            "jobs:\n  deploy:\n    steps:\n      - uses: tj-actions/changed-files@0e58ed8671d6b60d0890c21b07f8835ace038e67\n      - uses: actions/checkout@v4\n",
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/acme/{repo}/contents/.github/workflows"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([{"name": workflow}, {"name": "README.md"}])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/acme/{repo}/HEAD/.github/workflows/{workflow}"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_string(yaml))
+            .mount(&server)
+            .await;
+    }
+
+    Mock::given(method("GET"))
+        .and(path("/repos/tj-actions/changed-files/git/ref/tags/v35"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": {"type": "commit", "sha": "1111111111111111111111111111111111111111"}
+        })))
+        .mount(&server)
+        .await;
+
+    server
+}
+
+#[tokio::test]
+async fn find_usage_lists_uses_across_org() {
+    let server = setup_find_usage_mock_server().await;
+
+    let stdout = stdout_of_mock(
+        &server,
+        &["find-usage", "tj-actions/changed-files", "--org", "acme"],
+    );
+    assert_eq!(
+        stdout,
+        format!(
+            "2 uses of tj-actions/changed-files in acme:\n  \
+             [mutable] acme/app: tj-actions/changed-files@v35\n    \
+             at .github/workflows/ci.yml:4:15 (job build)\n  \
+             [pinned] acme/site: tj-actions/changed-files@{BAD_SHA}\n    \
+             at .github/workflows/deploy.yml:4:15 (job deploy)\n"
+        )
+    );
+}
+
+#[tokio::test]
+async fn find_usage_flags_compromised_sha() {
+    let server = setup_find_usage_mock_server().await;
+
+    let output = run_ghss_with_mock(
+        &server,
+        &[
+            "find-usage",
+            "tj-actions/changed-files",
+            "--org",
+            "acme",
+            "--sha",
+            &BAD_SHA[..12],
+            "--format",
+            "json",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let usages = parsed.as_array().unwrap();
+    assert_eq!(usages.len(), 2);
+    assert_eq!(usages[0]["repository"], "acme/site");
+    assert_eq!(usages[0]["exposure"], "compromised");
+    assert_eq!(usages[1]["exposure"], "mutable");
+    assert_eq!(
+        usages[1]["resolved_sha"],
+        "1111111111111111111111111111111111111111"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// find-duplicates
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn find_duplicates_groups_copied_workflows() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs/acme/repos"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"name": "app", "archived": false},
+            {"name": "site", "archived": false},
+            {"name": "docs", "archived": false}
+        ])))
+        .mount(&server)
+        .await;
+
+    let ci = "name: CI\non: push\njobs:\n  build:\n    steps:\n      - uses: tj-actions/changed-files@v35\n";
+    for (repo, workflow, yaml) in [
+        ("app", "ci.yml", ci.to_string()),
+        ("site", "build.yml", format!("# copied from app\n{ci}")),
+        ("docs", "ci.yml", ci.replace("@v35", "@v46")),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/acme/{repo}/contents/.github/workflows"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{"name": workflow}])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/acme/{repo}/HEAD/.github/workflows/{workflow}"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_string(yaml))
+            .mount(&server)
+            .await;
+    }
+
+    let stdout = stdout_of_mock(&server, &["find-duplicates", "--org", "acme"]);
+    assert!(
+        stdout.starts_with("1 duplicated workflows in acme:\n  2 copies of \"CI\" ("),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.ends_with(
+            "\n    acme/app: .github/workflows/ci.yml\n    acme/site: .github/workflows/build.yml\n"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let json = stdout_of_mock(
+        &server,
+        &["find-duplicates", "--org", "acme", "--format", "json"],
+    );
+    let groups: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(groups.as_array().unwrap().len(), 1);
+    assert_eq!(groups[0]["fingerprint"].as_str().unwrap().len(), 64);
+    assert_eq!(groups[0]["copies"][1]["repository"], "acme/site");
+}
+
+// ---------------------------------------------------------------------------
+// providers check
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn providers_check_reports_each_provider() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+            "message": "Bad credentials"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/osv-query"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"vulns": [
+                {"id": "GHSA-mrrh-fwg8-r2c3", "summary": "changed-files leaks secrets"}
+            ]})),
+        )
+        .mount(&server)
+        .await;
+
+    let output = run_ghss_with_mock_and_token(&server, &["providers", "check", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(3));
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let summary: Vec<(&str, &str, &str)> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["provider"].as_str().unwrap(),
+                r["kind"].as_str().unwrap(),
+                r["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        &summary[..2],
+        [("GHSA", "actions", "auth"), ("OSV", "actions", "ok")]
+    );
+    assert_eq!(results[1]["advisories"], 1);
+
+    let stdout = stdout_of_mock(&server, &["--provider", "osv", "providers", "check"]);
+    assert!(
+        stdout.starts_with("OSV (actions): ok in "),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn snyk_is_probed_with_token_from_env() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/snyk/orgs/my-org/packages/pkg:npm%2Flodash@4.17.20/issues",
+        ))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let snyk = |token: Option<&str>| {
+        let mut cmd = ghss();
+        cmd.args(["--provider", "ghsa", "--snyk", "providers", "check"])
+            .env("GHSS_API_BASE_URL", server.uri())
+            .env("GHSS_SNYK_BASE_URL", format!("{}/snyk", server.uri()))
+            .env("SNYK_ORG_ID", "my-org")
+            .env("GITHUB_TOKEN", "fake-token");
+        match token {
+            Some(token) => cmd.env("SNYK_TOKEN", token),
+            None => cmd.env_remove("SNYK_TOKEN"),
+        };
+        cmd.output().expect("failed to execute")
+    };
+
+    let output = snyk(Some("revoked"));
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("\nSnyk (packages): auth failed in "),
+        "got:\n{stdout}"
+    );
+
+    let output = snyk(None);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("SNYK_TOKEN"), "got:\n{stderr}");
+}
+
+#[tokio::test]
+async fn explain_merges_providers_by_advisory_id() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .and(query_param("cve_id", "CVE-2025-30066"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                "ghsa_id": "GHSA-mrrh-fwg8-r2c3",
+                "cve_id": "CVE-2025-30066",
+                "summary": "changed-files leaks secrets",
+                "description": "A compromised release printed CI secrets to logs.",
+                "severity": "high",
+                "html_url": "https://github.com/advisories/GHSA-mrrh-fwg8-r2c3",
+                "references": ["https://github.com/tj-actions/changed-files/issues/2463"],
+                "vulnerabilities": [{
+                    "package": {"ecosystem": "actions", "name": "tj-actions/changed-files"},
+                    "vulnerable_version_range": "< 46.0.1",
+                    "first_patched_version": "46.0.1"
+                }]
+            }])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/osv-vulns/CVE-2025-30066"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "GHSA-mrrh-fwg8-r2c3",
+            "aliases": ["CVE-2025-30066"],
+            "summary": "changed-files leaks secrets",
+            "references": [{"type": "WEB", "url": "https://example.com/write-up"}],
+            "affected": [{
+                "package": {"ecosystem": "GitHub Actions", "name": "tj-actions/changed-files"},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "46.0.1"}]}]
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let stdout = stdout_of_mock(&server, &["explain", "cve-2025-30066"]);
+    assert_eq!(
+        stdout,
+        "GHSA-mrrh-fwg8-r2c3 (high): changed-files leaks secrets\n    \
+         https://github.com/advisories/GHSA-mrrh-fwg8-r2c3\n    \
+         found by: GHSA, OSV\n    \
+         aliases: CVE-2025-30066\n    \
+         affected:\n      \
+         tj-actions/changed-files (actions): < 46.0.1, fixed in 46.0.1\n    \
+         description:\n      \
+         A compromised release printed CI secrets to logs.\n    \
+         references:\n      \
+         https://github.com/tj-actions/changed-files/issues/2463\n      \
+         https://example.com/write-up\n"
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&stdout_of_mock(
+        &server,
+        &["explain", "CVE-2025-30066", "--format", "json"],
+    ))
+    .unwrap();
+    assert_eq!(json["id"], "GHSA-mrrh-fwg8-r2c3");
+    assert_eq!(json["affected"][0]["fixed"], "46.0.1");
+
+    let output = run_ghss_with_mock(&server, &["explain", "GHSA-none-none-none"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("GHSA-none-none-none not found by GHSA, OSV")
+    );
+}
+
+#[tokio::test]
+async fn pin_rewrites_tag_refs_to_commit_shas() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/composite-a/git/ref/tags/v1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v1",
+            "object": {"type": "commit", "sha": "1111111111111111111111111111111111111111"}
+        })))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-pin-{}.yml", std::process::id()));
+    let original = std::fs::read_to_string(fixture("depth-test-workflow.yml")).unwrap();
+    std::fs::write(&workflow, &original).unwrap();
+    let file = workflow.to_str().unwrap();
+
+    // leaf-action@v1 resolves as neither tag nor branch, so it is left alone.
+    let output = run_ghss_with_mock(&server, &["pin", "--file", file]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("could not resolve test-org/leaf-action@v1"),
+        "got:\n{stderr}"
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "--- a/{file}\n+++ b/{file}\n@@ -7 +7 @@\n\
+             -      - uses: test-org/composite-a@v1\n\
+             +      - uses: test-org/composite-a@1111111111111111111111111111111111111111 # v1\n"
+        )
+    );
+    assert_eq!(std::fs::read_to_string(&workflow).unwrap(), original);
+
+    let output = run_ghss_with_mock(&server, &["pin", "--file", file, "--write"]);
+    assert_eq!(output.status.code(), Some(3));
+    let pinned = std::fs::read_to_string(&workflow).unwrap();
+    std::fs::remove_file(&workflow).unwrap();
+    assert_eq!(
+        pinned,
+        original.replace(
+            "composite-a@v1",
+            "composite-a@1111111111111111111111111111111111111111 # v1"
+        )
+    );
+}
+
+#[tokio::test]
+async fn update_moves_vulnerable_actions_to_a_fixed_release() {
+    let server = setup_advisory_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/composite-a/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"tag_name": "v2.0.0", "draft": false, "prerelease": false},
+            {"tag_name": "v1.9.0", "draft": false, "prerelease": false}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/composite-a/git/ref/tags/v2.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v2.0.0",
+            "object": {"type": "commit", "sha": "2222222222222222222222222222222222222222"}
+        })))
+        .mount(&server)
+        .await;
+    // The advisory mock matches leaf-action too, but it has no releases.
+    for listing in ["releases", "tags"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/leaf-action/{listing}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+    }
+
+    let workflow = std::env::temp_dir().join(format!("ghss-update-{}.yml", std::process::id()));
+    let original = std::fs::read_to_string(fixture("depth-test-workflow.yml")).unwrap();
+    std::fs::write(&workflow, &original).unwrap();
+    let file = workflow.to_str().unwrap();
+
+    let output = run_ghss_with_mock(&server, &["--provider", "ghsa", "update", "--file", file]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no fixed release found for test-org/leaf-action@v1"),
+        "got:\n{stderr}"
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "--- a/{file}\n+++ b/{file}\n@@ -7 +7 @@\n\
+             -      - uses: test-org/composite-a@v1\n\
+             +      - uses: test-org/composite-a@2222222222222222222222222222222222222222 # v2.0.0\n"
+        )
+    );
+
+    let output = run_ghss_with_mock(
+        &server,
+        &["--provider", "ghsa", "update", "--file", file, "--write"],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let updated = std::fs::read_to_string(&workflow).unwrap();
+    std::fs::remove_file(&workflow).unwrap();
+    assert_eq!(stdout, format!("updated 1 action reference(s) in {file}\n"));
+    assert_eq!(
+        updated,
+        original.replace(
+            "composite-a@v1",
+            "composite-a@2222222222222222222222222222222222222222 # v2.0.0"
+        )
+    );
+}
+
+async fn setup_tag_mock_server(sha: &str, release_target: &str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/test-org/leaf-action/v1.0.0/action.yml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("name: Leaf Action\nruns:\n  using: node20\n  main: index.js\n"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/git/ref/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v1.0.0",
+            "object": {"type": "commit", "sha": sha}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/releases/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "tag_name": "v1.0.0",
+            "target_commitish": release_target
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn tag_history_flags_release_tags_that_moved() {
+    let first = "1111111111111111111111111111111111111111";
+    let second = "2222222222222222222222222222222222222222";
+    let dir = std::env::temp_dir().join(format!("ghss-tag-history-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let workflow = dir.join("ci.yml");
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n",
+    )
+    .unwrap();
+    let history = dir.join("tags.json");
+    let args = [
+        "--file",
+        workflow.to_str().unwrap(),
+        "--provider",
+        "ghsa",
+        "--tag-history",
+        history.to_str().unwrap(),
+    ];
+
+    // First scan: released from a branch, so only the resolution is recorded.
+    let server = setup_tag_mock_server(first, "main").await;
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(!stdout.contains("moved-tag"), "got:\n{stdout}");
+    let saved = std::fs::read_to_string(&history).unwrap();
+    assert!(
+        saved.contains("test-org/leaf-action@v1.0.0") && saved.contains(first),
+        "got:\n{saved}"
+    );
+
+    // The tag now points elsewhere, and away from the release's commit.
+    let server = setup_tag_mock_server(second, first).await;
+    let stdout = stdout_of_mock(&server, &args);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        stdout.contains(&format!(
+            "moved-tag (high): test-org/leaf-action@v1.0.0 now resolves to {second}, but resolved to {first} when scanned on"
+        )),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!(
+            "moved-tag (high): test-org/leaf-action@v1.0.0 resolves to {second}, but its release was published from {first}"
+        )),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn branch_refs_report_their_head_and_a_finding() {
+    let sha = "3333333333333333333333333333333333333333";
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/test-org/leaf-action/main/action.yml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("name: Leaf Action\nruns:\n  using: node20\n  main: index.js\n"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/git/ref/heads/main"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/heads/main",
+            "object": {"type": "commit", "sha": sha}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-branch-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@main\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(&server, &["--file", file, "--provider", "ghsa"]);
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains(&format!("test-org/leaf-action@main\n  sha: {sha}\n")),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!(
+            "branch-ref (high): test-org/leaf-action@main follows branch `main`, currently at {sha}"
+        )),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn provenance_verifies_release_attestations() {
+    use base64::Engine;
+
+    let sha = "4444444444444444444444444444444444444444";
+    let server = MockServer::start().await;
+    for (repo, tag) in [("leaf-action", "v1.0.0"), ("other-action", "v2.0.0")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}/git/ref/tags/{tag}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ref": format!("refs/tags/{tag}"),
+                "object": {"type": "commit", "sha": sha}
+            })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/releases/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "tag_name": "v1.0.0",
+            "assets": [{"name": "dist.tgz", "digest": "sha256:5e8f0a1b"}]
+        })))
+        .mount(&server)
+        .await;
+    let statement = serde_json::json!({
+        "_type": "https://in-toto.io/Statement/v1",
+        "subject": [{"name": "dist.tgz", "digest": {"sha256": "5e8f0a1b"}}],
+        "predicateType": "https://slsa.dev/provenance/v1",
+        "predicate": {"buildDefinition": {"resolvedDependencies": [{
+            "uri": "git+https://github.com/test-org/leaf-action@refs/tags/v1.0.0",
+            "digest": {"gitCommit": sha}
+        }]}}
+    });
+    Mock::given(method("GET"))
+        .and(path(
+            "/repos/test-org/leaf-action/attestations/sha256:5e8f0a1b",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "attestations": [{"bundle": {"dsseEnvelope": {
+                "payload": base64::engine::general_purpose::STANDARD.encode(statement.to_string()),
+                "payloadType": "application/vnd.in-toto+json"
+            }}}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-provenance-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(
+        &server,
+        &["--file", file, "--provider", "ghsa", "--provenance"],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains("  provenance: verified (1 asset of v1.0.0)\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("  provenance: unverified: no release for v2.0.0\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "no-provenance (low): test-org/other-action@v2.0.0 has no verifiable build provenance: no release for v2.0.0"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("no-provenance (low)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn require_signed_flags_refs_without_verified_signatures() {
+    let signed = "5555555555555555555555555555555555555555";
+    let unsigned = "6666666666666666666666666666666666666666";
+    let tag_object = "7777777777777777777777777777777777777777";
+    let server = MockServer::start().await;
+    for (repo, tag, sha, verification) in [
+        (
+            "leaf-action",
+            "v1.0.0",
+            signed,
+            serde_json::json!({
+                "verified": true,
+                "reason": "valid",
+                "signature": "-----BEGIN SSH SIGNATURE-----\n-----END SSH SIGNATURE-----"
+            }),
+        ),
+        (
+            "other-action",
+            "v2.0.0",
+            unsigned,
+            serde_json::json!({"verified": false, "reason": "unsigned", "signature": null}),
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}/commits/{sha}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sha": sha,
+                "commit": {"verification": verification}
+            })))
+            .mount(&server)
+            .await;
+    }
+    // leaf-action's tag is annotated but unsigned; other-action's is lightweight.
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/git/ref/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v1.0.0",
+            "object": {"type": "tag", "sha": tag_object}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/repos/test-org/leaf-action/git/tags/{tag_object}"
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "tag": "v1.0.0",
+            "object": {"type": "commit", "sha": signed},
+            "verification": {"verified": false, "reason": "unsigned", "signature": null}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/other-action/git/ref/tags/v2.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v2.0.0",
+            "object": {"type": "commit", "sha": unsigned}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-signed-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(
+        &server,
+        &["--file", file, "--provider", "ghsa", "--require-signed"],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains("  signatures: commit verified (ssh), tag unsigned\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("  signatures: commit unsigned\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "unsigned-ref (medium): test-org/other-action@v2.0.0 has no verified signature: commit unsigned"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("unsigned-ref (medium)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn deny_license_flags_actions_under_a_denied_license() {
+    let server = MockServer::start().await;
+    for (repo, tag, sha, spdx_id) in [
+        (
+            "leaf-action",
+            "v1.0.0",
+            "8888888888888888888888888888888888888888",
+            "AGPL-3.0-only",
+        ),
+        (
+            "other-action",
+            "v2.0.0",
+            "9999999999999999999999999999999999999999",
+            "MIT",
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}/git/ref/tags/{tag}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ref": format!("refs/tags/{tag}"),
+                "object": {"type": "commit", "sha": sha}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}/license")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "LICENSE",
+                "license": {"key": spdx_id.to_lowercase(), "spdx_id": spdx_id}
+            })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-license-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(
+        &server,
+        &[
+            "--file",
+            file,
+            "--provider",
+            "ghsa",
+            "--deny-license",
+            "AGPL",
+        ],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains("  license: AGPL-3.0-only\n"),
+        "got:\n{stdout}"
+    );
+    assert!(stdout.contains("  license: MIT\n"), "got:\n{stdout}");
+    assert!(
+        stdout.contains(
+            "denied-license (medium): test-org/leaf-action@v1.0.0 is licensed under \
+             AGPL-3.0-only, which matches the denied license `AGPL`"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("denied-license (medium)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn renamed_repository_is_followed_and_reported() {
+    let sha = "abababababababababababababababababababab";
+    let server = MockServer::start().await;
+    // test-org/leaf-action moved to new-org/leaf-action; GitHub redirects the
+    // old name to the repository's ID.
+    for (from, to) in [
+        ("/repos/test-org/leaf-action", "/repositories/42"),
+        (
+            "/repos/test-org/leaf-action/git/ref/tags/v1.0.0",
+            "/repositories/42/git/ref/tags/v1.0.0",
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(from))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("location", format!("{}{to}", server.uri())),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/repositories/42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": 42,
+            "full_name": "new-org/leaf-action"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repositories/42/git/ref/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v1.0.0",
+            "object": {"type": "commit", "sha": sha}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/other-action"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": 43,
+            "full_name": "test-org/other-action"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/other-action/git/ref/tags/v2.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v2.0.0",
+            "object": {"type": "commit", "sha": sha}
+        })))
+        .mount(&server)
+        .await;
+    for (repo, tag) in [("leaf-action", "v1.0.0"), ("other-action", "v2.0.0")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-renamed-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(&server, &["--file", file, "--provider", "ghsa"]);
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains(
+            "renamed-action (medium): test-org/leaf-action@v1.0.0 refers to \
+             test-org/leaf-action, which GitHub redirects to new-org/leaf-action"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("renamed-action (medium)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+    // Both refs resolved, leaf-action's through the redirect.
+    assert_eq!(
+        stdout.matches(&format!("sha: {sha}")).count(),
+        2,
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn tag_protection_flags_release_tags_that_can_be_moved() {
+    let server = MockServer::start().await;
+    for (repo, tag) in [("leaf-action", "v1.0.0"), ("other-action", "v2.0.0")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}/git/ref/tags/{tag}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ref": format!("refs/tags/{tag}"),
+                "object": {"type": "commit", "sha": "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"}
+            })))
+            .mount(&server)
+            .await;
+    }
+    // leaf-action's release is mutable, but a ruleset locks its release
+    // tags; other-action has neither a release nor rulesets.
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/releases/tags/v1.0.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "tag_name": "v1.0.0",
+            "immutable": false
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/rulesets"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"id": 7, "name": "Release tags", "target": "tag", "enforcement": "active"}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/rulesets/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": 7,
+            "name": "Release tags",
+            "target": "tag",
+            "enforcement": "active",
+            "conditions": {"ref_name": {"include": ["refs/tags/v*"], "exclude": []}},
+            "rules": [{"type": "deletion"}, {"type": "update"}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow =
+        std::env::temp_dir().join(format!("ghss-tag-protection-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(
+        &server,
+        &["--file", file, "--provider", "ghsa", "--tag-protection"],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains("  tag protection: mutable release, ruleset Release tags\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "replaceable-tag (low): test-org/other-action@v2.0.0's tag can be moved to another \
+             commit: no release, no tag ruleset"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("replaceable-tag (low)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn repo_hygiene_reports_security_policies() {
+    let server = MockServer::start().await;
+    for (repo, tag) in [("leaf-action", "v1.0.0"), ("other-action", "v2.0.0")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+            )
+            .mount(&server)
+            .await;
+    }
+    // leaf-action has its own policy; other-action inherits the owner's.
+    for policy in [
+        "/test-org/leaf-action/HEAD/SECURITY.md",
+        "/test-org/.github/HEAD/SECURITY.md",
+    ] {
+        Mock::given(method("GET"))
+            .and(path(policy))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("# Security\n\nReport privately.\n"),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path(
+            "/repos/test-org/leaf-action/private-vulnerability-reporting",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"enabled": true})),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-hygiene-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(
+        &server,
+        &["--file", file, "--provider", "ghsa", "--repo-hygiene"],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(
+        stdout.contains(
+            "  hygiene: security policy (SECURITY.md), private vulnerability reporting enabled\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("  hygiene: security policy (SECURITY.md in test-org/.github)\n"),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn retired_node_runtime_is_flagged_during_expansion() {
+    let server = MockServer::start().await;
+    for (repo, tag, using) in [
+        ("leaf-action", "v1.0.0", "node16"),
+        ("other-action", "v2.0.0", "node20"),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/test-org/{repo}/{tag}/action.yml")))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                "name: Action\nruns:\n  using: {using}\n  main: index.js\n"
+            )))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow = std::env::temp_dir().join(format!("ghss-runtime-{}.yml", std::process::id()));
+    std::fs::write(
+        &workflow,
+        "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      \
+         - uses: test-org/leaf-action@v1.0.0\n      - uses: test-org/other-action@v2.0.0\n",
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(&server, &["--file", file, "--provider", "ghsa"]);
+    let json = stdout_of_mock(
+        &server,
+        &["--file", file, "--provider", "ghsa", "--format", "json"],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    assert!(stdout.contains("  runtime: node16\n"), "got:\n{stdout}");
+    assert!(stdout.contains("  runtime: node20\n"), "got:\n{stdout}");
+    assert!(
+        stdout.contains(
+            "deprecated-runtime (medium): test-org/leaf-action@v1.0.0 runs on node16, which \
+             GitHub Actions has retired"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("deprecated-runtime (medium)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed["actions"][0]["runtime"], "node16");
+}
+
+#[tokio::test]
+async fn verify_pins_flags_commits_outside_the_repository() {
+    let on_branch = "a".repeat(40);
+    let tagged = "b".repeat(40);
+    let fork = "c".repeat(40);
+    let missing = "d".repeat(40);
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"default_branch": "trunk"})),
+        )
+        .mount(&server)
+        .await;
+    for (sha, status) in [
+        (&on_branch, "behind"),
+        (&tagged, "diverged"),
+        (&fork, "ahead"),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/repos/test-org/leaf-action/compare/trunk...{sha}"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"status": status})),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"name": "v1.0.0", "commit": {"sha": tagged}}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/advisories"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&server)
+        .await;
+
+    let workflow =
+        std::env::temp_dir().join(format!("ghss-verify-pins-{}.yml", std::process::id()));
+    let steps: String = [&on_branch, &tagged, &fork, &missing]
+        .iter()
+        .map(|sha| format!("      - uses: test-org/leaf-action@{sha}\n"))
+        .collect();
+    std::fs::write(
+        &workflow,
+        format!("on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n{steps}"),
+    )
+    .unwrap();
+    let file = workflow.to_str().unwrap();
+    let stdout = stdout_of_mock(
+        &server,
+        &["--file", file, "--provider", "ghsa", "--verify-pins"],
+    );
+    std::fs::remove_file(&workflow).unwrap();
+
+    let flagged: Vec<&str> = stdout
+        .lines()
+        .filter(|l| l.contains("impostor-commit (high)"))
+        .collect();
+    assert_eq!(flagged.len(), 2, "got:\n{stdout}");
+    assert!(
+        flagged[0].ends_with(&format!(
+            "test-org/leaf-action@{fork} pins a commit that is not on test-org/leaf-action's default branch `trunk` or any of its tags; it may belong to a fork"
+        )),
+        "got:\n{stdout}"
+    );
+    assert!(
+        flagged[1].ends_with(&format!(
+            "test-org/leaf-action@{missing} pins a commit that test-org/leaf-action does not contain"
+        )),
+        "got:\n{stdout}"
+    );
+}
+
+#[tokio::test]
+async fn repo_metadata_flags_archived_and_stale_repositories() {
+    let server = setup_mock_server().await;
+    for (repo, archived, pushed_at) in [
+        ("composite-a", true, "2026-01-05T10:00:00Z"),
+        ("leaf-action", false, "2020-03-01T10:00:00Z"),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "full_name": format!("test-org/{repo}"),
+                "archived": archived,
+                "disabled": false,
+                "pushed_at": pushed_at
+            })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/repos/test-org/leaf-action/releases/latest"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "tag_name": "v1.2.0",
+            "published_at": "2020-02-01T10:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--depth",
+        "0",
+        "--repo-metadata",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains("test-org/composite-a@v1\n  repository: archived, last push 2026-01-05\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "test-org/leaf-action@v1\n  repository: active, last push 2020-03-01, last release 2020-02-01\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "stale-action (medium): test-org/leaf-action@v1 comes from test-org/leaf-action, which has had no push or release since 2020-03-01, over 18 months ago"
+        ),
+        "got:\n{stdout}"
+    );
+    // Archived repositories are not also reported as stale.
+    assert_eq!(
+        stdout.matches("stale-action (medium)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "archived-action (high): test-org/composite-a@v1 comes from test-org/composite-a, which is archived and will not receive security fixes"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    assert_eq!(
+        parsed["actions"][0]["repository"],
+        serde_json::json!({"archived": true, "disabled": false, "pushed_at": "2026-01-05T10:00:00Z"})
+    );
+}
+
+#[tokio::test]
+async fn scorecard_reports_failing_checks_and_minimum_score() {
+    let server = setup_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/scorecard/projects/github.com/test-org/composite-a"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "date": "2025-01-06T07:20:53Z",
+            "score": 3.4,
+            "checks": [
+                {"name": "Code-Review", "score": 0, "reason": "Found 0/30 approved changesets"},
+                {"name": "Pinned-Dependencies", "score": 10, "reason": "all dependencies are pinned"}
+            ]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/scorecard/projects/github.com/test-org/leaf-action"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "date": "2025-01-06T07:20:53Z",
+            "score": 8.1,
+            "checks": []
+        })))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--depth",
+        "0",
+        "--min-scorecard",
+        "5",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/composite-a@v1\n  scorecard: 3.4 (2025-01-06); failing: Code-Review 0\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("test-org/leaf-action@v1\n  scorecard: 8.1 (2025-01-06)\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "low-scorecard (medium): test-org/composite-a@v1 comes from test-org/composite-a, whose OpenSSF Scorecard score 3.4 is below the minimum 5.0 (failing: Code-Review)"
+        ),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("low-scorecard (medium)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    assert_eq!(
+        parsed["actions"][0]["scorecard"]["failing"][0],
+        serde_json::json!({
+            "name": "Code-Review",
+            "score": 0,
+            "reason": "Found 0/30 approved changesets"
+        })
+    );
+}
+
+#[tokio::test]
+async fn reputation_summarizes_each_action_repository() {
+    let server = setup_mock_server().await;
+    for (repo, stars, contributors) in [("composite-a", 1, 1), ("leaf-action", 420, 2)] {
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "full_name": format!("test-org/{repo}"),
+                "stargazers_count": stars,
+                "forks_count": 3
+            })))
+            .mount(&server)
+            .await;
+        let logins: Vec<_> = (0..contributors)
+            .map(|i| serde_json::json!({"login": format!("dev{i}")}))
+            .collect();
+        Mock::given(method("GET"))
+            .and(path(format!("/repos/test-org/{repo}/contributors")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(logins))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/users/test-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "login": "test-org",
+            "type": "Organization",
+            "created_at": "2024-11-02T08:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+
+    let args = [
+        "--file",
+        &fixture("depth-test-workflow.yml"),
+        "--provider",
+        "ghsa",
+        "--depth",
+        "0",
+        "--reputation",
+    ];
+    let stdout = stdout_of_mock(&server, &args);
+    assert!(
+        stdout.contains(
+            "test-org/composite-a@v1\n  reputation: 1 star, 3 forks, 1 contributor, organization since 2024-11-02\n"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "test-org/leaf-action@v1\n  reputation: 420 stars, 3 forks, 2 contributors, organization since 2024-11-02\n"
+        ),
+        "got:\n{stdout}"
+    );
+
+    let mut json_args = args.to_vec();
+    json_args.extend(["--format", "json"]);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout_of_mock(&server, &json_args)).unwrap();
+    assert_eq!(
+        parsed["actions"][0]["reputation"],
+        serde_json::json!({
+            "stars": 1,
+            "forks": 3,
+            "contributors": 1,
+            "owner_created": "2024-11-02T08:00:00Z",
+            "owner_type": "organization"
+        })
+    );
+}
@@ -23,6 +23,15 @@ fn stdout_of(args: &[&str]) -> String {
     String::from_utf8(output.stdout).unwrap()
 }
 
+/// Top-level action lines of text output, stopping at the summary footer.
+fn action_lines(stdout: &str) -> Vec<&str> {
+    stdout
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .filter(|l| !l.starts_with("  "))
+        .collect()
+}
+
 #[allow(dead_code)]
 fn stderr_of(args: &[&str]) -> String {
     let output = run_ghss(args);
@@ -32,9 +41,8 @@ fn stderr_of(args: &[&str]) -> String {
 #[test]
 fn sample_workflow_lists_sorted_third_party_actions() {
     let stdout = stdout_of(&["--file", &fixture("sample-workflow.yml")]);
-    let action_lines: Vec<&str> = stdout.lines().filter(|l| !l.starts_with("  ")).collect();
     assert_eq!(
-        action_lines,
+        action_lines(&stdout),
         vec![
             "actions/checkout@v4",
             "actions/setup-node@v4",
@@ -43,6 +51,65 @@ fn sample_workflow_lists_sorted_third_party_actions() {
     );
 }
 
+#[test]
+fn sample_workflow_reports_distinct_owners() {
+    let stdout = stdout_of(&["--file", &fixture("sample-workflow.yml")]);
+    assert!(stdout.contains(
+        "\n\ntrusts 2 GitHub owners across 3 actions: actions, codecov\n\
+         minimal token permissions: contents: read\n\
+         workflow findings:\n"
+    ));
+
+    let stdout = stdout_of(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--format",
+        "json",
+    ]);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        parsed["summary"]["owners"],
+        serde_json::json!(["actions", "codecov"])
+    );
+}
+
+#[test]
+fn knowledge_base_file_overrides_bundled_entries() {
+    let kb = std::env::temp_dir().join(format!("ghss-kb-{}.json", std::process::id()));
+    std::fs::write(
+        &kb,
+        r#"{"actions": {"codecov/codecov-action": {
+            "permissions": {"id-token": "write"},
+            "risky_inputs": {"token": "Upload token"},
+            "input_rules": [{"input": "token", "severity": "high", "message": "use OIDC"}]
+        }}}"#,
+    )
+    .unwrap();
+    let stdout = stdout_of(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--knowledge-base",
+        &kb.to_string_lossy(),
+    ]);
+    std::fs::remove_file(&kb).unwrap();
+
+    assert!(
+        stdout.contains("actions/checkout@v4\n  used 3 times across 3 jobs:"),
+        "got:\n{stdout}"
+    );
+    assert!(stdout.contains("  permissions: contents: read\n  risky input `persist-credentials`:"));
+    assert!(stdout.contains(
+        "codecov/codecov-action@v3\n  permissions: id-token: write\n  risky input `token`: Upload token\n"
+    ));
+    assert!(stdout.contains("minimal token permissions: contents: read, id-token: write\n"));
+    assert!(
+        stdout.contains(
+            "risky-input (high): codecov/codecov-action@v3 in job `test` sets `token: ${{ secrets.CODECOV_TOKEN }}`: use OIDC\n"
+        ),
+        "got:\n{stdout}"
+    );
+}
+
 #[test]
 fn sample_workflow_excludes_docker_actions() {
     let stdout = stdout_of(&["--file", &fixture("sample-workflow.yml")]);
@@ -74,12 +141,694 @@ fn sample_workflow_deduplicates_actions() {
     );
 }
 
+#[test]
+fn sample_workflow_reports_occurrences_of_duplicates() {
+    let path = fixture("sample-workflow.yml");
+    let stdout = stdout_of(&["--file", &path]);
+    assert!(stdout.contains("  used 3 times across 3 jobs:\n"));
+    assert!(stdout.contains(&format!("    {path}:13:15 (job build)\n")));
+    assert!(stdout.contains(&format!("    {path}:38:15 (job lint)\n")));
+
+    let stdout = stdout_of(&["--file", &path, "--format", "json"]);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let checkout = parsed["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["raw"] == "actions/checkout@v4")
+        .unwrap();
+    let jobs: Vec<&str> = checkout["occurrences"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["job"].as_str().unwrap())
+        .collect();
+    assert_eq!(jobs, ["build", "test", "lint"]);
+}
+
+#[test]
+fn dynamic_uses_are_reported_as_findings() {
+    let path = fixture("dynamic-uses-workflow.yml");
+    let stdout = stdout_of(&["--file", &path]);
+    assert!(stdout.starts_with("actions/checkout@v4\n"));
+    assert!(stdout.contains(&format!(
+        "\ntrusts 1 GitHub owner across 1 action: actions\n\
+         minimal token permissions: contents: read\n\
+         workflow findings:\n  dynamic-uses (medium): dynamic action reference `${{{{ matrix.action }}}}` cannot be audited\n    at {path}:12:15\n"
+    )));
+
+    let stdout = stdout_of(&["--file", &path, "--format", "json"]);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(parsed["actions"].as_array().unwrap().len(), 1);
+    assert_eq!(parsed["findings"][0]["rule_id"], "dynamic-uses");
+    assert_eq!(parsed["findings"][0]["location"]["line"], 12);
+
+    // The legacy --json flag keeps the bare action array.
+    let stdout = stdout_of(&["--file", &path, "--json"]);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(parsed.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn github_script_injection_is_reported_at_the_step() {
+    let path = fixture("github-script-workflow.yml");
+    let stdout = stdout_of(&["--file", &path, "--format", "json"]);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let findings: Vec<&serde_json::Value> = parsed["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["rule_id"] != "unpinned-action" && f["rule_id"] != "default-permissions")
+        .collect();
+    assert_eq!(findings.len(), 1, "got:\n{stdout}");
+    assert_eq!(findings[0]["rule_id"], "github-script-injection");
+    assert_eq!(findings[0]["severity"], "high");
+    assert_eq!(findings[0]["location"]["line"], 9);
+    assert!(
+        findings[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("step `Label issue` in job `triage`")
+    );
+}
+
+#[test]
+fn job_without_permissions_is_reported_with_what_its_actions_need() {
+    let path = fixture("github-script-workflow.yml");
+    let stdout = stdout_of(&["--file", &path]);
+    assert!(
+        stdout.contains(&format!(
+            "  default-permissions (medium): job `triage` has no `permissions:` and runs with the repository's default token permissions; its actions need no token permissions (unknown for: actions/github-script)\n    at {path}:5:3\n"
+        )),
+        "got:\n{stdout}"
+    );
+}
+
+#[test]
+fn lookalike_of_a_popular_action_is_reported() {
+    let path = fixture("typosquat-workflow.yml");
+    let stdout = stdout_of(&["--file", &path]);
+    assert!(
+        stdout.contains(&format!(
+            "  typosquat (high): actons/checkout differs from the popular action \
+             actions/checkout by 1 character; check that it is the action you meant\n    \
+             at {path}:7:15\n"
+        )),
+        "got:\n{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("typosquat (high)").count(),
+        1,
+        "got:\n{stdout}"
+    );
+}
+
+#[test]
+fn repo_without_action_updates_gets_a_finding() {
+    let repo = std::env::temp_dir().join(format!("ghss-hygiene-{}", std::process::id()));
+    let workflows = repo.join(".github/workflows");
+    std::fs::create_dir_all(&workflows).unwrap();
+    let workflow = workflows.join("ci.yml");
+    std::fs::copy(fixture("sample-workflow.yml"), &workflow).unwrap();
+    let path = workflow.to_string_lossy().into_owned();
+
+    let uncovered = stdout_of(&["--file", &path]);
+    std::fs::write(
+        repo.join(".github/dependabot.yml"),
+        "version: 2\nupdates:\n  - package-ecosystem: github-actions\n    directory: /\n",
+    )
+    .unwrap();
+    let covered = stdout_of(&["--file", &path]);
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    assert!(
+        uncovered.contains(&format!(
+            "  no-action-updates (low): no Dependabot or Renovate config\n    at {path}:1:1\n"
+        )),
+        "got:\n{uncovered}"
+    );
+    assert!(!covered.contains("no-action-updates"), "got:\n{covered}");
+}
+
+#[test]
+fn ignore_file_suppresses_actions_and_rules() {
+    let args = ["--file", &fixture("sample-workflow.yml")];
+    let stdout = stdout_of(&args);
+    assert!(stdout.contains("default-permissions (medium): "));
+    assert!(stdout.contains("codecov/codecov-action is referenced by tag `v3`"));
+
+    let ignore_file = fixture("accepted.ghssignore");
+    let mut ignoring = args.to_vec();
+    ignoring.extend(["--ignore-file", &ignore_file]);
+    let stdout = stdout_of(&ignoring);
+    assert!(!stdout.contains("default-permissions"), "got:\n{stdout}");
+    assert!(
+        !stdout.contains("codecov/codecov-action is referenced"),
+        "got:\n{stdout}"
+    );
+    assert!(!stdout.contains("risky-input"), "got:\n{stdout}");
+    assert!(
+        stdout.contains("actions/checkout is referenced by tag `v4`"),
+        "other actions keep their findings, got:\n{stdout}"
+    );
+
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--ignore-file",
+        &fixture("missing.ghssignore"),
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to read ignore file"));
+}
+
+#[test]
+fn forbidden_actions_fail_the_run() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--forbid",
+        "codecov/*",
+        "--fail-on-severity",
+        "critical",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(
+            "forbidden-action (critical): codecov/codecov-action@v3 is forbidden by policy \
+             (`codecov/*`)"
+        ),
+        "got:\n{stdout}"
+    );
+}
+
+#[test]
+fn config_policy_sets_thresholds_and_forbidden_owners() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--config",
+        &fixture("policy.toml"),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(
+            "forbidden-action (critical): codecov/codecov-action@v3 is forbidden by policy \
+             (`codecov`): token exfiltration in 2021"
+        ),
+        "got:\n{stderr}"
+    );
+    // Trusted owners' refs are not unpinned; codecov's still is.
+    assert!(stderr.contains("\n1 action reference(s) not pinned to a commit SHA:\n"));
+
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--config",
+        &fixture("custom-rules.yml"),
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid config file"));
+}
+
+#[test]
+fn config_profile_is_layered_over_the_policy() {
+    let run = |profile: Option<&str>| {
+        let mut args = vec![
+            "--file".to_string(),
+            fixture("sample-workflow.yml"),
+            "--config".to_string(),
+            fixture("profiles.toml"),
+        ];
+        args.extend(profile.map(|p| format!("--profile={p}")));
+        ghss().args(&args).output().expect("failed to execute")
+    };
+    assert_eq!(run(None).status.code(), Some(0));
+
+    let output = run(Some("ci"));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("forbidden-action (critical)"));
+
+    let output = run(Some("deep"));
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("profiles.toml: profile deep is not defined (profiles: ci)")
+    );
+}
+
+#[test]
+fn env_vars_set_options_unless_a_flag_does() {
+    let run = |envs: &[(&str, &str)], args: &[&str]| {
+        ghss()
+            .args(["--file", &fixture("sample-workflow.yml")])
+            .args(args)
+            .envs(envs.iter().copied())
+            .output()
+            .expect("failed to execute")
+    };
+    let is_json = |output: &std::process::Output| {
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).is_ok()
+    };
+    assert!(is_json(&run(&[("GHSS_FORMAT", "json")], &[])));
+    assert!(!is_json(&run(
+        &[("GHSS_FORMAT", "json")],
+        &["--format", "text"]
+    )));
+
+    // Without any of the variables below, the scan passes.
+    assert_eq!(run(&[], &[]).status.code(), Some(0));
+
+    let output = run(
+        &[("GHSS_PROFILE", "ci")],
+        &["--config", &fixture("profiles.toml")],
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run(
+        &[("GHSS_FORBID", "codecov,tj-actions/*")],
+        &["--fail-on-severity", "critical"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("forbidden-action (critical)"));
+
+    let output = run(&[("GHSS_REQUIRE_PINNED", "true")], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("action reference(s) not pinned to a commit SHA:"));
+    assert!(!stderr.contains("could not be read"));
+    assert_eq!(
+        run(&[("GHSS_REQUIRE_PINNED", "false")], &[]).status.code(),
+        Some(0)
+    );
+
+    let output = run(&[("GHSS_STRICT", "true")], &[]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("local action(s) could not be read"));
+    assert!(!stderr.contains("not pinned to a commit SHA"));
+}
+
+#[test]
+fn config_file_is_found_at_the_repository_root() {
+    let root = temp_path("config-repo");
+    let workflows = root.join(".github/workflows");
+    std::fs::create_dir_all(&workflows).unwrap();
+    std::fs::copy(fixture("sample-workflow.yml"), workflows.join("ci.yml")).unwrap();
+    std::fs::write(
+        root.join(".ghss.yml"),
+        "scan:\n  format: json\n  concurrency: 2\npolicy:\n  forbid: [codecov]\n",
+    )
+    .unwrap();
+    let file = workflows.join("ci.yml");
+    let file = file.to_str().unwrap();
+
+    let output = run_ghss(&["--file", file]);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(report["findings"].to_string().contains("forbidden-action"));
+
+    // A flag on the command line wins over the file.
+    let output = run_ghss(&["--file", file, "--format", "text"]);
+    assert!(serde_json::from_slice::<serde_json::Value>(&output.stdout).is_err());
+
+    let output = run_ghss(&["--file", file, "--no-config"]);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("forbidden-action"));
+}
+
+#[test]
+fn repository_config_cannot_run_a_token_command() {
+    let root = temp_path("config-untrusted");
+    let workflows = root.join(".github/workflows");
+    std::fs::create_dir_all(&workflows).unwrap();
+    std::fs::copy(fixture("sample-workflow.yml"), workflows.join("ci.yml")).unwrap();
+    let marker = root.join("ran");
+    let config = root.join(".ghss.toml");
+    std::fs::write(
+        &config,
+        format!("[github]\ntoken_command = \"touch {}\"\n", marker.display()),
+    )
+    .unwrap();
+    let file = workflows.join("ci.yml");
+    let run = |args: &[&str]| {
+        ghss()
+            .args(["--file", file.to_str().unwrap()])
+            .args(args)
+            .env_remove("GITHUB_TOKEN")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[]);
+    let ran_from_repository = marker.exists();
+    // Named with --config, the same file is trusted.
+    let explicit = run(&["--config", config.to_str().unwrap()]);
+    let ran_from_flag = marker.exists();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "github.token_command can only be set in a file given with --config or in the \
+             user config directory, not in a config file found in the scanned repository"
+        ),
+        "got:\n{stderr}"
+    );
+    assert!(!ran_from_repository);
+    assert!(
+        String::from_utf8_lossy(&explicit.stderr).contains("printed no token"),
+        "got:\n{}",
+        String::from_utf8_lossy(&explicit.stderr)
+    );
+    assert!(ran_from_flag);
+}
+
+#[test]
+fn init_writes_starter_files_once() {
+    let dir = temp_path("init");
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir_arg = dir.to_str().unwrap();
+
+    let stdout = stdout_of(&["init", "--dir", dir_arg, "--ignore"]);
+    assert_eq!(
+        stdout,
+        format!("wrote {dir_arg}/ghss.toml\nwrote {dir_arg}/.ghssignore\n")
+    );
+    let config = std::fs::read_to_string(dir.join("ghss.toml")).unwrap();
+    assert!(config.starts_with("# ghss config, written by `ghss init`."));
+
+    let output = run_ghss(&["init", "--dir", dir_arg]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("ghss.toml already exists"));
+    stdout_of(&["init", "--dir", dir_arg, "--force"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn nested_config_is_layered_over_the_root_config() {
+    let root = temp_path("config-monorepo");
+    let service = root.join("services/api");
+    std::fs::create_dir_all(root.join(".git")).unwrap();
+    std::fs::create_dir_all(&service).unwrap();
+    std::fs::write(
+        root.join("ghss.toml"),
+        "[scan]\nformat = \"json\"\n[policy]\nforbid = [\"codecov\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        service.join(".ghss.yml"),
+        "policy:\n  fail_on_severity: critical\n",
+    )
+    .unwrap();
+    for dir in [&root, &service] {
+        std::fs::copy(fixture("sample-workflow.yml"), dir.join("ci.yml")).unwrap();
+    }
+    let scan = |dir: &std::path::Path| run_ghss(&["--file", dir.join("ci.yml").to_str().unwrap()]);
+
+    let at_root = scan(&root);
+    let in_service = scan(&service);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(at_root.status.code(), Some(0));
+    assert_eq!(in_service.status.code(), Some(1));
+    for output in [at_root, in_service] {
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(report["findings"].to_string().contains("forbidden-action"));
+    }
+}
+
+#[test]
+fn config_policy_checks_warn_or_fail() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--config",
+        &fixture("policy-checks.toml"),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(
+            "setup-action (medium): actions/setup-node@v4 fails policy check \
+             `repo.startsWith('setup-')`"
+        ),
+        "got:\n{stdout}"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("\n1 policy check(s) failed:\n"));
+    assert!(
+        stderr.contains(
+            "sample-workflow.yml:31:15: third-party-tag (high): codecov/codecov-action@v3 is a \
+             third-party action pinned to a tag"
+        ),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn strict_fails_on_unreadable_local_actions() {
+    let output = run_ghss(&["--file", &fixture("sample-workflow.yml"), "--strict"]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(
+            "\n1 local action(s) could not be read, so the actions they use were not audited:\n\n  \
+             ./local-action: no action.yml or action.yaml in local-action\n"
+        ),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn trusted_owners_are_not_reported_as_unpinned() {
+    let args = [
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--fail-on",
+        "unpinned",
+    ];
+    assert_eq!(run_ghss(&args).status.code(), Some(1));
+
+    let mut trusting = args.to_vec();
+    trusting.extend(["--trusted-owner", "actions,Codecov"]);
+    let output = run_ghss(&trusting);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(0), "got:\n{stdout}");
+    assert!(!stdout.contains("unpinned-action"), "got:\n{stdout}");
+    assert!(
+        stdout.contains("default-permissions (medium): "),
+        "other findings stay, got:\n{stdout}"
+    );
+}
+
+#[test]
+fn deselected_actions_are_not_reported_as_unpinned() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--select",
+        "1",
+        "--require-pinned",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("\n3 action reference(s) not pinned to a commit SHA:\n"),
+        "got:\n{stderr}"
+    );
+    for deselected in ["setup-node", "codecov"] {
+        assert!(!stdout.contains(deselected), "got:\n{stdout}");
+        assert!(!stderr.contains(deselected), "got:\n{stderr}");
+    }
+}
+
+#[test]
+fn expired_ignore_entries_resurface() {
+    let ignore_file = std::env::temp_dir().join(format!("ghss-expired-{}", std::process::id()));
+    std::fs::write(
+        &ignore_file,
+        "codecov/codecov-action reason=\"no secrets\"\n\
+         default-permissions reason=\"temporary exception\" expires=2000-01-01\n",
+    )
+    .unwrap();
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--ignore-file",
+        ignore_file.to_str().unwrap(),
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("default-permissions (medium): "),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "expired-suppression (medium): suppression of `default-permissions` expired on \
+             2000-01-01 (temporary exception); it no longer applies"
+        ),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("at {}:2:1", ignore_file.display())),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.ends_with("1 suppression has expired and no longer applies\n"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        !stdout.contains("codecov/codecov-action is referenced"),
+        "got:\n{stdout}"
+    );
+
+    std::fs::write(&ignore_file, "default-permissions\n").unwrap();
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--ignore-file",
+        ignore_file.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&ignore_file).ok();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("line 1: `default-permissions`: missing reason=\"...\""),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn inline_comments_suppress_findings_on_their_line() {
+    let output = run_ghss(&["--file", &fixture("inline-ignore-workflow.yml")]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        !stdout.contains("actions/checkout is referenced by tag"),
+        "got:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("actions/setup-node is referenced by tag `v4`"),
+        "got:\n{stdout}"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(
+            "inline-ignore-workflow.yml:11: ghss-ignore names dynamic-uses, which is not reported there"
+        ),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn baseline_leaves_out_existing_findings() {
+    let baseline = std::env::temp_dir().join(format!("ghss-baseline-{}.json", std::process::id()));
+    let report = stdout_of(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--format",
+        "json",
+    ]);
+    std::fs::write(&baseline, report).unwrap();
+
+    let args = [
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--fail-on-severity",
+        "low",
+    ];
+    assert_eq!(run_ghss(&args).status.code(), Some(1));
+
+    let mut with_baseline = args.to_vec();
+    with_baseline.extend(["--baseline", baseline.to_str().unwrap()]);
+    let output = run_ghss(&with_baseline);
+    std::fs::remove_file(&baseline).ok();
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("workflow findings:"), "got:\n{stdout}");
+
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--baseline",
+        &fixture("sample-workflow.yml"),
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("baseline is not a ghss JSON report"));
+}
+
+#[test]
+fn local_actions_are_expanded_from_the_checkout() {
+    let repo = std::env::temp_dir().join(format!("ghss-local-cli-{}", std::process::id()));
+    let write = |path: &str, contents: &str| {
+        let path = repo.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        ".github/workflows/ci.yml",
+        "jobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n      - uses: ./.github/actions/setup\n",
+    );
+    write(
+        ".github/actions/setup/action.yml",
+        "runs:\n  using: composite\n  steps:\n    - uses: actions/setup-node@v4\n",
+    );
+
+    let workflow = repo.join(".github/workflows/ci.yml");
+    let stdout = stdout_of(&["--file", &workflow.to_string_lossy(), "--format", "json"]);
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let actions = parsed["actions"].as_array().unwrap();
+    let uses: Vec<&str> = actions.iter().map(|a| a["raw"].as_str().unwrap()).collect();
+    assert_eq!(uses, ["actions/checkout@v4", "actions/setup-node@v4"]);
+    assert_eq!(
+        actions[1]["location"]["file"],
+        ".github/actions/setup/action.yml"
+    );
+}
+
+#[test]
+fn workflow_template_is_audited_like_a_workflow() {
+    let output = run_ghss(&["--file", &fixture("workflow-templates/org-ci.yml")]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        action_lines(&stdout),
+        vec!["actions/checkout@v4", "actions/setup-node@v4"]
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        !stderr.contains("companion"),
+        "unexpected warning: {stderr}"
+    );
+}
+
+#[test]
+fn workflow_template_without_properties_warns() {
+    let output = run_ghss(&["--file", &fixture("workflow-templates/orphan.yml")]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("orphan.properties.json companion"),
+        "expected missing companion warning, got: {stderr}"
+    );
+}
+
 #[test]
 fn malformed_workflow_still_extracts_valid_actions() {
     let stdout = stdout_of(&["--file", &fixture("malformed-workflow.yml")]);
-    let action_lines: Vec<&str> = stdout.lines().filter(|l| !l.starts_with("  ")).collect();
     assert_eq!(
-        action_lines,
+        action_lines(&stdout),
         vec!["actions/checkout@v4", "actions/setup-node@v4",]
     );
 }
@@ -168,6 +917,21 @@ fn json_flag_outputs_valid_json_array() {
     assert!(raws.contains(&"codecov/codecov-action@v3"));
 }
 
+#[test]
+fn json_output_includes_uses_locations() {
+    let path = fixture("sample-workflow.yml");
+    let stdout = stdout_of(&["--file", &path, "--json"]);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let arr = parsed.as_array().unwrap();
+
+    for entry in arr {
+        let location = &entry["location"];
+        assert_eq!(location["file"], path.as_str());
+        assert!(location["line"].as_u64().unwrap() > 1);
+        assert!(location["column"].as_u64().unwrap() > 1);
+    }
+}
+
 #[test]
 fn json_output_always_includes_advisories_key() {
     let stdout = stdout_of(&["--file", &fixture("sample-workflow.yml"), "--json"]);
@@ -280,9 +1044,8 @@ fn depth_zero_explicit_matches_default_output() {
 fn depth_default_matches_current_behavior() {
     // No --depth flag (default) should behave like --depth 0
     let stdout = stdout_of(&["--file", &fixture("sample-workflow.yml")]);
-    let action_lines: Vec<&str> = stdout.lines().filter(|l| !l.starts_with("  ")).collect();
     assert_eq!(
-        action_lines,
+        action_lines(&stdout),
         vec![
             "actions/checkout@v4",
             "actions/setup-node@v4",
@@ -444,6 +1207,50 @@ fn fail_on_severity_flag_is_accepted() {
     );
 }
 
+#[test]
+fn fail_on_severity_counts_workflow_findings() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("github-script-workflow.yml"),
+        "--fail-on-severity",
+        "high",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("1 finding(s) at or above high severity:")
+            && stderr.contains("github-script-workflow.yml:9:15: github-script-injection (high): "),
+        "got:\n{stderr}"
+    );
+}
+
+#[test]
+fn help_documents_exit_codes() {
+    let stdout = stdout_of(&["--help"]);
+    assert!(
+        stdout
+            .contains("Exit codes:\n  0  clean: no findings at or above a --fail-on* threshold\n")
+            && stdout.contains("\n  3  scan completed with stage errors"),
+        "got:\n{stdout}"
+    );
+}
+
+#[test]
+fn fail_on_rejects_unknown_condition() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--fail-on",
+        "unpinned,bogus",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("invalid value 'bogus' for '--fail-on"),
+        "got:\n{stderr}"
+    );
+}
+
 #[test]
 fn fail_on_severity_rejects_invalid_value() {
     let output = run_ghss(&[
@@ -532,7 +1339,7 @@ fn format_sarif_round_trips_through_serde_sarif() {
 }
 
 #[test]
-fn format_sarif_emits_two_rules_in_driver() {
+fn format_sarif_emits_advisory_and_finding_rules_in_driver() {
     let stdout = stdout_of(&[
         "--file",
         &fixture("vulnerable-workflow.yml"),
@@ -543,10 +1350,16 @@ fn format_sarif_emits_two_rules_in_driver() {
     let rules = parsed["runs"][0]["tool"]["driver"]["rules"]
         .as_array()
         .unwrap();
-    assert_eq!(rules.len(), 2);
     let ids: Vec<&str> = rules.iter().map(|r| r["id"].as_str().unwrap()).collect();
-    assert!(ids.contains(&"ghss/vulnerable-action"));
-    assert!(ids.contains(&"ghss/vulnerable-dependency"));
+    assert_eq!(
+        ids,
+        [
+            "ghss/vulnerable-action",
+            "ghss/vulnerable-dependency",
+            "ghss/unpinned-action",
+            "ghss/default-permissions"
+        ]
+    );
 }
 
 #[test]
@@ -592,3 +1405,86 @@ fn format_and_json_flag_together_exits_with_error() {
         "expected conflict error, got: {stderr}"
     );
 }
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("ghss-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn signed_report_verifies_with_matching_key() {
+    let report_path = temp_path("signed-report.json");
+    let signature_path = temp_path("signed-report.sig");
+    let stdout = stdout_of(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--format",
+        "json",
+        "--sign-key",
+        &fixture("report-signing-key.pem"),
+        "--signature",
+        signature_path.to_str().unwrap(),
+    ]);
+    std::fs::write(&report_path, &stdout).unwrap();
+
+    let sig: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&signature_path).unwrap()).unwrap();
+    assert_eq!(sig["algorithm"], "ed25519");
+
+    let verified = stdout_of(&[
+        "verify-report",
+        "--report",
+        report_path.to_str().unwrap(),
+        "--signature",
+        signature_path.to_str().unwrap(),
+        "--public-key",
+        &fixture("report-signing-key.pub.pem"),
+    ]);
+    assert!(verified.contains("Signature OK"));
+
+    // Any modification to the stored report must be detected.
+    std::fs::write(&report_path, stdout.replace("checkout", "checkin")).unwrap();
+    let output = run_ghss(&[
+        "verify-report",
+        "--report",
+        report_path.to_str().unwrap(),
+        "--signature",
+        signature_path.to_str().unwrap(),
+        "--public-key",
+        &fixture("report-signing-key.pub.pem"),
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("modified"));
+
+    // A different trusted key must not accept the signature.
+    let output = run_ghss(&[
+        "verify-report",
+        "--report",
+        report_path.to_str().unwrap(),
+        "--signature",
+        signature_path.to_str().unwrap(),
+        "--public-key",
+        &fixture("other-signing-key.pub.pem"),
+    ]);
+    assert!(!output.status.success());
+
+    let _ = std::fs::remove_file(report_path);
+    let _ = std::fs::remove_file(signature_path);
+}
+
+#[test]
+fn sign_key_requires_signature_path() {
+    let output = run_ghss(&[
+        "--file",
+        &fixture("sample-workflow.yml"),
+        "--sign-key",
+        &fixture("report-signing-key.pem"),
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn file_is_required_without_subcommand() {
+    let output = run_ghss(&[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--file"));
+}
@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, bail};
+use ghss::providers::http::{HttpProvider, HttpProviderConfig};
+use ghss::providers::snyk::{SnykConfig, SnykProvider};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -74,6 +76,15 @@ pub struct PipelineSection {
     pub deps: bool,
     #[serde(default)]
     pub concurrency: Option<usize>,
+    /// Seconds to wait for each advisory provider per action (default 30).
+    #[serde(default)]
+    pub provider_timeout: Option<u64>,
+    /// Additional advisory endpoints queried for every action.
+    #[serde(default)]
+    pub http_providers: Vec<HttpProviderConfig>,
+    /// Snyk, queried for every dependency, for orgs that license it.
+    #[serde(default)]
+    pub snyk: Option<SnykConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -170,6 +181,17 @@ fn validate(config: &ScannerConfig) -> anyhow::Result<()> {
     if config.pipeline.concurrency == Some(0) {
         bail!("pipeline concurrency must be a positive integer (got 0)");
     }
+    if config.pipeline.provider_timeout == Some(0) {
+        bail!("provider_timeout must be a positive number of seconds (got 0)");
+    }
+
+    // Validate custom providers, including their ${VAR} references
+    for provider in &config.pipeline.http_providers {
+        HttpProvider::new(provider.clone())?;
+    }
+    if let Some(snyk) = &config.pipeline.snyk {
+        SnykProvider::new(snyk.clone())?;
+    }
 
     // Log effective values
     tracing::info!(
@@ -531,6 +553,7 @@ url = "sqlite:///tmp/ghss.db"
         let config = ScannerConfig::from_file(f.path()).unwrap();
         assert_eq!(config.scanner.max_repo_concurrency, None);
         assert_eq!(config.pipeline.concurrency, None);
+        assert_eq!(config.pipeline.provider_timeout, None);
     }
 
     #[test]
@@ -549,6 +572,7 @@ depth = "0"
 provider = "all"
 deps = false
 concurrency = 20
+provider_timeout = 10
 
 [storage]
 url = "sqlite:///tmp/ghss.db"
@@ -557,6 +581,7 @@ url = "sqlite:///tmp/ghss.db"
         let config = ScannerConfig::from_file(f.path()).unwrap();
         assert_eq!(config.scanner.max_repo_concurrency, Some(4));
         assert_eq!(config.pipeline.concurrency, Some(20));
+        assert_eq!(config.pipeline.provider_timeout, Some(10));
     }
 
     #[test]
@@ -613,6 +638,113 @@ url = "sqlite:///tmp/ghss.db"
         );
     }
 
+    #[test]
+    fn test_provider_timeout_zero_rejected() {
+        let content = r#"
+[scanner]
+schedule = "0 * * * *"
+
+[[repos]]
+owner = "org"
+name = "repo"
+
+[pipeline]
+depth = "0"
+provider = "all"
+deps = false
+provider_timeout = 0
+
+[storage]
+url = "sqlite:///tmp/ghss.db"
+"#;
+        let f = write_temp_config(content);
+        let err = ScannerConfig::from_file(f.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("provider_timeout"),
+            "expected provider_timeout error, got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_http_providers_parse_and_validate() {
+        let content = r#"
+[scanner]
+schedule = "0 * * * *"
+
+[[repos]]
+owner = "org"
+name = "repo"
+
+[pipeline]
+depth = "0"
+provider = "all"
+deps = false
+
+[[pipeline.http_providers]]
+name = "vulndb"
+url = "https://vulndb.example/api/actions/{package}?ref={ref}"
+
+[pipeline.http_providers.mapping]
+advisories = "results"
+summary = "title"
+
+[storage]
+url = "sqlite:///tmp/ghss.db"
+"#;
+        let f = write_temp_config(content);
+        let config = ScannerConfig::from_file(f.path()).unwrap();
+        let provider = &config.pipeline.http_providers[0];
+        assert_eq!(provider.name, "vulndb");
+        assert_eq!(provider.mapping.advisories, "results");
+        assert_eq!(provider.mapping.summary, "title");
+        assert_eq!(provider.mapping.id, "id");
+
+        let f = write_temp_config(&content.replace(
+            "[pipeline.http_providers.mapping]",
+            "auth_header = \"Authorization: Bearer ${GHSS_TEST_UNSET_VULNDB_TOKEN}\"\n\n[pipeline.http_providers.mapping]",
+        ));
+        let err = ScannerConfig::from_file(f.path()).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("GHSS_TEST_UNSET_VULNDB_TOKEN"),
+            "expected unset env var error, got: {err:#}"
+        );
+    }
+
+    #[test]
+    fn test_snyk_config_validates_token() {
+        let content = r#"
+[scanner]
+schedule = "0 * * * *"
+
+[[repos]]
+owner = "org"
+name = "repo"
+
+[pipeline]
+depth = "0"
+provider = "all"
+deps = true
+
+[pipeline.snyk]
+org_id = "4a18d42f-0706-4ad0-b127-24078731fbed"
+token = "${GHSS_TEST_UNSET_SNYK_TOKEN}"
+
+[storage]
+url = "sqlite:///tmp/ghss.db"
+"#;
+        let f = write_temp_config(content);
+        let err = ScannerConfig::from_file(f.path()).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("GHSS_TEST_UNSET_SNYK_TOKEN"),
+            "expected unset env var error, got: {err:#}"
+        );
+
+        let f = write_temp_config(&content.replace("${GHSS_TEST_UNSET_SNYK_TOKEN}", "secret"));
+        let config = ScannerConfig::from_file(f.path()).unwrap();
+        let snyk = config.pipeline.snyk.unwrap();
+        assert_eq!(snyk.org_id, "4a18d42f-0706-4ad0-b127-24078731fbed");
+    }
+
     #[test]
     fn test_github_app_config() {
         let content = r#"
@@ -1,6 +1,6 @@
-use std::collections::BTreeSet;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Context;
 use futures::StreamExt;
@@ -9,15 +9,18 @@ use ghss::depth::DepthLimit;
 use ghss::github::GitHubClient;
 use ghss::output::AuditNode;
 use ghss::pipeline::PipelineBuilder;
-use ghss::providers;
+use ghss::providers::{Providers, http::HttpProvider, snyk::SnykProvider};
 use ghss::stages::{
     AdvisoryStage, CompositeExpandStage, DependencyStage, RefResolveStage, ScanStage,
     WorkflowExpandStage,
 };
+use ghss::template;
 use ghss::walker::Walker;
 
 use crate::config::{PipelineSection, RepoEntry};
 
+const WORKFLOW_DIR: &str = ".github/workflows";
+
 type RepoOutcome = Result<(String, Vec<AuditNode>), (String, anyhow::Error)>;
 
 /// Result of a scan cycle, including findings and error tracking.
@@ -125,43 +128,71 @@ pub async fn run_scan_cycle(
     ScanCycleResult { results, failures }
 }
 
-/// Discover workflow files for a repo. If `repo.workflows` is set, use that
-/// list directly. Otherwise, query the GitHub Contents API for `.github/workflows/`.
+/// Discover workflow files for a repo, as paths relative to the repo root.
+/// If `repo.workflows` is set, use that list directly. Otherwise, query the
+/// GitHub Contents API for `.github/workflows/` and for organization workflow
+/// templates in `workflow-templates/`.
 async fn discover_workflows(
     client: &GitHubClient,
     repo: &RepoEntry,
 ) -> anyhow::Result<Vec<String>> {
     if let Some(ref workflows) = repo.workflows {
-        return Ok(workflows.clone());
+        return Ok(workflows
+            .iter()
+            .map(|name| format!("{WORKFLOW_DIR}/{name}"))
+            .collect());
     }
 
+    let names = list_directory(client, repo, WORKFLOW_DIR)
+        .await
+        .context("failed to list workflow files")?;
+    let mut workflows: Vec<String> = names
+        .iter()
+        .filter(|name| {
+            std::path::Path::new(name).extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml")
+            })
+        })
+        .map(|name| format!("{WORKFLOW_DIR}/{name}"))
+        .collect();
+
+    // Only an organization's `.github` repo normally has templates; a missing
+    // directory is not an error.
+    match list_directory(client, repo, template::TEMPLATE_DIR).await {
+        Ok(names) => workflows.extend(
+            template::template_workflows(&names)
+                .into_iter()
+                .map(|name| format!("{}/{name}", template::TEMPLATE_DIR)),
+        ),
+        Err(e) => tracing::debug!(error = %e, "No workflow templates found"),
+    }
+
+    Ok(workflows)
+}
+
+/// List the file names in a repo directory via the Contents API.
+async fn list_directory(
+    client: &GitHubClient,
+    repo: &RepoEntry,
+    dir: &str,
+) -> anyhow::Result<Vec<String>> {
     let url = format!(
-        "{}/repos/{}/{}/contents/.github/workflows",
+        "{}/repos/{}/{}/contents/{dir}",
         client.api_base_url(),
         repo.owner,
         repo.name
     );
 
-    let json = client
-        .api_get(&url)
-        .await
-        .context("failed to list workflow files")?;
+    let json = client.api_get(&url).await?;
 
     let entries = json
         .as_array()
         .context("expected array from Contents API")?;
 
-    let workflows: Vec<String> = entries
+    Ok(entries
         .iter()
         .filter_map(|entry| entry.get("name")?.as_str().map(String::from))
-        .filter(|name| {
-            std::path::Path::new(name).extension().is_some_and(|ext| {
-                ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml")
-            })
-        })
-        .collect();
-
-    Ok(workflows)
+        .collect())
 }
 
 /// Fetch a single workflow YAML file from a repo via raw content.
@@ -169,17 +200,12 @@ async fn fetch_workflow_yaml(
     client: &GitHubClient,
     owner: &str,
     repo: &str,
-    workflow: &str,
+    path: &str,
 ) -> anyhow::Result<String> {
     client
-        .get_raw_content(
-            owner,
-            repo,
-            "HEAD",
-            &format!(".github/workflows/{workflow}"),
-        )
+        .get_raw_content(owner, repo, "HEAD", path)
         .await
-        .context(format!("failed to fetch workflow {workflow}"))
+        .context(format!("failed to fetch workflow {path}"))
 }
 
 /// Build the ghss pipeline from config, following the CLI assembly pattern.
@@ -187,20 +213,32 @@ fn build_pipeline(
     client: &GitHubClient,
     pipeline_config: &PipelineSection,
 ) -> anyhow::Result<ghss::pipeline::Pipeline> {
-    let action_providers = providers::create_action_providers(&pipeline_config.provider, client)?;
-    let package_providers = providers::create_package_providers(&pipeline_config.provider)?;
+    let mut providers = Providers::named(&pipeline_config.provider, client)?;
+    for provider in &pipeline_config.http_providers {
+        providers = providers.with_action_provider(Arc::new(HttpProvider::new(provider.clone())?));
+    }
+    if let Some(snyk) = &pipeline_config.snyk {
+        providers = providers.with_package_provider(Arc::new(SnykProvider::new(snyk.clone())?));
+    }
 
     let mut builder = PipelineBuilder::default()
         .stage(CompositeExpandStage::new(client.clone()))
         .stage(WorkflowExpandStage::new(client.clone()))
         .stage(RefResolveStage::new(client.clone()))
-        .stage(AdvisoryStage::new(action_providers));
+        .stage(
+            AdvisoryStage::new(providers.action_providers()).with_timeout(Duration::from_secs(
+                pipeline_config.provider_timeout.unwrap_or(30),
+            )),
+        );
 
     if pipeline_config.deps {
         if client.has_token() {
             builder = builder
                 .stage(ScanStage::new(client.clone()))
-                .stage(DependencyStage::new(client.clone(), package_providers));
+                .stage(DependencyStage::new(
+                    client.clone(),
+                    providers.package_providers(),
+                ));
         } else {
             tracing::warn!(
                 "deps=true requires a GitHub token; skipping ecosystem scan and dependency audit"
@@ -253,12 +291,12 @@ async fn scan_repo(
         return Ok(vec![]);
     }
 
-    let mut all_actions: BTreeSet<ActionRef> = BTreeSet::new();
+    let mut all_actions: Vec<ActionRef> = Vec::new();
 
     for workflow_name in &workflows {
         match fetch_workflow_yaml(client, &repo.owner, &repo.name, workflow_name)
             .await
-            .and_then(|yaml| ghss::parse_actions(&yaml))
+            .and_then(|yaml| ghss::parse_actions_from(&yaml, workflow_name))
         {
             Ok(actions) => {
                 tracing::debug!(workflow = %workflow_name, actions = actions.len(), "Parsed workflow");
@@ -270,7 +308,7 @@ async fn scan_repo(
         }
     }
 
-    let actions: Vec<ActionRef> = all_actions.into_iter().collect();
+    let actions = ghss::merge_actions(all_actions);
 
     if actions.is_empty() {
         return Ok(vec![]);
@@ -300,6 +338,9 @@ mod tests {
             provider: "all".to_string(),
             deps: false,
             concurrency: None,
+            provider_timeout: None,
+            http_providers: vec![],
+            snyk: None,
         };
         let pipeline = build_pipeline(&client, &config).unwrap();
         // 4 base stages: composite, workflow_expand, resolve, advisory
@@ -314,6 +355,9 @@ mod tests {
             provider: "all".to_string(),
             deps: true,
             concurrency: None,
+            provider_timeout: None,
+            http_providers: vec![],
+            snyk: None,
         };
         let pipeline = build_pipeline(&client, &config).unwrap();
         // deps=true but no token: still 4 stages
@@ -328,9 +372,33 @@ mod tests {
             provider: "all".to_string(),
             deps: true,
             concurrency: None,
+            provider_timeout: None,
+            http_providers: vec![],
+            snyk: None,
         };
         let pipeline = build_pipeline(&client, &config).unwrap();
         // 4 base + scan + dependency = 6
         assert_eq!(pipeline.stage_count(), 6);
     }
+
+    #[test]
+    fn build_pipeline_rejects_invalid_http_provider() {
+        let client = GitHubClient::new(None);
+        let config = PipelineSection {
+            depth: "0".to_string(),
+            provider: "all".to_string(),
+            deps: false,
+            concurrency: None,
+            provider_timeout: None,
+            http_providers: vec![ghss::providers::http::HttpProviderConfig {
+                name: "vulndb".to_string(),
+                url: "vulndb.example/{package}".to_string(),
+                auth_header: None,
+                mapping: Default::default(),
+            }],
+            snyk: None,
+        };
+        let err = build_pipeline(&client, &config).err().unwrap();
+        assert!(err.to_string().contains("vulndb"), "{err}");
+    }
 }
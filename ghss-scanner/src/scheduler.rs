@@ -226,9 +226,9 @@ async fn insert_findings(
             .entry
             .advisories
             .iter()
-            .map(|a| a.severity.as_str())
-            .next()
-            .map(String::from);
+            .filter_map(|a| a.severity)
+            .max()
+            .map(|s| s.to_string());
         let serialized = serde_json::to_string(node).context("failed to serialize AuditNode")?;
 
         storage
@@ -269,6 +269,99 @@ name = "test-repo"
     pool.close().await;
 }
 
+#[tokio::test]
+async fn scan_includes_workflow_templates_with_properties() {
+    let server = setup_scan_mock_server().await;
+
+    // Only org-ci.yml has a properties companion; draft.yml is not a template.
+    Mock::given(method("GET"))
+        .and(path(
+            "/repos/test-org/test-repo/contents/workflow-templates",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"name": "org-ci.yml", "type": "file"},
+            {"name": "org-ci.properties.json", "type": "file"},
+            {"name": "draft.yml", "type": "file"}
+        ])))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/test-org/test-repo/HEAD/workflow-templates/org-ci.yml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "name: Org CI\non:\n  push:\n    branches: [ $default-branch ]\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/cache@v4\n",
+        ))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/actions/cache/git/ref/tags/v4"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ref": "refs/tags/v4",
+            "object": {
+                "type": "commit",
+                "sha": "0c45773b623bea8c8e75f6c82b208c3cf94ea4f9"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/actions/cache/v4/action.yml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("name: Action\nruns:\n  using: node20\n  main: index.js\n"),
+        )
+        .mount(&server)
+        .await;
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db_path = tmp_dir.path().join("templates-test.db");
+    let db_path_str = db_path.to_str().unwrap();
+
+    let repos_toml = r#"
+[[repos]]
+owner = "test-org"
+name = "test-repo"
+"#;
+
+    let config_file = write_config(db_path_str, repos_toml);
+
+    let output = scanner_bin()
+        .args([
+            "--once",
+            "--config",
+            config_file.path().to_str().unwrap(),
+            "-vv",
+        ])
+        .env("GHSS_API_BASE_URL", server.uri())
+        .env("GHSS_RAW_BASE_URL", server.uri())
+        .env("GHSS_OSV_BASE_URL", format!("{}/osv-query", server.uri()))
+        .output()
+        .expect("failed to run ghss-scanner");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "scanner exited with error: {stderr}"
+    );
+
+    let pool = SqlitePoolOptions::new()
+        .connect(&format!("sqlite://{db_path_str}"))
+        .await
+        .expect("failed to open test database");
+
+    let action_refs: Vec<String> =
+        sqlx::query_scalar("SELECT action_ref FROM findings ORDER BY action_ref")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(action_refs.len(), 4, "got: {action_refs:?}");
+    assert!(action_refs.iter().any(|r| r.contains("actions/cache")));
+
+    pool.close().await;
+}
+
 #[tokio::test]
 async fn explicit_workflows_config_skips_contents_api() {
     let server = setup_scan_mock_server().await;
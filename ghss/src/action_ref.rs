@@ -6,6 +6,8 @@ use std::str::FromStr;
 use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 
+use crate::location::{Occurrence, SourceLocation};
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RefType {
@@ -32,6 +34,14 @@ pub struct ActionRef {
     pub path: Option<String>,
     pub git_ref: String,
     pub ref_type: RefType,
+    /// Where this reference appears in its source file, when parsed from one.
+    /// Not part of the action's identity (ignored by Eq, Ord, and Hash).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+    /// Every place this ref is used, in document order. Populated when
+    /// duplicate refs are merged; like `location`, not part of identity.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub occurrences: Vec<Occurrence>,
 }
 
 impl FromStr for ActionRef {
@@ -64,6 +74,8 @@ impl FromStr for ActionRef {
             path,
             git_ref: git_ref.to_string(),
             ref_type,
+            location: None,
+            occurrences: Vec::new(),
         })
     }
 }
@@ -111,6 +123,11 @@ impl Hash for ActionRef {
 }
 
 impl ActionRef {
+    pub fn with_location(mut self, location: SourceLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
     pub fn package_name(&self) -> String {
         match &self.path {
             Some(p) => format!("{}/{}/{}", self.owner, self.repo, p),
@@ -251,6 +268,14 @@ mod tests {
         assert!(a < b);
     }
 
+    #[test]
+    fn location_does_not_affect_identity() {
+        let a: ActionRef = "actions/checkout@v4".parse().unwrap();
+        let b = a.clone().with_location(SourceLocation::new(12, 15));
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+    }
+
     #[test]
     fn ordering_by_ref_within_same_repo() {
         let a: ActionRef = "actions/checkout@v3".parse().unwrap();
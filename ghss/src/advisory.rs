@@ -1,33 +1,170 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cvss::CvssScore;
+use crate::epss::Epss;
+use crate::vex::VexAssessment;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Advisory {
     pub id: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub aliases: Vec<String>,
+    /// CWE IDs, e.g. `CWE-79`. Only the GHSA GraphQL provider reports them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cwes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvss_score: Option<CvssScore>,
+    /// e.g. `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvss_vector: Option<String>,
+    /// Exploit probability of the riskiest CVE alias, set by `EpssStage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epss: Option<Epss>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<DateTime<Utc>>,
+    /// Last change to the advisory at its source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+    /// When the source withdrew the advisory. Withdrawn advisories are
+    /// dropped unless `--include-withdrawn` is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<DateTime<Utc>>,
     pub summary: String,
-    pub severity: String,
+    /// Full advisory text, usually Markdown. Kept only with `--details`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Reference URLs: patches, reports, write-ups. Kept only with
+    /// `--details`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
+    /// `None` when the source gave no level it could be normalized from.
+    /// Serialized as `"unknown"`.
+    #[serde(with = "severity_label")]
+    pub severity: Option<Severity>,
     pub url: String,
     pub affected_range: Option<String>,
-    pub source: String,
+    /// First version outside `affected_range`: the source's patched
+    /// version, else the last `< X` bound of the range. Rendered as an
+    /// upgrade suggestion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_version: Option<String>,
+    /// Whether the action's pinned version is within `affected_range`, set
+    /// by `AdvisoryStage`. `None` when there is no range or version to
+    /// compare, or the version is a floating tag only partly affected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applicable: Option<bool>,
+    /// Providers that reported the advisory, in query order. Several when
+    /// duplicates were merged. Older reports have a single `source` string
+    /// instead, comma-separated after merging; it is read into this list.
+    #[serde(
+        default,
+        alias = "source",
+        deserialize_with = "source_list::deserialize"
+    )]
+    pub sources: Vec<String>,
+    /// Status from a VEX document passed with `--vex`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vex: Option<VexAssessment>,
 }
 
 impl fmt::Display for Advisory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{} ({}): {}", self.id, self.severity, self.summary)?;
+        writeln!(
+            f,
+            "{} ({}): {}",
+            self.id,
+            self.severity_label(),
+            self.summary
+        )?;
         write!(f, "    {}", self.url)?;
+        if !self.sources.is_empty() {
+            write!(f, "\n    found by: {}", self.sources.join(", "))?;
+        }
         if let Some(range) = &self.affected_range {
             write!(f, "\n    affected: {range}")?;
+            match self.applicable {
+                Some(true) => write!(f, " (applies to this version)")?,
+                Some(false) => write!(f, " (not applicable to this pinned version)")?,
+                None => {}
+            }
+        }
+        // No point upgrading away from a version the advisory doesn't affect.
+        if let Some(fixed) = self
+            .fixed_version
+            .as_ref()
+            .filter(|_| !self.is_not_applicable())
+        {
+            write!(f, "\n    fix: upgrade to {fixed}")?;
+        }
+        match (self.cvss_score, &self.cvss_vector) {
+            (Some(score), Some(vector)) => write!(f, "\n    cvss: {score} ({vector})")?,
+            (Some(score), None) => write!(f, "\n    cvss: {score}")?,
+            (None, Some(vector)) => write!(f, "\n    cvss: {vector}")?,
+            (None, None) => {}
+        }
+        if let Some(epss) = &self.epss {
+            write!(f, "\n    epss: {epss}")?;
+        }
+        let day = |t: DateTime<Utc>| t.format("%Y-%m-%d");
+        match (self.published, self.modified) {
+            (Some(published), Some(modified))
+                if modified.date_naive() != published.date_naive() =>
+            {
+                write!(
+                    f,
+                    "\n    published: {} (modified {})",
+                    day(published),
+                    day(modified)
+                )?
+            }
+            (Some(published), _) => write!(f, "\n    published: {}", day(published))?,
+            (None, Some(modified)) => write!(f, "\n    modified: {}", day(modified))?,
+            (None, None) => {}
+        }
+        if let Some(withdrawn) = self.withdrawn {
+            write!(f, "\n    withdrawn: {}", day(withdrawn))?;
+        }
+        if !self.cwes.is_empty() {
+            write!(f, "\n    weakness: {}", self.cwes.join(", "))?;
+        }
+        if let Some(vex) = &self.vex {
+            write!(f, "\n    vex: {vex}")?;
+        }
+        if let Some(description) = &self.description {
+            write_block(f, "description", description.trim().lines())?;
+        }
+        if !self.references.is_empty() {
+            write_block(f, "references", self.references.iter().map(String::as_str))?;
         }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// `heading:` on its own line under an advisory, then `lines` indented
+/// beneath it. Blank lines are kept, without trailing whitespace.
+pub(crate) fn write_block<'a>(
+    f: &mut fmt::Formatter<'_>,
+    heading: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> fmt::Result {
+    write!(f, "\n    {heading}:")?;
+    for line in lines.into_iter().map(str::trim_end) {
+        if line.is_empty() {
+            writeln!(f)?;
+        } else {
+            write!(f, "\n      {line}")?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Low,
     Medium,
@@ -35,6 +172,90 @@ pub enum Severity {
     Critical,
 }
 
+impl Severity {
+    /// Normalize a provider's severity label, in any case: GHSA's `low` to
+    /// `critical` (GraphQL's `MODERATE` included), OSV `database_specific`
+    /// values, and `important` as used by some distributions. `None` for
+    /// `unknown`, `none` and anything else unrecognized.
+    pub fn normalize(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "critical" => Some(Severity::Critical),
+            "high" | "important" => Some(Severity::High),
+            "medium" | "moderate" => Some(Severity::Medium),
+            "low" => Some(Severity::Low),
+            _ => None,
+        }
+    }
+
+    /// CVSS qualitative rating of a base score. `None` for 0.0 ("none").
+    pub fn from_cvss(score: CvssScore) -> Option<Self> {
+        match score.value() {
+            s if s >= 9.0 => Some(Severity::Critical),
+            s if s >= 7.0 => Some(Severity::High),
+            s if s >= 4.0 => Some(Severity::Medium),
+            s if s > 0.0 => Some(Severity::Low),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// An advisory's severity from the source's label, falling back to the
+/// CVSS rating when the label is missing or unrecognized.
+pub fn normalize_severity(label: Option<&str>, cvss_score: Option<CvssScore>) -> Option<Severity> {
+    label
+        .and_then(Severity::normalize)
+        .or_else(|| cvss_score.and_then(Severity::from_cvss))
+}
+
+/// Serde for `Advisory::severity`: `None` as `"unknown"`, and any label
+/// `Severity::normalize` accepts when reading.
+mod severity_label {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(severity: &Option<Severity>, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(severity.map_or("unknown", Severity::as_str))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Severity>, D::Error> {
+        let label = Option::<String>::deserialize(d)?;
+        Ok(label.as_deref().and_then(Severity::normalize))
+    }
+}
+
+/// Deserializer for `Advisory::sources`: a list, or the comma-separated
+/// `source` string of reports from before the list.
+mod source_list {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Sources {
+        List(Vec<String>),
+        Legacy(String),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+        Ok(match Sources::deserialize(d)? {
+            Sources::List(sources) => sources,
+            Sources::Legacy(source) => source
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+        })
+    }
+}
+
 impl FromStr for Severity {
     type Err = anyhow::Error;
 
@@ -51,43 +272,195 @@ impl FromStr for Severity {
 
 impl fmt::Display for Severity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Severity::Low => write!(f, "low"),
-            Severity::Medium => write!(f, "medium"),
-            Severity::High => write!(f, "high"),
-            Severity::Critical => write!(f, "critical"),
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+impl Advisory {
+    /// A high-severity advisory `id`, known also as `aliases`, with no range
+    /// or source.
+    pub(crate) fn for_test(id: &str, aliases: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            summary: String::new(),
+            description: None,
+            references: vec![],
+            severity: Some(Severity::High),
+            url: String::new(),
+            affected_range: None,
+            fixed_version: None,
+            applicable: None,
+            sources: vec![],
+            vex: None,
         }
     }
 }
 
 impl Advisory {
-    /// Parse the free-form `severity` string into a typed `Severity`.
-    /// Returns `None` for unrecognized values.
-    pub fn parsed_severity(&self) -> Option<Severity> {
-        self.severity.parse().ok()
+    /// The severity as shown in output: its level, or `unknown`.
+    pub fn severity_label(&self) -> &'static str {
+        self.severity.map_or("unknown", Severity::as_str)
+    }
+
+    /// Fold in `other`, a report of the same vulnerability from another
+    /// record or provider. `self`'s ID is kept and `other`'s joins the
+    /// aliases; lists are unioned, the higher severity wins, and fields
+    /// `self` lacks are taken from `other`.
+    pub fn merge(&mut self, other: Advisory) {
+        for id in std::iter::once(other.id).chain(other.aliases) {
+            if id != self.id && !self.aliases.contains(&id) {
+                self.aliases.push(id);
+            }
+        }
+        for cwe in other.cwes {
+            if !self.cwes.contains(&cwe) {
+                self.cwes.push(cwe);
+            }
+        }
+        // Score and vector describe the same CVSS entry, so move them together.
+        if self.cvss_score.is_none() && self.cvss_vector.is_none() {
+            self.cvss_score = other.cvss_score;
+            self.cvss_vector = other.cvss_vector;
+        }
+        self.epss = self.epss.or(other.epss);
+        self.published = match (self.published, other.published) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.modified = self.modified.max(other.modified);
+        self.withdrawn = self.withdrawn.or(other.withdrawn);
+        if self.summary.is_empty() {
+            self.summary = other.summary;
+        }
+        if self.description.as_deref().is_none_or(str::is_empty) {
+            self.description = other.description;
+        }
+        for reference in other.references {
+            if !self.references.contains(&reference) {
+                self.references.push(reference);
+            }
+        }
+        self.severity = self.severity.max(other.severity);
+        if self.url.is_empty() {
+            self.url = other.url;
+        }
+        if self.affected_range.is_none() {
+            self.affected_range = other.affected_range;
+        }
+        if self.fixed_version.is_none() {
+            self.fixed_version = other.fixed_version;
+        }
+        for source in other.sources {
+            if !self.sources.contains(&source) {
+                self.sources.push(source);
+            }
+        }
+        if self.vex.is_none() {
+            self.vex = other.vex;
+        }
+    }
+
+    /// Drop the description and references, which only `--details` shows.
+    pub fn clear_details(&mut self) {
+        self.description = None;
+        self.references.clear();
+    }
+
+    /// True if the source has withdrawn this advisory.
+    pub fn is_withdrawn(&self) -> bool {
+        self.withdrawn.is_some()
+    }
+
+    /// True if a VEX statement says this advisory doesn't apply.
+    pub fn is_vex_suppressed(&self) -> bool {
+        self.vex.as_ref().is_some_and(VexAssessment::suppresses)
+    }
+
+    /// True if the pinned version is outside the affected range.
+    pub fn is_not_applicable(&self) -> bool {
+        self.applicable == Some(false)
+    }
+
+    /// True if the advisory should not count against the audit: ruled out
+    /// by VEX, or not applicable to the pinned version.
+    pub fn is_suppressed(&self) -> bool {
+        self.is_vex_suppressed() || self.is_not_applicable()
     }
 }
 
-/// Deduplicate advisories by ID and aliases.
-///
-/// If an advisory's ID or any of its aliases have already been seen,
-/// it is dropped. This handles cross-provider duplicates where e.g.
-/// GHSA and OSV report the same vulnerability under different IDs
-/// linked by aliases.
-pub fn deduplicate_advisories(mut advisories: Vec<Advisory>) -> Vec<Advisory> {
-    let mut seen_ids: HashSet<String> = HashSet::new();
-    advisories.retain(|adv| {
-        if seen_ids.contains(&adv.id) {
+/// The first version outside an affected range, taken from its last `< X`
+/// bound, for sources that give a range but no patched version. `None` when
+/// the range records no fix.
+pub fn fixed_in_range(range: &str) -> Option<String> {
+    range
+        .split(',')
+        .rev()
+        .find_map(|part| part.trim().strip_prefix("< "))
+        .map(|v| v.trim().to_string())
+}
+
+/// Which advisories the advisory and dependency stages keep. The default
+/// drops withdrawn advisories and nothing else.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdvisoryFilter {
+    /// Keep advisories their source has withdrawn.
+    pub include_withdrawn: bool,
+    /// Drop advisories published before this. Advisories without a
+    /// publication date are kept.
+    pub published_since: Option<DateTime<Utc>>,
+}
+
+impl AdvisoryFilter {
+    pub fn keeps(&self, advisory: &Advisory) -> bool {
+        if advisory.is_withdrawn() && !self.include_withdrawn {
             return false;
         }
-        if adv.aliases.iter().any(|a| seen_ids.contains(a)) {
-            return false;
+        match (self.published_since, advisory.published) {
+            (Some(since), Some(published)) => published >= since,
+            _ => true,
         }
-        seen_ids.insert(adv.id.clone());
-        seen_ids.extend(adv.aliases.iter().cloned());
-        true
-    });
-    advisories
+    }
+}
+
+/// Deduplicate advisories by ID and aliases.
+///
+/// An advisory whose ID or any alias matches one already kept is merged
+/// into it (see [`Advisory::merge`]). This handles cross-provider
+/// duplicates where e.g. GHSA and OSV report the same vulnerability under
+/// different IDs linked by aliases, without losing what only one provider
+/// reported.
+pub fn deduplicate_advisories(advisories: Vec<Advisory>) -> Vec<Advisory> {
+    let mut merged: Vec<Advisory> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for adv in advisories {
+        let existing = std::iter::once(&adv.id)
+            .chain(&adv.aliases)
+            .find_map(|id| index.get(id).copied());
+        let i = match existing {
+            Some(i) => {
+                merged[i].merge(adv);
+                i
+            }
+            None => {
+                merged.push(adv);
+                merged.len() - 1
+            }
+        };
+        let kept = &merged[i];
+        for id in std::iter::once(&kept.id).chain(&kept.aliases) {
+            index.entry(id.clone()).or_insert(i);
+        }
+    }
+    merged
 }
 
 #[cfg(test)]
@@ -98,14 +471,136 @@ mod tests {
         Advisory {
             id: id.to_string(),
             aliases: aliases.into_iter().map(String::from).collect(),
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: None,
+            description: None,
+            references: vec![],
+            vex: None,
             summary: format!("Advisory {id}"),
-            severity: "high".to_string(),
+            severity: Some(Severity::High),
             url: format!("https://example.com/{id}"),
             affected_range: None,
-            source: source.to_string(),
+            sources: vec![source.to_string()],
         }
     }
 
+    #[test]
+    fn fixed_in_range_uses_last_upper_bound() {
+        assert_eq!(
+            fixed_in_range(">= 4.0.0, < 4.17.21").as_deref(),
+            Some("4.17.21")
+        );
+        assert_eq!(
+            fixed_in_range("< 1.2.0, >= 2.0.0, < 2.1.0").as_deref(),
+            Some("2.1.0")
+        );
+        assert_eq!(fixed_in_range("<= 3.0.0"), None);
+    }
+
+    #[test]
+    fn display_suggests_upgrade_unless_not_applicable() {
+        let mut adv = make_advisory("GHSA-1", vec![], "OSV");
+        adv.affected_range = Some("< 2.1.0".to_string());
+        adv.fixed_version = Some("2.1.0".to_string());
+        assert!(
+            adv.to_string()
+                .ends_with("\n    affected: < 2.1.0\n    fix: upgrade to 2.1.0"),
+            "{adv}"
+        );
+        adv.applicable = Some(false);
+        assert!(!adv.to_string().contains("upgrade to"), "{adv}");
+    }
+
+    #[test]
+    fn filter_drops_withdrawn_and_older_advisories() {
+        let at = |s: &str| Some(s.parse::<DateTime<Utc>>().unwrap());
+        let mut adv = make_advisory("GHSA-1", vec![], "OSV");
+        let default = AdvisoryFilter::default();
+        assert!(default.keeps(&adv));
+
+        let since = AdvisoryFilter {
+            published_since: at("2025-01-01T00:00:00Z"),
+            ..default
+        };
+        assert!(since.keeps(&adv), "undated advisories are kept");
+        adv.published = at("2024-12-31T23:59:59Z");
+        assert!(!since.keeps(&adv));
+        adv.published = at("2025-01-01T00:00:00Z");
+        assert!(since.keeps(&adv));
+
+        adv.withdrawn = at("2025-02-01T00:00:00Z");
+        assert!(!default.keeps(&adv));
+        assert!(
+            AdvisoryFilter {
+                include_withdrawn: true,
+                ..default
+            }
+            .keeps(&adv)
+        );
+    }
+
+    #[test]
+    fn display_shows_dates() {
+        let at = |s: &str| Some(s.parse::<DateTime<Utc>>().unwrap());
+        let mut adv = make_advisory("GHSA-1", vec![], "GHSA");
+        adv.published = at("2024-03-01T12:00:00Z");
+        adv.modified = at("2024-03-01T18:00:00Z");
+        assert!(adv.to_string().ends_with("\n    published: 2024-03-01"));
+        adv.modified = at("2024-05-20T08:00:00Z");
+        assert!(
+            adv.to_string()
+                .ends_with("\n    published: 2024-03-01 (modified 2024-05-20)")
+        );
+    }
+
+    #[test]
+    fn display_shows_details_until_cleared() {
+        let mut adv = make_advisory("GHSA-1", vec![], "GHSA");
+        adv.description = Some("### Impact\n\nSecrets leak.  \n".to_string());
+        adv.references = vec!["https://example.com/fix".to_string()];
+        assert!(adv.to_string().ends_with(
+            "\n    description:\n      ### Impact\n\n      Secrets leak.\n    references:\n      https://example.com/fix"
+        ));
+        adv.clear_details();
+        assert!(!adv.to_string().contains("description"));
+        assert!(
+            serde_json::to_value(&adv)
+                .unwrap()
+                .get("references")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn display_shows_cvss() {
+        let mut adv = make_advisory("GHSA-1", vec![], "GHSA");
+        adv.cvss_score = CvssScore::new(9.8);
+        adv.cvss_vector = Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string());
+        assert!(
+            adv.to_string()
+                .contains("\n    cvss: 9.8 (CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H)")
+        );
+        let json = serde_json::to_value(&adv).unwrap();
+        assert_eq!(json["cvss_score"], 9.8);
+
+        adv.cvss_score = None;
+        adv.cvss_vector = None;
+        assert!(!adv.to_string().contains("cvss"));
+        assert!(
+            serde_json::to_value(&adv)
+                .unwrap()
+                .get("cvss_score")
+                .is_none()
+        );
+    }
+
     #[test]
     fn dedup_removes_exact_duplicate_ids() {
         let advisories = vec![
@@ -152,6 +647,47 @@ mod tests {
         assert_eq!(result[1].id, "GHSA-cccc");
     }
 
+    #[test]
+    fn dedup_merges_fields_from_duplicates() {
+        let mut ghsa = make_advisory("GHSA-aaaa", vec!["CVE-2025-0001"], "GHSA");
+        ghsa.severity = Some(Severity::Medium);
+        ghsa.url = String::new();
+        ghsa.cwes = vec!["CWE-79".to_string()];
+        let mut osv = make_advisory("CVE-2025-0001", vec!["GHSA-aaaa", "PYSEC-1"], "OSV");
+        osv.severity = Some(Severity::Critical);
+        osv.affected_range = Some("< 2.0.0".to_string());
+        osv.cwes = vec!["CWE-79".to_string(), "CWE-80".to_string()];
+        osv.description = Some("Full text".to_string());
+        osv.references = vec!["https://example.com/a".to_string()];
+        let mut depsdev = make_advisory("PYSEC-1", vec![], "deps.dev");
+        depsdev.summary = String::new();
+        depsdev.references = vec![
+            "https://example.com/a".to_string(),
+            "https://example.com/b".to_string(),
+        ];
+
+        let result = deduplicate_advisories(vec![ghsa, osv, depsdev]);
+        assert_eq!(result.len(), 1);
+        let adv = &result[0];
+        assert_eq!(adv.id, "GHSA-aaaa");
+        assert_eq!(adv.aliases, ["CVE-2025-0001", "PYSEC-1"]);
+        assert_eq!(adv.cwes, ["CWE-79", "CWE-80"]);
+        assert_eq!(adv.severity, Some(Severity::Critical));
+        assert_eq!(adv.url, "https://example.com/CVE-2025-0001");
+        assert_eq!(adv.summary, "Advisory GHSA-aaaa");
+        assert_eq!(adv.affected_range.as_deref(), Some("< 2.0.0"));
+        assert_eq!(adv.sources, ["GHSA", "OSV", "deps.dev"]);
+        assert_eq!(adv.description.as_deref(), Some("Full text"));
+        assert_eq!(
+            adv.references,
+            ["https://example.com/a", "https://example.com/b"]
+        );
+        assert!(
+            adv.to_string()
+                .contains("\n    found by: GHSA, OSV, deps.dev")
+        );
+    }
+
     #[test]
     fn dedup_empty_input() {
         let result = deduplicate_advisories(vec![]);
@@ -197,15 +733,57 @@ mod tests {
     }
 
     #[test]
-    fn advisory_parsed_severity_known() {
-        let adv = make_advisory("GHSA-1234", vec![], "GHSA");
-        assert_eq!(adv.parsed_severity(), Some(Severity::High)); // default severity is "high"
+    fn normalizes_provider_labels() {
+        assert_eq!(Severity::normalize("MODERATE"), Some(Severity::Medium));
+        assert_eq!(Severity::normalize("Critical"), Some(Severity::Critical));
+        assert_eq!(Severity::normalize("important"), Some(Severity::High));
+        assert_eq!(Severity::normalize("unknown"), None);
+        assert_eq!(Severity::normalize(""), None);
     }
 
     #[test]
-    fn advisory_parsed_severity_unknown() {
+    fn falls_back_to_cvss_rating() {
+        let score = |s: f64| CvssScore::new(s);
+        assert_eq!(
+            normalize_severity(Some("low"), score(9.8)),
+            Some(Severity::Low)
+        );
+        assert_eq!(
+            normalize_severity(Some("unknown"), score(9.0)),
+            Some(Severity::Critical)
+        );
+        assert_eq!(normalize_severity(None, score(6.9)), Some(Severity::Medium));
+        assert_eq!(normalize_severity(None, score(0.1)), Some(Severity::Low));
+        assert_eq!(normalize_severity(None, score(0.0)), None);
+        assert_eq!(normalize_severity(None, None), None);
+    }
+
+    #[test]
+    fn unknown_severity_serializes_as_unknown() {
         let mut adv = make_advisory("GHSA-1234", vec![], "GHSA");
-        adv.severity = "moderate".to_string();
-        assert_eq!(adv.parsed_severity(), None);
+        adv.severity = None;
+        let json = serde_json::to_value(&adv).unwrap();
+        assert_eq!(json["severity"], "unknown");
+        assert!(adv.to_string().starts_with("GHSA-1234 (unknown): "));
+
+        let mut json = json;
+        json["severity"] = "MODERATE".into();
+        let adv: Advisory = serde_json::from_value(json).unwrap();
+        assert_eq!(adv.severity, Some(Severity::Medium));
+    }
+
+    #[test]
+    fn reads_the_legacy_source_string() {
+        let mut json = serde_json::to_value(make_advisory("GHSA-1234", vec![], "GHSA")).unwrap();
+        assert_eq!(json["sources"], serde_json::json!(["GHSA"]));
+
+        let object = json.as_object_mut().unwrap();
+        object.remove("sources");
+        let adv: Advisory = serde_json::from_value(json.clone()).unwrap();
+        assert!(adv.sources.is_empty());
+
+        json["source"] = "GHSA, OSV".into();
+        let adv: Advisory = serde_json::from_value(json).unwrap();
+        assert_eq!(adv.sources, ["GHSA", "OSV"]);
     }
 }
@@ -0,0 +1,165 @@
+//! Artifacts that `workflow_run` workflows take from other runs.
+//!
+//! The triggering run may have built a fork's pull request, so its artifacts
+//! are untrusted however trusted the downloading workflow is. A download is
+//! flagged when it is extracted into the workspace, where it can replace
+//! checked-out files, or when later steps of its job run code: a `run:`
+//! script, a local `./` action, or `actions/github-script`. Whether those
+//! steps validate what they read is not something a static check can tell.
+
+use crate::finding::{ARTIFACT_POISONING, Finding};
+use crate::location::{LocationIndex, SourceLocation};
+use crate::workflow::{Step, UsesRef, Workflow};
+
+/// Actions that download artifacts of another workflow run: the action and
+/// the input that selects the run, or `None` if it always reads another run.
+const RUN_DOWNLOADS: &[(&str, Option<&str>)] = &[
+    ("actions/download-artifact", Some("run-id")),
+    ("dawidd6/action-download-artifact", None),
+];
+
+/// A step that brings another run's artifacts into the job.
+struct Download {
+    what: String,
+    into_workspace: bool,
+    location: Option<SourceLocation>,
+}
+
+/// The download `step` performs, without its location.
+fn download(step: &Step) -> Option<Download> {
+    let input = |key: &str| step.with.as_ref().and_then(|w| w.get(key));
+    if let Some(run) = &step.run {
+        let command = run.lines().find(|l| l.contains("gh run download"))?;
+        return Some(Download {
+            what: "`gh run download`".to_string(),
+            into_workspace: !command.contains(" -D ") && !command.contains("--dir"),
+            location: None,
+        });
+    }
+    if step.uses_action("actions/github-script") {
+        let script = input("script")?.as_str()?;
+        return script.contains("downloadArtifact(").then(|| Download {
+            what: "actions/github-script".to_string(),
+            into_workspace: false,
+            location: None,
+        });
+    }
+    let Some(UsesRef::ThirdParty(action)) = step.action() else {
+        return None;
+    };
+    let name = action.package_name();
+    let (_, run_input) = RUN_DOWNLOADS
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(&name))?;
+    if run_input.is_some_and(|key| input(key).is_none()) {
+        return None;
+    }
+    Some(Download {
+        what: name,
+        into_workspace: input("path").is_none(),
+        location: None,
+    })
+}
+
+/// Raise [`ARTIFACT_POISONING`] for downloads of another run's artifacts in
+/// a `workflow_run` workflow, located at the step's `uses:` or `run:`.
+pub fn check_artifact_poisoning(yaml: &str, file: &str) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    if !workflow.events().iter().any(|e| e == "workflow_run") {
+        return Ok(Vec::new());
+    }
+    let mut uses_locations = LocationIndex::for_key(yaml, "jobs", "uses");
+    let mut run_locations = LocationIndex::for_key(yaml, "jobs", "run");
+
+    let mut findings = Vec::new();
+    for (job, definition) in workflow.jobs() {
+        if let Some(uses) = &definition.uses {
+            uses_locations.take(uses);
+        }
+        let mut pending: Option<Download> = None;
+        let mut consumers = 0;
+        let mut flush = |pending: &mut Option<Download>, consumers: usize| {
+            let Some(download) = pending.take() else {
+                return;
+            };
+            let mut message = format!(
+                "{} in job `{job}` downloads artifacts from another workflow run",
+                download.what
+            );
+            if download.into_workspace {
+                message.push_str(" into the workspace, over the checked-out files");
+            }
+            match consumers {
+                0 if !download.into_workspace => return,
+                0 => {}
+                1 => message.push_str(", and the step after it runs code"),
+                n => message.push_str(&format!(", and {n} steps after it run code")),
+            }
+            findings.push(
+                Finding::new(&ARTIFACT_POISONING, message)
+                    .with_location(download.location.map(|l| l.with_file(file))),
+            );
+        };
+        for step in definition.steps.unwrap_or_default() {
+            let location = match (&step.uses, &step.run) {
+                (Some(uses), _) => uses_locations.take(uses),
+                (None, Some(run)) => run_locations.take(run),
+                (None, None) => None,
+            };
+            if let Some(mut found) = download(&step) {
+                flush(&mut pending, consumers);
+                found.location = location;
+                pending = Some(found);
+                consumers = 0;
+            } else if pending.is_some() && step.runs_code() {
+                consumers += 1;
+            }
+        }
+        flush(&mut pending, consumers);
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(yaml: &str) -> Vec<String> {
+        check_artifact_poisoning(yaml, "deploy.yml")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn flags_downloads_from_the_triggering_run() {
+        let yaml = "on:\n  workflow_run:\n    workflows: [CI]\n    types: [completed]\njobs:\n  deploy:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/checkout@v4\n      - uses: actions/download-artifact@v4\n        with:\n          name: site\n          run-id: ${{ github.event.workflow_run.id }}\n          github-token: ${{ github.token }}\n      - run: ./deploy.sh\n      - uses: ./.github/actions/publish\n  report:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: dawidd6/action-download-artifact@v6\n        with:\n          path: ${{ runner.temp }}/report\n      - uses: actions/upload-artifact@v4\n  comment:\n    runs-on: ubuntu-latest\n    steps:\n      - run: gh run download ${{ github.event.workflow_run.id }} -n pr\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "artifact-poisoning (high): actions/download-artifact in job `deploy` downloads artifacts from another workflow run into the workspace, over the checked-out files, and 2 steps after it run code\n    at deploy.yml:10:15",
+                "artifact-poisoning (high): `gh run download` in job `comment` downloads artifacts from another workflow run into the workspace, over the checked-out files\n    at deploy.yml:27:14",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_scripts_that_run_after_a_separate_download() {
+        let yaml = "on: workflow_run\njobs:\n  label:\n    steps:\n      - uses: actions/github-script@v7\n        with:\n          script: |\n            const zip = await github.rest.actions.downloadArtifact({ owner, repo, artifact_id, archive_format: 'zip' });\n      - run: unzip pr.zip -d \"$RUNNER_TEMP\"\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "artifact-poisoning (high): actions/github-script in job `label` downloads artifacts from another workflow run, and the step after it runs code\n    at deploy.yml:5:15"
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_downloads_from_the_same_run() {
+        let yaml = "on: workflow_run\njobs:\n  build:\n    steps:\n      - uses: actions/download-artifact@v4\n        with:\n          name: dist\n      - run: ls dist\n";
+        assert!(check(yaml).is_empty());
+        let yaml = "on: pull_request\njobs:\n  build:\n    steps:\n      - uses: dawidd6/action-download-artifact@v6\n      - run: ./run.sh\n";
+        assert!(check(yaml).is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+//! Comparison with an earlier report (CLI `--baseline`).
+//!
+//! A baseline is a JSON report from a previous run (`--format json`).
+//! Advisories and findings it already contains are left out of the new
+//! report, so a pipeline adopting ghss on an existing repository fails only
+//! on problems introduced since.
+//!
+//! Advisories are matched by action, ignoring the ref, and by ID or alias,
+//! so moving to another affected release of the same action is not new.
+//! Dependency advisories are also matched by package. Findings are matched
+//! by rule and message but not location, so edits that shift lines don't
+//! make old findings look new.
+
+use std::collections::BTreeSet;
+
+use anyhow::{Context, Result};
+
+use crate::advisory::Advisory;
+use crate::finding::Finding;
+use crate::output::{AuditNode, AuditReport};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    /// (subject, advisory ID or alias), lowercased. The subject is the
+    /// action's name, or `action -> package` for a dependency.
+    advisories: BTreeSet<(String, String)>,
+    /// (rule ID, message).
+    findings: BTreeSet<(String, String)>,
+}
+
+/// How much [`Baseline::apply`] removed as already known.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Known {
+    pub advisories: usize,
+    pub findings: usize,
+}
+
+fn advisory_ids(advisory: &Advisory) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(&advisory.id)
+        .chain(&advisory.aliases)
+        .map(|id| id.to_lowercase())
+}
+
+fn finding_key(finding: &Finding) -> (String, String) {
+    (finding.rule_id.clone(), finding.message.clone())
+}
+
+/// The subject of a node's own advisories.
+fn subject(node: &AuditNode) -> String {
+    node.entry.action.package_name().to_lowercase()
+}
+
+/// The subject of advisories on one of the action's dependencies.
+fn dependency_subject(action: &str, package: &str) -> String {
+    format!("{action} -> {}", package.to_lowercase())
+}
+
+impl Baseline {
+    /// Read a report written with `--format json`, or the bare action array
+    /// of the legacy `--json` flag.
+    pub fn from_json(json: &str) -> Result<Self> {
+        if let Ok(report) = serde_json::from_str::<AuditReport>(json) {
+            return Ok(Self::from_report(&report));
+        }
+        let actions: Vec<AuditNode> =
+            serde_json::from_str(json).context("baseline is not a ghss JSON report")?;
+        let mut baseline = Self::default();
+        baseline.add_nodes(&actions);
+        Ok(baseline)
+    }
+
+    pub fn from_report(report: &AuditReport) -> Self {
+        let mut baseline = Self::default();
+        baseline.add_nodes(&report.actions);
+        baseline
+            .findings
+            .extend(report.findings.iter().map(finding_key));
+        baseline
+    }
+
+    fn add_nodes(&mut self, nodes: &[AuditNode]) {
+        for node in nodes {
+            let action = subject(node);
+            for adv in &node.entry.advisories {
+                self.advisories
+                    .extend(advisory_ids(adv).map(|id| (action.clone(), id)));
+            }
+            for dep in &node.entry.dep_vulnerabilities {
+                let dependency = dependency_subject(&action, &dep.package);
+                for adv in &dep.advisories {
+                    self.advisories
+                        .extend(advisory_ids(adv).map(|id| (dependency.clone(), id)));
+                }
+            }
+            self.findings
+                .extend(node.entry.findings.iter().map(finding_key));
+            self.add_nodes(&node.children);
+        }
+    }
+
+    fn knows_advisory(&self, subject: &str, advisory: &Advisory) -> bool {
+        advisory_ids(advisory).any(|id| self.advisories.contains(&(subject.to_string(), id)))
+    }
+
+    /// Remove advisories and findings the baseline already has, from the
+    /// audited tree and from the workflow's `findings`.
+    pub fn apply(&self, nodes: &mut [AuditNode], findings: &mut Vec<Finding>) -> Known {
+        let mut known = Known::default();
+        self.apply_nodes(nodes, &mut known);
+        let before = findings.len();
+        findings.retain(|f| !self.findings.contains(&finding_key(f)));
+        known.findings += before - findings.len();
+        known
+    }
+
+    fn apply_nodes(&self, nodes: &mut [AuditNode], known: &mut Known) {
+        for node in nodes {
+            let action = subject(node);
+            let entry = &mut node.entry;
+
+            let before = entry.advisories.len();
+            entry
+                .advisories
+                .retain(|adv| !self.knows_advisory(&action, adv));
+            known.advisories += before - entry.advisories.len();
+            for dep in &mut entry.dep_vulnerabilities {
+                let dependency = dependency_subject(&action, &dep.package);
+                let before = dep.advisories.len();
+                dep.advisories
+                    .retain(|adv| !self.knows_advisory(&dependency, adv));
+                known.advisories += before - dep.advisories.len();
+            }
+
+            let before = entry.findings.len();
+            entry
+                .findings
+                .retain(|f| !self.findings.contains(&finding_key(f)));
+            known.findings += before - entry.findings.len();
+
+            self.apply_nodes(&mut node.children, known);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finding;
+
+    #[test]
+    fn keeps_only_what_is_new_since_the_baseline() {
+        let previous = AuditReport::new(
+            vec![AuditNode::with_advisories(
+                "tj-actions/changed-files@v44",
+                vec![Advisory::for_test(
+                    "GHSA-mrrh-fwg8-r2c3",
+                    &["CVE-2025-30066"],
+                )],
+            )],
+            vec![
+                Finding::new(&finding::UNPINNED_ACTION, "old")
+                    .with_location(Some(crate::location::SourceLocation::new(7, 15))),
+            ],
+        );
+        let json = serde_json::to_string(&previous).unwrap();
+        let baseline = Baseline::from_json(&json).unwrap();
+
+        let mut nodes = vec![
+            // Another ref of the same action, and the advisory under its CVE.
+            AuditNode::with_advisories(
+                "tj-actions/changed-files@v45",
+                vec![
+                    Advisory::for_test("CVE-2025-30066", &[]),
+                    Advisory::for_test("GHSA-new0-new0-new0", &[]),
+                ],
+            ),
+            AuditNode::with_advisories(
+                "other/action@v1",
+                vec![Advisory::for_test("GHSA-mrrh-fwg8-r2c3", &[])],
+            ),
+        ];
+        let mut findings = vec![
+            Finding::new(&finding::UNPINNED_ACTION, "old"),
+            Finding::new(&finding::UNPINNED_ACTION, "new"),
+        ];
+        let known = baseline.apply(&mut nodes, &mut findings);
+
+        assert_eq!(
+            known,
+            Known {
+                advisories: 1,
+                findings: 1
+            }
+        );
+        assert_eq!(nodes[0].entry.advisories[0].id, "GHSA-new0-new0-new0");
+        assert_eq!(nodes[0].entry.advisories.len(), 1);
+        assert_eq!(nodes[1].entry.advisories.len(), 1);
+        assert_eq!(findings[0].message, "new");
+        assert_eq!(findings.len(), 1);
+    }
+
+    #[test]
+    fn reads_reports_from_before_advisory_sources() {
+        let node = AuditNode::with_advisories(
+            "tj-actions/changed-files@v44",
+            vec![Advisory::for_test("GHSA-mrrh-fwg8-r2c3", &[])],
+        );
+        let mut json = serde_json::to_value([node]).unwrap();
+        let advisory = json[0]["advisories"][0].as_object_mut().unwrap();
+        advisory.remove("sources");
+        advisory.insert("source".to_string(), "GHSA, OSV".into());
+        let baseline = Baseline::from_json(&json.to_string()).unwrap();
+
+        let mut nodes = vec![AuditNode::with_advisories(
+            "tj-actions/changed-files@v45",
+            vec![Advisory::for_test("GHSA-mrrh-fwg8-r2c3", &[])],
+        )];
+        let known = baseline.apply(&mut nodes, &mut vec![]);
+        assert_eq!(known.advisories, 1);
+        assert!(nodes[0].entry.advisories.is_empty());
+    }
+
+    #[test]
+    fn rejects_other_json() {
+        let err = Baseline::from_json("{\"version\": 1}").unwrap_err();
+        assert_eq!(err.to_string(), "baseline is not a ghss JSON report");
+    }
+}
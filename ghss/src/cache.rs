@@ -0,0 +1,163 @@
+//! Caches saved by workflows that untrusted events trigger.
+//!
+//! Such workflows run in the default branch's cache scope, so whatever they
+//! save is restored by every later run on that branch. A save is flagged when
+//! its key comes from event data, or when the job has checked out the pull
+//! request's code, whose files end up in the cache.
+
+use std::collections::BTreeSet;
+
+use crate::action_ref::ActionRef;
+use crate::finding::{CACHE_POISONING, Finding};
+use crate::script::{UNTRUSTED_EXPRESSIONS, expressions};
+use crate::workflow::{self, UNTRUSTED_EVENTS, UsesRef, Workflow};
+
+/// Actions that save a cache under the `key:` input.
+const CACHE_SAVES: &[&str] = &["actions/cache", "actions/cache/save"];
+
+/// Setup actions that cache dependencies: the input that turns caching on,
+/// and whether it is on when the input is absent.
+const SETUP_CACHES: &[(&str, &str, bool)] = &[
+    ("actions/setup-node", "cache", false),
+    ("actions/setup-python", "cache", false),
+    ("actions/setup-java", "cache", false),
+    ("actions/setup-dotnet", "cache", false),
+    ("actions/setup-go", "cache", true),
+];
+
+/// `actions/checkout` `ref:` or `repository:` values that check out the
+/// pull request's code rather than the base branch.
+pub(crate) const PR_HEAD_REFS: &[&str] = &[
+    "github.event.pull_request.head.",
+    "github.head_ref",
+    "github.event.workflow_run.head_",
+    "refs/pull/",
+];
+
+fn named(action: &ActionRef, names: &[&str]) -> bool {
+    let name = action.package_name();
+    names.iter().any(|n| n.eq_ignore_ascii_case(&name))
+}
+
+/// Raise [`CACHE_POISONING`] for cache saves in a workflow triggered by one
+/// of [`UNTRUSTED_EVENTS`], located at the step's `uses:`.
+pub fn check_cache_poisoning(yaml: &str, file: &str) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    let events: Vec<String> = workflow
+        .events()
+        .into_iter()
+        .filter(|e| UNTRUSTED_EVENTS.contains(&e.as_str()))
+        .collect();
+    if events.is_empty() {
+        return Ok(Vec::new());
+    }
+    let events = events.join(", ");
+    let actions: Vec<ActionRef> = workflow::parse_workflow_file(yaml, file)?
+        .into_iter()
+        .filter_map(UsesRef::into_third_party)
+        .collect();
+
+    // Jobs that check out the pull request's code.
+    let untrusted_checkouts: BTreeSet<&str> = actions
+        .iter()
+        .filter(|a| named(a, &["actions/checkout"]))
+        .flat_map(|a| &a.occurrences)
+        .filter(|o| {
+            ["ref", "repository"].iter().any(|input| {
+                o.with
+                    .get(*input)
+                    .is_some_and(|v| PR_HEAD_REFS.iter().any(|p| v.contains(p)))
+            })
+        })
+        .filter_map(|o| o.job.as_deref())
+        .collect();
+
+    let mut findings = Vec::new();
+    for action in &actions {
+        for occurrence in &action.occurrences {
+            let job = occurrence.job.as_deref().unwrap_or("<unnamed>");
+            let checked_out = untrusted_checkouts.contains(job);
+            let name = action.package_name();
+            let message = if named(action, CACHE_SAVES) {
+                let untrusted_key = occurrence
+                    .with
+                    .get("key")
+                    .into_iter()
+                    .flat_map(|key| expressions(key))
+                    .find(|expr| UNTRUSTED_EXPRESSIONS.iter().any(|u| expr.contains(u)));
+                match untrusted_key {
+                    Some(expr) => format!(
+                        "{name} in job `{job}` saves a cache keyed on untrusted `${{{{ {expr} }}}}` in a workflow triggered by {events}"
+                    ),
+                    None if checked_out => format!(
+                        "{name} in job `{job}` saves a cache after checking out the pull request's code, in a workflow triggered by {events}"
+                    ),
+                    None => continue,
+                }
+            } else if let Some((_, input, default)) = SETUP_CACHES
+                .iter()
+                .find(|(setup, ..)| named(action, &[setup]))
+            {
+                let enabled = occurrence
+                    .with
+                    .get(*input)
+                    .map_or(*default, |v| !v.is_empty() && v != "false");
+                if !(enabled && checked_out) {
+                    continue;
+                }
+                format!(
+                    "{name} in job `{job}` caches the dependencies of the pull request's code it checked out, in a workflow triggered by {events}"
+                )
+            } else {
+                continue;
+            };
+            findings.push(
+                Finding::new(&CACHE_POISONING, message).with_location(occurrence.location.clone()),
+            );
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(yaml: &str) -> Vec<String> {
+        check_cache_poisoning(yaml, "ci.yml")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn flags_cache_keys_from_event_data() {
+        let yaml = "on: pull_request_target\njobs:\n  build:\n    steps:\n      - uses: actions/cache@v4\n        with:\n          path: ~/.npm\n          key: npm-${{ github.event.pull_request.head.ref }}\n      - uses: actions/cache@v4\n        with:\n          path: ~/.cargo\n          key: cargo-${{ hashFiles('Cargo.lock') }}\n      - uses: actions/setup-node@v4\n        with:\n          cache: npm\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "cache-poisoning (high): actions/cache in job `build` saves a cache keyed on untrusted `${{ github.event.pull_request.head.ref }}` in a workflow triggered by pull_request_target\n    at ci.yml:5:15"
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_caches_of_checked_out_pull_requests() {
+        let yaml = "on:\n  pull_request_target:\n  issue_comment:\njobs:\n  test:\n    steps:\n      - uses: actions/checkout@v4\n        with:\n          ref: ${{ github.event.pull_request.head.sha }}\n      - uses: actions/setup-node@v4\n        with:\n          cache: npm\n      - uses: actions/setup-python@v5\n      - uses: actions/setup-go@v5\n      - uses: actions/cache/save@v4\n        with:\n          key: build-${{ github.sha }}\n  label:\n    steps:\n      - uses: actions/checkout@v4\n      - uses: actions/setup-go@v5\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "cache-poisoning (high): actions/setup-node in job `test` caches the dependencies of the pull request's code it checked out, in a workflow triggered by pull_request_target, issue_comment\n    at ci.yml:10:15",
+                "cache-poisoning (high): actions/setup-go in job `test` caches the dependencies of the pull request's code it checked out, in a workflow triggered by pull_request_target, issue_comment\n    at ci.yml:14:15",
+                "cache-poisoning (high): actions/cache/save in job `test` saves a cache after checking out the pull request's code, in a workflow triggered by pull_request_target, issue_comment\n    at ci.yml:15:15",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_trusted_events() {
+        let yaml = "on: [push, pull_request]\njobs:\n  build:\n    steps:\n      - uses: actions/cache@v4\n        with:\n          key: ${{ github.head_ref }}\n";
+        assert!(check(yaml).is_empty());
+    }
+}
@@ -0,0 +1,1029 @@
+//! A subset of [CEL](https://cel.dev), the Common Expression Language, for
+//! the policy checks in [`crate::policy`].
+//!
+//! Values are JSON, so an expression reads an action's fields as
+//! `--format json` reports them. Supported are literals (`1`, `2.5`, `"s"`,
+//! `'s'`, `true`, `null`, `[1, 2]`), field access (`a.b`) and indexing
+//! (`a[0]`, `a["b"]`), `!`, `-`, `*`, `/`, `%`, `+`, comparisons, `in`,
+//! `&&`, `||` and `? :`; the functions `size()` and `has()` (of a field,
+//! `has(a.b)`); the string
+//! methods `contains`, `startsWith`, `endsWith`, `matches` (a regular
+//! expression) and `lowerAscii`; and the list macros `exists`, `all`,
+//! `exists_one`, `filter` and `map`.
+//!
+//! Unlike CEL, a missing field is `null` rather than an error, since most
+//! fields are only collected by some flags, and `<`, `<=`, `>` and `>=`
+//! are false when either side is `null`. Numbers are not split into ints
+//! and doubles. Parse errors give the 1-based column of the offending
+//! token.
+
+use std::fmt;
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    source: String,
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Literal(Value),
+    List(Vec<Node>),
+    Ident(String),
+    Field(Box<Node>, String),
+    Index(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Neg(Box<Node>),
+    Binary(Op, Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Conditional(Box<Node>, Box<Node>, Box<Node>),
+    /// A global function (`size(x)`) or a method (`x.size()`).
+    Call {
+        target: Option<Box<Node>>,
+        name: String,
+        args: Vec<Node>,
+    },
+    /// `list.exists(x, predicate)` and the other list macros.
+    Macro {
+        target: Box<Node>,
+        kind: MacroKind,
+        var: String,
+        body: Box<Node>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacroKind {
+    Exists,
+    All,
+    ExistsOne,
+    Filter,
+    Map,
+}
+
+impl MacroKind {
+    fn named(name: &str) -> Option<Self> {
+        Some(match name {
+            "exists" => Self::Exists,
+            "all" => Self::All,
+            "exists_one" => Self::ExistsOne,
+            "filter" => Self::Filter,
+            "map" => Self::Map,
+            _ => return None,
+        })
+    }
+}
+
+impl Expr {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: source.chars().count() + 1,
+        };
+        let root = parser.expr()?;
+        if let Some(token) = parser.peek() {
+            bail!("unexpected {token} at column {}", parser.column());
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    /// Evaluate with the fields of `vars` as top-level identifiers.
+    pub fn eval(&self, vars: &Map<String, Value>) -> Result<Value> {
+        Eval {
+            vars,
+            locals: Vec::new(),
+        }
+        .eval(&self.root)
+    }
+
+    /// Evaluate an expression that must give a boolean.
+    pub fn eval_bool(&self, vars: &Map<String, Value>) -> Result<bool> {
+        match self.eval(vars)? {
+            Value::Bool(b) => Ok(b),
+            other => bail!("expected a boolean, got {}", type_name(&other)),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(Value),
+    Str(String),
+    Punct(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "`{name}`"),
+            Token::Number(n) => write!(f, "`{n}`"),
+            Token::Str(s) => write!(f, "{s:?}"),
+            Token::Punct(p) => write!(f, "`{p}`"),
+        }
+    }
+}
+
+/// Longest first, so `<=` is not read as `<`.
+const PUNCTUATION: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "(", ")", "[", "]", ".", ",", "?", ":", "!", "<", ">", "+",
+    "-", "*", "/", "%",
+];
+
+/// Tokens with the 1-based column each starts at.
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        let column = source[..source.len() - rest.len()].chars().count() + 1;
+        if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push((column, Token::Ident(rest[..end].to_string())));
+            rest = &rest[end..];
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                .unwrap_or(rest.len());
+            let text = &rest[..end];
+            let number = match text.parse::<i64>() {
+                Ok(n) => Value::from(n),
+                Err(_) => text
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .with_context(|| format!("invalid number `{text}` at column {column}"))?,
+            };
+            tokens.push((column, Token::Number(number)));
+            rest = &rest[end..];
+        } else if c == '"' || c == '\'' {
+            let (text, len) = string_literal(rest, c)
+                .with_context(|| format!("in the string at column {column}"))?;
+            tokens.push((column, Token::Str(text)));
+            rest = &rest[len..];
+        } else if let Some(p) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) {
+            tokens.push((column, Token::Punct(p)));
+            rest = &rest[p.len()..];
+        } else {
+            bail!("unexpected character `{c}` at column {column}");
+        }
+    }
+}
+
+/// The text of the string literal at the start of `rest`, and its length
+/// with the quotes.
+fn string_literal(rest: &str, quote: char) -> Result<(String, usize)> {
+    let mut text = String::new();
+    let mut chars = rest.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return Ok((text, i + 1)),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some(c @ ('\\' | '"' | '\'')) => text.push(c),
+                Some(c) => bail!("unknown escape `\\{c}`"),
+                None => break,
+            },
+            c => text.push(c),
+        }
+    }
+    bail!("unterminated string")
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// The column just past the last character.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    /// Column of the next token.
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |(column, _)| *column)
+    }
+
+    fn eat(&mut self, punct: &'static str) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &'static str) -> Result<()> {
+        if self.eat(punct) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => bail!(
+                "expected `{punct}`, found {token} at column {}",
+                self.column()
+            ),
+            None => bail!("expected `{punct}`, found the end of the expression"),
+        }
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(n)) if n == name)
+    }
+
+    fn expr(&mut self) -> Result<Node> {
+        let condition = self.or()?;
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+        let then = self.or()?;
+        self.expect(":")?;
+        let otherwise = self.expr()?;
+        Ok(Node::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn or(&mut self) -> Result<Node> {
+        let mut left = self.and()?;
+        while self.eat("||") {
+            left = Node::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Node> {
+        let mut left = self.relation()?;
+        while self.eat("&&") {
+            left = Node::And(Box::new(left), Box::new(self.relation()?));
+        }
+        Ok(left)
+    }
+
+    fn relation(&mut self) -> Result<Node> {
+        let mut left = self.addition()?;
+        loop {
+            let op = if self.eat("==") {
+                Op::Eq
+            } else if self.eat("!=") {
+                Op::Ne
+            } else if self.eat("<=") {
+                Op::Le
+            } else if self.eat(">=") {
+                Op::Ge
+            } else if self.eat("<") {
+                Op::Lt
+            } else if self.eat(">") {
+                Op::Gt
+            } else if self.is_ident("in") {
+                self.pos += 1;
+                Op::In
+            } else {
+                return Ok(left);
+            };
+            left = Node::Binary(op, Box::new(left), Box::new(self.addition()?));
+        }
+    }
+
+    fn addition(&mut self) -> Result<Node> {
+        let mut left = self.multiplication()?;
+        loop {
+            let op = if self.eat("+") {
+                Op::Add
+            } else if self.eat("-") {
+                Op::Sub
+            } else {
+                return Ok(left);
+            };
+            left = Node::Binary(op, Box::new(left), Box::new(self.multiplication()?));
+        }
+    }
+
+    fn multiplication(&mut self) -> Result<Node> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat("*") {
+                Op::Mul
+            } else if self.eat("/") {
+                Op::Div
+            } else if self.eat("%") {
+                Op::Rem
+            } else {
+                return Ok(left);
+            };
+            left = Node::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        if self.eat("!") {
+            Ok(Node::Not(Box::new(self.unary()?)))
+        } else if self.eat("-") {
+            Ok(Node::Neg(Box::new(self.unary()?)))
+        } else {
+            self.member()
+        }
+    }
+
+    fn member(&mut self) -> Result<Node> {
+        let mut node = self.primary()?;
+        loop {
+            if self.eat(".") {
+                let column = self.column();
+                let name = self.ident()?;
+                if !self.eat("(") {
+                    node = Node::Field(Box::new(node), name);
+                    continue;
+                }
+                let args = self.args()?;
+                node = match MacroKind::named(&name) {
+                    Some(kind) => {
+                        let mut args = args.into_iter();
+                        let (Some(Node::Ident(var)), Some(body), None) =
+                            (args.next(), args.next(), args.next())
+                        else {
+                            bail!("{name}() at column {column} takes a variable and an expression");
+                        };
+                        Node::Macro {
+                            target: Box::new(node),
+                            kind,
+                            var,
+                            body: Box::new(body),
+                        }
+                    }
+                    None => Node::Call {
+                        target: Some(Box::new(node)),
+                        name,
+                        args,
+                    },
+                };
+            } else if self.eat("[") {
+                let index = self.expr()?;
+                self.expect("]")?;
+                node = Node::Index(Box::new(node), Box::new(index));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node> {
+        let column = self.column();
+        let Some(token) = self.peek().cloned() else {
+            bail!("unexpected end of the expression");
+        };
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Node::Literal(n)),
+            Token::Str(s) => Ok(Node::Literal(Value::String(s))),
+            Token::Ident(name) => Ok(match name.as_str() {
+                "true" => Node::Literal(Value::Bool(true)),
+                "false" => Node::Literal(Value::Bool(false)),
+                "null" => Node::Literal(Value::Null),
+                "has" if self.eat("(") => match self.args()?.as_slice() {
+                    [arg @ (Node::Ident(_) | Node::Field(..))] => Node::Call {
+                        target: None,
+                        name,
+                        args: vec![arg.clone()],
+                    },
+                    _ => bail!("has() at column {column} takes a field, such as has(a.b)"),
+                },
+                _ if self.eat("(") => Node::Call {
+                    target: None,
+                    args: self.args()?,
+                    name,
+                },
+                _ => Node::Ident(name),
+            }),
+            Token::Punct("(") => {
+                let node = self.expr()?;
+                self.expect(")")?;
+                Ok(node)
+            }
+            Token::Punct("[") => {
+                let mut items = Vec::new();
+                if !self.eat("]") {
+                    loop {
+                        items.push(self.expr()?);
+                        if self.eat("]") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Node::List(items))
+            }
+            token => bail!("unexpected {token} at column {column}"),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.peek().cloned() {
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                Ok(name)
+            }
+            Some(token) => bail!(
+                "expected a field name, found {token} at column {}",
+                self.column()
+            ),
+            None => bail!("expected a field name, found the end of the expression"),
+        }
+    }
+
+    /// Arguments after `(`, through the closing `)`.
+    fn args(&mut self) -> Result<Vec<Node>> {
+        let mut args = Vec::new();
+        if self.eat(")") {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            if self.eat(")") {
+                return Ok(args);
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+struct Eval<'a> {
+    vars: &'a Map<String, Value>,
+    /// Macro variables, innermost last.
+    locals: Vec<(String, Value)>,
+}
+
+impl Eval<'_> {
+    fn eval(&mut self, node: &Node) -> Result<Value> {
+        Ok(match node {
+            Node::Literal(value) => value.clone(),
+            Node::List(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.eval(item))
+                    .collect::<Result<_>>()?,
+            ),
+            Node::Ident(name) => self
+                .locals
+                .iter()
+                .rev()
+                .find(|(local, _)| local == name)
+                .map(|(_, value)| value)
+                .or_else(|| self.vars.get(name))
+                .cloned()
+                .unwrap_or(Value::Null),
+            Node::Field(target, name) => match self.eval(target)? {
+                Value::Object(mut fields) => fields.remove(name).unwrap_or(Value::Null),
+                Value::Null => Value::Null,
+                other => bail!("cannot read .{name} of {}", type_name(&other)),
+            },
+            Node::Index(target, index) => match (self.eval(target)?, self.eval(index)?) {
+                (Value::Array(mut items), Value::Number(n)) => {
+                    match n.as_u64().map(|i| i as usize) {
+                        Some(i) if i < items.len() => items.swap_remove(i),
+                        _ => bail!("index {n} out of range"),
+                    }
+                }
+                (Value::Object(mut fields), Value::String(key)) => {
+                    fields.remove(&key).unwrap_or(Value::Null)
+                }
+                (Value::Null, _) => Value::Null,
+                (target, index) => bail!(
+                    "cannot index {} with {}",
+                    type_name(&target),
+                    type_name(&index)
+                ),
+            },
+            Node::Not(operand) => Value::Bool(!self.eval_bool(operand)?),
+            Node::Neg(operand) => match self.eval(operand)? {
+                Value::Number(n) => number(-as_f64(&n)),
+                other => bail!("cannot negate {}", type_name(&other)),
+            },
+            Node::And(left, right) => Value::Bool(self.eval_bool(left)? && self.eval_bool(right)?),
+            Node::Or(left, right) => Value::Bool(self.eval_bool(left)? || self.eval_bool(right)?),
+            Node::Conditional(condition, then, otherwise) => {
+                if self.eval_bool(condition)? {
+                    self.eval(then)?
+                } else {
+                    self.eval(otherwise)?
+                }
+            }
+            Node::Binary(op, left, right) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                binary(*op, left, right)?
+            }
+            Node::Call { target, name, args } => {
+                let target = target.as_deref().map(|t| self.eval(t)).transpose()?;
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>>>()?;
+                call(target, name, args)?
+            }
+            Node::Macro {
+                target,
+                kind,
+                var,
+                body,
+            } => {
+                let items = match self.eval(target)? {
+                    Value::Array(items) => items,
+                    Value::Null => Vec::new(),
+                    other => bail!("cannot iterate over {}", type_name(&other)),
+                };
+                self.eval_macro(*kind, var, body, items)?
+            }
+        })
+    }
+
+    fn eval_bool(&mut self, node: &Node) -> Result<bool> {
+        match self.eval(node)? {
+            Value::Bool(b) => Ok(b),
+            other => bail!("expected a boolean, got {}", type_name(&other)),
+        }
+    }
+
+    fn eval_macro(
+        &mut self,
+        kind: MacroKind,
+        var: &str,
+        body: &Node,
+        items: Vec<Value>,
+    ) -> Result<Value> {
+        let mut matched = 0;
+        let mut kept = Vec::new();
+        for item in items {
+            self.locals.push((var.to_string(), item.clone()));
+            let result = if kind == MacroKind::Map {
+                self.eval(body).map(|value| kept.push(value))
+            } else {
+                self.eval_bool(body).map(|holds| {
+                    if holds {
+                        matched += 1;
+                        if kind == MacroKind::Filter {
+                            kept.push(item);
+                        }
+                    } else if kind == MacroKind::All {
+                        matched = usize::MAX;
+                    }
+                })
+            };
+            self.locals.pop();
+            result?;
+            if kind == MacroKind::All && matched == usize::MAX {
+                return Ok(Value::Bool(false));
+            }
+        }
+        Ok(match kind {
+            MacroKind::Exists => Value::Bool(matched > 0),
+            MacroKind::All => Value::Bool(true),
+            MacroKind::ExistsOne => Value::Bool(matched == 1),
+            MacroKind::Filter | MacroKind::Map => Value::Array(kept),
+        })
+    }
+}
+
+fn binary(op: Op, left: Value, right: Value) -> Result<Value> {
+    use std::cmp::Ordering;
+    let ordering = |left: &Value, right: &Value| -> Result<Option<Ordering>> {
+        Ok(match (left, right) {
+            (Value::Null, _) | (_, Value::Null) => None,
+            (Value::Number(a), Value::Number(b)) => as_f64(a).partial_cmp(&as_f64(b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            _ => bail!(
+                "cannot compare {} with {}",
+                type_name(left),
+                type_name(right)
+            ),
+        })
+    };
+    Ok(match op {
+        Op::Eq => Value::Bool(equal(&left, &right)),
+        Op::Ne => Value::Bool(!equal(&left, &right)),
+        Op::Lt => Value::Bool(ordering(&left, &right)? == Some(Ordering::Less)),
+        Op::Le => Value::Bool(matches!(
+            ordering(&left, &right)?,
+            Some(Ordering::Less | Ordering::Equal)
+        )),
+        Op::Gt => Value::Bool(ordering(&left, &right)? == Some(Ordering::Greater)),
+        Op::Ge => Value::Bool(matches!(
+            ordering(&left, &right)?,
+            Some(Ordering::Greater | Ordering::Equal)
+        )),
+        Op::In => match &right {
+            Value::Array(items) => Value::Bool(items.iter().any(|item| equal(&left, item))),
+            Value::Object(fields) => match &left {
+                Value::String(key) => Value::Bool(fields.contains_key(key)),
+                _ => bail!("map keys are strings, not {}", type_name(&left)),
+            },
+            Value::Null => Value::Bool(false),
+            _ => bail!("`in` needs a list or map, not {}", type_name(&right)),
+        },
+        Op::Add => match (left, right) {
+            (Value::Number(a), Value::Number(b)) => number(as_f64(&a) + as_f64(&b)),
+            (Value::String(a), Value::String(b)) => Value::String(a + &b),
+            (Value::Array(mut a), Value::Array(b)) => {
+                a.extend(b);
+                Value::Array(a)
+            }
+            (a, b) => bail!("cannot add {} and {}", type_name(&a), type_name(&b)),
+        },
+        Op::Sub | Op::Mul | Op::Div | Op::Rem => {
+            let (Value::Number(a), Value::Number(b)) = (&left, &right) else {
+                bail!(
+                    "arithmetic needs numbers, not {} and {}",
+                    type_name(&left),
+                    type_name(&right)
+                );
+            };
+            let (a, b) = (as_f64(a), as_f64(b));
+            if matches!(op, Op::Div | Op::Rem) && b == 0.0 {
+                bail!("division by zero");
+            }
+            number(match op {
+                Op::Sub => a - b,
+                Op::Mul => a * b,
+                Op::Div => a / b,
+                _ => a % b,
+            })
+        }
+    })
+}
+
+fn call(target: Option<Value>, name: &str, args: Vec<Value>) -> Result<Value> {
+    let string_arg = |args: &[Value]| match args {
+        [Value::String(s)] => Ok(s.clone()),
+        _ => bail!("{name}() takes one string"),
+    };
+    Ok(match (target, name) {
+        (Some(value), "size") if args.is_empty() => size(&value)?,
+        (None, "size") => match args.as_slice() {
+            [value] => size(value)?,
+            _ => bail!("size() takes one argument"),
+        },
+        (None, "has") => match args.as_slice() {
+            [value] => Value::Bool(!value.is_null()),
+            _ => bail!("has() takes one field"),
+        },
+        (Some(Value::String(s)), "contains") => Value::Bool(s.contains(&string_arg(&args)?)),
+        (Some(Value::String(s)), "startsWith") => Value::Bool(s.starts_with(&string_arg(&args)?)),
+        (Some(Value::String(s)), "endsWith") => Value::Bool(s.ends_with(&string_arg(&args)?)),
+        (Some(Value::String(s)), "matches") => {
+            let pattern = string_arg(&args)?;
+            let regex = regex::Regex::new(&pattern)
+                .with_context(|| format!("invalid regular expression {pattern:?}"))?;
+            Value::Bool(regex.is_match(&s))
+        }
+        (Some(Value::String(s)), "lowerAscii") if args.is_empty() => {
+            Value::String(s.to_ascii_lowercase())
+        }
+        (Some(Value::Null), _) => Value::Null,
+        (Some(target), name) => bail!("{} has no method {name}()", type_name(&target)),
+        (None, name) => bail!("unknown function {name}()"),
+    })
+}
+
+fn size(value: &Value) -> Result<Value> {
+    Ok(Value::from(match value {
+        Value::String(s) => s.chars().count(),
+        Value::Array(items) => items.len(),
+        Value::Object(fields) => fields.len(),
+        Value::Null => 0,
+        other => bail!("{} has no size", type_name(other)),
+    }))
+}
+
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => as_f64(a) == as_f64(b),
+        _ => left == right,
+    }
+}
+
+fn as_f64(n: &serde_json::Number) -> f64 {
+    n.as_f64().unwrap_or(f64::NAN)
+}
+
+/// A whole number as an integer, so it prints without `.0`.
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "a map",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expr: &str, vars: Value) -> Result<Value> {
+        let Value::Object(vars) = vars else {
+            panic!("vars must be an object")
+        };
+        Expr::parse(expr)?.eval(&vars)
+    }
+
+    #[test]
+    fn evaluates_expressions() {
+        let vars = serde_json::json!({
+            "owner": "Acme",
+            "stars": 12,
+            "advisories": [
+                {"id": "GHSA-1", "severity": "high"},
+                {"id": "GHSA-2", "severity": "low"},
+            ],
+            "scan": null,
+        });
+        let cases = [
+            ("owner == 'Acme' && stars < 50", serde_json::json!(true)),
+            (
+                "owner.lowerAscii().startsWith(\"ac\")",
+                serde_json::json!(true),
+            ),
+            ("owner.matches('^A.m')", serde_json::json!(true)),
+            (
+                "size(advisories) + advisories.size() * 2",
+                serde_json::json!(6),
+            ),
+            (
+                "advisories.exists(a, a.severity in ['high', 'critical'])",
+                serde_json::json!(true),
+            ),
+            (
+                "advisories.all(a, a.severity == 'high')",
+                serde_json::json!(false),
+            ),
+            (
+                "advisories.filter(a, a.severity == 'low').map(a, a.id)",
+                serde_json::json!(["GHSA-2"]),
+            ),
+            ("advisories[0]['id']", serde_json::json!("GHSA-1")),
+            (
+                "scan.languages.exists(l, l == 'js')",
+                serde_json::json!(false),
+            ),
+            ("scan.stars > 3 || !has(scan)", serde_json::json!(true)),
+            ("stars / 4 - 0.5", serde_json::json!(2.5)),
+            ("stars > 10 ? 'many' : 'few'", serde_json::json!("many")),
+            ("-(1 + 2) % 2", serde_json::json!(-1)),
+            (
+                "'x' in ['x', 'y'] && 'id' in advisories[1]",
+                serde_json::json!(true),
+            ),
+        ];
+        for (expr, want) in cases {
+            assert_eq!(eval(expr, vars.clone()).unwrap(), want, "{expr}");
+        }
+
+        let err = |expr: &str| format!("{:#}", eval(expr, vars.clone()).unwrap_err());
+        assert_eq!(err("owner < 3"), "cannot compare a string with a number");
+        assert_eq!(err("stars / 0"), "division by zero");
+        assert_eq!(
+            err("owner.frobnicate()"),
+            "a string has no method frobnicate()"
+        );
+        assert_eq!(err("owner =="), "unexpected end of the expression");
+        assert_eq!(
+            err("(stars"),
+            "expected `)`, found the end of the expression"
+        );
+        assert_eq!(err("stars stars"), "unexpected `stars` at column 7");
+        assert_eq!(
+            err("advisories.exists(1, true)"),
+            "exists() at column 12 takes a variable and an expression"
+        );
+    }
+
+    fn action() -> Value {
+        serde_json::json!({
+            "owner": "Acme",
+            "stars": 12,
+            "labels": {"team": "core"},
+            "advisories": [
+                {"id": "GHSA-1", "severity": "high"},
+                {"id": "GHSA-2", "severity": "low"},
+            ],
+            "scan": null,
+        })
+    }
+
+    fn check(cases: &[(&str, Value)]) {
+        for (expr, want) in cases {
+            assert_eq!(&eval(expr, action()).unwrap(), want, "{expr}");
+        }
+    }
+
+    fn err(expr: &str) -> String {
+        format!("{:#}", eval(expr, action()).unwrap_err())
+    }
+
+    #[test]
+    fn operator_precedence() {
+        use serde_json::json;
+        check(&[
+            ("1 + 2 * 3", json!(7)),
+            ("(1 + 2) * 3", json!(9)),
+            ("10 - 4 - 3", json!(3)),
+            ("2 * 3 % 4", json!(2)),
+            ("-2 * 3", json!(-6)),
+            ("!false && false", json!(false)),
+            ("true || false && false", json!(true)),
+            ("1 + 1 == 2 && 'a' in ['a']", json!(true)),
+            ("1 < 2 == true", json!(true)),
+            ("true || false ? 1 : 2", json!(1)),
+        ]);
+    }
+
+    #[test]
+    fn conditionals_nest_to_the_right() {
+        use serde_json::json;
+        check(&[
+            (
+                "stars > 20 ? 'many' : stars > 10 ? 'some' : 'few'",
+                json!("some"),
+            ),
+            (
+                "stars > 20 ? 'many' : stars > 15 ? 'some' : 'few'",
+                json!("few"),
+            ),
+            ("true ? (false ? 1 : 2) : 3", json!(2)),
+        ]);
+        // As in CEL, a conditional in the middle needs parentheses.
+        assert_eq!(
+            err("true ? false ? 1 : 2 : 3"),
+            "expected `:`, found `?` at column 14"
+        );
+        assert_eq!(err("stars ? 1 : 2"), "expected a boolean, got a number");
+    }
+
+    #[test]
+    fn exists_one_and_in_over_maps() {
+        use serde_json::json;
+        check(&[
+            (
+                "advisories.exists_one(a, a.severity == 'high')",
+                json!(true),
+            ),
+            ("[1, 2, 3].exists_one(n, n > 1)", json!(false)),
+            ("[].exists_one(n, true)", json!(false)),
+            ("scan.exists_one(n, true)", json!(false)),
+            ("'team' in labels", json!(true)),
+            ("'owner' in labels", json!(false)),
+            ("'id' in advisories[0]", json!(true)),
+            ("'team' in scan", json!(false)),
+        ]);
+        assert_eq!(err("1 in labels"), "map keys are strings, not a number");
+        assert_eq!(
+            err("'A' in owner"),
+            "`in` needs a list or map, not a string"
+        );
+        assert_eq!(
+            err("advisories.exists_one(a, a.id)"),
+            "expected a boolean, got a string"
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_null() {
+        use serde_json::json;
+        check(&[
+            ("missing", json!(null)),
+            ("missing == null", json!(true)),
+            ("missing.deeper.still", json!(null)),
+            ("missing[0]", json!(null)),
+            ("labels.owner", json!(null)),
+            ("labels['owner']", json!(null)),
+            ("missing < 1 || missing >= 1", json!(false)),
+            ("null <= null", json!(false)),
+            ("size(missing)", json!(0)),
+            ("missing.contains('x') == null", json!(true)),
+            ("has(missing) || has(scan.languages)", json!(false)),
+            ("has(advisories) && has(labels.team)", json!(true)),
+        ]);
+        // Null is not false, and a field of something other than a map is
+        // not null.
+        assert_eq!(err("missing && true"), "expected a boolean, got null");
+        assert_eq!(err("!missing"), "expected a boolean, got null");
+        assert_eq!(err("owner.name"), "cannot read .name of a string");
+    }
+
+    #[test]
+    fn has_takes_a_field() {
+        for expr in ["has(1)", "has('owner')", "has()", "has(owner, stars)"] {
+            assert_eq!(
+                err(expr),
+                "has() at column 1 takes a field, such as has(a.b)",
+                "{expr}"
+            );
+        }
+        assert_eq!(
+            err("stars > 1 && has(advisories[0])"),
+            "has() at column 14 takes a field, such as has(a.b)"
+        );
+    }
+
+    #[test]
+    fn out_of_range_indexes_fail() {
+        assert_eq!(err("advisories[2]"), "index 2 out of range");
+        assert_eq!(err("advisories[-1]"), "index -1 out of range");
+        assert_eq!(err("advisories[0.5]"), "index 0.5 out of range");
+        assert_eq!(err("advisories['id']"), "cannot index a list with a string");
+    }
+
+    #[test]
+    fn invalid_regular_expressions_fail() {
+        let message = err("owner.matches('(')");
+        assert!(
+            message.starts_with("invalid regular expression \"(\": "),
+            "{message}"
+        );
+        assert_eq!(err("owner.matches(1)"), "matches() takes one string");
+    }
+
+    #[test]
+    fn parse_errors_give_the_column() {
+        let cases = [
+            ("stars > )", "unexpected `)` at column 9"),
+            ("stars > $", "unexpected character `$` at column 9"),
+            (
+                "owner.'x'",
+                "expected a field name, found \"x\" at column 7",
+            ),
+            ("[1 2]", "expected `,`, found `2` at column 4"),
+            ("[1, 2", "expected `,`, found the end of the expression"),
+            ("owner ==", "unexpected end of the expression"),
+            ("1.2.3 > 1", "invalid number `1.2.3` at column 1"),
+            (
+                "owner == 'Acme",
+                "in the string at column 10: unterminated string",
+            ),
+            (r"'a\q'", r"in the string at column 1: unknown escape `\q`"),
+            // Columns count characters, not bytes.
+            ("'é' == x y", "unexpected `y` at column 10"),
+        ];
+        for (expr, want) in cases {
+            assert_eq!(format!("{:#}", Expr::parse(expr).unwrap_err()), want);
+        }
+    }
+}
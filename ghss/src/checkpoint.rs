@@ -0,0 +1,119 @@
+//! Walker checkpoints, so an interrupted long walk can resume where it
+//! stopped instead of starting over.
+//!
+//! A checkpoint is the walker's state as JSON: the nodes already expanded
+//! (with everything stages recorded on them), the queue still to expand,
+//! and how many enrichment stages have run over the whole tree. The walker
+//! rewrites it periodically while expanding and after every enrichment
+//! stage.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+
+const FORMAT_VERSION: u32 = 1;
+
+/// A node waiting to be expanded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Queued {
+    pub action: ActionRef,
+    pub depth: usize,
+    pub parent: Option<ActionRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    version: u32,
+    /// Hash of the roots, depth limit and stages; a checkpoint only resumes
+    /// the walk that wrote it.
+    pub(crate) fingerprint: String,
+    pub(crate) frontier: Vec<Queued>,
+    /// Expanded nodes, in expansion order.
+    pub(crate) expanded: Vec<AuditContext>,
+    pub(crate) root_keys: Vec<ActionRef>,
+    pub(crate) children_order: Vec<(ActionRef, Vec<ActionRef>)>,
+    /// Enrichment stages that have run over every node.
+    pub(crate) enriched_stages: usize,
+    pub(crate) complete: bool,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(fingerprint: String, roots: Vec<ActionRef>) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            fingerprint,
+            frontier: roots
+                .into_iter()
+                .map(|action| Queued {
+                    action,
+                    depth: 0,
+                    parent: None,
+                })
+                .collect(),
+            expanded: vec![],
+            root_keys: vec![],
+            children_order: vec![],
+            enriched_stages: 0,
+            complete: false,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read checkpoint {}", path.display()))?;
+        let checkpoint: Self = serde_json::from_str(&json)
+            .with_context(|| format!("invalid checkpoint {}", path.display()))?;
+        if checkpoint.version != FORMAT_VERSION {
+            bail!(
+                "checkpoint {} has format version {}, expected {FORMAT_VERSION}",
+                path.display(),
+                checkpoint.version
+            );
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write atomically: to a sibling temp file, then rename over `path`.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let partial = path.with_extension("part");
+        let json = serde_json::to_vec(self).context("failed to serialize checkpoint")?;
+        std::fs::write(&partial, json)
+            .with_context(|| format!("failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, path)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Nodes expanded so far.
+    pub fn node_count(&self) -> usize {
+        self.expanded.len()
+    }
+
+    /// True once the walk that wrote it finished.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+/// Identify a walk by its roots, depth limit and stage names.
+pub(crate) fn fingerprint(
+    roots: &[ActionRef],
+    max_depth: Option<usize>,
+    stages: &[&str],
+) -> String {
+    let mut hasher = Sha256::new();
+    for root in roots {
+        hasher.update(root.to_string());
+        hasher.update([0]);
+    }
+    hasher.update(format!("{max_depth:?}"));
+    for stage in stages {
+        hasher.update([0]);
+        hasher.update(stage);
+    }
+    hex::encode(hasher.finalize())
+}
@@ -0,0 +1,168 @@
+//! Adaptive concurrency for the walker: shrink the number of nodes in
+//! flight as the GitHub rate-limit budget drains, and grow it back while
+//! there is plenty left, so long runs slow down instead of hitting the
+//! limit halfway through.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::info;
+
+use crate::github::{GitHubClient, RateLimit};
+
+/// Headroom at or above which the full concurrency is allowed. Below it
+/// the limit scales down linearly, to one at zero headroom.
+const FULL_HEADROOM: f64 = 0.5;
+
+/// Concurrency limit between 1 and `max`, driven by the rate-limit headers
+/// the client has seen.
+///
+/// The limit drops straight to its target when headroom shrinks and rises
+/// by one per observation when it recovers.
+pub struct AdaptiveConcurrency {
+    client: GitHubClient,
+    semaphore: Arc<Semaphore>,
+    max: usize,
+    state: Mutex<State>,
+}
+
+struct State {
+    limit: usize,
+    /// Permits to retire as they're released, when shrinking couldn't take
+    /// them from the semaphore because they were in use.
+    debt: usize,
+}
+
+/// Held while one node is processed. Dropping it returns the slot, unless
+/// the limit has shrunk since it was acquired.
+pub struct AdaptivePermit {
+    permit: Option<OwnedSemaphorePermit>,
+    owner: Arc<AdaptiveConcurrency>,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(client: GitHubClient, max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            client,
+            semaphore: Arc::new(Semaphore::new(max)),
+            max,
+            state: Mutex::new(State {
+                limit: max,
+                debt: 0,
+            }),
+        }
+    }
+
+    /// The current concurrency limit.
+    pub fn limit(&self) -> usize {
+        self.state.lock().expect("concurrency lock poisoned").limit
+    }
+
+    pub async fn acquire(self: &Arc<Self>) -> AdaptivePermit {
+        let permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .expect("semaphore closed unexpectedly");
+        AdaptivePermit {
+            permit: Some(permit),
+            owner: Arc::clone(self),
+        }
+    }
+
+    /// Adjust the limit to the client's latest rate-limit headers.
+    pub fn observe(&self) {
+        if let Some(rate_limit) = self.client.rate_limit() {
+            self.adjust(&rate_limit);
+        }
+    }
+
+    fn adjust(&self, rate_limit: &RateLimit) {
+        let target = target_limit(self.max, rate_limit.headroom());
+        let mut state = self.state.lock().expect("concurrency lock poisoned");
+        let previous = state.limit;
+        if target < state.limit {
+            let excess = state.limit - target;
+            let forgotten = self.semaphore.forget_permits(excess);
+            state.debt += excess - forgotten;
+            state.limit = target;
+        } else if target > state.limit {
+            if state.debt > 0 {
+                state.debt -= 1;
+            } else {
+                self.semaphore.add_permits(1);
+            }
+            state.limit += 1;
+        }
+        if state.limit != previous {
+            info!(
+                limit = state.limit,
+                remaining = rate_limit.remaining,
+                resource = %rate_limit.resource,
+                "adjusted concurrency to rate-limit headroom"
+            );
+        }
+    }
+}
+
+impl Drop for AdaptivePermit {
+    fn drop(&mut self) {
+        let Some(permit) = self.permit.take() else {
+            return;
+        };
+        let mut state = self.owner.state.lock().expect("concurrency lock poisoned");
+        if state.debt > 0 {
+            state.debt -= 1;
+            permit.forget();
+        }
+    }
+}
+
+fn target_limit(max: usize, headroom: f64) -> usize {
+    let scaled = (max as f64 * headroom / FULL_HEADROOM).ceil() as usize;
+    scaled.clamp(1, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limit(remaining: u64) -> RateLimit {
+        RateLimit {
+            resource: "core".to_string(),
+            limit: 1000,
+            remaining,
+        }
+    }
+
+    #[test]
+    fn target_scales_below_half_headroom() {
+        assert_eq!(target_limit(10, 1.0), 10);
+        assert_eq!(target_limit(10, 0.5), 10);
+        assert_eq!(target_limit(10, 0.25), 5);
+        assert_eq!(target_limit(10, 0.01), 1);
+        assert_eq!(target_limit(10, 0.0), 1);
+    }
+
+    #[tokio::test]
+    async fn shrinks_at_once_and_recovers_one_step_at_a_time() {
+        let limiter = Arc::new(AdaptiveConcurrency::new(GitHubClient::new(None), 4));
+        let first = limiter.acquire().await;
+        let second = limiter.acquire().await;
+
+        limiter.adjust(&rate_limit(100));
+        assert_eq!(limiter.limit(), 1);
+        // Only two idle permits could be forgotten; the third is retired
+        // when the first in-flight permit comes back.
+        assert_eq!(limiter.semaphore.available_permits(), 0);
+        drop(first);
+        assert_eq!(limiter.semaphore.available_permits(), 0);
+        drop(second);
+        assert_eq!(limiter.semaphore.available_permits(), 1);
+
+        limiter.adjust(&rate_limit(900));
+        limiter.adjust(&rate_limit(900));
+        assert_eq!(limiter.limit(), 3);
+        assert_eq!(limiter.semaphore.available_permits(), 3);
+    }
+}
@@ -0,0 +1,741 @@
+//! The config file, so a team can commit shared defaults and policy
+//! instead of repeating long command lines.
+//!
+//! Without `--config`, every directory from the scanned file's up to its
+//! repository root may hold one of [`FILE_NAMES`] (see [`discover_upward`]);
+//! each is layered over the ones above it, so a monorepo directory can
+//! adjust the root config. Only when there is none is the first found in the
+//! current directory, then `$XDG_CONFIG_HOME/ghss` (`~/.config/ghss`), used.
+//! A file is TOML, or YAML when its name ends in `.yml` or `.yaml`, with the
+//! same keys:
+//!
+//! ```toml
+//! [scan]
+//! provider = "osv"                # as --provider
+//! depth = 2                       # as --depth: a number or "unlimited"
+//! concurrency = 4                 # as --concurrency
+//! format = "json"                 # as --format
+//!
+//! [github]
+//! token_env = "ACME_GITHUB_TOKEN" # env var to read the token from
+//!
+//! [urls]                          # as the GHSS_*_URL variables
+//! github_api = "https://github.example.com/api/v3"
+//!
+//! [policy]
+//! fail_on_severity = "high"       # as --fail-on-severity
+//! fail_on_errors = true           # as --fail-on errors
+//! require_pinned = true           # as --require-pinned
+//! trusted_owners = ["acme"]       # as --trusted-owner
+//! forbid = ["tj-actions/*", { action = "evil-org", reason = "compromised" }]
+//! disable = ["remote-script"]     # built-in rules to turn off
+//!
+//! [[policy.checks]]               # see crate::policy
+//! id = "unscored-action"
+//! when = 'depth == 0 && !has(scorecard)'
+//! decision = "warn"
+//!
+//! [profiles.deep.scan]            # layered on with --profile deep
+//! depth = "unlimited"
+//! ```
+//!
+//! `[github]` and `[urls]` decide what program runs and where the token is
+//! sent, so they are only read from `--config` or the user config
+//! directory; a file found in the scanned repository may have been written
+//! by anyone who can open a pull request (see [`Config::load_layers`]).
+//!
+//! `policy.max_depth` is the older spelling of `scan.depth`. A flag on the
+//! command line or its environment variable wins over the file; lists are
+//! combined.
+
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::advisory::Severity;
+use crate::depth::DepthLimit;
+use crate::finding;
+use crate::policy::{self, Check};
+use crate::rules::Forbidden;
+
+/// Config file names looked for in each directory, in order.
+pub const FILE_NAMES: &[&str] = &["ghss.toml", ".ghss.toml", ".ghss.yml", ".ghss.yaml"];
+
+/// The commented config `ghss init` writes.
+pub const STARTER: &str = include_str!("../data/starter-config.toml");
+
+/// Config file names in the user config directory.
+const USER_FILE_NAMES: &[&str] = &["config.toml", "config.yml", "config.yaml"];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub scan: Scan,
+    #[serde(default)]
+    pub github: GitHub,
+    #[serde(default)]
+    pub urls: Urls,
+    #[serde(default)]
+    pub policy: Policy,
+    /// Named bundles of scan settings and policy, chosen with `--profile`.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// A `[profiles.<name>]` section, layered over the top-level `[scan]` and
+/// `[policy]` by [`Config::with_profile`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    pub scan: Scan,
+    #[serde(default)]
+    pub policy: Policy,
+}
+
+impl Profile {
+    fn overlay(&mut self, other: Profile) {
+        self.scan.overlay(other.scan);
+        self.policy.overlay(other.policy);
+    }
+}
+
+/// The `[scan]` section: defaults for how actions are audited and
+/// reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scan {
+    /// Advisory provider name, as `--provider` takes it.
+    pub provider: Option<String>,
+    pub depth: Option<DepthLimit>,
+    /// Actions audited at once.
+    pub concurrency: Option<usize>,
+    /// Output format name, as `--format` takes it.
+    pub format: Option<String>,
+}
+
+impl Scan {
+    /// Settings `other` has replace these.
+    fn overlay(&mut self, other: Scan) {
+        self.provider = other.provider.or(self.provider.take());
+        self.depth = other.depth.or(self.depth.take());
+        self.concurrency = other.concurrency.or(self.concurrency);
+        self.format = other.format.or(self.format.take());
+    }
+}
+
+/// The `[github]` section: where the token comes from when neither
+/// `--github-token` nor `GITHUB_TOKEN` is set. The token itself never
+/// belongs in a committed file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitHub {
+    /// Environment variable holding the token.
+    pub token_env: Option<String>,
+    /// Command that prints the token, e.g. `gh auth token`. Split on
+    /// whitespace and run without a shell.
+    pub token_command: Option<String>,
+}
+
+impl GitHub {
+    /// A token source in `other` replaces this one.
+    fn overlay(&mut self, other: GitHub) {
+        if other.token_env.is_some() || other.token_command.is_some() {
+            *self = other;
+        }
+    }
+
+    /// The token from `token_env` or `token_command`, if either is set.
+    pub fn token(&self) -> Result<Option<String>> {
+        if let Some(var) = &self.token_env {
+            let token = std::env::var(var)
+                .with_context(|| format!("github.token_env: env var {var} is not set"))?;
+            return Ok(Some(token));
+        }
+        let Some(command) = &self.token_command else {
+            return Ok(None);
+        };
+        let mut words = command.split_whitespace();
+        let program = words
+            .next()
+            .context("github.token_command: empty command")?;
+        let output = std::process::Command::new(program)
+            .args(words)
+            .stderr(std::process::Stdio::inherit())
+            .output()
+            .with_context(|| format!("github.token_command: failed to run {command:?}"))?;
+        if !output.status.success() {
+            bail!(
+                "github.token_command: {command:?} exited with {}",
+                output.status
+            );
+        }
+        let token = String::from_utf8(output.stdout)
+            .context("github.token_command: output is not UTF-8")?
+            .trim()
+            .to_string();
+        if token.is_empty() {
+            bail!("github.token_command: {command:?} printed no token");
+        }
+        Ok(Some(token))
+    }
+}
+
+/// The `[urls]` section: base URLs of the services ghss queries, for GitHub
+/// Enterprise Server, mirrors and proxies. Each one stands in for the
+/// environment variable that [`Urls::vars`] names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Urls {
+    pub github_api: Option<String>,
+    pub github_raw: Option<String>,
+    pub osv: Option<String>,
+    pub osv_dump: Option<String>,
+    pub depsdev: Option<String>,
+    pub epss: Option<String>,
+    pub scorecard: Option<String>,
+    pub snyk: Option<String>,
+    pub npm_registry: Option<String>,
+}
+
+impl Urls {
+    /// URLs `other` sets replace these.
+    fn overlay(&mut self, other: Urls) {
+        self.github_api = other.github_api.or(self.github_api.take());
+        self.github_raw = other.github_raw.or(self.github_raw.take());
+        self.osv = other.osv.or(self.osv.take());
+        self.osv_dump = other.osv_dump.or(self.osv_dump.take());
+        self.depsdev = other.depsdev.or(self.depsdev.take());
+        self.epss = other.epss.or(self.epss.take());
+        self.scorecard = other.scorecard.or(self.scorecard.take());
+        self.snyk = other.snyk.or(self.snyk.take());
+        self.npm_registry = other.npm_registry.or(self.npm_registry.take());
+    }
+
+    /// Each URL that is set, with the environment variable it stands in
+    /// for.
+    pub fn vars(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("GHSS_API_BASE_URL", &self.github_api),
+            ("GHSS_RAW_BASE_URL", &self.github_raw),
+            ("GHSS_OSV_BASE_URL", &self.osv),
+            ("GHSS_OSV_DUMP_URL", &self.osv_dump),
+            ("GHSS_DEPSDEV_BASE_URL", &self.depsdev),
+            ("GHSS_EPSS_BASE_URL", &self.epss),
+            ("GHSS_SCORECARD_BASE_URL", &self.scorecard),
+            ("GHSS_SNYK_BASE_URL", &self.snyk),
+            ("GHSS_NPM_REGISTRY_URL", &self.npm_registry),
+        ]
+        .into_iter()
+        .filter_map(|(var, url)| Some((var, url.as_deref()?)))
+        .collect()
+    }
+}
+
+/// The `[policy]` section. Every setting is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// Fail on advisories and findings at or above this severity.
+    pub fail_on_severity: Option<Severity>,
+    /// Fail when a stage leaves results incomplete.
+    #[serde(default)]
+    pub fail_on_errors: bool,
+    /// Fail on actions not pinned to a commit SHA.
+    #[serde(default)]
+    pub require_pinned: bool,
+    #[serde(default)]
+    pub trusted_owners: Vec<String>,
+    /// Actions and owners no workflow may use, as in the rules file's
+    /// `forbid:` list.
+    #[serde(default)]
+    pub forbid: Vec<Forbidden>,
+    /// The older spelling of `scan.depth`, moved there when the file is
+    /// loaded.
+    pub max_depth: Option<DepthLimit>,
+    /// Ids of built-in rules to turn off.
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// Expressions deciding whether each action warns or fails the run.
+    #[serde(default)]
+    pub checks: Vec<Check>,
+}
+
+impl Policy {
+    /// Thresholds `other` sets replace these, switches it turns on stay
+    /// on, and lists are combined. A check of `other` replaces the one
+    /// with its id.
+    fn overlay(&mut self, other: Policy) {
+        self.fail_on_severity = other.fail_on_severity.or(self.fail_on_severity);
+        self.fail_on_errors |= other.fail_on_errors;
+        self.require_pinned |= other.require_pinned;
+        self.trusted_owners.extend(other.trusted_owners);
+        self.forbid.extend(other.forbid);
+        self.disable.extend(other.disable);
+        self.checks
+            .retain(|check| !other.checks.iter().any(|c| c.id == check.id));
+        self.checks.extend(other.checks);
+    }
+}
+
+impl Config {
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let config: Self = toml::from_str(toml).context("invalid config file")?;
+        config.validate()
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        // An empty YAML document is null rather than an empty mapping.
+        if yaml.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_yaml::from_str(yaml).context("invalid config file")?;
+        config.validate()
+    }
+
+    /// Read the file at `path`, as YAML when it is named `*.yml` or
+    /// `*.yaml` and as TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config: {}", path.display()))?;
+        let yaml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"));
+        let config = if yaml {
+            Self::from_yaml(&text)
+        } else {
+            Self::from_toml(&text)
+        };
+        config.with_context(|| format!("{}", path.display()))
+    }
+
+    /// Load each of the discovered `paths`, layered over the ones before
+    /// it. A file outside the user config directory may not set
+    /// `[github]` or `[urls]`.
+    pub fn load_layers(paths: &[PathBuf]) -> Result<Self> {
+        let user_dir = user_config_dir();
+        let mut config = Self::default();
+        for path in paths {
+            let layer = Self::load(path)?;
+            if !user_dir.as_ref().is_some_and(|dir| path.starts_with(dir)) {
+                layer
+                    .check_untrusted()
+                    .with_context(|| format!("{}", path.display()))?;
+            }
+            config.overlay(layer);
+        }
+        Ok(config)
+    }
+
+    /// Fail if a file found in the scanned repository sets a token source
+    /// or a URL, either of which would let it read the GitHub token.
+    fn check_untrusted(&self) -> Result<()> {
+        let mut keys = Vec::new();
+        if self.github.token_env.is_some() {
+            keys.push("github.token_env");
+        }
+        if self.github.token_command.is_some() {
+            keys.push("github.token_command");
+        }
+        if self.urls != Urls::default() {
+            keys.push("[urls]");
+        }
+        if !keys.is_empty() {
+            bail!(
+                "{} can only be set in a file given with --config or in the user config \
+                 directory, not in a config file found in the scanned repository",
+                keys.join(" and ")
+            );
+        }
+        Ok(())
+    }
+
+    fn overlay(&mut self, other: Config) {
+        self.scan.overlay(other.scan);
+        self.github.overlay(other.github);
+        self.urls.overlay(other.urls);
+        self.policy.overlay(other.policy);
+        for (name, profile) in other.profiles {
+            match self.profiles.entry(name) {
+                Entry::Occupied(mut entry) => entry.get_mut().overlay(profile),
+                Entry::Vacant(entry) => {
+                    entry.insert(profile);
+                }
+            }
+        }
+    }
+
+    /// Layer the profile `name` over the top-level `[scan]` and `[policy]`.
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        let Some(profile) = self.profiles.remove(name) else {
+            let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            if names.is_empty() {
+                bail!("profile {name} is not defined: the config has no profiles");
+            }
+            bail!(
+                "profile {name} is not defined (profiles: {})",
+                names.join(", ")
+            );
+        };
+        self.scan.overlay(profile.scan);
+        self.policy.overlay(profile.policy);
+        Ok(self)
+    }
+
+    fn validate(mut self) -> Result<Self> {
+        if self.github.token_env.is_some() && self.github.token_command.is_some() {
+            bail!("github: set token_env or token_command, not both");
+        }
+        validate_sections(&mut self.scan, &mut self.policy)?;
+        for (name, profile) in &mut self.profiles {
+            validate_sections(&mut profile.scan, &mut profile.policy)
+                .with_context(|| format!("profiles.{name}"))?;
+        }
+        Ok(self)
+    }
+}
+
+/// Check a `[scan]` and `[policy]` pair, and move `policy.max_depth` to
+/// `scan.depth` so a profile's depth replaces the top-level one whichever
+/// spelling each uses.
+fn validate_sections(scan: &mut Scan, policy: &mut Policy) -> Result<()> {
+    if scan.concurrency == Some(0) {
+        bail!("scan.concurrency: must be at least 1");
+    }
+    if scan.depth.is_some() && policy.max_depth.is_some() {
+        bail!("scan.depth and policy.max_depth both set the depth; keep one");
+    }
+    if let Some(depth) = policy.max_depth.take() {
+        scan.depth = Some(depth);
+    }
+    if policy.trusted_owners.iter().any(|o| o.trim().is_empty()) {
+        bail!("policy.trusted_owners: owner must not be empty");
+    }
+    if policy.forbid.iter().any(|f| f.pattern.trim().is_empty()) {
+        bail!("policy.forbid: pattern must not be empty");
+    }
+    for id in &policy.disable {
+        if finding::rule(id).is_none() {
+            bail!("policy.disable: {id} is not a built-in rule");
+        }
+    }
+    policy::validate(&policy.checks).context("policy.checks")
+}
+
+/// The first config file in `dirs`, trying each of [`FILE_NAMES`] in a
+/// directory before moving to the next, then the user config directory.
+pub fn discover(dirs: &[&Path]) -> Option<PathBuf> {
+    let local = dirs
+        .iter()
+        .flat_map(|dir| FILE_NAMES.iter().map(move |name| dir.join(name)));
+    let user = user_config_dir()
+        .into_iter()
+        .flat_map(|dir| USER_FILE_NAMES.iter().map(move |name| dir.join(name)));
+    local.chain(user).find(|path| path.is_file())
+}
+
+/// The config file in each directory from `dir` up to the repository root,
+/// the root's first so each can be layered over the ones above it. The root
+/// is the first directory with a `.git`, or `root` (the checkout a
+/// `.github/workflows` file is in) when that comes first; with neither, only
+/// `dir` is searched.
+pub fn discover_upward(dir: &Path, root: Option<&Path>) -> Vec<PathBuf> {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let root = root.and_then(|root| root.canonicalize().ok());
+    let top = dir
+        .ancestors()
+        .find(|d| d.join(".git").exists() || root.as_deref() == Some(*d))
+        .unwrap_or(&dir);
+    let mut found: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|d| d.starts_with(top))
+        .filter_map(|d| {
+            FILE_NAMES
+                .iter()
+                .map(|name| d.join(name))
+                .find(|p| p.is_file())
+        })
+        .collect();
+    found.reverse();
+    found
+}
+
+/// `$XDG_CONFIG_HOME/ghss`, else `~/.config/ghss`.
+fn user_config_dir() -> Option<PathBuf> {
+    let config = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| Path::new(&home).join(".config")))
+        .ok()?;
+    Some(config.join("ghss"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_policy() {
+        let config = Config::from_toml(
+            r#"
+[policy]
+fail_on_severity = "high"
+fail_on_errors = true
+require_pinned = true
+trusted_owners = ["acme"]
+forbid = ["tj-actions/*", { action = "evil-org", reason = "compromised" }]
+max_depth = "unlimited"
+disable = ["remote-script"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.policy,
+            Policy {
+                fail_on_severity: Some(Severity::High),
+                fail_on_errors: true,
+                require_pinned: true,
+                trusted_owners: vec!["acme".into()],
+                forbid: vec![
+                    Forbidden::from("tj-actions/*"),
+                    Forbidden {
+                        pattern: "evil-org".into(),
+                        reason: Some("compromised".into()),
+                    },
+                ],
+                max_depth: None,
+                disable: vec!["remote-script".into()],
+                checks: vec![],
+            }
+        );
+        assert_eq!(config.scan.depth, Some(DepthLimit::Unlimited));
+
+        let config = Config::from_toml("[policy]\nmax_depth = 2\n").unwrap();
+        assert_eq!(config.scan.depth, Some(DepthLimit::Bounded(2)));
+        assert_eq!(Config::from_toml("").unwrap().policy, Policy::default());
+    }
+
+    #[test]
+    fn parses_defaults_as_toml_or_yaml() {
+        let toml = Config::from_toml(
+            r#"
+[scan]
+provider = "osv"
+depth = "unlimited"
+concurrency = 4
+format = "json"
+
+[github]
+token_env = "ACME_TOKEN"
+
+[urls]
+github_api = "https://github.example.com/api/v3"
+npm_registry = "https://npm.example.com"
+"#,
+        )
+        .unwrap();
+        let yaml = Config::from_yaml(
+            "scan:\n  provider: osv\n  depth: unlimited\n  concurrency: 4\n  format: json\ngithub:\n  token_env: ACME_TOKEN\nurls:\n  github_api: https://github.example.com/api/v3\n  npm_registry: https://npm.example.com\n",
+        )
+        .unwrap();
+        for config in [toml, yaml] {
+            assert_eq!(
+                config.scan,
+                Scan {
+                    provider: Some("osv".into()),
+                    depth: Some(DepthLimit::Unlimited),
+                    concurrency: Some(4),
+                    format: Some("json".into()),
+                }
+            );
+            assert_eq!(config.github.token_env.as_deref(), Some("ACME_TOKEN"));
+            assert_eq!(
+                config.urls.vars(),
+                [
+                    ("GHSS_API_BASE_URL", "https://github.example.com/api/v3"),
+                    ("GHSS_NPM_REGISTRY_URL", "https://npm.example.com"),
+                ]
+            );
+        }
+        assert_eq!(Config::from_yaml("").unwrap().scan, Scan::default());
+    }
+
+    #[test]
+    fn profiles_layer_over_the_top_level_sections() {
+        let config = Config::from_toml(
+            r#"
+[scan]
+provider = "osv"
+depth = 1
+
+[policy]
+fail_on_severity = "critical"
+trusted_owners = ["acme"]
+
+[profiles.quick.scan]
+depth = 0
+
+[profiles.ci.policy]
+fail_on_severity = "high"
+fail_on_errors = true
+trusted_owners = ["acme-labs"]
+max_depth = "unlimited"
+"#,
+        )
+        .unwrap();
+
+        let ci = config.clone().with_profile("ci").unwrap();
+        assert_eq!(ci.scan.provider.as_deref(), Some("osv"));
+        assert_eq!(ci.scan.depth, Some(DepthLimit::Unlimited));
+        assert_eq!(ci.policy.fail_on_severity, Some(Severity::High));
+        assert!(ci.policy.fail_on_errors);
+        assert_eq!(ci.policy.trusted_owners, ["acme", "acme-labs"]);
+
+        let quick = config.clone().with_profile("quick").unwrap();
+        assert_eq!(quick.scan.depth, Some(DepthLimit::Bounded(0)));
+        assert_eq!(quick.policy.fail_on_severity, Some(Severity::Critical));
+
+        assert_eq!(
+            config.with_profile("deep").unwrap_err().to_string(),
+            "profile deep is not defined (profiles: ci, quick)"
+        );
+        let err = Config::from_toml("[profiles.ci.scan]\nconcurrency = 0\n").unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "profiles.ci: scan.concurrency: must be at least 1"
+        );
+    }
+
+    #[test]
+    fn nested_configs_layer_over_the_root() {
+        let root = std::env::temp_dir().join(format!("ghss-config-up-{}", std::process::id()));
+        let service = root.join("services/api");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(&service).unwrap();
+        std::fs::write(
+            root.join("ghss.toml"),
+            "[scan]\nprovider = \"osv\"\ndepth = 1\n[policy]\ntrusted_owners = [\"acme\"]\n\
+             [[policy.checks]]\nid = \"mine\"\nwhen = \"false\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            service.join(".ghss.yml"),
+            "scan:\n  depth: 3\npolicy:\n  trusted_owners: [acme-api]\n  checks:\n    - id: mine\n      when: \"true\"\n",
+        )
+        .unwrap();
+
+        let paths = discover_upward(&service, None);
+        let from_root = discover_upward(&root, None);
+        let config = Config::load_layers(&paths).unwrap();
+        let canonical = root.canonicalize().unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let root = canonical;
+        assert_eq!(
+            paths,
+            [root.join("ghss.toml"), root.join("services/api/.ghss.yml")]
+        );
+        assert_eq!(from_root, [root.join("ghss.toml")]);
+        assert_eq!(config.scan.provider.as_deref(), Some("osv"));
+        assert_eq!(config.scan.depth, Some(DepthLimit::Bounded(3)));
+        assert_eq!(config.policy.trusted_owners, ["acme", "acme-api"]);
+        assert_eq!(config.policy.checks.len(), 1);
+        assert_eq!(config.policy.checks[0].when.to_string(), "true");
+    }
+
+    #[test]
+    fn repository_configs_cannot_choose_the_token_or_urls() {
+        let root = std::env::temp_dir().join(format!("ghss-config-trust-{}", std::process::id()));
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        let path = root.join(".ghss.toml");
+        let layers = |toml: &str| {
+            std::fs::write(&path, toml).unwrap();
+            Config::load_layers(std::slice::from_ref(&path)).map_err(|e| format!("{e:#}"))
+        };
+
+        let command = layers("[github]\ntoken_command = \"curl https://evil.example\"\n");
+        let urls = layers("[urls]\ngithub_api = \"https://evil.example\"\n");
+        let both = layers("[github]\ntoken_env = \"X\"\n[urls]\nosv = \"https://evil.example\"\n");
+        let explicit = Config::load(&path);
+        let policy = layers("[policy]\nrequire_pinned = true\n");
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let suffix = "can only be set in a file given with --config or in the user config \
+                      directory, not in a config file found in the scanned repository";
+        let path = path.display();
+        assert_eq!(
+            command.unwrap_err(),
+            format!("{path}: github.token_command {suffix}")
+        );
+        assert_eq!(urls.unwrap_err(), format!("{path}: [urls] {suffix}"));
+        assert_eq!(
+            both.unwrap_err(),
+            format!("{path}: github.token_env and [urls] {suffix}")
+        );
+        assert!(policy.unwrap().policy.require_pinned);
+        // A file named with --config is the user's own.
+        assert_eq!(
+            explicit.unwrap().urls.osv.as_deref(),
+            Some("https://evil.example")
+        );
+    }
+
+    #[test]
+    fn starter_config_is_valid() {
+        let config = Config::from_toml(STARTER).unwrap();
+        assert_eq!(config.scan.depth, Some(DepthLimit::Bounded(1)));
+        assert_eq!(config.policy.fail_on_severity, Some(Severity::High));
+    }
+
+    #[test]
+    fn discovers_the_first_config_file() {
+        let root = std::env::temp_dir().join(format!("ghss-config-{}", std::process::id()));
+        let (cwd, repo) = (root.join("cwd"), root.join("repo"));
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join(".ghss.yml"), "").unwrap();
+        std::fs::write(repo.join("ghss.toml"), "").unwrap();
+
+        let found = discover(&[&cwd, &repo]);
+        std::fs::write(cwd.join(".ghss.yaml"), "").unwrap();
+        let found_in_cwd = discover(&[&cwd, &repo]);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found, Some(repo.join("ghss.toml")));
+        assert_eq!(found_in_cwd, Some(cwd.join(".ghss.yaml")));
+    }
+
+    #[test]
+    fn rejects_invalid_policy() {
+        let err = |toml: &str| format!("{:#}", Config::from_toml(toml).unwrap_err());
+        assert_eq!(
+            err("[policy]\ndisable = [\"no-such-rule\"]\n"),
+            "policy.disable: no-such-rule is not a built-in rule"
+        );
+        assert_eq!(
+            err("[policy]\nforbid = [\" \"]\n"),
+            "policy.forbid: pattern must not be empty"
+        );
+        assert!(err("[policy]\nfail_on = \"high\"\n").contains("unknown field `fail_on`"));
+        assert!(err("[policy]\nmax_depth = \"deep\"\n").contains("invalid depth limit"));
+        assert!(
+            err("[[policy.checks]]\nid = \"x\"\nwhen = \"size(advisories) >\"\n")
+                .contains("\"size(advisories) >\": unexpected end of the expression")
+        );
+        assert_eq!(
+            err("[scan]\ndepth = 1\n[policy]\nmax_depth = 2\n"),
+            "scan.depth and policy.max_depth both set the depth; keep one"
+        );
+        assert_eq!(
+            err("[scan]\nconcurrency = 0\n"),
+            "scan.concurrency: must be at least 1"
+        );
+        assert_eq!(
+            err("[[policy.checks]]\nid = \"branch-ref\"\nwhen = \"true\"\n"),
+            "policy.checks: check branch-ref: id is already used by a built-in rule"
+        );
+    }
+}
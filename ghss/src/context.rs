@@ -1,9 +1,25 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
 use crate::action_ref::ActionRef;
 use crate::advisory::Advisory;
+use crate::finding::Finding;
+use crate::providers::depsdev::ProjectInfo;
+use crate::scorecard::Scorecard;
 use crate::stages::ScanResult;
 use crate::stages::dependency::DependencyReport;
+use crate::stages::hygiene::RepoHygiene;
+use crate::stages::protection::TagProtection;
+use crate::stages::provenance::Provenance;
+use crate::stages::recommend::RecommendedRef;
+use crate::stages::releases::ReleaseLag;
+use crate::stages::repository::RepoMetadata;
+use crate::stages::reputation::Reputation;
+use crate::stages::signature::Signatures;
+use crate::stages::updates::UpdateHygiene;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditContext {
     pub action: ActionRef,
     pub depth: usize,
@@ -14,20 +30,71 @@ pub struct AuditContext {
     pub advisories: Vec<Advisory>,
     pub scan: Option<ScanResult>,
     pub dependencies: Vec<DependencyReport>,
+    pub update_hygiene: Option<UpdateHygiene>,
+    pub project: Option<ProjectInfo>,
+    pub releases: Option<ReleaseLag>,
+    pub recommended_ref: Option<RecommendedRef>,
+    pub repository: Option<RepoMetadata>,
+    pub reputation: Option<Reputation>,
+    pub scorecard: Option<Scorecard>,
+    pub provenance: Option<Provenance>,
+    pub signatures: Option<Signatures>,
+    /// SPDX identifier of the action repository's license.
+    pub license: Option<String>,
+    pub tag_protection: Option<TagProtection>,
+    pub repo_hygiene: Option<RepoHygiene>,
+    /// `runs.using` from the action's `action.yml`, e.g. `node20`.
+    pub runtime: Option<String>,
+    /// Problems found while auditing this action, e.g. in how it is called.
+    pub findings: Vec<Finding>,
     pub errors: Vec<StageError>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StageError {
-    pub stage: &'static str,
+    /// Borrowed from the stage's name, or owned when read back from a
+    /// checkpoint.
+    pub stage: Cow<'static, str>,
     pub message: String,
 }
 
 impl AuditContext {
     pub fn record_error(&mut self, stage: &'static str, error: impl std::fmt::Display) {
         self.errors.push(StageError {
-            stage,
+            stage: Cow::Borrowed(stage),
             message: error.to_string(),
         });
     }
 }
+
+#[cfg(test)]
+impl AuditContext {
+    /// A root context for `action` with no stage results yet.
+    pub(crate) fn for_test(action: ActionRef) -> Self {
+        Self {
+            action,
+            depth: 0,
+            parent: None,
+            children: vec![],
+            resolved_ref: None,
+            advisories: vec![],
+            scan: None,
+            dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
+            errors: vec![],
+        }
+    }
+}
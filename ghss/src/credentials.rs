@@ -0,0 +1,126 @@
+//! `actions/checkout` credentials left behind for pull request code.
+//!
+//! Unless `persist-credentials: false` is set, checkout writes the job's
+//! token into `.git/config`, where every later step can read it. That
+//! matters once the job goes on to run code from a pull request: the
+//! default merge ref of a `pull_request` workflow, or the pull request head
+//! checked out by a workflow an untrusted event triggers.
+
+use crate::cache::PR_HEAD_REFS;
+use crate::finding::{Finding, PERSISTED_CREDENTIALS};
+use crate::location::LocationIndex;
+use crate::runners::PULL_REQUEST_EVENTS;
+use crate::workflow::{Step, UNTRUSTED_EVENTS, Workflow};
+
+/// Whether a checkout `step` fetches the pull request's code, given whether
+/// the workflow's default ref is the pull request's merge commit.
+fn checks_out_pull_request(step: &Step, merge_ref: bool, untrusted: bool) -> bool {
+    let input = |key: &str| {
+        step.with
+            .as_ref()
+            .and_then(|w| w.get(key))
+            .and_then(|v| v.as_str())
+    };
+    let head = ["ref", "repository"]
+        .iter()
+        .any(|key| input(key).is_some_and(|v| PR_HEAD_REFS.iter().any(|p| v.contains(p))));
+    (untrusted && head) || (merge_ref && (head || input("ref").is_none()))
+}
+
+/// Raise [`PERSISTED_CREDENTIALS`] for each `actions/checkout` that keeps its
+/// credentials while checking out pull request code that later steps run,
+/// located at the step's `uses:`.
+pub fn check_persisted_credentials(yaml: &str, file: &str) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    let events = workflow.events();
+    let merge_ref = events
+        .iter()
+        .any(|e| e != "pull_request_target" && PULL_REQUEST_EVENTS.contains(&e.as_str()));
+    let untrusted = events
+        .iter()
+        .any(|e| UNTRUSTED_EVENTS.contains(&e.as_str()));
+    if !merge_ref && !untrusted {
+        return Ok(Vec::new());
+    }
+    let mut locations = LocationIndex::for_key(yaml, "jobs", "uses");
+
+    let mut findings = Vec::new();
+    for (job, definition) in workflow.jobs() {
+        if let Some(uses) = &definition.uses {
+            locations.take(uses);
+        }
+        let steps = definition.steps.unwrap_or_default();
+        for (i, step) in steps.iter().enumerate() {
+            let location = step.uses.as_ref().and_then(|uses| locations.take(uses));
+            if !step.uses_action("actions/checkout")
+                || !checks_out_pull_request(step, merge_ref, untrusted)
+            {
+                continue;
+            }
+            let persisted = step
+                .with
+                .as_ref()
+                .and_then(|w| w.get("persist-credentials"))
+                .is_none_or(|v| v.as_bool() != Some(false) && v.as_str() != Some("false"));
+            let runners = steps[i + 1..].iter().filter(|s| s.runs_code()).count();
+            if !persisted || runners == 0 {
+                continue;
+            }
+            let later = match runners {
+                1 => "the step after it runs".to_string(),
+                n => format!("{n} steps after it run"),
+            };
+            findings.push(
+                Finding::new(
+                    &PERSISTED_CREDENTIALS,
+                    format!(
+                        "actions/checkout in job `{job}` leaves its token in .git/config and {later} the pull request's code; set `persist-credentials: false`"
+                    ),
+                )
+                .with_location(location.map(|l| l.with_file(file))),
+            );
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(yaml: &str) -> Vec<String> {
+        check_persisted_credentials(yaml, "ci.yml")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn flags_checkouts_of_pull_requests_that_keep_credentials() {
+        let yaml = "on: pull_request\njobs:\n  test:\n    steps:\n      - uses: actions/checkout@v4\n      - run: npm ci\n      - run: npm test\n  lint:\n    steps:\n      - uses: actions/checkout@v4\n        with:\n          persist-credentials: false\n      - run: npm run lint\n  docs:\n    steps:\n      - uses: actions/checkout@v4\n        with:\n          ref: main\n      - run: make docs\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "persist-credentials (medium): actions/checkout in job `test` leaves its token in .git/config and 2 steps after it run the pull request's code; set `persist-credentials: false`\n    at ci.yml:5:15"
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_untrusted_events_only_when_they_check_out_the_head() {
+        let yaml = "on: pull_request_target\njobs:\n  label:\n    steps:\n      - uses: actions/checkout@v4\n      - run: ./label.sh\n  test:\n    steps:\n      - uses: actions/checkout@v4\n        with:\n          ref: ${{ github.event.pull_request.head.sha }}\n      - uses: ./.github/actions/test\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "persist-credentials (medium): actions/checkout in job `test` leaves its token in .git/config and the step after it runs the pull request's code; set `persist-credentials: false`\n    at ci.yml:9:15"
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_trusted_events() {
+        let yaml = "on: push\njobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n      - run: make\n";
+        assert!(check(yaml).is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! CVSS base scores.
+//!
+//! GHSA reports a score alongside each vector; OSV only gives the vector.
+//! For CVSS v3 vectors the base score is computed here (CVSS v3.1
+//! specification, section 7); v4 vectors are kept without a score.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A CVSS base score between 0.0 and 10.0.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct CvssScore(f64);
+
+// Scores are range-checked on construction, so never NaN.
+impl Eq for CvssScore {}
+
+impl CvssScore {
+    /// `None` unless `score` is within 0.0–10.0.
+    pub fn new(score: f64) -> Option<Self> {
+        (0.0..=10.0).contains(&score).then_some(Self(score))
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for CvssScore {
+    type Error = String;
+
+    fn try_from(score: f64) -> Result<Self, Self::Error> {
+        Self::new(score).ok_or_else(|| format!("CVSS score {score} is not within 0.0-10.0"))
+    }
+}
+
+impl From<CvssScore> for f64 {
+    fn from(score: CvssScore) -> Self {
+        score.0
+    }
+}
+
+impl fmt::Display for CvssScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}", self.0)
+    }
+}
+
+/// Score and vector of the first entry that has a vector. A missing score
+/// is computed from a v3 vector.
+pub fn first_reported(
+    entries: impl IntoIterator<Item = (Option<f64>, Option<String>)>,
+) -> (Option<CvssScore>, Option<String>) {
+    entries
+        .into_iter()
+        .find_map(|(score, vector)| {
+            let vector = vector.filter(|v| !v.is_empty())?;
+            let score = score
+                .and_then(CvssScore::new)
+                .or_else(|| v3_base_score(&vector));
+            Some((score, Some(vector)))
+        })
+        .unwrap_or((None, None))
+}
+
+/// Base score of a `CVSS:3.0/...` or `CVSS:3.1/...` vector. `None` for
+/// other versions or when a base metric is missing or invalid.
+pub fn v3_base_score(vector: &str) -> Option<CvssScore> {
+    let mut parts = vector.split('/');
+    if !matches!(parts.next()?, "CVSS:3.0" | "CVSS:3.1") {
+        return None;
+    }
+    let metrics: Vec<(&str, &str)> = parts.filter_map(|p| p.split_once(':')).collect();
+    let metric = |name: &str| metrics.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+
+    let changed = match metric("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let av = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |name: &str| match metric(name)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let (c, i, a) = (cia("C")?, cia("I")?, cia("A")?);
+
+    let iss = 1.0 - (1.0 - c) * (1.0 - i) * (1.0 - a);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * f64::powi(iss - 0.02, 15)
+    } else {
+        6.42 * iss
+    };
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let score = if impact <= 0.0 {
+        0.0
+    } else if changed {
+        round_up(f64::min(1.08 * (impact + exploitability), 10.0))
+    } else {
+        round_up(f64::min(impact + exploitability, 10.0))
+    };
+    CvssScore::new(score)
+}
+
+/// The specification's `Roundup`: smallest one-decimal number >= `value`,
+/// computed in integers to avoid floating-point artifacts.
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(vector: &str) -> Option<String> {
+        v3_base_score(vector).map(|s| s.to_string())
+    }
+
+    #[test]
+    fn computes_v3_base_scores() {
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H").as_deref(),
+            Some("9.8")
+        );
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N").as_deref(),
+            Some("6.1")
+        );
+        assert_eq!(
+            score("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:N/A:N").as_deref(),
+            Some("5.5")
+        );
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:H/PR:H/UI:R/S:C/C:H/I:H/A:H/E:P").as_deref(),
+            Some("7.6")
+        );
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N").as_deref(),
+            Some("0.0")
+        );
+    }
+
+    #[test]
+    fn rejects_other_versions_and_incomplete_vectors() {
+        assert_eq!(
+            score("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"),
+            None
+        );
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H"), None);
+        assert_eq!(score("AV:N/AC:L/Au:N/C:P/I:P/A:P"), None);
+    }
+
+    #[test]
+    fn first_reported_prefers_given_score_and_skips_empty_vectors() {
+        let (score, vector) = first_reported([
+            (Some(0.0), None),
+            (
+                Some(7.5),
+                Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N".into()),
+            ),
+        ]);
+        assert_eq!(score.map(CvssScore::value), Some(7.5));
+        assert!(vector.unwrap().starts_with("CVSS:3.1/"));
+
+        let (score, _) = first_reported([(
+            None,
+            Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string()),
+        )]);
+        assert_eq!(score.map(CvssScore::value), Some(9.8));
+
+        assert_eq!(first_reported([(None, Some(String::new()))]), (None, None));
+    }
+
+    #[test]
+    fn deserializing_checks_the_range() {
+        let score: CvssScore = serde_json::from_str("7.5").unwrap();
+        assert_eq!(score.value(), 7.5);
+        assert_eq!(serde_json::to_string(&score).unwrap(), "7.5");
+        let err = serde_json::from_str::<CvssScore>("11.0").unwrap_err();
+        assert!(err.to_string().contains("not within 0.0-10.0"), "{err}");
+        assert!(serde_json::from_str::<CvssScore>("-1").is_err());
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt;
 use std::str::FromStr;
 
 use anyhow::bail;
+use serde::{Deserialize, Deserializer};
 
 /// Controls how deeply recursive workflow scanning descends.
 ///
@@ -51,6 +52,23 @@ impl FromStr for DepthLimit {
     }
 }
 
+/// A number, or a string in the [`FromStr`] forms, so config files can
+/// write `max_depth = 2` or `max_depth = "unlimited"`.
+impl<'de> Deserialize<'de> for DepthLimit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(usize),
+            Text(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(n) => Ok(DepthLimit::Bounded(n)),
+            Repr::Text(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
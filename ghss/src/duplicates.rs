@@ -0,0 +1,240 @@
+//! Copy-pasted workflows across an organization.
+//!
+//! Each workflow is fingerprinted by hashing its YAML after normalization
+//! (comments, formatting and mapping key order don't matter), and
+//! workflows sharing a fingerprint are reported as one group, so a fix to
+//! a shared pattern can be rolled out to every copy at once.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use sha2::{Digest, Sha256};
+use tracing::{instrument, warn};
+
+use crate::github::GitHubClient;
+use crate::usage::{self, REPO_CONCURRENCY};
+
+/// Hex digits of the fingerprint shown in text output.
+const SHORT_FINGERPRINT: usize = 12;
+
+/// One copy of a duplicated workflow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowCopy {
+    /// `owner/name` of the repository the copy lives in.
+    pub repository: String,
+    /// Workflow path relative to the repository root.
+    pub workflow: String,
+}
+
+/// Workflows with identical normalized content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// SHA-256 of the normalized YAML.
+    pub fingerprint: String,
+    /// The workflow's `name:`, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub copies: Vec<WorkflowCopy>,
+}
+
+impl fmt::Display for DuplicateGroup {
+    /// e.g. `3 copies of "CI" (4f1c0a9e2b7d)`, followed by one line per copy.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} copies of ", self.copies.len())?;
+        match &self.name {
+            Some(name) => write!(f, "{name:?}")?,
+            None => write!(f, "an unnamed workflow")?,
+        }
+        write!(f, " ({})", &self.fingerprint[..SHORT_FINGERPRINT])?;
+        for copy in &self.copies {
+            write!(f, "\n    {}: {}", copy.repository, copy.workflow)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fingerprint of a workflow's content: the SHA-256 of its YAML re-emitted
+/// with mapping keys sorted.
+pub fn fingerprint(yaml: &str) -> Result<String> {
+    let value: Value = serde_yaml::from_str(yaml).context("invalid workflow YAML")?;
+    let canonical = serde_yaml::to_string(&normalize(value))
+        .context("failed to serialize normalized workflow")?;
+    Ok(hex::encode(Sha256::digest(canonical)))
+}
+
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut entries: Vec<(String, Value, Value)> = mapping
+                .into_iter()
+                .map(|(k, v)| {
+                    let sort_key = serde_yaml::to_string(&k).unwrap_or_default();
+                    (sort_key, k, normalize(v))
+                })
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Mapping(
+                entries
+                    .into_iter()
+                    .map(|(_, k, v)| (k, v))
+                    .collect::<Mapping>(),
+            )
+        }
+        Value::Sequence(items) => Value::Sequence(items.into_iter().map(normalize).collect()),
+        Value::Tagged(mut tagged) => {
+            tagged.value = normalize(tagged.value);
+            Value::Tagged(tagged)
+        }
+        scalar => scalar,
+    }
+}
+
+/// A fingerprinted workflow file.
+struct Fingerprinted {
+    copy: WorkflowCopy,
+    fingerprint: String,
+    name: Option<String>,
+}
+
+/// Fingerprints of one repository's workflows. Files that aren't valid
+/// YAML are logged and skipped.
+async fn fingerprint_repo(
+    client: &GitHubClient,
+    org: &str,
+    repo: &str,
+) -> Result<Vec<Fingerprinted>> {
+    let mut found = Vec::new();
+    for workflow in usage::list_workflows(client, org, repo).await? {
+        let yaml = client
+            .get_raw_content(org, repo, "HEAD", &workflow)
+            .await
+            .with_context(|| format!("failed to fetch {workflow}"))?;
+        let fingerprint = match fingerprint(&yaml) {
+            Ok(fingerprint) => fingerprint,
+            Err(e) => {
+                warn!(repo, workflow, error = %e, "skipping unparseable workflow");
+                continue;
+            }
+        };
+        let name = serde_yaml::from_str::<Value>(&yaml)
+            .ok()
+            .and_then(|v| v.get("name")?.as_str().map(String::from));
+        found.push(Fingerprinted {
+            copy: WorkflowCopy {
+                repository: format!("{org}/{repo}"),
+                workflow,
+            },
+            fingerprint,
+            name,
+        });
+    }
+    Ok(found)
+}
+
+/// Group every workflow in `org` by fingerprint and return the groups with
+/// more than one copy, largest first. Repositories that cannot be read are
+/// logged and skipped.
+#[instrument(skip(client))]
+pub async fn find_duplicates(client: &GitHubClient, org: &str) -> Result<Vec<DuplicateGroup>> {
+    let repos = usage::list_org_repos(client, org).await?;
+
+    let results: Vec<(String, Result<Vec<Fingerprinted>>)> =
+        futures::stream::iter(repos.into_iter().map(|repo| async move {
+            let found = fingerprint_repo(client, org, &repo).await;
+            (repo, found)
+        }))
+        .buffer_unordered(REPO_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut groups: BTreeMap<String, DuplicateGroup> = BTreeMap::new();
+    for (repo, found) in results {
+        let found = match found {
+            Ok(found) => found,
+            Err(e) => {
+                warn!(
+                    repo,
+                    error = format!("{e:#}"),
+                    "failed to read repository workflows"
+                );
+                continue;
+            }
+        };
+        for workflow in found {
+            let group = groups
+                .entry(workflow.fingerprint.clone())
+                .or_insert_with(|| DuplicateGroup {
+                    fingerprint: workflow.fingerprint,
+                    name: workflow.name,
+                    copies: vec![],
+                });
+            group.copies.push(workflow.copy);
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_values()
+        .filter(|group| group.copies.len() > 1)
+        .map(|mut group| {
+            group
+                .copies
+                .sort_by(|a, b| (&a.repository, &a.workflow).cmp(&(&b.repository, &b.workflow)));
+            group
+        })
+        .collect();
+    duplicates.sort_by(|a, b| {
+        b.copies
+            .len()
+            .cmp(&a.copies.len())
+            .then_with(|| a.fingerprint.cmp(&b.fingerprint))
+    });
+    Ok(duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_ignores_formatting_comments_and_key_order() {
+        let original = "name: CI\non: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/checkout@v4\n";
+        let reformatted = "# copied from app\njobs:\n    build:\n        steps:\n        -   uses: 'actions/checkout@v4'\n        runs-on: ubuntu-latest\non: push\nname: CI\n";
+        assert_eq!(
+            fingerprint(original).unwrap(),
+            fingerprint(reformatted).unwrap()
+        );
+
+        let changed = original.replace("@v4", "@v3");
+        assert_ne!(
+            fingerprint(original).unwrap(),
+            fingerprint(&changed).unwrap()
+        );
+        assert!(fingerprint("jobs: [").is_err());
+    }
+
+    #[test]
+    fn group_display_lists_copies() {
+        let group = DuplicateGroup {
+            fingerprint: "4f1c0a9e2b7d".repeat(4),
+            name: Some("CI".to_string()),
+            copies: vec![
+                WorkflowCopy {
+                    repository: "acme/app".to_string(),
+                    workflow: ".github/workflows/ci.yml".to_string(),
+                },
+                WorkflowCopy {
+                    repository: "acme/site".to_string(),
+                    workflow: ".github/workflows/build.yml".to_string(),
+                },
+            ],
+        };
+        assert_eq!(
+            group.to_string(),
+            "2 copies of \"CI\" (4f1c0a9e2b7d)\n    acme/app: .github/workflows/ci.yml\n    acme/site: .github/workflows/build.yml"
+        );
+    }
+}
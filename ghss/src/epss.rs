@@ -0,0 +1,194 @@
+//! EPSS (Exploit Prediction Scoring System) scores from FIRST: the
+//! probability that a CVE is exploited in the next 30 days, for ranking
+//! advisories by real-world risk rather than severity alone.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::advisory::Advisory;
+
+const EPSS_API_URL: &str = "https://api.first.org/data/v1/epss";
+
+/// CVEs per request. The API pages at 100 rows by default.
+const BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Epss {
+    /// Probability of exploitation in the next 30 days, 0.0–1.0.
+    pub probability: f64,
+    /// Share of scored CVEs with a lower probability, 0.0–1.0.
+    pub percentile: f64,
+}
+
+// Both fields are range-checked when parsed, so never NaN.
+impl Eq for Epss {}
+
+impl fmt::Display for Epss {
+    /// e.g. `94.3% (percentile 99.9)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1}% (percentile {:.1})",
+            self.probability * 100.0,
+            self.percentile * 100.0
+        )
+    }
+}
+
+impl Epss {
+    fn parse(probability: &str, percentile: &str) -> Option<Self> {
+        let unit = |s: &str| s.parse::<f64>().ok().filter(|v| (0.0..=1.0).contains(v));
+        Some(Self {
+            probability: unit(probability)?,
+            percentile: unit(percentile)?,
+        })
+    }
+}
+
+/// The CVE IDs an advisory is known by: its ID and aliases.
+pub fn cve_ids(advisory: &Advisory) -> impl Iterator<Item = &str> {
+    std::iter::once(&advisory.id)
+        .chain(&advisory.aliases)
+        .map(String::as_str)
+        .filter(|id| id.starts_with("CVE-"))
+}
+
+#[derive(Deserialize)]
+struct EpssResponse {
+    #[serde(default)]
+    data: Vec<EpssRow>,
+}
+
+#[derive(Deserialize)]
+struct EpssRow {
+    cve: String,
+    epss: String,
+    percentile: String,
+}
+
+#[derive(Clone)]
+pub struct EpssClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for EpssClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpssClient {
+    pub fn new() -> Self {
+        Self::with_base_url(
+            std::env::var("GHSS_EPSS_BASE_URL").unwrap_or_else(|_| EPSS_API_URL.to_string()),
+        )
+    }
+
+    pub(crate) fn with_base_url(base_url: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    /// Scores for `cves`, [`BATCH_SIZE`] per request. CVEs FIRST has not
+    /// scored are absent from the result.
+    #[instrument(skip_all, fields(cves = cves.len()))]
+    pub async fn scores(&self, cves: &[String]) -> Result<HashMap<String, Epss>> {
+        let mut scores = HashMap::new();
+        for chunk in cves.chunks(BATCH_SIZE) {
+            // CVE IDs need no URL encoding.
+            let url = format!(
+                "{}?cve={}&limit={}",
+                self.base_url,
+                chunk.join(","),
+                chunk.len()
+            );
+            let response = self
+                .http
+                .get(&url)
+                .send()
+                .await
+                .context("failed to query EPSS")?;
+
+            let status = response.status();
+            if !status.is_success() {
+                bail!("EPSS API returned HTTP {status}");
+            }
+
+            let body: EpssResponse = response
+                .json()
+                .await
+                .context("failed to parse EPSS response")?;
+            scores.extend(body.data.into_iter().filter_map(|row| {
+                let epss = Epss::parse(&row.epss, &row.percentile)?;
+                Some((row.cve, epss))
+            }));
+        }
+        Ok(scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cve_ids_from_id_and_aliases() {
+        let advisory: Advisory = serde_json::from_value(serde_json::json!({
+            "id": "GHSA-mrrh-fwg8-r2c3",
+            "aliases": ["CVE-2023-51664", "PYSEC-2024-1"],
+            "summary": "",
+            "severity": "high",
+            "url": "",
+            "affected_range": null,
+            "sources": ["GHSA"]
+        }))
+        .unwrap();
+        assert_eq!(cve_ids(&advisory).collect::<Vec<_>>(), ["CVE-2023-51664"]);
+    }
+
+    #[test]
+    fn display_as_percentages() {
+        let epss = Epss::parse("0.94320", "0.99912").unwrap();
+        assert_eq!(epss.to_string(), "94.3% (percentile 99.9)");
+        assert!(Epss::parse("1.5", "0.5").is_none());
+        assert!(Epss::parse("NaN", "0.5").is_none());
+    }
+
+    #[tokio::test]
+    async fn scores_query_in_batches() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/epss"))
+            .and(query_param("cve", "CVE-2021-44228,CVE-2099-0001"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "OK",
+                "data": [{
+                    "cve": "CVE-2021-44228",
+                    "epss": "0.944000000",
+                    "percentile": "0.999900000",
+                    "date": "2025-01-01"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = EpssClient::with_base_url(format!("{}/epss", server.uri()));
+        let scores = client
+            .scores(&["CVE-2021-44228".to_string(), "CVE-2099-0001".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores["CVE-2021-44228"].probability, 0.944);
+    }
+}
@@ -0,0 +1,244 @@
+//! `ghss explain`: one advisory looked up by GHSA or CVE ID across the
+//! selected providers, independent of a workflow scan.
+
+use std::fmt;
+
+use anyhow::Result;
+use futures::future::join_all;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::advisory::{self, Advisory};
+use crate::providers::Providers;
+
+/// One package an advisory affects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AffectedPackage {
+    /// As the provider names it, e.g. `actions` (GHSA) or `GitHub Actions`
+    /// (OSV).
+    pub ecosystem: String,
+    pub name: String,
+    /// Vulnerable versions, e.g. `>= 6.0.0, < 8.3.1`.
+    pub range: Option<String>,
+    /// First version outside the range, if one has been released.
+    pub fixed: Option<String>,
+}
+
+impl fmt::Display for AffectedPackage {
+    /// e.g. `tj-actions/changed-files (actions): < 46.0.1, fixed in 46.0.1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.ecosystem)?;
+        if let Some(range) = &self.range {
+            write!(f, ": {range}")?;
+        }
+        match &self.fixed {
+            Some(fixed) => write!(f, ", fixed in {fixed}"),
+            None => write!(f, ", no fixed version"),
+        }
+    }
+}
+
+/// An advisory with every package it affects, merged across providers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    #[serde(flatten)]
+    pub advisory: Advisory,
+    pub affected: Vec<AffectedPackage>,
+}
+
+impl Explanation {
+    /// Fold another provider's record of the same advisory into this one.
+    /// Packages are matched by name, since providers name ecosystems
+    /// differently.
+    pub fn merge(&mut self, other: Explanation) {
+        self.advisory.merge(other.advisory);
+        for package in other.affected {
+            match self
+                .affected
+                .iter_mut()
+                .find(|p| p.name.eq_ignore_ascii_case(&package.name))
+            {
+                Some(known) => {
+                    known.range = known.range.take().or(package.range);
+                    known.fixed = known.fixed.take().or(package.fixed);
+                }
+                None => self.affected.push(package),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The advisory's own lines, minus the parts shown in full below.
+        let mut summary = self.advisory.clone();
+        summary.affected_range = None;
+        summary.fixed_version = None;
+        summary.clear_details();
+        write!(f, "{summary}")?;
+
+        if !self.advisory.aliases.is_empty() {
+            write!(f, "\n    aliases: {}", self.advisory.aliases.join(", "))?;
+        }
+        if !self.affected.is_empty() {
+            let lines: Vec<String> = self.affected.iter().map(ToString::to_string).collect();
+            advisory::write_block(f, "affected", lines.iter().map(String::as_str))?;
+        }
+        if let Some(description) = &self.advisory.description {
+            advisory::write_block(f, "description", description.trim().lines())?;
+        }
+        if !self.advisory.references.is_empty() {
+            let references = self.advisory.references.iter().map(String::as_str);
+            advisory::write_block(f, "references", references)?;
+        }
+        Ok(())
+    }
+}
+
+/// `ghsa-...` and `cve-...` in the case the providers index them by.
+pub fn normalize_id(id: &str) -> String {
+    let id = id.trim();
+    match id.split_once('-') {
+        Some((prefix, rest)) if prefix.eq_ignore_ascii_case("ghsa") => {
+            format!("GHSA-{}", rest.to_ascii_lowercase())
+        }
+        Some((prefix, _)) if prefix.eq_ignore_ascii_case("cve") => id.to_ascii_uppercase(),
+        _ => id.to_string(),
+    }
+}
+
+/// Look `id` up with every action provider in `providers` and merge what
+/// they return. `Ok(None)` when none of them knows it; an error only when
+/// every provider failed.
+pub async fn explain(providers: &Providers, id: &str) -> Result<Option<Explanation>> {
+    let id = normalize_id(id);
+    let providers = providers.action_providers();
+    let results = join_all(providers.iter().map(|p| p.lookup(&id))).await;
+
+    let mut explanation: Option<Explanation> = None;
+    let mut errors = Vec::new();
+    for (provider, result) in providers.iter().zip(results) {
+        match result {
+            Ok(Some(found)) => match &mut explanation {
+                Some(explanation) => explanation.merge(found),
+                None => explanation = Some(found),
+            },
+            Ok(None) => {}
+            Err(e) => {
+                warn!(provider = provider.name(), error = %e, "advisory lookup failed");
+                errors.push(e);
+            }
+        }
+    }
+    if explanation.is_none() && !errors.is_empty() && errors.len() == providers.len() {
+        return Err(errors.swap_remove(0));
+    }
+    Ok(explanation)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::action_ref::ActionRef;
+    use crate::advisory::Severity;
+    use crate::providers::ActionAdvisoryProvider;
+
+    struct Fixed(&'static str, Option<Explanation>);
+
+    #[async_trait]
+    impl ActionAdvisoryProvider for Fixed {
+        async fn query(&self, _action: &ActionRef) -> anyhow::Result<Vec<Advisory>> {
+            Ok(vec![])
+        }
+
+        async fn lookup(&self, id: &str) -> anyhow::Result<Option<Explanation>> {
+            assert_eq!(id, "GHSA-mcph-m25j-8j63");
+            match &self.1 {
+                Some(explanation) => Ok(Some(explanation.clone())),
+                None => anyhow::bail!("{} is down", self.0),
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    fn explanation(source: &str, ecosystem: &str, fixed: Option<&str>) -> Explanation {
+        let advisory = serde_json::from_value(serde_json::json!({
+            "id": "GHSA-mcph-m25j-8j63",
+            "aliases": ["CVE-2025-30066"],
+            "summary": "tj-actions/changed-files leaks secrets",
+            "severity": "high",
+            "url": "https://github.com/advisories/GHSA-mcph-m25j-8j63",
+            "affected_range": "< 46.0.1",
+            "sources": [source],
+            "references": [format!("https://example.com/{source}")]
+        }))
+        .unwrap();
+        Explanation {
+            advisory,
+            affected: vec![AffectedPackage {
+                ecosystem: ecosystem.to_string(),
+                name: "tj-actions/changed-files".to_string(),
+                range: Some("< 46.0.1".to_string()),
+                fixed: fixed.map(String::from),
+            }],
+        }
+    }
+
+    #[test]
+    fn normalizes_id_case() {
+        assert_eq!(normalize_id(" ghsa-MCPH-m25j-8j63"), "GHSA-mcph-m25j-8j63");
+        assert_eq!(normalize_id("cve-2025-30066"), "CVE-2025-30066");
+        assert_eq!(normalize_id("PYSEC-2024-1"), "PYSEC-2024-1");
+    }
+
+    #[tokio::test]
+    async fn merges_providers_and_tolerates_partial_failure() {
+        let providers = Providers::new()
+            .with_action_provider(Arc::new(Fixed(
+                "GHSA",
+                Some(explanation("GHSA", "actions", None)),
+            )))
+            .with_action_provider(Arc::new(Fixed("down", None)))
+            .with_action_provider(Arc::new(Fixed(
+                "OSV",
+                Some(explanation("OSV", "GitHub Actions", Some("46.0.1"))),
+            )));
+
+        let explained = explain(&providers, "ghsa-mcph-m25j-8j63")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(explained.advisory.sources, ["GHSA", "OSV"]);
+        assert_eq!(explained.affected.len(), 1);
+        assert_eq!(explained.affected[0].ecosystem, "actions");
+        assert_eq!(explained.affected[0].fixed.as_deref(), Some("46.0.1"));
+        assert_eq!(explained.advisory.severity, Some(Severity::High));
+
+        let text = explained.to_string();
+        assert!(!text.contains("\n    affected: < 46.0.1"), "{text}");
+        assert!(text.contains(
+            "\n    aliases: CVE-2025-30066\n    affected:\n      tj-actions/changed-files (actions): < 46.0.1, fixed in 46.0.1\n    references:\n      https://example.com/GHSA\n      https://example.com/OSV"
+        ), "{text}");
+    }
+
+    #[tokio::test]
+    async fn fails_only_when_every_provider_fails() {
+        let down = Providers::new().with_action_provider(Arc::new(Fixed("down", None)));
+        let err = explain(&down, "GHSA-mcph-m25j-8j63").await.unwrap_err();
+        assert!(err.to_string().contains("down is down"));
+
+        assert!(
+            explain(&Providers::new(), "GHSA-mcph-m25j-8j63")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,532 @@
+//! Workflow-level findings: problems in the workflow itself rather than in a
+//! specific audited action.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::advisory::Severity;
+use crate::location::SourceLocation;
+
+/// Static metadata for a kind of finding.
+#[derive(Debug)]
+pub struct Rule {
+    /// Stable kebab-case identifier, e.g. `dynamic-uses`.
+    pub id: &'static str,
+    /// PascalCase name used in SARIF rule descriptors.
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub help: &'static str,
+    pub severity: Severity,
+}
+
+pub const DYNAMIC_USES: Rule = Rule {
+    id: "dynamic-uses",
+    name: "DynamicUses",
+    summary: "A `uses:` reference is built from a `${{ }}` expression.",
+    help: "The action that runs is only decided at run time, so it cannot be pinned, \
+           resolved, or checked for advisories. Replace the expression with a literal \
+           owner/repo@ref, one step per allowed action.",
+    severity: Severity::Medium,
+};
+
+pub const NO_ACTION_UPDATES: Rule = Rule {
+    id: "no-action-updates",
+    name: "NoActionUpdates",
+    summary: "The repository has no Dependabot or Renovate config that updates actions.",
+    help: "Pinned action refs go stale and miss security fixes unless something bumps \
+           them. Add a `github-actions` entry to .github/dependabot.yml, or enable \
+           Renovate's github-actions manager.",
+    severity: Severity::Low,
+};
+
+pub const UNPINNED_ACTION: Rule = Rule {
+    id: "unpinned-action",
+    name: "UnpinnedAction",
+    summary: "A third-party action is referenced by tag or branch instead of a commit SHA.",
+    help: "Tags and branches can be moved to different code at any time, including by an \
+           attacker who gains access to the action's repository. Pin the full 40-character \
+           commit SHA, with the tag in a trailing comment (`@<sha> # v4.1.0`); \
+           `ghss pin` rewrites a workflow this way.",
+    severity: Severity::Medium,
+};
+
+pub const BRANCH_REF: Rule = Rule {
+    id: "branch-ref",
+    name: "BranchRef",
+    summary: "An action is referenced by a branch, so each run may execute different code.",
+    help: "A branch moves with every push, so the action's code can change between two \
+           runs of the same workflow without any change to the workflow, and a single \
+           malicious commit reaches every caller immediately. Pin the commit SHA the \
+           branch currently points at, or a release tag's SHA.",
+    severity: Severity::High,
+};
+
+pub const IMPOSTOR_COMMIT: Rule = Rule {
+    id: "impostor-commit",
+    name: "ImpostorCommit",
+    summary: "An action is pinned to a commit that is not part of the named repository.",
+    help: "GitHub serves commits from any fork under the parent repository's path, so \
+           `owner/repo@<sha>` runs a fork's code when the SHA was pushed to a fork. A pin \
+           to a commit that is on neither the default branch nor a tag of the repository \
+           is how such an impostor commit slips past review. Re-pin to the SHA of a \
+           release tag in the repository itself.",
+    severity: Severity::High,
+};
+
+pub const TYPOSQUAT: Rule = Rule {
+    id: "typosquat",
+    name: "Typosquat",
+    summary: "An action's owner/repo is one or two characters away from a popular action.",
+    help: "Attackers register lookalike owners and publish copies of popular actions with \
+           a payload added, so a typo in `uses:` runs their code with the job's token and \
+           secrets. Check the spelling against the action you meant to use, and correct \
+           it if it is a typo.",
+    severity: Severity::High,
+};
+
+pub const ARCHIVED_ACTION: Rule = Rule {
+    id: "archived-action",
+    name: "ArchivedAction",
+    summary: "An action's repository is archived or disabled.",
+    help: "An archived repository is read-only, so the action will never receive another \
+           fix, including for security issues found later; a disabled one has been taken \
+           down by GitHub. Replace the action with a maintained alternative, or fork it \
+           into an organization you control.",
+    severity: Severity::High,
+};
+
+pub const STALE_ACTION: Rule = Rule {
+    id: "stale-action",
+    name: "StaleAction",
+    summary: "An action's repository has had no push or release for a long time.",
+    help: "An action nobody maintains won't get fixes when a vulnerability is found in it \
+           or its dependencies, and an abandoned repository or account is easier to take \
+           over. Check whether the action is still maintained, and move to a maintained \
+           alternative if not.",
+    severity: Severity::Medium,
+};
+
+pub const LOW_SCORECARD: Rule = Rule {
+    id: "low-scorecard",
+    name: "LowScorecard",
+    summary: "An action's repository scores below the configured OpenSSF Scorecard minimum.",
+    help: "A low Scorecard score means the repository skips practices such as code review, \
+           branch protection, or pinned dependencies, which makes it easier to slip \
+           malicious code into a release. Review the failing checks, and prefer a \
+           better-maintained alternative or pin a commit you have reviewed.",
+    severity: Severity::Medium,
+};
+
+pub const NO_PROVENANCE: Rule = Rule {
+    id: "no-provenance",
+    name: "NoProvenance",
+    summary: "An action's release has no verifiable build provenance for the pinned commit.",
+    help: "SLSA provenance in a GitHub artifact attestation records which repository, \
+           commit, and workflow built each release asset, so a tampered or locally built \
+           release can be told apart from one built in CI. Without it, the release's \
+           contents can only be trusted as far as its maintainers' accounts. Prefer \
+           actions that attest their releases, or review the pinned commit yourself.",
+    severity: Severity::Low,
+};
+
+pub const UNSIGNED_REF: Rule = Rule {
+    id: "unsigned-ref",
+    name: "UnsignedRef",
+    summary: "Neither the tag nor the commit an action is pinned to has a verified signature.",
+    help: "A verified GPG, SSH, or Sigstore signature ties a tag or commit to a key \
+           registered with the signer's GitHub account, so code pushed with only a \
+           stolen token or a spoofed committer name stands out. Prefer releases the \
+           maintainers sign, or review the pinned commit yourself.",
+    severity: Severity::Medium,
+};
+
+pub const DENIED_LICENSE: Rule = Rule {
+    id: "denied-license",
+    name: "DeniedLicense",
+    summary: "An action's repository is under a license on the configured denylist.",
+    help: "Actions run inside your build, and some licenses carry obligations your \
+           organization has decided not to take on for CI dependencies. Replace the \
+           action with one under an allowed license, or remove the license from the \
+           denylist if this use has been approved.",
+    severity: Severity::Medium,
+};
+
+pub const RENAMED_ACTION: Rule = Rule {
+    id: "renamed-action",
+    name: "RenamedAction",
+    summary: "An action is referenced by the old name of a renamed or transferred repository.",
+    help: "GitHub redirects an old repository name to the new one only until someone \
+           creates a repository under the old name. Whoever registers the old owner or \
+           name then controls the code your workflow runs. Update `uses:` to the \
+           repository's current name.",
+    severity: Severity::Medium,
+};
+
+pub const REPLACEABLE_TAG: Rule = Rule {
+    id: "replaceable-tag",
+    name: "ReplaceableTag",
+    summary: "The release tag an action is pinned to can be moved to another commit.",
+    help: "Anyone with push access to the action's repository can move an unprotected \
+           tag, and every workflow pinned to it runs the new commit. Immutable releases \
+           and tag rulesets blocking updates and deletion keep a tag in place; ask the \
+           maintainers to enable one, or pin the commit SHA instead.",
+    severity: Severity::Low,
+};
+
+pub const DEPRECATED_RUNTIME: Rule = Rule {
+    id: "deprecated-runtime",
+    name: "DeprecatedRuntime",
+    summary: "An action runs on a Node.js runtime GitHub Actions has retired.",
+    help: "node12 and node16 are end-of-life: they get no security fixes, and GitHub \
+           runs such actions on a newer Node.js version they were never tested with, or \
+           refuses to run them. An action still declaring one is likely unmaintained. \
+           Update to a release that runs on a supported runtime, or replace the action.",
+    severity: Severity::Medium,
+};
+
+pub const OUTDATED_MAJOR: Rule = Rule {
+    id: "outdated-major",
+    name: "OutdatedMajor",
+    summary: "An action is pinned to a major version that is several majors old or \
+              runs on a retired Node runtime.",
+    help: "Old majors stop receiving fixes, so a vulnerability in one may never get an \
+           advisory or a patched release, and majors on node12 or node16 will not run \
+           once GitHub removes those runtimes from its runners. Move to the latest \
+           major, checking its release notes for breaking changes.",
+    severity: Severity::Medium,
+};
+
+pub const MOVED_TAG: Rule = Rule {
+    id: "moved-tag",
+    name: "MovedTag",
+    summary: "A release tag points at a different commit than it did before.",
+    help: "Release tags are expected never to change. A tag that resolves to a new commit \
+           since an earlier scan, or to a commit other than the one its GitHub release was \
+           published from, may have been rewritten by someone with push access; this is \
+           how the tj-actions/changed-files compromise reached its users. Review the new \
+           commit before trusting it, and pin the SHA you reviewed.",
+    severity: Severity::High,
+};
+
+pub const SECRETS_INHERIT: Rule = Rule {
+    id: "secrets-inherit",
+    name: "SecretsInherit",
+    summary: "A reusable workflow is called with `secrets: inherit`.",
+    help: "Every secret the calling workflow can read is handed to the called workflow, \
+           including ones it never uses. Pass only the secrets it declares under \
+           `on.workflow_call.secrets`.",
+    severity: Severity::Medium,
+};
+
+pub const UNDECLARED_SECRET: Rule = Rule {
+    id: "undeclared-secret",
+    name: "UndeclaredSecret",
+    summary: "A secret is passed to a reusable workflow that does not declare it.",
+    help: "The called workflow has no use for the secret, so passing it only widens \
+           exposure. Remove it from the job's `secrets:`, or check that the called \
+           workflow and ref are the intended ones.",
+    severity: Severity::Medium,
+};
+
+pub const WORKFLOW_CALL_MISMATCH: Rule = Rule {
+    id: "workflow-call-mismatch",
+    name: "WorkflowCallMismatch",
+    summary: "A reusable workflow call does not match the workflow's `on.workflow_call` interface.",
+    help: "The job passes an input the called workflow does not declare, or leaves out a \
+           required input or secret. The call fails at run time; update `with:` and \
+           `secrets:` to match the called workflow.",
+    severity: Severity::Low,
+};
+
+pub const GITHUB_SCRIPT_INJECTION: Rule = Rule {
+    id: "github-script-injection",
+    name: "GitHubScriptInjection",
+    summary: "An `actions/github-script` script can be injected with untrusted input.",
+    help: "Event data interpolated with `${{ }}` becomes part of the JavaScript source, \
+           and payload fields passed to a shell become part of the command. Pass the \
+           value through `env:` and read it from `process.env`, and pass arguments to \
+           `exec.exec` as an array.",
+    severity: Severity::High,
+};
+
+pub const GITHUB_SCRIPT_DANGEROUS_CALL: Rule = Rule {
+    id: "github-script-dangerous-call",
+    name: "GitHubScriptDangerousCall",
+    summary: "An `actions/github-script` script makes a call that needs review.",
+    help: "The script changes repository settings or access, touches Actions secrets, or \
+           evaluates dynamic code, all with the step's token. Check that the call is \
+           intended and that the job's `permissions:` are no wider than it needs.",
+    severity: Severity::Medium,
+};
+
+pub const DEFAULT_PERMISSIONS: Rule = Rule {
+    id: "default-permissions",
+    name: "DefaultPermissions",
+    summary: "A job runs with the repository's default `GITHUB_TOKEN` permissions.",
+    help: "Without a `permissions:` block at the top of the workflow or on the job, the \
+           token gets the repository or organization default, which grants write access \
+           to most scopes in older repositories. Any compromised action or injected \
+           command in the job can use it. Add `permissions:` listing only the scopes the \
+           job needs; the finding names those its actions are known to need.",
+    severity: Severity::Medium,
+};
+
+pub const WRITE_ALL_PERMISSIONS: Rule = Rule {
+    id: "write-all-permissions",
+    name: "WriteAllPermissions",
+    summary: "A job's `GITHUB_TOKEN` is granted `permissions: write-all`.",
+    help: "`write-all` lets every step in the job push code, create releases, change \
+           issues and pull requests, and more, so a single compromised action can \
+           tamper with the repository. Replace it with the scopes the job needs; the \
+           finding names those its actions are known to need.",
+    severity: Severity::High,
+};
+
+pub const SELF_HOSTED_RUNNER: Rule = Rule {
+    id: "self-hosted-runner",
+    name: "SelfHostedRunner",
+    summary: "A job triggered by pull requests runs on a self-hosted runner.",
+    help: "Pull requests from forks run the workflow with the pull request's code. In a \
+           public repository, anyone who opens one can then run commands on the runner, \
+           read what earlier jobs left on it, and, unless the runner is ephemeral, stay \
+           on it to attack later runs. Run pull request jobs on GitHub-hosted runners, \
+           or require approval for outside contributors and use ephemeral, isolated \
+           runners.",
+    severity: Severity::High,
+};
+
+pub const CACHE_POISONING: Rule = Rule {
+    id: "cache-poisoning",
+    name: "CachePoisoning",
+    summary: "A workflow triggered by untrusted events saves a cache built from \
+              attacker-controlled data.",
+    help: "Workflows triggered by `pull_request_target`, `workflow_run`, `issue_comment` \
+           and similar events run in the default branch's context, so the caches they \
+           save are restored by later runs on that branch, including release and deploy \
+           workflows. When the cache key comes from event data, or the cached files come \
+           from a checked-out pull request, an outsider can plant a cache entry that a \
+           privileged run restores and executes. Don't save caches in these workflows, or \
+           key them only on trusted data and never cache files from untrusted code.",
+    severity: Severity::High,
+};
+
+pub const ARTIFACT_POISONING: Rule = Rule {
+    id: "artifact-poisoning",
+    name: "ArtifactPoisoning",
+    summary: "A `workflow_run` workflow runs code after downloading another run's \
+              artifacts.",
+    help: "A `workflow_run` workflow runs with the default branch's secrets and token, \
+           but the run that triggered it may have built a fork's pull request, whose \
+           code chose what went into its artifacts. Anything later steps execute, source \
+           or read as configuration from those artifacts is then controlled by the \
+           pull request's author, and artifacts extracted into the workspace can \
+           overwrite the checked-out scripts themselves. Download into a directory of \
+           its own, such as `${{ runner.temp }}/artifacts`, and treat the contents as \
+           untrusted data: validate them before use and never execute them.",
+    severity: Severity::High,
+};
+
+pub const PERSISTED_CREDENTIALS: Rule = Rule {
+    id: "persist-credentials",
+    name: "PersistCredentials",
+    summary: "`actions/checkout` leaves its token on disk for pull request code that \
+              later steps run.",
+    help: "By default `actions/checkout` writes the job's `GITHUB_TOKEN` into \
+           `.git/config` so later git commands can push. Build scripts, test suites and \
+           package hooks from the pull request then run with that file in reach and can \
+           read the token, or upload it. Set `persist-credentials: false` on the \
+           checkout, and pass a token explicitly to the one step that has to push.",
+    severity: Severity::Medium,
+};
+
+pub const HARDCODED_SECRET: Rule = Rule {
+    id: "hardcoded-secret",
+    name: "HardcodedSecret",
+    summary: "A workflow contains what looks like a credential.",
+    help: "Workflow files are readable by everyone who can read the repository, and stay \
+           in its history after the value is removed. A token written into `env:`, a \
+           `with:` input or a `run:` script is exposed to all of them and to every fork. \
+           Revoke the credential, store its replacement as an Actions secret, and refer \
+           to it as `${{ secrets.NAME }}`.",
+    severity: Severity::High,
+};
+
+pub const WORKFLOW_RUN_ACTION: Rule = Rule {
+    id: "workflow-run-action",
+    name: "WorkflowRunAction",
+    summary: "A third-party action runs in a workflow triggered by `workflow_run`.",
+    help: "`workflow_run` workflows run from the default branch with its secrets and a \
+           token that can write, even when the run that triggered them built a fork's \
+           pull request. A third-party action there handles data from the untrusted run \
+           with those privileges, so a flaw in how it parses that data, or a compromise \
+           of the action, reaches the repository's secrets. Keep these workflows to \
+           GitHub's own actions where possible, pin the rest to a commit SHA, pass them \
+           only the secrets they need, and restrict `permissions:`.",
+    severity: Severity::Medium,
+};
+
+pub const REMOTE_SCRIPT: Rule = Rule {
+    id: "remote-script",
+    name: "RemoteScript",
+    summary: "A `run:` step executes code downloaded when the job runs.",
+    help: "A script piped from `curl` or `wget` into a shell, or a package installed from \
+           a branch, an unhashed URL or an unpinned version, is whatever the server \
+           returns when the job runs. Unlike a `uses:` ref it cannot be pinned to a \
+           commit or audited ahead of time, and it runs with the job's token and \
+           secrets. Download to a file and verify a checksum before running it, pin \
+           packages to exact versions or commit SHAs, or use a pinned action instead.",
+    severity: Severity::Medium,
+};
+
+pub const RISKY_INPUT: Rule = Rule {
+    id: "risky-input",
+    name: "RiskyInput",
+    summary: "An action is given an input value known to make it riskier to use.",
+    help: "Some inputs hand an action more than it needs, such as a personal access \
+           token where the job's `GITHUB_TOKEN` or OIDC would do. The knowledge base \
+           lists such values per action, with the safer alternative in the message; \
+           the finding's severity comes from that entry. Change the input as the \
+           message suggests, or override the entry with `--knowledge-base` if the \
+           value is intended.",
+    severity: Severity::Medium,
+};
+
+pub const EXPIRED_SUPPRESSION: Rule = Rule {
+    id: "expired-suppression",
+    name: "ExpiredSuppression",
+    summary: "A `.ghssignore` entry or `ghss-ignore:` comment is past its expiry date.",
+    help: "Suppressions can carry `expires=YYYY-MM-DD` so that accepted risks are \
+           reviewed again instead of staying silenced forever. From that date the \
+           suppression no longer applies, and whatever it hid is reported again. Review \
+           the risk, then fix it, remove the suppression, or give it a new date.",
+    severity: Severity::Medium,
+};
+
+pub const FORBIDDEN_ACTION: Rule = Rule {
+    id: "forbidden-action",
+    name: "ForbiddenAction",
+    summary: "A workflow uses an action that policy forbids.",
+    help: "The `forbid:` list of the rules file, or `--forbid`, names actions and owners \
+           that no workflow may use, such as an organization whose actions were \
+           compromised. Every use is reported, including uses inside composite actions \
+           and reusable workflows. Replace the action, or remove the entry once the ban \
+           no longer applies.",
+    severity: Severity::Critical,
+};
+
+/// Every rule that can produce a finding.
+pub const RULES: &[&Rule] = &[
+    &DYNAMIC_USES,
+    &NO_ACTION_UPDATES,
+    &UNPINNED_ACTION,
+    &BRANCH_REF,
+    &IMPOSTOR_COMMIT,
+    &TYPOSQUAT,
+    &ARCHIVED_ACTION,
+    &STALE_ACTION,
+    &LOW_SCORECARD,
+    &NO_PROVENANCE,
+    &UNSIGNED_REF,
+    &DENIED_LICENSE,
+    &RENAMED_ACTION,
+    &REPLACEABLE_TAG,
+    &DEPRECATED_RUNTIME,
+    &OUTDATED_MAJOR,
+    &MOVED_TAG,
+    &SECRETS_INHERIT,
+    &UNDECLARED_SECRET,
+    &WORKFLOW_CALL_MISMATCH,
+    &GITHUB_SCRIPT_INJECTION,
+    &GITHUB_SCRIPT_DANGEROUS_CALL,
+    &DEFAULT_PERMISSIONS,
+    &WRITE_ALL_PERMISSIONS,
+    &SELF_HOSTED_RUNNER,
+    &CACHE_POISONING,
+    &ARTIFACT_POISONING,
+    &PERSISTED_CREDENTIALS,
+    &HARDCODED_SECRET,
+    &WORKFLOW_RUN_ACTION,
+    &REMOTE_SCRIPT,
+    &RISKY_INPUT,
+    &EXPIRED_SUPPRESSION,
+    &FORBIDDEN_ACTION,
+];
+
+/// Look up a rule by its `id`.
+pub fn rule(id: &str) -> Option<&'static Rule> {
+    RULES.iter().copied().find(|r| r.id == id)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+impl Finding {
+    /// A finding for `rule` at the rule's default severity.
+    pub fn new(rule: &Rule, message: impl Into<String>) -> Self {
+        Self {
+            rule_id: rule.id.to_string(),
+            severity: rule.severity,
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    pub fn with_location(mut self, location: Option<SourceLocation>) -> Self {
+        self.location = location;
+        self
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.rule_id, self.severity, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, "\n    at {location}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_uses_rule_defaults() {
+        let finding = Finding::new(&DYNAMIC_USES, "msg");
+        assert_eq!(finding.rule_id, "dynamic-uses");
+        assert_eq!(finding.severity, Severity::Medium);
+        assert_eq!(finding.location, None);
+    }
+
+    #[test]
+    fn display_includes_location_when_present() {
+        let finding = Finding::new(&DYNAMIC_USES, "msg")
+            .with_location(Some(SourceLocation::new(3, 7).with_file("ci.yml")));
+        assert_eq!(
+            finding.to_string(),
+            "dynamic-uses (medium): msg\n    at ci.yml:3:7"
+        );
+    }
+
+    #[test]
+    fn rule_lookup_by_id() {
+        assert_eq!(rule("dynamic-uses").unwrap().name, "DynamicUses");
+        assert!(rule("nope").is_none());
+    }
+
+    #[test]
+    fn rule_ids_are_unique() {
+        let mut ids: Vec<&str> = RULES.iter().map(|r| r.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), RULES.len());
+    }
+}
@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
@@ -34,12 +35,50 @@ enum AuthState {
     },
 }
 
+/// Rate-limit budget reported by the `X-RateLimit-*` headers of the most
+/// recent response for one resource (`core`, `graphql`, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    pub resource: String,
+    pub limit: u64,
+    pub remaining: u64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        Some(Self {
+            resource: header("x-ratelimit-resource").unwrap_or("core").to_string(),
+            limit: header("x-ratelimit-limit")?.parse().ok()?,
+            remaining: header("x-ratelimit-remaining")?.parse().ok()?,
+        })
+    }
+
+    /// Fraction of the budget still available, from 0.0 to 1.0.
+    pub fn headroom(&self) -> f64 {
+        if self.limit == 0 {
+            return 1.0;
+        }
+        (self.remaining as f64 / self.limit as f64).min(1.0)
+    }
+}
+
+/// What a ref name resolved as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    /// Already a full commit SHA.
+    Commit,
+    Tag,
+    Branch,
+}
+
 #[derive(Clone)]
 pub struct GitHubClient {
     client: reqwest::Client,
     auth: AuthState,
     api_base_url: String,
     raw_base_url: String,
+    rate_limits: Arc<Mutex<HashMap<String, RateLimit>>>,
 }
 
 fn build_http_client() -> reqwest::Client {
@@ -65,6 +104,7 @@ impl GitHubClient {
             auth: AuthState::Pat(token),
             api_base_url,
             raw_base_url,
+            rate_limits: Arc::default(),
         }
     }
 
@@ -97,6 +137,7 @@ impl GitHubClient {
             },
             api_base_url,
             raw_base_url,
+            rate_limits: Arc::default(),
         })
     }
 
@@ -111,6 +152,25 @@ impl GitHubClient {
         &self.api_base_url
     }
 
+    /// The rate-limit resource with the least headroom left, as last seen
+    /// in response headers. `None` until a response carried them.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let limits = self.rate_limits.lock().expect("rate limit lock poisoned");
+        limits
+            .values()
+            .min_by(|a, b| a.headroom().total_cmp(&b.headroom()))
+            .cloned()
+    }
+
+    fn record_rate_limit(&self, response: &reqwest::Response) {
+        if let Some(limit) = RateLimit::from_headers(response.headers()) {
+            self.rate_limits
+                .lock()
+                .expect("rate limit lock poisoned")
+                .insert(limit.resource.clone(), limit);
+        }
+    }
+
     /// Return a valid Bearer token, minting or refreshing as needed for App auth.
     #[instrument(skip(self))]
     async fn get_token(&self) -> Result<Option<String>> {
@@ -190,8 +250,15 @@ impl GitHubClient {
 
     #[instrument(skip(self), fields(action = %action))]
     pub async fn resolve_ref(&self, action: &ActionRef) -> Result<String> {
+        Ok(self.resolve_ref_kind(action).await?.0)
+    }
+
+    /// The commit `action`'s ref points at, and whether it named a tag or a
+    /// branch. Tags win when both exist, as they do for `uses:`.
+    #[instrument(skip(self), fields(action = %action))]
+    pub async fn resolve_ref_kind(&self, action: &ActionRef) -> Result<(String, RefKind)> {
         if action.ref_type == RefType::Sha {
-            return Ok(action.git_ref.clone());
+            return Ok((action.git_ref.clone(), RefKind::Commit));
         }
 
         // Try as a tag first
@@ -202,9 +269,10 @@ impl GitHubClient {
         );
 
         if let Some(json) = self.api_get_optional(&tag_url).await? {
-            return self
+            let sha = self
                 .extract_commit_sha(&json, &action.owner, &action.repo)
-                .await;
+                .await?;
+            return Ok((sha, RefKind::Tag));
         }
 
         // Fall back to branch
@@ -218,8 +286,28 @@ impl GitHubClient {
             .await
             .with_context(|| format!("ref '{}' not found as tag or branch", action.git_ref))?;
 
-        self.extract_commit_sha(&json, &action.owner, &action.repo)
-            .await
+        let sha = self
+            .extract_commit_sha(&json, &action.owner, &action.repo)
+            .await?;
+        Ok((sha, RefKind::Branch))
+    }
+
+    /// The current `owner/repo` name of a repository that was renamed or
+    /// transferred, if `owner/repo` is an old name GitHub redirects from.
+    /// `None` for a current name or a repository that doesn't exist.
+    ///
+    /// Redirects are followed by the HTTP client, so other requests under an
+    /// old name reach the repository without this.
+    #[instrument(skip(self))]
+    pub async fn renamed_to(&self, owner: &str, repo: &str) -> Result<Option<String>> {
+        let url = format!("{}/repos/{owner}/{repo}", self.api_base_url);
+        let Some(json) = self.api_get_optional(&url).await? else {
+            return Ok(None);
+        };
+        let full_name = json.get("full_name").and_then(Value::as_str);
+        Ok(full_name
+            .filter(|name| !name.eq_ignore_ascii_case(&format!("{owner}/{repo}")))
+            .map(str::to_string))
     }
 
     #[instrument(skip(self, ref_json))]
@@ -265,8 +353,41 @@ impl GitHubClient {
         bail!("unexpected ref object type: {obj_type}");
     }
 
+    /// GET a REST API URL, returning `None` on 404.
     #[tracing::instrument(skip(self))]
-    async fn api_get_optional(&self, url: &str) -> Result<Option<Value>> {
+    pub async fn api_get_optional(&self, url: &str) -> Result<Option<Value>> {
+        let Some(response) = self.get_success(url).await? else {
+            return Ok(None);
+        };
+        let json = response
+            .json()
+            .await
+            .with_context(|| format!("failed to parse JSON from {url}"))?;
+        Ok(Some(json))
+    }
+
+    /// GET one page of a paginated REST listing. Returns the page and the
+    /// `rel="next"` URL from its `Link` header, if there is one.
+    #[instrument(skip(self))]
+    pub async fn api_get_page(&self, url: &str) -> Result<(Value, Option<String>)> {
+        let response = self
+            .get_success(url)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("{url} returned HTTP 404"))?;
+        let next = response
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_link);
+        let json = response
+            .json()
+            .await
+            .with_context(|| format!("failed to parse JSON from {url}"))?;
+        Ok((json, next))
+    }
+
+    /// Send an authenticated REST GET. `None` on 404; other failures are errors.
+    async fn get_success(&self, url: &str) -> Result<Option<reqwest::Response>> {
         let mut request = self
             .client
             .get(url)
@@ -278,6 +399,7 @@ impl GitHubClient {
             .send()
             .await
             .with_context(|| format!("request to {url} failed"))?;
+        self.record_rate_limit(&response);
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
             return Ok(None);
@@ -286,12 +408,7 @@ impl GitHubClient {
         let response = response
             .error_for_status()
             .with_context(|| format!("{url} returned non-success status"))?;
-
-        let json = response
-            .json()
-            .await
-            .with_context(|| format!("failed to parse JSON from {url}"))?;
-        Ok(Some(json))
+        Ok(Some(response))
     }
 
     #[instrument(skip(self))]
@@ -373,6 +490,7 @@ impl GitHubClient {
             .send()
             .await
             .context("GraphQL request failed")?;
+        self.record_rate_limit(&response);
 
         let response = response
             .error_for_status()
@@ -413,6 +531,24 @@ fn generate_jwt(app_id: u64, key: &jsonwebtoken::EncodingKey) -> Result<String>
     jsonwebtoken::encode(&header, &claims, key).context("failed to sign JWT")
 }
 
+/// The `rel="next"` target of a `Link` header, e.g.
+/// `<https://api.github.com/advisories?after=Y3Vy>; rel="next", <...>; rel="prev"`.
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| p.trim() == r#"rel="next""#)
+            .then(|| {
+                target
+                    .trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -498,6 +634,106 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn records_tightest_rate_limit_from_headers() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/core"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-limit", "5000")
+                    .insert_header("x-ratelimit-remaining", "4000")
+                    .insert_header("x-ratelimit-resource", "core")
+                    .set_body_json(serde_json::json!({})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-limit", "5000")
+                    .insert_header("x-ratelimit-remaining", "500")
+                    .insert_header("x-ratelimit-resource", "graphql")
+                    .set_body_json(serde_json::json!({"data": {}})),
+            )
+            .mount(&server)
+            .await;
+
+        let mut client = GitHubClient::new(Some("token".to_string()));
+        client.api_base_url = server.uri();
+        assert_eq!(client.rate_limit(), None);
+
+        client
+            .api_get(&format!("{}/core", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(client.rate_limit().unwrap().remaining, 4000);
+
+        client.graphql_post("{ viewer { login } }").await.unwrap();
+        let tightest = client.clone().rate_limit().unwrap();
+        assert_eq!(tightest.resource, "graphql");
+        assert!((tightest.headroom() - 0.1).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn renamed_to_follows_repository_redirects() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/old-org/tool"))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("location", format!("{}/repositories/42", server.uri())),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repositories/42"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"id": 42, "full_name": "new-org/tool"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/New-Org/Tool"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"id": 42, "full_name": "new-org/tool"})),
+            )
+            .mount(&server)
+            .await;
+
+        let mut client = GitHubClient::new(None);
+        client.api_base_url = server.uri();
+        assert_eq!(
+            client.renamed_to("old-org", "tool").await.unwrap(),
+            Some("new-org/tool".to_string())
+        );
+        assert_eq!(client.renamed_to("New-Org", "Tool").await.unwrap(), None);
+        assert_eq!(client.renamed_to("missing", "tool").await.unwrap(), None);
+    }
+
+    #[test]
+    fn next_link_picks_rel_next() {
+        let header = r#"<https://api.github.com/advisories?before=Y3Vy>; rel="prev", <https://api.github.com/advisories?after=Y3Vy&per_page=100>; rel="next""#;
+        assert_eq!(
+            next_link(header).as_deref(),
+            Some("https://api.github.com/advisories?after=Y3Vy&per_page=100")
+        );
+        assert_eq!(
+            next_link(r#"<https://api.github.com/x?page=1>; rel="first""#),
+            None
+        );
+        assert_eq!(next_link(""), None);
+    }
+
     // ── GitHub App auth tests ──
 
     const TEST_PEM: &[u8] = include_bytes!("../tests/fixtures/test-rsa-key.pem");
@@ -616,6 +852,7 @@ mod tests {
             },
             api_base_url: base_url.to_string(),
             raw_base_url: "http://unused".to_string(),
+            rate_limits: Arc::default(),
         }
     }
 
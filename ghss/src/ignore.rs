@@ -0,0 +1,782 @@
+//! Accepted risks listed in a `.ghssignore` file.
+//!
+//! One entry per line; `#` starts a comment. An entry containing `/` names
+//! an action as `owner/repo[/path]`, optionally with `@ref`, and `*`
+//! matches any run of characters. Any other entry is an advisory ID (or
+//! alias) or a rule ID. Each entry must say why it is accepted, and may say
+//! until when:
+//!
+//! ```text
+//! GHSA-mrrh-fwg8-r2c3 reason="patched in our fork" expires=2025-09-30
+//! default-permissions reason="read-only repository default"
+//! actions/checkout@v4 reason="reviewed at this tag"
+//! my-org/* reason="internal actions"
+//! ```
+//!
+//! Matching advisories and findings are removed before output and policy
+//! checks, so they neither show nor fail the run. An ignored action loses
+//! its advisories, its dependencies' advisories, and every finding
+//! reported at one of its `uses:` lines. From its `expires` date an entry
+//! no longer applies and is reported as an [`EXPIRED_SUPPRESSION`] finding
+//! instead (see [`IgnoreList::expire`]).
+//!
+//! A single use can be excused in the workflow itself instead, with a
+//! comment on its line (see [`InlineSuppressions`]):
+//!
+//! ```yaml
+//! - uses: tj-actions/changed-files@v45 # ghss-ignore: GHSA-mrrh-fwg8-r2c3 reason="no secrets in this job"
+//! ```
+//!
+//! [`EXPIRED_SUPPRESSION`]: crate::finding::EXPIRED_SUPPRESSION
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use tracing::{info, warn};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::Advisory;
+use crate::finding::{self, Finding};
+use crate::location::SourceLocation;
+use crate::output::AuditNode;
+use crate::rules::glob_match;
+
+/// The ignore file looked for at the repository root.
+pub const FILE_NAME: &str = ".ghssignore";
+
+/// The commented ignore file `ghss init --ignore` writes.
+pub const STARTER: &str = include_str!("../data/starter.ghssignore");
+
+/// What follows `#` in an inline suppression comment.
+const INLINE_MARKER: &str = "ghss-ignore:";
+
+/// Why a suppression exists and until when, from its `reason="..."` and
+/// `expires=YYYY-MM-DD` attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rationale {
+    pub reason: String,
+    /// The first day the suppression no longer applies.
+    pub expires: Option<NaiveDate>,
+}
+
+impl Rationale {
+    /// Read `key=value` attributes; values with spaces are quoted. A
+    /// reason is required.
+    fn parse(text: &str) -> Result<Self> {
+        let mut reason = None;
+        let mut expires = None;
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let Some((key, after)) = rest.split_once('=') else {
+                bail!("expected reason=\"...\" or expires=YYYY-MM-DD, got `{rest}`");
+            };
+            let (value, after) = match after.strip_prefix('"') {
+                Some(quoted) => quoted
+                    .split_once('"')
+                    .with_context(|| format!("unterminated quote in {key}="))?,
+                None => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+            };
+            match key {
+                "reason" if !value.trim().is_empty() => reason = Some(value.trim().to_string()),
+                "reason" => bail!("reason is empty"),
+                "expires" => {
+                    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+                        format!("invalid expiry date `{value}`, expected YYYY-MM-DD")
+                    })?;
+                    expires = Some(date);
+                }
+                _ => bail!("unknown attribute `{key}`"),
+            }
+            rest = after.trim_start();
+        }
+        let reason = reason.context("missing reason=\"...\"")?;
+        Ok(Self { reason, expires })
+    }
+
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires.is_some_and(|date| today >= date)
+    }
+
+    /// The finding raised for an expired suppression of `what`.
+    fn expired_finding(&self, what: &str, location: SourceLocation) -> Finding {
+        let date = self.expires.map(|d| d.to_string()).unwrap_or_default();
+        Finding::new(
+            &finding::EXPIRED_SUPPRESSION,
+            format!(
+                "suppression of {what} expired on {date} ({}); it no longer applies",
+                self.reason
+            ),
+        )
+        .with_location(Some(location))
+    }
+}
+
+/// One line of an ignore file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreEntry {
+    /// An action pattern, with or without `@ref`, or an advisory or rule
+    /// ID, lowercased.
+    pub pattern: String,
+    pub rationale: Rationale,
+    pub line: usize,
+}
+
+impl IgnoreEntry {
+    fn is_action(&self) -> bool {
+        self.pattern.contains('/')
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreList {
+    /// The path findings about the file are reported at.
+    file: Option<String>,
+    entries: Vec<IgnoreEntry>,
+}
+
+/// How much [`IgnoreList::apply`] removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Ignored {
+    pub advisories: usize,
+    pub findings: usize,
+}
+
+/// `line` without its `#` comment. A `#` inside a quoted value is kept.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+impl IgnoreList {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut list = Self::default();
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let entry = strip_comment(line).trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (pattern, attributes) =
+                entry.split_once(char::is_whitespace).unwrap_or((entry, ""));
+            let rationale = Rationale::parse(attributes)
+                .with_context(|| format!("line {line_no}: `{pattern}`"))?;
+            let pattern = if pattern.contains('/') {
+                pattern.to_string()
+            } else {
+                pattern.to_lowercase()
+            };
+            list.entries.push(IgnoreEntry {
+                pattern,
+                rationale,
+                line: line_no,
+            });
+        }
+        Ok(list)
+    }
+
+    /// Report findings about the list itself at `file`.
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove the entries expired by `today`, returning an
+    /// [`EXPIRED_SUPPRESSION`](finding::EXPIRED_SUPPRESSION) finding at each
+    /// one's line.
+    pub fn expire(&mut self, today: NaiveDate) -> Vec<Finding> {
+        let (expired, current) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|e: &IgnoreEntry| e.rationale.is_expired(today));
+        self.entries = current;
+        expired
+            .iter()
+            .map(|e| {
+                let mut location = SourceLocation::new(e.line, 1);
+                location.file.clone_from(&self.file);
+                e.rationale
+                    .expired_finding(&format!("`{}`", e.pattern), location)
+            })
+            .collect()
+    }
+
+    /// Whether `action` matches an action entry. An entry without `@ref`
+    /// matches every ref.
+    pub fn ignores_action(&self, action: &ActionRef) -> bool {
+        let name = action.package_name();
+        let full = format!("{name}@{}", action.git_ref);
+        self.entries.iter().filter(|e| e.is_action()).any(|e| {
+            if e.pattern.contains('@') {
+                glob_match(&e.pattern, &full)
+            } else {
+                glob_match(&e.pattern, &name)
+            }
+        })
+    }
+
+    fn ignores_id(&self, id: &str) -> bool {
+        let id = id.to_lowercase();
+        self.entries
+            .iter()
+            .any(|e| !e.is_action() && e.pattern == id)
+    }
+
+    fn ignores_advisory(&self, advisory: &Advisory) -> bool {
+        std::iter::once(&advisory.id)
+            .chain(&advisory.aliases)
+            .any(|id| self.ignores_id(id))
+    }
+
+    fn ignores_rule(&self, rule_id: &str) -> bool {
+        self.ignores_id(rule_id)
+    }
+
+    /// Remove ignored advisories and findings from the audited tree and from
+    /// the workflow's `findings`. Run before [`crate::output::AuditReport::new`]
+    /// so findings still on the tree are covered too.
+    pub fn apply(&self, nodes: &mut [AuditNode], findings: &mut Vec<Finding>) -> Ignored {
+        let mut ignored = Ignored::default();
+        // `uses:` lines of ignored actions, as (file, line).
+        let mut lines = BTreeSet::new();
+        self.apply_nodes(nodes, &mut lines, &mut ignored);
+
+        let before = findings.len();
+        findings.retain(|f| {
+            let at_ignored_action = f
+                .location
+                .as_ref()
+                .is_some_and(|l| lines.contains(&(l.file.clone(), l.line)));
+            !at_ignored_action && !self.ignores_rule(&f.rule_id)
+        });
+        ignored.findings += before - findings.len();
+        ignored
+    }
+
+    fn apply_nodes(
+        &self,
+        nodes: &mut [AuditNode],
+        lines: &mut BTreeSet<(Option<String>, usize)>,
+        ignored: &mut Ignored,
+    ) {
+        for node in nodes {
+            let entry = &mut node.entry;
+            let whole_action = self.ignores_action(&entry.action);
+            if whole_action {
+                let action = &entry.action;
+                let locations = action.location.iter().chain(
+                    action
+                        .occurrences
+                        .iter()
+                        .filter_map(|o| o.location.as_ref()),
+                );
+                lines.extend(locations.map(|l| (l.file.clone(), l.line)));
+            }
+            let keep = |adv: &Advisory| !whole_action && !self.ignores_advisory(adv);
+
+            let before = entry.advisories.len();
+            entry.advisories.retain(keep);
+            ignored.advisories += before - entry.advisories.len();
+            for dep in &mut entry.dep_vulnerabilities {
+                let before = dep.advisories.len();
+                dep.advisories.retain(keep);
+                ignored.advisories += before - dep.advisories.len();
+            }
+
+            let before = entry.findings.len();
+            entry
+                .findings
+                .retain(|f| !whole_action && !self.ignores_rule(&f.rule_id));
+            ignored.findings += before - entry.findings.len();
+
+            self.apply_nodes(&mut node.children, lines, ignored);
+        }
+    }
+}
+
+/// A `# ghss-ignore: ID[,ID...] reason="..." [expires=YYYY-MM-DD]` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    /// Advisory (or alias) and rule IDs, lowercased.
+    pub ids: Vec<String>,
+    pub rationale: Rationale,
+}
+
+impl Suppression {
+    /// The suppression in `line`'s comment, if it has one.
+    fn parse(line: &str) -> Result<Option<Self>> {
+        let Some(start) = line.find(INLINE_MARKER) else {
+            return Ok(None);
+        };
+        let hash = line[..start].trim_end();
+        // The marker must open a comment, not sit inside a value.
+        let Some(comment) = hash.strip_suffix('#') else {
+            return Ok(None);
+        };
+        if !comment.is_empty() && !comment.ends_with(char::is_whitespace) {
+            return Ok(None);
+        }
+        let rest = &line[start + INLINE_MARKER.len()..];
+        let attributes = ["reason=", "expires="]
+            .iter()
+            .filter_map(|key| rest.find(key))
+            .min()
+            .unwrap_or(rest.len());
+        let ids: Vec<String> = rest[..attributes]
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|id| !id.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if ids.is_empty() {
+            return Ok(None);
+        }
+        let rationale = Rationale::parse(&rest[attributes..])?;
+        Ok(Some(Self { ids, rationale }))
+    }
+}
+
+/// `# ghss-ignore:` comments in one workflow file, each excusing the
+/// advisories and findings it names on its own line only.
+///
+/// A finding located on a commented line is removed when the comment names
+/// its rule. An advisory is removed when every use of the action in the
+/// file carries a comment naming it (or an alias), since an unexcused use
+/// is still exposed. Names that match nothing are warned about, so stale
+/// comments get noticed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InlineSuppressions {
+    file: String,
+    by_line: BTreeMap<usize, Suppression>,
+}
+
+impl InlineSuppressions {
+    /// Read the comments in `yaml`, whose findings are located in `file`.
+    /// Fails on a comment without a reason.
+    pub fn parse(yaml: &str, file: &str) -> Result<Self> {
+        let mut by_line = BTreeMap::new();
+        for (i, line) in yaml.lines().enumerate() {
+            let suppression = Suppression::parse(line)
+                .with_context(|| format!("{file}:{}: invalid ghss-ignore comment", i + 1))?;
+            if let Some(suppression) = suppression {
+                by_line.insert(i + 1, suppression);
+            }
+        }
+        Ok(Self {
+            file: file.to_string(),
+            by_line,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_line.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_line.is_empty()
+    }
+
+    /// The suppression on `line`, if any.
+    pub fn at(&self, line: usize) -> Option<&Suppression> {
+        self.by_line.get(&line)
+    }
+
+    /// Remove the comments expired by `today`, like [`IgnoreList::expire`].
+    pub fn expire(&mut self, today: NaiveDate) -> Vec<Finding> {
+        let expired: Vec<usize> = self
+            .by_line
+            .iter()
+            .filter(|(_, s)| s.rationale.is_expired(today))
+            .map(|(line, _)| *line)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|line| {
+                let suppression = self.by_line.remove(&line)?;
+                let location = SourceLocation::new(line, 1).with_file(self.file.as_str());
+                Some(
+                    suppression
+                        .rationale
+                        .expired_finding(&format!("`{}`", suppression.ids.join("`, `")), location),
+                )
+            })
+            .collect()
+    }
+
+    /// Remove suppressed advisories and findings, like [`IgnoreList::apply`].
+    pub fn apply(&self, nodes: &mut [AuditNode], findings: &mut Vec<Finding>) -> Ignored {
+        let mut ignored = Ignored::default();
+        if self.is_empty() {
+            return ignored;
+        }
+        let mut used = BTreeSet::new();
+        self.apply_nodes(nodes, &mut used, &mut ignored);
+
+        let before = findings.len();
+        findings.retain(|f| !self.suppresses_finding(f, &[], &mut used));
+        ignored.findings += before - findings.len();
+
+        for (line, suppression) in &self.by_line {
+            for id in &suppression.ids {
+                if used.contains(&(*line, id.clone())) {
+                    info!(file = %self.file, line, id, reason = %suppression.rationale.reason, "suppressed inline");
+                } else {
+                    warn!(
+                        "{}:{line}: ghss-ignore names {id}, which is not reported there",
+                        self.file
+                    );
+                }
+            }
+        }
+        ignored
+    }
+
+    /// The ID in `ids` that the comment on `line` names.
+    fn named<'a>(&self, line: usize, mut ids: impl Iterator<Item = &'a str>) -> Option<String> {
+        let suppression = self.by_line.get(&line)?;
+        ids.find_map(|id| {
+            let id = id.to_lowercase();
+            suppression.ids.contains(&id).then_some(id)
+        })
+    }
+
+    /// Whether every line in `lines` names one of `ids`, marking what was
+    /// used. `false` when `lines` is empty.
+    fn all_name<'a>(
+        &self,
+        lines: &[usize],
+        ids: impl Iterator<Item = &'a str> + Clone,
+        used: &mut BTreeSet<(usize, String)>,
+    ) -> bool {
+        let named: Option<Vec<(usize, String)>> = lines
+            .iter()
+            .map(|line| Some((*line, self.named(*line, ids.clone())?)))
+            .collect();
+        match named {
+            Some(named) if !named.is_empty() => {
+                used.extend(named);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// A finding is suppressed by a comment on its own line, or, without a
+    /// location, on every one of `uses` (its action's lines).
+    fn suppresses_finding(
+        &self,
+        finding: &Finding,
+        uses: &[usize],
+        used: &mut BTreeSet<(usize, String)>,
+    ) -> bool {
+        let rule = std::iter::once(finding.rule_id.as_str());
+        match &finding.location {
+            Some(location) if location.file.as_deref().is_none_or(|f| f == self.file) => {
+                self.all_name(&[location.line], rule, used)
+            }
+            Some(_) => false,
+            None => self.all_name(uses, rule, used),
+        }
+    }
+
+    fn apply_nodes(
+        &self,
+        nodes: &mut [AuditNode],
+        used: &mut BTreeSet<(usize, String)>,
+        ignored: &mut Ignored,
+    ) {
+        for node in nodes {
+            let entry = &mut node.entry;
+            let action = &entry.action;
+            let mut uses: Vec<usize> = action
+                .location
+                .iter()
+                .chain(
+                    action
+                        .occurrences
+                        .iter()
+                        .filter_map(|o| o.location.as_ref()),
+                )
+                .filter(|l| l.file.as_deref() == Some(self.file.as_str()))
+                .map(|l| l.line)
+                .collect();
+            uses.sort_unstable();
+            uses.dedup();
+
+            let mut keep = |adv: &Advisory| {
+                let ids =
+                    std::iter::once(adv.id.as_str()).chain(adv.aliases.iter().map(String::as_str));
+                !self.all_name(&uses, ids, used)
+            };
+            let before = entry.advisories.len();
+            entry.advisories.retain(&mut keep);
+            ignored.advisories += before - entry.advisories.len();
+            for dep in &mut entry.dep_vulnerabilities {
+                let before = dep.advisories.len();
+                dep.advisories.retain(&mut keep);
+                ignored.advisories += before - dep.advisories.len();
+            }
+
+            let before = entry.findings.len();
+            entry
+                .findings
+                .retain(|f| !self.suppresses_finding(f, &uses, used));
+            ignored.findings += before - entry.findings.len();
+
+            self.apply_nodes(&mut node.children, used, ignored);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::AuditContext;
+    use crate::finding;
+    use crate::location::SourceLocation;
+
+    /// Root nodes for setup-node (line 4) and changed-files (line 5), each
+    /// with one finding of its own.
+    fn nodes() -> Vec<AuditNode> {
+        nodes_of(
+            "jobs:\n  build:\n    steps:\n      - uses: actions/setup-node@v4\n      - uses: tj-actions/changed-files@v45\n",
+        )
+    }
+
+    fn nodes_of(yaml: &str) -> Vec<AuditNode> {
+        crate::parse_actions_from(yaml, "ci.yml")
+            .unwrap()
+            .into_iter()
+            .map(|action| {
+                AuditNode::from(AuditContext {
+                    findings: vec![Finding::new(&finding::ARCHIVED_ACTION, "archived")],
+                    ..AuditContext::for_test(action)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_entries_and_comments() {
+        let list = IgnoreList::parse(
+            "# accepted risks\n\nGHSA-AAAA-bbbb-cccc reason=\"fixed in our fork #12\" expires=2025-06-30 # comment\nactions/checkout@v4 reason=reviewed\n",
+        )
+        .unwrap();
+        assert_eq!(list.len(), 2);
+        assert!(list.ignores_rule("ghsa-aaaa-bbbb-cccc"));
+        assert!(list.ignores_action(&"actions/checkout@v4".parse().unwrap()));
+        assert!(!list.ignores_action(&"actions/checkout@v5".parse().unwrap()));
+        assert_eq!(
+            list.entries[0].rationale,
+            Rationale {
+                reason: "fixed in our fork #12".to_string(),
+                expires: NaiveDate::from_ymd_opt(2025, 6, 30),
+            }
+        );
+
+        let err = |text| format!("{:#}", IgnoreList::parse(text).unwrap_err());
+        assert_eq!(
+            err("ok reason=x\nunpinned-action actions/checkout\n"),
+            "line 2: `unpinned-action`: expected reason=\"...\" or expires=YYYY-MM-DD, got `actions/checkout`"
+        );
+        assert_eq!(
+            err("default-permissions\n"),
+            "line 1: `default-permissions`: missing reason=\"...\""
+        );
+        assert_eq!(
+            err("x reason=y expires=30/06/2025\n"),
+            "line 1: `x`: invalid expiry date `30/06/2025`, expected YYYY-MM-DD: input contains invalid characters"
+        );
+    }
+
+    #[test]
+    fn starter_is_empty_and_its_examples_parse() {
+        assert!(IgnoreList::parse(STARTER).unwrap().is_empty());
+        let examples: String = STARTER
+            .lines()
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| line.contains("reason="))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        assert_eq!(IgnoreList::parse(&examples).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn action_patterns_match_any_ref_and_wildcards() {
+        let list = IgnoreList::parse(
+            "my-org/* reason=internal\ngithub/codeql-action/*@v3 reason=reviewed\n",
+        )
+        .unwrap();
+        assert!(list.ignores_action(&"My-Org/deploy@main".parse().unwrap()));
+        assert!(list.ignores_action(&"github/codeql-action/analyze@v3".parse().unwrap()));
+        assert!(!list.ignores_action(&"github/codeql-action/analyze@v2".parse().unwrap()));
+        assert!(!list.ignores_action(&"actions/checkout@v4".parse().unwrap()));
+    }
+
+    #[test]
+    fn removes_ignored_advisories_and_findings() {
+        let list =
+            IgnoreList::parse("CVE-2025-0001 reason=a\ndefault-permissions reason=b\ntj-actions/changed-files reason=c\n")
+                .unwrap();
+        let mut nodes = nodes();
+        nodes[0].entry.advisories = vec![
+            Advisory::for_test("GHSA-keep-keep-keep", &[]),
+            Advisory::for_test("GHSA-drop-drop-drop", &["CVE-2025-0001"]),
+        ];
+        nodes[1].entry.advisories = vec![Advisory::for_test("GHSA-mrrh-fwg8-r2c3", &[])];
+        let at = |line| Some(SourceLocation::new(line, 15).with_file("ci.yml"));
+        let mut findings = vec![
+            Finding::new(&finding::UNPINNED_ACTION, "setup-node").with_location(at(4)),
+            Finding::new(&finding::UNPINNED_ACTION, "changed-files").with_location(at(5)),
+            Finding::new(&finding::DEFAULT_PERMISSIONS, "job `build`").with_location(at(2)),
+        ];
+
+        let ignored = list.apply(&mut nodes, &mut findings);
+        assert_eq!(
+            ignored,
+            Ignored {
+                advisories: 2,
+                findings: 3
+            }
+        );
+        let ids: Vec<&str> = nodes[0]
+            .entry
+            .advisories
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect();
+        assert_eq!(ids, ["GHSA-keep-keep-keep"]);
+        assert!(nodes[1].entry.advisories.is_empty());
+        assert_eq!(nodes[0].entry.findings.len(), 1);
+        assert!(nodes[1].entry.findings.is_empty());
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, ["setup-node"]);
+    }
+
+    #[test]
+    fn parses_inline_comments() {
+        let parse = |line| Suppression::parse(line).unwrap();
+        let suppression = parse(
+            "      - uses: a/b@v1 # ghss-ignore: GHSA-x, unpinned-action reason=\"fork is patched\"",
+        )
+        .unwrap();
+        assert_eq!(suppression.ids, ["ghsa-x", "unpinned-action"]);
+        assert_eq!(suppression.rationale.reason, "fork is patched");
+        assert_eq!(suppression.rationale.expires, None);
+
+        let suppression =
+            parse("#ghss-ignore: CVE-2025-0001 expires=2025-01-31 reason=temporary").unwrap();
+        assert_eq!(suppression.ids, ["cve-2025-0001"]);
+        assert_eq!(
+            suppression.rationale.expires,
+            NaiveDate::from_ymd_opt(2025, 1, 31)
+        );
+        assert!(parse("  run: echo '#ghss-ignore: x'").is_none());
+        assert!(parse("  - uses: a/b@v1 # ghss-ignore:").is_none());
+        assert!(parse("  - uses: a/b@v1 # pinned later").is_none());
+
+        let err = InlineSuppressions::parse("a: 1\nb: 2 # ghss-ignore: x\n", "ci.yml").unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "ci.yml:2: invalid ghss-ignore comment: missing reason=\"...\""
+        );
+    }
+
+    #[test]
+    fn inline_comments_suppress_only_their_own_use() {
+        let yaml = "jobs:\n  build:\n    steps:\n      - uses: tj-actions/changed-files@v45 # ghss-ignore: GHSA-mrrh-fwg8-r2c3, unpinned-action reason=\"no secrets here\"\n      - uses: actions/setup-node@v4 # ghss-ignore: cve-2025-0001 reason=\"not reachable\"\n  lint:\n    steps:\n      - uses: actions/setup-node@v4\n";
+        let suppressions = InlineSuppressions::parse(yaml, "ci.yml").unwrap();
+        assert_eq!(suppressions.len(), 2);
+        assert_eq!(
+            suppressions.at(4).unwrap().rationale.reason,
+            "no secrets here"
+        );
+
+        let mut nodes = nodes_of(yaml);
+        let index = |nodes: &[AuditNode], name: &str| {
+            nodes
+                .iter()
+                .position(|n| n.entry.action.package_name() == name)
+                .unwrap()
+        };
+        let changed_files = index(&nodes, "tj-actions/changed-files");
+        let setup_node = index(&nodes, "actions/setup-node");
+        nodes[changed_files].entry.advisories =
+            vec![Advisory::for_test("GHSA-mrrh-fwg8-r2c3", &[])];
+        // Used again on line 8 without a comment, so still reported.
+        nodes[setup_node].entry.advisories = vec![Advisory::for_test(
+            "GHSA-drop-drop-drop",
+            &["CVE-2025-0001"],
+        )];
+
+        let at = |line| Some(SourceLocation::new(line, 15).with_file("ci.yml"));
+        let mut findings = vec![
+            Finding::new(&finding::UNPINNED_ACTION, "changed-files").with_location(at(4)),
+            Finding::new(&finding::UNPINNED_ACTION, "setup-node").with_location(at(5)),
+        ];
+
+        let ignored = suppressions.apply(&mut nodes, &mut findings);
+        assert_eq!(
+            ignored,
+            Ignored {
+                advisories: 1,
+                findings: 1
+            }
+        );
+        assert!(nodes[changed_files].entry.advisories.is_empty());
+        assert_eq!(nodes[setup_node].entry.advisories.len(), 1);
+        // The comment names no `archived-action`, so the action's own finding stays.
+        assert_eq!(nodes[changed_files].entry.findings.len(), 1);
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, ["setup-node"]);
+    }
+
+    #[test]
+    fn expired_suppressions_stop_applying_and_are_reported() {
+        let today = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let mut list = IgnoreList::parse(
+            "GHSA-mrrh-fwg8-r2c3 reason=\"patched in fork\" expires=2025-07-01\ndefault-permissions reason=\"read-only\" expires=2025-07-02\n",
+        )
+        .unwrap()
+        .with_file(".ghssignore");
+        let findings = list.expire(today);
+        assert_eq!(list.len(), 1);
+        assert!(!list.ignores_rule("ghsa-mrrh-fwg8-r2c3"));
+        assert!(list.ignores_rule("default-permissions"));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule_id, "expired-suppression");
+        assert_eq!(
+            findings[0].message,
+            "suppression of `ghsa-mrrh-fwg8-r2c3` expired on 2025-07-01 (patched in fork); it no longer applies"
+        );
+        let location = findings[0].location.as_ref().unwrap();
+        assert_eq!(
+            (location.file.as_deref(), location.line),
+            (Some(".ghssignore"), 1)
+        );
+
+        let yaml = "steps:\n  - uses: a/b@v1 # ghss-ignore: unpinned-action, GHSA-x reason=later expires=2025-06-01\n  - uses: c/d@v1 # ghss-ignore: unpinned-action reason=forever\n";
+        let mut inline = InlineSuppressions::parse(yaml, "ci.yml").unwrap();
+        let findings = inline.expire(today);
+        assert!(inline.at(2).is_none());
+        assert!(inline.at(3).is_some());
+        assert_eq!(
+            findings[0].message,
+            "suppression of `unpinned-action`, `ghsa-x` expired on 2025-06-01 (later); it no longer applies"
+        );
+        assert_eq!(findings[0].location.as_ref().unwrap().line, 2);
+    }
+}
@@ -0,0 +1,401 @@
+//! Offline knowledge about widely used actions: the `GITHUB_TOKEN`
+//! permissions each needs, and inputs that make it riskier to use.
+//!
+//! A curated dataset is bundled from `data/popular-actions.json`. Users can
+//! layer their own file over it, in the same format, to add actions or
+//! correct entries without waiting for a release. Entries can also carry
+//! input rules, which turn a risky input value into a finding.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::Severity;
+use crate::finding::{Finding, RISKY_INPUT};
+use crate::output::AuditNode;
+use crate::rules::glob_match;
+
+const BUNDLED: &str = include_str!("../data/popular-actions.json");
+
+/// Level of access to one token permission scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Access::Read => write!(f, "read"),
+            Access::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// Token permissions by scope, e.g. `contents: read`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Permissions(pub BTreeMap<String, Access>);
+
+impl Permissions {
+    /// Add `other`'s scopes, keeping the higher access where both have one.
+    pub fn merge(&mut self, other: &Permissions) {
+        for (scope, access) in &other.0 {
+            let entry = self.0.entry(scope.clone()).or_insert(*access);
+            *entry = (*entry).max(*access);
+        }
+    }
+}
+
+impl fmt::Display for Permissions {
+    /// e.g. `contents: read, pull-requests: write`, or `none`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+        let scopes: Vec<String> = self
+            .0
+            .iter()
+            .map(|(scope, access)| format!("{scope}: {access}"))
+            .collect();
+        write!(f, "{}", scopes.join(", "))
+    }
+}
+
+/// What is known about one action.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionKnowledge {
+    /// Minimal token permissions. `None` when they depend on how the action
+    /// is used (e.g. `actions/github-script`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Permissions>,
+    /// Input name to why setting it is risky.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub risky_inputs: BTreeMap<String, String>,
+    /// Input values reported as `risky-input` findings where a use sets them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_rules: Vec<InputRule>,
+}
+
+/// A risky value of one input, e.g. a personal access token passed as
+/// `token:`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputRule {
+    pub input: String,
+    /// Globs for the value (`*` matches any run of characters, compared
+    /// case-insensitively); any value matches when the list is empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<String>,
+    #[serde(default = "default_input_severity")]
+    pub severity: Severity,
+    /// Why the value is risky and what to use instead.
+    pub message: String,
+}
+
+fn default_input_severity() -> Severity {
+    Severity::Medium
+}
+
+impl InputRule {
+    fn matches(&self, value: &str) -> bool {
+        self.matches.is_empty() || self.matches.iter().any(|p| glob_match(p, value))
+    }
+}
+
+/// Curated action knowledge, keyed by lowercased `owner/repo[/path]`.
+#[derive(Debug, Clone, Default)]
+pub struct KnowledgeBase {
+    actions: BTreeMap<String, ActionKnowledge>,
+}
+
+#[derive(Deserialize)]
+struct KnowledgeFile {
+    actions: BTreeMap<String, ActionKnowledge>,
+}
+
+impl KnowledgeBase {
+    /// The dataset shipped with ghss.
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED).expect("bundled knowledge base is valid")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: KnowledgeFile = serde_json::from_str(json).context("invalid knowledge base")?;
+        Ok(Self {
+            actions: file
+                .actions
+                .into_iter()
+                .map(|(name, knowledge)| (name.to_ascii_lowercase(), knowledge))
+                .collect(),
+        })
+    }
+
+    /// Layer `other` over this one. Its entries replace whole entries here.
+    pub fn extend(&mut self, other: KnowledgeBase) {
+        self.actions.extend(other.actions);
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Look up an action by `owner/repo/path`, falling back to `owner/repo`
+    /// so sub-actions like `actions/cache/restore` share their repo's entry.
+    pub fn lookup(&self, action: &ActionRef) -> Option<&ActionKnowledge> {
+        self.actions
+            .get(&action.package_name().to_ascii_lowercase())
+            .or_else(|| {
+                action.path.as_ref()?;
+                self.actions
+                    .get(&format!("{}/{}", action.owner, action.repo).to_ascii_lowercase())
+            })
+    }
+
+    /// Attach knowledge to every node in the tree. Nested actions run with
+    /// the same token as the step that calls them, so they count too.
+    pub fn annotate(&self, nodes: &mut [AuditNode]) {
+        for node in nodes {
+            node.entry.knowledge = self.lookup(&node.entry.action).cloned();
+            self.annotate(&mut node.children);
+        }
+    }
+}
+
+/// Raise [`RISKY_INPUT`] for each use in the annotated tree that sets an
+/// input to a value one of its action's [`InputRule`]s matches.
+pub fn check_inputs(nodes: &[AuditNode]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for node in nodes {
+        let action = &node.entry.action;
+        let rules = node
+            .entry
+            .knowledge
+            .as_ref()
+            .map(|k| k.input_rules.as_slice())
+            .unwrap_or_default();
+        for occurrence in &action.occurrences {
+            for rule in rules {
+                let Some(value) = occurrence.with.get(&rule.input) else {
+                    continue;
+                };
+                if !rule.matches(value) {
+                    continue;
+                }
+                let place = match &occurrence.job {
+                    Some(job) => format!(" in job `{job}`"),
+                    None => String::new(),
+                };
+                findings.push(Finding {
+                    rule_id: RISKY_INPUT.id.to_string(),
+                    severity: rule.severity,
+                    message: format!(
+                        "{action}{place} sets `{}: {value}`: {}",
+                        rule.input, rule.message
+                    ),
+                    location: occurrence.location.clone(),
+                });
+            }
+        }
+        findings.extend(check_inputs(&node.children));
+    }
+    findings
+}
+
+/// The least token permissions a workflow's actions need, over the whole
+/// expanded tree.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequiredPermissions {
+    pub permissions: Permissions,
+    /// Actions whose needs are unknown, lowercased and sorted. The
+    /// permissions above are a lower bound while this is non-empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unknown: Vec<String>,
+}
+
+impl RequiredPermissions {
+    /// Combine the knowledge attached by [`KnowledgeBase::annotate`].
+    pub fn from_nodes<'a>(nodes: impl IntoIterator<Item = &'a AuditNode>) -> Self {
+        fn visit(node: &AuditNode, permissions: &mut Permissions, unknown: &mut BTreeSet<String>) {
+            match node
+                .entry
+                .knowledge
+                .as_ref()
+                .and_then(|k| k.permissions.as_ref())
+            {
+                Some(p) => permissions.merge(p),
+                None => {
+                    unknown.insert(node.entry.action.package_name().to_ascii_lowercase());
+                }
+            }
+            for child in &node.children {
+                visit(child, permissions, unknown);
+            }
+        }
+
+        let mut permissions = Permissions::default();
+        let mut unknown = BTreeSet::new();
+        for node in nodes {
+            visit(node, &mut permissions, &mut unknown);
+        }
+        Self {
+            permissions,
+            unknown: unknown.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for RequiredPermissions {
+    /// e.g. `minimal token permissions: contents: read (unknown for: org/tool)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "minimal token permissions: {}", self.permissions)?;
+        if !self.unknown.is_empty() {
+            write!(f, " (unknown for: {})", self.unknown.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(uses: &str, children: Vec<AuditNode>) -> AuditNode {
+        AuditNode {
+            children,
+            ..AuditNode::with_advisories(uses, vec![])
+        }
+    }
+
+    #[test]
+    fn bundled_dataset_parses() {
+        let kb = KnowledgeBase::bundled();
+        assert!(kb.len() > 50);
+        let checkout = kb.lookup(&"actions/checkout@v4".parse().unwrap()).unwrap();
+        assert_eq!(
+            checkout.permissions.as_ref().unwrap().to_string(),
+            "contents: read"
+        );
+        assert!(checkout.risky_inputs.contains_key("persist-credentials"));
+    }
+
+    #[test]
+    fn lookup_is_case_insensitive_and_falls_back_to_repo() {
+        let kb = KnowledgeBase::bundled();
+        assert!(kb.lookup(&"Actions/Checkout@v4".parse().unwrap()).is_some());
+        assert!(
+            kb.lookup(&"actions/cache/restore@v4".parse().unwrap())
+                .is_some()
+        );
+        assert_eq!(
+            kb.lookup(&"github/codeql-action/upload-sarif@v3".parse().unwrap())
+                .and_then(|k| k.permissions.as_ref())
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("security-events: write")
+        );
+        assert!(kb.lookup(&"nobody/nothing@v1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn extend_replaces_entries() {
+        let mut kb = KnowledgeBase::bundled();
+        let before = kb.len();
+        kb.extend(
+            KnowledgeBase::from_json(
+                r#"{"actions": {
+                    "actions/checkout": {"permissions": {"contents": "write"}},
+                    "org/internal": {"permissions": {}}
+                }}"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(kb.len(), before + 1);
+        let checkout = kb.lookup(&"actions/checkout@v4".parse().unwrap()).unwrap();
+        assert!(checkout.risky_inputs.is_empty());
+        assert_eq!(
+            checkout.permissions.as_ref().unwrap().0["contents"],
+            Access::Write
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_access_level() {
+        assert!(
+            KnowledgeBase::from_json(
+                r#"{"actions": {"a/b": {"permissions": {"contents": "admin"}}}}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn input_rules_flag_matching_values() {
+        let yaml = "on: push\njobs:\n  test:\n    steps:\n      - uses: actions/setup-node@v4\n        with:\n          token: ${{ secrets.BOT_PAT }}\n      - uses: actions/setup-go@v5\n        with:\n          token: ${{ github.token }}\n      - uses: org/upload@v1\n        with:\n          key: abc\n";
+        let mut nodes: Vec<AuditNode> = crate::workflow::parse_workflow_refs(yaml)
+            .unwrap()
+            .into_iter()
+            .map(|action| {
+                let mut n = node("a/b@v1", vec![]);
+                n.entry.action = action;
+                n
+            })
+            .collect();
+        let mut kb = KnowledgeBase::bundled();
+        kb.extend(
+            KnowledgeBase::from_json(
+                r#"{"actions": {"org/upload": {"input_rules": [
+                    {"input": "key", "severity": "high", "message": "use OIDC"}
+                ]}}}"#,
+            )
+            .unwrap(),
+        );
+        kb.annotate(&mut nodes);
+
+        let findings: Vec<String> = check_inputs(&nodes)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(findings.len(), 2);
+        assert!(
+            findings[0].starts_with(
+                "risky-input (medium): actions/setup-node@v4 in job `test` sets `token: ${{ secrets.BOT_PAT }}`: only downloads the toolchain"
+            ),
+            "{findings:?}"
+        );
+        assert!(findings[0].ends_with("\n    at 5:15"), "{findings:?}");
+        assert_eq!(
+            findings[1],
+            "risky-input (high): org/upload@v1 in job `test` sets `key: abc`: use OIDC\n    at 11:15"
+        );
+    }
+
+    #[test]
+    fn required_permissions_take_highest_access_across_tree() {
+        let mut nodes = vec![
+            node("actions/checkout@v4", vec![]),
+            node(
+                "org/composite@v1",
+                vec![node("peter-evans/create-pull-request@v6", vec![])],
+            ),
+            node("actions/github-script@v7", vec![]),
+        ];
+        KnowledgeBase::bundled().annotate(&mut nodes);
+        assert!(nodes[1].children[0].entry.knowledge.is_some());
+
+        let required = RequiredPermissions::from_nodes(&nodes);
+        assert_eq!(
+            required.to_string(),
+            "minimal token permissions: contents: write, pull-requests: write \
+             (unknown for: actions/github-script, org/composite)"
+        );
+    }
+}
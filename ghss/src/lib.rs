@@ -1,23 +1,66 @@
 pub mod action_ref;
 pub mod advisory;
+pub mod artifacts;
+pub mod baseline;
+pub mod cache;
+pub mod cel;
+pub mod checkpoint;
+pub mod concurrency;
+pub mod config;
 pub mod context;
+pub mod credentials;
+pub mod cvss;
 pub mod depth;
+pub mod duplicates;
+pub mod epss;
+pub mod explain;
+pub mod finding;
 pub mod github;
+pub mod ignore;
+pub mod knowledge;
+pub mod lint;
+pub mod local;
+pub mod location;
 pub mod output;
+pub mod overrides;
+pub mod permissions;
+pub mod pin;
 pub mod pipeline;
+pub mod policy;
 pub mod providers;
+pub mod remote_script;
+pub mod rules;
+pub mod runners;
+pub mod runtime;
+pub mod scorecard;
+pub mod script;
+pub mod secrets;
+pub mod signing;
 pub mod stages;
+pub mod tag_history;
+pub mod template;
+pub mod trust;
+pub mod typosquat;
+pub mod update;
+pub mod usage;
+pub mod version_range;
+pub mod vex;
 pub mod walker;
 pub mod workflow;
+pub mod workflow_run;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt;
+use std::path::Path;
 use std::str::FromStr;
 
 use anyhow::bail;
 use tracing::debug;
 
-use action_ref::ActionRef;
+use action_ref::{ActionRef, RefType};
+use finding::Finding;
+use lint::Linter;
+use location::{Occurrence, SourceLocation};
 
 /// Specifies which root actions to include, by 1-indexed position.
 ///
@@ -104,21 +147,237 @@ impl FromStr for ActionSelection {
 }
 
 pub fn parse_actions(yaml: &str) -> anyhow::Result<Vec<ActionRef>> {
-    let refs = workflow::parse_workflow(yaml)?;
+    Ok(dedupe_actions(workflow::parse_workflow(yaml)?))
+}
 
-    let unique: BTreeSet<ActionRef> = refs
-        .into_iter()
-        .filter_map(workflow::UsesRef::into_third_party)
+/// Like [`parse_actions`], but records `file` in each action's source location.
+pub fn parse_actions_from(yaml: &str, file: &str) -> anyhow::Result<Vec<ActionRef>> {
+    Ok(dedupe_actions(workflow::parse_workflow_file(yaml, file)?))
+}
+
+/// Third-party actions and workflow-level findings from one workflow file.
+#[derive(Debug)]
+pub struct WorkflowAnalysis {
+    pub actions: Vec<ActionRef>,
+    pub findings: Vec<Finding>,
+    /// Local actions and workflows that could not be read.
+    pub local_errors: Vec<local::LocalError>,
+}
+
+impl WorkflowAnalysis {
+    /// Keep the root actions `selection` includes (0-indexed positions in
+    /// `actions`), and drop the `unpinned-action` findings at the uses of
+    /// the others, so a deselected action is not reported at all.
+    pub fn select(&mut self, selection: &ActionSelection) {
+        let (kept, dropped): (Vec<_>, Vec<_>) = std::mem::take(&mut self.actions)
+            .into_iter()
+            .enumerate()
+            .partition(|(i, _)| selection.includes(*i));
+        let dropped_at: HashSet<&SourceLocation> = dropped
+            .iter()
+            .flat_map(|(_, action)| &action.occurrences)
+            .filter_map(|occurrence| occurrence.location.as_ref())
+            .collect();
+        self.findings.retain(|f| {
+            f.rule_id != finding::UNPINNED_ACTION.id
+                || !f.location.as_ref().is_some_and(|l| dropped_at.contains(l))
+        });
+        self.actions = kept.into_iter().map(|(_, action)| action).collect();
+    }
+}
+
+/// Parse a workflow and collect both its auditable actions (deduplicated, as
+/// in [`parse_actions_from`]) and findings about the workflow itself.
+pub fn analyze_workflow(yaml: &str, file: &str) -> anyhow::Result<WorkflowAnalysis> {
+    let mut analysis = analyze_refs(workflow::parse_workflow_file(yaml, file)?);
+    analysis.findings.extend(Linter::default().run(yaml, file)?);
+    Ok(analysis)
+}
+
+/// Like [`analyze_workflow`], but `./path` actions and reusable workflows are
+/// read from the checkout at `repo_root` and their refs analyzed in their place,
+/// and only the rules `linter` leaves enabled are reported.
+pub fn analyze_workflow_in(
+    yaml: &str,
+    file: &str,
+    repo_root: &Path,
+    linter: &Linter,
+) -> anyhow::Result<WorkflowAnalysis> {
+    let refs = workflow::parse_workflow_file(yaml, file)?;
+    let (refs, local_errors) = local::expand_local_refs(refs, repo_root);
+    let mut analysis = analyze_refs(refs);
+    analysis.local_errors = local_errors;
+    linter.retain_enabled(&mut analysis.findings);
+    analysis.findings.extend(linter.run(yaml, file)?);
+    Ok(analysis)
+}
+
+fn analyze_refs(refs: Vec<workflow::UsesRef>) -> WorkflowAnalysis {
+    let mut findings: Vec<Finding> = refs
+        .iter()
+        .filter_map(|r| match r {
+            workflow::UsesRef::Dynamic { raw, location } => Some(
+                Finding::new(
+                    &finding::DYNAMIC_USES,
+                    format!("dynamic action reference `{raw}` cannot be audited"),
+                )
+                .with_location(location.clone()),
+            ),
+            _ => None,
+        })
         .collect();
+    findings.extend(refs.iter().filter_map(|r| match r {
+        workflow::UsesRef::ThirdParty(action) => unpinned_finding(action),
+        _ => None,
+    }));
+    let third_party = || {
+        refs.iter().filter_map(|r| match r {
+            workflow::UsesRef::ThirdParty(action) => Some(action),
+            _ => None,
+        })
+    };
+    findings.extend(typosquat::check_typosquats(third_party()));
+    findings.extend(script::check_github_scripts(third_party()));
+
+    WorkflowAnalysis {
+        actions: dedupe_actions(refs),
+        findings,
+        local_errors: Vec::new(),
+    }
+}
+
+/// An `unpinned-action` finding unless `action` is pinned to a full SHA.
+fn unpinned_finding(action: &ActionRef) -> Option<Finding> {
+    let kind = match action.ref_type {
+        RefType::Sha => return None,
+        RefType::Tag => "tag",
+        RefType::Unknown => "branch or tag",
+    };
+    let message = format!(
+        "{} is referenced by {kind} `{}`, not a full commit SHA",
+        action.package_name(),
+        action.git_ref
+    );
+    Some(Finding::new(&finding::UNPINNED_ACTION, message).with_location(action.location.clone()))
+}
+
+fn dedupe_actions(refs: Vec<workflow::UsesRef>) -> Vec<ActionRef> {
+    let unique = merge_actions(
+        refs.into_iter()
+            .filter_map(workflow::UsesRef::into_third_party),
+    );
 
     debug!(count = unique.len(), "parsed unique third-party actions");
-    Ok(unique.into_iter().collect())
+    unique
+}
+
+/// Deduplicate actions, keeping the first of each (its `location` stays the
+/// primary one) and concatenating the `occurrences` of all duplicates.
+/// Returns actions in sorted order.
+pub fn merge_actions(actions: impl IntoIterator<Item = ActionRef>) -> Vec<ActionRef> {
+    let mut merged: BTreeMap<ActionRef, Vec<Occurrence>> = BTreeMap::new();
+    for mut action in actions {
+        let occurrences = std::mem::take(&mut action.occurrences);
+        merged.entry(action).or_default().extend(occurrences);
+    }
+
+    merged
+        .into_iter()
+        .map(|(mut action, occurrences)| {
+            action.occurrences = occurrences;
+            action
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_actions_merges_occurrences_of_duplicates() {
+        let yaml = "jobs:\n  a:\n    steps:\n      - uses: x/y@v1\n  b:\n    uses: x/y@v1\n  c:\n    steps:\n      - uses: z/w@v2\n";
+        let actions = parse_actions(yaml).unwrap();
+        assert_eq!(actions.len(), 2);
+
+        let xy = &actions[0];
+        assert_eq!(xy.to_string(), "x/y@v1");
+        assert_eq!(xy.location.as_ref().unwrap().line, 4);
+        let jobs: Vec<_> = xy.occurrences.iter().map(|o| o.job.as_deref()).collect();
+        assert_eq!(jobs, [Some("a"), Some("b")]);
+        let lines: Vec<_> = xy
+            .occurrences
+            .iter()
+            .map(|o| o.location.as_ref().unwrap().line)
+            .collect();
+        assert_eq!(lines, [4, 6]);
+
+        assert_eq!(actions[1].occurrences.len(), 1);
+    }
+
+    #[test]
+    fn analyze_workflow_reports_dynamic_uses() {
+        let yaml = "jobs:\n  a:\n    strategy:\n      matrix:\n        action: [x/y@v1, z/w@v2]\n    steps:\n      - uses: ${{ matrix.action }}\n      - uses: x/y@v1\n";
+        let analysis = analyze_workflow(yaml, "ci.yml").unwrap();
+        assert_eq!(analysis.actions.len(), 1);
+        // The dynamic ref, then `x/y@v1` as unpinned.
+        assert_eq!(analysis.findings.len(), 2);
+
+        let finding = &analysis.findings[0];
+        assert_eq!(finding.rule_id, "dynamic-uses");
+        assert!(finding.message.contains("${{ matrix.action }}"));
+        assert_eq!(
+            finding.location.as_ref().unwrap().to_string(),
+            "ci.yml:7:15"
+        );
+    }
+
+    #[test]
+    fn analyze_workflow_reports_each_unpinned_use() {
+        let yaml = "jobs:\n  a:\n    steps:\n      - uses: x/y@v1\n      - uses: x/y@main\n      - uses: x/y@11bd71901bbe5b1630ceea73d27597364c9af683\n      - uses: x/y@v1\n";
+        let analysis = analyze_workflow(yaml, "ci.yml").unwrap();
+        let unpinned: Vec<String> = analysis
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "unpinned-action")
+            .map(|f| f.to_string())
+            .collect();
+        assert_eq!(
+            unpinned,
+            [
+                "unpinned-action (medium): x/y is referenced by tag `v1`, not a full commit SHA\n    at ci.yml:4:15",
+                "unpinned-action (medium): x/y is referenced by branch or tag `main`, not a full commit SHA\n    at ci.yml:5:15",
+                "unpinned-action (medium): x/y is referenced by tag `v1`, not a full commit SHA\n    at ci.yml:7:15",
+            ]
+        );
+    }
+
+    #[test]
+    fn analyze_workflow_reports_job_setup() {
+        let yaml = "on: pull_request\njobs:\n  a:\n    runs-on: self-hosted\n    steps:\n      - run: make\n";
+        let analysis = analyze_workflow(yaml, "ci.yml").unwrap();
+        let rules: Vec<&str> = analysis
+            .findings
+            .iter()
+            .map(|f| f.rule_id.as_str())
+            .collect();
+        assert_eq!(rules, ["self-hosted-runner"]);
+    }
+
+    #[test]
+    fn merge_actions_combines_across_files() {
+        let a = parse_actions_from("jobs:\n  a:\n    uses: x/y@v1\n", "a.yml").unwrap();
+        let b = parse_actions_from("jobs:\n  b:\n    uses: x/y@v1\n", "b.yml").unwrap();
+        let merged = merge_actions(a.into_iter().chain(b));
+        assert_eq!(merged.len(), 1);
+        let files: Vec<_> = merged[0]
+            .occurrences
+            .iter()
+            .map(|o| o.location.as_ref().unwrap().file.as_deref().unwrap())
+            .collect();
+        assert_eq!(files, ["a.yml", "b.yml"]);
+    }
+
     #[test]
     fn action_selection_parse_all() {
         assert_eq!(
@@ -0,0 +1,143 @@
+//! The static checks run over each workflow file, and which of them are on.
+//!
+//! Each check in [`LINTS`] reads the workflow YAML and raises findings of
+//! one [`Rule`]. A [`Linter`] runs them all except those disabled by id;
+//! the same ids filter findings of every other built-in rule, so one
+//! `disable:` list in the rules file turns any rule off.
+
+use std::collections::BTreeSet;
+
+use anyhow::{Result, bail};
+
+use crate::finding::{self, Finding, Rule};
+use crate::{artifacts, cache, credentials, remote_script, runners, secrets, workflow_run};
+
+/// A check over one workflow file: its YAML and the path findings report.
+pub type Check = fn(&str, &str) -> Result<Vec<Finding>>;
+
+/// A check and the rule its findings belong to.
+pub struct Lint {
+    pub rule: &'static Rule,
+    pub check: Check,
+}
+
+/// Every workflow check, in the order their findings are reported.
+pub const LINTS: &[Lint] = &[
+    Lint {
+        rule: &finding::SELF_HOSTED_RUNNER,
+        check: runners::check_self_hosted,
+    },
+    Lint {
+        rule: &finding::CACHE_POISONING,
+        check: cache::check_cache_poisoning,
+    },
+    Lint {
+        rule: &finding::ARTIFACT_POISONING,
+        check: artifacts::check_artifact_poisoning,
+    },
+    Lint {
+        rule: &finding::PERSISTED_CREDENTIALS,
+        check: credentials::check_persisted_credentials,
+    },
+    Lint {
+        rule: &finding::HARDCODED_SECRET,
+        check: secrets::check_hardcoded_secrets,
+    },
+    Lint {
+        rule: &finding::WORKFLOW_RUN_ACTION,
+        check: workflow_run::check_workflow_run_actions,
+    },
+    Lint {
+        rule: &finding::REMOTE_SCRIPT,
+        check: remote_script::check_remote_scripts,
+    },
+];
+
+/// Runs the enabled [`LINTS`] and filters findings of disabled rules.
+#[derive(Debug, Clone, Default)]
+pub struct Linter {
+    disabled: BTreeSet<String>,
+}
+
+impl Linter {
+    /// Turn off the built-in rules with these ids. Fails on an id no
+    /// built-in rule has, so a typo doesn't silently leave a rule on.
+    pub fn with_disabled(
+        mut self,
+        ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self> {
+        for id in ids {
+            let id = id.into();
+            if finding::rule(&id).is_none() {
+                bail!("cannot disable unknown rule {id}");
+            }
+            self.disabled.insert(id);
+        }
+        Ok(self)
+    }
+
+    pub fn is_enabled(&self, rule_id: &str) -> bool {
+        !self.disabled.contains(rule_id)
+    }
+
+    /// Findings of every enabled check over one workflow file.
+    pub fn run(&self, yaml: &str, file: &str) -> Result<Vec<Finding>> {
+        let mut findings = Vec::new();
+        for lint in LINTS.iter().filter(|l| self.is_enabled(l.rule.id)) {
+            findings.extend((lint.check)(yaml, file)?);
+        }
+        Ok(findings)
+    }
+
+    /// Drop findings of disabled rules.
+    pub fn retain_enabled(&self, findings: &mut Vec<Finding>) {
+        findings.retain(|f| self.is_enabled(&f.rule_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_lint_is_a_known_rule() {
+        for lint in LINTS {
+            assert!(finding::rule(lint.rule.id).is_some(), "{}", lint.rule.id);
+        }
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped_and_filtered() {
+        let yaml = "on: pull_request\njobs:\n  test:\n    runs-on: self-hosted\n    steps:\n      - uses: actions/checkout@v4\n        with:\n          persist-credentials: false\n      - run: curl -fsSL https://example.com/x.sh | bash\n";
+        let ids = |linter: &Linter| -> Vec<String> {
+            linter
+                .run(yaml, "ci.yml")
+                .unwrap()
+                .into_iter()
+                .map(|f| f.rule_id)
+                .collect()
+        };
+        assert_eq!(
+            ids(&Linter::default()),
+            ["self-hosted-runner", "remote-script"]
+        );
+
+        let linter = Linter::default()
+            .with_disabled(["self-hosted-runner", "unpinned-action"])
+            .unwrap();
+        assert_eq!(ids(&linter), ["remote-script"]);
+
+        let mut findings = vec![
+            Finding::new(&finding::UNPINNED_ACTION, "x"),
+            Finding::new(&finding::DYNAMIC_USES, "y"),
+        ];
+        linter.retain_enabled(&mut findings);
+        assert_eq!(findings[0].rule_id, "dynamic-uses");
+        assert_eq!(findings.len(), 1);
+
+        let err = Linter::default()
+            .with_disabled(["no-such-rule"])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "cannot disable unknown rule no-such-rule");
+    }
+}
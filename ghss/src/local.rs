@@ -0,0 +1,177 @@
+//! Local `./path` actions and reusable workflows.
+//!
+//! A `uses: ./path` ref names an action (or, at job level, a reusable
+//! workflow) in the same repository, resolved against the repository root.
+//! It has no owner or ref of its own to audit, but the third-party actions it
+//! calls run all the same, so the local file is read from the checkout and
+//! its refs take its place.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use tracing::warn;
+
+use crate::workflow::{self, UsesRef};
+
+/// Action metadata file names, in the order GitHub looks for them.
+const ACTION_FILES: &[&str] = &["action.yml", "action.yaml"];
+
+/// A local action or workflow that could not be read, so the actions it
+/// calls went unaudited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalError {
+    /// The `uses:` value, e.g. `./local-action`.
+    pub uses: String,
+    pub message: String,
+}
+
+/// Replace every local ref in `refs` with the refs found in the local action
+/// or workflow, recursively. Locations of the expanded refs are relative to
+/// `repo_root`. Local actions that cannot be read are logged, dropped, and
+/// returned as errors.
+pub fn expand_local_refs(refs: Vec<UsesRef>, repo_root: &Path) -> (Vec<UsesRef>, Vec<LocalError>) {
+    let mut errors = Vec::new();
+    let refs = expand(refs, repo_root, &mut HashSet::new(), &mut errors);
+    (refs, errors)
+}
+
+fn expand(
+    refs: Vec<UsesRef>,
+    repo_root: &Path,
+    visited: &mut HashSet<PathBuf>,
+    errors: &mut Vec<LocalError>,
+) -> Vec<UsesRef> {
+    let mut expanded = Vec::new();
+    for uses in refs {
+        let UsesRef::Local(raw) = uses else {
+            expanded.push(uses);
+            continue;
+        };
+        let path = local_path(&raw);
+        // Each local file is expanded once; repeats add nothing new, and a
+        // cycle would never end.
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        match read_local(repo_root, &path) {
+            Ok(children) => expanded.extend(expand(children, repo_root, visited, errors)),
+            Err(e) => {
+                let message = format!("{e:#}");
+                warn!(uses = %raw, error = %message, "skipping local action");
+                errors.push(LocalError { uses: raw, message });
+            }
+        }
+    }
+    expanded
+}
+
+/// `./path/to/action/` → `path/to/action`.
+fn local_path(raw: &str) -> PathBuf {
+    Path::new(raw)
+        .components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+fn is_workflow_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"))
+}
+
+fn read_local(repo_root: &Path, path: &Path) -> Result<Vec<UsesRef>> {
+    if is_workflow_file(path) {
+        let file = path.to_string_lossy();
+        let yaml = std::fs::read_to_string(repo_root.join(path))
+            .with_context(|| format!("failed to read {file}"))?;
+        return workflow::parse_workflow_file(&yaml, &file);
+    }
+
+    for name in ACTION_FILES {
+        let file = path.join(name);
+        if let Ok(yaml) = std::fs::read_to_string(repo_root.join(&file)) {
+            let file = file.to_string_lossy();
+            // Node and Docker actions have no steps, so nothing to expand.
+            return Ok(workflow::parse_composite_action_file(&yaml, &file)
+                .with_context(|| format!("failed to parse {file}"))?
+                .unwrap_or_default());
+        }
+    }
+    bail!("no action.yml or action.yaml in {}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn third_party(refs: &[UsesRef]) -> Vec<String> {
+        refs.iter()
+            .filter_map(|r| match r {
+                UsesRef::ThirdParty(a) => Some(format!(
+                    "{a} at {}",
+                    a.location
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default()
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn local_path_strips_leading_dot() {
+        assert_eq!(
+            local_path("./.github/actions/setup/"),
+            Path::new(".github/actions/setup")
+        );
+        assert_eq!(local_path("./"), Path::new(""));
+    }
+
+    #[test]
+    fn expands_nested_local_actions_and_workflows() {
+        let root = std::env::temp_dir().join(format!("ghss-local-{}", std::process::id()));
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write(
+            ".github/actions/setup/action.yml",
+            "runs:\n  using: composite\n  steps:\n    - uses: actions/setup-node@v4\n    - uses: ./.github/actions/cache\n    - uses: ./.github/actions/setup\n",
+        );
+        write(
+            ".github/actions/cache/action.yaml",
+            "runs:\n  using: composite\n  steps:\n    - uses: actions/cache@v4\n",
+        );
+        write(
+            ".github/workflows/reusable.yml",
+            "jobs:\n  a:\n    steps:\n      - uses: org/deploy@v1\n",
+        );
+
+        let refs = workflow::parse_workflow(
+            "jobs:\n  a:\n    steps:\n      - uses: actions/checkout@v4\n      - uses: ./.github/actions/setup\n      - uses: ./missing\n  b:\n    uses: ./.github/workflows/reusable.yml\n",
+        )
+        .unwrap();
+        let (expanded, errors) = expand_local_refs(refs, &root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(!expanded.iter().any(|r| matches!(r, UsesRef::Local(_))));
+        assert_eq!(
+            third_party(&expanded),
+            [
+                "actions/checkout@v4 at 4:15",
+                "actions/setup-node@v4 at .github/actions/setup/action.yml:4:13",
+                "actions/cache@v4 at .github/actions/cache/action.yaml:4:13",
+                "org/deploy@v1 at .github/workflows/reusable.yml:4:15",
+            ]
+        );
+        assert_eq!(
+            errors,
+            [LocalError {
+                uses: "./missing".into(),
+                message: "no action.yml or action.yaml in missing".into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,519 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+use crate::workflow::CallArgs;
+
+/// A position within a source file. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SourceLocation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self {
+            file: None,
+            line,
+            column,
+        }
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// One appearance of a `uses:` ref in a workflow or composite action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Occurrence {
+    /// The job containing the ref. `None` for composite action steps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<String>,
+    /// The step's `name:`, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+    /// For a job-level reusable workflow call, what the job passes to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call: Option<CallArgs>,
+    /// The step's `with:` inputs that are plain strings. Kept for checks on
+    /// how the action is used; not serialized.
+    #[serde(skip)]
+    pub with: BTreeMap<String, String>,
+}
+
+impl fmt::Display for Occurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.location, &self.job) {
+            (Some(loc), Some(job)) => write!(f, "{loc} (job {job})"),
+            (Some(loc), None) => write!(f, "{loc}"),
+            (None, Some(job)) => write!(f, "job {job}"),
+            (None, None) => write!(f, "unknown location"),
+        }
+    }
+}
+
+/// Per-value queues of source locations, consumed in document order.
+///
+/// Serde-based parsing discards positions, so callers look up the location
+/// of each extracted value here. Repeated values yield successive locations.
+#[derive(Debug, Default)]
+pub(crate) struct LocationIndex {
+    by_value: HashMap<String, VecDeque<SourceLocation>>,
+}
+
+impl LocationIndex {
+    /// Index the scalar values of every mapping entry named `key` under the
+    /// top-level `root` entry of `yaml` (`jobs` for a workflow, `runs` for an
+    /// action), where serde reads them. Returns an empty index if the
+    /// document cannot be tokenized.
+    pub fn for_key(yaml: &str, root: &str, key: &str) -> Self {
+        let mut collector = KeyValueCollector::new(root, key);
+        let mut parser = Parser::new_from_str(yaml);
+        if parser.load(&mut collector, false).is_err() {
+            return Self::default();
+        }
+
+        let mut by_value: HashMap<String, VecDeque<SourceLocation>> = HashMap::new();
+        for (value, location) in collector.found {
+            by_value.entry(value).or_default().push_back(location);
+        }
+        Self { by_value }
+    }
+
+    /// Pop the next location recorded for `value`.
+    pub fn take(&mut self, value: &str) -> Option<SourceLocation> {
+        self.by_value.get_mut(value)?.pop_front()
+    }
+}
+
+/// Positions of text inside values, found by searching the raw YAML.
+///
+/// For text within a scalar, such as a word of a `run:` script, which the
+/// YAML parser cannot point at. Repeated text yields successive locations.
+pub(crate) struct TextIndex<'a> {
+    yaml: &'a str,
+    seen: HashMap<String, usize>,
+}
+
+impl<'a> TextIndex<'a> {
+    pub fn new(yaml: &'a str) -> Self {
+        Self {
+            yaml,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Location of the next occurrence of `text`.
+    pub fn locate(&mut self, text: &str) -> Option<SourceLocation> {
+        let nth = self.seen.entry(text.to_string()).or_default();
+        let (offset, _) = self.yaml.match_indices(text).nth(*nth)?;
+        *nth += 1;
+        let before = &self.yaml[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+            .chars()
+            .count()
+            + 1;
+        Some(SourceLocation::new(line, column))
+    }
+}
+
+/// Values reachable through a YAML anchor.
+enum Anchored {
+    /// An anchored scalar that was itself a `key` value.
+    Scalar(String),
+    /// Every `key` value found inside an anchored collection.
+    Collection(Vec<String>),
+}
+
+/// Event receiver that records `key: <scalar>` pairs under the top-level
+/// `root` entry, with the scalar's position.
+///
+/// Values reached through an alias (`*name`) are recorded at the alias site,
+/// so each expanded occurrence gets its own location. An anchor defined
+/// outside `root` (say `x-step: &co {uses: ...}`) is not a use itself: its
+/// values are only remembered for its aliases.
+struct KeyValueCollector<'a> {
+    root: &'a str,
+    key: &'a str,
+    /// One frame per open collection: `Some(expecting_key)` for mappings, `None` for sequences.
+    stack: Vec<Option<bool>>,
+    /// Set when the last mapping key matched `key` and its value is pending.
+    pending: bool,
+    /// Whether the current top-level entry is `root`.
+    in_root: bool,
+    /// Every `key` value seen, in or out of `root`, for anchored collections.
+    seen: Vec<String>,
+    found: Vec<(String, SourceLocation)>,
+    anchors: HashMap<usize, Anchored>,
+    /// Open anchored collections: (anchor id, stack depth, index into `seen`).
+    open_anchors: Vec<(usize, usize, usize)>,
+}
+
+impl<'a> KeyValueCollector<'a> {
+    fn new(root: &'a str, key: &'a str) -> Self {
+        Self {
+            root,
+            key,
+            stack: Vec::new(),
+            pending: false,
+            in_root: false,
+            seen: Vec::new(),
+            found: Vec::new(),
+            anchors: HashMap::new(),
+            open_anchors: Vec::new(),
+        }
+    }
+
+    fn open_collection(&mut self, frame: Option<bool>, anchor_id: usize) {
+        self.pending = false;
+        self.advance();
+        self.stack.push(frame);
+        if anchor_id > 0 {
+            self.open_anchors
+                .push((anchor_id, self.stack.len(), self.seen.len()));
+        }
+    }
+
+    fn close_collection(&mut self) {
+        if let Some(&(anchor_id, depth, start)) = self.open_anchors.last()
+            && depth == self.stack.len()
+        {
+            self.open_anchors.pop();
+            let values = self.seen[start..].to_vec();
+            self.anchors.insert(anchor_id, Anchored::Collection(values));
+        }
+        self.stack.pop();
+    }
+
+    /// Note a `key` value, and record it if it is under `root`.
+    fn record(&mut self, value: String, location: &SourceLocation) {
+        if self.in_root {
+            self.found.push((value.clone(), location.clone()));
+        }
+        self.seen.push(value);
+    }
+
+    /// Advance the enclosing mapping's key/value alternation. Returns true if
+    /// the node just consumed was in key position.
+    fn advance(&mut self) -> bool {
+        match self.stack.last_mut() {
+            Some(Some(expecting_key)) => {
+                let was_key = *expecting_key;
+                *expecting_key = !was_key;
+                was_key
+            }
+            _ => false,
+        }
+    }
+}
+
+impl MarkedEventReceiver for KeyValueCollector<'_> {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        let location = SourceLocation::new(mark.line(), mark.col() + 1);
+        match ev {
+            Event::Scalar(value, _, anchor_id, _) => {
+                let pending = std::mem::take(&mut self.pending);
+                if self.advance() {
+                    if self.stack.len() == 1 {
+                        self.in_root = value == self.root;
+                    }
+                    self.pending = value == self.key;
+                } else if pending {
+                    if anchor_id > 0 {
+                        self.anchors
+                            .insert(anchor_id, Anchored::Scalar(value.clone()));
+                    }
+                    self.record(value, &location);
+                }
+            }
+            Event::Alias(anchor_id) => {
+                let pending = std::mem::take(&mut self.pending);
+                if self.advance() {
+                    return;
+                }
+                let values = match self.anchors.get(&anchor_id) {
+                    Some(Anchored::Scalar(value)) if pending => vec![value.clone()],
+                    Some(Anchored::Collection(values)) => values.clone(),
+                    _ => return,
+                };
+                for value in values {
+                    self.record(value, &location);
+                }
+            }
+            Event::MappingStart(anchor_id, _) => self.open_collection(Some(true), anchor_id),
+            Event::SequenceStart(anchor_id, _) => self.open_collection(None, anchor_id),
+            Event::MappingEnd | Event::SequenceEnd => self.close_collection(),
+            _ => {}
+        }
+    }
+}
+
+/// Locations of the keys of the mapping reached by following `path` from the
+/// document root, e.g. `&["jobs"]` for job names. Keys reached through an
+/// alias are not recorded. Empty if the document cannot be tokenized.
+pub(crate) fn key_locations(yaml: &str, path: &[&str]) -> HashMap<String, SourceLocation> {
+    let mut collector = KeyCollector {
+        path,
+        frames: Vec::new(),
+        found: HashMap::new(),
+    };
+    let mut parser = Parser::new_from_str(yaml);
+    if parser.load(&mut collector, false).is_err() {
+        return HashMap::new();
+    }
+    collector.found
+}
+
+/// One open collection: the mapping key currently being read (`None` while a
+/// key is expected), or `None` for sequences.
+enum Frame {
+    Mapping { key: Option<String> },
+    Sequence,
+}
+
+/// Event receiver that records the keys of the mapping at `path`.
+struct KeyCollector<'a> {
+    path: &'a [&'a str],
+    frames: Vec<Frame>,
+    found: HashMap<String, SourceLocation>,
+}
+
+impl KeyCollector<'_> {
+    /// Whether the innermost open collection is the mapping at `path`.
+    fn at_path(&self) -> bool {
+        let Some((Frame::Mapping { .. }, outer)) = self.frames.split_last() else {
+            return false;
+        };
+        outer.len() == self.path.len()
+            && outer.iter().zip(self.path).all(
+                |(frame, want)| matches!(frame, Frame::Mapping { key: Some(key) } if key == want),
+            )
+    }
+
+    /// Consume one node. Returns true if it was in key position.
+    fn consume(&mut self, scalar: Option<&str>) -> bool {
+        // A value keeps its key until it is finished, so nested
+        // collections see the key in their path.
+        if let Some(Frame::Mapping { key }) = self.frames.last_mut()
+            && key.is_none()
+        {
+            *key = Some(scalar.unwrap_or_default().to_string());
+            return true;
+        }
+        false
+    }
+
+    /// Mark the pending value of the innermost mapping as read.
+    fn finish_value(&mut self) {
+        if let Some(Frame::Mapping { key }) = self.frames.last_mut() {
+            *key = None;
+        }
+    }
+}
+
+impl MarkedEventReceiver for KeyCollector<'_> {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        let scalar = match ev {
+            Event::Scalar(value, ..) => Some(value),
+            Event::Alias(_) => None,
+            Event::MappingStart(..) => {
+                self.consume(None);
+                self.frames.push(Frame::Mapping { key: None });
+                return;
+            }
+            Event::SequenceStart(..) => {
+                self.consume(None);
+                self.frames.push(Frame::Sequence);
+                return;
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.frames.pop();
+                self.finish_value();
+                return;
+            }
+            _ => return,
+        };
+        let at_path = self.at_path();
+        if !self.consume(scalar.as_deref()) {
+            self.finish_value();
+        } else if at_path && let Some(key) = scalar {
+            self.found
+                .entry(key)
+                .or_insert_with(|| SourceLocation::new(mark.line(), mark.col() + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_block_style_values() {
+        let yaml = "jobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n      - run: make\n      - uses: actions/setup-node@v4\n";
+        let mut index = LocationIndex::for_key(yaml, "jobs", "uses");
+        assert_eq!(
+            index.take("actions/checkout@v4"),
+            Some(SourceLocation::new(4, 15))
+        );
+        assert_eq!(
+            index.take("actions/setup-node@v4"),
+            Some(SourceLocation::new(6, 15))
+        );
+        assert_eq!(index.take("actions/checkout@v4"), None);
+    }
+
+    #[test]
+    fn key_locations_follow_path() {
+        let yaml = "on: push\npermissions: write-all\njobs:\n  build:\n    permissions:\n      contents: read\n    steps:\n      - uses: x/y@v1\n  \"test\":\n    runs-on: [self-hosted]\n";
+        let top = key_locations(yaml, &[]);
+        assert_eq!(top["permissions"], SourceLocation::new(2, 1));
+        assert_eq!(top.len(), 3);
+
+        let jobs = key_locations(yaml, &["jobs"]);
+        assert_eq!(jobs["build"], SourceLocation::new(4, 3));
+        assert_eq!(jobs["test"], SourceLocation::new(9, 3));
+        assert_eq!(jobs.len(), 2);
+
+        let build = key_locations(yaml, &["jobs", "build"]);
+        assert_eq!(build["permissions"], SourceLocation::new(5, 5));
+        assert!(!build.contains_key("contents"));
+        assert!(key_locations(yaml, &["jobs", "nope"]).is_empty());
+    }
+
+    #[test]
+    fn text_index_finds_successive_occurrences() {
+        let yaml = "run: |\n  curl x | sh\n  echo é; curl x | sh\n";
+        let mut index = TextIndex::new(yaml);
+        assert_eq!(index.locate("curl x"), Some(SourceLocation::new(2, 3)));
+        assert_eq!(index.locate("curl x"), Some(SourceLocation::new(3, 11)));
+        assert_eq!(index.locate("curl x"), None);
+    }
+
+    #[test]
+    fn repeated_values_yield_locations_in_document_order() {
+        let yaml = "jobs:\n  a:\n    uses: x/y@v1\n  b:\n    uses: x/y@v1\n";
+        let mut index = LocationIndex::for_key(yaml, "jobs", "uses");
+        assert_eq!(index.take("x/y@v1").unwrap().line, 3);
+        assert_eq!(index.take("x/y@v1").unwrap().line, 5);
+    }
+
+    #[test]
+    fn ignores_matching_text_in_value_position() {
+        let yaml = "jobs:\n  name: uses\n  step:\n    run: uses\n";
+        let mut index = LocationIndex::for_key(yaml, "jobs", "uses");
+        assert_eq!(index.take("uses"), None);
+    }
+
+    #[test]
+    fn quoted_values_point_at_opening_quote() {
+        let yaml = "runs:\n  steps:\n    - uses: \"actions/checkout@v4\"\n";
+        let mut index = LocationIndex::for_key(yaml, "runs", "uses");
+        assert_eq!(
+            index.take("actions/checkout@v4"),
+            Some(SourceLocation::new(3, 13))
+        );
+    }
+
+    #[test]
+    fn aliased_collections_record_alias_site() {
+        let yaml = "\
+defaults: &setup
+  - uses: actions/checkout@v4
+  - uses: actions/setup-node@v4
+jobs:
+  a:
+    steps: *setup
+";
+        let mut index = LocationIndex::for_key(yaml, "jobs", "uses");
+        assert_eq!(
+            index.take("actions/checkout@v4"),
+            Some(SourceLocation::new(6, 12))
+        );
+        assert_eq!(
+            index.take("actions/setup-node@v4"),
+            Some(SourceLocation::new(6, 12))
+        );
+        assert_eq!(index.take("actions/checkout@v4"), None);
+    }
+
+    #[test]
+    fn anchors_outside_root_are_not_uses() {
+        let yaml = "\
+x-step: &co {uses: actions/checkout@v4}
+jobs:
+  a:
+    steps:
+      - *co
+  b:
+    steps:
+      - *co
+";
+        let mut index = LocationIndex::for_key(yaml, "jobs", "uses");
+        assert_eq!(index.take("actions/checkout@v4").unwrap().line, 5);
+        assert_eq!(index.take("actions/checkout@v4").unwrap().line, 8);
+        assert_eq!(index.take("actions/checkout@v4"), None);
+    }
+
+    #[test]
+    fn aliased_scalar_values_record_alias_site() {
+        let yaml = "jobs:\n  a:\n    uses: &co actions/checkout@v4\n  b:\n    uses: *co\n  c:\n    name: *co\n";
+        let mut index = LocationIndex::for_key(yaml, "jobs", "uses");
+        assert_eq!(index.take("actions/checkout@v4").unwrap().line, 3);
+        assert_eq!(index.take("actions/checkout@v4").unwrap().line, 5);
+        assert_eq!(index.take("actions/checkout@v4"), None);
+    }
+
+    #[test]
+    fn invalid_yaml_yields_empty_index() {
+        let mut index = LocationIndex::for_key("not: [valid: yaml: {{{", "jobs", "uses");
+        assert_eq!(index.take("anything"), None);
+    }
+
+    #[test]
+    fn occurrence_display() {
+        let occ = Occurrence {
+            job: Some("build".to_string()),
+            step: None,
+            location: Some(SourceLocation::new(8, 15).with_file("ci.yml")),
+            call: None,
+            with: BTreeMap::new(),
+        };
+        assert_eq!(occ.to_string(), "ci.yml:8:15 (job build)");
+        let occ = Occurrence {
+            job: None,
+            step: None,
+            location: None,
+            call: None,
+            with: BTreeMap::new(),
+        };
+        assert_eq!(occ.to_string(), "unknown location");
+    }
+
+    #[test]
+    fn display_includes_file_when_present() {
+        let loc = SourceLocation::new(3, 7);
+        assert_eq!(loc.to_string(), "3:7");
+        assert_eq!(loc.with_file("ci.yml").to_string(), "ci.yml:3:7");
+    }
+}
@@ -0,0 +1,254 @@
+//! GitHub Actions workflow command annotations (`::error file=...::...`).
+//!
+//! When printed from a step, the runner turns these into inline annotations
+//! on the workflow file in the run summary and on pull request diffs.
+
+use std::path::Path;
+
+use crate::action_ref::ActionRef;
+use crate::advisory::{Advisory, Severity};
+use crate::finding::Finding;
+use crate::output::AuditNode;
+
+/// Write one workflow command per advisory in `nodes` and per workflow finding.
+///
+/// Findings in transitive dependencies are reported at the line of the
+/// top-level `uses:` ref that pulled them in.
+pub fn write_annotations(
+    nodes: &[AuditNode],
+    findings: &[Finding],
+    workflow_path: &Path,
+    writer: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let workflow = workflow_path.to_string_lossy();
+    for node in nodes {
+        write_node(node, &node.entry.action, &workflow, &[], writer)?;
+    }
+    for finding in findings {
+        let file = finding
+            .location
+            .as_ref()
+            .and_then(|loc| loc.file.as_deref())
+            .unwrap_or(&workflow);
+        let mut props = format!("file={}", escape_property(file));
+        if let Some(loc) = &finding.location {
+            props.push_str(&format!(",line={},col={}", loc.line, loc.column));
+        }
+        props.push_str(&format!(",title={}", escape_property(&finding.rule_id)));
+        writeln!(
+            writer,
+            "::{} {props}::{}",
+            command_for(Some(finding.severity)),
+            escape_data(&finding.message)
+        )?;
+    }
+    Ok(())
+}
+
+fn write_node(
+    node: &AuditNode,
+    anchor: &ActionRef,
+    workflow: &str,
+    ancestors: &[String],
+    writer: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let action_str = node.entry.action.to_string();
+    let via = if ancestors.is_empty() {
+        String::new()
+    } else {
+        format!(" (via {})", ancestors.join(" → "))
+    };
+
+    for adv in node.entry.advisories.iter().filter(|a| !a.is_suppressed()) {
+        let title = format!("{} in {action_str}", adv.id);
+        let message = format!("{}{via}", adv.summary);
+        write_command(writer, adv, anchor, workflow, &title, &message)?;
+    }
+
+    for dep in &node.entry.dep_vulnerabilities {
+        for adv in dep.advisories.iter().filter(|a| !a.is_suppressed()) {
+            let title = format!("{} in {}@{}", adv.id, dep.package, dep.version);
+            let message = format!(
+                "{} ({} dependency of {action_str}){via}",
+                adv.summary, dep.ecosystem
+            );
+            write_command(writer, adv, anchor, workflow, &title, &message)?;
+        }
+    }
+
+    if !node.children.is_empty() {
+        let mut next: Vec<String> = ancestors.to_vec();
+        next.push(action_str);
+        for child in &node.children {
+            write_node(child, anchor, workflow, &next, writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_command(
+    writer: &mut dyn std::io::Write,
+    advisory: &Advisory,
+    anchor: &ActionRef,
+    workflow: &str,
+    title: &str,
+    message: &str,
+) -> std::io::Result<()> {
+    let file = anchor
+        .location
+        .as_ref()
+        .and_then(|loc| loc.file.as_deref())
+        .unwrap_or(workflow);
+
+    let mut props = format!("file={}", escape_property(file));
+    if let Some(loc) = &anchor.location {
+        props.push_str(&format!(",line={},col={}", loc.line, loc.column));
+    }
+    props.push_str(&format!(",title={}", escape_property(title)));
+
+    writeln!(
+        writer,
+        "::{} {props}::{}",
+        command_for(advisory.severity),
+        escape_data(message)
+    )
+}
+
+/// Map severity onto the three annotation levels, mirroring the SARIF levels.
+fn command_for(severity: Option<Severity>) -> &'static str {
+    match severity {
+        Some(Severity::Critical | Severity::High) => "error",
+        Some(Severity::Low) => "notice",
+        Some(Severity::Medium) | None => "warning",
+    }
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::SourceLocation;
+    use crate::stages::Ecosystem;
+    use crate::stages::dependency::DependencyReport;
+
+    fn advisory(id: &str, severity: &str) -> Advisory {
+        Advisory {
+            id: id.to_string(),
+            aliases: vec![],
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: None,
+            description: None,
+            references: vec![],
+            vex: None,
+            summary: format!("Issue {id}"),
+            severity: Severity::normalize(severity),
+            url: format!("https://example.com/{id}"),
+            affected_range: None,
+            sources: vec!["ghsa".to_string()],
+        }
+    }
+
+    fn render(nodes: &[AuditNode]) -> String {
+        let mut buf = Vec::new();
+        write_annotations(nodes, &[], Path::new(".github/workflows/ci.yml"), &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn emits_one_command_per_advisory_with_location() {
+        let mut root = AuditNode::with_advisories(
+            "actions/checkout@v1",
+            vec![advisory("GHSA-a", "critical"), advisory("GHSA-b", "low")],
+        );
+        root.entry.action = root
+            .entry
+            .action
+            .with_location(SourceLocation::new(9, 15).with_file(".github/workflows/ci.yml"));
+
+        let out = render(&[root]);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "::error file=.github/workflows/ci.yml,line=9,col=15,title=GHSA-a in actions/checkout@v1::Issue GHSA-a",
+                "::notice file=.github/workflows/ci.yml,line=9,col=15,title=GHSA-b in actions/checkout@v1::Issue GHSA-b",
+            ]
+        );
+    }
+
+    #[test]
+    fn omits_line_without_location() {
+        let out = render(&[AuditNode::with_advisories(
+            "actions/checkout@v1",
+            vec![advisory("GHSA-a", "moderate")],
+        )]);
+        assert!(out.starts_with("::warning file=.github/workflows/ci.yml,title="));
+        assert!(!out.contains("line="));
+    }
+
+    #[test]
+    fn children_and_dependencies_anchor_at_root() {
+        let mut child =
+            AuditNode::with_advisories("actions/setup-node@v1", vec![advisory("GHSA-c", "high")]);
+        child.entry.dep_vulnerabilities = vec![DependencyReport {
+            package: "lodash".to_string(),
+            version: "4.17.20".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![advisory("GHSA-d", "medium")],
+            maintenance: None,
+            not_affected: vec![],
+        }];
+        let mut root = AuditNode::with_advisories("org/composite@v1", vec![]);
+        root.entry.action = root.entry.action.with_location(SourceLocation::new(4, 11));
+        root.children = vec![child];
+
+        let out = render(&[root]);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.contains(",line=4,col=11,")));
+        assert!(lines[0].ends_with("::Issue GHSA-c (via org/composite@v1)"));
+        assert!(lines[1].contains("title=GHSA-d in lodash@4.17.20::"));
+        assert!(lines[1].contains("npm dependency of actions/setup-node@v1"));
+    }
+
+    #[test]
+    fn emits_workflow_findings() {
+        let finding = Finding::new(&crate::finding::DYNAMIC_USES, "dynamic ref")
+            .with_location(Some(SourceLocation::new(7, 15)));
+        let mut buf = Vec::new();
+        write_annotations(
+            &[],
+            &[finding],
+            Path::new(".github/workflows/ci.yml"),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "::warning file=.github/workflows/ci.yml,line=7,col=15,title=dynamic-uses::dynamic ref\n"
+        );
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(escape_data("50%\nnext"), "50%25%0Anext");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+}
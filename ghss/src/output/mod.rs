@@ -1,13 +1,31 @@
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
 use crate::action_ref::ActionRef;
 use crate::advisory::{Advisory, Severity};
-use crate::context::AuditContext;
+use crate::context::{AuditContext, StageError};
+use crate::finding::{self, Finding};
+use crate::knowledge::{ActionKnowledge, RequiredPermissions};
+use crate::providers::depsdev::ProjectInfo;
+use crate::runtime::ActionExecution;
+use crate::scorecard::Scorecard;
 use crate::stages::ScanResult;
 use crate::stages::dependency::DependencyReport;
-
+use crate::stages::hygiene::RepoHygiene;
+use crate::stages::protection::TagProtection;
+use crate::stages::provenance::Provenance;
+use crate::stages::recommend::RecommendedRef;
+use crate::stages::releases::ReleaseLag;
+use crate::stages::repository::RepoMetadata;
+use crate::stages::reputation::Reputation;
+use crate::stages::signature::Signatures;
+use crate::stages::updates::UpdateHygiene;
+use crate::workflow::CallSecrets;
+
+pub mod annotations;
+pub mod openvex;
 pub mod sarif;
 
 /// Output format selector for the formatter factory.
@@ -16,6 +34,7 @@ pub enum OutputFormat {
     Text,
     Json,
     Sarif,
+    OpenVex,
 }
 
 #[derive(PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +48,60 @@ pub struct ActionEntry {
     pub scan: Option<ScanResult>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub dep_vulnerabilities: Vec<DependencyReport>,
+    /// Whether the action ran in a given workflow run (CLI `--run-id`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution: Option<ActionExecution>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_hygiene: Option<UpdateHygiene>,
+    /// License and Scorecard data from deps.dev (CLI `--project-info`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<ProjectInfo>,
+    /// How far the pinned version is behind the latest release (CLI
+    /// `--releases`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub releases: Option<ReleaseLag>,
+    /// The latest release with no known advisory, to pin instead (CLI
+    /// `--recommend`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended_ref: Option<RecommendedRef>,
+    /// The action repository's status (CLI `--repo-metadata`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<RepoMetadata>,
+    /// Stars, forks, contributors, and owner age (CLI `--reputation`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reputation: Option<Reputation>,
+    /// OpenSSF Scorecard result (CLI `--scorecard`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorecard: Option<Scorecard>,
+    /// Build provenance of the pinned release (CLI `--provenance`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Commit and tag signature status (CLI `--signatures`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Signatures>,
+    /// SPDX identifier of the repository's license (CLI `--license`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Immutable release and tag rulesets (CLI `--tag-protection`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_protection: Option<TagProtection>,
+    /// Security policy and private vulnerability reporting (CLI
+    /// `--repo-hygiene`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_hygiene: Option<RepoHygiene>,
+    /// `runs.using` from the action's `action.yml`, e.g. `node20`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+    /// Curated permissions and risky inputs (see [`crate::knowledge`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub knowledge: Option<ActionKnowledge>,
+    /// Findings raised while auditing this action. [`AuditReport::new`] moves
+    /// them into the report's findings, so they are never serialized here.
+    #[serde(skip)]
+    pub findings: Vec<Finding>,
+    /// Stages that failed for this action, so its results may be incomplete.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<StageError>,
 }
 
 impl From<AuditContext> for ActionEntry {
@@ -39,6 +112,23 @@ impl From<AuditContext> for ActionEntry {
             advisories: ctx.advisories,
             scan: ctx.scan,
             dep_vulnerabilities: ctx.dependencies,
+            execution: None,
+            update_hygiene: ctx.update_hygiene,
+            project: ctx.project,
+            releases: ctx.releases,
+            recommended_ref: ctx.recommended_ref,
+            repository: ctx.repository,
+            reputation: ctx.reputation,
+            scorecard: ctx.scorecard,
+            provenance: ctx.provenance,
+            signatures: ctx.signatures,
+            license: ctx.license,
+            tag_protection: ctx.tag_protection,
+            repo_hygiene: ctx.repo_hygiene,
+            runtime: ctx.runtime,
+            knowledge: None,
+            findings: ctx.findings,
+            errors: ctx.errors,
         }
     }
 }
@@ -60,12 +150,130 @@ impl From<AuditContext> for AuditNode {
     }
 }
 
+#[cfg(test)]
+impl AuditNode {
+    /// A leaf node for `uses` with `advisories` and no other stage results.
+    pub(crate) fn with_advisories(uses: &str, advisories: Vec<Advisory>) -> Self {
+        Self::from(AuditContext {
+            advisories,
+            ..AuditContext::for_test(uses.parse().unwrap())
+        })
+    }
+}
+
+/// Layout of the `--format json` report. Version 1 was the bare array of
+/// actions that the deprecated `--json` still prints; version 2 is
+/// [`AuditReport`].
+pub const SCHEMA_VERSION: u32 = 2;
+
+fn schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+/// Everything reported for one workflow: the audited action tree plus
+/// findings about the workflow itself.
+#[derive(PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// [`SCHEMA_VERSION`] when written.
+    #[serde(default = "schema_version")]
+    pub schema_version: u32,
+    pub actions: Vec<AuditNode>,
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+    #[serde(default)]
+    pub summary: TrustSummary,
+    #[serde(default)]
+    pub permissions: RequiredPermissions,
+}
+
+impl AuditReport {
+    /// Findings raised on individual actions are moved out of the tree and
+    /// appended to `findings`.
+    pub fn new(mut actions: Vec<AuditNode>, mut findings: Vec<Finding>) -> Self {
+        fn collect(nodes: &mut [AuditNode], findings: &mut Vec<Finding>) {
+            for node in nodes {
+                findings.append(&mut node.entry.findings);
+                collect(&mut node.children, findings);
+            }
+        }
+        collect(&mut actions, &mut findings);
+        let summary = TrustSummary::from_nodes(&actions);
+        let permissions = RequiredPermissions::from_nodes(&actions);
+        Self {
+            schema_version: SCHEMA_VERSION,
+            actions,
+            findings,
+            summary,
+            permissions,
+        }
+    }
+}
+
+/// Who the workflow ultimately trusts, over the whole expanded tree.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustSummary {
+    /// Distinct GitHub accounts and organizations whose actions or reusable
+    /// workflows run, lowercased and sorted.
+    pub owners: Vec<String>,
+    /// Distinct actions and reusable workflows, ignoring the ref.
+    pub actions: usize,
+}
+
+impl TrustSummary {
+    pub fn from_nodes(nodes: &[AuditNode]) -> Self {
+        fn visit(node: &AuditNode, owners: &mut BTreeSet<String>, actions: &mut BTreeSet<String>) {
+            let action = &node.entry.action;
+            owners.insert(action.owner.to_ascii_lowercase());
+            actions.insert(action.package_name().to_ascii_lowercase());
+            for child in &node.children {
+                visit(child, owners, actions);
+            }
+        }
+
+        let mut owners = BTreeSet::new();
+        let mut actions = BTreeSet::new();
+        for node in nodes {
+            visit(node, &mut owners, &mut actions);
+        }
+        Self {
+            owners: owners.into_iter().collect(),
+            actions: actions.len(),
+        }
+    }
+}
+
+impl std::fmt::Display for TrustSummary {
+    /// e.g. `trusts 3 GitHub owners across 5 actions: actions, aws-actions, codecov`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plural = |n: usize, word: &str| {
+            if n == 1 {
+                format!("{n} {word}")
+            } else {
+                format!("{n} {word}s")
+            }
+        };
+        write!(
+            f,
+            "trusts {} across {}: {}",
+            plural(self.owners.len(), "GitHub owner"),
+            plural(self.actions, "action"),
+            self.owners.join(", ")
+        )
+    }
+}
+
 pub trait OutputFormatter {
     fn write_results(
         &self,
         nodes: &[AuditNode],
         writer: &mut dyn std::io::Write,
     ) -> std::io::Result<()>;
+
+    fn write_report(
+        &self,
+        report: &AuditReport,
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()>;
 }
 
 pub struct TextOutput;
@@ -80,10 +288,119 @@ fn write_node(
 
     writeln!(writer, "{indent}{}", entry.action)?;
 
+    let occurrences = &entry.action.occurrences;
+    if occurrences.len() > 1 {
+        let jobs: BTreeSet<&str> = occurrences
+            .iter()
+            .filter_map(|o| o.job.as_deref())
+            .collect();
+        if jobs.len() > 1 {
+            writeln!(
+                writer,
+                "{indent}  used {} times across {} jobs:",
+                occurrences.len(),
+                jobs.len()
+            )?;
+        } else {
+            writeln!(writer, "{indent}  used {} times:", occurrences.len())?;
+        }
+        for occurrence in occurrences {
+            writeln!(writer, "{indent}    {occurrence}")?;
+        }
+    }
+
+    // The caller's secrets a reusable workflow receives, across every job
+    // calling it.
+    let calls = || occurrences.iter().filter_map(|o| o.call.as_ref());
+    if calls().any(|c| c.secrets == CallSecrets::Inherit) {
+        writeln!(writer, "{indent}  secrets: all (inherit)")?;
+    } else {
+        let exposed: BTreeSet<&str> = calls()
+            .flat_map(|c| c.exposed.iter().map(String::as_str))
+            .collect();
+        if !exposed.is_empty() {
+            let exposed: Vec<&str> = exposed.into_iter().collect();
+            writeln!(writer, "{indent}  secrets: {}", exposed.join(", "))?;
+        }
+    }
+
     if let Some(sha) = &entry.resolved_sha {
         writeln!(writer, "{indent}  sha: {sha}")?;
     }
 
+    if let Some(execution) = &entry.execution {
+        let status = if execution.executed() {
+            "executed"
+        } else {
+            "not executed"
+        };
+        writeln!(writer, "{indent}  run {}: {status}", execution.run_id)?;
+        for step in &execution.steps {
+            writeln!(writer, "{indent}    {step}")?;
+        }
+    }
+
+    if let Some(hygiene) = &entry.update_hygiene {
+        writeln!(writer, "{indent}  updates: {hygiene}")?;
+    }
+
+    if let Some(project) = &entry.project {
+        writeln!(writer, "{indent}  project: {project}")?;
+    }
+
+    if let Some(releases) = &entry.releases {
+        writeln!(writer, "{indent}  releases: {releases}")?;
+    }
+
+    if let Some(recommended) = &entry.recommended_ref {
+        writeln!(writer, "{indent}  recommended: {recommended}")?;
+    }
+
+    if let Some(repository) = &entry.repository {
+        writeln!(writer, "{indent}  repository: {repository}")?;
+    }
+
+    if let Some(reputation) = &entry.reputation {
+        writeln!(writer, "{indent}  reputation: {reputation}")?;
+    }
+
+    if let Some(scorecard) = &entry.scorecard {
+        writeln!(writer, "{indent}  scorecard: {scorecard}")?;
+    }
+
+    if let Some(provenance) = &entry.provenance {
+        writeln!(writer, "{indent}  provenance: {provenance}")?;
+    }
+
+    if let Some(signatures) = &entry.signatures {
+        writeln!(writer, "{indent}  signatures: {signatures}")?;
+    }
+
+    if let Some(license) = &entry.license {
+        writeln!(writer, "{indent}  license: {license}")?;
+    }
+
+    if let Some(protection) = &entry.tag_protection {
+        writeln!(writer, "{indent}  tag protection: {protection}")?;
+    }
+
+    if let Some(hygiene) = &entry.repo_hygiene {
+        writeln!(writer, "{indent}  hygiene: {hygiene}")?;
+    }
+
+    if let Some(runtime) = &entry.runtime {
+        writeln!(writer, "{indent}  runtime: {runtime}")?;
+    }
+
+    if let Some(knowledge) = &entry.knowledge {
+        if let Some(permissions) = &knowledge.permissions {
+            writeln!(writer, "{indent}  permissions: {permissions}")?;
+        }
+        for (input, why) in &knowledge.risky_inputs {
+            writeln!(writer, "{indent}  risky input `{input}`: {why}")?;
+        }
+    }
+
     if let Some(scan) = &entry.scan {
         if let Some(lang) = &scan.primary_language {
             writeln!(writer, "{indent}  language: {lang}")?;
@@ -110,8 +427,38 @@ fn write_node(
                 "{indent}    {}@{} ({}):",
                 dep.package, dep.version, dep.ecosystem
             )?;
-            for adv in &dep.advisories {
-                writeln!(writer, "{indent}      {adv}")?;
+            if let Some(maintenance) = &dep.maintenance {
+                writeln!(writer, "{indent}      registry: {maintenance}")?;
+            }
+            for group in dep.fix_groups() {
+                match (group.fix_version, group.advisories.as_slice()) {
+                    (Some(fix), advisories) if advisories.len() > 1 => {
+                        let highest = advisories.iter().filter_map(|a| a.severity).max();
+                        let ids: Vec<&str> = advisories.iter().map(|a| a.id.as_str()).collect();
+                        write!(
+                            writer,
+                            "{indent}      {} advisories fixed by upgrading to {fix}",
+                            advisories.len()
+                        )?;
+                        if let Some(highest) = highest {
+                            write!(writer, " (highest: {highest})")?;
+                        }
+                        writeln!(writer)?;
+                        writeln!(writer, "{indent}        {}", ids.join(", "))?;
+                    }
+                    (_, advisories) => {
+                        for adv in advisories {
+                            writeln!(writer, "{indent}      {adv}")?;
+                        }
+                    }
+                }
+            }
+            for s in &dep.not_affected {
+                writeln!(
+                    writer,
+                    "{indent}      not affected: {} ({})",
+                    s.advisory.id, s.justification
+                )?;
             }
         }
     }
@@ -134,6 +481,38 @@ impl OutputFormatter for TextOutput {
         }
         Ok(())
     }
+
+    fn write_report(
+        &self,
+        report: &AuditReport,
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        self.write_results(&report.actions, writer)?;
+        if !report.summary.owners.is_empty() || !report.findings.is_empty() {
+            writeln!(writer)?;
+        }
+        if !report.summary.owners.is_empty() {
+            writeln!(writer, "{}", report.summary)?;
+            writeln!(writer, "{}", report.permissions)?;
+        }
+        if !report.findings.is_empty() {
+            writeln!(writer, "workflow findings:")?;
+            for finding in &report.findings {
+                writeln!(writer, "  {finding}")?;
+            }
+        }
+        let expired = report
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == finding::EXPIRED_SUPPRESSION.id)
+            .count();
+        match expired {
+            0 => {}
+            1 => writeln!(writer, "1 suppression has expired and no longer applies")?,
+            n => writeln!(writer, "{n} suppressions have expired and no longer apply")?,
+        }
+        Ok(())
+    }
 }
 
 pub struct JsonOutput;
@@ -148,12 +527,22 @@ impl OutputFormatter for JsonOutput {
         writeln!(writer)?;
         Ok(())
     }
+
+    fn write_report(
+        &self,
+        report: &AuditReport,
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, report)?;
+        writeln!(writer)?;
+        Ok(())
+    }
 }
 
 pub struct SeverityViolation {
     pub action: String,
     pub advisory_id: String,
-    pub severity: String,
+    pub severity: Severity,
     pub summary: String,
 }
 
@@ -175,14 +564,14 @@ fn collect_violations_recursive(
 ) {
     let action_name = node.entry.action.to_string();
 
-    for adv in &node.entry.advisories {
-        if let Some(sev) = adv.parsed_severity()
+    for adv in node.entry.advisories.iter().filter(|a| !a.is_suppressed()) {
+        if let Some(sev) = adv.severity
             && sev >= threshold
         {
             violations.push(SeverityViolation {
                 action: action_name.clone(),
                 advisory_id: adv.id.clone(),
-                severity: adv.severity.clone(),
+                severity: sev,
                 summary: adv.summary.clone(),
             });
         }
@@ -190,14 +579,14 @@ fn collect_violations_recursive(
 
     for dep in &node.entry.dep_vulnerabilities {
         let dep_action = format!("{} -> {}@{}", action_name, dep.package, dep.version);
-        for adv in &dep.advisories {
-            if let Some(sev) = adv.parsed_severity()
+        for adv in dep.advisories.iter().filter(|a| !a.is_suppressed()) {
+            if let Some(sev) = adv.severity
                 && sev >= threshold
             {
                 violations.push(SeverityViolation {
                     action: dep_action.clone(),
                     advisory_id: adv.id.clone(),
-                    severity: adv.severity.clone(),
+                    severity: sev,
                     summary: adv.summary.clone(),
                 });
             }
@@ -209,11 +598,24 @@ fn collect_violations_recursive(
     }
 }
 
+/// Every stage error in the tree, with the action it was recorded for.
+pub fn collect_stage_errors(nodes: &[AuditNode]) -> Vec<(String, &StageError)> {
+    let mut errors = Vec::new();
+    let mut stack: Vec<&AuditNode> = nodes.iter().rev().collect();
+    while let Some(node) = stack.pop() {
+        let action = node.entry.action.to_string();
+        errors.extend(node.entry.errors.iter().map(|e| (action.clone(), e)));
+        stack.extend(node.children.iter().rev());
+    }
+    errors
+}
+
 pub fn formatter(format: OutputFormat, workflow_path: PathBuf) -> Box<dyn OutputFormatter> {
     match format {
         OutputFormat::Text => Box::new(TextOutput),
         OutputFormat::Json => Box::new(JsonOutput),
         OutputFormat::Sarif => Box::new(sarif::SarifOutput::new(workflow_path)),
+        OutputFormat::OpenVex => Box::new(openvex::OpenVexOutput),
     }
 }
 
@@ -221,6 +623,9 @@ pub fn formatter(format: OutputFormat, workflow_path: PathBuf) -> Box<dyn Output
 mod tests {
     use super::*;
     use crate::action_ref::ActionRef;
+    use crate::location::{Occurrence, SourceLocation};
+    use crate::workflow::CallArgs;
+    use std::collections::BTreeMap;
 
     fn sample_action() -> ActionRef {
         "actions/checkout@v4".parse::<ActionRef>().unwrap()
@@ -240,6 +645,23 @@ mod tests {
             advisories: vec![],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         }
     }
 
@@ -262,6 +684,23 @@ mod tests {
             advisories: vec![],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let mut buf = Vec::new();
         let fmt = TextOutput;
@@ -271,6 +710,90 @@ mod tests {
         assert!(output.contains("  sha: abc123"));
     }
 
+    #[test]
+    fn text_output_lists_occurrences_of_repeated_refs() {
+        let mut entry = sample_entry();
+        entry.action.occurrences = ["build", "test", "test"]
+            .iter()
+            .zip([8, 20, 24])
+            .map(|(job, line)| Occurrence {
+                job: Some(job.to_string()),
+                step: None,
+                location: Some(SourceLocation::new(line, 15).with_file("ci.yml")),
+                call: None,
+                with: BTreeMap::new(),
+            })
+            .collect();
+
+        let mut buf = Vec::new();
+        TextOutput
+            .write_results(&[leaf_node(entry)], &mut buf)
+            .unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains(
+            "  used 3 times across 2 jobs:\n    ci.yml:8:15 (job build)\n    ci.yml:20:15 (job test)\n    ci.yml:24:15 (job test)\n"
+        ));
+    }
+
+    #[test]
+    fn text_output_lists_secrets_passed_to_reusable_workflows() {
+        let call = |secrets, exposed: &[&str]| Occurrence {
+            job: Some("deploy".to_string()),
+            step: None,
+            location: None,
+            call: Some(CallArgs {
+                inputs: vec![],
+                secrets,
+                exposed: exposed.iter().map(ToString::to_string).collect(),
+            }),
+            with: BTreeMap::new(),
+        };
+        let output = |occurrences| {
+            let mut entry = sample_entry();
+            entry.action.occurrences = occurrences;
+            let mut buf = Vec::new();
+            TextOutput
+                .write_results(&[leaf_node(entry)], &mut buf)
+                .unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        let named = || CallSecrets::Named(vec!["token".to_string()]);
+        assert!(
+            output(vec![
+                call(named(), &["NPM_TOKEN"]),
+                call(named(), &["DEPLOY_KEY"])
+            ])
+            .contains("\n  secrets: DEPLOY_KEY, NPM_TOKEN\n")
+        );
+        assert!(
+            output(vec![
+                call(named(), &["NPM_TOKEN"]),
+                call(CallSecrets::Inherit, &[])
+            ])
+            .contains("\n  secrets: all (inherit)\n")
+        );
+        assert!(!output(vec![call(CallSecrets::default(), &[])]).contains("secrets:"));
+    }
+
+    #[test]
+    fn text_output_omits_single_occurrence() {
+        let mut entry = sample_entry();
+        entry.action.occurrences = vec![Occurrence {
+            job: Some("build".to_string()),
+            step: None,
+            location: Some(SourceLocation::new(8, 15)),
+            call: None,
+            with: BTreeMap::new(),
+        }];
+
+        let mut buf = Vec::new();
+        TextOutput
+            .write_results(&[leaf_node(entry)], &mut buf)
+            .unwrap();
+        assert!(!String::from_utf8(buf).unwrap().contains("used"));
+    }
+
     #[test]
     fn text_output_with_no_advisories() {
         let nodes = vec![leaf_node(sample_entry())];
@@ -289,14 +812,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-1234".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Bad thing".to_string(),
-                severity: "high".to_string(),
+                severity: Some(Severity::High),
                 url: "https://ghsa.example.com/1234".to_string(),
                 affected_range: Some(">= 1.0, < 2.0".to_string()),
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let mut buf = Vec::new();
         let fmt = TextOutput;
@@ -332,6 +884,78 @@ mod tests {
         );
     }
 
+    fn report_with_finding() -> AuditReport {
+        AuditReport::new(
+            vec![leaf_node(sample_entry())],
+            vec![
+                Finding::new(&crate::finding::DYNAMIC_USES, "dynamic ref")
+                    .with_location(Some(SourceLocation::new(7, 15).with_file("ci.yml"))),
+            ],
+        )
+    }
+
+    #[test]
+    fn text_report_lists_workflow_findings() {
+        let mut buf = Vec::new();
+        TextOutput
+            .write_report(&report_with_finding(), &mut buf)
+            .unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.starts_with("actions/checkout@v4\n"));
+        assert!(output.contains(
+            "\ntrusts 1 GitHub owner across 1 action: actions\n\
+             minimal token permissions: none (unknown for: actions/checkout)\n\
+             workflow findings:\n  dynamic-uses (medium): dynamic ref\n    at ci.yml:7:15\n"
+        ));
+    }
+
+    #[test]
+    fn trust_summary_counts_distinct_owners_across_tree() {
+        let node = |uses: &str, children: Vec<AuditNode>| AuditNode {
+            entry: ActionEntry {
+                action: uses.parse::<ActionRef>().unwrap(),
+                ..sample_entry()
+            },
+            children,
+        };
+        let nodes = vec![
+            node(
+                "org/composite@v1",
+                vec![
+                    node("actions/checkout@v4", vec![]),
+                    node("Third-Party/tool/sub@v2", vec![]),
+                ],
+            ),
+            node("actions/checkout@v3", vec![]),
+            node("third-party/tool@v1", vec![]),
+        ];
+
+        let summary = TrustSummary::from_nodes(&nodes);
+        assert_eq!(summary.owners, ["actions", "org", "third-party"]);
+        assert_eq!(summary.actions, 4);
+        assert_eq!(
+            summary.to_string(),
+            "trusts 3 GitHub owners across 4 actions: actions, org, third-party"
+        );
+    }
+
+    #[test]
+    fn json_report_wraps_actions_and_findings() {
+        let mut buf = Vec::new();
+        JsonOutput
+            .write_report(&report_with_finding(), &mut buf)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(parsed["schema_version"], 2);
+        assert_eq!(parsed["actions"][0]["raw"], "actions/checkout@v4");
+        let finding = &parsed["findings"][0];
+        assert_eq!(finding["rule_id"], "dynamic-uses");
+        assert_eq!(finding["severity"], "medium");
+        assert_eq!(finding["location"]["line"], 7);
+        assert_eq!(parsed["summary"]["owners"][0], "actions");
+        assert_eq!(parsed["summary"]["actions"], 1);
+    }
+
     #[test]
     fn json_output_with_all_fields() {
         let nodes = vec![leaf_node(ActionEntry {
@@ -340,14 +964,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-1234".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Bad thing".to_string(),
-                severity: "high".to_string(),
+                severity: Some(Severity::High),
                 url: "https://ghsa.example.com/1234".to_string(),
                 affected_range: Some(">= 1.0".to_string()),
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let mut buf = Vec::new();
         let fmt = JsonOutput;
@@ -397,6 +1050,17 @@ mod tests {
         assert!(parsed["runs"].is_array());
     }
 
+    #[test]
+    fn factory_returns_openvex() {
+        let f = formatter(OutputFormat::OpenVex, PathBuf::from("workflow.yml"));
+        let nodes = vec![leaf_node(sample_entry())];
+        let mut buf = Vec::new();
+        f.write_results(&nodes, &mut buf).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(parsed["@context"], crate::vex::OPENVEX_CONTEXT);
+        assert!(parsed["statements"].is_array());
+    }
+
     #[test]
     fn json_output_omits_scan_when_none() {
         let nodes = vec![leaf_node(sample_entry())];
@@ -420,6 +1084,23 @@ mod tests {
                 ecosystems: vec![Ecosystem::Npm, Ecosystem::Docker],
             }),
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let mut buf = Vec::new();
         JsonOutput.write_results(&nodes, &mut buf).unwrap();
@@ -448,17 +1129,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-1234".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Bad thing".to_string(),
-                severity: "high".to_string(),
+                severity: Some(Severity::High),
                 url: "https://example.com".to_string(),
                 affected_range: None,
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: Some(ScanResult {
                 primary_language: Some("TypeScript".to_string()),
                 ecosystems: vec![Ecosystem::Npm],
             }),
             dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
             errors: vec![],
         };
 
@@ -483,6 +1190,23 @@ mod tests {
                 ecosystems: vec![Ecosystem::Npm, Ecosystem::Docker],
             }),
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let mut buf = Vec::new();
         TextOutput.write_results(&nodes, &mut buf).unwrap();
@@ -506,14 +1230,40 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-5678".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Test advisory".to_string(),
-                severity: "medium".to_string(),
+                severity: Some(Severity::Medium),
                 url: "https://example.com/5678".to_string(),
                 affected_range: None,
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
             errors: vec![],
         };
 
@@ -546,6 +1296,23 @@ mod tests {
             advisories: vec![],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         });
 
         let parent = AuditNode {
@@ -555,6 +1322,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![child],
         };
@@ -582,6 +1366,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             }),
             leaf_node(ActionEntry {
                 action: "actions/setup-node@v4".parse::<ActionRef>().unwrap(),
@@ -589,14 +1390,43 @@ mod tests {
                 advisories: vec![Advisory {
                     id: "GHSA-9999".to_string(),
                     aliases: vec![],
+                    cwes: vec![],
+                    cvss_score: None,
+                    cvss_vector: None,
+                    epss: None,
+                    published: None,
+                    modified: None,
+                    withdrawn: None,
+                    applicable: None,
+                    fixed_version: None,
+                    description: None,
+                    references: vec![],
+                    vex: None,
                     summary: "Something bad".to_string(),
-                    severity: "critical".to_string(),
+                    severity: Some(Severity::Critical),
                     url: "https://example.com/9999".to_string(),
                     affected_range: None,
-                    source: "osv".to_string(),
+                    sources: vec!["osv".to_string()],
                 }],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             }),
         ];
         let mut buf = Vec::new();
@@ -631,6 +1461,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             }),
         ];
         let mut buf = Vec::new();
@@ -657,6 +1504,23 @@ mod tests {
             advisories: vec![],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         });
         let parent = AuditNode {
             entry: ActionEntry {
@@ -665,6 +1529,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![child],
         };
@@ -692,6 +1573,23 @@ mod tests {
             advisories: vec![],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         });
         let child = AuditNode {
             entry: ActionEntry {
@@ -700,6 +1598,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![grandchild],
         };
@@ -710,6 +1625,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![child],
         };
@@ -738,6 +1670,23 @@ mod tests {
             advisories: vec![],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         });
         let parent = AuditNode {
             entry: ActionEntry {
@@ -746,6 +1695,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![child],
         };
@@ -782,13 +1748,44 @@ mod tests {
                 advisories: vec![Advisory {
                     id: "GHSA-dep1".to_string(),
                     aliases: vec![],
+                    cwes: vec![],
+                    cvss_score: None,
+                    cvss_vector: None,
+                    epss: None,
+                    published: None,
+                    modified: None,
+                    withdrawn: None,
+                    applicable: None,
+                    fixed_version: None,
+                    description: None,
+                    references: vec![],
+                    vex: None,
                     summary: "Prototype pollution".to_string(),
-                    severity: "high".to_string(),
+                    severity: Some(Severity::High),
                     url: "https://example.com/dep1".to_string(),
                     affected_range: None,
-                    source: "osv".to_string(),
+                    sources: vec!["osv".to_string()],
                 }],
+                maintenance: None,
+                not_affected: vec![],
             }],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         });
         let root = AuditNode {
             entry: sample_entry(),
@@ -805,6 +1802,59 @@ mod tests {
         assert!(output.contains("        GHSA-dep1"));
     }
 
+    #[test]
+    fn text_output_collapses_advisories_sharing_a_fix() {
+        use crate::advisory::fixed_in_range;
+        use crate::stages::Ecosystem;
+        use crate::stages::dependency::DependencyReport;
+
+        let adv = |id: &str, severity: &str, range: &str| Advisory {
+            id: id.to_string(),
+            aliases: vec![],
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: fixed_in_range(range),
+            description: None,
+            references: vec![],
+            vex: None,
+            summary: format!("Issue {id}"),
+            severity: Severity::normalize(severity),
+            url: format!("https://example.com/{id}"),
+            affected_range: Some(range.to_string()),
+            sources: vec!["osv".to_string()],
+        };
+        let mut entry = sample_entry();
+        entry.dep_vulnerabilities = vec![DependencyReport {
+            package: "lodash".to_string(),
+            version: "4.17.15".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![
+                adv("GHSA-a", "moderate", "< 4.17.21"),
+                adv("GHSA-b", "critical", ">= 4.0.0, < 4.17.21"),
+                adv("GHSA-c", "high", "< 4.17.19"),
+            ],
+            maintenance: None,
+            not_affected: vec![],
+        }];
+
+        let mut buf = Vec::new();
+        TextOutput
+            .write_results(&[leaf_node(entry)], &mut buf)
+            .unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains(
+            "      2 advisories fixed by upgrading to 4.17.21 (highest: critical)\n        GHSA-a, GHSA-b\n"
+        ));
+        assert!(!output.contains("Issue GHSA-a"));
+        assert!(output.contains("      GHSA-c (high): Issue GHSA-c\n"));
+    }
+
     // --- collect_severity_violations tests ---
 
     #[test]
@@ -815,14 +1865,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-1111".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Bad thing".to_string(),
-                severity: "high".to_string(),
+                severity: Some(Severity::High),
                 url: "https://example.com".to_string(),
                 affected_range: None,
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let violations = collect_severity_violations(&nodes, Severity::High);
         assert_eq!(violations.len(), 1);
@@ -838,14 +1917,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-2222".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Minor issue".to_string(),
-                severity: "medium".to_string(),
+                severity: Some(Severity::Medium),
                 url: "https://example.com".to_string(),
                 affected_range: None,
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let violations = collect_severity_violations(&nodes, Severity::High);
         assert!(violations.is_empty());
@@ -868,13 +1976,44 @@ mod tests {
                 advisories: vec![Advisory {
                     id: "GHSA-dep1".to_string(),
                     aliases: vec![],
+                    cwes: vec![],
+                    cvss_score: None,
+                    cvss_vector: None,
+                    epss: None,
+                    published: None,
+                    modified: None,
+                    withdrawn: None,
+                    applicable: None,
+                    fixed_version: None,
+                    description: None,
+                    references: vec![],
+                    vex: None,
                     summary: "Prototype pollution".to_string(),
-                    severity: "high".to_string(),
+                    severity: Some(Severity::High),
                     url: "https://example.com".to_string(),
                     affected_range: None,
-                    source: "osv".to_string(),
+                    sources: vec!["osv".to_string()],
                 }],
+                maintenance: None,
+                not_affected: vec![],
             }],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let violations = collect_severity_violations(&nodes, Severity::High);
         assert_eq!(violations.len(), 1);
@@ -889,14 +2028,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-3333".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Weird one".to_string(),
-                severity: "moderate".to_string(),
+                severity: None,
                 url: "https://example.com".to_string(),
                 affected_range: None,
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         })];
         let violations = collect_severity_violations(&nodes, Severity::Low);
         assert!(violations.is_empty());
@@ -910,14 +2078,43 @@ mod tests {
             advisories: vec![Advisory {
                 id: "GHSA-child".to_string(),
                 aliases: vec![],
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version: None,
+                description: None,
+                references: vec![],
+                vex: None,
                 summary: "Child issue".to_string(),
-                severity: "critical".to_string(),
+                severity: Some(Severity::Critical),
                 url: "https://example.com".to_string(),
                 affected_range: None,
-                source: "ghsa".to_string(),
+                sources: vec!["ghsa".to_string()],
             }],
             scan: None,
             dep_vulnerabilities: vec![],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         });
         let nodes = vec![AuditNode {
             entry: sample_entry(),
@@ -928,4 +2125,38 @@ mod tests {
         assert_eq!(violations[0].advisory_id, "GHSA-child");
         assert_eq!(violations[0].action, "actions/setup-node@v4");
     }
+
+    #[test]
+    fn collect_stage_errors_walks_children_and_serializes() {
+        let error = |stage: &'static str| StageError {
+            stage: stage.into(),
+            message: "HTTP 503".to_string(),
+        };
+        let mut child = sample_entry();
+        child.action = "actions/setup-node@v4".parse().unwrap();
+        child.errors = vec![error("Advisory")];
+        let mut root = sample_entry();
+        root.errors = vec![error("RefResolve")];
+        let nodes = vec![AuditNode {
+            entry: root,
+            children: vec![leaf_node(child)],
+        }];
+
+        let errors: Vec<(String, &str)> = collect_stage_errors(&nodes)
+            .into_iter()
+            .map(|(action, e)| (action, e.stage.as_ref()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                ("actions/checkout@v4".to_string(), "RefResolve"),
+                ("actions/setup-node@v4".to_string(), "Advisory"),
+            ]
+        );
+
+        let json = serde_json::to_value(&nodes[0].entry).unwrap();
+        assert_eq!(json["errors"][0]["stage"], "RefResolve");
+        let json = serde_json::to_value(sample_entry()).unwrap();
+        assert!(json.get("errors").is_none());
+    }
 }
@@ -0,0 +1,30 @@
+//! OpenVEX output: one statement per advisory, for VEX-aware tooling.
+
+use chrono::Utc;
+
+use crate::output::{AuditNode, AuditReport, OutputFormatter};
+use crate::vex::VexDocument;
+
+pub struct OpenVexOutput;
+
+impl OutputFormatter for OpenVexOutput {
+    fn write_results(
+        &self,
+        nodes: &[AuditNode],
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let document = VexDocument::from_audit(nodes, Utc::now());
+        serde_json::to_writer_pretty(&mut *writer, &document)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    /// Workflow findings are not advisories, so only the actions are stated.
+    fn write_report(
+        &self,
+        report: &AuditReport,
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        self.write_results(&report.actions, writer)
+    }
+}
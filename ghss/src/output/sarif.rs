@@ -6,14 +6,16 @@ use std::path::{Path, PathBuf};
 
 use serde_sarif::sarif::{
     ArtifactLocation, Location, Message, MultiformatMessageString, PhysicalLocation, PropertyBag,
-    Region, ReportingDescriptor, Result as SarifResult, ResultLevel, Run, Sarif, Tool,
+    Region, ReportingDescriptor, Result as SarifResult, ResultLevel, Run, Sarif, Suppression, Tool,
     ToolComponent,
 };
 use sha2::{Digest, Sha256};
 
 use crate::action_ref::ActionRef;
 use crate::advisory::{Advisory, Severity};
-use crate::output::{AuditNode, OutputFormatter};
+use crate::cvss::CvssScore;
+use crate::finding::{self, Finding, Rule};
+use crate::output::{AuditNode, AuditReport, OutputFormatter};
 
 const RULE_VULNERABLE_ACTION: &str = "ghss/vulnerable-action";
 const RULE_VULNERABLE_DEPENDENCY: &str = "ghss/vulnerable-dependency";
@@ -46,21 +48,58 @@ impl OutputFormatter for SarifOutput {
         writeln!(writer)?;
         Ok(())
     }
+
+    fn write_report(
+        &self,
+        report: &AuditReport,
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let sarif = build_sarif_log_with_findings(
+            &report.actions,
+            &report.findings,
+            &self.workflow_path,
+            self.tool_version,
+        );
+        serde_json::to_writer_pretty(&mut *writer, &sarif)?;
+        writeln!(writer)?;
+        Ok(())
+    }
 }
 
 pub fn build_sarif_log(nodes: &[AuditNode], workflow_path: &Path, tool_version: &str) -> Sarif {
+    build_sarif_log_with_findings(nodes, &[], workflow_path, tool_version)
+}
+
+/// Like [`build_sarif_log`], additionally reporting workflow-level findings.
+/// Rule descriptors are only emitted for finding rules that actually fired.
+pub fn build_sarif_log_with_findings(
+    nodes: &[AuditNode],
+    findings: &[Finding],
+    workflow_path: &Path,
+    tool_version: &str,
+) -> Sarif {
     let workflow_uri = workflow_path.to_string_lossy().into_owned();
 
     let mut results = Vec::new();
     for node in nodes {
-        collect_results(node, &workflow_uri, &mut results, &[]);
+        collect_results(node, &workflow_uri, &mut results, &[], &node.entry.action);
+    }
+
+    let mut rules = rules();
+    for rule in finding::RULES {
+        if findings.iter().any(|f| f.rule_id == rule.id) {
+            rules.push(finding_rule(rule));
+        }
+    }
+    for f in findings {
+        results.push(finding_result(&workflow_uri, f));
     }
 
     let driver = ToolComponent::builder()
         .name("ghss")
         .semantic_version(tool_version.to_string())
         .information_uri(TOOL_INFORMATION_URI.to_string())
-        .rules(rules())
+        .rules(rules)
         .build();
 
     let tool = Tool::builder().driver(driver).build();
@@ -79,6 +118,7 @@ fn collect_results(
     workflow_uri: &str,
     out: &mut Vec<SarifResult>,
     ancestors: &[String],
+    anchor: &ActionRef,
 ) {
     let action_str = node.entry.action.to_string();
     let path_descriptor = if ancestors.is_empty() {
@@ -94,6 +134,7 @@ fn collect_results(
     for advisory in &node.entry.advisories {
         out.push(make_result(
             workflow_uri,
+            anchor,
             RULE_VULNERABLE_ACTION,
             &node.entry.action,
             &advisory.id,
@@ -119,6 +160,7 @@ fn collect_results(
             );
             out.push(make_dep_result(
                 workflow_uri,
+                anchor,
                 &dep_subject,
                 &advisory.id,
                 advisory,
@@ -131,13 +173,14 @@ fn collect_results(
         let mut next_ancestors: Vec<String> = ancestors.to_vec();
         next_ancestors.push(action_str);
         for child in &node.children {
-            collect_results(child, workflow_uri, out, &next_ancestors);
+            collect_results(child, workflow_uri, out, &next_ancestors, anchor);
         }
     }
 }
 
 fn make_result(
     workflow_uri: &str,
+    anchor: &ActionRef,
     rule_id: &str,
     action: &ActionRef,
     advisory_id: &str,
@@ -147,6 +190,7 @@ fn make_result(
     let fingerprint_subject = action.package_name();
     finish_result(
         workflow_uri,
+        anchor,
         rule_id,
         &fingerprint_subject,
         advisory_id,
@@ -157,6 +201,7 @@ fn make_result(
 
 fn make_dep_result(
     workflow_uri: &str,
+    anchor: &ActionRef,
     dep_subject: &str,
     advisory_id: &str,
     advisory: &Advisory,
@@ -164,6 +209,7 @@ fn make_dep_result(
 ) -> SarifResult {
     finish_result(
         workflow_uri,
+        anchor,
         RULE_VULNERABLE_DEPENDENCY,
         dep_subject,
         advisory_id,
@@ -174,24 +220,87 @@ fn make_dep_result(
 
 fn finish_result(
     workflow_uri: &str,
+    anchor: &ActionRef,
     rule_id: &str,
     fingerprint_subject: &str,
     advisory_id: &str,
     advisory: &Advisory,
     message_text: &str,
 ) -> SarifResult {
-    let (level, security_severity) = map_severity(advisory);
+    let mut result = build_result(
+        workflow_uri,
+        region_for(anchor),
+        rule_id,
+        advisory.severity,
+        advisory.cvss_score,
+        fingerprint(workflow_uri, fingerprint_subject, advisory_id),
+        message_text,
+    );
+    // Code scanning hides suppressed results instead of dropping them, so
+    // the VEX claim stays visible to reviewers.
+    let justification = if let Some(vex) = advisory.vex.as_ref().filter(|v| v.suppresses()) {
+        Some(format!("VEX {vex} ({})", vex.document))
+    } else if advisory.is_not_applicable() {
+        advisory
+            .affected_range
+            .as_ref()
+            .map(|range| format!("pinned version is outside the affected range {range}"))
+    } else {
+        None
+    };
+    if let Some(justification) = justification {
+        result.suppressions = Some(vec![
+            Suppression::builder()
+                .kind(serde_json::Value::String("external".to_string()))
+                .justification(justification)
+                .build(),
+        ]);
+    }
+    result
+}
 
+fn finding_result(workflow_uri: &str, finding: &Finding) -> SarifResult {
+    let file = finding
+        .location
+        .as_ref()
+        .and_then(|loc| loc.file.as_deref())
+        .unwrap_or(workflow_uri);
+    let (line, column) = finding
+        .location
+        .as_ref()
+        .map_or((1, 1), |loc| (loc.line as i64, loc.column as i64));
     let region = Region::builder()
-        .start_line(1i64)
-        .end_line(1i64)
-        .start_column(1i64)
-        .end_column(1i64)
+        .start_line(line)
+        .end_line(line)
+        .start_column(column)
         .build();
 
-    let artifact = ArtifactLocation::builder()
-        .uri(workflow_uri.to_string())
-        .build();
+    build_result(
+        file,
+        region,
+        &format!("ghss/{}", finding.rule_id),
+        Some(finding.severity),
+        None,
+        fingerprint(file, &finding.rule_id, &finding.message),
+        &finding.message,
+    )
+}
+
+fn build_result(
+    uri: &str,
+    region: Region,
+    rule_id: &str,
+    severity: Option<Severity>,
+    cvss_score: Option<CvssScore>,
+    fingerprint: String,
+    message_text: &str,
+) -> SarifResult {
+    let (level, security_severity) = map_severity(severity);
+    // Code scanning ranks results by this score, so prefer the real one.
+    let security_severity =
+        cvss_score.map_or_else(|| security_severity.to_string(), |s| s.to_string());
+
+    let artifact = ArtifactLocation::builder().uri(uri.to_string()).build();
 
     let physical = PhysicalLocation::builder()
         .artifact_location(artifact)
@@ -203,17 +312,14 @@ fn finish_result(
     let mut additional = BTreeMap::new();
     additional.insert(
         "security-severity".to_string(),
-        serde_json::Value::String(security_severity.to_string()),
+        serde_json::Value::String(security_severity),
     );
     let result_props = PropertyBag::builder()
         .additional_properties(additional)
         .build();
 
     let mut fps: BTreeMap<String, String> = BTreeMap::new();
-    fps.insert(
-        "primaryLocationLineHash".to_string(),
-        fingerprint(workflow_uri, fingerprint_subject, advisory_id),
-    );
+    fps.insert("primaryLocationLineHash".to_string(), fingerprint);
 
     SarifResult::builder()
         .rule_id(rule_id.to_string())
@@ -225,8 +331,32 @@ fn finish_result(
         .build()
 }
 
-fn map_severity(advisory: &Advisory) -> (ResultLevel, &'static str) {
-    match advisory.parsed_severity() {
+/// Region covering the workflow `uses:` value that pulled in this finding.
+/// Findings in transitive dependencies are anchored at their top-level ref.
+/// Falls back to 1:1 when the ref has no recorded location.
+fn region_for(anchor: &ActionRef) -> Region {
+    let (line, start_column, end_column) = match &anchor.location {
+        Some(loc) => {
+            let width = anchor.to_string().chars().count();
+            (
+                loc.line as i64,
+                loc.column as i64,
+                (loc.column + width) as i64,
+            )
+        }
+        None => (1, 1, 1),
+    };
+
+    Region::builder()
+        .start_line(line)
+        .end_line(line)
+        .start_column(start_column)
+        .end_column(end_column)
+        .build()
+}
+
+fn map_severity(severity: Option<Severity>) -> (ResultLevel, &'static str) {
+    match severity {
         Some(Severity::Critical) => (ResultLevel::Error, "9.5"),
         Some(Severity::High) => (ResultLevel::Error, "8.0"),
         Some(Severity::Medium) => (ResultLevel::Warning, "5.5"),
@@ -292,6 +422,26 @@ fn rule_vulnerable_action() -> ReportingDescriptor {
         .build()
 }
 
+fn finding_rule(rule: &Rule) -> ReportingDescriptor {
+    let props = PropertyBag::builder().tags(security_tags()).build();
+
+    ReportingDescriptor::builder()
+        .id(format!("ghss/{}", rule.id))
+        .name(rule.name.to_string())
+        .short_description(
+            MultiformatMessageString::builder()
+                .text(rule.summary.to_string())
+                .build(),
+        )
+        .help(
+            MultiformatMessageString::builder()
+                .text(rule.help.to_string())
+                .build(),
+        )
+        .properties(props)
+        .build()
+}
+
 fn rule_vulnerable_dependency() -> ReportingDescriptor {
     let props = PropertyBag::builder().tags(security_tags()).build();
 
@@ -334,6 +484,7 @@ fn rule_vulnerable_dependency() -> ReportingDescriptor {
 mod tests {
     use super::*;
     use crate::action_ref::ActionRef;
+    use crate::location::SourceLocation;
     use crate::output::{ActionEntry, AuditNode};
     use crate::stages::Ecosystem;
     use crate::stages::dependency::DependencyReport;
@@ -342,11 +493,23 @@ mod tests {
         Advisory {
             id: id.to_string(),
             aliases: vec![],
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: None,
+            description: None,
+            references: vec![],
+            vex: None,
             summary: format!("Issue {id}"),
-            severity: severity.to_string(),
+            severity: Severity::normalize(severity),
             url: format!("https://example.com/{id}"),
             affected_range: None,
-            source: "ghsa".to_string(),
+            sources: vec!["ghsa".to_string()],
         }
     }
 
@@ -358,6 +521,23 @@ mod tests {
                 advisories: advs,
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![],
         }
@@ -389,6 +569,22 @@ mod tests {
         assert!(action_tags.iter().any(|t| t == "security"));
     }
 
+    #[test]
+    fn security_severity_uses_cvss_score_when_known() {
+        let mut scored = advisory("GHSA-aaaa", "high");
+        scored.cvss_score = CvssScore::new(7.3);
+        let nodes = vec![leaf_with_advisories(
+            "actions/checkout@v1",
+            vec![scored, advisory("GHSA-bbbb", "high")],
+        )];
+
+        let sarif = build_sarif_log(&nodes, Path::new(".github/workflows/ci.yml"), "test");
+        let json = serde_json::to_value(&sarif).unwrap();
+        let results = json["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results[0]["properties"]["security-severity"], "7.3");
+        assert_eq!(results[1]["properties"]["security-severity"], "8.0");
+    }
+
     #[test]
     fn build_sarif_log_emits_one_result_per_advisory() {
         let nodes = vec![leaf_with_advisories(
@@ -421,7 +617,7 @@ mod tests {
                 advisory("GHSA-med", "medium"),
                 advisory("GHSA-high", "high"),
                 advisory("GHSA-crit", "critical"),
-                advisory("GHSA-unk", "moderate"),
+                advisory("GHSA-unk", "unknown"),
             ],
         )];
 
@@ -454,7 +650,26 @@ mod tests {
                 version: "4.17.20".to_string(),
                 ecosystem: Ecosystem::Npm,
                 advisories: vec![advisory("GHSA-dep", "critical")],
+                maintenance: None,
+                not_affected: vec![],
             }],
+            execution: None,
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            knowledge: None,
+            findings: vec![],
+            errors: vec![],
         };
         let nodes = vec![AuditNode {
             entry,
@@ -492,6 +707,30 @@ mod tests {
         assert_eq!(loc["region"]["endColumn"], 1);
     }
 
+    #[test]
+    fn build_sarif_log_region_uses_source_location() {
+        let mut node =
+            leaf_with_advisories("actions/checkout@v1", vec![advisory("GHSA-aaaa", "high")]);
+        node.entry.action = node.entry.action.with_location(SourceLocation::new(12, 15));
+        node.children = vec![leaf_with_advisories(
+            "actions/setup-node@v1",
+            vec![advisory("GHSA-child", "high")],
+        )];
+
+        let sarif = build_sarif_log(&[node], Path::new(".github/workflows/ci.yml"), "test");
+        let json = serde_json::to_value(&sarif).unwrap();
+        let results = json["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        for result in results {
+            // Child findings are anchored at the top-level uses: line.
+            let region = &result["locations"][0]["physicalLocation"]["region"];
+            assert_eq!(region["startLine"], 12);
+            assert_eq!(region["endLine"], 12);
+            assert_eq!(region["startColumn"], 15);
+            assert_eq!(region["endColumn"], 15 + "actions/checkout@v1".len());
+        }
+    }
+
     #[test]
     fn build_sarif_log_sets_fingerprints() {
         let nodes = vec![leaf_with_advisories(
@@ -535,6 +774,23 @@ mod tests {
                 advisories: vec![],
                 scan: None,
                 dep_vulnerabilities: vec![],
+                execution: None,
+                update_hygiene: None,
+                project: None,
+                releases: None,
+                recommended_ref: None,
+                repository: None,
+                reputation: None,
+                scorecard: None,
+                provenance: None,
+                signatures: None,
+                license: None,
+                tag_protection: None,
+                repo_hygiene: None,
+                runtime: None,
+                knowledge: None,
+                findings: vec![],
+                errors: vec![],
             },
             children: vec![child],
         };
@@ -563,4 +819,33 @@ mod tests {
         let parsed: serde_json::Value = serde_json::from_slice(&buf).unwrap();
         assert_eq!(parsed["version"], "2.1.0");
     }
+
+    #[test]
+    fn build_sarif_log_reports_workflow_findings() {
+        let findings = vec![
+            Finding::new(&finding::DYNAMIC_USES, "dynamic ref").with_location(Some(
+                SourceLocation::new(7, 15).with_file(".github/workflows/ci.yml"),
+            )),
+        ];
+        let log = build_sarif_log_with_findings(
+            &[],
+            &findings,
+            Path::new(".github/workflows/ci.yml"),
+            "test",
+        );
+        let json = serde_json::to_value(&log).unwrap();
+        let run = &json["runs"][0];
+
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[2]["id"], "ghss/dynamic-uses");
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "ghss/dynamic-uses");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["message"]["text"], "dynamic ref");
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 7);
+        assert_eq!(region["startColumn"], 15);
+    }
 }
@@ -0,0 +1,269 @@
+//! Dependency overrides: user-supplied "not affected" claims for advisories
+//! that match a dependency's version data but don't apply in practice.
+//!
+//! The file is YAML with a `not_affected` list. Each entry names a package
+//! and version range as `package@range`, the advisories it covers (all of
+//! them when omitted), and an OpenVEX justification:
+//!
+//! ```yaml
+//! not_affected:
+//!   - package: "lodash@^4.17.0"
+//!     advisories: [GHSA-35jh-r3h4-6jhm]
+//!     justification: vulnerable_code_not_in_execute_path
+//!     statement: template() is never called with user input
+//! ```
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::advisory::Advisory;
+use crate::stages::Ecosystem;
+use crate::vex::Justification;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OverridesFile {
+    #[serde(default)]
+    not_affected: Vec<RawOverride>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawOverride {
+    package: String,
+    #[serde(default)]
+    advisories: Vec<String>,
+    justification: Justification,
+    #[serde(default)]
+    statement: Option<String>,
+    #[serde(default)]
+    ecosystem: Option<Ecosystem>,
+}
+
+/// One `not_affected` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Override {
+    pub package: String,
+    /// `None` matches every version.
+    pub range: Option<String>,
+    /// Advisory IDs or aliases covered. Empty covers every advisory.
+    pub advisories: Vec<String>,
+    pub justification: Justification,
+    pub statement: Option<String>,
+    pub ecosystem: Option<Ecosystem>,
+}
+
+/// An advisory suppressed by an override, kept so the claim can be reported
+/// and exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotAffected {
+    pub advisory: Advisory,
+    pub justification: Justification,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    entries: Vec<Override>,
+}
+
+impl Overrides {
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let file: OverridesFile = serde_yaml::from_str(yaml).context("invalid overrides file")?;
+        let entries = file
+            .not_affected
+            .into_iter()
+            .map(|raw| {
+                let (package, range) = split_package(&raw.package);
+                if let Some(range) = range
+                    && range != "*"
+                    && parse_range(range).is_none()
+                {
+                    bail!("invalid version range in `{}`", raw.package);
+                }
+                Ok(Override {
+                    package: package.to_string(),
+                    range: range.filter(|r| *r != "*").map(String::from),
+                    advisories: raw.advisories,
+                    justification: raw.justification,
+                    statement: raw.statement,
+                    ecosystem: raw.ecosystem,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The first override covering `advisory` on `package` at `version`.
+    pub fn find(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: Ecosystem,
+        advisory: &Advisory,
+    ) -> Option<&Override> {
+        self.entries.iter().find(|o| {
+            o.package == package
+                && o.ecosystem.is_none_or(|e| e == ecosystem)
+                && o.range.as_deref().is_none_or(|r| range_matches(r, version))
+                && (o.advisories.is_empty()
+                    || o.advisories
+                        .iter()
+                        .any(|id| *id == advisory.id || advisory.aliases.contains(id)))
+        })
+    }
+
+    /// Split `advisories` into those still affecting the package and those
+    /// an override claims it is not affected by.
+    pub fn partition(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: Ecosystem,
+        advisories: Vec<Advisory>,
+    ) -> (Vec<Advisory>, Vec<NotAffected>) {
+        let mut affected = Vec::new();
+        let mut not_affected = Vec::new();
+        for advisory in advisories {
+            match self.find(package, version, ecosystem, &advisory) {
+                Some(o) => not_affected.push(NotAffected {
+                    justification: o.justification,
+                    statement: o.statement.clone(),
+                    advisory,
+                }),
+                None => affected.push(advisory),
+            }
+        }
+        (affected, not_affected)
+    }
+}
+
+/// `lodash@^4` → (`lodash`, `^4`); `@scope/pkg@1.x` → (`@scope/pkg`, `1.x`).
+/// A leading `@` is part of a scoped npm name, not a separator.
+fn split_package(spec: &str) -> (&str, Option<&str>) {
+    match spec.rfind('@') {
+        Some(i) if i > 0 => (&spec[..i], Some(&spec[i + 1..])),
+        _ => (spec, None),
+    }
+}
+
+/// Parse an npm or Cargo style range. npm separates comparators with
+/// spaces where semver expects commas.
+fn parse_range(range: &str) -> Option<semver::VersionReq> {
+    let mut normalized = String::new();
+    for part in range.split_whitespace() {
+        if normalized.ends_with(',') {
+            normalized.push(' ');
+        } else if !normalized.is_empty() && !normalized.ends_with(['<', '>', '=', '~', '^']) {
+            normalized.push_str(", ");
+        }
+        normalized.push_str(part);
+    }
+    semver::VersionReq::parse(&normalized).ok()
+}
+
+/// True if `version` (a concrete version or a manifest range) falls within
+/// `range`. A manifest range is compared by its lowest concrete version; a
+/// version that isn't semver never matches.
+fn range_matches(range: &str, version: &str) -> bool {
+    let concrete = version.trim_start_matches(['^', '~', '=', '>', '<', 'v', ' ']);
+    let concrete = concrete.split_whitespace().next().unwrap_or("");
+    match (parse_range(range), semver::Version::parse(concrete)) {
+        (Some(req), Ok(v)) => req.matches(&v),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_scoped_names() {
+        assert_eq!(split_package("lodash@^4"), ("lodash", Some("^4")));
+        assert_eq!(
+            split_package("@actions/core@1.x"),
+            ("@actions/core", Some("1.x"))
+        );
+        assert_eq!(split_package("@actions/core"), ("@actions/core", None));
+    }
+
+    #[test]
+    fn ranges_accept_npm_and_cargo_syntax() {
+        assert!(range_matches("^4.17.0", "4.17.20"));
+        assert!(range_matches(">= 4.0.0 < 5", "^4.17.15"));
+        assert!(range_matches(">=4.0.0, <5", "~4.2.0"));
+        assert!(!range_matches("<4.17.21", "4.17.21"));
+        assert!(!range_matches("^4", "latest"));
+    }
+
+    #[test]
+    fn matches_by_id_alias_and_ecosystem() {
+        let overrides = Overrides::from_yaml(
+            r#"
+not_affected:
+  - package: "lodash@^4.17.0"
+    advisories: [CVE-2021-23337]
+    justification: vulnerable_code_not_in_execute_path
+    statement: template() is never called
+  - package: "@actions/core"
+    ecosystem: npm
+    justification: component_not_present
+"#,
+        )
+        .unwrap();
+        assert_eq!(overrides.len(), 2);
+
+        let (affected, suppressed) = overrides.partition(
+            "lodash",
+            "4.17.20",
+            Ecosystem::Npm,
+            vec![
+                Advisory::for_test("GHSA-35jh-r3h4-6jhm", &["CVE-2021-23337"]),
+                Advisory::for_test("GHSA-p6mc-m468-83gw", &[]),
+            ],
+        );
+        assert_eq!(affected.len(), 1);
+        assert_eq!(affected[0].id, "GHSA-p6mc-m468-83gw");
+        assert_eq!(suppressed[0].advisory.id, "GHSA-35jh-r3h4-6jhm");
+        assert_eq!(
+            suppressed[0].statement.as_deref(),
+            Some("template() is never called")
+        );
+
+        let other = Advisory::for_test("GHSA-xxxx", &[]);
+        assert!(
+            overrides
+                .find("lodash", "3.10.1", Ecosystem::Npm, &other)
+                .is_none()
+        );
+        assert!(
+            overrides
+                .find("@actions/core", "^1.10.0", Ecosystem::Npm, &other)
+                .is_some()
+        );
+        assert!(
+            overrides
+                .find("@actions/core", "1.10.0", Ecosystem::Go, &other)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_ranges_and_justifications() {
+        let bad_range =
+            "not_affected:\n  - package: lodash@>>4\n    justification: component_not_present\n";
+        assert!(Overrides::from_yaml(bad_range).is_err());
+        let bad_justification = "not_affected:\n  - package: lodash\n    justification: trust_me\n";
+        assert!(Overrides::from_yaml(bad_justification).is_err());
+    }
+}
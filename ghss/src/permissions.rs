@@ -0,0 +1,164 @@
+//! `GITHUB_TOKEN` permissions a workflow grants its jobs.
+//!
+//! Each job runs with its own `permissions:`, or the workflow's, or failing
+//! both the repository default. Jobs left on the default or granted
+//! `write-all` are flagged, with the scopes their actions are known to need
+//! (see [`crate::knowledge`]) as the suggested replacement.
+
+use std::collections::HashMap;
+
+use crate::finding::{DEFAULT_PERMISSIONS, Finding, WRITE_ALL_PERMISSIONS};
+use crate::knowledge::RequiredPermissions;
+use crate::location::{SourceLocation, key_locations};
+use crate::output::AuditNode;
+use crate::workflow::Workflow;
+
+/// A `permissions:` value, reduced to what the checks need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grant {
+    ReadAll,
+    WriteAll,
+    /// A mapping of scopes; unlisted scopes get no access.
+    Scopes,
+}
+
+impl Grant {
+    fn from_yaml(value: &serde_yaml::Value) -> Self {
+        match value.as_str() {
+            Some("write-all") => Grant::WriteAll,
+            Some("read-all") => Grant::ReadAll,
+            _ => Grant::Scopes,
+        }
+    }
+}
+
+/// Check the token permissions of each job in `yaml`. `nodes` is the audited
+/// tree with knowledge attached ([`crate::knowledge::KnowledgeBase::annotate`]);
+/// the actions a job uses decide the permissions suggested for it.
+pub fn check_permissions(
+    yaml: &str,
+    file: &str,
+    nodes: &[AuditNode],
+) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    let top = key_locations(yaml, &[]);
+    let jobs = key_locations(yaml, &["jobs"]);
+    let locate = |locations: &HashMap<String, SourceLocation>, key: &str| {
+        locations.get(key).map(|l| l.clone().with_file(file))
+    };
+
+    let mut findings = Vec::new();
+    for (name, job) in workflow.jobs() {
+        let (grant, location) = match (&job.permissions, &workflow.permissions) {
+            (Some(permissions), _) => (
+                Some(Grant::from_yaml(permissions)),
+                locate(&key_locations(yaml, &["jobs", &name]), "permissions"),
+            ),
+            (None, Some(permissions)) => (
+                Some(Grant::from_yaml(permissions)),
+                locate(&top, "permissions"),
+            ),
+            (None, None) => (None, locate(&jobs, &name)),
+        };
+        let needed = || suggestion(&job_needs(nodes, &name));
+        let finding = match grant {
+            None => Finding::new(
+                &DEFAULT_PERMISSIONS,
+                format!(
+                    "job `{name}` has no `permissions:` and runs with the repository's default token permissions; {}",
+                    needed()
+                ),
+            ),
+            Some(Grant::WriteAll) => Finding::new(
+                &WRITE_ALL_PERMISSIONS,
+                format!(
+                    "job `{name}` runs with `write-all` token permissions; {}",
+                    needed()
+                ),
+            ),
+            Some(Grant::ReadAll | Grant::Scopes) => continue,
+        };
+        findings.push(finding.with_location(location));
+    }
+    Ok(findings)
+}
+
+/// What the actions used directly in job `name` need, including everything
+/// they use in turn.
+fn job_needs(nodes: &[AuditNode], name: &str) -> RequiredPermissions {
+    RequiredPermissions::from_nodes(nodes.iter().filter(|node| {
+        node.entry
+            .action
+            .occurrences
+            .iter()
+            .any(|o| o.job.as_deref() == Some(name))
+    }))
+}
+
+/// e.g. `its actions need contents: read (unknown for: org/tool)`.
+fn suggestion(required: &RequiredPermissions) -> String {
+    let mut text = if required.permissions.0.is_empty() {
+        "its actions need no token permissions".to_string()
+    } else {
+        format!("its actions need {}", required.permissions)
+    };
+    if !required.unknown.is_empty() {
+        text.push_str(&format!(" (unknown for: {})", required.unknown.join(", ")));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::AuditContext;
+    use crate::knowledge::KnowledgeBase;
+
+    fn nodes(yaml: &str) -> Vec<AuditNode> {
+        let mut nodes: Vec<AuditNode> = crate::parse_actions_from(yaml, "ci.yml")
+            .unwrap()
+            .into_iter()
+            .map(|action| AuditNode::from(AuditContext::for_test(action)))
+            .collect();
+        KnowledgeBase::bundled().annotate(&mut nodes);
+        nodes
+    }
+
+    fn check(yaml: &str) -> Vec<String> {
+        check_permissions(yaml, "ci.yml", &nodes(yaml))
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn flags_jobs_on_default_permissions() {
+        let yaml = "on: push\njobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n  release:\n    permissions:\n      contents: write\n    steps:\n      - uses: actions/checkout@v4\n      - uses: org/publish@v1\n  lint:\n    steps:\n      - run: make lint\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "default-permissions (medium): job `build` has no `permissions:` and runs with the repository's default token permissions; its actions need contents: read\n    at ci.yml:3:3",
+                "default-permissions (medium): job `lint` has no `permissions:` and runs with the repository's default token permissions; its actions need no token permissions\n    at ci.yml:12:3",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_write_all_where_it_is_granted() {
+        let yaml = "on: push\npermissions: write-all\njobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n      - uses: org/tool@v1\n  docs:\n    permissions: read-all\n    steps:\n      - run: make docs\n  release:\n    permissions: write-all\n    steps:\n      - run: make release\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "write-all-permissions (high): job `build` runs with `write-all` token permissions; its actions need contents: read (unknown for: org/tool)\n    at ci.yml:2:1",
+                "write-all-permissions (high): job `release` runs with `write-all` token permissions; its actions need no token permissions\n    at ci.yml:13:5",
+            ]
+        );
+    }
+
+    #[test]
+    fn workflow_permissions_cover_every_job() {
+        let yaml = "on: push\npermissions:\n  contents: read\njobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n";
+        assert!(check(yaml).is_empty());
+    }
+}
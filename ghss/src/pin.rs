@@ -0,0 +1,217 @@
+//! `ghss pin`: rewrite a workflow's tag and branch refs to the commit SHAs
+//! they currently point at, keeping the rest of the file as written.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use futures::future::join_all;
+
+use crate::action_ref::{ActionRef, RefType};
+use crate::github::GitHubClient;
+use crate::location::SourceLocation;
+use crate::workflow::{self, UsesRef};
+
+/// One `uses:` value to replace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub location: SourceLocation,
+    /// The value as written, e.g. `actions/checkout@v4`.
+    pub from: String,
+    /// Its replacement, e.g. `actions/checkout@11bd7190...`.
+    pub to: String,
+    /// Written as a trailing `# comment`, e.g. the tag `to` was resolved from.
+    pub comment: String,
+}
+
+impl Edit {
+    /// Pin `action` to `sha`, noting the ref it replaces. `None` if the
+    /// action wasn't parsed from a file.
+    pub fn pin(action: &ActionRef, sha: &str, comment: &str) -> Option<Self> {
+        let from = action.to_string();
+        let (name, _) = from.rsplit_once('@')?;
+        Some(Self {
+            location: action.location.clone()?,
+            to: format!("{name}@{sha}"),
+            comment: comment.to_string(),
+            from,
+        })
+    }
+}
+
+/// Every third-party ref in the workflow, one per `uses:`.
+pub fn third_party_refs(yaml: &str, file: &str) -> Result<Vec<ActionRef>> {
+    Ok(workflow::parse_workflow_file(yaml, file)?
+        .into_iter()
+        .filter_map(|uses| match uses {
+            UsesRef::ThirdParty(action) => Some(action),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Third-party refs in the workflow that aren't pinned to a commit SHA.
+pub fn unpinned_refs(yaml: &str, file: &str) -> Result<Vec<ActionRef>> {
+    let mut refs = third_party_refs(yaml, file)?;
+    refs.retain(|action| action.ref_type != RefType::Sha);
+    Ok(refs)
+}
+
+/// Resolve each ref to its commit and build the edit pinning it there.
+/// Refs used more than once are resolved once. Refs that fail to resolve
+/// are returned with their error instead.
+pub async fn resolve_pins(
+    client: &GitHubClient,
+    refs: &[ActionRef],
+) -> (Vec<Edit>, Vec<(ActionRef, anyhow::Error)>) {
+    let unique: BTreeSet<&ActionRef> = refs.iter().collect();
+    let resolved = join_all(unique.iter().map(|action| client.resolve_ref(action))).await;
+    let mut shas = BTreeMap::new();
+    let mut failed = Vec::new();
+    for (action, result) in unique.into_iter().zip(resolved) {
+        match result {
+            Ok(sha) => {
+                shas.insert(action, sha);
+            }
+            Err(e) => failed.push((action.clone(), e)),
+        }
+    }
+    let edits = refs
+        .iter()
+        .filter_map(|action| Edit::pin(action, shas.get(action)?, &action.git_ref))
+        .collect();
+    (edits, failed)
+}
+
+/// `yaml` with every edit applied. Each edit's location must point at its
+/// `from` value, optionally quoted.
+pub fn apply(yaml: &str, edits: &[Edit]) -> Result<String> {
+    let mut by_line: BTreeMap<usize, Vec<&Edit>> = BTreeMap::new();
+    for edit in edits {
+        by_line.entry(edit.location.line).or_default().push(edit);
+    }
+    let mut out = String::with_capacity(yaml.len());
+    for (index, line) in yaml.split_inclusive('\n').enumerate() {
+        let Some(edits) = by_line.get(&(index + 1)) else {
+            out.push_str(line);
+            continue;
+        };
+        let body = line.trim_end_matches(['\r', '\n']);
+        let mut rewritten = body.to_string();
+        for edit in edits {
+            rewritten = rewrite_line(&rewritten, edit)?;
+        }
+        out.push_str(&rewritten);
+        out.push_str(&line[body.len()..]);
+    }
+    Ok(out)
+}
+
+/// Replace `edit.from` at its column and set the line's trailing comment.
+/// A comment that is a single word (a ref from an earlier pin, like
+/// `# v4`) is replaced; a longer one is the author's and is left alone.
+fn rewrite_line(line: &str, edit: &Edit) -> Result<String> {
+    let location = &edit.location;
+    let mismatch = || anyhow::anyhow!("line {} no longer reads `{}`", location.line, edit.from);
+    let start = line
+        .char_indices()
+        .nth(location.column.saturating_sub(1))
+        .map(|(i, _)| i)
+        .ok_or_else(mismatch)?;
+    let value_start = match line[start..].chars().next() {
+        Some('"' | '\'') => start + 1,
+        _ => start,
+    };
+    if !line[value_start..].starts_with(&edit.from) {
+        return Err(mismatch());
+    }
+    let rest = &line[value_start + edit.from.len()..];
+    let (code, comment) = match rest.find(" #") {
+        Some(i) => (&rest[..i], Some(&rest[i..])),
+        None => (rest, None),
+    };
+    let tail = match comment {
+        Some(comment) if comment.trim()[1..].trim().contains(char::is_whitespace) => {
+            rest.to_string()
+        }
+        _ => format!("{} # {}", code.trim_end(), edit.comment),
+    };
+    Ok(format!("{}{}{tail}", &line[..value_start], edit.to))
+}
+
+/// A unified diff of the lines that differ between `before` and `after`,
+/// which must have the same number of lines. Hunks carry no context.
+pub fn diff(file: &str, before: &str, after: &str) -> String {
+    let mut out = String::new();
+    for (index, (old, new)) in before.lines().zip(after.lines()).enumerate() {
+        if old == new {
+            continue;
+        }
+        if out.is_empty() {
+            out.push_str(&format!("--- a/{file}\n+++ b/{file}\n"));
+        }
+        let line = index + 1;
+        out.push_str(&format!("@@ -{line} +{line} @@\n-{old}\n+{new}\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA: &str = "11bd71901bbe5b1630ceea73d27597364c9af683";
+
+    fn edits(yaml: &str) -> Vec<Edit> {
+        unpinned_refs(yaml, "ci.yml")
+            .unwrap()
+            .iter()
+            .filter_map(|action| Edit::pin(action, SHA, &action.git_ref))
+            .collect()
+    }
+
+    #[test]
+    fn pins_tags_and_branches_keeping_formatting() {
+        let yaml = "on: push\r\njobs:\r\n  build:\r\n    runs-on: ubuntu-latest\r\n    steps:\r\n      \
+                    # check out first\r\n      - uses: actions/checkout@v4\r\n      \
+                    - uses: \"actions/cache@main\"   # keep: see #12\r\n      \
+                    - uses: 'actions/setup-node@v3'  # v3\r\n      \
+                    - uses: actions/upload-artifact@11bd71901bbe5b1630ceea73d27597364c9af683\r\n";
+        let pinned = apply(yaml, &edits(yaml)).unwrap();
+        assert_eq!(
+            pinned,
+            format!(
+                "on: push\r\njobs:\r\n  build:\r\n    runs-on: ubuntu-latest\r\n    steps:\r\n      \
+                 # check out first\r\n      - uses: actions/checkout@{SHA} # v4\r\n      \
+                 - uses: \"actions/cache@{SHA}\"   # keep: see #12\r\n      \
+                 - uses: 'actions/setup-node@{SHA}' # v3\r\n      \
+                 - uses: actions/upload-artifact@{SHA}\r\n"
+            )
+        );
+        assert_eq!(
+            diff("ci.yml", yaml, &pinned)
+                .lines()
+                .take(5)
+                .collect::<Vec<_>>(),
+            [
+                "--- a/ci.yml".to_string(),
+                "+++ b/ci.yml".to_string(),
+                "@@ -7 +7 @@".to_string(),
+                "-      - uses: actions/checkout@v4".to_string(),
+                format!("+      - uses: actions/checkout@{SHA} # v4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_to_edit_a_line_that_moved() {
+        let yaml = "jobs:\n  a:\n    steps:\n      - uses: actions/checkout@v4\n";
+        let edits = edits(yaml);
+        let shifted = format!("# header\n{yaml}");
+        let err = apply(&shifted, &edits).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 4 no longer reads `actions/checkout@v4`"
+        );
+        assert!(diff("ci.yml", yaml, yaml).is_empty());
+    }
+}
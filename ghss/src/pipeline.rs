@@ -3,7 +3,7 @@ use std::sync::Arc;
 use tracing::{debug, instrument};
 
 use crate::context::AuditContext;
-use crate::stages::Stage;
+use crate::stages::{Phase, Stage};
 
 #[derive(Clone)]
 pub struct Pipeline {
@@ -12,23 +12,30 @@ pub struct Pipeline {
 }
 
 impl Pipeline {
+    /// Run every stage on one node, regardless of phase.
     #[instrument(skip(self, ctx), fields(action = %ctx.action, stage_count = self.stages.len()))]
     pub async fn run_one(&self, ctx: &mut AuditContext) {
         for stage in self.stages.iter() {
-            if let Err(e) = stage.run(ctx).await {
-                tracing::warn!(
-                    stage = stage.name(),
-                    action = %ctx.action,
-                    error = %e,
-                    "stage failed"
-                );
-                ctx.record_error(stage.name(), &e);
-            } else {
-                debug!(stage = stage.name(), action = %ctx.action, "stage complete");
-            }
+            run_stage(stage.as_ref(), ctx).await;
         }
     }
 
+    /// Run the stages of one phase on one node, in order.
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    pub async fn run_phase(&self, ctx: &mut AuditContext, phase: Phase) {
+        for stage in self.stages(phase) {
+            run_stage(stage, ctx).await;
+        }
+    }
+
+    /// The stages of one phase, in pipeline order.
+    pub fn stages(&self, phase: Phase) -> impl Iterator<Item = &dyn Stage> {
+        self.stages
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(move |stage| stage.phase() == phase)
+    }
+
     pub fn stage_count(&self) -> usize {
         self.stages.len()
     }
@@ -38,6 +45,22 @@ impl Pipeline {
     }
 }
 
+/// Run one stage on one node, recording a failure on the context instead
+/// of stopping.
+pub async fn run_stage(stage: &dyn Stage, ctx: &mut AuditContext) {
+    if let Err(e) = stage.run(ctx).await {
+        tracing::warn!(
+            stage = stage.name(),
+            action = %ctx.action,
+            error = %e,
+            "stage failed"
+        );
+        ctx.record_error(stage.name(), &e);
+    } else {
+        debug!(stage = stage.name(), action = %ctx.action, "stage complete");
+    }
+}
+
 pub struct PipelineBuilder {
     stages: Vec<Box<dyn Stage>>,
     max_concurrency: usize,
@@ -92,6 +115,20 @@ mod tests {
             advisories: vec![],
             scan: None,
             dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
             errors: vec![],
         }
     }
@@ -183,6 +220,43 @@ mod tests {
         assert_eq!(names, vec!["first", "second", "third"]);
     }
 
+    struct ExpandStage {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Stage for ExpandStage {
+        async fn run(&self, _ctx: &mut AuditContext) -> anyhow::Result<()> {
+            self.log.lock().unwrap().push("expand".to_string());
+            Ok(())
+        }
+        fn name(&self) -> &'static str {
+            "expand"
+        }
+        fn phase(&self) -> Phase {
+            Phase::Expand
+        }
+    }
+
+    #[tokio::test]
+    async fn run_phase_only_runs_matching_stages() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pipeline = PipelineBuilder::new()
+            .stage(TrackingStage {
+                name: "enrich",
+                log: log.clone(),
+            })
+            .stage(ExpandStage { log: log.clone() })
+            .build();
+
+        let mut ctx = test_ctx();
+        pipeline.run_phase(&mut ctx, Phase::Expand).await;
+        assert_eq!(*log.lock().unwrap(), vec!["expand"]);
+
+        let enrich: Vec<&str> = pipeline.stages(Phase::Enrich).map(|s| s.name()).collect();
+        assert_eq!(enrich, vec!["enrich"]);
+    }
+
     #[tokio::test]
     async fn run_one_with_no_stages() {
         let pipeline = PipelineBuilder::new().build();
@@ -0,0 +1,233 @@
+//! Organization-specific gating written as expressions, for checks the
+//! custom rules' globs can't express.
+//!
+//! Each check in the config file's `[[policy.checks]]` is a [CEL](crate::cel)
+//! expression evaluated against every audited action, nested ones
+//! included. When it is true, the action is reported as a finding with the
+//! check's id; `decision = "fail"` also fails the run:
+//!
+//! ```toml
+//! [[policy.checks]]
+//! id = "young-personal-action"
+//! when = 'reputation.owner_type == "user" && reputation.stars < 50'
+//! decision = "warn"
+//! message = "{action} is a little-used action from a personal account"
+//! ```
+//!
+//! The expression sees the action as `--format json` reports it (`owner`,
+//! `repo`, `git_ref`, `advisories`, `scan`, `reputation`, `scorecard`,
+//! ...) plus `action` (`owner/repo[/path]`), `depth` (0 for the workflow's
+//! own `uses:`) and `parent` (the including action, or `null`).
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::Severity;
+use crate::cel::Expr;
+use crate::finding::{self, Finding};
+use crate::output::AuditNode;
+
+/// What a check that holds does to the run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    /// Report a finding.
+    Warn,
+    /// Report a finding and fail the run.
+    #[default]
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Check {
+    pub id: String,
+    #[serde(deserialize_with = "expr")]
+    pub when: Expr,
+    #[serde(default)]
+    pub decision: Decision,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// `{action}` is replaced with the action and its ref.
+    pub message: Option<String>,
+}
+
+fn default_severity() -> Severity {
+    Severity::Medium
+}
+
+fn expr<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Expr, D::Error> {
+    let source = String::deserialize(deserializer)?;
+    Expr::parse(&source).map_err(|e| serde::de::Error::custom(format!("{source:?}: {e:#}")))
+}
+
+impl Check {
+    fn finding(&self, action: &ActionRef) -> Finding {
+        let action_at = format!("{}@{}", action.package_name(), action.git_ref);
+        let message = match &self.message {
+            Some(message) => message.replace("{action}", &action_at),
+            None => format!("{action_at} fails policy check `{}`", self.when),
+        };
+        Finding {
+            rule_id: self.id.clone(),
+            severity: self.severity,
+            message,
+            location: action.location.clone(),
+        }
+    }
+}
+
+/// Check ids must be unique and not shadow a built-in rule, so findings
+/// can be told apart and ignored by id.
+pub fn validate(checks: &[Check]) -> Result<()> {
+    for (i, check) in checks.iter().enumerate() {
+        if check.id.trim().is_empty() {
+            bail!("check id must not be empty");
+        }
+        if finding::rule(&check.id).is_some() {
+            bail!("check {}: id is already used by a built-in rule", check.id);
+        }
+        if checks[..i].iter().any(|c| c.id == check.id) {
+            bail!("check {}: defined more than once", check.id);
+        }
+    }
+    Ok(())
+}
+
+/// Findings for every action in `nodes` that a check holds for. Fails
+/// when an expression can't be evaluated, such as comparing a string with
+/// a number, rather than letting the check pass.
+pub fn evaluate(checks: &[Check], nodes: &[AuditNode]) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    if !checks.is_empty() {
+        evaluate_level(checks, nodes, 0, None, &mut findings)?;
+    }
+    Ok(findings)
+}
+
+fn evaluate_level(
+    checks: &[Check],
+    nodes: &[AuditNode],
+    depth: usize,
+    parent: Option<&ActionRef>,
+    findings: &mut Vec<Finding>,
+) -> Result<()> {
+    for node in nodes {
+        let action = &node.entry.action;
+        let vars = vars(node, depth, parent)?;
+        for check in checks {
+            let holds = check.when.eval_bool(&vars).with_context(|| {
+                format!(
+                    "check {} on {}@{}",
+                    check.id,
+                    action.package_name(),
+                    action.git_ref
+                )
+            })?;
+            if holds {
+                findings.push(check.finding(action));
+            }
+        }
+        evaluate_level(checks, &node.children, depth + 1, Some(action), findings)?;
+    }
+    Ok(())
+}
+
+/// The identifiers an expression sees for one action.
+fn vars(node: &AuditNode, depth: usize, parent: Option<&ActionRef>) -> Result<Map<String, Value>> {
+    let Value::Object(mut vars) = serde_json::to_value(&node.entry)? else {
+        unreachable!("an action entry serializes to an object");
+    };
+    vars.insert(
+        "action".into(),
+        Value::String(node.entry.action.package_name()),
+    );
+    vars.insert("depth".into(), Value::from(depth));
+    vars.insert(
+        "parent".into(),
+        parent.map_or(Value::Null, |p| Value::String(p.package_name())),
+    );
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisory::Advisory;
+
+    fn check(id: &str, when: &str, decision: Decision) -> Check {
+        Check {
+            id: id.into(),
+            when: Expr::parse(when).unwrap(),
+            decision,
+            severity: Severity::High,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn checks_report_matching_actions_at_every_depth() {
+        let mut root =
+            AuditNode::with_advisories("acme/deploy@v1", vec![Advisory::for_test("GHSA-1", &[])]);
+        root.children
+            .push(AuditNode::with_advisories("someone/helper@main", vec![]));
+        let checks = [
+            check("has-advisory", "size(advisories) > 0", Decision::Fail),
+            check(
+                "nested-branch",
+                "depth > 0 && ref_type == 'unknown' && parent == 'acme/deploy'",
+                Decision::Warn,
+            ),
+        ];
+        validate(&checks).unwrap();
+
+        let findings = evaluate(&checks, &[root]).unwrap();
+        let found: Vec<(&str, &str)> = findings
+            .iter()
+            .map(|f| (f.rule_id.as_str(), f.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "has-advisory",
+                    "acme/deploy@v1 fails policy check `size(advisories) > 0`"
+                ),
+                (
+                    "nested-branch",
+                    "someone/helper@main fails policy check `depth > 0 && ref_type == 'unknown' && parent == 'acme/deploy'`"
+                ),
+            ]
+        );
+
+        let bad = [check("bad", "owner + 1 > 0", Decision::Fail)];
+        let err = evaluate(
+            &bad,
+            &[AuditNode::with_advisories("acme/deploy@v1", vec![])],
+        )
+        .unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "check bad on acme/deploy@v1: cannot add a string and a number"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_and_built_in_ids() {
+        let dup = [
+            check("mine", "true", Decision::Warn),
+            check("mine", "false", Decision::Warn),
+        ];
+        assert_eq!(
+            validate(&dup).unwrap_err().to_string(),
+            "check mine: defined more than once"
+        );
+        let built_in = [check("unpinned-action", "true", Decision::Warn)];
+        assert_eq!(
+            validate(&built_in).unwrap_err().to_string(),
+            "check unpinned-action: id is already used by a built-in rule"
+        );
+    }
+}
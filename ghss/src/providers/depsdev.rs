@@ -0,0 +1,480 @@
+use std::fmt;
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::action_ref::ActionRef;
+use crate::advisory::{Advisory, normalize_severity};
+use crate::cvss::CvssScore;
+
+use super::PackageAdvisoryProvider;
+
+const DEPSDEV_API_URL: &str = "https://api.deps.dev";
+
+#[derive(Deserialize)]
+struct PackageResponse {
+    #[serde(default)]
+    versions: Vec<PackageVersion>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageVersion {
+    version_key: VersionKey,
+    #[serde(default)]
+    is_default: bool,
+}
+
+#[derive(Deserialize)]
+struct VersionKey {
+    version: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionResponse {
+    #[serde(default)]
+    advisory_keys: Vec<AdvisoryKey>,
+}
+
+#[derive(Deserialize)]
+struct AdvisoryKey {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdvisoryResponse {
+    advisory_key: AdvisoryKey,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    cvss3_score: Option<f64>,
+    #[serde(default)]
+    cvss3_vector: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProjectResponse {
+    #[serde(default)]
+    license: Option<String>,
+    #[serde(default)]
+    scorecard: Option<ScorecardResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScorecardResponse {
+    #[serde(default)]
+    date: Option<String>,
+    overall_score: f64,
+}
+
+/// License and OpenSSF Scorecard data for a source repository, from deps.dev.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    /// SPDX license expression, e.g. `MIT`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Overall Scorecard score, 0 to 10.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorecard: Option<f64>,
+    /// Date of the Scorecard run, e.g. `2025-01-06`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorecard_date: Option<String>,
+}
+
+// Scores are parsed from JSON numbers, so they are never NaN.
+impl Eq for ProjectInfo {}
+
+impl fmt::Display for ProjectInfo {
+    /// e.g. `license MIT, scorecard 7.3 (2025-01-06)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "license {}",
+            self.license.as_deref().unwrap_or("unknown")
+        )?;
+        match self.scorecard {
+            Some(score) => {
+                write!(f, ", scorecard {score:.1}")?;
+                if let Some(date) = &self.scorecard_date {
+                    write!(f, " ({date})")?;
+                }
+                Ok(())
+            }
+            None => write!(f, ", no scorecard"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shared client
+// ---------------------------------------------------------------------------
+
+/// deps.dev API client. No token is needed. Base URL overridable via
+/// `GHSS_DEPSDEV_BASE_URL`.
+#[derive(Clone)]
+pub struct DepsDevClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for DepsDevClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DepsDevClient {
+    pub fn new() -> Self {
+        let base_url =
+            std::env::var("GHSS_DEPSDEV_BASE_URL").unwrap_or_else(|_| DEPSDEV_API_URL.to_string());
+        Self {
+            http: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    /// GET `{base}/v3/{segments...}`, percent-encoding each segment. Returns
+    /// `None` on 404, which deps.dev uses for packages it does not know.
+    async fn get<T: DeserializeOwned>(&self, segments: &[&str]) -> Result<Option<T>> {
+        let mut url = reqwest::Url::parse(&self.base_url)
+            .with_context(|| format!("invalid deps.dev base URL: {}", self.base_url))?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("deps.dev base URL cannot have a path"))?
+            .pop_if_empty()
+            .push("v3")
+            .extend(segments);
+
+        let response = self
+            .http
+            .get(url.clone())
+            .send()
+            .await
+            .with_context(|| format!("failed to query deps.dev: {url}"))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            bail!("deps.dev returned HTTP {status} for {url}");
+        }
+        let body = response
+            .json()
+            .await
+            .context("failed to parse deps.dev response")?;
+        Ok(Some(body))
+    }
+
+    /// Advisories affecting `version` of a package, or the package's default
+    /// (usually latest) version when `version` is `None` or unknown.
+    #[instrument(skip(self))]
+    pub async fn advisories(
+        &self,
+        system: &str,
+        package: &str,
+        version: Option<&str>,
+    ) -> Result<Vec<Advisory>> {
+        let Some(pkg) = self
+            .get::<PackageResponse>(&["systems", system, "packages", package])
+            .await?
+        else {
+            return Ok(vec![]);
+        };
+        let exact =
+            version.and_then(|v| pkg.versions.iter().find(|pv| pv.version_key.version == v));
+        let Some(chosen) = exact.or_else(|| pkg.versions.iter().find(|pv| pv.is_default)) else {
+            return Ok(vec![]);
+        };
+
+        let Some(details) = self
+            .get::<VersionResponse>(&[
+                "systems",
+                system,
+                "packages",
+                package,
+                "versions",
+                &chosen.version_key.version,
+            ])
+            .await?
+        else {
+            return Ok(vec![]);
+        };
+
+        let mut advisories = Vec::new();
+        for key in details.advisory_keys {
+            if let Some(adv) = self
+                .get::<AdvisoryResponse>(&["advisories", &key.id])
+                .await?
+            {
+                advisories.push(to_advisory(adv));
+            }
+        }
+        Ok(advisories)
+    }
+
+    /// License and Scorecard data for an action's GitHub repository. `None`
+    /// if deps.dev does not track the repository.
+    #[instrument(skip(self), fields(action = %action))]
+    pub async fn project(&self, action: &ActionRef) -> Result<Option<ProjectInfo>> {
+        let key = format!("github.com/{}/{}", action.owner, action.repo).to_ascii_lowercase();
+        Ok(self
+            .get::<ProjectResponse>(&["projects", &key])
+            .await?
+            .map(|p| ProjectInfo {
+                license: p.license.filter(|l| !l.is_empty() && l != "non-standard"),
+                scorecard: p.scorecard.as_ref().map(|s| s.overall_score),
+                scorecard_date: p
+                    .scorecard
+                    .and_then(|s| s.date)
+                    .map(|d| d.chars().take(10).collect()),
+            }))
+    }
+}
+
+fn to_advisory(adv: AdvisoryResponse) -> Advisory {
+    // deps.dev reports 0 for advisories without a CVSS v3 score.
+    let cvss_score = adv
+        .cvss3_score
+        .filter(|s| *s > 0.0)
+        .and_then(CvssScore::new);
+    Advisory {
+        id: adv.advisory_key.id,
+        aliases: adv.aliases,
+        cwes: vec![],
+        cvss_score,
+        cvss_vector: adv.cvss3_vector.filter(|v| !v.is_empty()),
+        epss: None,
+        published: None,
+        modified: None,
+        withdrawn: None,
+        applicable: None,
+        fixed_version: None,
+        description: None,
+        references: vec![],
+        vex: None,
+        summary: adv.title,
+        // deps.dev has no severity label, only the CVSS score.
+        severity: normalize_severity(None, cvss_score),
+        url: adv.url,
+        affected_range: None,
+        sources: vec!["deps.dev".to_string()],
+    }
+}
+
+/// deps.dev system name for an OSV ecosystem string, if deps.dev covers it.
+fn system_for(ecosystem: &str) -> Option<&'static str> {
+    match ecosystem {
+        "npm" => Some("npm"),
+        "Go" => Some("go"),
+        "PyPI" => Some("pypi"),
+        "crates.io" => Some("cargo"),
+        "Maven" => Some("maven"),
+        "NuGet" => Some("nuget"),
+        "RubyGems" => Some("rubygems"),
+        _ => None,
+    }
+}
+
+/// A concrete version from a manifest version spec: `^4.17.0` → `4.17.0`,
+/// `v1.2.3` → `1.2.3`. `None` for wildcards and tags like `latest`.
+pub(crate) fn concrete_version(spec: &str) -> Option<&str> {
+    let version = spec
+        .split_whitespace()
+        .next()?
+        .trim_start_matches(['^', '~', '=', '>', '<', 'v']);
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(version)
+}
+
+// ---------------------------------------------------------------------------
+// Trait provider
+// ---------------------------------------------------------------------------
+
+pub struct DepsDevProvider {
+    client: DepsDevClient,
+}
+
+impl DepsDevProvider {
+    pub fn new(client: DepsDevClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl PackageAdvisoryProvider for DepsDevProvider {
+    #[instrument(skip(self))]
+    async fn query(&self, package: &str, ecosystem: &str) -> Result<Vec<Advisory>> {
+        match system_for(ecosystem) {
+            Some(system) => self.client.advisories(system, package, None).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn query_version(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: &str,
+    ) -> Result<Vec<Advisory>> {
+        match system_for(ecosystem) {
+            Some(system) => {
+                self.client
+                    .advisories(system, package, concrete_version(version))
+                    .await
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "deps.dev"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisory::Severity;
+
+    #[test]
+    fn concrete_version_strips_range_operators() {
+        assert_eq!(concrete_version("^4.17.0"), Some("4.17.0"));
+        assert_eq!(concrete_version("~1.2.3"), Some("1.2.3"));
+        assert_eq!(concrete_version(">=2.0.0 <3"), Some("2.0.0"));
+        assert_eq!(concrete_version("v0.9.1"), Some("0.9.1"));
+        assert_eq!(concrete_version("*"), None);
+        assert_eq!(concrete_version("latest"), None);
+    }
+
+    #[test]
+    fn project_info_display() {
+        let info = ProjectInfo {
+            license: Some("MIT".to_string()),
+            scorecard: Some(7.25),
+            scorecard_date: Some("2025-01-06".to_string()),
+        };
+        assert_eq!(info.to_string(), "license MIT, scorecard 7.2 (2025-01-06)");
+        let info = ProjectInfo {
+            license: None,
+            scorecard: None,
+            scorecard_date: None,
+        };
+        assert_eq!(info.to_string(), "license unknown, no scorecard");
+    }
+
+    fn client_for(server: &wiremock::MockServer) -> DepsDevClient {
+        // SAFETY: test-only; env var mutation is unsafe in Rust 2024
+        unsafe { std::env::set_var("GHSS_DEPSDEV_BASE_URL", server.uri()) };
+        let client = DepsDevClient::new();
+        unsafe { std::env::remove_var("GHSS_DEPSDEV_BASE_URL") };
+        client
+    }
+
+    #[tokio::test]
+    async fn provider_queries_matching_version() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v3/systems/npm/packages/@scope%2Flib"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "versions": [
+                    {"versionKey": {"version": "1.0.0"}, "isDefault": false},
+                    {"versionKey": {"version": "2.0.0"}, "isDefault": true}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v3/systems/npm/packages/@scope%2Flib/versions/1.0.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "licenses": ["MIT"],
+                "advisoryKeys": [{"id": "GHSA-xxxx-yyyy-zzzz"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v3/advisories/GHSA-xxxx-yyyy-zzzz"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "advisoryKey": {"id": "GHSA-xxxx-yyyy-zzzz"},
+                "url": "https://osv.dev/vulnerability/GHSA-xxxx-yyyy-zzzz",
+                "title": "Prototype pollution",
+                "aliases": ["CVE-2024-0001"],
+                "cvss3Score": 7.5,
+                "cvss3Vector": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N"
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = DepsDevProvider::new(client_for(&server));
+        let advisories = provider
+            .query_version("@scope/lib", "^1.0.0", "npm")
+            .await
+            .unwrap();
+        assert_eq!(advisories.len(), 1);
+        let a = &advisories[0];
+        assert_eq!(a.id, "GHSA-xxxx-yyyy-zzzz");
+        assert_eq!(a.aliases, ["CVE-2024-0001"]);
+        assert_eq!(a.severity, Some(Severity::High));
+        assert_eq!(a.cvss_score.map(|s| s.value()), Some(7.5));
+        assert!(a.cvss_vector.is_some());
+        assert_eq!(a.sources, ["deps.dev"]);
+
+        // The default version has no advisory keys mocked, so it 404s.
+        assert!(
+            provider
+                .query("@scope/lib", "npm")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(provider.query("lib", "Packagist").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn project_reads_license_and_scorecard() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v3/projects/github.com%2Factions%2Fcheckout"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "projectKey": {"id": "github.com/actions/checkout"},
+                "license": "MIT",
+                "scorecard": {"date": "2025-01-06T00:00:00Z", "overallScore": 6.8}
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let info = client
+            .project(&"Actions/Checkout@v4".parse().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+        assert_eq!(info.scorecard, Some(6.8));
+        assert_eq!(info.scorecard_date.as_deref(), Some("2025-01-06"));
+
+        assert!(
+            client
+                .project(&"nobody/nothing@v1".parse().unwrap())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,28 +1,77 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_json::Value;
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 use crate::action_ref::ActionRef;
-use crate::advisory::Advisory;
+use crate::advisory::{Advisory, fixed_in_range, normalize_severity};
+use crate::cvss;
+use crate::explain::{AffectedPackage, Explanation};
 use crate::github::GitHubClient;
 
 use super::ActionAdvisoryProvider;
 
+/// Advisories requested per page (the REST API maximum).
+const PER_PAGE: usize = 100;
+
+/// Upper bound on pages followed for one action, so a misbehaving API
+/// can't loop forever.
+const MAX_PAGES: usize = 10;
+
 #[derive(Deserialize)]
 struct GhsaAdvisoryResponse {
     ghsa_id: Option<String>,
+    cve_id: Option<String>,
+    #[serde(default)]
+    identifiers: Vec<GhsaIdentifier>,
     summary: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    references: Vec<String>,
     severity: Option<String>,
     html_url: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    cvss_severities: Option<GhsaCvssSeverities>,
+    /// Deprecated in favor of `cvss_severities`, but still the only CVSS
+    /// on some records.
+    cvss: Option<GhsaCvss>,
     #[serde(default)]
     vulnerabilities: Vec<GhsaVulnerability>,
 }
 
+#[derive(Deserialize)]
+struct GhsaCvssSeverities {
+    cvss_v3: Option<GhsaCvss>,
+    cvss_v4: Option<GhsaCvss>,
+}
+
+#[derive(Deserialize)]
+struct GhsaCvss {
+    vector_string: Option<String>,
+    score: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct GhsaIdentifier {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+}
+
 #[derive(Deserialize)]
 struct GhsaVulnerability {
+    package: Option<GhsaPackage>,
     vulnerable_version_range: Option<String>,
+    first_patched_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhsaPackage {
+    ecosystem: String,
+    name: String,
 }
 
 pub struct GhsaProvider {
@@ -41,15 +90,50 @@ impl ActionAdvisoryProvider for GhsaProvider {
     async fn query(&self, action: &ActionRef) -> Result<Vec<Advisory>> {
         let package_name = action.package_name();
         let api_base = self.client.api_base_url();
-        let json = self
-            .client
-            .api_get(&format!(
-                "{api_base}/advisories?ecosystem=actions&affects={package_name}"
-            ))
-            .await
-            .with_context(|| format!("failed to query advisories for {package_name}"))?;
-
-        parse_advisories(json)
+        let mut url = Some(format!(
+            "{api_base}/advisories?ecosystem=actions&affects={package_name}&per_page={PER_PAGE}"
+        ));
+        let mut advisories = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let (json, next) = self
+                .client
+                .api_get_page(&page_url)
+                .await
+                .with_context(|| format!("failed to query advisories for {package_name}"))?;
+            advisories.extend(parse_advisories(json)?);
+            url = next;
+        }
+        if url.is_some() {
+            warn!(
+                package = %package_name,
+                count = advisories.len(),
+                "stopped after {MAX_PAGES} pages of advisories"
+            );
+        }
+        Ok(advisories)
+    }
+
+    #[instrument(skip(self))]
+    async fn lookup(&self, id: &str) -> Result<Option<Explanation>> {
+        let api_base = self.client.api_base_url();
+        let json = if id.starts_with("GHSA-") {
+            let url = format!("{api_base}/advisories/{id}");
+            match self.client.api_get_optional(&url).await? {
+                Some(advisory) => Value::Array(vec![advisory]),
+                None => return Ok(None),
+            }
+        } else if id.starts_with("CVE-") {
+            let url = format!("{api_base}/advisories?cve_id={id}");
+            self.client.api_get(&url).await?
+        } else {
+            return Ok(None);
+        };
+        let responses: Vec<GhsaAdvisoryResponse> =
+            serde_json::from_value(json).context("expected JSON array from advisory API")?;
+        Ok(responses.into_iter().next().map(to_explanation))
     }
 
     fn name(&self) -> &'static str {
@@ -62,32 +146,92 @@ fn parse_advisories(json: Value) -> Result<Vec<Advisory>> {
     let responses: Vec<GhsaAdvisoryResponse> =
         serde_json::from_value(json).context("expected JSON array from advisory API")?;
 
-    let advisories = responses
+    Ok(responses.into_iter().map(to_advisory).collect())
+}
+
+fn to_advisory(item: GhsaAdvisoryResponse) -> Advisory {
+    let (affected_range, fixed_version) = item
+        .vulnerabilities
         .into_iter()
-        .map(|item| {
-            let affected_range = item
-                .vulnerabilities
-                .into_iter()
-                .find_map(|v| v.vulnerable_version_range);
-
-            Advisory {
-                id: item.ghsa_id.unwrap_or_else(|| "unknown".to_string()),
-                aliases: vec![],
-                summary: item.summary.unwrap_or_default(),
-                severity: item.severity.unwrap_or_else(|| "unknown".to_string()),
-                url: item.html_url.unwrap_or_default(),
-                affected_range,
-                source: "GHSA".to_string(),
-            }
+        .find_map(|v| {
+            let range = v.vulnerable_version_range?;
+            let fixed = v.first_patched_version.or_else(|| fixed_in_range(&range));
+            Some((range, fixed))
         })
-        .collect();
+        .unzip();
+
+    let id = item.ghsa_id.unwrap_or_else(|| "unknown".to_string());
+    // `cve_id` usually repeats a CVE entry in `identifiers`.
+    let mut aliases: Vec<String> = Vec::new();
+    let others = item.cve_id.into_iter().chain(
+        item.identifiers
+            .into_iter()
+            .filter(|i| i.kind != "GHSA")
+            .map(|i| i.value),
+    );
+    for alias in others {
+        if alias != id && !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
 
-    Ok(advisories)
+    let (v3, v4) = item
+        .cvss_severities
+        .map_or((None, None), |s| (s.cvss_v3, s.cvss_v4));
+    let (cvss_score, cvss_vector) = cvss::first_reported(
+        [v3, v4, item.cvss]
+            .into_iter()
+            .flatten()
+            .map(|c| (c.score, c.vector_string)),
+    );
+
+    Advisory {
+        id,
+        aliases,
+        cwes: vec![],
+        cvss_score,
+        cvss_vector,
+        epss: None,
+        published: item.published_at,
+        modified: item.updated_at,
+        withdrawn: None,
+        applicable: None,
+        fixed_version: fixed_version.flatten(),
+        description: item.description.filter(|d| !d.trim().is_empty()),
+        references: item.references,
+        vex: None,
+        summary: item.summary.unwrap_or_default(),
+        severity: normalize_severity(item.severity.as_deref(), cvss_score),
+        url: item.html_url.unwrap_or_default(),
+        affected_range,
+        sources: vec!["GHSA".to_string()],
+    }
+}
+
+fn to_explanation(item: GhsaAdvisoryResponse) -> Explanation {
+    let affected = item
+        .vulnerabilities
+        .iter()
+        .filter_map(|v| {
+            let package = v.package.as_ref()?;
+            Some(AffectedPackage {
+                ecosystem: package.ecosystem.clone(),
+                name: package.name.clone(),
+                range: v.vulnerable_version_range.clone(),
+                fixed: v.first_patched_version.clone(),
+            })
+        })
+        .collect();
+    Explanation {
+        advisory: to_advisory(item),
+        affected,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::advisory::Severity;
     use serde_json::json;
 
     #[test]
@@ -103,14 +247,19 @@ mod tests {
         let json = json!([{
             "ghsa_id": "GHSA-r79c-pqj3-577x",
             "summary": "Super-linter is vulnerable to command injection via crafted filenames",
+            "description": "### Impact\n\nFilenames with shell metacharacters are executed.\n",
+            "references": ["https://github.com/super-linter/super-linter/pull/6000"],
             "severity": "high",
             "html_url": "https://github.com/advisories/GHSA-r79c-pqj3-577x",
+            "published_at": "2025-12-03T21:08:38Z",
+            "updated_at": "2025-12-04T15:30:00Z",
             "vulnerabilities": [{
                 "package": {
                     "ecosystem": "actions",
                     "name": "super-linter/super-linter"
                 },
-                "vulnerable_version_range": ">= 6.0.0, < 8.3.1"
+                "vulnerable_version_range": ">= 6.0.0, < 8.3.1",
+                "first_patched_version": "8.3.1"
             }]
         }]);
 
@@ -123,10 +272,24 @@ mod tests {
             a.summary,
             "Super-linter is vulnerable to command injection via crafted filenames"
         );
-        assert_eq!(a.severity, "high");
+        assert_eq!(a.severity, Some(Severity::High));
         assert_eq!(a.url, "https://github.com/advisories/GHSA-r79c-pqj3-577x");
         assert_eq!(a.affected_range, Some(">= 6.0.0, < 8.3.1".to_string()));
-        assert_eq!(a.source, "GHSA");
+        assert_eq!(a.fixed_version.as_deref(), Some("8.3.1"));
+        assert_eq!(
+            a.published.unwrap().to_rfc3339(),
+            "2025-12-03T21:08:38+00:00"
+        );
+        assert_eq!(
+            a.modified.unwrap().to_rfc3339(),
+            "2025-12-04T15:30:00+00:00"
+        );
+        assert_eq!(a.sources, ["GHSA"]);
+        assert!(a.description.as_deref().unwrap().starts_with("### Impact"));
+        assert_eq!(
+            a.references,
+            ["https://github.com/super-linter/super-linter/pull/6000"]
+        );
     }
 
     #[test]
@@ -143,6 +306,70 @@ mod tests {
         assert!(advisories[0].affected_range.is_none());
     }
 
+    #[test]
+    fn parse_aliases_from_cve_id_and_identifiers() {
+        let json = json!([{
+            "ghsa_id": "GHSA-mrrh-fwg8-r2c3",
+            "cve_id": "CVE-2023-51664",
+            "identifiers": [
+                {"type": "GHSA", "value": "GHSA-mrrh-fwg8-r2c3"},
+                {"type": "CVE", "value": "CVE-2023-51664"}
+            ],
+            "summary": "tj-actions/changed-files leaks secrets",
+            "severity": "high",
+            "html_url": "https://github.com/advisories/GHSA-mrrh-fwg8-r2c3"
+        }, {
+            "ghsa_id": "GHSA-aaaa-bbbb-cccc",
+            "cve_id": null,
+            "summary": "No CVE",
+            "severity": "low",
+            "html_url": "https://example.com/1"
+        }]);
+
+        let advisories = parse_advisories(json).unwrap();
+        assert_eq!(advisories[0].aliases, ["CVE-2023-51664"]);
+        assert!(advisories[1].aliases.is_empty());
+    }
+
+    #[test]
+    fn parse_cvss_prefers_v3_then_v4_then_legacy() {
+        let json = json!([{
+            "ghsa_id": "GHSA-v3",
+            "cvss_severities": {
+                "cvss_v3": {"vector_string": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", "score": 9.8},
+                "cvss_v4": {"vector_string": null, "score": 0.0}
+            },
+            "cvss": {"vector_string": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N", "score": 7.5}
+        }, {
+            "ghsa_id": "GHSA-v4",
+            "cvss_severities": {
+                "cvss_v3": {"vector_string": null, "score": null},
+                "cvss_v4": {"vector_string": "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:N/VA:N/SC:N/SI:N/SA:N", "score": 8.7}
+            }
+        }, {
+            "ghsa_id": "GHSA-legacy",
+            "cvss": {"vector_string": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N", "score": 7.5}
+        }, {
+            "ghsa_id": "GHSA-none",
+            "cvss": {"vector_string": null, "score": 0.0}
+        }]);
+
+        let advisories = parse_advisories(json).unwrap();
+        let scores: Vec<Option<f64>> = advisories
+            .iter()
+            .map(|a| a.cvss_score.map(|s| s.value()))
+            .collect();
+        assert_eq!(scores, [Some(9.8), Some(8.7), Some(7.5), None]);
+        assert!(
+            advisories[1]
+                .cvss_vector
+                .as_deref()
+                .unwrap()
+                .starts_with("CVSS:4.0/")
+        );
+        assert!(advisories[3].cvss_vector.is_none());
+    }
+
     #[test]
     fn parse_multiple_advisories() {
         let json = json!([
@@ -171,4 +398,44 @@ mod tests {
         let json = json!({"error": "bad request"});
         assert!(parse_advisories(json).is_err());
     }
+
+    #[test]
+    fn explanation_lists_packages_and_patched_versions() {
+        let item: GhsaAdvisoryResponse = serde_json::from_value(json!({
+            "ghsa_id": "GHSA-r79c-pqj3-577x",
+            "summary": "Super-linter is vulnerable to command injection",
+            "severity": "high",
+            "vulnerabilities": [
+                {
+                    "package": {"ecosystem": "actions", "name": "super-linter/super-linter"},
+                    "vulnerable_version_range": ">= 6.0.0, < 8.3.1",
+                    "first_patched_version": "8.3.1"
+                },
+                {
+                    "package": {"ecosystem": "actions", "name": "super-linter/super-linter/slim"},
+                    "vulnerable_version_range": ">= 6.0.0, <= 8.3.0",
+                    "first_patched_version": null
+                }
+            ]
+        }))
+        .unwrap();
+
+        let explanation = to_explanation(item);
+        assert_eq!(
+            explanation.advisory.affected_range.as_deref(),
+            Some(">= 6.0.0, < 8.3.1")
+        );
+        let fixed: Vec<_> = explanation
+            .affected
+            .iter()
+            .map(|p| (p.name.as_str(), p.fixed.as_deref()))
+            .collect();
+        assert_eq!(
+            fixed,
+            [
+                ("super-linter/super-linter", Some("8.3.1")),
+                ("super-linter/super-linter/slim", None)
+            ]
+        );
+    }
 }
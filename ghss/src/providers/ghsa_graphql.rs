@@ -0,0 +1,420 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::{debug, instrument};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::{Advisory, fixed_in_range, normalize_severity};
+use crate::cvss;
+use crate::github::GitHubClient;
+
+use super::{ActionAdvisoryProvider, PackageAdvisoryProvider};
+
+/// Vulnerabilities fetched per GraphQL request.
+const PAGE_SIZE: usize = 100;
+
+/// Upper bound on pages fetched for one package, so a misbehaving API
+/// can't loop forever.
+const MAX_PAGES: usize = 20;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VulnerabilityConnection {
+    nodes: Vec<VulnerabilityNode>,
+    page_info: PageInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VulnerabilityNode {
+    vulnerable_version_range: Option<String>,
+    first_patched_version: Option<PatchedVersion>,
+    advisory: AdvisoryNode,
+}
+
+#[derive(Deserialize)]
+struct PatchedVersion {
+    identifier: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdvisoryNode {
+    ghsa_id: String,
+    summary: Option<String>,
+    description: Option<String>,
+    severity: Option<String>,
+    permalink: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    identifiers: Vec<Identifier>,
+    #[serde(default)]
+    references: Vec<Reference>,
+    cwes: Option<CweConnection>,
+    cvss_severities: Option<CvssSeverities>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CvssSeverities {
+    cvss_v3: Option<Cvss>,
+    cvss_v4: Option<Cvss>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cvss {
+    score: Option<f64>,
+    vector_string: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Identifier {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct Reference {
+    url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CweConnection {
+    nodes: Vec<CweNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CweNode {
+    cwe_id: String,
+}
+
+/// GitHub Advisory Database through the GraphQL `securityVulnerabilities`
+/// API. Unlike the REST provider it returns CWEs, and it covers package
+/// ecosystems as well as GitHub Actions. Requires a token.
+pub struct GhsaGraphqlProvider {
+    client: GitHubClient,
+}
+
+impl GhsaGraphqlProvider {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+
+    /// Every advisory affecting `package` in a GraphQL
+    /// `SecurityAdvisoryEcosystem`, following pagination.
+    async fn fetch(&self, ecosystem: &str, package: &str) -> Result<Vec<Advisory>> {
+        let mut nodes = Vec::new();
+        let mut cursor = None;
+        for _ in 0..MAX_PAGES {
+            let query = build_query(ecosystem, package, cursor.as_deref());
+            let data = self
+                .client
+                .graphql_post(&query)
+                .await
+                .with_context(|| format!("failed to query GraphQL advisories for {package}"))?;
+            let page = parse_page(data)?;
+            nodes.extend(page.nodes);
+            if !page.page_info.has_next_page {
+                break;
+            }
+            cursor = page.page_info.end_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        debug!(
+            package,
+            count = nodes.len(),
+            "fetched GraphQL vulnerabilities"
+        );
+        Ok(to_advisories(nodes))
+    }
+}
+
+#[async_trait]
+impl ActionAdvisoryProvider for GhsaGraphqlProvider {
+    #[instrument(skip(self), fields(action = %action))]
+    async fn query(&self, action: &ActionRef) -> Result<Vec<Advisory>> {
+        self.fetch("ACTIONS", &action.package_name()).await
+    }
+
+    fn name(&self) -> &'static str {
+        "GHSA GraphQL"
+    }
+}
+
+#[async_trait]
+impl PackageAdvisoryProvider for GhsaGraphqlProvider {
+    #[instrument(skip(self))]
+    async fn query(&self, package: &str, ecosystem: &str) -> Result<Vec<Advisory>> {
+        match graphql_ecosystem(ecosystem) {
+            Some(ecosystem) => self.fetch(ecosystem, package).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "GHSA GraphQL"
+    }
+}
+
+/// GraphQL `SecurityAdvisoryEcosystem` for an OSV ecosystem name.
+fn graphql_ecosystem(osv_ecosystem: &str) -> Option<&'static str> {
+    match osv_ecosystem {
+        "npm" => Some("NPM"),
+        "Go" => Some("GO"),
+        "PyPI" => Some("PIP"),
+        "crates.io" => Some("RUST"),
+        "Maven" => Some("MAVEN"),
+        "RubyGems" => Some("RUBYGEMS"),
+        "Packagist" => Some("COMPOSER"),
+        "NuGet" => Some("NUGET"),
+        "Pub" => Some("PUB"),
+        "SwiftURL" => Some("SWIFT"),
+        "Hex" => Some("ERLANG"),
+        "GitHub Actions" => Some("ACTIONS"),
+        _ => None,
+    }
+}
+
+fn build_query(ecosystem: &str, package: &str, cursor: Option<&str>) -> String {
+    // JSON string literals are valid GraphQL string literals, so this
+    // escapes quotes and backslashes in package names.
+    let package = Value::from(package);
+    let after = cursor.map_or_else(String::new, |c| format!(", after: {}", Value::from(c)));
+    format!(
+        r#"query {{
+  securityVulnerabilities(first: {PAGE_SIZE}, ecosystem: {ecosystem}, package: {package}{after}) {{
+    nodes {{
+      vulnerableVersionRange
+      firstPatchedVersion {{ identifier }}
+      advisory {{
+        ghsaId
+        summary
+        description
+        severity
+        permalink
+        publishedAt
+        updatedAt
+        identifiers {{ type value }}
+        references {{ url }}
+        cwes(first: 10) {{ nodes {{ cweId }} }}
+        cvssSeverities {{
+          cvssV3 {{ score vectorString }}
+          cvssV4 {{ score vectorString }}
+        }}
+      }}
+    }}
+    pageInfo {{ hasNextPage endCursor }}
+  }}
+}}"#
+    )
+}
+
+fn parse_page(data: Value) -> Result<VulnerabilityConnection> {
+    let connection = data
+        .get("securityVulnerabilities")
+        .cloned()
+        .context("GraphQL response has no securityVulnerabilities")?;
+    serde_json::from_value(connection).context("unexpected securityVulnerabilities shape")
+}
+
+/// One advisory per GHSA ID. An advisory with several vulnerable ranges for
+/// the same package keeps the first.
+fn to_advisories(nodes: Vec<VulnerabilityNode>) -> Vec<Advisory> {
+    let mut advisories: Vec<Advisory> = Vec::new();
+    for node in nodes {
+        let adv = node.advisory;
+        let fixed_version = node
+            .first_patched_version
+            .map(|v| v.identifier)
+            .or_else(|| {
+                node.vulnerable_version_range
+                    .as_deref()
+                    .and_then(fixed_in_range)
+            });
+        if advisories.iter().any(|a| a.id == adv.ghsa_id) {
+            continue;
+        }
+        let aliases = adv
+            .identifiers
+            .into_iter()
+            .filter(|i| i.kind != "GHSA" && i.value != adv.ghsa_id)
+            .map(|i| i.value)
+            .collect();
+        let cwes = adv
+            .cwes
+            .map(|c| c.nodes.into_iter().map(|n| n.cwe_id).collect())
+            .unwrap_or_default();
+        let (cvss_score, cvss_vector) = cvss::first_reported(
+            adv.cvss_severities
+                .into_iter()
+                .flat_map(|s| [s.cvss_v3, s.cvss_v4])
+                .flatten()
+                .map(|c| (c.score, c.vector_string)),
+        );
+        advisories.push(Advisory {
+            id: adv.ghsa_id,
+            aliases,
+            cwes,
+            cvss_score,
+            cvss_vector,
+            epss: None,
+            published: adv.published_at,
+            modified: adv.updated_at,
+            withdrawn: None,
+            applicable: None,
+            fixed_version,
+            description: adv.description.filter(|d| !d.trim().is_empty()),
+            references: adv.references.into_iter().map(|r| r.url).collect(),
+            vex: None,
+            summary: adv.summary.unwrap_or_default(),
+            severity: normalize_severity(adv.severity.as_deref(), cvss_score),
+            url: adv.permalink.unwrap_or_default(),
+            affected_range: node.vulnerable_version_range,
+            sources: vec!["GHSA".to_string()],
+        });
+    }
+    advisories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisory::Severity;
+    use serde_json::json;
+
+    fn node(ghsa: &str, range: &str) -> Value {
+        json!({
+            "vulnerableVersionRange": range,
+            "firstPatchedVersion": {"identifier": "4.17.19"},
+            "advisory": {
+                "ghsaId": ghsa,
+                "summary": "Prototype pollution",
+                "description": "Versions of lodash prior to 4.17.19 are vulnerable.\n",
+                "severity": "MODERATE",
+                "permalink": format!("https://github.com/advisories/{ghsa}"),
+                "publishedAt": "2020-07-15T19:15:48Z",
+                "updatedAt": "2024-02-08T22:44:52Z",
+                "identifiers": [
+                    {"type": "GHSA", "value": ghsa},
+                    {"type": "CVE", "value": "CVE-2020-8203"}
+                ],
+                "references": [{"url": "https://nvd.nist.gov/vuln/detail/CVE-2020-8203"}],
+                "cwes": {"nodes": [{"cweId": "CWE-1321"}]},
+                "cvssSeverities": {
+                    "cvssV3": {"score": 7.4, "vectorString": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:N/I:H/A:H"},
+                    "cvssV4": {"score": 0.0, "vectorString": null}
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn query_escapes_package_and_adds_cursor() {
+        let query = build_query("NPM", "evil\"pkg", Some("Y3Vyc29y"));
+        assert!(query.contains(r#"ecosystem: NPM, package: "evil\"pkg", after: "Y3Vyc29y""#));
+        assert!(!build_query("ACTIONS", "a/b", None).contains("after"));
+    }
+
+    #[test]
+    fn maps_osv_ecosystems() {
+        assert_eq!(graphql_ecosystem("crates.io"), Some("RUST"));
+        assert_eq!(graphql_ecosystem("PyPI"), Some("PIP"));
+        assert_eq!(graphql_ecosystem("Linux"), None);
+    }
+
+    #[test]
+    fn parses_aliases_cwes_cvss_details_and_normalizes_severity() {
+        let page = parse_page(json!({
+            "securityVulnerabilities": {
+                "nodes": [
+                    node("GHSA-p6mc-m468-83gw", "< 4.17.19"),
+                    node("GHSA-p6mc-m468-83gw", ">= 5.0.0, < 5.0.1")
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        }))
+        .unwrap();
+        let advisories = to_advisories(page.nodes);
+        assert_eq!(advisories.len(), 1);
+        let a = &advisories[0];
+        assert_eq!(a.aliases, ["CVE-2020-8203"]);
+        assert_eq!(a.cwes, ["CWE-1321"]);
+        assert!(
+            a.description
+                .as_deref()
+                .unwrap()
+                .starts_with("Versions of lodash")
+        );
+        assert_eq!(
+            a.references,
+            ["https://nvd.nist.gov/vuln/detail/CVE-2020-8203"]
+        );
+        assert_eq!(a.cvss_score.map(|s| s.value()), Some(7.4));
+        assert!(a.cvss_vector.as_deref().unwrap().starts_with("CVSS:3.1/"));
+        assert_eq!(a.severity, Some(Severity::Medium));
+        assert_eq!(
+            a.published.unwrap().to_rfc3339(),
+            "2020-07-15T19:15:48+00:00"
+        );
+        assert!(a.modified > a.published);
+        assert_eq!(a.affected_range.as_deref(), Some("< 4.17.19"));
+        assert_eq!(a.fixed_version.as_deref(), Some("4.17.19"));
+    }
+
+    #[tokio::test]
+    async fn follows_pagination() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("after"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"securityVulnerabilities": {
+                    "nodes": [node("GHSA-2222-2222-2222", "< 2.0.0")],
+                    "pageInfo": {"hasNextPage": false, "endCursor": null}
+                }}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"securityVulnerabilities": {
+                    "nodes": [node("GHSA-1111-1111-1111", "< 1.0.0")],
+                    "pageInfo": {"hasNextPage": true, "endCursor": "page2"}
+                }}
+            })))
+            .mount(&server)
+            .await;
+
+        // SAFETY: test-only; env var mutation is unsafe in Rust 2024
+        unsafe { std::env::set_var("GHSS_API_BASE_URL", server.uri()) };
+        let client = GitHubClient::new(Some("fake-token".to_string()));
+        unsafe { std::env::remove_var("GHSS_API_BASE_URL") };
+
+        let provider = GhsaGraphqlProvider::new(client);
+        let advisories = PackageAdvisoryProvider::query(&provider, "lodash", "npm")
+            .await
+            .unwrap();
+        let ids: Vec<&str> = advisories.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["GHSA-1111-1111-1111", "GHSA-2222-2222-2222"]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Provider health check: one known query per configured provider, so a
+//! bad token or an unreachable endpoint shows up before a long scan rather
+//! than as a stage error on every node.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use serde::Serialize;
+
+use super::Providers;
+use crate::action_ref::ActionRef;
+
+/// Action queried against action providers. It has published advisories,
+/// so a healthy provider returns a non-empty answer.
+pub const PROBE_ACTION: &str = "tj-actions/changed-files@v35";
+
+/// Package, version and ecosystem queried against package providers. A
+/// concrete version, since some providers cannot look up a package without
+/// one.
+pub const PROBE_PACKAGE: (&str, &str, &str) = ("lodash", "4.17.20", "npm");
+
+/// Which provider trait was probed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeKind {
+    Actions,
+    Packages,
+}
+
+impl fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeKind::Actions => write!(f, "actions"),
+            ProbeKind::Packages => write!(f, "packages"),
+        }
+    }
+}
+
+/// Outcome of one probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    /// HTTP 401 or 403: the token is missing, invalid, or lacks access.
+    Auth,
+    /// The provider answered with another error, or not in time.
+    Error,
+    /// The request never got a response.
+    Unreachable,
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthStatus::Ok => write!(f, "ok"),
+            HealthStatus::Auth => write!(f, "auth failed"),
+            HealthStatus::Error => write!(f, "error"),
+            HealthStatus::Unreachable => write!(f, "unreachable"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub provider: &'static str,
+    pub kind: ProbeKind,
+    pub status: HealthStatus,
+    pub latency_ms: u64,
+    /// Advisories returned, on success.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advisories: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl fmt::Display for ProviderHealth {
+    /// e.g. `GHSA (actions): ok in 212 ms, 3 advisories`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): {} in {} ms",
+            self.provider, self.kind, self.status, self.latency_ms
+        )?;
+        if let Some(count) = self.advisories {
+            write!(f, ", {count} advisories")?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, "\n    {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl ProviderHealth {
+    fn from_result<T>(
+        provider: &'static str,
+        kind: ProbeKind,
+        started: Instant,
+        result: Option<anyhow::Result<Vec<T>>>,
+        timeout: Duration,
+    ) -> Self {
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let (status, advisories, error) = match result {
+            Some(Ok(advisories)) => (HealthStatus::Ok, Some(advisories.len()), None),
+            Some(Err(e)) => (classify(&e), None, Some(format!("{e:#}"))),
+            None => (
+                HealthStatus::Error,
+                None,
+                Some(format!("timed out after {}s", timeout.as_secs_f64())),
+            ),
+        };
+        Self {
+            provider,
+            kind,
+            status,
+            latency_ms,
+            advisories,
+            error,
+        }
+    }
+}
+
+fn classify(error: &anyhow::Error) -> HealthStatus {
+    let Some(reqwest_error) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+    else {
+        return HealthStatus::Error;
+    };
+    match reqwest_error.status() {
+        Some(status) if status.as_u16() == 401 || status.as_u16() == 403 => HealthStatus::Auth,
+        Some(_) => HealthStatus::Error,
+        None if reqwest_error.is_connect() || reqwest_error.is_timeout() => {
+            HealthStatus::Unreachable
+        }
+        None => HealthStatus::Error,
+    }
+}
+
+/// Probe every provider in `providers` concurrently, giving each up to
+/// `timeout`. Results are in registration order, action providers first.
+pub async fn check(providers: &Providers, timeout: Duration) -> Vec<ProviderHealth> {
+    let action: ActionRef = PROBE_ACTION.parse().expect("probe action is valid");
+    let (package, version, ecosystem) = PROBE_PACKAGE;
+
+    let actions = join_all(providers.action_providers().into_iter().map(|provider| {
+        let action = action.clone();
+        async move {
+            let started = Instant::now();
+            let result = tokio::time::timeout(timeout, provider.query(&action))
+                .await
+                .ok();
+            ProviderHealth::from_result(
+                provider.name(),
+                ProbeKind::Actions,
+                started,
+                result,
+                timeout,
+            )
+        }
+    }));
+    let packages = join_all(
+        providers
+            .package_providers()
+            .into_iter()
+            .map(|provider| async move {
+                let started = Instant::now();
+                let result = tokio::time::timeout(
+                    timeout,
+                    provider.query_version(package, version, ecosystem),
+                )
+                .await
+                .ok();
+                ProviderHealth::from_result(
+                    provider.name(),
+                    ProbeKind::Packages,
+                    started,
+                    result,
+                    timeout,
+                )
+            }),
+    );
+    let (mut results, packages) = futures::join!(actions, packages);
+    results.extend(packages);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::http::{FieldMapping, HttpProvider, HttpProviderConfig};
+    use std::sync::Arc;
+
+    fn http(url: String) -> Arc<HttpProvider> {
+        Arc::new(
+            HttpProvider::new(HttpProviderConfig {
+                name: "probe".to_string(),
+                url,
+                auth_header: None,
+                mapping: FieldMapping::default(),
+            })
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn reports_ok_auth_and_unreachable() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let ok = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([{"id": "GHSA-mrrh-fwg8-r2c3"}])),
+            )
+            .mount(&ok)
+            .await;
+        let denied = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&denied)
+            .await;
+        // Bind then drop a listener so nothing is listening on its port.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let closed_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let providers = Providers::new()
+            .with_action_provider(http(ok.uri()))
+            .with_action_provider(http(denied.uri()))
+            .with_action_provider(http(closed_url));
+
+        let results = check(&providers, Duration::from_secs(10)).await;
+        let statuses: Vec<HealthStatus> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                HealthStatus::Ok,
+                HealthStatus::Auth,
+                HealthStatus::Unreachable
+            ]
+        );
+        assert_eq!(results[0].advisories, Some(1));
+        assert!(results[1].error.as_deref().unwrap().contains("401"));
+    }
+}
@@ -0,0 +1,349 @@
+//! Advisories from an arbitrary HTTP endpoint, so internal vulnerability
+//! databases can be queried alongside GHSA and OSV.
+//!
+//! The endpoint is described by an [`HttpProviderConfig`]: a URL template
+//! with `{owner}`, `{repo}`, `{path}`, `{package}` and `{ref}` placeholders,
+//! an optional auth header, and dotted paths (`data.items`, `refs.0.url`)
+//! locating the advisory list and each advisory field in the JSON response.
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::action_ref::ActionRef;
+use crate::advisory::{Advisory, fixed_in_range, normalize_severity};
+
+use super::ActionAdvisoryProvider;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpProviderConfig {
+    /// Reported as each advisory's source and in query errors.
+    pub name: String,
+    pub url: String,
+    /// `Header-Name: value`. `${VAR}` in the value is read from the
+    /// environment when the provider is created.
+    #[serde(default)]
+    pub auth_header: Option<String>,
+    #[serde(default)]
+    pub mapping: FieldMapping,
+}
+
+/// Dotted paths into the response. `advisories` locates the list (the
+/// response itself when empty); the rest are relative to one advisory.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldMapping {
+    pub advisories: String,
+    pub id: String,
+    pub summary: String,
+    pub severity: String,
+    pub url: String,
+    pub affected_range: String,
+    /// Falls back to the last `< X` bound of the affected range.
+    pub fixed_version: String,
+    /// A string or an array of strings.
+    pub aliases: String,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            advisories: String::new(),
+            id: "id".to_string(),
+            summary: "summary".to_string(),
+            severity: "severity".to_string(),
+            url: "url".to_string(),
+            affected_range: "affected_range".to_string(),
+            fixed_version: "fixed_version".to_string(),
+            aliases: "aliases".to_string(),
+        }
+    }
+}
+
+pub struct HttpProvider {
+    config: HttpProviderConfig,
+    auth: Option<(String, String)>,
+    client: reqwest::Client,
+}
+
+impl HttpProvider {
+    /// Validate `config` and resolve `${VAR}` references in its auth header.
+    pub fn new(config: HttpProviderConfig) -> Result<Self> {
+        if !config.url.starts_with("http://") && !config.url.starts_with("https://") {
+            bail!(
+                "provider {}: url must start with http:// or https://",
+                config.name
+            );
+        }
+        if config.mapping.id.is_empty() {
+            bail!("provider {}: mapping.id must not be empty", config.name);
+        }
+        let auth = config
+            .auth_header
+            .as_deref()
+            .map(|header| {
+                let (name, value) = header.split_once(':').with_context(|| {
+                    format!(
+                        "provider {}: auth_header must look like `Name: value`",
+                        config.name
+                    )
+                })?;
+                let value = expand_env(value.trim())
+                    .with_context(|| format!("provider {}: auth_header", config.name))?;
+                Ok::<_, anyhow::Error>((name.trim().to_string(), value))
+            })
+            .transpose()?;
+        Ok(Self {
+            config,
+            auth,
+            client: reqwest::Client::builder()
+                .user_agent("ghss")
+                .build()
+                .expect("failed to build HTTP client"),
+        })
+    }
+
+    fn url_for(&self, action: &ActionRef) -> String {
+        let path = action.path.as_deref().unwrap_or("");
+        self.config
+            .url
+            .replace("{owner}", &encode(&action.owner))
+            .replace("{repo}", &encode(&action.repo))
+            .replace("{path}", &encode(path))
+            .replace("{package}", &encode(&action.package_name()))
+            .replace("{ref}", &encode(&action.git_ref))
+    }
+}
+
+#[async_trait]
+impl ActionAdvisoryProvider for HttpProvider {
+    #[instrument(skip(self), fields(action = %action, provider = %self.config.name))]
+    async fn query(&self, action: &ActionRef) -> Result<Vec<Advisory>> {
+        let name = &self.config.name;
+        let url = self.url_for(action);
+        let mut request = self.client.get(&url).header("Accept", "application/json");
+        if let Some((header, value)) = &self.auth {
+            request = request.header(header, value);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("{name}: request to {url} failed"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        let json: Value = response
+            .error_for_status()
+            .with_context(|| format!("{name}: {url} returned non-success status"))?
+            .json()
+            .await
+            .with_context(|| format!("{name}: failed to parse JSON from {url}"))?;
+        map_advisories(&self.config, &json)
+    }
+
+    fn name(&self) -> &'static str {
+        "HTTP"
+    }
+}
+
+fn map_advisories(config: &HttpProviderConfig, json: &Value) -> Result<Vec<Advisory>> {
+    let mapping = &config.mapping;
+    let items = lookup(json, &mapping.advisories)
+        .and_then(Value::as_array)
+        .with_context(|| {
+            format!(
+                "{}: no advisory array at `{}`",
+                config.name, mapping.advisories
+            )
+        })?;
+    items
+        .iter()
+        .map(|item| {
+            let field = |path: &str| lookup(item, path).and_then(scalar);
+            let id = field(&mapping.id)
+                .with_context(|| format!("{}: advisory without `{}`", config.name, mapping.id))?;
+            let aliases = match lookup(item, &mapping.aliases) {
+                Some(Value::Array(values)) => values.iter().filter_map(scalar).collect(),
+                Some(value) => scalar(value).into_iter().collect(),
+                None => vec![],
+            };
+            let affected_range = field(&mapping.affected_range);
+            let fixed_version = field(&mapping.fixed_version)
+                .or_else(|| affected_range.as_deref().and_then(fixed_in_range));
+            Ok(Advisory {
+                id,
+                aliases,
+                cwes: vec![],
+                cvss_score: None,
+                cvss_vector: None,
+                epss: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                applicable: None,
+                fixed_version,
+                description: None,
+                references: vec![],
+                vex: None,
+                summary: field(&mapping.summary).unwrap_or_default(),
+                severity: normalize_severity(field(&mapping.severity).as_deref(), None),
+                url: field(&mapping.url).unwrap_or_default(),
+                affected_range,
+                sources: vec![config.name.clone()],
+            })
+        })
+        .collect()
+}
+
+/// Follow a dotted path; numeric segments index arrays. An empty path is
+/// `value` itself.
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.').try_fold(value, |v, segment| match v {
+        Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => v.get(segment),
+    })
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Percent-encode everything but unreserved characters and `/`.
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+/// Replace each `${VAR}` in `value` with the environment variable's value.
+pub(crate) fn expand_env(value: &str) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .with_context(|| format!("unterminated `${{` in `{value}`"))?;
+        let var = &rest[start + 2..start + end];
+        out.push_str(&rest[..start]);
+        out.push_str(&std::env::var(var).with_context(|| format!("env var {var} is not set"))?);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisory::Severity;
+    use serde_json::json;
+
+    fn config(url: &str) -> HttpProviderConfig {
+        HttpProviderConfig {
+            name: "vulndb".to_string(),
+            url: url.to_string(),
+            auth_header: None,
+            mapping: FieldMapping {
+                advisories: "data.advisories".to_string(),
+                id: "key".to_string(),
+                summary: "title".to_string(),
+                severity: "rating.level".to_string(),
+                url: "links.0".to_string(),
+                affected_range: "versions".to_string(),
+                fixed_version: "patched".to_string(),
+                aliases: "cves".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn maps_fields_by_dotted_path() {
+        let json = json!({"data": {"advisories": [{
+            "key": "VULN-17",
+            "title": "Token leak",
+            "rating": {"level": "HIGH"},
+            "links": ["https://vulndb.example/VULN-17"],
+            "versions": "< 2.1.0",
+            "cves": ["CVE-2025-0001"]
+        }, {"key": 18, "cves": "CVE-2025-0002"}]}});
+        let advisories = map_advisories(&config("https://x"), &json).unwrap();
+        assert_eq!(advisories.len(), 2);
+        let a = &advisories[0];
+        assert_eq!(a.id, "VULN-17");
+        assert_eq!(a.severity, Some(Severity::High));
+        assert_eq!(a.url, "https://vulndb.example/VULN-17");
+        assert_eq!(a.affected_range.as_deref(), Some("< 2.1.0"));
+        assert_eq!(a.fixed_version.as_deref(), Some("2.1.0"));
+        assert_eq!(a.aliases, ["CVE-2025-0001"]);
+        assert_eq!(a.sources, ["vulndb"]);
+        assert_eq!(advisories[1].id, "18");
+        assert_eq!(advisories[1].severity, None);
+        assert_eq!(advisories[1].aliases, ["CVE-2025-0002"]);
+
+        let err = map_advisories(&config("https://x"), &json!({"data": {}})).unwrap_err();
+        assert!(err.to_string().contains("data.advisories"), "{err}");
+    }
+
+    #[test]
+    fn rejects_bad_config() {
+        assert!(HttpProvider::new(config("ftp://x")).is_err());
+        let mut cfg = config("https://x");
+        cfg.auth_header = Some("Bearer abc".to_string());
+        assert!(HttpProvider::new(cfg).is_err());
+        let mut cfg = config("https://x");
+        cfg.auth_header = Some("Authorization: Bearer ${GHSS_TEST_UNSET_HTTP_TOKEN}".to_string());
+        let err = HttpProvider::new(cfg).err().unwrap();
+        assert!(format!("{err:#}").contains("GHSS_TEST_UNSET_HTTP_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn queries_templated_url_with_auth() {
+        use wiremock::matchers::{header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/actions/github/codeql-action/init"))
+            .and(query_param("ref", "v3"))
+            .and(header("x-api-key", "s3cret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"advisories": [{"key": "VULN-1", "title": "Internal finding"}]}
+            })))
+            .mount(&server)
+            .await;
+
+        let mut cfg = config(&format!(
+            "{}/v1/actions/{{package}}?ref={{ref}}",
+            server.uri()
+        ));
+        // SAFETY: test-only; env var mutation is unsafe in Rust 2024
+        unsafe { std::env::set_var("GHSS_TEST_HTTP_PROVIDER_KEY", "s3cret") };
+        cfg.auth_header = Some("X-Api-Key: ${GHSS_TEST_HTTP_PROVIDER_KEY}".to_string());
+        let provider = HttpProvider::new(cfg).unwrap();
+        unsafe { std::env::remove_var("GHSS_TEST_HTTP_PROVIDER_KEY") };
+
+        let action: ActionRef = "github/codeql-action/init@v3".parse().unwrap();
+        let advisories = provider.query(&action).await.unwrap();
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].summary, "Internal finding");
+
+        let other: ActionRef = "actions/checkout@v4".parse().unwrap();
+        assert!(provider.query(&other).await.unwrap().is_empty());
+    }
+}
@@ -5,12 +5,20 @@ use async_trait::async_trait;
 
 use crate::action_ref::ActionRef;
 use crate::advisory::Advisory;
+use crate::explain::Explanation;
 use crate::github::GitHubClient;
 
 /// Advisory provider that queries by action reference (e.g. "owner/repo@ref").
 #[async_trait]
 pub trait ActionAdvisoryProvider: Send + Sync {
     async fn query(&self, action: &ActionRef) -> anyhow::Result<Vec<Advisory>>;
+
+    /// Fetch one advisory by GHSA or CVE ID, for `ghss explain`. `Ok(None)`
+    /// when the provider doesn't know it or can't look advisories up by ID.
+    async fn lookup(&self, _id: &str) -> anyhow::Result<Option<Explanation>> {
+        Ok(None)
+    }
+
     fn name(&self) -> &'static str;
 }
 
@@ -18,14 +26,55 @@ pub trait ActionAdvisoryProvider: Send + Sync {
 #[async_trait]
 pub trait PackageAdvisoryProvider: Send + Sync {
     async fn query(&self, package: &str, ecosystem: &str) -> anyhow::Result<Vec<Advisory>>;
+
+    /// Query advisories for one version (or manifest version spec) of a
+    /// package. Providers that cannot narrow by version query the package.
+    async fn query_version(
+        &self,
+        package: &str,
+        _version: &str,
+        ecosystem: &str,
+    ) -> anyhow::Result<Vec<Advisory>> {
+        self.query(package, ecosystem).await
+    }
+
+    /// Query every package of a manifest. Returns one result per query, in
+    /// order. Providers without a batch endpoint query one at a time.
+    async fn query_batch(&self, queries: &[PackageQuery]) -> Vec<anyhow::Result<Vec<Advisory>>> {
+        let mut results = Vec::with_capacity(queries.len());
+        for q in queries {
+            results.push(self.query_version(&q.name, &q.version, &q.ecosystem).await);
+        }
+        results
+    }
+
     fn name(&self) -> &'static str;
 }
 
+/// One package in a [`PackageAdvisoryProvider::query_batch`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageQuery {
+    pub name: String,
+    /// Version or manifest version spec.
+    pub version: String,
+    /// OSV ecosystem name.
+    pub ecosystem: String,
+}
+
+pub mod depsdev;
 pub mod ghsa;
+pub mod ghsa_graphql;
+pub mod health;
+pub mod http;
 pub mod osv;
+pub mod osv_db;
+pub mod snyk;
 
+use depsdev::{DepsDevClient, DepsDevProvider};
 use ghsa::GhsaProvider;
+use ghsa_graphql::GhsaGraphqlProvider;
 use osv::{OsvActionProvider, OsvClient, OsvPackageProvider};
+use osv_db::{OsvDatabase, OsvDbProvider};
 
 pub fn create_action_providers(
     provider: &str,
@@ -33,22 +82,101 @@ pub fn create_action_providers(
 ) -> anyhow::Result<Vec<Arc<dyn ActionAdvisoryProvider>>> {
     match provider {
         "ghsa" => Ok(vec![Arc::new(GhsaProvider::new(github_client.clone()))]),
+        "ghsa-graphql" => Ok(vec![Arc::new(GhsaGraphqlProvider::new(
+            github_client.clone(),
+        ))]),
         "osv" => Ok(vec![Arc::new(OsvActionProvider::new(OsvClient::new()))]),
+        "osv-offline" => Ok(vec![Arc::new(OsvDbProvider::new(OsvDatabase::new(
+            OsvDatabase::default_dir(),
+        )))]),
+        // deps.dev has no advisories for GitHub Actions.
+        "depsdev" => Ok(vec![]),
         "all" => Ok(vec![
             Arc::new(GhsaProvider::new(github_client.clone())),
             Arc::new(OsvActionProvider::new(OsvClient::new())),
         ]),
-        other => bail!("unknown provider: {other} (valid: ghsa, osv, all)"),
+        other => bail!(
+            "unknown provider: {other} (valid: ghsa, ghsa-graphql, osv, osv-offline, depsdev, all)"
+        ),
     }
 }
 
 pub fn create_package_providers(
     provider: &str,
+    github_client: &GitHubClient,
 ) -> anyhow::Result<Vec<Arc<dyn PackageAdvisoryProvider>>> {
     match provider {
         "ghsa" => Ok(vec![]),
-        "osv" | "all" => Ok(vec![Arc::new(OsvPackageProvider::new(OsvClient::new()))]),
-        other => bail!("unknown provider: {other} (valid: ghsa, osv, all)"),
+        "ghsa-graphql" => Ok(vec![Arc::new(GhsaGraphqlProvider::new(
+            github_client.clone(),
+        ))]),
+        "osv" => Ok(vec![Arc::new(OsvPackageProvider::new(OsvClient::new()))]),
+        "osv-offline" => Ok(vec![Arc::new(OsvDbProvider::new(OsvDatabase::new(
+            OsvDatabase::default_dir(),
+        )))]),
+        "depsdev" => Ok(vec![Arc::new(DepsDevProvider::new(DepsDevClient::new()))]),
+        "all" => Ok(vec![
+            Arc::new(OsvPackageProvider::new(OsvClient::new())),
+            Arc::new(DepsDevProvider::new(DepsDevClient::new())),
+        ]),
+        other => bail!(
+            "unknown provider: {other} (valid: ghsa, ghsa-graphql, osv, osv-offline, depsdev, all)"
+        ),
+    }
+}
+
+/// The advisory providers an audit queries: a named built-in selection plus
+/// any implementations registered by the caller.
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ghss::github::GitHubClient;
+/// # use ghss::providers::{ActionAdvisoryProvider, Providers};
+/// # fn demo(client: &GitHubClient, internal: Arc<dyn ActionAdvisoryProvider>) -> anyhow::Result<()> {
+/// let providers = Providers::named("osv", client)?.with_action_provider(internal);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Providers {
+    action: Vec<Arc<dyn ActionAdvisoryProvider>>,
+    package: Vec<Arc<dyn PackageAdvisoryProvider>>,
+}
+
+impl Providers {
+    /// No providers; register them with `with_action_provider` and
+    /// `with_package_provider`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in providers for a `--provider` name (`ghsa`, `osv`, `all`, ...).
+    pub fn named(provider: &str, github_client: &GitHubClient) -> anyhow::Result<Self> {
+        Ok(Self {
+            action: create_action_providers(provider, github_client)?,
+            package: create_package_providers(provider, github_client)?,
+        })
+    }
+
+    /// Also query `provider` for every action. Registered providers run
+    /// after the built-in ones.
+    pub fn with_action_provider(mut self, provider: Arc<dyn ActionAdvisoryProvider>) -> Self {
+        self.action.push(provider);
+        self
+    }
+
+    /// Also query `provider` for every dependency.
+    pub fn with_package_provider(mut self, provider: Arc<dyn PackageAdvisoryProvider>) -> Self {
+        self.package.push(provider);
+        self
+    }
+
+    pub fn action_providers(&self) -> Vec<Arc<dyn ActionAdvisoryProvider>> {
+        self.action.clone()
+    }
+
+    pub fn package_providers(&self) -> Vec<Arc<dyn PackageAdvisoryProvider>> {
+        self.package.clone()
     }
 }
 
@@ -89,21 +217,89 @@ mod tests {
 
     #[test]
     fn package_providers_ghsa_returns_empty() {
-        let providers = create_package_providers("ghsa").unwrap();
+        let providers = create_package_providers("ghsa", &GitHubClient::new(None)).unwrap();
         assert!(providers.is_empty());
     }
 
     #[test]
     fn package_providers_osv() {
-        let providers = create_package_providers("osv").unwrap();
+        let providers = create_package_providers("osv", &GitHubClient::new(None)).unwrap();
         assert_eq!(providers.len(), 1);
         assert_eq!(providers[0].name(), "OSV");
     }
 
     #[test]
     fn package_providers_all() {
-        let providers = create_package_providers("all").unwrap();
-        assert_eq!(providers.len(), 1);
+        let providers = create_package_providers("all", &GitHubClient::new(None)).unwrap();
+        assert_eq!(providers.len(), 2);
         assert_eq!(providers[0].name(), "OSV");
+        assert_eq!(providers[1].name(), "deps.dev");
+    }
+
+    #[test]
+    fn depsdev_provides_packages_only() {
+        let client = GitHubClient::new(None);
+        assert!(
+            create_action_providers("depsdev", &client)
+                .unwrap()
+                .is_empty()
+        );
+        let providers = create_package_providers("depsdev", &client).unwrap();
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].name(), "deps.dev");
+    }
+
+    struct Internal;
+
+    #[async_trait]
+    impl ActionAdvisoryProvider for Internal {
+        async fn query(&self, _action: &ActionRef) -> anyhow::Result<Vec<Advisory>> {
+            Ok(vec![])
+        }
+
+        fn name(&self) -> &'static str {
+            "internal"
+        }
+    }
+
+    #[async_trait]
+    impl PackageAdvisoryProvider for Internal {
+        async fn query(&self, _package: &str, _ecosystem: &str) -> anyhow::Result<Vec<Advisory>> {
+            Ok(vec![])
+        }
+
+        fn name(&self) -> &'static str {
+            "internal"
+        }
+    }
+
+    #[test]
+    fn registered_providers_follow_named_ones() {
+        let providers = Providers::named("osv", &GitHubClient::new(None))
+            .unwrap()
+            .with_action_provider(Arc::new(Internal))
+            .with_package_provider(Arc::new(Internal));
+        let names: Vec<_> = providers
+            .action_providers()
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(names, ["OSV", "internal"]);
+        assert_eq!(providers.package_providers()[1].name(), "internal");
+
+        let only = Providers::new().with_action_provider(Arc::new(Internal));
+        assert_eq!(only.action_providers().len(), 1);
+        assert!(only.package_providers().is_empty());
+    }
+
+    #[test]
+    fn ghsa_graphql_provides_actions_and_packages() {
+        let client = GitHubClient::new(None);
+        let actions = create_action_providers("ghsa-graphql", &client).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].name(), "GHSA GraphQL");
+        let packages = create_package_providers("ghsa-graphql", &client).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name(), "GHSA GraphQL");
     }
 }
@@ -1,21 +1,53 @@
-use anyhow::{Context, Result, bail};
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{Context, Result, anyhow, bail};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use serde::Deserialize;
 use tracing::instrument;
 
 use crate::action_ref::ActionRef;
-use crate::advisory::Advisory;
+use crate::advisory::{Advisory, normalize_severity};
+use crate::cvss;
+use crate::explain::{AffectedPackage, Explanation};
 
-use super::{ActionAdvisoryProvider, PackageAdvisoryProvider};
+use super::{ActionAdvisoryProvider, PackageAdvisoryProvider, PackageQuery};
 
 const OSV_API_URL: &str = "https://api.osv.dev/v1/query";
 
+/// Most queries OSV accepts in one `querybatch` request.
+const BATCH_SIZE: usize = 1000;
+
+/// Concurrent `vulns/{id}` fetches while resolving a batch.
+const VULN_CONCURRENCY: usize = 8;
+
 #[derive(Deserialize)]
 struct OsvResponse {
     #[serde(default)]
     vulns: Vec<OsvVuln>,
 }
 
+/// `querybatch` answers with vulnerability IDs only.
+#[derive(Deserialize)]
+struct OsvBatchResponse {
+    #[serde(default)]
+    results: Vec<OsvBatchResult>,
+}
+
+#[derive(Deserialize)]
+struct OsvBatchResult {
+    #[serde(default)]
+    vulns: Vec<OsvVulnId>,
+    /// Set when a package has more vulnerabilities than one page holds.
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvVulnId {
+    id: String,
+}
+
 #[derive(Deserialize)]
 struct OsvVuln {
     id: String,
@@ -24,11 +56,29 @@ struct OsvVuln {
     #[serde(default)]
     summary: String,
     #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
     references: Vec<OsvReference>,
     #[serde(default)]
     affected: Vec<OsvAffected>,
     #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    #[serde(default)]
     database_specific: Option<OsvDatabaseSpecific>,
+    #[serde(default)]
+    published: Option<DateTime<Utc>>,
+    #[serde(default)]
+    modified: Option<DateTime<Utc>>,
+    #[serde(default)]
+    withdrawn: Option<DateTime<Utc>>,
+}
+
+/// `score` holds the vector, e.g. `CVSS:3.1/AV:N/...`.
+#[derive(Deserialize)]
+struct OsvSeverity {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
 }
 
 #[derive(Deserialize)]
@@ -40,10 +90,18 @@ struct OsvReference {
 
 #[derive(Deserialize)]
 struct OsvAffected {
+    #[serde(default)]
+    package: Option<OsvPackage>,
     #[serde(default)]
     ranges: Vec<OsvRange>,
 }
 
+#[derive(Deserialize)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
 #[derive(Deserialize)]
 struct OsvRange {
     #[serde(default)]
@@ -88,14 +146,16 @@ impl OsvClient {
         }
     }
 
+    /// Advisories for `package`, narrowed by OSV to those affecting
+    /// `version` when one is given.
     #[instrument(skip(self))]
-    pub async fn query(&self, package: &str, ecosystem: &str) -> Result<Vec<Advisory>> {
-        let body = serde_json::json!({
-            "package": {
-                "name": package,
-                "ecosystem": ecosystem
-            }
-        });
+    pub async fn query(
+        &self,
+        package: &str,
+        version: Option<&str>,
+        ecosystem: &str,
+    ) -> Result<Vec<Advisory>> {
+        let body = query_body(package, version, ecosystem);
 
         let response = self
             .http
@@ -117,6 +177,176 @@ impl OsvClient {
 
         parse_osv_response(json)
     }
+
+    /// Query many packages through `querybatch`, [`BATCH_SIZE`] per request.
+    /// Returns one result per query, in order; a failed request fails every
+    /// query it carried.
+    #[instrument(skip_all, fields(queries = queries.len()))]
+    pub async fn query_batch(&self, queries: &[PackageQuery]) -> Vec<Result<Vec<Advisory>>> {
+        let mut results = Vec::with_capacity(queries.len());
+        for chunk in queries.chunks(BATCH_SIZE) {
+            match self.query_chunk(chunk).await {
+                Ok(chunk_results) => results.extend(chunk_results),
+                Err(e) => results.extend(chunk.iter().map(|_| Err(anyhow!("{e:#}")))),
+            }
+        }
+        results
+    }
+
+    async fn query_chunk(&self, chunk: &[PackageQuery]) -> Result<Vec<Result<Vec<Advisory>>>> {
+        let body = serde_json::json!({
+            "queries": chunk
+                .iter()
+                .map(|q| query_body(&q.name, exact_version(&q.version), &q.ecosystem))
+                .collect::<Vec<_>>()
+        });
+
+        let url = self.endpoint("querybatch");
+        let response = self
+            .http
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("failed to query OSV batch of {} packages", chunk.len()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            bail!("OSV API returned HTTP {status} for querybatch");
+        }
+
+        let batch: OsvBatchResponse = response
+            .json()
+            .await
+            .context("failed to parse OSV querybatch response")?;
+        if batch.results.len() != chunk.len() {
+            bail!(
+                "OSV querybatch returned {} results for {} queries",
+                batch.results.len(),
+                chunk.len()
+            );
+        }
+
+        // The batch only lists IDs; fetch each distinct vulnerability once.
+        let ids: BTreeSet<String> = batch
+            .results
+            .iter()
+            .filter(|r| r.next_page_token.is_none())
+            .flat_map(|r| r.vulns.iter().map(|v| v.id.clone()))
+            .collect();
+        let vulns: HashMap<String, Result<Advisory>> = futures::stream::iter(ids)
+            .map(|id| async move {
+                let vuln = self.get_vuln(&id).await;
+                (id, vuln)
+            })
+            .buffer_unordered(VULN_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut results = Vec::with_capacity(chunk.len());
+        for (query, result) in chunk.iter().zip(&batch.results) {
+            if result.next_page_token.is_some() {
+                // Paginated: the single-package query returns the full list.
+                let version = exact_version(&query.version);
+                results.push(self.query(&query.name, version, &query.ecosystem).await);
+                continue;
+            }
+            results.push(
+                result
+                    .vulns
+                    .iter()
+                    .map(|v| match &vulns[&v.id] {
+                        Ok(advisory) => Ok(advisory.clone()),
+                        Err(e) => Err(anyhow!("{e:#}")),
+                    })
+                    .collect(),
+            );
+        }
+        Ok(results)
+    }
+
+    async fn get_vuln(&self, id: &str) -> Result<Advisory> {
+        let vuln = self
+            .fetch_vuln(id)
+            .await?
+            .with_context(|| format!("OSV API returned HTTP 404 for {id}"))?;
+        Ok(to_advisory(vuln))
+    }
+
+    /// One vulnerability by OSV ID or alias, with every package it
+    /// affects. `None` if OSV has no such record.
+    #[instrument(skip(self))]
+    pub async fn lookup(&self, id: &str) -> Result<Option<Explanation>> {
+        Ok(self.fetch_vuln(id).await?.map(to_explanation))
+    }
+
+    async fn fetch_vuln(&self, id: &str) -> Result<Option<OsvVuln>> {
+        let url = self.endpoint(&format!("vulns/{id}"));
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch OSV vulnerability {id}"))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            bail!("OSV API returned HTTP {status} for {id}");
+        }
+
+        let vuln: OsvVuln = response
+            .json()
+            .await
+            .with_context(|| format!("failed to parse OSV vulnerability {id}"))?;
+        Ok(Some(vuln))
+    }
+
+    /// `querybatch` and `vulns/{id}` sit next to the configured `query` URL.
+    fn endpoint(&self, name: &str) -> String {
+        let root = self
+            .base_url
+            .strip_suffix("query")
+            .unwrap_or(&self.base_url);
+        format!("{root}{name}")
+    }
+}
+
+/// A `query` body, or one entry of a `querybatch` body.
+fn query_body(package: &str, version: Option<&str>, ecosystem: &str) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "package": {
+            "name": package,
+            "ecosystem": ecosystem
+        }
+    });
+    if let Some(version) = version {
+        body["version"] = version.into();
+    }
+    body
+}
+
+/// The exact version in a manifest version spec or action tag, e.g.
+/// `4.17.21`, `==2.31.0` or `v1.2.3`. Ranges and floating tags like `4` are
+/// `None`: OSV would filter by a version the package may not be at.
+pub(crate) fn exact_version(spec: &str) -> Option<&str> {
+    let spec = spec.trim();
+    let version = spec
+        .strip_prefix("==")
+        .or_else(|| spec.strip_prefix('='))
+        .unwrap_or(spec)
+        .trim_start_matches('v');
+    let parts: Vec<&str> = version.split('.').collect();
+    let exact = parts.len() >= 3
+        && parts[..3]
+            .iter()
+            .all(|p| p.starts_with(|c: char| c.is_ascii_digit()))
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+    exact.then_some(version)
 }
 
 // ---------------------------------------------------------------------------
@@ -137,11 +367,16 @@ impl OsvActionProvider {
 impl ActionAdvisoryProvider for OsvActionProvider {
     #[instrument(skip(self), fields(action = %action))]
     async fn query(&self, action: &ActionRef) -> Result<Vec<Advisory>> {
+        let version = action.version().and_then(exact_version);
         self.client
-            .query(&action.package_name(), "GitHub Actions")
+            .query(&action.package_name(), version, "GitHub Actions")
             .await
     }
 
+    async fn lookup(&self, id: &str) -> Result<Option<Explanation>> {
+        self.client.lookup(id).await
+    }
+
     fn name(&self) -> &'static str {
         "OSV"
     }
@@ -161,7 +396,22 @@ impl OsvPackageProvider {
 impl PackageAdvisoryProvider for OsvPackageProvider {
     #[instrument(skip(self))]
     async fn query(&self, package: &str, ecosystem: &str) -> Result<Vec<Advisory>> {
-        self.client.query(package, ecosystem).await
+        self.client.query(package, None, ecosystem).await
+    }
+
+    async fn query_version(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: &str,
+    ) -> Result<Vec<Advisory>> {
+        self.client
+            .query(package, exact_version(version), ecosystem)
+            .await
+    }
+
+    async fn query_batch(&self, queries: &[PackageQuery]) -> Vec<Result<Vec<Advisory>>> {
+        self.client.query_batch(queries).await
     }
 
     fn name(&self) -> &'static str {
@@ -177,47 +427,84 @@ pub fn parse_osv_response(json: serde_json::Value) -> Result<Vec<Advisory>> {
     let response: OsvResponse =
         serde_json::from_value(json).context("failed to deserialize OSV response")?;
 
-    let advisories = response
-        .vulns
-        .into_iter()
-        .map(|vuln| {
-            let severity = vuln
-                .database_specific
-                .as_ref()
-                .and_then(|db| db.severity.as_ref())
-                .map_or_else(|| "unknown".to_string(), |s| s.to_lowercase());
-
-            let url = vuln
-                .references
+    Ok(response.vulns.into_iter().map(to_advisory).collect())
+}
+
+fn to_advisory(vuln: OsvVuln) -> Advisory {
+    let url = vuln
+        .references
+        .iter()
+        .find(|r| r.ref_type.as_deref() == Some("ADVISORY"))
+        .or_else(|| {
+            vuln.references
                 .iter()
-                .find(|r| r.ref_type.as_deref() == Some("ADVISORY"))
-                .or_else(|| {
-                    vuln.references
-                        .iter()
-                        .find(|r| r.ref_type.as_deref() == Some("WEB"))
-                })
-                .and_then(|r| r.url.clone())
-                .unwrap_or_default();
-
-            let affected_range = vuln
-                .affected
-                .first()
-                .and_then(|a| a.ranges.first())
-                .map(|r| format_range_events(&r.events));
-
-            Advisory {
-                id: vuln.id,
-                aliases: vuln.aliases,
-                summary: vuln.summary,
-                severity,
-                url,
-                affected_range,
-                source: "OSV".to_string(),
-            }
+                .find(|r| r.ref_type.as_deref() == Some("WEB"))
         })
-        .collect();
+        .and_then(|r| r.url.clone())
+        .unwrap_or_default();
+
+    // v3 first: its base score can be computed from the vector.
+    let (cvss_score, cvss_vector) = cvss::first_reported(
+        ["CVSS_V3", "CVSS_V4"]
+            .into_iter()
+            .filter_map(|kind| vuln.severity.iter().find(|s| s.kind == kind))
+            .map(|s| (None, Some(s.score.clone()))),
+    );
+
+    let severity = normalize_severity(
+        vuln.database_specific
+            .as_ref()
+            .and_then(|db| db.severity.as_deref()),
+        cvss_score,
+    );
 
-    Ok(advisories)
+    let first_range = vuln.affected.first().and_then(|a| a.ranges.first());
+    let affected_range = first_range.map(|r| format_range_events(&r.events));
+    let fixed_version =
+        first_range.and_then(|r| r.events.iter().rev().find_map(|e| e.fixed.clone()));
+
+    Advisory {
+        id: vuln.id,
+        aliases: vuln.aliases,
+        cwes: vec![],
+        cvss_score,
+        cvss_vector,
+        epss: None,
+        published: vuln.published,
+        modified: vuln.modified,
+        withdrawn: vuln.withdrawn,
+        applicable: None,
+        fixed_version,
+        description: vuln.details.filter(|d| !d.trim().is_empty()),
+        references: vuln.references.into_iter().filter_map(|r| r.url).collect(),
+        vex: None,
+        summary: vuln.summary,
+        severity,
+        url,
+        affected_range,
+        sources: vec!["OSV".to_string()],
+    }
+}
+
+fn to_explanation(vuln: OsvVuln) -> Explanation {
+    let affected = vuln
+        .affected
+        .iter()
+        .filter_map(|a| {
+            let package = a.package.as_ref()?;
+            let events = a.ranges.first().map(|r| r.events.as_slice());
+            Some(AffectedPackage {
+                ecosystem: package.ecosystem.clone(),
+                name: package.name.clone(),
+                range: events.map(format_range_events),
+                fixed: events.and_then(|events| events.iter().rev().find_map(|e| e.fixed.clone())),
+            })
+        })
+        .collect();
+    Explanation {
+        advisory: to_advisory(vuln),
+        affected,
+    }
 }
 
 fn format_range_events(events: &[OsvEvent]) -> String {
@@ -243,6 +530,7 @@ fn format_range_events(events: &[OsvEvent]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::advisory::Severity;
     use serde_json::json;
 
     #[test]
@@ -265,6 +553,7 @@ mod tests {
             "vulns": [{
                 "id": "GHSA-mcph-m25j-8j63",
                 "summary": "tj-actions/changed-files workflow compromise",
+                "details": "The action was modified to print CI secrets to build logs.",
                 "references": [
                     {"type": "ADVISORY", "url": "https://github.com/advisories/GHSA-mcph-m25j-8j63"},
                     {"type": "WEB", "url": "https://example.com/other"}
@@ -290,10 +579,82 @@ mod tests {
         let a = &advisories[0];
         assert_eq!(a.id, "GHSA-mcph-m25j-8j63");
         assert_eq!(a.summary, "tj-actions/changed-files workflow compromise");
-        assert_eq!(a.severity, "critical");
+        assert_eq!(a.severity, Some(Severity::Critical));
         assert_eq!(a.url, "https://github.com/advisories/GHSA-mcph-m25j-8j63");
         assert_eq!(a.affected_range, Some("< 46.0.1".to_string()));
-        assert_eq!(a.source, "OSV");
+        assert_eq!(a.fixed_version.as_deref(), Some("46.0.1"));
+        assert_eq!(a.sources, ["OSV"]);
+        assert_eq!(
+            a.description.as_deref(),
+            Some("The action was modified to print CI secrets to build logs.")
+        );
+        assert_eq!(
+            a.references,
+            [
+                "https://github.com/advisories/GHSA-mcph-m25j-8j63",
+                "https://example.com/other"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_dates_and_withdrawn_timestamp() {
+        let json = json!({
+            "vulns": [
+                {"id": "GHSA-live", "published": "2024-01-02T00:00:00Z", "modified": "2024-02-03T04:05:06Z"},
+                {"id": "GHSA-gone", "withdrawn": "2024-03-05T10:00:00Z"}
+            ]
+        });
+        let advisories = parse_osv_response(json).unwrap();
+        assert_eq!(advisories[0].withdrawn, None);
+        assert_eq!(
+            advisories[0].modified.unwrap().to_rfc3339(),
+            "2024-02-03T04:05:06+00:00"
+        );
+        assert!(advisories[1].published.is_none());
+        assert_eq!(
+            advisories[1].withdrawn.unwrap().to_rfc3339(),
+            "2024-03-05T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn parse_vuln_cvss_from_severity_vectors() {
+        let json = json!({
+            "vulns": [{
+                "id": "GHSA-both",
+                "severity": [
+                    {"type": "CVSS_V4", "score": "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"},
+                    {"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"}
+                ]
+            }, {
+                "id": "GHSA-v4-only",
+                "severity": [
+                    {"type": "CVSS_V4", "score": "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"}
+                ]
+            }]
+        });
+
+        let advisories = parse_osv_response(json).unwrap();
+        assert_eq!(advisories[0].cvss_score.map(|s| s.value()), Some(6.1));
+        // No database_specific severity, so it comes from the CVSS score.
+        assert_eq!(advisories[0].severity, Some(Severity::Medium));
+        assert_eq!(advisories[1].severity, None);
+        assert!(
+            advisories[0]
+                .cvss_vector
+                .as_deref()
+                .unwrap()
+                .starts_with("CVSS:3.1/")
+        );
+        assert!(advisories[1].cvss_score.is_none());
+        assert!(
+            advisories[1]
+                .cvss_vector
+                .as_deref()
+                .unwrap()
+                .starts_with("CVSS:4.0/")
+        );
     }
 
     #[test]
@@ -312,7 +673,7 @@ mod tests {
 
         let advisories = parse_osv_response(json).unwrap();
         assert_eq!(advisories[0].url, "https://example.com/web");
-        assert_eq!(advisories[0].severity, "unknown");
+        assert_eq!(advisories[0].severity, None);
     }
 
     #[test]
@@ -356,9 +717,10 @@ mod tests {
         let advisories = parse_osv_response(json).unwrap();
         assert_eq!(advisories.len(), 1);
         assert_eq!(advisories[0].id, "OSV-MINIMAL");
-        assert_eq!(advisories[0].severity, "unknown");
+        assert_eq!(advisories[0].severity, None);
         assert!(advisories[0].url.is_empty());
         assert!(advisories[0].affected_range.is_none());
+        assert!(advisories[0].description.is_none());
     }
 
     #[test]
@@ -469,4 +831,200 @@ mod tests {
         let advisories = parse_osv_response(json).unwrap();
         assert!(advisories[0].aliases.is_empty());
     }
+
+    #[test]
+    fn exact_versions_only() {
+        assert_eq!(exact_version("4.17.21"), Some("4.17.21"));
+        assert_eq!(exact_version("==2.31.0"), Some("2.31.0"));
+        assert_eq!(exact_version("v1.2.3"), Some("1.2.3"));
+        assert_eq!(exact_version("2.13.4.2"), Some("2.13.4.2"));
+        assert_eq!(exact_version("1.0.0-rc.1"), Some("1.0.0-rc.1"));
+        assert_eq!(exact_version("^4.17.0"), None);
+        assert_eq!(exact_version(">=1.0.0 <2"), None);
+        assert_eq!(exact_version("1.2.x"), None);
+        assert_eq!(exact_version("4"), None);
+        assert_eq!(exact_version("latest"), None);
+    }
+
+    #[tokio::test]
+    async fn action_query_sends_exact_pinned_version() {
+        use wiremock::matchers::{body_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .and(body_json(json!({
+                "package": {"name": "tj-actions/changed-files", "ecosystem": "GitHub Actions"},
+                "version": "46.0.1"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .and(body_json(json!({
+                "package": {"name": "tj-actions/changed-files", "ecosystem": "GitHub Actions"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = OsvActionProvider::new(OsvClient {
+            http: reqwest::Client::new(),
+            base_url: format!("{}/v1/query", server.uri()),
+        });
+        for uses in [
+            "tj-actions/changed-files@v46.0.1",
+            "tj-actions/changed-files@v46",
+        ] {
+            provider.query(&uses.parse().unwrap()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn query_batch_fetches_each_vuln_once() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .and(body_string_contains("minimist"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    {"vulns": [{"id": "GHSA-shared"}, {"id": "GHSA-lodash"}]},
+                    {},
+                    {"vulns": [{"id": "GHSA-shared"}]},
+                    {"vulns": [{"id": "GHSA-first-page"}], "next_page_token": "abc"}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        for id in ["GHSA-shared", "GHSA-lodash"] {
+            Mock::given(method("GET"))
+                .and(path(format!("/v1/vulns/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "id": id,
+                    "summary": format!("{id} summary"),
+                    "database_specific": {"severity": "HIGH"}
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .and(body_string_contains("semver"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "vulns": [{"id": "GHSA-first-page"}, {"id": "GHSA-second-page"}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OsvClient {
+            http: reqwest::Client::new(),
+            base_url: format!("{}/v1/query", server.uri()),
+        };
+        let queries: Vec<PackageQuery> = ["lodash", "left-pad", "minimist", "semver"]
+            .into_iter()
+            .map(|name| PackageQuery {
+                name: name.to_string(),
+                version: "1.0.0".to_string(),
+                ecosystem: "npm".to_string(),
+            })
+            .collect();
+        let results = client.query_batch(&queries).await;
+
+        let ids: Vec<Vec<String>> = results
+            .into_iter()
+            .map(|r| r.unwrap().into_iter().map(|a| a.id).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                vec!["GHSA-shared", "GHSA-lodash"],
+                vec![],
+                vec!["GHSA-shared"],
+                vec!["GHSA-first-page", "GHSA-second-page"],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn query_batch_failure_fails_every_query() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let client = OsvClient {
+            http: reqwest::Client::new(),
+            base_url: format!("{}/v1/query", server.uri()),
+        };
+        let query = PackageQuery {
+            name: "lodash".to_string(),
+            version: "4.17.20".to_string(),
+            ecosystem: "npm".to_string(),
+        };
+        let results = client.query_batch(&[query.clone(), query]).await;
+        assert_eq!(results.len(), 2);
+        for result in results {
+            assert!(result.unwrap_err().to_string().contains("HTTP 500"));
+        }
+    }
+
+    #[tokio::test]
+    async fn lookup_lists_affected_packages() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/vulns/CVE-2025-30066"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "GHSA-mcph-m25j-8j63",
+                "aliases": ["CVE-2025-30066"],
+                "summary": "tj-actions/changed-files workflow compromise",
+                "affected": [{
+                    "package": {"ecosystem": "GitHub Actions", "name": "tj-actions/changed-files"},
+                    "ranges": [{
+                        "type": "ECOSYSTEM",
+                        "events": [{"introduced": "0"}, {"fixed": "46.0.1"}]
+                    }]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = OsvClient {
+            http: reqwest::Client::new(),
+            base_url: format!("{}/v1/query", server.uri()),
+        };
+        let explanation = client.lookup("CVE-2025-30066").await.unwrap().unwrap();
+        assert_eq!(explanation.advisory.id, "GHSA-mcph-m25j-8j63");
+        assert_eq!(
+            explanation.affected,
+            [AffectedPackage {
+                ecosystem: "GitHub Actions".to_string(),
+                name: "tj-actions/changed-files".to_string(),
+                range: Some("< 46.0.1".to_string()),
+                fixed: Some("46.0.1".to_string()),
+            }]
+        );
+        assert!(client.lookup("GHSA-none").await.unwrap().is_none());
+    }
 }
@@ -0,0 +1,329 @@
+//! OSV advisories from a local copy of the per-ecosystem data dumps at
+//! <https://osv-vulnerabilities.storage.googleapis.com>, for CI runners
+//! without network access to api.osv.dev.
+//!
+//! `ghss db update` downloads `{ecosystem}/all.zip` into the database
+//! directory; providers load an ecosystem's zip into memory on first use.
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tracing::{debug, instrument};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::Advisory;
+
+use super::osv::parse_osv_response;
+use super::{ActionAdvisoryProvider, PackageAdvisoryProvider};
+
+const OSV_DUMP_URL: &str = "https://osv-vulnerabilities.storage.googleapis.com";
+
+/// Ecosystems `ghss db update` downloads by default: GitHub Actions plus
+/// every OSV ecosystem the dependency scan can query.
+pub const DEFAULT_ECOSYSTEMS: &[&str] = &[
+    "GitHub Actions",
+    "npm",
+    "Go",
+    "PyPI",
+    "crates.io",
+    "Maven",
+    "RubyGems",
+    "Packagist",
+];
+
+/// Advisory records for one ecosystem, as raw JSON keyed by lowercased
+/// package name.
+type Index = HashMap<String, Vec<Value>>;
+
+/// A directory of downloaded OSV dumps, laid out as `{ecosystem}/all.zip`.
+#[derive(Clone)]
+pub struct OsvDatabase {
+    dir: PathBuf,
+    loaded: Arc<Mutex<HashMap<String, Arc<Index>>>>,
+}
+
+impl OsvDatabase {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            loaded: Arc::default(),
+        }
+    }
+
+    /// `GHSS_OSV_DB_DIR`, else `$XDG_CACHE_HOME/ghss/osv`, else
+    /// `~/.cache/ghss/osv`.
+    pub fn default_dir() -> PathBuf {
+        if let Ok(dir) = std::env::var("GHSS_OSV_DB_DIR") {
+            return PathBuf::from(dir);
+        }
+        let cache = std::env::var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::var("HOME").map(|home| Path::new(&home).join(".cache")))
+            .unwrap_or_else(|_| PathBuf::from("."));
+        cache.join("ghss").join("osv")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn zip_path(&self, ecosystem: &str) -> PathBuf {
+        self.dir.join(ecosystem).join("all.zip")
+    }
+
+    /// Download the dump for `ecosystem`, replacing any previous copy only
+    /// once the new one is known to be a readable zip. Returns the number
+    /// of advisories in it.
+    #[instrument(skip(self))]
+    pub async fn update(&self, ecosystem: &str) -> Result<usize> {
+        let base = std::env::var("GHSS_OSV_DUMP_URL").unwrap_or_else(|_| OSV_DUMP_URL.to_string());
+        let mut url = reqwest::Url::parse(&base).context("invalid OSV dump URL")?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("invalid OSV dump URL: {base}"))?
+            .pop_if_empty()
+            .extend([ecosystem, "all.zip"]);
+
+        let bytes = reqwest::get(url.clone())
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("failed to download {url}"))?
+            .bytes()
+            .await
+            .with_context(|| format!("failed to download {url}"))?;
+        let count = zip::ZipArchive::new(Cursor::new(&bytes))
+            .with_context(|| format!("{url} is not a zip archive"))?
+            .len();
+
+        let path = self.zip_path(ecosystem);
+        let dir = path.parent().expect("zip path has a parent");
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        let partial = path.with_extension("zip.part");
+        std::fs::write(&partial, &bytes)
+            .with_context(|| format!("failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, &path)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        self.loaded.lock().await.remove(ecosystem);
+        Ok(count)
+    }
+
+    /// Every advisory for `package` in `ecosystem`, like an OSV API query
+    /// without a version.
+    pub async fn query(&self, package: &str, ecosystem: &str) -> Result<Vec<Advisory>> {
+        let index = self.index(ecosystem).await?;
+        let vulns = index
+            .get(&package.to_lowercase())
+            .cloned()
+            .unwrap_or_default();
+        parse_osv_response(serde_json::json!({ "vulns": vulns }))
+    }
+
+    async fn index(&self, ecosystem: &str) -> Result<Arc<Index>> {
+        let mut loaded = self.loaded.lock().await;
+        if let Some(index) = loaded.get(ecosystem) {
+            return Ok(index.clone());
+        }
+        let path = self.zip_path(ecosystem);
+        if !path.exists() {
+            bail!(
+                "no offline OSV data for {ecosystem} in {}; run `ghss db update`",
+                self.dir.display()
+            );
+        }
+        let index = tokio::task::spawn_blocking(move || load_index(&path))
+            .await
+            .context("OSV database loader panicked")??;
+        debug!(ecosystem, packages = index.len(), "loaded offline OSV data");
+        let index = Arc::new(index);
+        loaded.insert(ecosystem.to_string(), index.clone());
+        Ok(index)
+    }
+}
+
+/// Read every `*.json` record in the zip at `path` and index it by the
+/// names of the packages it affects.
+fn load_index(path: &Path) -> Result<Index> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("{} is not a zip archive", path.display()))?;
+    let mut index = Index::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if !entry.name().ends_with(".json") {
+            continue;
+        }
+        let mut json = String::new();
+        entry
+            .read_to_string(&mut json)
+            .with_context(|| format!("failed to read {} in {}", entry.name(), path.display()))?;
+        let vuln: Value = serde_json::from_str(&json).with_context(|| {
+            format!("invalid OSV record {} in {}", entry.name(), path.display())
+        })?;
+        let mut names: Vec<String> = vuln["affected"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|a| a["package"]["name"].as_str())
+            .map(str::to_lowercase)
+            .collect();
+        names.dedup();
+        for name in names {
+            index.entry(name).or_default().push(vuln.clone());
+        }
+    }
+    Ok(index)
+}
+
+/// OSV advisories for actions and dependencies, read from an
+/// [`OsvDatabase`] instead of the network.
+pub struct OsvDbProvider {
+    db: OsvDatabase,
+}
+
+impl OsvDbProvider {
+    pub fn new(db: OsvDatabase) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ActionAdvisoryProvider for OsvDbProvider {
+    #[instrument(skip(self), fields(action = %action))]
+    async fn query(&self, action: &ActionRef) -> Result<Vec<Advisory>> {
+        self.db
+            .query(&action.package_name(), "GitHub Actions")
+            .await
+    }
+
+    fn name(&self) -> &'static str {
+        "OSV (offline)"
+    }
+}
+
+#[async_trait]
+impl PackageAdvisoryProvider for OsvDbProvider {
+    #[instrument(skip(self))]
+    async fn query(&self, package: &str, ecosystem: &str) -> Result<Vec<Advisory>> {
+        self.db.query(package, ecosystem).await
+    }
+
+    fn name(&self) -> &'static str {
+        "OSV (offline)"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::advisory::Severity;
+    use serde_json::json;
+
+    fn dump(records: &[Value]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for record in records {
+            writer
+                .start_file(
+                    format!("{}.json", record["id"].as_str().unwrap()),
+                    zip::write::SimpleFileOptions::default(),
+                )
+                .unwrap();
+            writer.write_all(record.to_string().as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn record(id: &str, package: &str) -> Value {
+        json!({
+            "id": id,
+            "summary": format!("{id} summary"),
+            "affected": [{
+                "package": {"ecosystem": "npm", "name": package},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "2.0.0"}]}]
+            }],
+            "database_specific": {"severity": "HIGH"}
+        })
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ghss-osv-db-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn queries_loaded_dump_by_package() {
+        let dir = temp_dir("query");
+        std::fs::create_dir_all(dir.join("npm")).unwrap();
+        std::fs::write(
+            dir.join("npm/all.zip"),
+            dump(&[
+                record("GHSA-aaaa-0001", "Left-Pad"),
+                record("GHSA-aaaa-0002", "left-pad"),
+                record("GHSA-bbbb-0001", "lodash"),
+            ]),
+        )
+        .unwrap();
+
+        let db = OsvDatabase::new(&dir);
+        let advisories = db.query("left-pad", "npm").await.unwrap();
+        let ids: Vec<&str> = advisories.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["GHSA-aaaa-0001", "GHSA-aaaa-0002"]);
+        assert_eq!(advisories[0].severity, Some(Severity::High));
+        assert_eq!(advisories[0].affected_range.as_deref(), Some("< 2.0.0"));
+        assert!(db.query("react", "npm").await.unwrap().is_empty());
+
+        let err = db.query("requests", "PyPI").await.unwrap_err();
+        assert!(err.to_string().contains("run `ghss db update`"), "{err}");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn update_downloads_and_replaces_dump() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/GitHub%20Actions/all.zip"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(dump(&[record(
+                "GHSA-cccc-0001",
+                "tj-actions/changed-files",
+            )])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/npm/all.zip"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not a zip"))
+            .mount(&server)
+            .await;
+
+        let dir = temp_dir("update");
+        let db = OsvDatabase::new(&dir);
+        // SAFETY: test-only; env var mutation is unsafe in Rust 2024
+        unsafe { std::env::set_var("GHSS_OSV_DUMP_URL", server.uri()) };
+        let updated = db.update("GitHub Actions").await;
+        let rejected = db.update("npm").await;
+        unsafe { std::env::remove_var("GHSS_OSV_DUMP_URL") };
+
+        assert_eq!(updated.unwrap(), 1);
+        assert!(rejected.unwrap_err().to_string().contains("not a zip"));
+        assert!(!dir.join("npm/all.zip").exists());
+
+        let action: ActionRef = "tj-actions/changed-files@v45".parse().unwrap();
+        let advisories = ActionAdvisoryProvider::query(&OsvDbProvider::new(db), &action)
+            .await
+            .unwrap();
+        assert_eq!(advisories[0].id, "GHSA-cccc-0001");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
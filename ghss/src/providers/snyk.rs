@@ -0,0 +1,417 @@
+//! Snyk's curated vulnerability database, for organizations that license
+//! Snyk. Queries the REST API's package issues endpoint by Package URL, so
+//! only dependencies with a concrete version can be looked up.
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use crate::advisory::{Advisory, fixed_in_range, normalize_severity};
+use crate::cvss;
+
+use super::PackageAdvisoryProvider;
+use super::depsdev::concrete_version;
+use super::http::expand_env;
+
+const SNYK_API_URL: &str = "https://api.snyk.io/rest";
+
+/// REST API version sent with every request.
+const SNYK_API_VERSION: &str = "2024-10-15";
+
+/// Issues requested per package. The endpoint's maximum, and more than any
+/// single package version has.
+const PAGE_LIMIT: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnykConfig {
+    /// Organization the API calls are made under.
+    pub org_id: String,
+    /// API token. Defaults to `${SNYK_TOKEN}`.
+    #[serde(default = "default_token")]
+    pub token: String,
+}
+
+fn default_token() -> String {
+    "${SNYK_TOKEN}".to_string()
+}
+
+impl SnykConfig {
+    /// Token and organization from `SNYK_TOKEN` and `SNYK_ORG_ID`.
+    pub fn from_env() -> Self {
+        Self {
+            org_id: "${SNYK_ORG_ID}".to_string(),
+            token: default_token(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IssuesResponse {
+    #[serde(default)]
+    data: Vec<Issue>,
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    id: String,
+    attributes: IssueAttributes,
+}
+
+#[derive(Deserialize)]
+struct IssueAttributes {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    key: Option<String>,
+    #[serde(default)]
+    title: String,
+    description: Option<String>,
+    updated_at: Option<DateTime<Utc>>,
+    effective_severity_level: Option<String>,
+    #[serde(default)]
+    problems: Vec<Problem>,
+    #[serde(default)]
+    coordinates: Vec<Coordinate>,
+    #[serde(default)]
+    severities: Vec<SnykSeverity>,
+    slots: Option<Slots>,
+}
+
+#[derive(Deserialize)]
+struct Problem {
+    id: String,
+    source: String,
+}
+
+#[derive(Deserialize)]
+struct Coordinate {
+    #[serde(default)]
+    representations: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SnykSeverity {
+    score: Option<f64>,
+    vector: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Slots {
+    publication_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    references: Vec<SlotReference>,
+}
+
+#[derive(Deserialize)]
+struct SlotReference {
+    url: String,
+}
+
+pub struct SnykProvider {
+    http: reqwest::Client,
+    base_url: String,
+    org_id: String,
+    token: String,
+}
+
+impl SnykProvider {
+    /// Resolve `${VAR}` references in `config`. Base URL overridable via
+    /// `GHSS_SNYK_BASE_URL`.
+    pub fn new(config: SnykConfig) -> Result<Self> {
+        let org_id = expand_env(&config.org_id).context("snyk: org_id")?;
+        let token = expand_env(&config.token).context("snyk: token")?;
+        if org_id.is_empty() || token.is_empty() {
+            bail!("snyk: org_id and token must not be empty");
+        }
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url: std::env::var("GHSS_SNYK_BASE_URL")
+                .unwrap_or_else(|_| SNYK_API_URL.to_string()),
+            org_id,
+            token,
+        })
+    }
+
+    async fn issues(&self, purl: &str) -> Result<Vec<Advisory>> {
+        let mut url = reqwest::Url::parse(&self.base_url)
+            .with_context(|| format!("invalid Snyk base URL: {}", self.base_url))?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("Snyk base URL cannot have a path"))?
+            .pop_if_empty()
+            .extend(["orgs", &self.org_id, "packages", purl, "issues"]);
+        url.query_pairs_mut()
+            .append_pair("version", SNYK_API_VERSION)
+            .append_pair("limit", &PAGE_LIMIT.to_string());
+
+        let response = self
+            .http
+            .get(url)
+            .header("Authorization", format!("token {}", self.token))
+            .header("Accept", "application/vnd.api+json")
+            .send()
+            .await
+            .with_context(|| format!("failed to query Snyk for {purl}"))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        // Kept as a reqwest error so `providers check` can tell a bad token.
+        let body: IssuesResponse = response
+            .error_for_status()
+            .with_context(|| format!("Snyk API returned non-success status for {purl}"))?
+            .json()
+            .await
+            .context("failed to parse Snyk response")?;
+        Ok(body
+            .data
+            .into_iter()
+            // License issues share the endpoint.
+            .filter(|i| i.attributes.kind.as_deref() == Some("package_vulnerability"))
+            .map(to_advisory)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PackageAdvisoryProvider for SnykProvider {
+    async fn query(&self, package: &str, _ecosystem: &str) -> Result<Vec<Advisory>> {
+        bail!("Snyk needs a version to look up {package}")
+    }
+
+    #[instrument(skip(self))]
+    async fn query_version(
+        &self,
+        package: &str,
+        version: &str,
+        ecosystem: &str,
+    ) -> Result<Vec<Advisory>> {
+        let Some(kind) = purl_type(ecosystem) else {
+            return Ok(vec![]);
+        };
+        let Some(version) = concrete_version(version) else {
+            debug!("no concrete version to look up in Snyk");
+            return Ok(vec![]);
+        };
+        // Maven coordinates are `group:artifact`; purls use `group/artifact`.
+        let name = package.replace(':', "/");
+        self.issues(&format!("pkg:{kind}/{name}@{version}")).await
+    }
+
+    fn name(&self) -> &'static str {
+        "Snyk"
+    }
+}
+
+/// Package URL type for an OSV ecosystem string, if Snyk covers it.
+fn purl_type(ecosystem: &str) -> Option<&'static str> {
+    match ecosystem {
+        "npm" => Some("npm"),
+        "PyPI" => Some("pypi"),
+        "Maven" => Some("maven"),
+        "RubyGems" => Some("gem"),
+        "NuGet" => Some("nuget"),
+        "Packagist" => Some("composer"),
+        "crates.io" => Some("cargo"),
+        "Go" => Some("golang"),
+        _ => None,
+    }
+}
+
+fn to_advisory(issue: Issue) -> Advisory {
+    let attrs = issue.attributes;
+    let id = attrs.key.unwrap_or(issue.id);
+    let problems = |source: &str| -> Vec<String> {
+        attrs
+            .problems
+            .iter()
+            .filter(|p| p.source == source)
+            .map(|p| p.id.clone())
+            .collect()
+    };
+    let mut aliases = problems("CVE");
+    aliases.extend(problems("GHSA"));
+    let cwes = problems("CWE");
+
+    let (cvss_score, cvss_vector) =
+        cvss::first_reported(attrs.severities.into_iter().map(|s| (s.score, s.vector)));
+    let affected_range = attrs
+        .coordinates
+        .iter()
+        .flat_map(|c| &c.representations)
+        .next()
+        .map(|r| interval_range(r));
+    let (published, references) = attrs.slots.map_or((None, vec![]), |slots| {
+        let references = slots.references.into_iter().map(|r| r.url).collect();
+        (slots.publication_time, references)
+    });
+
+    Advisory {
+        url: format!("https://security.snyk.io/vuln/{id}"),
+        id,
+        aliases,
+        cwes,
+        cvss_score,
+        cvss_vector,
+        epss: None,
+        published,
+        modified: attrs.updated_at,
+        withdrawn: None,
+        applicable: None,
+        fixed_version: affected_range.as_deref().and_then(fixed_in_range),
+        description: attrs.description.filter(|d| !d.trim().is_empty()),
+        references,
+        vex: None,
+        summary: attrs.title,
+        severity: normalize_severity(attrs.effective_severity_level.as_deref(), cvss_score),
+        affected_range,
+        sources: vec!["Snyk".to_string()],
+    }
+}
+
+/// A Snyk version interval in the range syntax the other providers use:
+/// `[,4.17.21)` → `< 4.17.21`, `[1.0.0,1.2.0)` → `>= 1.0.0, < 1.2.0`.
+/// Anything else, such as npm-style `<4.17.21`, is kept as is.
+fn interval_range(interval: &str) -> String {
+    let interval = interval.trim();
+    let (Some(open), Some(close)) = (interval.chars().next(), interval.chars().last()) else {
+        return interval.to_string();
+    };
+    if !matches!(open, '[' | '(') || !matches!(close, ']' | ')') || interval.len() < 2 {
+        return interval.to_string();
+    }
+    let inner = &interval[1..interval.len() - 1];
+    let Some((low, high)) = inner.split_once(',') else {
+        return format!("= {}", inner.trim());
+    };
+    let mut parts = Vec::new();
+    if !low.trim().is_empty() {
+        let op = if open == '[' { ">=" } else { ">" };
+        parts.push(format!("{op} {}", low.trim()));
+    }
+    if !high.trim().is_empty() {
+        let op = if close == ']' { "<=" } else { "<" };
+        parts.push(format!("{op} {}", high.trim()));
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisory::Severity;
+
+    #[test]
+    fn converts_intervals() {
+        assert_eq!(interval_range("[,4.17.21)"), "< 4.17.21");
+        assert_eq!(interval_range("[1.0.0,1.2.0)"), ">= 1.0.0, < 1.2.0");
+        assert_eq!(interval_range("(1.0,2.0]"), "> 1.0, <= 2.0");
+        assert_eq!(interval_range("[1.2.3]"), "= 1.2.3");
+        assert_eq!(interval_range("<4.17.21"), "<4.17.21");
+    }
+
+    #[test]
+    fn config_requires_set_variables() {
+        let err = SnykProvider::new(SnykConfig {
+            org_id: "org".to_string(),
+            token: "${GHSS_TEST_UNSET_SNYK_TOKEN}".to_string(),
+        })
+        .err()
+        .unwrap();
+        assert!(format!("{err:#}").contains("GHSS_TEST_UNSET_SNYK_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn queries_package_version_by_purl() {
+        use wiremock::matchers::{header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orgs/my-org/packages/pkg:npm%2Flodash@4.17.20/issues"))
+            .and(query_param("version", SNYK_API_VERSION))
+            .and(header("authorization", "token secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {
+                        "id": "SNYK-JS-LODASH-1040724",
+                        "type": "issue",
+                        "attributes": {
+                            "key": "SNYK-JS-LODASH-1040724",
+                            "type": "package_vulnerability",
+                            "title": "Command Injection",
+                            "description": "## Overview\n\nlodash is vulnerable.",
+                            "updated_at": "2024-03-11T09:53:00Z",
+                            "effective_severity_level": "high",
+                            "problems": [
+                                {"id": "CWE-78", "source": "CWE"},
+                                {"id": "CVE-2021-23337", "source": "CVE"}
+                            ],
+                            "coordinates": [{"representations": ["[,4.17.21)"]}],
+                            "severities": [{
+                                "source": "Snyk",
+                                "level": "high",
+                                "score": 7.2,
+                                "vector": "CVSS:3.1/AV:N/AC:L/PR:H/UI:N/S:U/C:H/I:H/A:H"
+                            }],
+                            "slots": {
+                                "publication_time": "2021-02-15T11:50:49Z",
+                                "references": [{"title": "Fix", "url": "https://github.com/lodash/lodash/commit/3469357"}]
+                            }
+                        }
+                    },
+                    {
+                        "id": "snyk:lic:npm:lodash:MIT",
+                        "type": "issue",
+                        "attributes": {"type": "license", "title": "MIT license"}
+                    }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = SnykProvider {
+            base_url: server.uri(),
+            ..SnykProvider::new(SnykConfig {
+                org_id: "my-org".to_string(),
+                token: "secret".to_string(),
+            })
+            .unwrap()
+        };
+        let advisories = provider
+            .query_version("lodash", "^4.17.20", "npm")
+            .await
+            .unwrap();
+        assert_eq!(advisories.len(), 1);
+        let a = &advisories[0];
+        assert_eq!(a.id, "SNYK-JS-LODASH-1040724");
+        assert_eq!(a.aliases, ["CVE-2021-23337"]);
+        assert_eq!(a.cwes, ["CWE-78"]);
+        assert_eq!(a.severity, Some(Severity::High));
+        assert_eq!(a.cvss_score.map(|s| s.value()), Some(7.2));
+        assert_eq!(a.affected_range.as_deref(), Some("< 4.17.21"));
+        assert_eq!(
+            a.url,
+            "https://security.snyk.io/vuln/SNYK-JS-LODASH-1040724"
+        );
+        assert_eq!(a.sources, ["Snyk"]);
+        assert!(a.published < a.modified);
+
+        assert!(
+            provider
+                .query_version("lodash", "latest", "npm")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            provider
+                .query_version("left-pad", "1.0.0", "Hex")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
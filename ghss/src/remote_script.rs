@@ -0,0 +1,294 @@
+//! Code that `run:` steps download and execute.
+//!
+//! `uses:` refs can be pinned and audited; a script fetched with `curl` and
+//! piped into a shell, or a package installed from a moving URL or version,
+//! is whatever the server returns on the day the job runs. The checks here
+//! are line-based matches over the shell script, not a shell parser.
+
+use crate::finding::{Finding, REMOTE_SCRIPT};
+use crate::location::{SourceLocation, TextIndex};
+use crate::workflow::Workflow;
+
+/// Commands that fetch a URL.
+const DOWNLOADERS: &[&str] = &["curl", "wget"];
+
+/// Interpreters that run a script read from stdin or a file argument.
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "python", "python3", "node", "perl", "ruby", "pwsh",
+];
+
+/// Commands that install npm packages globally, as word sequences.
+const NPM_GLOBAL: &[&[&str]] = &[
+    &["npm", "install", "-g"],
+    &["npm", "i", "-g"],
+    &["npm", "install", "--global"],
+    &["npm", "i", "--global"],
+    &["yarn", "global", "add"],
+    &["pnpm", "add", "-g"],
+];
+
+/// A line of a `run:` script that executes remote code.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RemoteExecution {
+    /// The script line, as written.
+    pub line: String,
+    pub message: String,
+}
+
+/// The command name of `word`, without a directory, e.g. `bash` for `/bin/bash`.
+fn command(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+/// The first word of a pipeline stage, past `sudo` and its flags.
+fn stage_command(stage: &str) -> Option<&str> {
+    stage
+        .split_whitespace()
+        .find(|w| *w != "sudo" && !w.starts_with('-'))
+        .map(command)
+}
+
+/// `curl … | sh` and `sh <(curl …)` / `sh -c "$(curl …)"`.
+fn piped_download(line: &str) -> Option<String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let downloader = words
+        .iter()
+        .map(|w| {
+            command(
+                w.trim_start_matches("<(")
+                    .trim_start_matches("\"$(")
+                    .trim_start_matches("$("),
+            )
+        })
+        .find(|w| DOWNLOADERS.contains(w))?;
+    let mut stages = line.split('|').skip(1);
+    if let Some(shell) =
+        stages.find_map(|stage| stage_command(stage).filter(|c| INTERPRETERS.contains(c)))
+    {
+        return Some(format!("pipes `{downloader}` output into `{shell}`"));
+    }
+    let shell = words
+        .iter()
+        .map(|w| command(w))
+        .find(|w| INTERPRETERS.contains(w))?;
+    let substituted = ["<(", "$("]
+        .iter()
+        .any(|open| line.contains(&format!("{open}{downloader}")));
+    substituted.then(|| format!("runs a script downloaded with `{downloader}` in `{shell}`"))
+}
+
+/// Whether a pip requirement is a URL that can change under the same name.
+fn unpinned_pip_url(requirement: &str) -> bool {
+    let Some(url) = requirement
+        .strip_prefix("git+")
+        .or_else(|| requirement.contains("://").then_some(requirement))
+    else {
+        return false;
+    };
+    if requirement.starts_with("git+") {
+        // A commit SHA after `@` pins it; a branch or tag does not.
+        let rev = url
+            .rsplit_once('@')
+            .map(|(_, rev)| rev.split('#').next().unwrap_or(rev));
+        return !rev.is_some_and(|r| r.len() == 40 && r.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+    // Archives and wheels from a URL are only as fixed as the server keeps them.
+    !requirement.contains("#sha256=")
+}
+
+/// `pip install` of a git or HTTP URL without a commit or hash.
+fn pip_url(line: &str) -> Vec<String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some(start) = words
+        .windows(2)
+        .position(|w| matches!(command(w[0]), "pip" | "pip3") && w[1] == "install")
+    else {
+        return Vec::new();
+    };
+    words[start + 2..]
+        .iter()
+        .take_while(|w| !matches!(**w, "&&" | "||" | ";" | "|"))
+        .map(|w| w.trim_matches(|c| c == '"' || c == '\''))
+        .filter(|w| !w.starts_with('-') && !w.contains('$') && unpinned_pip_url(w))
+        .map(|w| format!("installs `{w}` with pip without pinning a commit or hash"))
+        .collect()
+}
+
+/// Whether an npm package spec names an exact version.
+fn npm_pinned(spec: &str) -> bool {
+    let name_end = if spec.starts_with('@') { 1 } else { 0 };
+    let Some((_, version)) = spec[name_end..].split_once('@') else {
+        return false;
+    };
+    version.starts_with(|c: char| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '+')
+        && !version.contains('x')
+}
+
+/// Global npm installs of packages without an exact version.
+fn npm_global(line: &str) -> Vec<String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let mut found = Vec::new();
+    for prefix in NPM_GLOBAL {
+        let Some(start) = words.windows(prefix.len()).position(|w| w == *prefix) else {
+            continue;
+        };
+        found.extend(
+            words[start + prefix.len()..]
+                .iter()
+                .take_while(|w| !matches!(**w, "&&" | "||" | ";" | "|"))
+                .map(|w| w.trim_matches(|c| c == '"' || c == '\''))
+                .filter(|w| !w.starts_with('-') && !w.contains('$') && !npm_pinned(w))
+                .map(|w| {
+                    format!(
+                        "installs `{w}` globally with {} without pinning a version",
+                        prefix[0]
+                    )
+                }),
+        );
+    }
+    found
+}
+
+/// Lines of `script` that download and run code nobody can audit ahead of
+/// time. Lines continued with a trailing `\` are read as one line.
+pub fn audit_run(script: &str) -> Vec<RemoteExecution> {
+    let mut findings = Vec::new();
+    let mut lines = script.lines();
+    while let Some(first) = lines.next() {
+        let mut joined = first.trim_end().to_string();
+        while joined.ends_with('\\') {
+            joined.pop();
+            match lines.next() {
+                Some(next) => joined.push_str(&format!(" {}", next.trim())),
+                None => break,
+            }
+        }
+        let trimmed = joined.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        let mut messages: Vec<String> = piped_download(trimmed).into_iter().collect();
+        messages.extend(pip_url(trimmed));
+        messages.extend(npm_global(trimmed));
+        findings.extend(messages.into_iter().map(|message| RemoteExecution {
+            line: first.trim().to_string(),
+            message,
+        }));
+    }
+    findings
+}
+
+/// Raise [`REMOTE_SCRIPT`] for each line of a `run:` step that executes
+/// remote code, located at that line.
+pub fn check_remote_scripts(yaml: &str, file: &str) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    let mut index = TextIndex::new(yaml);
+    let mut findings = Vec::new();
+    for (job, definition) in workflow.jobs() {
+        for (i, step) in definition.steps.unwrap_or_default().into_iter().enumerate() {
+            let Some(run) = &step.run else {
+                continue;
+            };
+            let step_name = step.name.unwrap_or_else(|| format!("#{}", i + 1));
+            // A line with several findings is located once.
+            let mut located: Option<(String, Option<SourceLocation>)> = None;
+            for execution in audit_run(run) {
+                if located
+                    .as_ref()
+                    .is_none_or(|(line, _)| *line != execution.line)
+                {
+                    let location = index.locate(&execution.line).map(|l| l.with_file(file));
+                    located = Some((execution.line.clone(), location));
+                }
+                let location = located.as_ref().and_then(|(_, l)| l.clone());
+                findings.push(
+                    Finding::new(
+                        &REMOTE_SCRIPT,
+                        format!("step `{step_name}` in job `{job}` {}", execution.message),
+                    )
+                    .with_location(location),
+                );
+            }
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(script: &str) -> Vec<String> {
+        audit_run(script).into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn flags_downloads_piped_into_interpreters() {
+        assert_eq!(
+            messages("curl -fsSL https://get.example.com | sudo -E bash -s -- --yes"),
+            ["pipes `curl` output into `bash`"]
+        );
+        assert_eq!(
+            messages("wget -qO- https://example.com/install.sh | sh"),
+            ["pipes `wget` output into `sh`"]
+        );
+        assert_eq!(
+            messages("bash <(curl -s https://example.com/x.sh)"),
+            ["runs a script downloaded with `curl` in `bash`"]
+        );
+        assert_eq!(
+            messages("sh -c \"$(curl -fsSL https://example.com/x.sh)\""),
+            ["runs a script downloaded with `curl` in `sh`"]
+        );
+        assert_eq!(
+            messages("curl -sL https://example.com/x.sh \\\n  | python3 -"),
+            ["pipes `curl` output into `python3`"]
+        );
+
+        assert!(messages("curl -fsSL https://example.com/data.json | jq .version").is_empty());
+        assert!(messages("curl -o install.sh https://example.com/x.sh").is_empty());
+        assert!(messages("# curl https://example.com | sh").is_empty());
+    }
+
+    #[test]
+    fn flags_unpinned_remote_packages() {
+        assert_eq!(
+            messages("pip install --upgrade git+https://github.com/org/tool@main requests"),
+            [
+                "installs `git+https://github.com/org/tool@main` with pip without pinning a commit or hash"
+            ]
+        );
+        assert_eq!(
+            messages("npm install -g @org/cli typescript@5.4.5 eslint@^9"),
+            [
+                "installs `@org/cli` globally with npm without pinning a version",
+                "installs `eslint@^9` globally with npm without pinning a version",
+            ]
+        );
+
+        assert!(messages("pip install git+https://github.com/org/tool@0123456789abcdef0123456789abcdef01234567").is_empty());
+        assert!(messages("pip install -r requirements.txt").is_empty());
+        assert!(messages("npm ci && npm install -g pnpm@9.1.0").is_empty());
+    }
+
+    #[test]
+    fn locates_the_script_line() {
+        let yaml = "on: push\njobs:\n  setup:\n    steps:\n      - name: Install tools\n        run: |\n          echo start\n          curl -sSf https://sh.rustup.rs | sh -s -- -y\n          npm i -g yarn && pip install git+https://github.com/org/tool@main\n";
+        let findings: Vec<String> = check_remote_scripts(yaml, "ci.yml")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                "remote-script (medium): step `Install tools` in job `setup` pipes `curl` output into `sh`\n    at ci.yml:8:11",
+                "remote-script (medium): step `Install tools` in job `setup` installs `git+https://github.com/org/tool@main` with pip without pinning a commit or hash\n    at ci.yml:9:11",
+                "remote-script (medium): step `Install tools` in job `setup` installs `yarn` globally with npm without pinning a version\n    at ci.yml:9:11",
+            ]
+        );
+    }
+}
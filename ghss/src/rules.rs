@@ -0,0 +1,699 @@
+//! User-defined checks over the audited action tree, so organizations can
+//! enforce their own policies without writing Rust.
+//!
+//! Rules are YAML. Each one lists conditions on fields of a single `uses:`
+//! of an action; when every condition holds, the use is reported as a
+//! finding with the rule's id, severity and message:
+//!
+//! ```yaml
+//! rules:
+//!   - id: deploy-external-action
+//!     severity: high
+//!     message: "deploy job {job} uses {action}, which is not an internal action"
+//!     when:
+//!       job: "deploy*"
+//!       owner: { not: [acme, acme-*] }
+//! ```
+//!
+//! A condition is a glob (`*` matches any run of characters, compared
+//! case-insensitively) or a list of globs, any of which may match; under
+//! `not:` none may match. Fields with several values, such as `advisory`,
+//! match when any value does. `{field}` in the message is replaced with
+//! the field's value.
+//!
+//! A `forbid:` list bans actions outright. Each use of a listed action is
+//! a critical `forbidden-action` finding:
+//!
+//! ```yaml
+//! forbid:
+//!   - tj-actions/*
+//!   - action: evil-org
+//!     reason: "organization compromised in March 2025"
+//! ```
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Deserializer};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::Severity;
+use crate::finding::{self, Finding};
+use crate::location::Occurrence;
+use crate::output::AuditNode;
+
+/// A field of one use of an action that conditions can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    /// `owner/repo[/path]`, without the ref.
+    Action,
+    Owner,
+    Repo,
+    Path,
+    Ref,
+    /// `sha`, `tag` or `unknown`.
+    RefType,
+    /// The job the use appears in; missing for composite action steps.
+    Job,
+    /// The step's `name:`.
+    Step,
+    /// The file the use appears in.
+    Workflow,
+    /// 0 for actions used directly by the workflow, 1 for what they use, ...
+    Depth,
+    /// `owner/repo[/path]` of the action or workflow that uses this one.
+    Parent,
+    /// IDs and aliases of the action's advisories.
+    Advisory,
+    /// Severities of the action's advisories.
+    Severity,
+    /// `user` or `organization`, for the account owning the action's
+    /// repository. Known only with `--reputation`.
+    OwnerType,
+}
+
+const FIELDS: &[(&str, Field)] = &[
+    ("action", Field::Action),
+    ("owner", Field::Owner),
+    ("repo", Field::Repo),
+    ("path", Field::Path),
+    ("ref", Field::Ref),
+    ("ref_type", Field::RefType),
+    ("job", Field::Job),
+    ("step", Field::Step),
+    ("workflow", Field::Workflow),
+    ("depth", Field::Depth),
+    ("parent", Field::Parent),
+    ("advisory", Field::Advisory),
+    ("severity", Field::Severity),
+    ("owner_type", Field::OwnerType),
+];
+
+/// Globs from a scalar or a list of scalars. Numbers and booleans are
+/// taken as text, so `depth: 0` works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patterns(Vec<String>);
+
+impl<'de> Deserialize<'de> for Patterns {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn text(value: serde_yaml::Value) -> Option<String> {
+            match value {
+                serde_yaml::Value::String(s) => Some(s),
+                serde_yaml::Value::Number(n) => Some(n.to_string()),
+                serde_yaml::Value::Bool(b) => Some(b.to_string()),
+                _ => None,
+            }
+        }
+        let value = serde_yaml::Value::deserialize(deserializer)?;
+        let values = match value {
+            serde_yaml::Value::Sequence(items) => items,
+            scalar => vec![scalar],
+        };
+        values
+            .into_iter()
+            .map(|v| {
+                text(v).ok_or_else(|| {
+                    serde::de::Error::custom("expected a string or a list of strings")
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Patterns)
+    }
+}
+
+impl Patterns {
+    fn any_match(&self, values: &[String]) -> bool {
+        values
+            .iter()
+            .any(|value| self.0.iter().any(|pattern| glob_match(pattern, value)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Negated {
+    pub not: Patterns,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Condition {
+    Not(Negated),
+    Matches(Patterns),
+}
+
+impl Condition {
+    fn holds(&self, values: &[String]) -> bool {
+        match self {
+            Condition::Matches(patterns) => patterns.any_match(values),
+            Condition::Not(negated) => !negated.not.any_match(values),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    /// Reported as the finding's rule id. Must not clash with a built-in
+    /// rule.
+    pub id: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    pub message: String,
+    pub when: BTreeMap<Field, Condition>,
+}
+
+fn default_severity() -> Severity {
+    Severity::Medium
+}
+
+/// An action no workflow may use. A pattern with `/` is matched against
+/// `owner/repo[/path]`, or with `@ref` when it has one; without `/` it
+/// names an owner.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "ForbiddenRepr")]
+pub struct Forbidden {
+    pub pattern: String,
+    pub reason: Option<String>,
+}
+
+/// A bare pattern, or one with a reason.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ForbiddenRepr {
+    Pattern(String),
+    Entry {
+        action: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+}
+
+impl From<ForbiddenRepr> for Forbidden {
+    fn from(repr: ForbiddenRepr) -> Self {
+        match repr {
+            ForbiddenRepr::Pattern(pattern) => Self::from(pattern),
+            ForbiddenRepr::Entry { action, reason } => Self {
+                pattern: action,
+                reason,
+            },
+        }
+    }
+}
+
+impl From<String> for Forbidden {
+    fn from(pattern: String) -> Self {
+        Self {
+            pattern,
+            reason: None,
+        }
+    }
+}
+
+impl From<&str> for Forbidden {
+    fn from(pattern: &str) -> Self {
+        Self::from(pattern.to_string())
+    }
+}
+
+impl Forbidden {
+    pub fn matches(&self, action: &ActionRef) -> bool {
+        if !self.pattern.contains('/') {
+            glob_match(&self.pattern, &action.owner)
+        } else if self.pattern.contains('@') {
+            let full = format!("{}@{}", action.package_name(), action.git_ref);
+            glob_match(&self.pattern, &full)
+        } else {
+            glob_match(&self.pattern, &action.package_name())
+        }
+    }
+
+    fn finding(&self, action: &ActionRef) -> Finding {
+        let mut message = format!(
+            "{}@{} is forbidden by policy (`{}`)",
+            action.package_name(),
+            action.git_ref,
+            self.pattern
+        );
+        if let Some(reason) = &self.reason {
+            message = format!("{message}: {reason}");
+        }
+        Finding::new(&finding::FORBIDDEN_ACTION, message)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<CustomRule>,
+    /// Ids of built-in rules to turn off.
+    #[serde(default)]
+    disable: Vec<String>,
+    #[serde(default)]
+    forbid: Vec<Forbidden>,
+}
+
+/// A set of user-defined rules, the built-in rules to turn off, and the
+/// actions to forbid.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    rules: Vec<CustomRule>,
+    disabled: Vec<String>,
+    forbidden: Vec<Forbidden>,
+}
+
+impl Rules {
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let file: RulesFile = serde_yaml::from_str(yaml).context("invalid rules file")?;
+        let mut seen = Vec::new();
+        for rule in &file.rules {
+            if rule.id.is_empty() {
+                bail!("rule id must not be empty");
+            }
+            if finding::rule(&rule.id).is_some() {
+                bail!("rule {}: id is already used by a built-in rule", rule.id);
+            }
+            if seen.contains(&&rule.id) {
+                bail!("rule {}: defined more than once", rule.id);
+            }
+            seen.push(&rule.id);
+            if rule.when.is_empty() {
+                bail!("rule {}: `when` needs at least one condition", rule.id);
+            }
+            for placeholder in placeholders(&rule.message) {
+                if field_named(placeholder).is_none() {
+                    bail!(
+                        "rule {}: unknown field {{{placeholder}}} in message",
+                        rule.id
+                    );
+                }
+            }
+        }
+        for id in &file.disable {
+            if finding::rule(id).is_none() {
+                bail!("disable: {id} is not a built-in rule");
+            }
+        }
+        if file.forbid.iter().any(|f| f.pattern.trim().is_empty()) {
+            bail!("forbid: pattern must not be empty");
+        }
+        Ok(Self {
+            rules: file.rules,
+            disabled: file.disable,
+            forbidden: file.forbid,
+        })
+    }
+
+    /// Also forbid these actions: bare patterns (CLI `--forbid`) or
+    /// entries with a reason (the config file's policy).
+    pub fn with_forbidden(
+        mut self,
+        entries: impl IntoIterator<Item = impl Into<Forbidden>>,
+    ) -> Self {
+        self.forbidden.extend(entries.into_iter().map(Into::into));
+        self
+    }
+
+    /// The `forbid:` entries, then those added with [`Rules::with_forbidden`].
+    pub fn forbidden(&self) -> &[Forbidden] {
+        &self.forbidden
+    }
+
+    /// Ids of the built-in rules listed under `disable:`.
+    pub fn disabled(&self) -> &[String] {
+        &self.disabled
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Findings for every use in `nodes` that a rule matches or that is
+    /// forbidden. `workflow` is the audited file, used for uses without a
+    /// recorded location.
+    pub fn evaluate(&self, nodes: &[AuditNode], workflow: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if !self.rules.is_empty() || !self.forbidden.is_empty() {
+            self.evaluate_level(nodes, workflow, 0, None, &mut findings);
+        }
+        findings
+    }
+
+    fn evaluate_level(
+        &self,
+        nodes: &[AuditNode],
+        workflow: &str,
+        depth: usize,
+        parent: Option<&ActionRef>,
+        findings: &mut Vec<Finding>,
+    ) {
+        for node in nodes {
+            let action = &node.entry.action;
+            let uses: Vec<Option<&Occurrence>> = if action.occurrences.is_empty() {
+                vec![None]
+            } else {
+                action.occurrences.iter().map(Some).collect()
+            };
+            for occurrence in uses {
+                let subject = Subject {
+                    node,
+                    occurrence,
+                    workflow,
+                    depth,
+                    parent,
+                };
+                let location = occurrence
+                    .and_then(|o| o.location.clone())
+                    .or_else(|| action.location.clone());
+                if let Some(forbidden) = self.forbidden.iter().find(|f| f.matches(action)) {
+                    findings.push(forbidden.finding(action).with_location(location.clone()));
+                }
+                for rule in &self.rules {
+                    if rule
+                        .when
+                        .iter()
+                        .all(|(field, condition)| condition.holds(&subject.values(*field)))
+                    {
+                        findings.push(Finding {
+                            rule_id: rule.id.clone(),
+                            severity: rule.severity,
+                            message: subject.render(&rule.message),
+                            location: location.clone(),
+                        });
+                    }
+                }
+            }
+            self.evaluate_level(&node.children, workflow, depth + 1, Some(action), findings);
+        }
+    }
+}
+
+/// One use of an action, as seen by rule conditions.
+struct Subject<'a> {
+    node: &'a AuditNode,
+    occurrence: Option<&'a Occurrence>,
+    workflow: &'a str,
+    depth: usize,
+    parent: Option<&'a ActionRef>,
+}
+
+impl Subject<'_> {
+    fn values(&self, field: Field) -> Vec<String> {
+        let entry = &self.node.entry;
+        let action = &entry.action;
+        let one = |value: String| vec![value];
+        match field {
+            Field::Action => one(action.package_name()),
+            Field::Owner => one(action.owner.clone()),
+            Field::Repo => one(action.repo.clone()),
+            Field::Path => action.path.iter().cloned().collect(),
+            Field::Ref => one(action.git_ref.clone()),
+            Field::RefType => one(action.ref_type.to_string()),
+            Field::Job => self
+                .occurrence
+                .and_then(|o| o.job.clone())
+                .into_iter()
+                .collect(),
+            Field::Step => self
+                .occurrence
+                .and_then(|o| o.step.clone())
+                .into_iter()
+                .collect(),
+            Field::Workflow => {
+                let file = self
+                    .occurrence
+                    .and_then(|o| o.location.as_ref())
+                    .or(action.location.as_ref())
+                    .and_then(|l| l.file.clone());
+                match file {
+                    Some(file) => one(file),
+                    None if self.depth == 0 => one(self.workflow.to_string()),
+                    None => vec![],
+                }
+            }
+            Field::Depth => one(self.depth.to_string()),
+            Field::Parent => self
+                .parent
+                .map(ActionRef::package_name)
+                .into_iter()
+                .collect(),
+            Field::Advisory => entry
+                .advisories
+                .iter()
+                .flat_map(|a| std::iter::once(a.id.clone()).chain(a.aliases.iter().cloned()))
+                .collect(),
+            Field::Severity => entry
+                .advisories
+                .iter()
+                .map(|a| a.severity_label().to_string())
+                .collect(),
+            Field::OwnerType => entry
+                .reputation
+                .as_ref()
+                .and_then(|r| r.owner_type)
+                .map(|t| t.to_string())
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// `message` with each `{field}` replaced by the field's values.
+    fn render(&self, message: &str) -> String {
+        let mut out = message.to_string();
+        for placeholder in placeholders(message) {
+            if let Some(field) = field_named(placeholder) {
+                let value = self.values(field).join(", ");
+                out = out.replace(&format!("{{{placeholder}}}"), &value);
+            }
+        }
+        out
+    }
+}
+
+fn field_named(name: &str) -> Option<Field> {
+    FIELDS
+        .iter()
+        .find(|(field_name, _)| *field_name == name)
+        .map(|(_, field)| *field)
+}
+
+/// Names inside `{...}` in `message`.
+fn placeholders(message: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        names.push(&rest[start + 1..start + len]);
+        rest = &rest[start + len + 1..];
+    }
+    names
+}
+
+/// Case-insensitive match where `*` stands for any run of characters.
+pub(crate) fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let value = value.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::AuditContext;
+
+    const RULES: &str = r#"
+rules:
+  - id: deploy-external-action
+    severity: high
+    message: "deploy job {job} uses {action}, which is not an internal action"
+    when:
+      job: "deploy*"
+      owner: { not: [acme, acme-*] }
+  - id: nested-tag
+    message: "{action} is pinned to a tag inside {parent}"
+    when:
+      depth: [1, 2]
+      ref_type: tag
+"#;
+
+    fn nodes(yaml: &str) -> Vec<AuditNode> {
+        crate::parse_actions_from(yaml, ".github/workflows/deploy.yml")
+            .unwrap()
+            .into_iter()
+            .map(|action| AuditNode::from(AuditContext::for_test(action)))
+            .collect()
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("acme-*", "ACME-tools"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("deploy*prod", "deploy-eu-prod"));
+        assert!(!glob_match("deploy*prod", "deploy-eu-staging"));
+        assert!(glob_match("a*b*a", "abba"));
+        assert!(!glob_match("a*a", "a"));
+        assert!(!glob_match("acme", "acme-tools"));
+    }
+
+    #[test]
+    fn reports_matching_uses_with_rendered_message() {
+        let rules = Rules::from_yaml(RULES).unwrap();
+        let mut nodes = nodes(
+            "jobs:\n  deploy-prod:\n    steps:\n      - uses: acme/deploy@v1\n      - uses: aws-actions/configure-aws-credentials@v4\n  build:\n    steps:\n      - uses: aws-actions/configure-aws-credentials@v4\n",
+        );
+        let child = nodes.remove(0);
+        nodes[0].children.push(child);
+
+        let findings = rules.evaluate(&nodes, ".github/workflows/deploy.yml");
+        let summary: Vec<(String, Severity, String)> = findings
+            .iter()
+            .map(|f| (f.rule_id.clone(), f.severity, f.message.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "deploy-external-action".to_string(),
+                    Severity::High,
+                    "deploy job deploy-prod uses aws-actions/configure-aws-credentials, which is not an internal action".to_string()
+                ),
+                (
+                    "nested-tag".to_string(),
+                    Severity::Medium,
+                    "acme/deploy is pinned to a tag inside aws-actions/configure-aws-credentials"
+                        .to_string()
+                ),
+            ]
+        );
+        assert_eq!(findings[0].location.as_ref().unwrap().line, 5);
+    }
+
+    #[test]
+    fn owner_type_needs_reputation_data() {
+        use crate::stages::reputation::{OwnerType, Reputation};
+
+        let rules = Rules::from_yaml(
+            "rules:\n  - id: personal-action\n    message: \"{action} is owned by a {owner_type}\"\n    when:\n      owner_type: user\n",
+        )
+        .unwrap();
+        let mut nodes = nodes(
+            "jobs:\n  build:\n    steps:\n      - uses: someone/tool@v1\n      - uses: acme/deploy@v1\n",
+        );
+        assert!(rules.evaluate(&nodes, "ci.yml").is_empty());
+
+        for node in &mut nodes {
+            let owner_type = if node.entry.action.owner == "acme" {
+                OwnerType::Organization
+            } else {
+                OwnerType::User
+            };
+            node.entry.reputation = Some(Reputation {
+                owner_type: Some(owner_type),
+                ..Reputation::default()
+            });
+        }
+        let findings = rules.evaluate(&nodes, "ci.yml");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].message, "someone/tool is owned by a user");
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let err = |yaml: &str| Rules::from_yaml(yaml).unwrap_err().to_string();
+        assert!(
+            err("rules:\n  - id: dynamic-uses\n    message: x\n    when: {owner: a}\n")
+                .contains("built-in")
+        );
+        assert!(err("rules:\n  - id: x\n    message: x\n    when: {}\n").contains("at least one"));
+        assert!(
+            err("rules:\n  - id: x\n    message: \"{nope}\"\n    when: {owner: a}\n")
+                .contains("{nope}")
+        );
+        assert!(
+            Rules::from_yaml("rules:\n  - id: x\n    message: x\n    when: {colour: a}\n").is_err()
+        );
+        assert!(
+            err("rules:\n  - id: x\n    message: x\n    when: {owner: a}\n  - id: x\n    message: y\n    when: {owner: b}\n")
+                .contains("more than once")
+        );
+        assert_eq!(
+            err("disable: [remote-script, no-such-rule]\n"),
+            "disable: no-such-rule is not a built-in rule"
+        );
+    }
+
+    #[test]
+    fn lists_disabled_built_in_rules() {
+        let rules = Rules::from_yaml("disable:\n  - remote-script\n  - unpinned-action\n").unwrap();
+        assert!(rules.is_empty());
+        assert_eq!(rules.disabled(), ["remote-script", "unpinned-action"]);
+    }
+
+    #[test]
+    fn forbidden_actions_are_reported_at_every_use() {
+        let rules = Rules::from_yaml(
+            "forbid:\n  - tj-actions/*\n  - action: Evil-Org\n    reason: compromised in 2025\n  - actions/cache@v1\n",
+        )
+        .unwrap()
+        .with_forbidden(["someone/tool"]);
+        assert_eq!(rules.forbidden().len(), 4);
+        let mut nodes = nodes(
+            "jobs:\n  a:\n    steps:\n      - uses: tj-actions/changed-files@v45\n      - uses: evil-org/helper/sub@main\n      - uses: actions/cache@v4\n      - uses: someone/tool@v2\n  b:\n    steps:\n      - uses: tj-actions/changed-files@v45\n",
+        );
+        let child = nodes.remove(2);
+        nodes[0].children.push(child);
+
+        let findings = rules.evaluate(&nodes, ".github/workflows/deploy.yml");
+        let mut found: Vec<(usize, &str)> = findings
+            .iter()
+            .map(|f| (f.location.as_ref().unwrap().line, f.message.as_str()))
+            .collect();
+        found.sort_unstable();
+        assert_eq!(
+            found,
+            [
+                (
+                    4,
+                    "tj-actions/changed-files@v45 is forbidden by policy (`tj-actions/*`)"
+                ),
+                (
+                    5,
+                    "evil-org/helper/sub@main is forbidden by policy (`Evil-Org`): compromised in 2025"
+                ),
+                (7, "someone/tool@v2 is forbidden by policy (`someone/tool`)"),
+                (
+                    10,
+                    "tj-actions/changed-files@v45 is forbidden by policy (`tj-actions/*`)"
+                ),
+            ]
+        );
+        assert!(findings.iter().all(|f| f.rule_id == "forbidden-action"));
+        assert!(findings.iter().all(|f| f.severity == Severity::Critical));
+
+        let err = Rules::from_yaml("forbid: [\"\"]\n").unwrap_err();
+        assert_eq!(err.to_string(), "forbid: pattern must not be empty");
+    }
+}
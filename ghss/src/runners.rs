@@ -0,0 +1,167 @@
+//! Jobs that run pull request code on self-hosted runners.
+//!
+//! Which runner a job gets is decided by its `runs-on:` labels. GitHub's
+//! hosted images are `ubuntu-*`, `windows-*`, and `macos-*`; anything else,
+//! including a runner `group`, is treated as self-hosted. Labels built from
+//! `${{ }}` expressions are unknown until run time and are skipped.
+
+use crate::finding::{Finding, SELF_HOSTED_RUNNER};
+use crate::location::key_locations;
+use crate::workflow::Workflow;
+
+/// Events that run a workflow for a pull request, including ones from forks.
+pub const PULL_REQUEST_EVENTS: &[&str] = &[
+    "pull_request",
+    "pull_request_target",
+    "pull_request_review",
+    "pull_request_review_comment",
+];
+
+/// Label prefixes of GitHub-hosted runner images.
+const HOSTED_PREFIXES: &[&str] = &["ubuntu-", "windows-", "macos-"];
+
+/// The runner a job asks for, as its labels and optional runner group.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RunsOn {
+    pub labels: Vec<String>,
+    pub group: Option<String>,
+}
+
+impl RunsOn {
+    fn from_yaml(value: &serde_yaml::Value) -> Self {
+        let strings = |v: Option<&serde_yaml::Value>| -> Vec<String> {
+            match v {
+                Some(serde_yaml::Value::String(s)) => vec![s.clone()],
+                Some(serde_yaml::Value::Sequence(items)) => items
+                    .iter()
+                    .filter_map(|i| i.as_str().map(String::from))
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+        match value {
+            serde_yaml::Value::Mapping(_) => RunsOn {
+                labels: strings(value.get("labels")),
+                group: value
+                    .get("group")
+                    .and_then(|g| g.as_str())
+                    .map(String::from),
+            },
+            _ => RunsOn {
+                labels: strings(Some(value)),
+                group: None,
+            },
+        }
+    }
+
+    /// True for a runner group, or any label that is not a GitHub-hosted
+    /// image. False when a label or the group is an expression.
+    pub fn is_self_hosted(&self) -> bool {
+        let dynamic = |s: &String| s.contains("${{");
+        if self.labels.iter().any(dynamic) || self.group.as_ref().is_some_and(dynamic) {
+            return false;
+        }
+        self.group.is_some()
+            || self.labels.iter().any(|label| {
+                let label = label.to_ascii_lowercase();
+                !HOSTED_PREFIXES.iter().any(|p| label.starts_with(p))
+            })
+    }
+}
+
+/// Raise [`SELF_HOSTED_RUNNER`] for each self-hosted job in a workflow that
+/// pull requests trigger, located at the job's `runs-on:`.
+pub fn check_self_hosted(yaml: &str, file: &str) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    let events: Vec<String> = workflow
+        .events()
+        .into_iter()
+        .filter(|e| PULL_REQUEST_EVENTS.contains(&e.as_str()))
+        .collect();
+    if events.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut findings = Vec::new();
+    for (name, job) in workflow.jobs() {
+        let Some(runs_on) = job.runs_on.as_ref().map(RunsOn::from_yaml) else {
+            continue;
+        };
+        if !runs_on.is_self_hosted() {
+            continue;
+        }
+        let mut runner = format!("`{}`", runs_on.labels.join(", "));
+        if let Some(group) = &runs_on.group {
+            runner = format!("group `{group}`");
+            if !runs_on.labels.is_empty() {
+                runner.push_str(&format!(" ({})", runs_on.labels.join(", ")));
+            }
+        }
+        let location = key_locations(yaml, &["jobs", &name])
+            .remove("runs-on")
+            .map(|l| l.with_file(file));
+        findings.push(
+            Finding::new(
+                &SELF_HOSTED_RUNNER,
+                format!(
+                    "job `{name}` runs on self-hosted runner {runner} and the workflow is triggered by {}",
+                    events.join(", ")
+                ),
+            )
+            .with_location(location),
+        );
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs_on(yaml: &str) -> RunsOn {
+        RunsOn::from_yaml(&serde_yaml::from_str(yaml).unwrap())
+    }
+
+    #[test]
+    fn hosted_images_are_not_self_hosted() {
+        assert!(!runs_on("ubuntu-latest").is_self_hosted());
+        assert!(!runs_on("[windows-2022]").is_self_hosted());
+        assert!(!runs_on("macos-14-xlarge").is_self_hosted());
+        assert!(!runs_on("${{ matrix.os }}").is_self_hosted());
+
+        assert!(runs_on("self-hosted").is_self_hosted());
+        assert!(runs_on("[self-hosted, linux, x64]").is_self_hosted());
+        assert!(runs_on("gpu-box").is_self_hosted());
+        assert_eq!(
+            runs_on("{group: build-farm, labels: [linux]}"),
+            RunsOn {
+                labels: vec!["linux".to_string()],
+                group: Some("build-farm".to_string()),
+            }
+        );
+        assert!(runs_on("{group: build-farm}").is_self_hosted());
+    }
+
+    #[test]
+    fn flags_self_hosted_jobs_of_pull_request_workflows() {
+        let yaml = "on:\n  pull_request:\n  push:\njobs:\n  test:\n    runs-on: [self-hosted, linux]\n    steps:\n      - run: make test\n  lint:\n    runs-on: ubuntu-latest\n    steps:\n      - run: make lint\n  gpu:\n    runs-on:\n      group: gpu\n      labels: cuda\n    steps:\n      - run: make gpu\n";
+        let findings: Vec<String> = check_self_hosted(yaml, "ci.yml")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                "self-hosted-runner (high): job `test` runs on self-hosted runner `self-hosted, linux` and the workflow is triggered by pull_request\n    at ci.yml:6:5",
+                "self-hosted-runner (high): job `gpu` runs on self-hosted runner group `gpu` (cuda) and the workflow is triggered by pull_request\n    at ci.yml:14:5",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_workflows_pull_requests_cannot_trigger() {
+        let yaml = "on: [push, workflow_dispatch]\njobs:\n  deploy:\n    runs-on: self-hosted\n    steps:\n      - run: make deploy\n";
+        assert!(check_self_hosted(yaml, "ci.yml").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+//! Correlate audited actions with a real workflow run.
+//!
+//! The static audit says which actions a workflow *can* run; a run's jobs
+//! and steps say which ones *did*. Steps are matched by name: a `uses:` step
+//! without `name:` shows up in the run as `Run owner/repo@ref`, and a named
+//! step under its `name:`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::action_ref::ActionRef;
+use crate::github::GitHubClient;
+use crate::output::AuditNode;
+
+/// A job from `GET /repos/{owner}/{repo}/actions/runs/{run_id}/jobs`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunJob {
+    pub name: String,
+    #[serde(default)]
+    pub steps: Vec<RunStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunStep {
+    pub name: String,
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct JobsResponse {
+    jobs: Vec<RunJob>,
+}
+
+/// How an audited action showed up in a workflow run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionExecution {
+    pub run_id: u64,
+    /// Every run step matched to this action. Empty if none matched.
+    pub steps: Vec<StepExecution>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepExecution {
+    pub job: String,
+    pub step: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conclusion: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<i64>,
+}
+
+impl ActionExecution {
+    /// True if at least one matched step ran (was not skipped).
+    pub fn executed(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|s| s.conclusion.as_deref().is_some_and(|c| c != "skipped"))
+    }
+}
+
+impl std::fmt::Display for StepExecution {
+    /// e.g. `build / Run actions/checkout@v4: success in 3s`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} / {}: {}",
+            self.job,
+            self.step,
+            self.conclusion.as_deref().unwrap_or("not completed")
+        )?;
+        if let Some(secs) = self.duration_secs {
+            write!(f, " in {secs}s")?;
+        }
+        Ok(())
+    }
+}
+
+/// Fetch the jobs and steps of a workflow run. `repo` is `owner/name`.
+/// Only the first 100 jobs are fetched.
+#[instrument(skip(client))]
+pub async fn fetch_run_jobs(client: &GitHubClient, repo: &str, run_id: u64) -> Result<Vec<RunJob>> {
+    let url = format!(
+        "{}/repos/{repo}/actions/runs/{run_id}/jobs?per_page=100",
+        client.api_base_url()
+    );
+    let json = client
+        .api_get(&url)
+        .await
+        .with_context(|| format!("failed to fetch jobs for run {run_id}"))?;
+    let response: JobsResponse =
+        serde_json::from_value(json).context("unexpected workflow run jobs response")?;
+    Ok(response.jobs)
+}
+
+/// Attach an [`ActionExecution`] to every root node. Nested actions run
+/// inside their parent's step, so they have no steps of their own to match.
+pub fn correlate(nodes: &mut [AuditNode], run_id: u64, jobs: &[RunJob]) {
+    for node in nodes {
+        node.entry.execution = Some(ActionExecution {
+            run_id,
+            steps: matching_steps(&node.entry.action, jobs),
+        });
+    }
+}
+
+fn matching_steps(action: &ActionRef, jobs: &[RunJob]) -> Vec<StepExecution> {
+    let default_name = format!("Run {action}");
+    let mut names: Vec<&str> = vec![&default_name];
+    names.extend(action.occurrences.iter().filter_map(|o| o.step.as_deref()));
+    let workflow_jobs: Vec<&str> = action
+        .occurrences
+        .iter()
+        .filter_map(|o| o.job.as_deref())
+        .collect();
+
+    let matches = |job_filter: bool| -> Vec<StepExecution> {
+        jobs.iter()
+            .filter(|job| !job_filter || workflow_jobs.iter().any(|w| job_matches(&job.name, w)))
+            .flat_map(|job| {
+                job.steps
+                    .iter()
+                    .filter(|step| names.contains(&step.name.as_str()))
+                    .map(|step| StepExecution {
+                        job: job.name.clone(),
+                        step: step.name.clone(),
+                        conclusion: step.conclusion.clone(),
+                        duration_secs: step
+                            .started_at
+                            .zip(step.completed_at)
+                            .map(|(start, end)| (end - start).num_seconds()),
+                    })
+            })
+            .collect()
+    };
+
+    // Run job names default to the job id (plus ` (matrix values)`), but a
+    // job `name:` replaces it, so fall back to searching every job.
+    let in_known_jobs = matches(true);
+    if in_known_jobs.is_empty() {
+        matches(false)
+    } else {
+        in_known_jobs
+    }
+}
+
+fn job_matches(run_job: &str, workflow_job: &str) -> bool {
+    run_job == workflow_job
+        || run_job
+            .strip_prefix(workflow_job)
+            .is_some_and(|rest| rest.starts_with(" ("))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Occurrence;
+    use std::collections::BTreeMap;
+
+    fn jobs() -> Vec<RunJob> {
+        let response: JobsResponse = serde_json::from_value(serde_json::json!({
+            "total_count": 2,
+            "jobs": [
+                {
+                    "name": "build (ubuntu-latest)",
+                    "steps": [
+                        {"name": "Set up job", "conclusion": "success"},
+                        {
+                            "name": "Run actions/checkout@v4",
+                            "conclusion": "success",
+                            "started_at": "2026-01-01T00:00:00Z",
+                            "completed_at": "2026-01-01T00:00:03Z"
+                        },
+                        {"name": "Upload coverage", "conclusion": "skipped"}
+                    ]
+                },
+                {
+                    "name": "Lint",
+                    "steps": [
+                        {"name": "Run actions/checkout@v4", "conclusion": "success"}
+                    ]
+                }
+            ]
+        }))
+        .unwrap();
+        response.jobs
+    }
+
+    fn node(uses: &str, occurrences: Vec<Occurrence>) -> AuditNode {
+        let mut node = AuditNode::with_advisories(uses, vec![]);
+        node.entry.action.occurrences = occurrences;
+        node
+    }
+
+    fn occurrence(job: &str, step: Option<&str>) -> Occurrence {
+        Occurrence {
+            job: Some(job.to_string()),
+            step: step.map(String::from),
+            location: None,
+            call: None,
+            with: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn matches_default_step_name_in_matrix_job() {
+        let mut nodes = vec![node("actions/checkout@v4", vec![occurrence("build", None)])];
+        correlate(&mut nodes, 42, &jobs());
+
+        let execution = nodes[0].entry.execution.as_ref().unwrap();
+        assert_eq!(execution.run_id, 42);
+        assert!(execution.executed());
+        assert_eq!(execution.steps.len(), 1);
+        assert_eq!(
+            execution.steps[0].to_string(),
+            "build (ubuntu-latest) / Run actions/checkout@v4: success in 3s"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_all_jobs_when_job_is_renamed() {
+        let mut nodes = vec![node("actions/checkout@v4", vec![occurrence("lint", None)])];
+        correlate(&mut nodes, 42, &jobs());
+        assert_eq!(nodes[0].entry.execution.as_ref().unwrap().steps.len(), 2);
+    }
+
+    #[test]
+    fn matches_named_steps_and_reports_skipped() {
+        let mut nodes = vec![node(
+            "codecov/codecov-action@v3",
+            vec![occurrence("build", Some("Upload coverage"))],
+        )];
+        correlate(&mut nodes, 42, &jobs());
+
+        let execution = nodes[0].entry.execution.as_ref().unwrap();
+        assert_eq!(execution.steps.len(), 1);
+        assert!(!execution.executed());
+    }
+
+    #[test]
+    fn unmatched_action_is_not_executed() {
+        let mut nodes = vec![node("org/never-ran@v1", vec![])];
+        correlate(&mut nodes, 42, &jobs());
+
+        let execution = nodes[0].entry.execution.as_ref().unwrap();
+        assert!(execution.steps.is_empty());
+        assert!(!execution.executed());
+    }
+}
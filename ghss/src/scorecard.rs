@@ -0,0 +1,199 @@
+//! OpenSSF Scorecard results from the public Scorecard API: how well an
+//! action's repository follows security practices such as code review,
+//! branch protection, and pinned dependencies, scored 0 to 10.
+
+use std::fmt;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::action_ref::ActionRef;
+
+const SCORECARD_API_URL: &str = "https://api.securityscorecards.dev";
+
+/// Checks scoring below this are reported as failing. Inconclusive checks
+/// (score -1) are never failing.
+const FAILING_BELOW: i32 = 5;
+
+/// One Scorecard check that scored below [`FAILING_BELOW`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailingCheck {
+    /// e.g. `Code-Review`.
+    pub name: String,
+    pub score: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The latest Scorecard result for a repository.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scorecard {
+    /// Overall score, 0 to 10.
+    pub score: f64,
+    /// Date of the Scorecard run, e.g. `2025-01-06`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failing: Vec<FailingCheck>,
+}
+
+// Scores are parsed from JSON numbers, so they are never NaN.
+impl Eq for Scorecard {}
+
+impl fmt::Display for Scorecard {
+    /// e.g. `4.2 (2025-01-06); failing: Code-Review 0, Maintained 3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}", self.score)?;
+        if let Some(date) = &self.date {
+            write!(f, " ({date})")?;
+        }
+        if !self.failing.is_empty() {
+            let checks: Vec<String> = self
+                .failing
+                .iter()
+                .map(|c| format!("{} {}", c.name, c.score))
+                .collect();
+            write!(f, "; failing: {}", checks.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct ScorecardResponse {
+    #[serde(default)]
+    date: Option<String>,
+    score: f64,
+    #[serde(default)]
+    checks: Vec<CheckResponse>,
+}
+
+#[derive(Deserialize)]
+struct CheckResponse {
+    name: String,
+    score: i32,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+impl From<ScorecardResponse> for Scorecard {
+    fn from(response: ScorecardResponse) -> Self {
+        Self {
+            score: response.score,
+            date: response.date.map(|d| d.chars().take(10).collect()),
+            failing: response
+                .checks
+                .into_iter()
+                .filter(|c| (0..FAILING_BELOW).contains(&c.score))
+                .map(|c| FailingCheck {
+                    name: c.name,
+                    score: c.score,
+                    reason: c.reason.filter(|r| !r.is_empty()),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Scorecard API client. No token is needed. Base URL overridable via
+/// `GHSS_SCORECARD_BASE_URL`.
+#[derive(Clone)]
+pub struct ScorecardClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for ScorecardClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScorecardClient {
+    pub fn new() -> Self {
+        Self::with_base_url(
+            std::env::var("GHSS_SCORECARD_BASE_URL")
+                .unwrap_or_else(|_| SCORECARD_API_URL.to_string()),
+        )
+    }
+
+    pub(crate) fn with_base_url(base_url: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    /// The latest result for an action's GitHub repository. `None` if
+    /// Scorecard has not scored it.
+    #[instrument(skip(self), fields(action = %action))]
+    pub async fn scorecard(&self, action: &ActionRef) -> Result<Option<Scorecard>> {
+        let url = format!(
+            "{}/projects/github.com/{}/{}",
+            self.base_url, action.owner, action.repo
+        );
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to query Scorecard: {url}"))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            bail!("Scorecard API returned HTTP {status} for {url}");
+        }
+        let body: ScorecardResponse = response
+            .json()
+            .await
+            .context("failed to parse Scorecard response")?;
+        Ok(Some(body.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn keeps_failing_checks_and_treats_404_as_unscored() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/github.com/some-user/deploy-action"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "date": "2025-01-06T07:20:53Z",
+                "repo": {"name": "github.com/some-user/deploy-action"},
+                "score": 4.25,
+                "checks": [
+                    {"name": "Code-Review", "score": 0, "reason": "Found 0/30 approved changesets"},
+                    {"name": "Maintained", "score": 3, "reason": ""},
+                    {"name": "License", "score": 10, "reason": "license file detected"},
+                    {"name": "Packaging", "score": -1, "reason": "packaging workflow not detected"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ScorecardClient::with_base_url(server.uri());
+        let action: ActionRef = "some-user/deploy-action@v1".parse().unwrap();
+        let scorecard = client.scorecard(&action).await.unwrap().unwrap();
+        assert_eq!(
+            scorecard.to_string(),
+            "4.2 (2025-01-06); failing: Code-Review 0, Maintained 3"
+        );
+        assert_eq!(
+            scorecard.failing[0].reason.as_deref(),
+            Some("Found 0/30 approved changesets")
+        );
+        assert_eq!(scorecard.failing[1].reason, None);
+
+        let unscored: ActionRef = "some-user/other-action@v1".parse().unwrap();
+        assert_eq!(client.scorecard(&unscored).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,316 @@
+//! Static checks on the inline JavaScript of `actions/github-script` steps.
+//!
+//! The script runs with an authenticated Octokit client and the step's
+//! token, so it can do anything the token allows. The checks here are
+//! line-based pattern matches, not a JavaScript parser: they point a
+//! reviewer at calls worth a second look rather than prove misuse.
+
+use crate::action_ref::ActionRef;
+use crate::finding::{Finding, GITHUB_SCRIPT_DANGEROUS_CALL, GITHUB_SCRIPT_INJECTION};
+
+/// REST calls that change repository or organization settings, access, or
+/// secrets. Matched after the `github.rest.` / `github.` client prefix.
+const ADMIN_CALLS: &[&str] = &[
+    "repos.update(",
+    "repos.delete(",
+    "repos.transfer(",
+    "repos.addCollaborator(",
+    "repos.updateBranchProtection(",
+    "repos.deleteBranchProtection(",
+    "repos.createDeployKey(",
+    "repos.createWebhook(",
+    "repos.createOrUpdateEnvironment(",
+    "git.updateRef(",
+    "git.deleteRef(",
+    "orgs.setMembershipForUser(",
+    "teams.addOrUpdateRepoPermissionsInOrg(",
+    "actions.setGithubActionsPermissionsRepository(",
+];
+
+/// REST calls that read or write Actions secrets.
+const SECRET_CALLS: &[&str] = &[
+    "actions.createOrUpdateRepoSecret(",
+    "actions.createOrUpdateOrgSecret(",
+    "actions.createOrUpdateEnvironmentSecret(",
+    "actions.getRepoPublicKey(",
+    "actions.listRepoSecrets(",
+];
+
+/// Code evaluated from a string or loaded from a computed module name.
+const DYNAMIC_CODE: &[&str] = &["eval(", "new Function(", "require(", "import("];
+
+/// Ways a script can run a shell command.
+const SHELL_OUTS: &[&str] = &[
+    "exec.exec(",
+    "exec.getExecOutput(",
+    "child_process",
+    "execSync(",
+    "spawn(",
+];
+
+/// `${{ }}` expressions holding data an outside contributor controls.
+pub(crate) const UNTRUSTED_EXPRESSIONS: &[&str] = &["github.event.", "github.head_ref"];
+
+/// One suspicious line in a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptIssue {
+    /// 1-based line within the script.
+    pub line: usize,
+    /// True for code injection; false for a call that only needs review.
+    pub injection: bool,
+    pub message: String,
+}
+
+/// True for `actions/github-script` at any ref.
+pub fn is_github_script(action: &ActionRef) -> bool {
+    action.owner.eq_ignore_ascii_case("actions")
+        && action.repo.eq_ignore_ascii_case("github-script")
+        && action.path.is_none()
+}
+
+/// Scan `script` for dangerous patterns. Lines that are `//` comments are
+/// skipped.
+pub fn audit_script(script: &str) -> Vec<ScriptIssue> {
+    let mut issues = Vec::new();
+    for (i, line) in script.lines().enumerate() {
+        let code = line.trim_start();
+        if code.starts_with("//") {
+            continue;
+        }
+        let mut push = |injection, message: String| {
+            issues.push(ScriptIssue {
+                line: i + 1,
+                injection,
+                message,
+            });
+        };
+
+        for expr in expressions(code) {
+            if expr.starts_with("secrets.") {
+                push(
+                    false,
+                    format!("interpolates `${{{{ {expr} }}}}` into the script source"),
+                );
+            } else if UNTRUSTED_EXPRESSIONS.iter().any(|u| expr.starts_with(u)) {
+                push(
+                    true,
+                    format!("interpolates untrusted `${{{{ {expr} }}}}` into JavaScript"),
+                );
+            }
+        }
+        if let Some(shell) = SHELL_OUTS.iter().find(|s| code.contains(*s))
+            && code.contains("context.payload")
+        {
+            let shell = shell.trim_end_matches('(');
+            push(true, format!("passes `context.payload` data to `{shell}`"));
+        }
+        if let Some(call) = ADMIN_CALLS.iter().find(|c| code.contains(*c)) {
+            let call = call.trim_end_matches('(');
+            push(
+                false,
+                format!("calls `{call}`, which changes repository settings or access"),
+            );
+        }
+        if let Some(call) = SECRET_CALLS.iter().find(|c| code.contains(*c)) {
+            let call = call.trim_end_matches('(');
+            push(
+                false,
+                format!("calls `{call}`, which accesses Actions secrets"),
+            );
+        }
+        for pattern in DYNAMIC_CODE {
+            if let Some(pos) = find_call(code, pattern)
+                && !is_string_literal(&code[pos + pattern.len()..])
+            {
+                let name = pattern.trim_end_matches('(');
+                push(false, format!("runs dynamic code via `{name}`"));
+            }
+        }
+    }
+    issues
+}
+
+/// Findings for every `actions/github-script` occurrence in `actions` whose
+/// `script` input matches a dangerous pattern, located at the step's `uses:`.
+pub fn check_github_scripts<'a>(actions: impl IntoIterator<Item = &'a ActionRef>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for action in actions.into_iter().filter(|a| is_github_script(a)) {
+        for occurrence in &action.occurrences {
+            let Some(script) = occurrence.with.get("script") else {
+                continue;
+            };
+            let step = match (&occurrence.step, &occurrence.job) {
+                (Some(step), Some(job)) => format!("step `{step}` in job `{job}`"),
+                (Some(step), None) => format!("step `{step}`"),
+                (None, Some(job)) => format!("a step in job `{job}`"),
+                (None, None) => "a step".to_string(),
+            };
+            for issue in audit_script(script) {
+                let rule = if issue.injection {
+                    &GITHUB_SCRIPT_INJECTION
+                } else {
+                    &GITHUB_SCRIPT_DANGEROUS_CALL
+                };
+                findings.push(
+                    Finding::new(
+                        rule,
+                        format!(
+                            "github-script {step} {} (script line {})",
+                            issue.message, issue.line
+                        ),
+                    )
+                    .with_location(occurrence.location.clone()),
+                );
+            }
+        }
+    }
+    findings
+}
+
+/// Trimmed contents of each `${{ ... }}` expression in `line`.
+pub(crate) fn expressions(line: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("${{") {
+        let after = &rest[start + 3..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        found.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    found
+}
+
+/// Position of `pattern` in `code` where it is not part of a longer
+/// identifier or a method call (e.g. `foo.eval(`, `myrequire(`).
+fn find_call(code: &str, pattern: &str) -> Option<usize> {
+    code.match_indices(pattern).map(|(i, _)| i).find(|&i| {
+        !code[..i]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '$')
+    })
+}
+
+/// True if `args` starts with a plain string literal, e.g. `'fs')`.
+fn is_string_literal(args: &str) -> bool {
+    let args = args.trim_start();
+    let Some(quote) = args.chars().next().filter(|c| matches!(c, '\'' | '"')) else {
+        return false;
+    };
+    args[1..]
+        .find(quote)
+        .is_some_and(|end| args[1 + end + 1..].trim_start().starts_with(')'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::{self, UsesRef};
+
+    fn messages(script: &str) -> Vec<String> {
+        audit_script(script)
+            .into_iter()
+            .map(|i| format!("{}: {}", i.line, i.message))
+            .collect()
+    }
+
+    #[test]
+    fn flags_admin_and_secret_calls() {
+        let script = "await github.rest.repos.update({ owner, repo, private: false });\n\
+                      await github.rest.repos.updateBranchProtection(params);\n\
+                      await github.rest.actions.createOrUpdateRepoSecret(params);\n\
+                      await github.rest.issues.createComment(params);\n";
+        assert_eq!(
+            messages(script),
+            [
+                "1: calls `repos.update`, which changes repository settings or access",
+                "2: calls `repos.updateBranchProtection`, which changes repository settings or access",
+                "3: calls `actions.createOrUpdateRepoSecret`, which accesses Actions secrets",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_interpolated_secrets_and_event_data() {
+        let issues = audit_script(
+            "const token = '${{ secrets.DEPLOY_TOKEN }}';\n\
+             const title = \"${{ github.event.issue.title }}\";\n\
+             const sha = '${{ github.sha }}';\n",
+        );
+        assert_eq!(issues.len(), 2);
+        assert!(!issues[0].injection);
+        assert_eq!(
+            issues[0].message,
+            "interpolates `${{ secrets.DEPLOY_TOKEN }}` into the script source"
+        );
+        assert!(issues[1].injection);
+        assert_eq!(issues[1].line, 2);
+    }
+
+    #[test]
+    fn flags_dynamic_code_but_not_literal_requires() {
+        assert_eq!(
+            messages(
+                "const fs = require('fs');\n\
+                 const mod = require(context.payload.inputs.module);\n\
+                 eval(body);\n\
+                 core.eval(x);\n\
+                 // eval(commented)\n"
+            ),
+            [
+                "2: runs dynamic code via `require`",
+                "3: runs dynamic code via `eval`",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_payload_in_shell_out() {
+        let issues = audit_script(
+            "await exec.exec(`git checkout ${context.payload.pull_request.head.ref}`);\n\
+             await exec.exec('make');\n",
+        );
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].injection);
+        assert_eq!(
+            issues[0].message,
+            "passes `context.payload` data to `exec.exec`"
+        );
+    }
+
+    #[test]
+    fn findings_are_located_at_the_step() {
+        let yaml = r#"
+jobs:
+  triage:
+    steps:
+      - name: Label
+        uses: actions/github-script@v7
+        with:
+          script: |
+            const title = "${{ github.event.issue.title }}";
+            await github.rest.repos.delete({ owner, repo });
+      - uses: actions/checkout@v4
+"#;
+        let actions: Vec<ActionRef> = workflow::parse_workflow_file(yaml, "triage.yml")
+            .unwrap()
+            .into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .collect();
+        let findings = check_github_scripts(&actions);
+        let rendered: Vec<String> = findings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            [
+                "github-script-injection (high): github-script step `Label` in job `triage` \
+                 interpolates untrusted `${{ github.event.issue.title }}` into JavaScript \
+                 (script line 1)\n    at triage.yml:6:15",
+                "github-script-dangerous-call (medium): github-script step `Label` in job \
+                 `triage` calls `repos.delete`, which changes repository settings or access \
+                 (script line 2)\n    at triage.yml:6:15",
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Detached ed25519 signatures for emitted reports.
+//!
+//! A report is signed over its exact output bytes. The signature is stored
+//! next to the report as a small JSON document so that a consumer holding the
+//! trusted public key can check the report was not modified after generation.
+
+use anyhow::{Context, Result, bail};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// Detached signature over a report's bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSignature {
+    pub algorithm: String,
+    /// Hex-encoded SHA-256 of the signed report.
+    pub sha256: String,
+    /// Hex-encoded public key of the signer. Informational only: verification
+    /// always uses the caller's trusted key.
+    pub public_key: String,
+    /// Hex-encoded signature over the report bytes.
+    pub signature: String,
+}
+
+/// Parse a PKCS#8 PEM ed25519 private key (e.g. from `openssl genpkey -algorithm ed25519`).
+pub fn load_signing_key(pem: &str) -> Result<SigningKey> {
+    SigningKey::from_pkcs8_pem(pem).map_err(|e| anyhow::anyhow!("invalid ed25519 private key: {e}"))
+}
+
+/// Parse an SPKI PEM ed25519 public key (e.g. from `openssl pkey -pubout`).
+pub fn load_verifying_key(pem: &str) -> Result<VerifyingKey> {
+    VerifyingKey::from_public_key_pem(pem)
+        .map_err(|e| anyhow::anyhow!("invalid ed25519 public key: {e}"))
+}
+
+pub fn sign_report(report: &[u8], key: &SigningKey) -> ReportSignature {
+    ReportSignature {
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        sha256: hex::encode(Sha256::digest(report)),
+        public_key: hex::encode(key.verifying_key().as_bytes()),
+        signature: hex::encode(key.sign(report).to_bytes()),
+    }
+}
+
+/// Check `signature` against `report` using the trusted `key`.
+pub fn verify_report(report: &[u8], signature: &ReportSignature, key: &VerifyingKey) -> Result<()> {
+    if signature.algorithm != SIGNATURE_ALGORITHM {
+        bail!("unsupported signature algorithm: {}", signature.algorithm);
+    }
+
+    if signature.public_key != hex::encode(key.as_bytes()) {
+        bail!(
+            "report was signed by a different key ({})",
+            signature.public_key
+        );
+    }
+
+    if signature.sha256 != hex::encode(Sha256::digest(report)) {
+        bail!("report digest does not match signature; the report has been modified");
+    }
+
+    let bytes = hex::decode(&signature.signature).context("signature is not valid hex")?;
+    let sig = Signature::from_slice(&bytes).context("signature has the wrong length")?;
+    key.verify_strict(report, &sig)
+        .context("signature verification failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+    use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn sign_then_verify_round_trips() {
+        let key = key(1);
+        let report = br#"[{"raw":"actions/checkout@v4"}]"#;
+        let sig = sign_report(report, &key);
+        assert_eq!(sig.algorithm, "ed25519");
+        assert_eq!(sig.sha256.len(), 64);
+        verify_report(report, &sig, &key.verifying_key()).unwrap();
+    }
+
+    #[test]
+    fn tampered_report_is_rejected() {
+        let key = key(1);
+        let sig = sign_report(b"original", &key);
+        let err = verify_report(b"modified", &sig, &key.verifying_key()).unwrap_err();
+        assert!(err.to_string().contains("modified"));
+    }
+
+    #[test]
+    fn forged_digest_is_rejected_by_signature_check() {
+        let key = key(1);
+        let mut sig = sign_report(b"original", &key);
+        sig.sha256 = hex::encode(Sha256::digest(b"modified"));
+        assert!(verify_report(b"modified", &sig, &key.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn untrusted_key_is_rejected() {
+        let sig = sign_report(b"report", &key(1));
+        let err = verify_report(b"report", &sig, &key(2).verifying_key()).unwrap_err();
+        assert!(err.to_string().contains("different key"));
+    }
+
+    #[test]
+    fn keys_load_from_pem() {
+        let key = key(3);
+        let private_pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let public_pem = key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+
+        let loaded = load_signing_key(&private_pem).unwrap();
+        assert_eq!(loaded.to_bytes(), key.to_bytes());
+        let loaded = load_verifying_key(&public_pem).unwrap();
+        assert_eq!(loaded, key.verifying_key());
+    }
+
+    #[test]
+    fn invalid_pem_is_error() {
+        assert!(load_signing_key("not a key").is_err());
+        assert!(load_verifying_key("not a key").is_err());
+    }
+}
@@ -1,21 +1,43 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use futures::future::join_all;
 use tracing::{debug, instrument, warn};
 
 use super::Stage;
-use crate::advisory::deduplicate_advisories;
+use crate::advisory::{AdvisoryFilter, deduplicate_advisories};
 use crate::context::AuditContext;
 use crate::providers::ActionAdvisoryProvider;
+use crate::version_range;
 
 pub struct AdvisoryStage {
     providers: Vec<Arc<dyn ActionAdvisoryProvider>>,
+    timeout: Option<Duration>,
+    filter: AdvisoryFilter,
 }
 
 impl AdvisoryStage {
     pub fn new(providers: Vec<Arc<dyn ActionAdvisoryProvider>>) -> Self {
-        Self { providers }
+        Self {
+            providers,
+            timeout: None,
+            filter: AdvisoryFilter::default(),
+        }
+    }
+
+    /// Give up on a provider's query after `timeout`, recording a stage
+    /// error and keeping the other providers' results.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Keep only the advisories `filter` keeps. By default withdrawn
+    /// advisories are dropped.
+    pub fn with_filter(mut self, filter: AdvisoryFilter) -> Self {
+        self.filter = filter;
+        self
     }
 }
 
@@ -26,7 +48,18 @@ impl Stage for AdvisoryStage {
         let results = join_all(self.providers.iter().map(|p| {
             let p = p.clone();
             let action = ctx.action.clone();
-            async move { (p.name().to_string(), p.query(&action).await) }
+            let timeout = self.timeout;
+            async move {
+                let result = match timeout {
+                    Some(limit) => tokio::time::timeout(limit, p.query(&action))
+                        .await
+                        .unwrap_or_else(|_| {
+                            Err(anyhow::anyhow!("timed out after {}s", limit.as_secs_f64()))
+                        }),
+                    None => p.query(&action).await,
+                };
+                (p.name().to_string(), result)
+            }
         }))
         .await;
 
@@ -40,7 +73,16 @@ impl Stage for AdvisoryStage {
                 }
             }
         }
+        advisories.retain(|adv| self.filter.keeps(adv));
         ctx.advisories = deduplicate_advisories(advisories);
+        if let Some(version) = ctx.action.version() {
+            for adv in &mut ctx.advisories {
+                adv.applicable = adv
+                    .affected_range
+                    .as_deref()
+                    .and_then(|range| version_range::applies(range, version));
+            }
+        }
         debug!(action = %ctx.action, count = ctx.advisories.len(), "advisories collected");
         Ok(())
     }
@@ -55,6 +97,7 @@ mod tests {
     use super::*;
     use crate::action_ref::ActionRef;
     use crate::advisory::Advisory;
+    use crate::advisory::Severity;
     use crate::context::AuditContext;
 
     struct FakeProvider {
@@ -72,15 +115,40 @@ mod tests {
         }
     }
 
+    /// Never answers.
+    struct HangingProvider;
+
+    #[async_trait]
+    impl ActionAdvisoryProvider for HangingProvider {
+        async fn query(&self, _action: &ActionRef) -> anyhow::Result<Vec<Advisory>> {
+            std::future::pending().await
+        }
+        fn name(&self) -> &'static str {
+            "SlowProvider"
+        }
+    }
+
     fn make_advisory(id: &str) -> Advisory {
         Advisory {
             id: id.to_string(),
             aliases: vec![],
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: None,
+            description: None,
+            references: vec![],
+            vex: None,
             summary: format!("Advisory {id}"),
-            severity: "high".to_string(),
+            severity: Some(Severity::High),
             url: format!("https://example.com/{id}"),
             affected_range: None,
-            source: "fake".to_string(),
+            sources: vec!["fake".to_string()],
         }
     }
 
@@ -95,6 +163,20 @@ mod tests {
             advisories: vec![],
             scan: None,
             dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
             errors: vec![],
         }
     }
@@ -144,4 +226,79 @@ mod tests {
         assert!(ctx.errors[0].message.contains("BadProvider"));
         assert!(ctx.errors[0].message.contains("connection refused"));
     }
+
+    #[tokio::test]
+    async fn drops_withdrawn_advisories_unless_included() {
+        let mut withdrawn = make_advisory("GHSA-0002");
+        withdrawn.withdrawn = Some("2024-03-05T10:00:00Z".parse().unwrap());
+        let provider: Arc<dyn ActionAdvisoryProvider> = Arc::new(FakeProvider {
+            name: "ProviderA",
+            result: Ok(vec![make_advisory("GHSA-0001"), withdrawn]),
+        });
+
+        let mut ctx = make_ctx();
+        AdvisoryStage::new(vec![provider.clone()])
+            .run(&mut ctx)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = ctx.advisories.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["GHSA-0001"]);
+
+        let mut ctx = make_ctx();
+        AdvisoryStage::new(vec![provider])
+            .with_filter(AdvisoryFilter {
+                include_withdrawn: true,
+                ..AdvisoryFilter::default()
+            })
+            .run(&mut ctx)
+            .await
+            .unwrap();
+        assert_eq!(ctx.advisories.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn marks_advisories_outside_the_pinned_version() {
+        let mut fixed = make_advisory("GHSA-0001");
+        fixed.affected_range = Some("< 4.1.0".to_string());
+        let mut open = make_advisory("GHSA-0002");
+        open.affected_range = Some(">= 4.0.0".to_string());
+        let stage = AdvisoryStage::new(vec![Arc::new(FakeProvider {
+            name: "ProviderA",
+            result: Ok(vec![fixed, open, make_advisory("GHSA-0003")]),
+        })]);
+
+        let mut ctx = make_ctx();
+        ctx.action = "actions/checkout@v4.2.0".parse().unwrap();
+        stage.run(&mut ctx).await.unwrap();
+        let applicable: Vec<Option<bool>> = ctx.advisories.iter().map(|a| a.applicable).collect();
+        assert_eq!(applicable, [Some(false), Some(true), None]);
+        assert!(ctx.advisories[0].is_suppressed());
+
+        // A SHA pin has no version to compare.
+        let mut ctx = make_ctx();
+        ctx.action = "actions/checkout@8f4b7f84864484a7bf31766abe9204da3cbe65b3"
+            .parse()
+            .unwrap();
+        stage.run(&mut ctx).await.unwrap();
+        assert!(ctx.advisories.iter().all(|a| a.applicable.is_none()));
+    }
+
+    #[tokio::test]
+    async fn slow_provider_times_out_without_losing_others() {
+        let stage = AdvisoryStage::new(vec![
+            Arc::new(HangingProvider),
+            Arc::new(FakeProvider {
+                name: "FastProvider",
+                result: Ok(vec![make_advisory("GHSA-0001")]),
+            }),
+        ])
+        .with_timeout(Duration::from_millis(50));
+
+        let mut ctx = make_ctx();
+        stage.run(&mut ctx).await.unwrap();
+
+        assert_eq!(ctx.advisories.len(), 1);
+        assert_eq!(ctx.errors.len(), 1);
+        assert_eq!(ctx.errors[0].message, "SlowProvider: timed out after 0.05s");
+    }
 }
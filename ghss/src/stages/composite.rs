@@ -2,11 +2,15 @@ use async_trait::async_trait;
 use tracing::{debug, instrument};
 
 use crate::context::AuditContext;
+use crate::finding::{DEPRECATED_RUNTIME, Finding};
 use crate::github::GitHubClient;
-use crate::workflow;
+use crate::workflow::{self, EOL_RUNTIMES};
 
-use super::Stage;
+use super::{Phase, Stage};
 
+/// Fetches each action's `action.yml`, records its `runs.using` in
+/// `ctx.runtime`, raising [`DEPRECATED_RUNTIME`] for retired Node.js
+/// runtimes, and adds the `uses:` refs of composite actions as children.
 pub struct CompositeExpandStage {
     client: GitHubClient,
 }
@@ -43,6 +47,19 @@ impl Stage for CompositeExpandStage {
             return Ok(());
         };
 
+        let runtime = workflow::action_runtime(&yaml_content)?;
+        if let Some(runtime) = runtime.as_deref().filter(|r| EOL_RUNTIMES.contains(r)) {
+            let message = format!(
+                "{} runs on {runtime}, which GitHub Actions has retired",
+                ctx.action
+            );
+            ctx.findings.push(
+                Finding::new(&DEPRECATED_RUNTIME, message)
+                    .with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.runtime = runtime;
+
         if let Some(children) = workflow::parse_composite_action(&yaml_content)? {
             debug!(action = %ctx.action, count = children.len(), "discovered composite action children");
             ctx.children.extend(children);
@@ -54,4 +71,8 @@ impl Stage for CompositeExpandStage {
     fn name(&self) -> &'static str {
         "CompositeExpand"
     }
+
+    fn phase(&self) -> Phase {
+        Phase::Expand
+    }
 }
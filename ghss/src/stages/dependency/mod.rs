@@ -4,16 +4,18 @@ mod npm;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument, warn};
 
 use super::Ecosystem;
 use super::Stage;
-use crate::advisory::{Advisory, deduplicate_advisories};
+use crate::advisory::{Advisory, AdvisoryFilter, deduplicate_advisories};
 use crate::context::AuditContext;
 use crate::github::GitHubClient;
-use crate::providers::PackageAdvisoryProvider;
+use crate::overrides::{NotAffected, Overrides};
+use crate::providers::{PackageAdvisoryProvider, PackageQuery};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DependencyReport {
@@ -21,16 +23,126 @@ pub struct DependencyReport {
     pub version: String,
     pub ecosystem: Ecosystem,
     pub advisories: Vec<Advisory>,
+    /// Registry age and maintenance signals. Only fetched for npm packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<PackageMaintenance>,
+    /// Advisories an override claims don't apply. Not counted as findings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_affected: Vec<NotAffected>,
+}
+
+/// Advisories on one package that share a fix version.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FixGroup<'a> {
+    /// `None` for an advisory with no recorded fix; such advisories are never grouped.
+    pub fix_version: Option<&'a str>,
+    pub advisories: Vec<&'a Advisory>,
+}
+
+impl DependencyReport {
+    /// Group advisories by the version that fixes them, in first-seen order.
+    pub fn fix_groups(&self) -> Vec<FixGroup<'_>> {
+        let mut groups: Vec<FixGroup<'_>> = Vec::new();
+        for adv in &self.advisories {
+            let fix_version = adv.fixed_version.as_deref();
+            match groups
+                .iter_mut()
+                .find(|g| fix_version.is_some() && g.fix_version == fix_version)
+            {
+                Some(group) => group.advisories.push(adv),
+                None => groups.push(FixGroup {
+                    fix_version,
+                    advisories: vec![adv],
+                }),
+            }
+        }
+        groups
+    }
+}
+
+/// Registry metadata used to tell "bump the patch" apart from "migrate off an
+/// abandoned package" when remediating a vulnerable dependency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageMaintenance {
+    pub latest_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_release: Option<DateTime<Utc>>,
+    /// Major version targeted by the installed range, if it could be determined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_major: Option<u64>,
+    /// Newest stable release on the installed major line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_in_major: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_in_major_release: Option<DateTime<Utc>>,
+    /// Whether the installed major line is the latest one or released within
+    /// the last year. `None` when the installed range can't be mapped to a line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub major_maintained: Option<bool>,
+    /// Deprecation message on the newest release of the installed major line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+}
+
+impl std::fmt::Display for PackageMaintenance {
+    /// e.g. `latest 5.1.0 (2025-06-01); 4.x unmaintained, newest 4.17.21 (2021-02-20)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "latest {}", self.latest_version)?;
+        if let Some(released) = self.latest_release {
+            write!(f, " ({})", released.format("%Y-%m-%d"))?;
+        }
+        if let (Some(major), Some(maintained), Some(newest)) = (
+            self.installed_major,
+            self.major_maintained,
+            &self.latest_in_major,
+        ) {
+            let status = if maintained {
+                "maintained"
+            } else {
+                "unmaintained"
+            };
+            write!(f, "; {major}.x {status}, newest {newest}")?;
+            if let Some(released) = self.latest_in_major_release {
+                write!(f, " ({})", released.format("%Y-%m-%d"))?;
+            }
+        }
+        if let Some(msg) = &self.deprecated {
+            write!(f, "; deprecated: {msg}")?;
+        }
+        Ok(())
+    }
 }
 
 pub struct DependencyStage {
     client: GitHubClient,
     providers: Vec<Arc<dyn PackageAdvisoryProvider>>,
+    npm_registry: npm::NpmRegistry,
+    overrides: Arc<Overrides>,
+    filter: AdvisoryFilter,
 }
 
 impl DependencyStage {
     pub fn new(client: GitHubClient, providers: Vec<Arc<dyn PackageAdvisoryProvider>>) -> Self {
-        Self { client, providers }
+        Self {
+            client,
+            providers,
+            npm_registry: npm::NpmRegistry::new(),
+            overrides: Arc::default(),
+            filter: AdvisoryFilter::default(),
+        }
+    }
+
+    /// Drop advisories that `overrides` marks as not affecting a package.
+    pub fn with_overrides(mut self, overrides: Arc<Overrides>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Keep only the advisories `filter` keeps. By default withdrawn
+    /// advisories are dropped.
+    pub fn with_filter(mut self, filter: AdvisoryFilter) -> Self {
+        self.filter = filter;
+        self
     }
 }
 
@@ -46,6 +158,10 @@ impl Stage for DependencyStage {
         let mut packages: Vec<(String, String, Ecosystem)> = Vec::new();
 
         for &ecosystem in &ecosystems {
+            if ecosystem.osv_ecosystem().is_none() {
+                // No advisory database to query its packages against.
+                continue;
+            }
             let result = match ecosystem {
                 Ecosystem::Npm => {
                     npm::fetch_npm_packages(&ctx.action, &ecosystems, &self.client).await
@@ -72,22 +188,33 @@ impl Stage for DependencyStage {
             return Ok(());
         }
 
-        let mut reports = Vec::new();
+        // One batch per provider, so providers with a batch endpoint answer
+        // the whole manifest in a single request.
+        let queries: Vec<PackageQuery> = packages
+            .iter()
+            .map(|(name, version, ecosystem)| PackageQuery {
+                name: name.clone(),
+                version: version.clone(),
+                ecosystem: ecosystem
+                    .osv_ecosystem()
+                    .expect("only ecosystems OSV knows are fetched")
+                    .to_string(),
+            })
+            .collect();
+        let batches = join_all(self.providers.iter().map(|p| {
+            let queries = &queries;
+            async move { (p.name(), p.query_batch(queries).await) }
+        }))
+        .await;
 
-        for (name, version, ecosystem) in packages {
-            let osv_eco = ecosystem.osv_ecosystem().to_string();
-            let results = join_all(self.providers.iter().map(|p| {
-                let p = p.clone();
-                let pkg = name.clone();
-                let eco = osv_eco.clone();
-                async move { (p.name().to_string(), p.query(&pkg, &eco).await) }
-            }))
-            .await;
-
-            let mut advisories = Vec::new();
-            for (provider_name, result) in results {
+        let mut per_package: Vec<Vec<Advisory>> = vec![Vec::new(); packages.len()];
+        for (provider_name, results) in batches {
+            for (i, result) in results.into_iter().enumerate() {
+                let (name, _, ecosystem) = &packages[i];
                 match result {
-                    Ok(advs) => advisories.extend(advs),
+                    Ok(advs) => {
+                        per_package[i].extend(advs.into_iter().filter(|adv| self.filter.keeps(adv)))
+                    }
                     Err(e) => {
                         warn!(
                             package = %name,
@@ -99,16 +226,43 @@ impl Stage for DependencyStage {
                     }
                 }
             }
+        }
 
-            let advisories = deduplicate_advisories(advisories);
-            if !advisories.is_empty() {
-                reports.push(DependencyReport {
-                    package: name,
-                    version,
-                    ecosystem,
-                    advisories,
-                });
+        let mut reports = Vec::new();
+
+        for ((name, version, ecosystem), advisories) in packages.into_iter().zip(per_package) {
+            let (advisories, not_affected) = self.overrides.partition(
+                &name,
+                &version,
+                ecosystem,
+                deduplicate_advisories(advisories),
+            );
+            if advisories.is_empty() && not_affected.is_empty() {
+                continue;
             }
+
+            let maintenance = match ecosystem {
+                Ecosystem::Npm if !advisories.is_empty() => {
+                    match self.npm_registry.maintenance(&name, &version).await {
+                        Ok(m) => m,
+                        Err(e) => {
+                            warn!(package = %name, error = %e, "failed to fetch npm registry metadata");
+                            ctx.record_error(self.name(), format!("npm registry: {name}: {e}"));
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            reports.push(DependencyReport {
+                package: name,
+                version,
+                ecosystem,
+                advisories,
+                maintenance,
+                not_affected,
+            });
         }
 
         ctx.dependencies = reports;
@@ -124,6 +278,7 @@ impl Stage for DependencyStage {
 mod tests {
     use super::*;
     use crate::action_ref::ActionRef;
+    use crate::advisory::{Severity, fixed_in_range};
     use crate::context::AuditContext;
     use crate::stages::ScanResult;
 
@@ -138,6 +293,20 @@ mod tests {
             advisories: vec![],
             scan: None,
             dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
             errors: vec![],
         }
     }
@@ -165,4 +334,92 @@ mod tests {
         assert!(ctx.dependencies.is_empty());
         assert!(ctx.errors.is_empty());
     }
+
+    #[test]
+    fn fix_groups_collect_advisories_sharing_a_fix() {
+        let adv = |id: &str, range: Option<&str>| Advisory {
+            id: id.to_string(),
+            aliases: vec![],
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: range.and_then(fixed_in_range),
+            description: None,
+            references: vec![],
+            vex: None,
+            summary: String::new(),
+            severity: Some(Severity::High),
+            url: String::new(),
+            affected_range: range.map(String::from),
+            sources: vec!["OSV".to_string()],
+        };
+        let report = DependencyReport {
+            package: "lodash".to_string(),
+            version: "4.17.15".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![
+                adv("A", Some("< 4.17.21")),
+                adv("B", None),
+                adv("C", Some(">= 4.0.0, < 4.17.19")),
+                adv("D", Some("< 4.17.21")),
+                adv("E", None),
+            ],
+            maintenance: None,
+            not_affected: vec![],
+        };
+
+        let groups: Vec<(Option<&str>, Vec<&str>)> = report
+            .fix_groups()
+            .into_iter()
+            .map(|g| {
+                (
+                    g.fix_version,
+                    g.advisories.iter().map(|a| a.id.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (Some("4.17.21"), vec!["A", "D"]),
+                (None, vec!["B"]),
+                (Some("4.17.19"), vec!["C"]),
+                (None, vec!["E"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn maintenance_display_describes_major_line() {
+        let date = |d: &str| DateTime::parse_from_rfc3339(d).unwrap().with_timezone(&Utc);
+        let m = PackageMaintenance {
+            latest_version: "5.1.0".to_string(),
+            latest_release: Some(date("2025-06-01T00:00:00Z")),
+            installed_major: Some(4),
+            latest_in_major: Some("4.17.21".to_string()),
+            latest_in_major_release: Some(date("2021-02-20T00:00:00Z")),
+            major_maintained: Some(false),
+            deprecated: Some("use 5.x".to_string()),
+        };
+        assert_eq!(
+            m.to_string(),
+            "latest 5.1.0 (2025-06-01); 4.x unmaintained, newest 4.17.21 (2021-02-20); deprecated: use 5.x"
+        );
+
+        let m = PackageMaintenance {
+            latest_version: "1.0.0".to_string(),
+            latest_release: None,
+            installed_major: None,
+            latest_in_major: None,
+            latest_in_major_release: None,
+            major_maintained: None,
+            deprecated: None,
+        };
+        assert_eq!(m.to_string(), "latest 1.0.0");
+    }
 }
@@ -1,9 +1,20 @@
-use anyhow::{Context, Result};
+use std::collections::HashMap;
 
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::instrument;
+
+use super::PackageMaintenance;
 use crate::action_ref::ActionRef;
 use crate::github::GitHubClient;
 use crate::stages::Ecosystem;
 
+const NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+
+/// A major line counts as maintained if it published a release within this window.
+const MAINTAINED_WINDOW_DAYS: i64 = 365;
+
 /// Fetch and parse npm dependencies from an action's package.json.
 ///
 /// Returns an empty Vec if the action's ecosystems don't include npm.
@@ -45,6 +56,160 @@ fn parse_npm_dependencies(content: &str) -> Result<Vec<(String, String)>> {
         .collect())
 }
 
+// ---------------------------------------------------------------------------
+// Registry metadata
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+struct Packument {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+    /// Publish time per version, plus `created` and `modified`.
+    #[serde(default)]
+    time: HashMap<String, String>,
+    #[serde(default)]
+    versions: HashMap<String, PackumentVersion>,
+}
+
+#[derive(Deserialize)]
+struct PackumentVersion {
+    /// A deprecation message, or `false` on some older packages.
+    #[serde(default)]
+    deprecated: Option<serde_json::Value>,
+}
+
+#[derive(Clone)]
+pub(super) struct NpmRegistry {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl NpmRegistry {
+    pub(super) fn new() -> Self {
+        let base_url =
+            std::env::var("GHSS_NPM_REGISTRY_URL").unwrap_or_else(|_| NPM_REGISTRY_URL.to_string());
+        Self::with_base_url(base_url)
+    }
+
+    pub(super) fn with_base_url(base_url: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    /// Fetch registry metadata for `package` and summarize the maintenance
+    /// status of the major line that `installed` (a package.json range) targets.
+    #[instrument(skip(self))]
+    pub(super) async fn maintenance(
+        &self,
+        package: &str,
+        installed: &str,
+    ) -> Result<Option<PackageMaintenance>> {
+        // Scoped packages keep the `@` but escape the slash.
+        let url = format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            package.replace('/', "%2f")
+        );
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to query npm registry for {package}"))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            bail!("npm registry returned HTTP {status} for {package}");
+        }
+
+        let packument: Packument = response
+            .json()
+            .await
+            .context("failed to parse npm registry response")?;
+
+        Ok(summarize(&packument, installed, Utc::now()))
+    }
+}
+
+fn summarize(
+    packument: &Packument,
+    installed: &str,
+    now: DateTime<Utc>,
+) -> Option<PackageMaintenance> {
+    let latest_version = packument.dist_tags.get("latest")?.clone();
+    let latest_major = parse_version(&latest_version).map(|(major, ..)| major);
+    let installed_major = installed_major(installed);
+
+    let latest_in_major = installed_major.and_then(|major| {
+        packument
+            .versions
+            .keys()
+            .filter_map(|v| parse_version(v).map(|parsed| (parsed, v)))
+            .filter(|((m, ..), _)| *m == major)
+            .max()
+            .map(|(_, v)| v.clone())
+    });
+    let latest_in_major_release = latest_in_major
+        .as_deref()
+        .and_then(|v| release_time(packument, v));
+
+    let major_maintained = match (installed_major, &latest_in_major) {
+        (Some(major), Some(_)) => Some(
+            Some(major) == latest_major
+                || latest_in_major_release.is_some_and(|released| {
+                    now - released <= chrono::Duration::days(MAINTAINED_WINDOW_DAYS)
+                }),
+        ),
+        _ => None,
+    };
+
+    let deprecated = latest_in_major
+        .as_deref()
+        .and_then(|v| packument.versions.get(v))
+        .and_then(|v| v.deprecated.as_ref())
+        .and_then(|d| d.as_str())
+        .map(str::to_string);
+
+    Some(PackageMaintenance {
+        latest_release: release_time(packument, &latest_version),
+        latest_version,
+        installed_major,
+        latest_in_major,
+        latest_in_major_release,
+        major_maintained,
+        deprecated,
+    })
+}
+
+fn release_time(packument: &Packument, version: &str) -> Option<DateTime<Utc>> {
+    let raw = packument.time.get(version)?;
+    DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Parse a stable `major.minor.patch` version. Pre-releases yield `None`.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split('+').next()?;
+    if version.contains('-') {
+        return None;
+    }
+    let mut parts = version.split('.').map(|p| p.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next()??;
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+/// The major version targeted by a package.json range like `^4.17.20` or `~1.2`.
+fn installed_major(spec: &str) -> Option<u64> {
+    let spec = spec.trim_start_matches(|c: char| "^~=<>v ".contains(c));
+    let major = spec.split(['.', ' ']).next()?;
+    major.parse().ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,4 +285,135 @@ mod tests {
             assert!(result.unwrap().is_empty());
         });
     }
+
+    fn packument(json: serde_json::Value) -> Packument {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn lodash_like() -> Packument {
+        packument(serde_json::json!({
+            "dist-tags": {"latest": "5.1.0"},
+            "time": {
+                "created": "2012-01-01T00:00:00.000Z",
+                "4.17.20": "2020-08-13T00:00:00.000Z",
+                "4.17.21": "2021-02-20T00:00:00.000Z",
+                "5.0.0": "2025-01-01T00:00:00.000Z",
+                "5.1.0": "2025-06-01T00:00:00.000Z"
+            },
+            "versions": {
+                "4.17.20": {},
+                "4.17.21": {"deprecated": "4.x is end of life"},
+                "5.0.0": {},
+                "5.1.0-beta.1": {},
+                "5.1.0": {"deprecated": false}
+            }
+        }))
+    }
+
+    fn at(date: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn summarize_flags_abandoned_major_line() {
+        let m = summarize(&lodash_like(), "^4.17.20", at("2026-01-01T00:00:00Z")).unwrap();
+        assert_eq!(m.latest_version, "5.1.0");
+        assert_eq!(m.latest_release, Some(at("2025-06-01T00:00:00Z")));
+        assert_eq!(m.installed_major, Some(4));
+        assert_eq!(m.latest_in_major.as_deref(), Some("4.17.21"));
+        assert_eq!(m.latest_in_major_release, Some(at("2021-02-20T00:00:00Z")));
+        assert_eq!(m.major_maintained, Some(false));
+        assert_eq!(m.deprecated.as_deref(), Some("4.x is end of life"));
+    }
+
+    #[test]
+    fn summarize_treats_latest_major_as_maintained() {
+        let m = summarize(&lodash_like(), "~5.0.0", at("2030-01-01T00:00:00Z")).unwrap();
+        assert_eq!(m.latest_in_major.as_deref(), Some("5.1.0"));
+        assert_eq!(m.major_maintained, Some(true));
+        assert_eq!(m.deprecated, None);
+    }
+
+    #[test]
+    fn summarize_old_major_with_recent_release_is_maintained() {
+        let m = summarize(&lodash_like(), "4.17.20", at("2021-06-01T00:00:00Z")).unwrap();
+        assert_eq!(m.major_maintained, Some(true));
+    }
+
+    #[test]
+    fn summarize_unknown_range_leaves_maintenance_unknown() {
+        let m = summarize(
+            &lodash_like(),
+            "github:lodash/lodash",
+            at("2026-01-01T00:00:00Z"),
+        )
+        .unwrap();
+        assert_eq!(m.installed_major, None);
+        assert_eq!(m.major_maintained, None);
+    }
+
+    #[test]
+    fn summarize_without_latest_tag_is_none() {
+        let p = packument(serde_json::json!({"versions": {"1.0.0": {}}}));
+        assert!(summarize(&p, "^1.0.0", Utc::now()).is_none());
+    }
+
+    #[test]
+    fn installed_major_handles_common_ranges() {
+        assert_eq!(installed_major("^4.17.20"), Some(4));
+        assert_eq!(installed_major("~1.2"), Some(1));
+        assert_eq!(installed_major(">=2.0.0 <3"), Some(2));
+        assert_eq!(installed_major("v3.0.0"), Some(3));
+        assert_eq!(installed_major("*"), None);
+        assert_eq!(installed_major("latest"), None);
+    }
+
+    #[test]
+    fn parse_version_skips_prereleases() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3+build.5"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3-rc.1"), None);
+        assert_eq!(parse_version("1.2"), None);
+    }
+
+    #[tokio::test]
+    async fn registry_fetches_scoped_packages() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/@actions%2fcore"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dist-tags": {"latest": "1.11.1"},
+                "time": {"1.11.1": "2024-10-01T00:00:00.000Z"},
+                "versions": {"1.11.1": {}}
+            })))
+            .mount(&server)
+            .await;
+
+        let registry = NpmRegistry::with_base_url(server.uri());
+        let m = registry
+            .maintenance("@actions/core", "^1.10.0")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(m.latest_version, "1.11.1");
+        assert_eq!(m.major_maintained, Some(true));
+    }
+
+    #[tokio::test]
+    async fn registry_http_error_is_reported() {
+        use wiremock::MockServer;
+
+        let server = MockServer::start().await;
+        let registry = NpmRegistry::with_base_url(server.uri());
+        let err = registry
+            .maintenance("left-pad", "^1.0.0")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 404"));
+    }
 }
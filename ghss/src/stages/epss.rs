@@ -0,0 +1,219 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::advisory::Advisory;
+use crate::context::AuditContext;
+use crate::epss::{self, EpssClient};
+
+/// Attaches EPSS exploit probabilities to every advisory with a CVE alias,
+/// on actions and their dependencies. Batched, so the whole tree costs one
+/// request per hundred CVEs.
+pub struct EpssStage {
+    client: EpssClient,
+}
+
+impl EpssStage {
+    pub fn new(client: EpssClient) -> Self {
+        Self { client }
+    }
+}
+
+/// The action's advisories, then its dependencies'.
+fn advisories(ctx: &AuditContext) -> impl Iterator<Item = &Advisory> {
+    ctx.advisories
+        .iter()
+        .chain(ctx.dependencies.iter().flat_map(|dep| &dep.advisories))
+}
+
+fn advisories_mut(ctx: &mut AuditContext) -> impl Iterator<Item = &mut Advisory> {
+    ctx.advisories.iter_mut().chain(
+        ctx.dependencies
+            .iter_mut()
+            .flat_map(|dep| dep.advisories.iter_mut()),
+    )
+}
+
+#[async_trait]
+impl Stage for EpssStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        self.run_batch(std::slice::from_mut(ctx)).await;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "EPSS"
+    }
+
+    fn batched(&self) -> bool {
+        true
+    }
+
+    #[instrument(skip_all, fields(nodes = ctxs.len()))]
+    async fn run_batch(&self, ctxs: &mut [AuditContext]) {
+        let cves: BTreeSet<String> = ctxs
+            .iter()
+            .flat_map(advisories)
+            .flat_map(epss::cve_ids)
+            .map(String::from)
+            .collect();
+        if cves.is_empty() {
+            return;
+        }
+
+        let cves: Vec<String> = cves.into_iter().collect();
+        let scores = match self.client.scores(&cves).await {
+            Ok(scores) => scores,
+            Err(e) => {
+                warn!(error = %e, "failed to fetch EPSS scores");
+                for ctx in ctxs
+                    .iter_mut()
+                    .filter(|ctx| advisories(ctx).any(|a| epss::cve_ids(a).next().is_some()))
+                {
+                    ctx.record_error(self.name(), &e);
+                }
+                return;
+            }
+        };
+
+        for ctx in ctxs.iter_mut() {
+            for adv in advisories_mut(ctx) {
+                // An advisory covering several CVEs is as risky as the worst.
+                adv.epss = epss::cve_ids(adv)
+                    .filter_map(|cve| scores.get(cve))
+                    .max_by(|a, b| a.probability.total_cmp(&b.probability))
+                    .copied();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisory::Severity;
+    use crate::stages::Ecosystem;
+    use crate::stages::dependency::DependencyReport;
+
+    fn make_advisory(id: &str, aliases: &[&str]) -> Advisory {
+        Advisory {
+            id: id.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            cwes: vec![],
+            cvss_score: None,
+            cvss_vector: None,
+            epss: None,
+            published: None,
+            modified: None,
+            withdrawn: None,
+            applicable: None,
+            fixed_version: None,
+            description: None,
+            references: vec![],
+            vex: None,
+            summary: format!("Advisory {id}"),
+            severity: Some(Severity::High),
+            url: format!("https://example.com/{id}"),
+            affected_range: None,
+            sources: vec!["fake".to_string()],
+        }
+    }
+
+    fn make_ctx(uses: &str, advisories: Vec<Advisory>) -> AuditContext {
+        AuditContext {
+            advisories,
+            ..AuditContext::for_test(uses.parse().unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn scores_every_node_in_one_request() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/epss"))
+            .and(query_param(
+                "cve",
+                "CVE-2023-0001,CVE-2023-0002,CVE-2024-0003",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {"cve": "CVE-2023-0001", "epss": "0.10", "percentile": "0.90"},
+                    {"cve": "CVE-2023-0002", "epss": "0.50", "percentile": "0.98"},
+                    {"cve": "CVE-2024-0003", "epss": "0.01", "percentile": "0.40"}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut with_dep = make_ctx(
+            "actions/setup-node@v4",
+            vec![make_advisory("GHSA-no-cve", &[])],
+        );
+        with_dep.dependencies.push(DependencyReport {
+            package: "lodash".to_string(),
+            version: "4.17.20".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![make_advisory("CVE-2024-0003", &[])],
+            maintenance: None,
+            not_affected: vec![],
+        });
+        let mut ctxs = vec![
+            make_ctx(
+                "actions/checkout@v4",
+                vec![make_advisory(
+                    "GHSA-two-cves",
+                    &["CVE-2023-0001", "CVE-2023-0002"],
+                )],
+            ),
+            with_dep,
+        ];
+
+        let stage = EpssStage::new(EpssClient::with_base_url(format!("{}/epss", server.uri())));
+        stage.run_batch(&mut ctxs).await;
+
+        let riskiest = ctxs[0].advisories[0].epss.unwrap();
+        assert_eq!(riskiest.probability, 0.5);
+        assert!(ctxs[1].advisories[0].epss.is_none());
+        assert_eq!(
+            ctxs[1].dependencies[0].advisories[0]
+                .epss
+                .unwrap()
+                .percentile,
+            0.4
+        );
+        assert!(ctxs.iter().all(|ctx| ctx.errors.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn failure_is_recorded_on_nodes_with_cves() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let mut ctxs = vec![
+            make_ctx(
+                "actions/checkout@v4",
+                vec![make_advisory("GHSA-1", &["CVE-2023-0001"])],
+            ),
+            make_ctx("actions/cache@v4", vec![make_advisory("GHSA-2", &[])]),
+        ];
+        let stage = EpssStage::new(EpssClient::with_base_url(server.uri()));
+        stage.run_batch(&mut ctxs).await;
+
+        assert_eq!(ctxs[0].errors.len(), 1);
+        assert!(ctxs[0].errors[0].message.contains("503"));
+        assert!(ctxs[1].errors.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::github::GitHubClient;
+
+/// Where GitHub looks for a security policy, in its lookup order. An
+/// owner's `.github` repository supplies one for repositories without.
+const SECURITY_POLICY_PATHS: &[&str] = &[".github/SECURITY.md", "SECURITY.md", "docs/SECURITY.md"];
+
+/// How an action's maintainers take vulnerability reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepoHygiene {
+    /// Where the security policy was found, e.g. `SECURITY.md`, or
+    /// `SECURITY.md in acme/.github` for one inherited from the owner.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_policy: Option<String>,
+    /// Whether private vulnerability reporting is enabled. `None` when
+    /// GitHub doesn't say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_reporting: Option<bool>,
+}
+
+impl fmt::Display for RepoHygiene {
+    /// e.g. `security policy (SECURITY.md), private vulnerability reporting
+    /// enabled`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.security_policy {
+            Some(path) => write!(f, "security policy ({path})")?,
+            None => write!(f, "no security policy")?,
+        }
+        match self.private_reporting {
+            Some(true) => write!(f, ", private vulnerability reporting enabled"),
+            Some(false) => write!(f, ", private vulnerability reporting disabled"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads whether each action repository publishes a security policy and
+/// has private vulnerability reporting enabled into `ctx.repo_hygiene`.
+/// It raises no findings.
+pub struct RepoHygieneStage {
+    client: GitHubClient,
+}
+
+impl RepoHygieneStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+
+    /// The first security policy path on the default branch of `repo`.
+    async fn policy_path(&self, owner: &str, repo: &str) -> anyhow::Result<Option<&'static str>> {
+        for path in SECURITY_POLICY_PATHS {
+            if self
+                .client
+                .get_raw_content_optional(owner, repo, "HEAD", path)
+                .await?
+                .is_some()
+            {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn hygiene(&self, action: &ActionRef) -> anyhow::Result<RepoHygiene> {
+        let owner = &action.owner;
+        let security_policy = match self.policy_path(owner, &action.repo).await? {
+            Some(path) => Some(path.to_string()),
+            None => self
+                .policy_path(owner, ".github")
+                .await?
+                .map(|path| format!("{path} in {owner}/.github")),
+        };
+        let url = format!(
+            "{}/repos/{owner}/{}/private-vulnerability-reporting",
+            self.client.api_base_url(),
+            action.repo
+        );
+        let private_reporting = self
+            .client
+            .api_get_optional(&url)
+            .await?
+            .and_then(|json| json.get("enabled").and_then(Value::as_bool));
+        Ok(RepoHygiene {
+            security_policy,
+            private_reporting,
+        })
+    }
+}
+
+#[async_trait]
+impl Stage for RepoHygieneStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        match self.hygiene(&ctx.action).await {
+            Ok(hygiene) => ctx.repo_hygiene = Some(hygiene),
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to check security policy");
+                ctx.record_error(self.name(), &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "RepoHygiene"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_policy_and_private_reporting() {
+        let mut hygiene = RepoHygiene {
+            security_policy: Some("SECURITY.md in acme/.github".to_string()),
+            private_reporting: Some(true),
+        };
+        assert_eq!(
+            hygiene.to_string(),
+            "security policy (SECURITY.md in acme/.github), private vulnerability reporting \
+             enabled"
+        );
+        hygiene.security_policy = None;
+        hygiene.private_reporting = None;
+        assert_eq!(hygiene.to_string(), "no security policy");
+    }
+}
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::finding::{DENIED_LICENSE, Finding};
+use crate::github::GitHubClient;
+
+/// SPDX's term for a repository with no license file.
+const NO_LICENSE: &str = "NONE";
+
+/// True if `pattern` denies `license`: the same identifier, or a family
+/// prefix of it (`AGPL` denies `AGPL-3.0-only`, but not `LGPL-2.1`).
+/// Case is ignored.
+fn denies(pattern: &str, license: &str) -> bool {
+    let (pattern, license) = (pattern.to_ascii_lowercase(), license.to_ascii_lowercase());
+    license == pattern || license.starts_with(&format!("{pattern}-"))
+}
+
+/// Reads each action repository's license from GitHub's license detection
+/// into `ctx.license` as an SPDX identifier, and raises [`DENIED_LICENSE`]
+/// for licenses on the denylist.
+pub struct LicenseStage {
+    client: GitHubClient,
+    denylist: Vec<String>,
+}
+
+impl LicenseStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self {
+            client,
+            denylist: Vec::new(),
+        }
+    }
+
+    /// SPDX identifiers or family prefixes, e.g. `AGPL` or `SSPL-1.0`.
+    pub fn with_denylist(mut self, denylist: Vec<String>) -> Self {
+        self.denylist = denylist;
+        self
+    }
+
+    /// The SPDX identifier GitHub detected: `NOASSERTION` for a license it
+    /// can't identify, `NONE` without a license file.
+    async fn license(&self, action: &ActionRef) -> anyhow::Result<String> {
+        let url = format!(
+            "{}/repos/{}/{}/license",
+            self.client.api_base_url(),
+            action.owner,
+            action.repo
+        );
+        let Some(json) = self.client.api_get_optional(&url).await? else {
+            return Ok(NO_LICENSE.to_string());
+        };
+        Ok(json
+            .get("license")
+            .and_then(|l| l.get("spdx_id"))
+            .and_then(Value::as_str)
+            .unwrap_or("NOASSERTION")
+            .to_string())
+    }
+}
+
+#[async_trait]
+impl Stage for LicenseStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let license = match self.license(&ctx.action).await {
+            Ok(license) => license,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to fetch license");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        if let Some(pattern) = self.denylist.iter().find(|p| denies(p, &license)) {
+            ctx.findings.push(
+                Finding::new(
+                    &DENIED_LICENSE,
+                    format!(
+                        "{} is licensed under {license}, which matches the denied license \
+                         `{pattern}`",
+                        ctx.action
+                    ),
+                )
+                .with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.license = Some(license);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "License"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denylist_matches_identifiers_and_families() {
+        assert!(denies("AGPL", "AGPL-3.0-only"));
+        assert!(denies("agpl-3.0-or-later", "AGPL-3.0-or-later"));
+        assert!(denies("GPL", "GPL-2.0"));
+        assert!(!denies("GPL", "LGPL-2.1"));
+        assert!(!denies("GPL", "AGPL-3.0"));
+        assert!(denies("NONE", NO_LICENSE));
+    }
+}
@@ -1,24 +1,87 @@
 pub mod advisory;
 pub mod composite;
 pub mod dependency;
+pub mod epss;
+pub mod hygiene;
+pub mod license;
+pub mod ownership;
+pub mod project;
+pub mod protection;
+pub mod provenance;
+pub mod recommend;
+pub mod releases;
+pub mod repository;
+pub mod reputation;
 pub mod resolve;
 pub mod scan;
+pub mod scorecard;
+pub mod signature;
+pub mod tags;
+pub mod updates;
 pub mod workflow_expand;
 
 use async_trait::async_trait;
 
 use crate::context::AuditContext;
 
+/// Which walker pass runs a stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Resolves a node and discovers its children. Runs as the walker
+    /// reaches each node, so the tree can grow.
+    Expand,
+    /// Everything else. Runs once per unique node after the whole tree has
+    /// been expanded.
+    Enrich,
+}
+
 #[async_trait]
 pub trait Stage: Send + Sync {
     async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()>;
     fn name(&self) -> &'static str;
+
+    fn phase(&self) -> Phase {
+        Phase::Enrich
+    }
+
+    /// True if the walker should hand every unique node to `run_batch` at
+    /// once instead of calling `run` per node. For stages backed by bulk
+    /// APIs.
+    fn batched(&self) -> bool {
+        false
+    }
+
+    /// Run over all unique nodes of the enrichment pass. Failures are
+    /// recorded on the affected node's context. The default runs each node
+    /// in turn.
+    async fn run_batch(&self, ctxs: &mut [AuditContext]) {
+        for ctx in ctxs.iter_mut() {
+            if let Err(e) = self.run(ctx).await {
+                ctx.record_error(self.name(), &e);
+            }
+        }
+    }
 }
 
 pub use advisory::AdvisoryStage;
 pub use composite::CompositeExpandStage;
 pub use dependency::DependencyReport;
 pub use dependency::DependencyStage;
+pub use epss::EpssStage;
+pub use hygiene::RepoHygieneStage;
+pub use license::LicenseStage;
+pub use ownership::CommitOwnershipStage;
+pub use project::ProjectInfoStage;
+pub use protection::TagProtectionStage;
+pub use provenance::ProvenanceStage;
+pub use recommend::RecommendStage;
+pub use releases::ReleaseStage;
+pub use repository::RepoMetadataStage;
+pub use reputation::ReputationStage;
 pub use resolve::RefResolveStage;
 pub use scan::{Ecosystem, ScanResult, ScanStage};
+pub use scorecard::ScorecardStage;
+pub use signature::SignatureStage;
+pub use tags::TagMovementStage;
+pub use updates::UpdateHygieneStage;
 pub use workflow_expand::WorkflowExpandStage;
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use super::releases;
+use crate::action_ref::{ActionRef, RefType};
+use crate::context::AuditContext;
+use crate::finding::{Finding, IMPOSTOR_COMMIT};
+use crate::github::GitHubClient;
+
+/// Where a pinned commit was found in the action's repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Ownership {
+    /// On the default branch's history, or tagged.
+    Reachable,
+    /// Served under the repository's path but on neither its default branch
+    /// nor any tag, as fork commits are.
+    Unreachable { default_branch: String },
+    /// Not known to the repository's fork network at all.
+    Missing,
+}
+
+/// `status` of comparing the default branch with the pinned commit:
+/// `identical` or `behind` when the commit is in the branch's history.
+fn on_branch(compare: &Value) -> bool {
+    matches!(
+        compare.get("status").and_then(Value::as_str),
+        Some("identical" | "behind")
+    )
+}
+
+async fn ownership(action: &ActionRef, client: &GitHubClient) -> anyhow::Result<Ownership> {
+    let api = client.api_base_url();
+    let (owner, repo, sha) = (&action.owner, &action.repo, &action.git_ref);
+    let metadata = client
+        .api_get(&format!("{api}/repos/{owner}/{repo}"))
+        .await?;
+    let default_branch = metadata
+        .get("default_branch")
+        .and_then(Value::as_str)
+        .unwrap_or("main")
+        .to_string();
+
+    let compare_url = format!("{api}/repos/{owner}/{repo}/compare/{default_branch}...{sha}");
+    let Some(compare) = client.api_get_optional(&compare_url).await? else {
+        return Ok(Ownership::Missing);
+    };
+    if on_branch(&compare) {
+        return Ok(Ownership::Reachable);
+    }
+
+    // Release tags often sit on maintenance branches off the default one.
+    let tagged = releases::list(client, action, "tags", |t| {
+        t.get("commit")?.get("sha")?.as_str()
+    })
+    .await?;
+    if tagged
+        .iter()
+        .any(|tag_sha| tag_sha.eq_ignore_ascii_case(sha))
+    {
+        return Ok(Ownership::Reachable);
+    }
+    Ok(Ownership::Unreachable { default_branch })
+}
+
+/// For actions pinned to a commit SHA, checks that the commit belongs to the
+/// named repository, on its default branch or a tag, and raises
+/// [`IMPOSTOR_COMMIT`] otherwise.
+pub struct CommitOwnershipStage {
+    client: GitHubClient,
+}
+
+impl CommitOwnershipStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Stage for CommitOwnershipStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        if ctx.action.ref_type != RefType::Sha {
+            return Ok(());
+        }
+        let repo = format!("{}/{}", ctx.action.owner, ctx.action.repo);
+        let message = match ownership(&ctx.action, &self.client).await {
+            Ok(Ownership::Reachable) => return Ok(()),
+            Ok(Ownership::Unreachable { default_branch }) => format!(
+                "{} pins a commit that is not on {repo}'s default branch `{default_branch}` \
+                 or any of its tags; it may belong to a fork",
+                ctx.action
+            ),
+            Ok(Ownership::Missing) => {
+                format!("{} pins a commit that {repo} does not contain", ctx.action)
+            }
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to check commit ownership");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        ctx.findings.push(
+            Finding::new(&IMPOSTOR_COMMIT, message).with_location(ctx.action.location.clone()),
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "CommitOwnership"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_ancestors_of_the_branch_are_on_it() {
+        for (status, expected) in [
+            ("identical", true),
+            ("behind", true),
+            ("ahead", false),
+            ("diverged", false),
+        ] {
+            let compare = serde_json::json!({"status": status});
+            assert_eq!(on_branch(&compare), expected, "{status}");
+        }
+        assert!(!on_branch(&serde_json::json!({})));
+    }
+}
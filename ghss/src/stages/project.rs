@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::context::AuditContext;
+use crate::providers::depsdev::DepsDevClient;
+
+/// Looks up the action repository's license and OpenSSF Scorecard on deps.dev.
+pub struct ProjectInfoStage {
+    client: DepsDevClient,
+}
+
+impl ProjectInfoStage {
+    pub fn new(client: DepsDevClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Stage for ProjectInfoStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        match self.client.project(&ctx.action).await {
+            Ok(info) => ctx.project = info,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to fetch project info");
+                ctx.record_error(self.name(), &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "ProjectInfo"
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::{ActionRef, RefType};
+use crate::context::AuditContext;
+use crate::finding::{Finding, REPLACEABLE_TAG};
+use crate::github::GitHubClient;
+use crate::rules::glob_match;
+use crate::version_range;
+
+/// Ruleset rules that together stop a tag from being moved: `update`
+/// blocks force-pushing it, `deletion` blocks deleting and recreating it.
+const PROTECTING_RULES: [&str; 2] = ["update", "deletion"];
+
+/// What stops the tag an action is pinned to from being moved to another
+/// commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagProtection {
+    /// Whether the tag's GitHub release is immutable, which locks the tag.
+    /// `None` when the tag has no release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub immutable_release: Option<bool>,
+    /// Names of active tag rulesets that block updating and deleting it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rulesets: Vec<String>,
+}
+
+impl TagProtection {
+    /// True if an immutable release or a ruleset keeps the tag in place.
+    pub fn protected(&self) -> bool {
+        self.immutable_release == Some(true) || !self.rulesets.is_empty()
+    }
+}
+
+impl fmt::Display for TagProtection {
+    /// e.g. `immutable release, ruleset Release tags` or `mutable release,
+    /// no tag ruleset`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.immutable_release {
+            Some(true) => write!(f, "immutable release")?,
+            Some(false) => write!(f, "mutable release")?,
+            None => write!(f, "no release")?,
+        }
+        match self.rulesets.as_slice() {
+            [] => write!(f, ", no tag ruleset"),
+            [name] => write!(f, ", ruleset {name}"),
+            names => write!(f, ", rulesets {}", names.join(", ")),
+        }
+    }
+}
+
+/// The `ref_name` patterns under `key` (`include` or `exclude`) of a
+/// ruleset's conditions.
+fn ref_patterns<'a>(conditions: &'a Value, key: &str) -> impl Iterator<Item = &'a str> {
+    conditions
+        .get("ref_name")
+        .and_then(|r| r.get(key))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+/// True if `ruleset` is enforced on `tag` and blocks both updating and
+/// deleting it.
+fn protects(ruleset: &Value, tag: &str) -> bool {
+    if ruleset.get("enforcement").and_then(Value::as_str) != Some("active") {
+        return false;
+    }
+    let full_ref = format!("refs/tags/{tag}");
+    let matches = |pattern: &str| pattern == "~ALL" || glob_match(pattern, &full_ref);
+    let conditions = ruleset.get("conditions").unwrap_or(&Value::Null);
+    if !ref_patterns(conditions, "include").any(matches)
+        || ref_patterns(conditions, "exclude").any(matches)
+    {
+        return false;
+    }
+    let rule_types: Vec<&str> = ruleset
+        .get("rules")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|r| r.get("type")?.as_str())
+        .collect();
+    PROTECTING_RULES
+        .iter()
+        .all(|rule| rule_types.contains(rule))
+}
+
+/// For tag-pinned actions, reads whether the tag's release is immutable and
+/// which tag rulesets protect it into `ctx.tag_protection`. Raises
+/// [`REPLACEABLE_TAG`] for release tags (`v1.2.3`, not a floating `v1`)
+/// that neither keeps in place.
+///
+/// Rulesets are read as the repository reports them; bypass lists are not
+/// considered.
+pub struct TagProtectionStage {
+    client: GitHubClient,
+}
+
+impl TagProtectionStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+
+    async fn protection(&self, action: &ActionRef) -> anyhow::Result<TagProtection> {
+        let repo_url = format!(
+            "{}/repos/{}/{}",
+            self.client.api_base_url(),
+            action.owner,
+            action.repo
+        );
+        let tag = &action.git_ref;
+        let immutable_release = self
+            .client
+            .api_get_optional(&format!("{repo_url}/releases/tags/{tag}"))
+            .await?
+            .map(|release| release.get("immutable").and_then(Value::as_bool) == Some(true));
+
+        let listing = self
+            .client
+            .api_get_optional(&format!("{repo_url}/rulesets?targets=tag&per_page=100"))
+            .await?;
+        let ids: Vec<u64> = listing
+            .as_ref()
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|r| r.get("target").and_then(Value::as_str) == Some("tag"))
+            .filter(|r| r.get("enforcement").and_then(Value::as_str) == Some("active"))
+            .filter_map(|r| r.get("id")?.as_u64())
+            .collect();
+        let mut rulesets = Vec::new();
+        for id in ids {
+            // The listing leaves out conditions and rules.
+            let ruleset = self
+                .client
+                .api_get(&format!("{repo_url}/rulesets/{id}"))
+                .await?;
+            if protects(&ruleset, tag) {
+                let name = ruleset.get("name").and_then(Value::as_str);
+                rulesets.push(name.map_or_else(|| id.to_string(), str::to_string));
+            }
+        }
+        Ok(TagProtection {
+            immutable_release,
+            rulesets,
+        })
+    }
+}
+
+#[async_trait]
+impl Stage for TagProtectionStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        if ctx.action.ref_type != RefType::Tag {
+            return Ok(());
+        }
+        let protection = match self.protection(&ctx.action).await {
+            Ok(protection) => protection,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to read tag protection");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        let release_tag = version_range::parse_release(&ctx.action.git_ref).is_some();
+        if release_tag && !protection.protected() {
+            ctx.findings.push(
+                Finding::new(
+                    &REPLACEABLE_TAG,
+                    format!(
+                        "{}'s tag can be moved to another commit: {protection}",
+                        ctx.action
+                    ),
+                )
+                .with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.tag_protection = Some(protection);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "TagProtection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rulesets_must_cover_the_tag_and_block_updates_and_deletion() {
+        let ruleset = |enforcement: &str, include: &str, rules: &[&str]| {
+            let rules: Vec<Value> = rules
+                .iter()
+                .map(|r| serde_json::json!({"type": r}))
+                .collect();
+            serde_json::json!({
+                "name": "Release tags",
+                "target": "tag",
+                "enforcement": enforcement,
+                "conditions": {"ref_name": {"include": [include], "exclude": ["refs/tags/v0.*"]}},
+                "rules": rules
+            })
+        };
+        let both = ["update", "deletion"];
+        assert!(protects(
+            &ruleset("active", "refs/tags/v*", &both),
+            "v1.2.0"
+        ));
+        assert!(protects(&ruleset("active", "~ALL", &both), "v1.2.0"));
+        assert!(!protects(&ruleset("active", "~ALL", &both), "v0.9.0"));
+        assert!(!protects(&ruleset("evaluate", "~ALL", &both), "v1.2.0"));
+        assert!(!protects(
+            &ruleset("active", "refs/tags/release-*", &both),
+            "v1.2.0"
+        ));
+        assert!(!protects(&ruleset("active", "~ALL", &["update"]), "v1.2.0"));
+    }
+
+    #[test]
+    fn displays_release_and_rulesets() {
+        let mut protection = TagProtection {
+            immutable_release: Some(false),
+            rulesets: vec![],
+        };
+        assert!(!protection.protected());
+        assert_eq!(protection.to_string(), "mutable release, no tag ruleset");
+        protection.immutable_release = None;
+        protection.rulesets = vec!["Release tags".to_string()];
+        assert!(protection.protected());
+        assert_eq!(protection.to_string(), "no release, ruleset Release tags");
+        protection.immutable_release = Some(true);
+        protection.rulesets.clear();
+        assert!(protection.protected());
+        assert_eq!(protection.to_string(), "immutable release, no tag ruleset");
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use super::releases;
+use crate::action_ref::{ActionRef, RefType};
+use crate::context::AuditContext;
+use crate::finding::{Finding, NO_PROVENANCE};
+use crate::github::GitHubClient;
+
+const SLSA_PROVENANCE: &str = "https://slsa.dev/provenance/";
+
+/// Whether the release an action is pinned to carries build provenance for
+/// the pinned commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Provenance {
+    /// Every asset of `release` has an artifact attestation with SLSA
+    /// provenance built from the action's repository at the pinned commit.
+    Verified {
+        release: String,
+        assets: usize,
+    },
+    Unverified {
+        reason: String,
+    },
+}
+
+impl fmt::Display for Provenance {
+    /// e.g. `verified (2 assets of v1.2.0)` or `unverified: no release for v4`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provenance::Verified { release, assets } => {
+                let plural = if *assets == 1 { "" } else { "s" };
+                write!(f, "verified ({assets} asset{plural} of {release})")
+            }
+            Provenance::Unverified { reason } => write!(f, "unverified: {reason}"),
+        }
+    }
+}
+
+/// The in-toto statement signed in an attestation's DSSE envelope.
+fn statement(attestation: &Value) -> Option<Value> {
+    let payload = attestation
+        .get("bundle")?
+        .get("dsseEnvelope")?
+        .get("payload")?
+        .as_str()?;
+    serde_json::from_slice(&STANDARD.decode(payload).ok()?).ok()
+}
+
+/// Check that `statement` is SLSA provenance for the asset with `digest`
+/// (`sha256:...`), built from `repo` (`owner/repo`) at `commit`. On
+/// failure, says what doesn't match.
+fn check_statement(
+    statement: &Value,
+    asset: &str,
+    digest: &str,
+    repo: &str,
+    commit: &str,
+) -> Result<(), String> {
+    let predicate_type = statement
+        .get("predicateType")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !predicate_type.starts_with(SLSA_PROVENANCE) {
+        return Err(format!(
+            "the attestation for {asset} is not SLSA provenance"
+        ));
+    }
+    let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+    let subjects = statement.get("subject").and_then(Value::as_array);
+    let covers_asset = subjects.into_iter().flatten().any(|s| {
+        s.get("digest")
+            .and_then(|d| d.get("sha256"))
+            .and_then(Value::as_str)
+            .is_some_and(|d| d.eq_ignore_ascii_case(hex))
+    });
+    if !covers_asset {
+        return Err(format!(
+            "the provenance for {asset} does not cover its digest"
+        ));
+    }
+    let source_prefix = format!("git+https://github.com/{}@", repo.to_ascii_lowercase());
+    let sources: Vec<(&str, &str)> = statement
+        .pointer("/predicate/buildDefinition/resolvedDependencies")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|dep| {
+            let uri = dep.get("uri")?.as_str()?;
+            let sha = dep.get("digest")?.get("gitCommit")?.as_str()?;
+            Some((uri, sha))
+        })
+        .collect();
+    if sources.iter().any(|(uri, sha)| {
+        uri.to_ascii_lowercase().starts_with(&source_prefix) && sha.eq_ignore_ascii_case(commit)
+    }) {
+        return Ok(());
+    }
+    let built_from = match sources.first() {
+        Some((uri, sha)) => format!("{uri} at {}", &sha[..sha.len().min(7)]),
+        None => "an unnamed source".to_string(),
+    };
+    Err(format!(
+        "the provenance for {asset} was built from {built_from}, not {repo} at {}",
+        &commit[..commit.len().min(7)]
+    ))
+}
+
+/// For each action, verifies the artifact attestations of the release it is
+/// pinned to and raises [`NO_PROVENANCE`] when there is no SLSA provenance
+/// for the pinned commit. A SHA pin is checked against the release of a tag
+/// pointing at it.
+///
+/// The attestation's contents are checked, not its Sigstore signature.
+pub struct ProvenanceStage {
+    client: GitHubClient,
+}
+
+impl ProvenanceStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+
+    /// The release tag to check: the pinned tag, or for a SHA pin, a tag
+    /// pointing at it, preferring exact releases.
+    async fn tag(&self, action: &ActionRef) -> anyhow::Result<Option<String>> {
+        if action.ref_type != RefType::Sha {
+            return Ok(Some(action.git_ref.clone()));
+        }
+        let sha = &action.git_ref;
+        let tags = releases::list(&self.client, action, "tags", |t| {
+            let tagged = t.get("commit")?.get("sha")?.as_str()?;
+            t.get("name")?
+                .as_str()
+                .filter(|_| tagged.eq_ignore_ascii_case(sha))
+        })
+        .await?;
+        let release = tags
+            .iter()
+            .find(|tag| crate::version_range::parse_release(tag).is_some());
+        Ok(release.or(tags.first()).cloned())
+    }
+
+    async fn provenance(&self, action: &ActionRef, commit: &str) -> anyhow::Result<Provenance> {
+        let unverified = |reason: String| Ok(Provenance::Unverified { reason });
+        let Some(tag) = self.tag(action).await? else {
+            return unverified("no tag points at the pinned commit".to_string());
+        };
+        let repo = format!("{}/{}", action.owner, action.repo);
+        let repo_url = format!("{}/repos/{repo}", self.client.api_base_url());
+        let Some(release) = self
+            .client
+            .api_get_optional(&format!("{repo_url}/releases/tags/{tag}"))
+            .await?
+        else {
+            return unverified(format!("no release for {tag}"));
+        };
+        let assets: Vec<(&str, &str)> = release
+            .get("assets")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|a| Some((a.get("name")?.as_str()?, a.get("digest")?.as_str()?)))
+            .collect();
+        if assets.is_empty() {
+            return unverified(format!("release {tag} has no assets to attest"));
+        }
+        for (asset, digest) in &assets {
+            let attestations = self
+                .client
+                .api_get_optional(&format!("{repo_url}/attestations/{digest}"))
+                .await?;
+            let statements: Vec<Value> = attestations
+                .as_ref()
+                .and_then(|a| a.get("attestations"))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(statement)
+                .collect();
+            let mut result = Err(format!("no attestation for {asset}"));
+            for statement in &statements {
+                result = check_statement(statement, asset, digest, &repo, commit);
+                if result.is_ok() {
+                    break;
+                }
+            }
+            if let Err(reason) = result {
+                return unverified(reason);
+            }
+        }
+        Ok(Provenance::Verified {
+            release: tag,
+            assets: assets.len(),
+        })
+    }
+}
+
+#[async_trait]
+impl Stage for ProvenanceStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let Some(commit) = ctx.resolved_ref.clone() else {
+            return Ok(());
+        };
+        let provenance = match self.provenance(&ctx.action, &commit).await {
+            Ok(provenance) => provenance,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to verify provenance");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        if let Provenance::Unverified { reason } = &provenance {
+            ctx.findings.push(
+                Finding::new(
+                    &NO_PROVENANCE,
+                    format!(
+                        "{} has no verifiable build provenance: {reason}",
+                        ctx.action
+                    ),
+                )
+                .with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.provenance = Some(provenance);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Provenance"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMIT: &str = "11bd71901bbe5b1630ceea73d27597364c9af683";
+    const DIGEST: &str = "sha256:5e8f0a1b";
+
+    fn provenance(predicate_type: &str, digest: &str, source: &str) -> Value {
+        serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{"name": "dist.tgz", "digest": {"sha256": digest}}],
+            "predicateType": predicate_type,
+            "predicate": {"buildDefinition": {"resolvedDependencies": [
+                {"uri": source, "digest": {"gitCommit": COMMIT}}
+            ]}}
+        })
+    }
+
+    #[test]
+    fn statement_decodes_the_dsse_payload() {
+        let statement = provenance(SLSA_PROVENANCE, "5e8f0a1b", "");
+        let attestation = serde_json::json!({"bundle": {"dsseEnvelope": {
+            "payload": STANDARD.encode(statement.to_string()),
+            "payloadType": "application/vnd.in-toto+json"
+        }}});
+        assert_eq!(super::statement(&attestation), Some(statement));
+        assert_eq!(super::statement(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn provenance_must_cover_the_asset_and_name_the_pinned_source() {
+        let check = |statement: &Value, commit: &str| {
+            check_statement(statement, "dist.tgz", DIGEST, "Org/Action", commit)
+        };
+        let slsa = "https://slsa.dev/provenance/v1";
+        let source = "git+https://github.com/org/action@refs/tags/v1.2.0";
+        assert_eq!(check(&provenance(slsa, "5E8F0A1B", source), COMMIT), Ok(()));
+
+        assert_eq!(
+            check(
+                &provenance("https://spdx.dev/Document/v2.3", "5e8f0a1b", source),
+                COMMIT
+            ),
+            Err("the attestation for dist.tgz is not SLSA provenance".to_string())
+        );
+        assert_eq!(
+            check(&provenance(slsa, "00000000", source), COMMIT),
+            Err("the provenance for dist.tgz does not cover its digest".to_string())
+        );
+        let fork = "git+https://github.com/someone/action@refs/heads/main";
+        assert_eq!(
+            check(&provenance(slsa, "5e8f0a1b", fork), COMMIT),
+            Err("the provenance for dist.tgz was built from \
+                 git+https://github.com/someone/action@refs/heads/main at 11bd719, not \
+                 Org/Action at 11bd719"
+                .to_string())
+        );
+        assert!(check(&provenance(slsa, "5e8f0a1b", source), "2222222").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use async_trait::async_trait;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, warn};
+
+use super::Stage;
+use super::releases;
+use crate::action_ref::{ActionRef, RefType};
+use crate::advisory::Advisory;
+use crate::context::AuditContext;
+use crate::github::GitHubClient;
+use crate::version_range;
+
+/// The release an action should be pinned to, and the commit it points at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecommendedRef {
+    pub tag: String,
+    pub sha: String,
+}
+
+impl fmt::Display for RecommendedRef {
+    /// The pin as written in `uses:`, e.g. `11bd7190... # v4.2.2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} # {}", self.sha, self.tag)
+    }
+}
+
+/// True if the action needs a new pin: it isn't pinned to a commit, or an
+/// advisory applies to what it is pinned to.
+fn needs_recommendation(ctx: &AuditContext) -> bool {
+    ctx.action.ref_type != RefType::Sha || ctx.advisories.iter().any(|a| !a.is_suppressed())
+}
+
+/// The newest release no advisory's `affected_range` covers. Advisories
+/// without a range can't rule a release out, so they are ignored.
+fn latest_safe<'a>(
+    releases: &'a BTreeMap<Version, String>,
+    advisories: &[Advisory],
+) -> Option<&'a str> {
+    releases
+        .values()
+        .rev()
+        .find(|tag| {
+            !advisories.iter().any(|a| {
+                a.affected_range
+                    .as_deref()
+                    .and_then(|range| version_range::applies(range, tag))
+                    == Some(true)
+            })
+        })
+        .map(String::as_str)
+}
+
+/// For each action with an unpinned ref or an applicable advisory, finds the
+/// latest release with no known advisory and the commit SHA to pin it by.
+pub struct RecommendStage {
+    client: GitHubClient,
+}
+
+impl RecommendStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+
+    async fn recommend(&self, ctx: &AuditContext) -> anyhow::Result<Option<RecommendedRef>> {
+        let tags = releases::release_tags(&ctx.action, &self.client).await?;
+        let releases = releases::by_version(tags);
+        let Some(tag) = latest_safe(&releases, &ctx.advisories) else {
+            return Ok(None);
+        };
+        let target: ActionRef =
+            format!("{}/{}@{tag}", ctx.action.owner, ctx.action.repo).parse()?;
+        let sha = self.client.resolve_ref(&target).await?;
+        Ok(Some(RecommendedRef {
+            tag: tag.to_string(),
+            sha,
+        }))
+    }
+}
+
+#[async_trait]
+impl Stage for RecommendStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        if !needs_recommendation(ctx) {
+            return Ok(());
+        }
+        match self.recommend(ctx).await {
+            Ok(recommended) => ctx.recommended_ref = recommended,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to find a recommended ref");
+                ctx.record_error(self.name(), &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Recommend"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(range: Option<&str>) -> Advisory {
+        serde_json::from_value(serde_json::json!({
+            "id": "GHSA-test",
+            "summary": "",
+            "severity": "high",
+            "url": "",
+            "affected_range": range,
+            "sources": ["GHSA"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn skips_releases_an_advisory_covers() {
+        let releases = releases::by_version(["v3.0.1", "v3.0.0", "v2.9.0", "v3"].map(String::from));
+        assert_eq!(latest_safe(&releases, &[]), Some("v3.0.1"));
+        let advisories = [advisory(Some(">= 3.0.0")), advisory(None)];
+        assert_eq!(latest_safe(&releases, &advisories), Some("v2.9.0"));
+        assert_eq!(latest_safe(&releases, &[advisory(Some("< 4.0.0"))]), None);
+    }
+
+    #[test]
+    fn only_sha_pins_without_advisories_are_left_alone() {
+        let mut ctx = AuditContext::for_test(
+            "actions/checkout@11bd71901bbe5b1630ceea73d27597364c9af683"
+                .parse()
+                .unwrap(),
+        );
+        assert!(!needs_recommendation(&ctx));
+        ctx.advisories.push(advisory(Some("< 4.2.2")));
+        assert!(needs_recommendation(&ctx));
+        ctx.action = "actions/checkout@v4".parse().unwrap();
+        ctx.advisories.clear();
+        assert!(needs_recommendation(&ctx));
+
+        let pin = RecommendedRef {
+            tag: "v4.2.2".to_string(),
+            sha: "11bd71901bbe5b1630ceea73d27597364c9af683".to_string(),
+        };
+        assert_eq!(
+            pin.to_string(),
+            "11bd71901bbe5b1630ceea73d27597364c9af683 # v4.2.2"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::finding::{Finding, OUTDATED_MAJOR};
+use crate::github::GitHubClient;
+use crate::version_range;
+use crate::workflow::{self, EOL_RUNTIMES};
+
+/// Releases and tags are listed newest first, so a few pages reach the
+/// latest release and everything a recent pin is behind.
+const PER_PAGE: usize = 100;
+const MAX_PAGES: usize = 3;
+
+/// How many majors behind the latest release a pin must be to raise
+/// [`OUTDATED_MAJOR`]. One major behind is routine between updates.
+const OUTDATED_MAJORS: u64 = 2;
+
+/// How far a pinned action version trails the repository's latest release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseLag {
+    /// The latest release's tag, as named in the repository.
+    pub latest: String,
+    /// Releases newer than the newest one the pinned ref can point to.
+    pub behind: usize,
+    pub majors_behind: u64,
+    /// The newest release on the pinned major line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub major_latest: Option<String>,
+    /// `runs.using` of `major_latest`, looked up when a newer major exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+}
+
+impl ReleaseLag {
+    /// Compare `pinned` (e.g. `4` or `4.1.0`) against release `tags`.
+    /// `None` if either can't be parsed as a version.
+    fn new(pinned: &str, tags: impl IntoIterator<Item = String>) -> Option<Self> {
+        let pinned = version_range::parse_pinned(pinned)?;
+        let releases = by_version(tags);
+        let (latest, latest_tag) = releases.last_key_value()?;
+        let current = releases
+            .keys()
+            .rfind(|v| pinned.matches(v))
+            .unwrap_or(pinned.floor());
+        let next_major = Version::new(current.major + 1, 0, 0);
+        let major_latest = releases
+            .range(current..&next_major)
+            .next_back()
+            .map(|(_, tag)| tag.clone());
+        Some(Self {
+            latest: latest_tag.clone(),
+            behind: releases
+                .range(current..)
+                .filter(|(v, _)| *v > current)
+                .count(),
+            majors_behind: latest.major.saturating_sub(current.major),
+            major_latest,
+            runtime: None,
+        })
+    }
+
+    /// True if the pinned major's newest release runs on a retired runtime,
+    /// so the major line will never run on a supported one.
+    pub fn eol_runtime(&self) -> bool {
+        self.runtime
+            .as_deref()
+            .is_some_and(|r| EOL_RUNTIMES.contains(&r))
+    }
+
+    /// An [`OUTDATED_MAJOR`] message, if the pin is several majors behind or
+    /// its major runs on a retired runtime.
+    fn outdated_message(&self, action: &ActionRef) -> Option<String> {
+        let mut message = if self.majors_behind >= OUTDATED_MAJORS {
+            format!(
+                "{action} is {} major versions behind the latest release {}",
+                self.majors_behind, self.latest
+            )
+        } else if self.eol_runtime() {
+            format!("{action} is behind the latest release {}", self.latest)
+        } else {
+            return None;
+        };
+        if let (true, Some(tag), Some(runtime)) =
+            (self.eol_runtime(), &self.major_latest, &self.runtime)
+        {
+            message.push_str(&format!(
+                "; its newest release on this major, {tag}, runs on {runtime}, which GitHub \
+                 Actions no longer supports"
+            ));
+        }
+        Some(message)
+    }
+}
+
+/// Release `tags` keyed by version, so `v1.0.0` and `1.0.0` count once
+/// under the name listed first. Tags that aren't full releases are dropped.
+pub(crate) fn by_version(tags: impl IntoIterator<Item = String>) -> BTreeMap<Version, String> {
+    let mut releases = BTreeMap::new();
+    for tag in tags {
+        if let Some(version) = version_range::parse_release(&tag) {
+            releases.entry(version).or_insert(tag);
+        }
+    }
+    releases
+}
+
+impl fmt::Display for ReleaseLag {
+    /// e.g. `3 releases behind v4.2.0 (1 major)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.behind == 0 {
+            return write!(f, "up to date with {}", self.latest);
+        }
+        let plural = |n, word| format!("{n} {word}{}", if n == 1 { "" } else { "s" });
+        write!(
+            f,
+            "{} behind {}",
+            plural(self.behind as u64, "release"),
+            self.latest
+        )?;
+        if self.majors_behind > 0 {
+            write!(f, " ({})", plural(self.majors_behind, "major"))?;
+        }
+        if let (true, Some(runtime)) = (self.eol_runtime(), &self.runtime) {
+            write!(f, "; this major runs on {runtime}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Names of the repository's published releases, or of its tags when it
+/// publishes none. Drafts and prereleases are skipped.
+#[instrument(skip(client), fields(action = %action))]
+pub(crate) async fn release_tags(
+    action: &ActionRef,
+    client: &GitHubClient,
+) -> anyhow::Result<Vec<String>> {
+    let releases = list(client, action, "releases", |r| {
+        let published = r.get("draft") != Some(&Value::Bool(true))
+            && r.get("prerelease") != Some(&Value::Bool(true));
+        r.get("tag_name")
+            .and_then(Value::as_str)
+            .filter(|_| published)
+    })
+    .await?;
+    if !releases.is_empty() {
+        return Ok(releases);
+    }
+    list(client, action, "tags", |t| {
+        t.get("name").and_then(Value::as_str)
+    })
+    .await
+}
+
+/// Up to [`MAX_PAGES`] of a repository listing, mapped through `name`.
+pub(crate) async fn list(
+    client: &GitHubClient,
+    action: &ActionRef,
+    listing: &str,
+    name: impl Fn(&Value) -> Option<&str>,
+) -> anyhow::Result<Vec<String>> {
+    let api = client.api_base_url();
+    let mut url = Some(format!(
+        "{api}/repos/{}/{}/{listing}?per_page={PER_PAGE}",
+        action.owner, action.repo
+    ));
+    let mut names = Vec::new();
+    for _ in 0..MAX_PAGES {
+        let Some(page_url) = url.take() else {
+            break;
+        };
+        let (json, next) = client
+            .api_get_page(&page_url)
+            .await
+            .with_context(|| format!("failed to list {listing} of {}", action.package_name()))?;
+        let items = json.as_array().map(Vec::as_slice).unwrap_or_default();
+        names.extend(items.iter().filter_map(&name).map(String::from));
+        url = next;
+    }
+    Ok(names)
+}
+
+/// `runs.using` of the action at `tag`, or `None` without an action.yml.
+#[instrument(skip(client), fields(action = %action))]
+async fn runtime_at(
+    action: &ActionRef,
+    tag: &str,
+    client: &GitHubClient,
+) -> anyhow::Result<Option<String>> {
+    for filename in ["action.yml", "action.yaml"] {
+        let path = match &action.path {
+            Some(dir) => format!("{dir}/{filename}"),
+            None => filename.to_string(),
+        };
+        if let Some(yaml) = client
+            .get_raw_content_optional(&action.owner, &action.repo, tag, &path)
+            .await?
+        {
+            return workflow::action_runtime(&yaml);
+        }
+    }
+    Ok(None)
+}
+
+/// Reports how far each tag-pinned action is behind its latest release,
+/// and raises [`OUTDATED_MAJOR`] when it is several majors behind, or when
+/// a newer major exists and the pinned one runs on a retired Node runtime.
+pub struct ReleaseStage {
+    client: GitHubClient,
+}
+
+impl ReleaseStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Stage for ReleaseStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let Some(pinned) = ctx.action.version() else {
+            return Ok(());
+        };
+        let pinned = pinned.to_string();
+        let tags = match release_tags(&ctx.action, &self.client).await {
+            Ok(tags) => tags,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to list releases");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        let Some(mut lag) = ReleaseLag::new(&pinned, tags) else {
+            return Ok(());
+        };
+        if let (true, Some(tag)) = (lag.majors_behind > 0, &lag.major_latest) {
+            match runtime_at(&ctx.action, tag, &self.client).await {
+                Ok(runtime) => lag.runtime = runtime,
+                Err(e) => {
+                    warn!(action = %ctx.action, error = %e, "failed to read action runtime");
+                    ctx.record_error(self.name(), &e);
+                }
+            }
+        }
+        if let Some(message) = lag.outdated_message(&ctx.action) {
+            ctx.findings.push(
+                Finding::new(&OUTDATED_MAJOR, message).with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.releases = Some(lag);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Releases"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn floating_tag_is_behind_releases_after_its_newest() {
+        let lag = ReleaseLag::new(
+            "3",
+            tags(&["v4.2.0", "v4", "v4.1.0", "v4.0.0", "v3.6.0", "v3.5.3", "v3"]),
+        )
+        .unwrap();
+        assert_eq!(lag.latest, "v4.2.0");
+        assert_eq!(lag.behind, 3);
+        assert_eq!(lag.majors_behind, 1);
+        assert_eq!(lag.to_string(), "3 releases behind v4.2.0 (1 major)");
+    }
+
+    #[test]
+    fn exact_pin_and_up_to_date() {
+        let names = tags(&["v2.0.0", "2.0.0", "v1.1.0", "v5.0.0-beta.1"]);
+        let lag = ReleaseLag::new("1.1.0", names.clone()).unwrap();
+        assert_eq!((lag.behind, lag.majors_behind), (1, 1));
+        assert_eq!(lag.to_string(), "1 release behind v2.0.0 (1 major)");
+
+        let lag = ReleaseLag::new("2", names).unwrap();
+        assert_eq!(lag.to_string(), "up to date with v2.0.0");
+    }
+
+    #[test]
+    fn eol_runtime_flags_a_major_one_behind() {
+        let action: ActionRef = "actions/upload-artifact@v2".parse().unwrap();
+        let mut lag = ReleaseLag::new("2", tags(&["v3.1.0", "v2.3.1", "v2.3.0", "v2"])).unwrap();
+        assert_eq!(lag.major_latest.as_deref(), Some("v2.3.1"));
+        assert_eq!(lag.outdated_message(&action), None);
+
+        lag.runtime = Some("node12".to_string());
+        assert_eq!(
+            lag.outdated_message(&action).unwrap(),
+            "actions/upload-artifact@v2 is behind the latest release v3.1.0; its newest \
+             release on this major, v2.3.1, runs on node12, which GitHub Actions no longer \
+             supports"
+        );
+        assert_eq!(
+            lag.to_string(),
+            "1 release behind v3.1.0 (1 major); this major runs on node12"
+        );
+
+        lag.runtime = Some("node20".to_string());
+        assert_eq!(lag.outdated_message(&action), None);
+    }
+
+    #[test]
+    fn unknown_without_versions() {
+        assert!(ReleaseLag::new("main", tags(&["v1.0.0"])).is_none());
+        assert!(ReleaseLag::new("1", tags(&["v1", "latest"])).is_none());
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::finding::{ARCHIVED_ACTION, Finding, STALE_ACTION};
+use crate::github::GitHubClient;
+
+/// Months without a push or release after which an action counts as
+/// abandoned, unless configured otherwise.
+pub const DEFAULT_STALE_AFTER_MONTHS: u32 = 18;
+
+/// The action repository's status, from `/repos/{owner}/{repo}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepoMetadata {
+    pub archived: bool,
+    /// Disabled by GitHub, e.g. for a terms of service violation.
+    pub disabled: bool,
+    /// The last push to any branch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pushed_at: Option<DateTime<Utc>>,
+    /// When the latest release was published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_release: Option<DateTime<Utc>>,
+}
+
+fn timestamp(json: &Value, key: &str) -> Option<DateTime<Utc>> {
+    json.get(key)?.as_str()?.parse().ok()
+}
+
+impl RepoMetadata {
+    fn from_json(json: &Value) -> Self {
+        let flag = |key| json.get(key).and_then(Value::as_bool).unwrap_or(false);
+        Self {
+            archived: flag("archived"),
+            disabled: flag("disabled"),
+            pushed_at: timestamp(json, "pushed_at"),
+            last_release: None,
+        }
+    }
+
+    /// The later of the last push and the latest release.
+    pub fn last_activity(&self) -> Option<DateTime<Utc>> {
+        self.pushed_at.max(self.last_release)
+    }
+
+    /// A [`STALE_ACTION`] message, if the repository has seen no push or
+    /// release in the `months` before `now`. Archived repositories are
+    /// already reported as such.
+    fn stale_message(&self, action: &ActionRef, months: u32, now: DateTime<Utc>) -> Option<String> {
+        let last = self.last_activity()?;
+        let cutoff = now.checked_sub_months(Months::new(months))?;
+        if self.archived || self.disabled || last >= cutoff {
+            return None;
+        }
+        Some(format!(
+            "{action} comes from {}/{}, which has had no push or release since {}, over {months} \
+             months ago",
+            action.owner,
+            action.repo,
+            last.format("%Y-%m-%d")
+        ))
+    }
+
+    /// An [`ARCHIVED_ACTION`] message, if the repository is archived or
+    /// disabled.
+    fn archived_message(&self, action: &ActionRef) -> Option<String> {
+        let repo = format!("{}/{}", action.owner, action.repo);
+        if self.disabled {
+            Some(format!(
+                "{action} comes from {repo}, which GitHub has disabled"
+            ))
+        } else if self.archived {
+            Some(format!(
+                "{action} comes from {repo}, which is archived and will not receive security fixes"
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for RepoMetadata {
+    /// e.g. `active, last push 2025-01-06, last release 2024-12-02`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.disabled, self.archived) {
+            (true, _) => write!(f, "disabled")?,
+            (false, true) => write!(f, "archived")?,
+            (false, false) => write!(f, "active")?,
+        }
+        if let Some(pushed) = self.pushed_at {
+            write!(f, ", last push {}", pushed.format("%Y-%m-%d"))?;
+        }
+        if let Some(released) = self.last_release {
+            write!(f, ", last release {}", released.format("%Y-%m-%d"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads each action repository's metadata and latest release, raising
+/// [`ARCHIVED_ACTION`] for archived or disabled repositories and
+/// [`STALE_ACTION`] for ones inactive longer than the staleness threshold.
+pub struct RepoMetadataStage {
+    client: GitHubClient,
+    stale_after_months: u32,
+}
+
+impl RepoMetadataStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self {
+            client,
+            stale_after_months: DEFAULT_STALE_AFTER_MONTHS,
+        }
+    }
+
+    pub fn with_stale_after(mut self, months: u32) -> Self {
+        self.stale_after_months = months;
+        self
+    }
+
+    async fn metadata(&self, action: &ActionRef) -> anyhow::Result<RepoMetadata> {
+        let repo_url = format!(
+            "{}/repos/{}/{}",
+            self.client.api_base_url(),
+            action.owner,
+            action.repo
+        );
+        let mut metadata = RepoMetadata::from_json(&self.client.api_get(&repo_url).await?);
+        // 404 when the repository has never published a release.
+        if let Some(release) = self
+            .client
+            .api_get_optional(&format!("{repo_url}/releases/latest"))
+            .await?
+        {
+            metadata.last_release = timestamp(&release, "published_at");
+        }
+        Ok(metadata)
+    }
+}
+
+#[async_trait]
+impl Stage for RepoMetadataStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let metadata = match self.metadata(&ctx.action).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to fetch repository metadata");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        let location = ctx.action.location.clone();
+        if let Some(message) = metadata.archived_message(&ctx.action) {
+            ctx.findings
+                .push(Finding::new(&ARCHIVED_ACTION, message).with_location(location.clone()));
+        }
+        if let Some(message) =
+            metadata.stale_message(&ctx.action, self.stale_after_months, Utc::now())
+        {
+            ctx.findings
+                .push(Finding::new(&STALE_ACTION, message).with_location(location));
+        }
+        ctx.repository = Some(metadata);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "RepoMetadata"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archived_and_disabled_repositories_are_flagged() {
+        let action: ActionRef = "actions/create-release@v1".parse().unwrap();
+        let active = RepoMetadata::from_json(&serde_json::json!({"archived": false}));
+        assert_eq!(active.to_string(), "active");
+        assert_eq!(active.archived_message(&action), None);
+
+        let archived = RepoMetadata::from_json(&serde_json::json!({"archived": true}));
+        assert_eq!(archived.to_string(), "archived");
+        assert_eq!(
+            archived.archived_message(&action).unwrap(),
+            "actions/create-release@v1 comes from actions/create-release, which is archived and \
+             will not receive security fixes"
+        );
+
+        let disabled =
+            RepoMetadata::from_json(&serde_json::json!({"archived": true, "disabled": true}));
+        assert_eq!(disabled.to_string(), "disabled");
+        assert!(
+            disabled
+                .archived_message(&action)
+                .unwrap()
+                .ends_with("which GitHub has disabled")
+        );
+    }
+
+    #[test]
+    fn stale_after_months_without_push_or_release() {
+        let action: ActionRef = "someone/old-action@v1".parse().unwrap();
+        let now: DateTime<Utc> = "2026-10-16T00:00:00Z".parse().unwrap();
+        let mut metadata = RepoMetadata::from_json(&serde_json::json!({
+            "archived": false,
+            "pushed_at": "2024-10-01T12:00:00Z"
+        }));
+        assert_eq!(
+            metadata.stale_message(&action, 18, now).unwrap(),
+            "someone/old-action@v1 comes from someone/old-action, which has had no push or \
+             release since 2024-10-01, over 18 months ago"
+        );
+        assert_eq!(metadata.stale_message(&action, 25, now), None);
+
+        metadata.last_release = Some("2025-06-01T00:00:00Z".parse().unwrap());
+        assert_eq!(metadata.stale_message(&action, 18, now), None);
+        assert_eq!(
+            metadata.to_string(),
+            "active, last push 2024-10-01, last release 2025-06-01"
+        );
+
+        metadata.archived = true;
+        assert_eq!(metadata.stale_message(&action, 1, now), None);
+        assert_eq!(RepoMetadata::default().stale_message(&action, 1, now), None);
+    }
+}
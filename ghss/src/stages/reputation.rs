@@ -0,0 +1,180 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use super::releases;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::github::GitHubClient;
+
+/// Contributors are counted from the first pages of the listing only, so
+/// popular repositories report this many or more.
+const MAX_CONTRIBUTORS: usize = 300;
+
+/// The kind of account that owns an action repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OwnerType {
+    /// A personal account, often a single maintainer with no review process.
+    User,
+    Organization,
+}
+
+impl fmt::Display for OwnerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OwnerType::User => write!(f, "user"),
+            OwnerType::Organization => write!(f, "organization"),
+        }
+    }
+}
+
+/// Signals of how widely an action repository is used and maintained, to
+/// tell an established action from a one-person experiment.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reputation {
+    pub stars: u64,
+    pub forks: u64,
+    /// Capped at [`MAX_CONTRIBUTORS`].
+    pub contributors: usize,
+    /// When the owning user or organization account was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_created: Option<DateTime<Utc>>,
+    /// Whether a user or an organization owns the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_type: Option<OwnerType>,
+}
+
+impl Reputation {
+    fn from_json(repo: &Value, owner: &Value, contributors: usize) -> Self {
+        let count = |key| repo.get(key).and_then(Value::as_u64).unwrap_or(0);
+        Self {
+            stars: count("stargazers_count"),
+            forks: count("forks_count"),
+            contributors,
+            owner_created: owner
+                .get("created_at")
+                .and_then(Value::as_str)
+                .and_then(|s| s.parse().ok()),
+            owner_type: match owner.get("type").and_then(Value::as_str) {
+                Some("User") => Some(OwnerType::User),
+                Some("Organization") => Some(OwnerType::Organization),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Reputation {
+    /// e.g. `1 star, 0 forks, 1 contributor, user since 2024-11-02`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: u64, word| format!("{n} {word}{}", if n == 1 { "" } else { "s" });
+        let contributors = if self.contributors >= MAX_CONTRIBUTORS {
+            format!("{MAX_CONTRIBUTORS}+ contributors")
+        } else {
+            plural(self.contributors as u64, "contributor")
+        };
+        write!(
+            f,
+            "{}, {}, {contributors}",
+            plural(self.stars, "star"),
+            plural(self.forks, "fork")
+        )?;
+        let owner = self
+            .owner_type
+            .map_or("owner".to_string(), |t| t.to_string());
+        match self.owner_created {
+            Some(created) => write!(f, ", {owner} since {}", created.format("%Y-%m-%d")),
+            None if self.owner_type.is_some() => write!(f, ", {owner}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Collects stars, forks, contributor count, and the owner's account type
+/// and age for each action repository into `ctx.reputation`.
+pub struct ReputationStage {
+    client: GitHubClient,
+}
+
+impl ReputationStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+
+    async fn reputation(&self, action: &ActionRef) -> anyhow::Result<Reputation> {
+        let api = self.client.api_base_url();
+        let repo = self
+            .client
+            .api_get(&format!("{api}/repos/{}/{}", action.owner, action.repo))
+            .await?;
+        let owner = self
+            .client
+            .api_get(&format!("{api}/users/{}", action.owner))
+            .await?;
+        let contributors = releases::list(&self.client, action, "contributors", |c| {
+            c.get("login").and_then(Value::as_str)
+        })
+        .await?;
+        Ok(Reputation::from_json(
+            &repo,
+            &owner,
+            contributors.len().min(MAX_CONTRIBUTORS),
+        ))
+    }
+}
+
+#[async_trait]
+impl Stage for ReputationStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        match self.reputation(&ctx.action).await {
+            Ok(reputation) => ctx.reputation = Some(reputation),
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to fetch repository reputation");
+                ctx.record_error(self.name(), &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Reputation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_counts_and_owner_age() {
+        let repo = serde_json::json!({"stargazers_count": 1, "forks_count": 0});
+        let owner = serde_json::json!({"login": "someone", "created_at": "2024-11-02T08:00:00Z"});
+        let reputation = Reputation::from_json(&repo, &owner, 1);
+        assert_eq!(
+            reputation.to_string(),
+            "1 star, 0 forks, 1 contributor, owner since 2024-11-02"
+        );
+
+        let repo = serde_json::json!({"stargazers_count": 6200, "forks_count": 1800});
+        let reputation = Reputation::from_json(&repo, &serde_json::json!({}), MAX_CONTRIBUTORS);
+        assert_eq!(
+            reputation.to_string(),
+            "6200 stars, 1800 forks, 300+ contributors"
+        );
+
+        let owner = serde_json::json!({"type": "Organization"});
+        let reputation = Reputation::from_json(&repo, &owner, 12);
+        assert_eq!(reputation.owner_type, Some(OwnerType::Organization));
+        assert_eq!(
+            reputation.to_string(),
+            "6200 stars, 1800 forks, 12 contributors, organization"
+        );
+    }
+}
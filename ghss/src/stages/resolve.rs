@@ -1,10 +1,14 @@
 use async_trait::async_trait;
 use tracing::{instrument, warn};
 
-use super::Stage;
+use super::{Phase, Stage};
 use crate::context::AuditContext;
-use crate::github::GitHubClient;
+use crate::finding::{BRANCH_REF, Finding, RENAMED_ACTION};
+use crate::github::{GitHubClient, RefKind};
 
+/// Resolves each action's ref to a commit SHA, raising [`BRANCH_REF`] when
+/// the ref names a branch and, for tag and branch refs, [`RENAMED_ACTION`]
+/// when the repository is referenced by a name GitHub redirects from.
 pub struct RefResolveStage {
     client: GitHubClient,
 }
@@ -19,11 +23,49 @@ impl RefResolveStage {
 impl Stage for RefResolveStage {
     #[instrument(skip(self, ctx), fields(action = %ctx.action))]
     async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
-        match self.client.resolve_ref(&ctx.action).await {
-            Ok(sha) => ctx.resolved_ref = Some(sha),
+        let kind = match self.client.resolve_ref_kind(&ctx.action).await {
+            Ok((sha, kind)) => {
+                if kind == RefKind::Branch {
+                    let message = format!(
+                        "{} follows branch `{}`, currently at {sha}",
+                        ctx.action, ctx.action.git_ref
+                    );
+                    ctx.findings.push(
+                        Finding::new(&BRANCH_REF, message)
+                            .with_location(ctx.action.location.clone()),
+                    );
+                }
+                ctx.resolved_ref = Some(sha);
+                kind
+            }
             Err(e) => {
                 warn!(action = %ctx.action, error = %e, "failed to resolve ref");
                 ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+
+        // A SHA pin runs the same code whoever registers the old name.
+        if kind == RefKind::Commit {
+            return Ok(());
+        }
+        let action = &ctx.action;
+        match self.client.renamed_to(&action.owner, &action.repo).await {
+            Ok(Some(current)) => {
+                let message = format!(
+                    "{action} refers to {}/{}, which GitHub redirects to {current}; the old \
+                     name can be re-registered by someone else",
+                    action.owner, action.repo
+                );
+                ctx.findings.push(
+                    Finding::new(&RENAMED_ACTION, message)
+                        .with_location(ctx.action.location.clone()),
+                );
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to check for a rename");
+                ctx.record_error(self.name(), &e);
             }
         }
         Ok(())
@@ -32,6 +74,10 @@ impl Stage for RefResolveStage {
     fn name(&self) -> &'static str {
         "RefResolve"
     }
+
+    fn phase(&self) -> Phase {
+        Phase::Expand
+    }
 }
 
 #[cfg(test)]
@@ -50,6 +96,20 @@ mod tests {
             advisories: vec![],
             scan: None,
             dependencies: vec![],
+            update_hygiene: None,
+            project: None,
+            releases: None,
+            recommended_ref: None,
+            repository: None,
+            reputation: None,
+            scorecard: None,
+            provenance: None,
+            signatures: None,
+            license: None,
+            tag_protection: None,
+            repo_hygiene: None,
+            runtime: None,
+            findings: vec![],
             errors: vec![],
         }
     }
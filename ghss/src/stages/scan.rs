@@ -27,18 +27,19 @@ pub enum Ecosystem {
 }
 
 impl Ecosystem {
-    /// Returns the ecosystem string expected by the OSV.dev API.
-    pub fn osv_ecosystem(&self) -> &'static str {
+    /// Returns the ecosystem string expected by the OSV.dev API, or `None`
+    /// when OSV has no ecosystem for the manifest kind. Gradle resolves
+    /// Maven artifacts; Dockerfile base images are not OSV packages.
+    pub fn osv_ecosystem(&self) -> Option<&'static str> {
         match self {
-            Ecosystem::Npm => "npm",
-            Ecosystem::Cargo => "crates.io",
-            Ecosystem::Go => "Go",
-            Ecosystem::Pip => "PyPI",
-            Ecosystem::Maven => "Maven",
-            Ecosystem::Gradle => "Maven",
-            Ecosystem::RubyGems => "RubyGems",
-            Ecosystem::Composer => "Packagist",
-            Ecosystem::Docker => "Linux",
+            Ecosystem::Npm => Some("npm"),
+            Ecosystem::Cargo => Some("crates.io"),
+            Ecosystem::Go => Some("Go"),
+            Ecosystem::Pip => Some("PyPI"),
+            Ecosystem::Maven | Ecosystem::Gradle => Some("Maven"),
+            Ecosystem::RubyGems => Some("RubyGems"),
+            Ecosystem::Composer => Some("Packagist"),
+            Ecosystem::Docker => None,
         }
     }
 }
@@ -257,6 +258,19 @@ mod tests {
         assert_eq!(Ecosystem::Docker.to_string(), "docker");
     }
 
+    #[test]
+    fn osv_ecosystem_names() {
+        assert_eq!(Ecosystem::Npm.osv_ecosystem(), Some("npm"));
+        assert_eq!(Ecosystem::Cargo.osv_ecosystem(), Some("crates.io"));
+        assert_eq!(Ecosystem::Go.osv_ecosystem(), Some("Go"));
+        assert_eq!(Ecosystem::Pip.osv_ecosystem(), Some("PyPI"));
+        assert_eq!(Ecosystem::Maven.osv_ecosystem(), Some("Maven"));
+        assert_eq!(Ecosystem::Gradle.osv_ecosystem(), Some("Maven"));
+        assert_eq!(Ecosystem::RubyGems.osv_ecosystem(), Some("RubyGems"));
+        assert_eq!(Ecosystem::Composer.osv_ecosystem(), Some("Packagist"));
+        assert_eq!(Ecosystem::Docker.osv_ecosystem(), None);
+    }
+
     #[test]
     fn all_ecosystems_detected() {
         let repo = mock_graphql_response(
@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::finding::{Finding, LOW_SCORECARD};
+use crate::scorecard::{Scorecard, ScorecardClient};
+
+/// A [`LOW_SCORECARD`] message, if `scorecard` is below `min`.
+fn low_score_message(action: &ActionRef, scorecard: &Scorecard, min: f64) -> Option<String> {
+    if scorecard.score >= min {
+        return None;
+    }
+    let mut message = format!(
+        "{action} comes from {}/{}, whose OpenSSF Scorecard score {:.1} is below the minimum \
+         {min:.1}",
+        action.owner, action.repo, scorecard.score
+    );
+    if !scorecard.failing.is_empty() {
+        let names: Vec<&str> = scorecard.failing.iter().map(|c| c.name.as_str()).collect();
+        message.push_str(&format!(" (failing: {})", names.join(", ")));
+    }
+    Some(message)
+}
+
+/// Fetches the OpenSSF Scorecard result for each action repository into
+/// `ctx.scorecard`, raising [`LOW_SCORECARD`] below an optional minimum.
+pub struct ScorecardStage {
+    client: ScorecardClient,
+    min_score: Option<f64>,
+}
+
+impl ScorecardStage {
+    pub fn new(client: ScorecardClient) -> Self {
+        Self {
+            client,
+            min_score: None,
+        }
+    }
+
+    pub fn with_min_score(mut self, min_score: Option<f64>) -> Self {
+        self.min_score = min_score;
+        self
+    }
+}
+
+#[async_trait]
+impl Stage for ScorecardStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let scorecard = match self.client.scorecard(&ctx.action).await {
+            Ok(scorecard) => scorecard,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to fetch Scorecard result");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        let message = scorecard
+            .as_ref()
+            .zip(self.min_score)
+            .and_then(|(scorecard, min)| low_score_message(&ctx.action, scorecard, min));
+        if let Some(message) = message {
+            ctx.findings.push(
+                Finding::new(&LOW_SCORECARD, message).with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.scorecard = scorecard;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Scorecard"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorecard::FailingCheck;
+
+    #[test]
+    fn flags_scores_below_the_minimum() {
+        let action: ActionRef = "some-user/deploy-action@v1".parse().unwrap();
+        let mut scorecard = Scorecard {
+            score: 4.25,
+            date: None,
+            failing: vec![FailingCheck {
+                name: "Code-Review".to_string(),
+                score: 0,
+                reason: None,
+            }],
+        };
+        assert_eq!(
+            low_score_message(&action, &scorecard, 5.0).unwrap(),
+            "some-user/deploy-action@v1 comes from some-user/deploy-action, whose OpenSSF \
+             Scorecard score 4.2 is below the minimum 5.0 (failing: Code-Review)"
+        );
+        assert_eq!(low_score_message(&action, &scorecard, 4.0), None);
+        scorecard.score = 5.0;
+        assert_eq!(low_score_message(&action, &scorecard, 5.0), None);
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::{ActionRef, RefType};
+use crate::context::AuditContext;
+use crate::finding::{Finding, UNSIGNED_REF};
+use crate::github::GitHubClient;
+
+/// GitHub's verdict on one commit or tag signature, from the API's
+/// `verification` object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    pub verified: bool,
+    /// Why, e.g. `valid`, `unsigned`, or `unknown_key`.
+    pub reason: String,
+    /// `gpg`, `ssh`, or `x509` (which includes Sigstore gitsign), when
+    /// there is a signature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+impl Verification {
+    fn from_json(object: &Value) -> Self {
+        let verification = object.get("verification");
+        let field = |key| verification.and_then(|v| v.get(key));
+        let signature = field("signature").and_then(Value::as_str);
+        let kind = signature.and_then(|s| {
+            let kind = if s.contains("BEGIN PGP SIGNATURE") {
+                "gpg"
+            } else if s.contains("BEGIN SSH SIGNATURE") {
+                "ssh"
+            } else if s.contains("BEGIN SIGNED MESSAGE") {
+                "x509"
+            } else {
+                return None;
+            };
+            Some(kind.to_string())
+        });
+        Self {
+            verified: field("verified").and_then(Value::as_bool).unwrap_or(false),
+            reason: field("reason")
+                .and_then(Value::as_str)
+                .unwrap_or("unsigned")
+                .to_string(),
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for Verification {
+    /// e.g. `verified (ssh)`, `unsigned`, or `unverified: unknown_key (gpg)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.verified, self.reason.as_str()) {
+            (true, _) => write!(f, "verified")?,
+            (false, "unsigned") => write!(f, "unsigned")?,
+            (false, reason) => write!(f, "unverified: {reason}")?,
+        }
+        if let Some(kind) = &self.kind {
+            write!(f, " ({kind})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Signature status of the commit an action resolves to, and of the tag
+/// it names when that is an annotated tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signatures {
+    pub commit: Verification,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<Verification>,
+}
+
+impl Signatures {
+    /// True if either the tag or the commit carries a verified signature.
+    pub fn signed(&self) -> bool {
+        self.commit.verified || self.tag.as_ref().is_some_and(|t| t.verified)
+    }
+}
+
+impl fmt::Display for Signatures {
+    /// e.g. `commit verified (gpg), tag unsigned`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "commit {}", self.commit)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ", tag {tag}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the signature verification of each action's commit, and of its
+/// tag when it is an annotated tag, into `ctx.signatures`. With
+/// `with_require_signed()`, raises [`UNSIGNED_REF`] when neither is signed.
+pub struct SignatureStage {
+    client: GitHubClient,
+    require_signed: bool,
+}
+
+impl SignatureStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self {
+            client,
+            require_signed: false,
+        }
+    }
+
+    pub fn with_require_signed(mut self, require_signed: bool) -> Self {
+        self.require_signed = require_signed;
+        self
+    }
+
+    async fn signatures(&self, action: &ActionRef, commit: &str) -> anyhow::Result<Signatures> {
+        let repo_url = format!(
+            "{}/repos/{}/{}",
+            self.client.api_base_url(),
+            action.owner,
+            action.repo
+        );
+        let commit_json = self
+            .client
+            .api_get(&format!("{repo_url}/commits/{commit}"))
+            .await?;
+        let commit = Verification::from_json(commit_json.get("commit").unwrap_or(&Value::Null));
+
+        let mut tag = None;
+        if action.ref_type != RefType::Sha {
+            // Branches and lightweight tags have no tag object to sign.
+            let reference = self
+                .client
+                .api_get_optional(&format!("{repo_url}/git/ref/tags/{}", action.git_ref))
+                .await?;
+            let object = reference.as_ref().and_then(|r| r.get("object"));
+            let kind = object.and_then(|o| o.get("type")).and_then(Value::as_str);
+            let sha = object.and_then(|o| o.get("sha")).and_then(Value::as_str);
+            if let (Some("tag"), Some(sha)) = (kind, sha) {
+                let tag_json = self
+                    .client
+                    .api_get(&format!("{repo_url}/git/tags/{sha}"))
+                    .await?;
+                tag = Some(Verification::from_json(&tag_json));
+            }
+        }
+        Ok(Signatures { commit, tag })
+    }
+}
+
+#[async_trait]
+impl Stage for SignatureStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let Some(commit) = ctx.resolved_ref.clone() else {
+            return Ok(());
+        };
+        let signatures = match self.signatures(&ctx.action, &commit).await {
+            Ok(signatures) => signatures,
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to read signature status");
+                ctx.record_error(self.name(), &e);
+                return Ok(());
+            }
+        };
+        if self.require_signed && !signatures.signed() {
+            ctx.findings.push(
+                Finding::new(
+                    &UNSIGNED_REF,
+                    format!("{} has no verified signature: {signatures}", ctx.action),
+                )
+                .with_location(ctx.action.location.clone()),
+            );
+        }
+        ctx.signatures = Some(signatures);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Signature"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_github_verification_objects() {
+        let signed = Verification::from_json(&serde_json::json!({"verification": {
+            "verified": true,
+            "reason": "valid",
+            "signature": "-----BEGIN SSH SIGNATURE-----\nU1NIU0lH\n-----END SSH SIGNATURE-----"
+        }}));
+        assert_eq!(signed.to_string(), "verified (ssh)");
+
+        let unsigned = Verification::from_json(&serde_json::json!({"verification": {
+            "verified": false,
+            "reason": "unsigned",
+            "signature": null
+        }}));
+        assert_eq!(unsigned.to_string(), "unsigned");
+        assert_eq!(Verification::from_json(&serde_json::json!({})), unsigned);
+
+        let unknown = Verification::from_json(&serde_json::json!({"verification": {
+            "verified": false,
+            "reason": "unknown_key",
+            "signature": "-----BEGIN PGP SIGNATURE-----\n\n-----END PGP SIGNATURE-----"
+        }}));
+        assert_eq!(unknown.to_string(), "unverified: unknown_key (gpg)");
+
+        let mut signatures = Signatures {
+            commit: unknown,
+            tag: Some(unsigned),
+        };
+        assert!(!signatures.signed());
+        assert_eq!(
+            signatures.to_string(),
+            "commit unverified: unknown_key (gpg), tag unsigned"
+        );
+        signatures.tag = Some(signed);
+        assert!(signatures.signed());
+    }
+}
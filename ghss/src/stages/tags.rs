@@ -0,0 +1,102 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::{ActionRef, RefType};
+use crate::context::AuditContext;
+use crate::finding::{Finding, MOVED_TAG};
+use crate::github::GitHubClient;
+use crate::tag_history::TagHistory;
+use crate::version_range;
+
+/// The commit the tag's GitHub release was published from, when the
+/// release names one. Releases made from a branch name record the branch.
+async fn release_commit(
+    action: &ActionRef,
+    client: &GitHubClient,
+) -> anyhow::Result<Option<String>> {
+    let url = format!(
+        "{}/repos/{}/{}/releases/tags/{}",
+        client.api_base_url(),
+        action.owner,
+        action.repo,
+        action.git_ref
+    );
+    let Some(release) = client.api_get_optional(&url).await? else {
+        return Ok(None);
+    };
+    Ok(release
+        .get("target_commitish")
+        .and_then(Value::as_str)
+        .filter(|target| target.len() == 40 && target.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_ascii_lowercase))
+}
+
+/// Raises [`MOVED_TAG`] when a release tag (`v1.2.3`, not a floating `v1`)
+/// resolves to a different commit than in an earlier scan, or than its
+/// GitHub release was published from. Every resolution is recorded in the
+/// shared [`TagHistory`], which the caller saves after the walk.
+pub struct TagMovementStage {
+    client: GitHubClient,
+    history: Arc<Mutex<TagHistory>>,
+}
+
+impl TagMovementStage {
+    pub fn new(client: GitHubClient, history: Arc<Mutex<TagHistory>>) -> Self {
+        Self { client, history }
+    }
+}
+
+#[async_trait]
+impl Stage for TagMovementStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        let release_tag = ctx.action.ref_type == RefType::Tag
+            && version_range::parse_release(&ctx.action.git_ref).is_some();
+        let (true, Some(sha)) = (release_tag, ctx.resolved_ref.clone()) else {
+            return Ok(());
+        };
+        let location = ctx.action.location.clone();
+
+        let previous = self
+            .history
+            .lock()
+            .expect("tag history lock poisoned")
+            .record(&ctx.action, &sha, Utc::now());
+        if let Some(previous) = previous {
+            let message = format!(
+                "{} now resolves to {sha}, but resolved to {} when scanned on {}",
+                ctx.action,
+                previous.sha,
+                previous.seen.format("%Y-%m-%d")
+            );
+            ctx.findings
+                .push(Finding::new(&MOVED_TAG, message).with_location(location.clone()));
+        }
+
+        match release_commit(&ctx.action, &self.client).await {
+            Ok(Some(published)) if published != sha => {
+                let message = format!(
+                    "{} resolves to {sha}, but its release was published from {published}",
+                    ctx.action
+                );
+                ctx.findings
+                    .push(Finding::new(&MOVED_TAG, message).with_location(location));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to read release");
+                ctx.record_error(self.name(), &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "TagMovement"
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{instrument, warn};
+
+use super::Stage;
+use crate::action_ref::ActionRef;
+use crate::context::AuditContext;
+use crate::github::GitHubClient;
+
+/// Dependabot config locations, relative to the repository root.
+const DEPENDABOT_PATHS: &[&str] = &[".github/dependabot.yml", ".github/dependabot.yaml"];
+
+/// Renovate config locations, in Renovate's own lookup order.
+const RENOVATE_PATHS: &[&str] = &[
+    "renovate.json",
+    "renovate.json5",
+    ".github/renovate.json",
+    ".github/renovate.json5",
+    ".renovaterc",
+    ".renovaterc.json",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateTool {
+    Dependabot,
+    Renovate,
+}
+
+impl fmt::Display for UpdateTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateTool::Dependabot => write!(f, "dependabot"),
+            UpdateTool::Renovate => write!(f, "renovate"),
+        }
+    }
+}
+
+/// Whether a repository keeps its `uses:` refs up to date automatically.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateHygiene {
+    /// The update tool config found, preferring one that covers actions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<UpdateTool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_path: Option<String>,
+    /// True if the config updates the `github-actions` ecosystem.
+    pub covers_actions: bool,
+}
+
+impl UpdateHygiene {
+    /// Pick the best config from `(path, contents)` pairs in lookup order.
+    fn from_configs<'a>(configs: impl IntoIterator<Item = (&'a str, String)>) -> Self {
+        let mut first = None;
+        for (path, contents) in configs {
+            let (tool, covers_actions) = classify_config(path, &contents);
+            let hygiene = Self {
+                tool: Some(tool),
+                config_path: Some(path.to_string()),
+                covers_actions,
+            };
+            if covers_actions {
+                return hygiene;
+            }
+            first.get_or_insert(hygiene);
+        }
+        first.unwrap_or(Self {
+            tool: None,
+            config_path: None,
+            covers_actions: false,
+        })
+    }
+}
+
+impl fmt::Display for UpdateHygiene {
+    /// e.g. `dependabot (.github/dependabot.yml), covers github-actions`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Some(tool), Some(path)) = (self.tool, &self.config_path) else {
+            return write!(f, "no Dependabot or Renovate config");
+        };
+        let coverage = if self.covers_actions {
+            "covers github-actions"
+        } else {
+            "does not cover github-actions"
+        };
+        write!(f, "{tool} ({path}), {coverage}")
+    }
+}
+
+/// Which tool a config file belongs to, and whether it covers actions.
+fn classify_config(path: &str, contents: &str) -> (UpdateTool, bool) {
+    if DEPENDABOT_PATHS.contains(&path) {
+        (UpdateTool::Dependabot, dependabot_covers_actions(contents))
+    } else {
+        (UpdateTool::Renovate, renovate_covers_actions(contents))
+    }
+}
+
+/// True if a `dependabot.yml` has an `updates` entry for `github-actions`.
+fn dependabot_covers_actions(yaml: &str) -> bool {
+    let Ok(config) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else {
+        return false;
+    };
+    config
+        .get("updates")
+        .and_then(|u| u.as_sequence())
+        .is_some_and(|updates| {
+            updates.iter().any(|u| {
+                u.get("package-ecosystem").and_then(|e| e.as_str()) == Some("github-actions")
+            })
+        })
+}
+
+/// Renovate's `github-actions` manager is on by default, so a config covers
+/// actions unless it disables Renovate, the manager, or leaves the manager
+/// out of `enabledManagers`. JSON5 that doesn't parse as JSON is assumed to
+/// keep the default.
+fn renovate_covers_actions(json: &str) -> bool {
+    let Ok(config) = serde_json::from_str::<Value>(json) else {
+        return true;
+    };
+    if config.get("enabled") == Some(&Value::Bool(false)) {
+        return false;
+    }
+    if config.get("github-actions").and_then(|m| m.get("enabled")) == Some(&Value::Bool(false)) {
+        return false;
+    }
+    match config.get("enabledManagers").and_then(Value::as_array) {
+        Some(managers) => managers.iter().any(|m| m == "github-actions"),
+        None => true,
+    }
+}
+
+/// Check a local checkout rooted at `repo_root`.
+pub fn check_local(repo_root: &Path) -> UpdateHygiene {
+    UpdateHygiene::from_configs(
+        DEPENDABOT_PATHS
+            .iter()
+            .chain(RENOVATE_PATHS)
+            .filter_map(|path| Some((*path, std::fs::read_to_string(repo_root.join(path)).ok()?))),
+    )
+}
+
+/// Check an action's repository on its default branch.
+#[instrument(skip(client), fields(action = %action))]
+pub async fn check_action_repo(
+    action: &ActionRef,
+    client: &GitHubClient,
+) -> anyhow::Result<UpdateHygiene> {
+    let mut configs = Vec::new();
+    for path in DEPENDABOT_PATHS.iter().chain(RENOVATE_PATHS) {
+        if let Some(contents) = client
+            .get_raw_content_optional(&action.owner, &action.repo, "HEAD", path)
+            .await?
+        {
+            let (_, covers) = classify_config(path, &contents);
+            configs.push((*path, contents));
+            if covers {
+                break;
+            }
+        }
+    }
+    Ok(UpdateHygiene::from_configs(configs))
+}
+
+pub struct UpdateHygieneStage {
+    client: GitHubClient,
+}
+
+impl UpdateHygieneStage {
+    pub fn new(client: GitHubClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Stage for UpdateHygieneStage {
+    #[instrument(skip(self, ctx), fields(action = %ctx.action))]
+    async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+        match check_action_repo(&ctx.action, &self.client).await {
+            Ok(h) => ctx.update_hygiene = Some(h),
+            Err(e) => {
+                warn!(action = %ctx.action, error = %e, "failed to check update config");
+                ctx.record_error(self.name(), &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "UpdateHygiene"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependabot_requires_github_actions_ecosystem() {
+        let covered = "version: 2\nupdates:\n  - package-ecosystem: npm\n    directory: /\n  - package-ecosystem: github-actions\n    directory: /\n";
+        assert!(dependabot_covers_actions(covered));
+        let npm_only = "version: 2\nupdates:\n  - package-ecosystem: npm\n    directory: /\n";
+        assert!(!dependabot_covers_actions(npm_only));
+        assert!(!dependabot_covers_actions("not: [valid"));
+    }
+
+    #[test]
+    fn renovate_covers_actions_by_default() {
+        assert!(renovate_covers_actions(
+            r#"{"extends": ["config:recommended"]}"#
+        ));
+        assert!(renovate_covers_actions(
+            "// json5\n{extends: ['config:recommended']}"
+        ));
+        assert!(!renovate_covers_actions(r#"{"enabled": false}"#));
+        assert!(!renovate_covers_actions(
+            r#"{"github-actions": {"enabled": false}}"#
+        ));
+        assert!(!renovate_covers_actions(r#"{"enabledManagers": ["npm"]}"#));
+        assert!(renovate_covers_actions(
+            r#"{"enabledManagers": ["npm", "github-actions"]}"#
+        ));
+    }
+
+    #[test]
+    fn prefers_config_that_covers_actions() {
+        let hygiene = UpdateHygiene::from_configs([
+            (
+                ".github/dependabot.yml",
+                "version: 2\nupdates:\n  - package-ecosystem: npm\n".to_string(),
+            ),
+            ("renovate.json", "{}".to_string()),
+        ]);
+        assert_eq!(hygiene.tool, Some(UpdateTool::Renovate));
+        assert_eq!(
+            hygiene.to_string(),
+            "renovate (renovate.json), covers github-actions"
+        );
+    }
+
+    #[test]
+    fn reports_uncovering_config_when_nothing_covers() {
+        let hygiene = UpdateHygiene::from_configs([(
+            ".github/dependabot.yml",
+            "version: 2\nupdates: []\n".to_string(),
+        )]);
+        assert!(!hygiene.covers_actions);
+        assert_eq!(
+            hygiene.to_string(),
+            "dependabot (.github/dependabot.yml), does not cover github-actions"
+        );
+        assert_eq!(
+            UpdateHygiene::from_configs([]).to_string(),
+            "no Dependabot or Renovate config"
+        );
+    }
+
+    #[test]
+    fn check_local_reads_repo_files() {
+        let dir = std::env::temp_dir().join(format!("ghss-updates-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".github")).unwrap();
+        std::fs::write(
+            dir.join(".github/dependabot.yml"),
+            "version: 2\nupdates:\n  - package-ecosystem: github-actions\n    directory: /\n",
+        )
+        .unwrap();
+
+        let hygiene = check_local(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(hygiene.tool, Some(UpdateTool::Dependabot));
+        assert!(hygiene.covers_actions);
+    }
+
+    #[tokio::test]
+    async fn stage_records_action_repo_config() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/tool/HEAD/renovate.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        // SAFETY: test-only; env var mutation is unsafe in Rust 2024
+        unsafe { std::env::set_var("GHSS_RAW_BASE_URL", server.uri()) };
+        let client = GitHubClient::new(None);
+        unsafe { std::env::remove_var("GHSS_RAW_BASE_URL") };
+
+        let action: ActionRef = "org/tool@v1".parse().unwrap();
+        let hygiene = check_action_repo(&action, &client).await.unwrap();
+        assert_eq!(hygiene.tool, Some(UpdateTool::Renovate));
+        assert_eq!(hygiene.config_path.as_deref(), Some("renovate.json"));
+    }
+}
@@ -1,19 +1,38 @@
 use async_trait::async_trait;
 use tracing::{debug, instrument};
 
+use crate::action_ref::ActionRef;
 use crate::context::AuditContext;
+use crate::finding::{Finding, SECRETS_INHERIT, UNDECLARED_SECRET, WORKFLOW_CALL_MISMATCH};
 use crate::github::GitHubClient;
-use crate::workflow;
+use crate::workflow::{self, CallSecrets, WorkflowCallDef};
 
-use super::Stage;
+use super::{Phase, Stage};
 
 pub struct WorkflowExpandStage {
     client: GitHubClient,
+    trusted_owners: Vec<String>,
 }
 
 impl WorkflowExpandStage {
     pub fn new(client: GitHubClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            trusted_owners: Vec::new(),
+        }
+    }
+
+    /// Owners whose reusable workflows are first-party, e.g. your own
+    /// organization. `secrets: inherit` is only flagged for other owners.
+    pub fn with_trusted_owners(mut self, owners: Vec<String>) -> Self {
+        self.trusted_owners = owners;
+        self
+    }
+
+    fn is_trusted(&self, workflow: &ActionRef) -> bool {
+        self.trusted_owners
+            .iter()
+            .any(|o| o.eq_ignore_ascii_case(&workflow.owner))
     }
 }
 
@@ -42,10 +61,19 @@ impl Stage for WorkflowExpandStage {
             Some(content) => content,
             None => {
                 debug!(action = %ctx.action, "workflow file not found, skipping");
+                ctx.findings
+                    .extend(check_calls(&ctx.action, None, self.is_trusted(&ctx.action)));
                 return Ok(());
             }
         };
 
+        let def = workflow::parse_workflow_call(&yaml_content)?;
+        ctx.findings.extend(check_calls(
+            &ctx.action,
+            def.as_ref(),
+            self.is_trusted(&ctx.action),
+        ));
+
         let children = workflow::parse_workflow_refs(&yaml_content)?;
         debug!(action = %ctx.action, count = children.len(), "discovered workflow children");
         ctx.children.extend(children);
@@ -56,4 +84,144 @@ impl Stage for WorkflowExpandStage {
     fn name(&self) -> &'static str {
         "WorkflowExpand"
     }
+
+    fn phase(&self) -> Phase {
+        Phase::Expand
+    }
+}
+
+/// Compare each job's `with:` and `secrets:` for a call to `workflow`
+/// against its `on.workflow_call` interface. `secrets: inherit` is flagged
+/// even when the interface is unknown, unless `trusted`.
+fn check_calls(workflow: &ActionRef, def: Option<&WorkflowCallDef>, trusted: bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for occurrence in &workflow.occurrences {
+        let Some(call) = &occurrence.call else {
+            continue;
+        };
+        let job = occurrence.job.as_deref().unwrap_or("<unnamed>");
+        let mut push = |rule, message: String| {
+            findings.push(Finding::new(rule, message).with_location(occurrence.location.clone()));
+        };
+
+        if call.secrets == CallSecrets::Inherit && !trusted {
+            push(
+                &SECRETS_INHERIT,
+                format!("job `{job}` passes all of its secrets to {workflow}"),
+            );
+        }
+        let Some(def) = def else {
+            continue;
+        };
+        if let CallSecrets::Named(secrets) = &call.secrets {
+            for secret in secrets.iter().filter(|s| !def.secrets.contains_key(*s)) {
+                push(
+                    &UNDECLARED_SECRET,
+                    format!(
+                        "job `{job}` passes secret `{secret}` to {workflow}, which does not declare it"
+                    ),
+                );
+            }
+            for (secret, _) in def
+                .secrets
+                .iter()
+                .filter(|(s, required)| **required && !secrets.contains(s))
+            {
+                push(
+                    &WORKFLOW_CALL_MISMATCH,
+                    format!("job `{job}` does not pass required secret `{secret}` to {workflow}"),
+                );
+            }
+        }
+        for input in call.inputs.iter().filter(|i| !def.inputs.contains_key(*i)) {
+            push(
+                &WORKFLOW_CALL_MISMATCH,
+                format!(
+                    "job `{job}` passes input `{input}` to {workflow}, which does not declare it"
+                ),
+            );
+        }
+        for (input, _) in def
+            .inputs
+            .iter()
+            .filter(|(i, required)| **required && !call.inputs.contains(i))
+        {
+            push(
+                &WORKFLOW_CALL_MISMATCH,
+                format!("job `{job}` does not pass required input `{input}` to {workflow}"),
+            );
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::UsesRef;
+
+    fn called(caller: &str) -> ActionRef {
+        workflow::parse_workflow(caller)
+            .unwrap()
+            .into_iter()
+            .find_map(UsesRef::into_third_party)
+            .unwrap()
+    }
+
+    fn messages(findings: &[Finding]) -> Vec<String> {
+        findings
+            .iter()
+            .map(|f| format!("{}: {}", f.rule_id, f.message))
+            .collect()
+    }
+
+    #[test]
+    fn flags_secrets_inherit_without_interface() {
+        let workflow = called(
+            "jobs:\n  deploy:\n    uses: org/shared/.github/workflows/deploy.yml@v1\n    secrets: inherit\n",
+        );
+        let findings = check_calls(&workflow, None, false);
+        assert_eq!(
+            messages(&findings),
+            [
+                "secrets-inherit: job `deploy` passes all of its secrets to org/shared/.github/workflows/deploy.yml@v1"
+            ]
+        );
+        assert_eq!(findings[0].location.as_ref().unwrap().line, 3);
+        assert!(check_calls(&workflow, None, true).is_empty());
+    }
+
+    #[test]
+    fn compares_call_against_interface() {
+        let def = workflow::parse_workflow_call(
+            "on:\n  workflow_call:\n    inputs:\n      env:\n        type: string\n        required: true\n      dry-run:\n        type: boolean\n    secrets:\n      deploy-key:\n        required: true\n",
+        )
+        .unwrap()
+        .unwrap();
+        let workflow = called(
+            "jobs:\n  deploy:\n    uses: org/shared/.github/workflows/deploy.yml@v1\n    with:\n      region: eu\n    secrets:\n      npm-token: ${{ secrets.NPM_TOKEN }}\n",
+        );
+        assert_eq!(
+            messages(&check_calls(&workflow, Some(&def), false)),
+            [
+                "undeclared-secret: job `deploy` passes secret `npm-token` to org/shared/.github/workflows/deploy.yml@v1, which does not declare it",
+                "workflow-call-mismatch: job `deploy` does not pass required secret `deploy-key` to org/shared/.github/workflows/deploy.yml@v1",
+                "workflow-call-mismatch: job `deploy` passes input `region` to org/shared/.github/workflows/deploy.yml@v1, which does not declare it",
+                "workflow-call-mismatch: job `deploy` does not pass required input `env` to org/shared/.github/workflows/deploy.yml@v1",
+            ]
+        );
+    }
+
+    #[test]
+    fn matching_call_has_no_findings() {
+        let def = workflow::parse_workflow_call(
+            "on:\n  workflow_call:\n    inputs:\n      env:\n        required: true\n    secrets:\n      token:\n",
+        )
+        .unwrap()
+        .unwrap();
+        let workflow = called(
+            "jobs:\n  a:\n    uses: org/shared/.github/workflows/ci.yml@v1\n    with:\n      env: prod\n    secrets:\n      token: ${{ secrets.T }}\n",
+        );
+        assert!(check_calls(&workflow, Some(&def), false).is_empty());
+    }
 }
@@ -0,0 +1,96 @@
+//! Commits that release tags resolved to in earlier scans (`--tag-history`),
+//! so a tag moved to different code between scans can be flagged.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::action_ref::ActionRef;
+
+/// The commit a tag pointed at, and since when.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagResolution {
+    pub sha: String,
+    /// The first scan that saw the tag at `sha`.
+    pub seen: DateTime<Utc>,
+}
+
+/// Tag resolutions keyed by `owner/repo@tag`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TagHistory {
+    tags: BTreeMap<String, TagResolution>,
+}
+
+impl TagHistory {
+    /// Read a history file, or start an empty history if there is none yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read tag history {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("invalid tag history {}", path.display()))
+    }
+
+    /// Write atomically: to a sibling temp file, then rename over `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let partial = path.with_extension("part");
+        let json = serde_json::to_vec_pretty(self).context("failed to serialize tag history")?;
+        std::fs::write(&partial, json)
+            .with_context(|| format!("failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, path)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Record that `action`'s tag resolves to `sha`. Returns the earlier
+    /// resolution if the tag pointed at a different commit before.
+    pub fn record(
+        &mut self,
+        action: &ActionRef,
+        sha: &str,
+        now: DateTime<Utc>,
+    ) -> Option<TagResolution> {
+        let key = format!("{}/{}@{}", action.owner, action.repo, action.git_ref);
+        let current = TagResolution {
+            sha: sha.to_string(),
+            seen: now,
+        };
+        match self.tags.get_mut(&key) {
+            Some(known) if known.sha == sha => None,
+            Some(known) => Some(std::mem::replace(known, current)),
+            None => {
+                self.tags.insert(key, current);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_moved_tags_and_keeps_first_seen() {
+        let action: ActionRef = "tj-actions/changed-files@v35.7.1".parse().unwrap();
+        let day =
+            |d: u32| -> DateTime<Utc> { format!("2025-03-{d:02}T00:00:00Z").parse().unwrap() };
+        let mut history = TagHistory::default();
+        assert_eq!(history.record(&action, "aaaa", day(1)), None);
+        assert_eq!(history.record(&action, "aaaa", day(2)), None);
+
+        let path = std::env::temp_dir().join(format!("ghss-tags-{}.json", std::process::id()));
+        history.save(&path).unwrap();
+        let mut history = TagHistory::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let moved = history.record(&action, "bbbb", day(14)).unwrap();
+        assert_eq!((moved.sha.as_str(), moved.seen), ("aaaa", day(1)));
+        assert_eq!(history.record(&action, "bbbb", day(15)), None);
+        assert!(TagHistory::load(&path).unwrap().tags.is_empty());
+    }
+}
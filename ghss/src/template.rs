@@ -0,0 +1,134 @@
+//! Organization workflow templates (starter workflows).
+//!
+//! Templates live in `workflow-templates/` of an organization's `.github`
+//! repository. Each `name.yml` is paired with a `name.properties.json`
+//! describing it in the "New workflow" picker. The YAML is an ordinary
+//! workflow apart from placeholders such as `$default-branch`, so it goes
+//! through the same parsing and audit pipeline.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Directory that holds workflow templates.
+pub const TEMPLATE_DIR: &str = "workflow-templates";
+
+const PROPERTIES_SUFFIX: &str = ".properties.json";
+
+/// Contents of a template's `*.properties.json` companion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateProperties {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub icon_name: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub file_patterns: Vec<String>,
+}
+
+impl TemplateProperties {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("invalid workflow template properties")
+    }
+}
+
+/// True if `path` sits inside a `workflow-templates` directory.
+pub fn is_template_path(path: &Path) -> bool {
+    path.parent()
+        .is_some_and(|dir| dir.components().any(|c| c.as_os_str() == TEMPLATE_DIR))
+}
+
+/// Path of the `*.properties.json` companion for a template workflow file.
+pub fn properties_path(workflow: &Path) -> Option<std::path::PathBuf> {
+    let stem = workflow.file_stem()?.to_str()?;
+    Some(workflow.with_file_name(format!("{stem}{PROPERTIES_SUFFIX}")))
+}
+
+/// Pick the template workflows out of a `workflow-templates` directory listing:
+/// YAML files that have a matching `*.properties.json`, which GitHub requires
+/// before it offers a template.
+pub fn template_workflows(file_names: &[String]) -> Vec<String> {
+    file_names
+        .iter()
+        .filter(|name| is_yaml(name))
+        .filter(|name| {
+            properties_path(Path::new(name.as_str()))
+                .and_then(|p| p.to_str().map(String::from))
+                .is_some_and(|props| file_names.contains(&props))
+        })
+        .cloned()
+        .collect()
+}
+
+fn is_yaml(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_template_paths() {
+        assert!(is_template_path(Path::new("workflow-templates/ci.yml")));
+        assert!(is_template_path(Path::new(
+            "/src/org/.github/workflow-templates/ci.yml"
+        )));
+        assert!(!is_template_path(Path::new(".github/workflows/ci.yml")));
+        assert!(!is_template_path(Path::new("workflow-templates")));
+    }
+
+    #[test]
+    fn properties_path_replaces_extension() {
+        assert_eq!(
+            properties_path(Path::new("workflow-templates/ci.yml")).unwrap(),
+            Path::new("workflow-templates/ci.properties.json")
+        );
+    }
+
+    #[test]
+    fn template_workflows_require_companion() {
+        let names: Vec<String> = [
+            "ci.yml",
+            "ci.properties.json",
+            "release.yaml",
+            "release.properties.json",
+            "draft.yml",
+            "README.md",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(template_workflows(&names), ["ci.yml", "release.yaml"]);
+    }
+
+    #[test]
+    fn parses_properties() {
+        let props = TemplateProperties::from_json(
+            r#"{
+                "name": "Org CI",
+                "description": "Standard build",
+                "iconName": "ci",
+                "categories": ["Rust"],
+                "filePatterns": ["Cargo.toml$"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(props.name, "Org CI");
+        assert_eq!(props.icon_name.as_deref(), Some("ci"));
+        assert_eq!(props.categories, ["Rust"]);
+        assert_eq!(props.file_patterns, ["Cargo.toml$"]);
+    }
+
+    #[test]
+    fn properties_require_name() {
+        assert!(TemplateProperties::from_json(r#"{"description": "x"}"#).is_err());
+    }
+}
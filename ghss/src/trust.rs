@@ -0,0 +1,153 @@
+//! First-party owners given with `--trusted-owner`.
+//!
+//! Actions and reusable workflows of a trusted owner, such as your own
+//! organization, are maintained by people you already trust, so how they
+//! are pinned and the advisories on them are not reported. Everything else
+//! about them still is, such as an archived repository or a moved tag.
+
+use std::collections::BTreeSet;
+
+use crate::action_ref::ActionRef;
+use crate::finding::{self, Finding, Rule};
+use crate::ignore::Ignored;
+use crate::output::AuditNode;
+
+/// Findings about how an action is pinned, left out for trusted owners.
+pub const PINNING_RULES: &[&Rule] = &[
+    &finding::UNPINNED_ACTION,
+    &finding::BRANCH_REF,
+    &finding::REPLACEABLE_TAG,
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedOwners {
+    /// Lowercased.
+    owners: BTreeSet<String>,
+}
+
+fn is_pinning(finding: &Finding) -> bool {
+    PINNING_RULES.iter().any(|r| r.id == finding.rule_id)
+}
+
+impl TrustedOwners {
+    pub fn new(owners: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            owners: owners
+                .into_iter()
+                .map(|o| o.as_ref().to_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+
+    pub fn trusts(&self, action: &ActionRef) -> bool {
+        self.owners.contains(&action.owner.to_lowercase())
+    }
+
+    /// Remove the advisories and pinning findings of trusted actions from
+    /// the audited tree, and pinning findings located on their `uses:`
+    /// lines from the workflow's `findings`.
+    pub fn apply(&self, nodes: &mut [AuditNode], findings: &mut Vec<Finding>) -> Ignored {
+        let mut removed = Ignored::default();
+        if self.is_empty() {
+            return removed;
+        }
+        // `uses:` lines of trusted actions, as (file, line).
+        let mut lines = BTreeSet::new();
+        self.apply_nodes(nodes, &mut lines, &mut removed);
+
+        let before = findings.len();
+        findings.retain(|f| {
+            let at_trusted_action = f
+                .location
+                .as_ref()
+                .is_some_and(|l| lines.contains(&(l.file.clone(), l.line)));
+            !(at_trusted_action && is_pinning(f))
+        });
+        removed.findings += before - findings.len();
+        removed
+    }
+
+    fn apply_nodes(
+        &self,
+        nodes: &mut [AuditNode],
+        lines: &mut BTreeSet<(Option<String>, usize)>,
+        removed: &mut Ignored,
+    ) {
+        for node in nodes {
+            let entry = &mut node.entry;
+            if self.trusts(&entry.action) {
+                let action = &entry.action;
+                let locations = action.location.iter().chain(
+                    action
+                        .occurrences
+                        .iter()
+                        .filter_map(|o| o.location.as_ref()),
+                );
+                lines.extend(locations.map(|l| (l.file.clone(), l.line)));
+
+                removed.advisories += entry.advisories.len();
+                entry.advisories.clear();
+                let before = entry.findings.len();
+                entry.findings.retain(|f| !is_pinning(f));
+                removed.findings += before - entry.findings.len();
+            }
+            self.apply_nodes(&mut node.children, lines, removed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::AuditContext;
+    use crate::location::SourceLocation;
+
+    #[test]
+    fn drops_advisories_and_pinning_findings_of_trusted_owners() {
+        let yaml = "jobs:\n  build:\n    steps:\n      - uses: My-Org/deploy@main\n      - uses: tj-actions/changed-files@v45\n";
+        let advisory = crate::advisory::Advisory::for_test("GHSA-aaaa-bbbb-cccc", &[]);
+        let mut nodes: Vec<AuditNode> = crate::parse_actions_from(yaml, "ci.yml")
+            .unwrap()
+            .into_iter()
+            .map(|action| {
+                AuditNode::from(AuditContext {
+                    advisories: vec![advisory.clone()],
+                    findings: vec![
+                        Finding::new(&finding::BRANCH_REF, "branch"),
+                        Finding::new(&finding::ARCHIVED_ACTION, "archived"),
+                    ],
+                    ..AuditContext::for_test(action)
+                })
+            })
+            .collect();
+        let at = |line| Some(SourceLocation::new(line, 15).with_file("ci.yml"));
+        let mut findings = vec![
+            Finding::new(&finding::UNPINNED_ACTION, "deploy").with_location(at(4)),
+            Finding::new(&finding::UNPINNED_ACTION, "changed-files").with_location(at(5)),
+            Finding::new(&finding::DEFAULT_PERMISSIONS, "job `build`").with_location(at(4)),
+        ];
+
+        let trusted = TrustedOwners::new(["my-org"]);
+        let removed = trusted.apply(&mut nodes, &mut findings);
+        assert_eq!(
+            removed,
+            Ignored {
+                advisories: 1,
+                findings: 2
+            }
+        );
+        let deploy = &nodes[0].entry;
+        assert_eq!(deploy.action.owner, "My-Org");
+        assert!(deploy.advisories.is_empty());
+        assert_eq!(deploy.findings[0].rule_id, "archived-action");
+        assert_eq!(deploy.findings.len(), 1);
+        assert_eq!(nodes[1].entry.advisories.len(), 1);
+        assert_eq!(nodes[1].entry.findings.len(), 2);
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, ["changed-files", "job `build`"]);
+    }
+}
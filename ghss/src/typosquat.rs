@@ -0,0 +1,184 @@
+//! Flags actions whose `owner/repo` is a near-miss spelling of a widely
+//! used action, e.g. `actons/checkout` for `actions/checkout`.
+//!
+//! Anyone can register a lookalike owner and publish a copy of a popular
+//! action with a payload added, so a one-letter typo in `uses:` can hand the
+//! job's token and secrets to an attacker. The check is offline: it compares
+//! names against a curated list and never fetches the lookalike.
+
+use crate::action_ref::ActionRef;
+use crate::finding::{Finding, TYPOSQUAT};
+
+/// Widely used action repositories, lowercased `owner/repo`.
+const POPULAR: &[&str] = &[
+    "actions/attest-build-provenance",
+    "actions/cache",
+    "actions/checkout",
+    "actions/configure-pages",
+    "actions/create-github-app-token",
+    "actions/create-release",
+    "actions/dependency-review-action",
+    "actions/deploy-pages",
+    "actions/download-artifact",
+    "actions/github-script",
+    "actions/labeler",
+    "actions/setup-dotnet",
+    "actions/setup-go",
+    "actions/setup-java",
+    "actions/setup-node",
+    "actions/setup-python",
+    "actions/stale",
+    "actions/upload-artifact",
+    "actions/upload-pages-artifact",
+    "actions-rs/toolchain",
+    "amannn/action-semantic-pull-request",
+    "aws-actions/configure-aws-credentials",
+    "azure/login",
+    "codecov/codecov-action",
+    "docker/build-push-action",
+    "docker/login-action",
+    "docker/metadata-action",
+    "docker/setup-buildx-action",
+    "docker/setup-qemu-action",
+    "dorny/paths-filter",
+    "dtolnay/rust-toolchain",
+    "github/codeql-action",
+    "golangci/golangci-lint-action",
+    "google-github-actions/auth",
+    "googleapis/release-please-action",
+    "gradle/actions",
+    "hashicorp/setup-terraform",
+    "jamesives/github-pages-deploy-action",
+    "ncipollo/release-action",
+    "ossf/scorecard-action",
+    "peaceiris/actions-gh-pages",
+    "peter-evans/create-pull-request",
+    "pnpm/action-setup",
+    "pypa/gh-action-pypi-publish",
+    "release-drafter/release-drafter",
+    "ruby/setup-ruby",
+    "shivammathur/setup-php",
+    "sigstore/cosign-installer",
+    "slackapi/slack-github-action",
+    "softprops/action-gh-release",
+    "step-security/harden-runner",
+    "swatinem/rust-cache",
+    "tj-actions/changed-files",
+];
+
+/// Names this long or longer may differ by two edits; shorter ones by one,
+/// since two edits to a short name often spell an unrelated real action.
+const TWO_EDIT_LENGTH: usize = 16;
+
+/// Edit distance counting insertions, deletions, substitutions, and swaps
+/// of adjacent characters (optimal string alignment).
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// The popular action `name` (lowercased `owner/repo`) is a lookalike of,
+/// and how many edits apart they are. `None` for popular actions themselves.
+fn lookalike(name: &str) -> Option<(&'static str, usize)> {
+    if POPULAR.contains(&name) {
+        return None;
+    }
+    let max = if name.len() >= TWO_EDIT_LENGTH { 2 } else { 1 };
+    POPULAR
+        .iter()
+        .map(|popular| (*popular, distance(name, popular)))
+        .filter(|(_, edits)| *edits <= max)
+        .min_by_key(|(_, edits)| *edits)
+}
+
+/// A [`TYPOSQUAT`] finding for each action whose repository is a near miss
+/// of a popular one.
+pub fn check_typosquats<'a>(actions: impl IntoIterator<Item = &'a ActionRef>) -> Vec<Finding> {
+    actions
+        .into_iter()
+        .filter_map(|action| {
+            let name = format!("{}/{}", action.owner, action.repo);
+            let (popular, edits) = lookalike(&name.to_ascii_lowercase())?;
+            let characters = if edits == 1 {
+                "character"
+            } else {
+                "characters"
+            };
+            Some(
+                Finding::new(
+                    &TYPOSQUAT,
+                    format!(
+                        "{name} differs from the popular action {popular} by {edits} \
+                         {characters}; check that it is the action you meant"
+                    ),
+                )
+                .with_location(action.location.clone()),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_swaps_as_one_edit() {
+        assert_eq!(distance("actions/checkout", "actions/checkout"), 0);
+        assert_eq!(distance("actons/checkout", "actions/checkout"), 1);
+        assert_eq!(distance("actoins/checkout", "actions/checkout"), 1);
+        assert_eq!(distance("actions/setup-go", "actions/setup-node"), 3);
+    }
+
+    #[test]
+    fn flags_near_misses_but_not_popular_or_unrelated_actions() {
+        let actions: Vec<ActionRef> = [
+            "actons/checkout@v4",
+            "Actions/Checkout@v4",
+            "docker/build-pusher-action@v5",
+            "actions/setup-ruby@v1",
+            "dockr/login-action@v3",
+            "actions/cache/restore@v4",
+            "test-org/leaf-action@v1",
+        ]
+        .iter()
+        .map(|uses| uses.parse().unwrap())
+        .collect();
+        let messages: Vec<String> = check_typosquats(&actions)
+            .into_iter()
+            .map(|f| f.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "actons/checkout differs from the popular action actions/checkout by 1 \
+                 character; check that it is the action you meant",
+                "docker/build-pusher-action differs from the popular action \
+                 docker/build-push-action by 2 characters; check that it is the action you \
+                 meant",
+                "dockr/login-action differs from the popular action docker/login-action by 1 \
+                 character; check that it is the action you meant",
+            ]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! `ghss update`: move each action that has an advisory against it to the
+//! latest release no advisory covers, pinned by commit SHA.
+
+use std::collections::BTreeMap;
+
+use crate::action_ref::ActionRef;
+use crate::output::{ActionEntry, AuditNode};
+use crate::pin::Edit;
+
+/// What `update` will change, and the vulnerable actions it can't fix.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub edits: Vec<Edit>,
+    /// Actions with an advisory against them but no recommended release,
+    /// because every release is affected or the lookup failed.
+    pub unfixed: Vec<ActionRef>,
+}
+
+/// True if an advisory counts against the action as pinned.
+fn vulnerable(entry: &ActionEntry) -> bool {
+    entry.advisories.iter().any(|a| !a.is_suppressed())
+}
+
+/// Plan edits for `refs` (every `uses:` in the workflow) from the audited
+/// `nodes`, which must have run the advisory and recommend stages.
+pub fn plan(refs: &[ActionRef], nodes: &[AuditNode]) -> Plan {
+    let entries: BTreeMap<&ActionRef, &ActionEntry> = nodes
+        .iter()
+        .map(|node| (&node.entry.action, &node.entry))
+        .filter(|(_, entry)| vulnerable(entry))
+        .collect();
+
+    let mut plan = Plan::default();
+    for (action, entry) in &entries {
+        if entry.recommended_ref.is_none() {
+            plan.unfixed.push((*action).clone());
+        }
+    }
+    plan.edits = refs
+        .iter()
+        .filter_map(|action| {
+            let recommended = entries.get(action)?.recommended_ref.as_ref()?;
+            if recommended.sha == action.git_ref {
+                return None;
+            }
+            Edit::pin(action, &recommended.sha, &recommended.tag)
+        })
+        .collect();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::AuditContext;
+    use crate::pin;
+    use crate::stages::recommend::RecommendedRef;
+
+    const SHA: &str = "2222222222222222222222222222222222222222";
+
+    fn node(uses: &str, advisory: Option<Option<bool>>, recommended: bool) -> AuditNode {
+        let advisories = advisory
+            .map(|applicable| crate::advisory::Advisory {
+                applicable,
+                ..crate::advisory::Advisory::for_test("GHSA-test", &[])
+            })
+            .into_iter()
+            .collect();
+        AuditContext {
+            advisories,
+            recommended_ref: recommended.then(|| RecommendedRef {
+                tag: "v2.0.0".to_string(),
+                sha: SHA.to_string(),
+            }),
+            ..AuditContext::for_test(uses.parse().unwrap())
+        }
+        .into()
+    }
+
+    #[test]
+    fn bumps_only_vulnerable_actions_with_a_fix() {
+        let yaml = "jobs:\n  a:\n    steps:\n      - uses: org/vulnerable@v1\n      \
+                    - uses: org/vulnerable@v1 # v1\n      - uses: org/not-applicable@v1\n      \
+                    - uses: org/clean@v1\n      - uses: org/unfixed@v1\n";
+        let refs = pin::third_party_refs(yaml, "ci.yml").unwrap();
+        let nodes = [
+            node("org/vulnerable@v1", Some(Some(true)), true),
+            node("org/not-applicable@v1", Some(Some(false)), true),
+            node("org/clean@v1", None, true),
+            node("org/unfixed@v1", Some(None), false),
+        ];
+
+        let plan = plan(&refs, &nodes);
+        let lines: Vec<usize> = plan.edits.iter().map(|e| e.location.line).collect();
+        assert_eq!(lines, [4, 5]);
+        assert_eq!(plan.edits[0].to, format!("org/vulnerable@{SHA}"));
+        assert_eq!(plan.edits[0].comment, "v2.0.0");
+        assert_eq!(
+            plan.unfixed,
+            ["org/unfixed@v1".parse::<ActionRef>().unwrap()]
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! Incident response: find every workflow in an organization that uses a
+//! given action, and rank the uses by how exposed they are.
+//!
+//! Only `.github/workflows/` of each non-archived repository is searched, on
+//! its default branch. Uses hidden inside composite actions or reusable
+//! workflows from other repositories are not followed.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use anyhow::{Context, Result, bail};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, warn};
+
+use crate::action_ref::{ActionRef, RefType};
+use crate::github::GitHubClient;
+
+const WORKFLOW_DIR: &str = ".github/workflows";
+
+/// Repositories searched at once.
+pub(crate) const REPO_CONCURRENCY: usize = 8;
+
+/// Repositories per page of `GET /orgs/{org}/repos` (the API maximum).
+const PER_PAGE: usize = 100;
+
+/// The action being searched for, and the commits known to be bad.
+#[derive(Debug, Clone)]
+pub struct UsageQuery {
+    pub owner: String,
+    pub repo: String,
+    /// Subdirectory action or workflow path. `None` matches every path.
+    pub path: Option<String>,
+    /// Lowercased commit SHAs or SHA prefixes.
+    pub bad_shas: Vec<String>,
+}
+
+impl UsageQuery {
+    /// Parse `owner/repo[/path]`. The target takes no `@ref`: every ref is
+    /// matched, and `bad_shas` decides which ones are compromised.
+    pub fn new(target: &str, bad_shas: &[String]) -> Result<Self> {
+        if target.contains('@') {
+            bail!("expected owner/repo without a ref, got {target}; use --sha to match commits");
+        }
+        let mut segments = target.splitn(3, '/');
+        let (Some(owner), Some(repo)) = (segments.next(), segments.next()) else {
+            bail!("expected owner/repo, got {target}");
+        };
+        if owner.is_empty() || repo.is_empty() {
+            bail!("expected owner/repo, got {target}");
+        }
+        let bad_shas = bad_shas
+            .iter()
+            .map(|sha| {
+                if sha.len() < 7 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                    bail!("expected a commit SHA of at least 7 hex digits, got {sha}");
+                }
+                Ok(sha.to_ascii_lowercase())
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: segments.next().map(|p| p.trim_end_matches('/').to_string()),
+            bad_shas,
+        })
+    }
+
+    /// True if `action` is a ref to the searched action, at any ref.
+    pub fn matches(&self, action: &ActionRef) -> bool {
+        action.owner.eq_ignore_ascii_case(&self.owner)
+            && action.repo.eq_ignore_ascii_case(&self.repo)
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|p| action.path.as_deref() == Some(p.as_str()))
+    }
+
+    fn is_bad(&self, sha: &str) -> bool {
+        let sha = sha.to_ascii_lowercase();
+        self.bad_shas
+            .iter()
+            .any(|bad| sha.starts_with(bad.as_str()))
+    }
+}
+
+impl fmt::Display for UsageQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.repo)?;
+        if let Some(path) = &self.path {
+            write!(f, "/{path}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How exposed a use is, most urgent first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Exposure {
+    /// The ref resolves to a known-bad commit.
+    Compromised,
+    /// The ref could not be resolved, so it may point at a bad commit.
+    Unresolved,
+    /// A tag or branch, which can be moved to a bad commit at any time.
+    Mutable,
+    /// Pinned to a commit SHA not known to be bad.
+    Pinned,
+}
+
+impl fmt::Display for Exposure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exposure::Compromised => write!(f, "compromised"),
+            Exposure::Unresolved => write!(f, "unresolved"),
+            Exposure::Mutable => write!(f, "mutable"),
+            Exposure::Pinned => write!(f, "pinned"),
+        }
+    }
+}
+
+/// One workflow's use of the searched action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// `owner/name` of the repository the workflow lives in. Not `repo`,
+    /// which the flattened action already uses.
+    pub repository: String,
+    /// Workflow path relative to the repository root.
+    pub workflow: String,
+    #[serde(flatten)]
+    pub action: ActionRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_sha: Option<String>,
+    pub exposure: Exposure,
+}
+
+impl fmt::Display for Usage {
+    /// e.g. `[mutable] org/app: tj-actions/changed-files@v35`, followed by
+    /// one `at` line per occurrence.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            self.exposure, self.repository, self.action
+        )?;
+        if let Some(sha) = &self.resolved_sha
+            && self.action.ref_type != RefType::Sha
+        {
+            write!(f, " (resolves to {sha})")?;
+        }
+        for occurrence in &self.action.occurrences {
+            write!(f, "\n    at {occurrence}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Exposure of one ref. Refs are resolved only when there are bad commits
+/// to compare against.
+fn classify(query: &UsageQuery, action: &ActionRef, resolved: Option<&str>) -> Exposure {
+    match resolved {
+        Some(sha) if query.is_bad(sha) => Exposure::Compromised,
+        None if !query.bad_shas.is_empty() => Exposure::Unresolved,
+        _ if action.ref_type == RefType::Sha => Exposure::Pinned,
+        _ => Exposure::Mutable,
+    }
+}
+
+/// Names of the organization's repositories, skipping archived ones since
+/// their workflows cannot run.
+#[instrument(skip(client))]
+pub async fn list_org_repos(client: &GitHubClient, org: &str) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for page in 1.. {
+        let url = format!(
+            "{}/orgs/{org}/repos?per_page={PER_PAGE}&page={page}",
+            client.api_base_url()
+        );
+        let json = client
+            .api_get(&url)
+            .await
+            .with_context(|| format!("failed to list repositories of {org}"))?;
+        let repos = json
+            .as_array()
+            .context("expected array from organization repositories API")?;
+        names.extend(
+            repos
+                .iter()
+                .filter(|r| r.get("archived").and_then(|a| a.as_bool()) != Some(true))
+                .filter_map(|r| r.get("name")?.as_str().map(String::from)),
+        );
+        if repos.len() < PER_PAGE {
+            break;
+        }
+    }
+    Ok(names)
+}
+
+/// Workflow files of a repository, as paths relative to its root. A
+/// repository without `.github/workflows` has none.
+pub(crate) async fn list_workflows(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+) -> Result<Vec<String>> {
+    let url = format!(
+        "{}/repos/{owner}/{repo}/contents/{WORKFLOW_DIR}",
+        client.api_base_url()
+    );
+    let Some(json) = client.api_get_optional(&url).await? else {
+        return Ok(Vec::new());
+    };
+    let entries = json
+        .as_array()
+        .context("expected array from Contents API")?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| entry.get("name")?.as_str())
+        .filter(|name| name.ends_with(".yml") || name.ends_with(".yaml"))
+        .map(|name| format!("{WORKFLOW_DIR}/{name}"))
+        .collect())
+}
+
+/// A matching ref and the workflow path it was found in.
+type WorkflowRef = (String, ActionRef);
+
+/// Matching refs in one repository's workflows.
+async fn search_repo(
+    client: &GitHubClient,
+    org: &str,
+    repo: &str,
+    query: &UsageQuery,
+) -> Result<Vec<WorkflowRef>> {
+    let mut found = Vec::new();
+    for workflow in list_workflows(client, org, repo).await? {
+        let yaml = client
+            .get_raw_content(org, repo, "HEAD", &workflow)
+            .await
+            .with_context(|| format!("failed to fetch {workflow}"))?;
+        match crate::parse_actions_from(&yaml, &workflow) {
+            Ok(actions) => found.extend(
+                actions
+                    .into_iter()
+                    .filter(|a| query.matches(a))
+                    .map(|a| (workflow.clone(), a)),
+            ),
+            Err(e) => warn!(repo, workflow, error = %e, "skipping unparseable workflow"),
+        }
+    }
+    Ok(found)
+}
+
+/// Search every repository in `org` for uses of the queried action, most
+/// exposed first. Repositories that cannot be read are logged and skipped.
+#[instrument(skip(client), fields(action = %query))]
+pub async fn find_usage(
+    client: &GitHubClient,
+    org: &str,
+    query: &UsageQuery,
+) -> Result<Vec<Usage>> {
+    let repos = list_org_repos(client, org).await?;
+
+    let results: Vec<(String, Result<Vec<WorkflowRef>>)> =
+        futures::stream::iter(repos.into_iter().map(|repo| async move {
+            let found = search_repo(client, org, &repo, query).await;
+            (repo, found)
+        }))
+        .buffer_unordered(REPO_CONCURRENCY)
+        .collect()
+        .await;
+
+    // The same ref is typically used across many repositories; resolve it once.
+    let mut resolved: HashMap<ActionRef, Option<String>> = HashMap::new();
+    let mut usages = Vec::new();
+    for (repo, found) in results {
+        let found = match found {
+            Ok(found) => found,
+            Err(e) => {
+                warn!(
+                    repo,
+                    error = format!("{e:#}"),
+                    "failed to search repository"
+                );
+                continue;
+            }
+        };
+        for (workflow, action) in found {
+            let sha = if query.bad_shas.is_empty() {
+                None
+            } else if let Some(sha) = resolved.get(&action) {
+                sha.clone()
+            } else {
+                let sha = match client.resolve_ref(&action).await {
+                    Ok(sha) => Some(sha),
+                    Err(e) => {
+                        warn!(action = %action, error = %e, "failed to resolve ref");
+                        None
+                    }
+                };
+                resolved.insert(action.clone(), sha.clone());
+                sha
+            };
+            usages.push(Usage {
+                repository: format!("{org}/{repo}"),
+                workflow,
+                exposure: classify(query, &action, sha.as_deref()),
+                resolved_sha: sha,
+                action,
+            });
+        }
+    }
+
+    usages.sort_by(|a, b| {
+        (a.exposure, &a.repository, &a.workflow).cmp(&(b.exposure, &b.repository, &b.workflow))
+    });
+    Ok(usages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BAD: &str = "0e58ed8671d6b60d0890c21b07f8835ace038e67";
+
+    fn query(target: &str, shas: &[&str]) -> UsageQuery {
+        let shas: Vec<String> = shas.iter().map(|s| s.to_string()).collect();
+        UsageQuery::new(target, &shas).unwrap()
+    }
+
+    #[test]
+    fn query_matches_any_ref_of_the_action() {
+        let q = query("tj-actions/changed-files", &[]);
+        assert!(q.matches(&"tj-actions/changed-files@v35".parse().unwrap()));
+        assert!(q.matches(&"TJ-Actions/Changed-Files@main".parse().unwrap()));
+        assert!(!q.matches(&"tj-actions/verify-changed-files@v1".parse().unwrap()));
+    }
+
+    #[test]
+    fn query_with_path_matches_only_that_path() {
+        let q = query("org/tools/.github/workflows/ci.yml", &[]);
+        assert!(q.matches(&"org/tools/.github/workflows/ci.yml@v1".parse().unwrap()));
+        assert!(!q.matches(&"org/tools@v1".parse().unwrap()));
+
+        let q = query("org/tools", &[]);
+        assert!(q.matches(&"org/tools/.github/workflows/ci.yml@v1".parse().unwrap()));
+    }
+
+    #[test]
+    fn query_rejects_refs_and_bad_shas() {
+        assert!(UsageQuery::new("tj-actions/changed-files@v35", &[]).is_err());
+        assert!(UsageQuery::new("tj-actions", &[]).is_err());
+        assert!(UsageQuery::new("a/b", &["0e58".to_string()]).is_err());
+        assert!(UsageQuery::new("a/b", &["not-a-sha".to_string()]).is_err());
+    }
+
+    #[test]
+    fn classify_ranks_exposure() {
+        let tag: ActionRef = "tj-actions/changed-files@v35".parse().unwrap();
+        let pinned: ActionRef = format!("tj-actions/changed-files@{BAD}").parse().unwrap();
+
+        let without_shas = query("tj-actions/changed-files", &[]);
+        assert_eq!(classify(&without_shas, &tag, None), Exposure::Mutable);
+        assert_eq!(classify(&without_shas, &pinned, None), Exposure::Pinned);
+
+        let with_shas = query("tj-actions/changed-files", &["0E58ED8"]);
+        assert_eq!(classify(&with_shas, &tag, Some(BAD)), Exposure::Compromised);
+        assert_eq!(
+            classify(&with_shas, &pinned, Some(BAD)),
+            Exposure::Compromised
+        );
+        assert_eq!(
+            classify(&with_shas, &tag, Some("abc1234")),
+            Exposure::Mutable
+        );
+        assert_eq!(classify(&with_shas, &tag, None), Exposure::Unresolved);
+
+        assert!(Exposure::Compromised < Exposure::Unresolved);
+        assert!(Exposure::Mutable < Exposure::Pinned);
+    }
+
+    #[test]
+    fn usage_display_lists_occurrences() {
+        let mut action = crate::parse_actions_from(
+            "jobs:\n  a:\n    steps:\n      - uses: tj-actions/changed-files@v35\n",
+            ".github/workflows/ci.yml",
+        )
+        .unwrap()
+        .remove(0);
+        action.location = None;
+        let usage = Usage {
+            repository: "org/app".to_string(),
+            workflow: ".github/workflows/ci.yml".to_string(),
+            action,
+            resolved_sha: Some(BAD.to_string()),
+            exposure: Exposure::Compromised,
+        };
+        assert_eq!(
+            usage.to_string(),
+            format!(
+                "[compromised] org/app: tj-actions/changed-files@v35 (resolves to {BAD})\n    at .github/workflows/ci.yml:4:15 (job a)"
+            )
+        );
+    }
+}
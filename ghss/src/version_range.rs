@@ -0,0 +1,276 @@
+//! Whether a pinned version falls within an advisory's `affected_range`.
+//!
+//! Ranges are in the syntax the providers produce: comparators separated by
+//! commas, e.g. `>= 6.0.0, < 8.3.1`. A lower bound opens an interval and an
+//! upper bound closes it, so OSV ranges with several events, like
+//! `< 1.2.0, >= 2.0.0, < 2.1.0`, are a union of intervals.
+
+use std::cmp::Ordering;
+
+use semver::Version;
+
+/// One end of an interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bound {
+    version: Version,
+    inclusive: bool,
+}
+
+/// Versions between two bounds. A missing bound is unbounded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Interval {
+    lower: Option<Bound>,
+    upper: Option<Bound>,
+}
+
+impl Interval {
+    fn contains(&self, v: &Version) -> bool {
+        let above = self.lower.as_ref().is_none_or(|l| match v.cmp(&l.version) {
+            Ordering::Greater => true,
+            Ordering::Equal => l.inclusive,
+            Ordering::Less => false,
+        });
+        let below = self.upper.as_ref().is_none_or(|u| match v.cmp(&u.version) {
+            Ordering::Less => true,
+            Ordering::Equal => u.inclusive,
+            Ordering::Greater => false,
+        });
+        above && below
+    }
+
+    /// True if every version in `[low, high)` is in the interval.
+    fn covers(&self, low: &Version, high: &Version) -> bool {
+        self.lower.as_ref().is_none_or(|l| {
+            if l.inclusive {
+                *low >= l.version
+            } else {
+                *low > l.version
+            }
+        }) && self.upper.as_ref().is_none_or(|u| *high <= u.version)
+    }
+
+    /// True if no version in `[low, high)` is in the interval.
+    fn disjoint(&self, low: &Version, high: &Version) -> bool {
+        let below = self.upper.as_ref().is_some_and(|u| {
+            if u.inclusive {
+                u.version < *low
+            } else {
+                u.version <= *low
+            }
+        });
+        let above = self.lower.as_ref().is_some_and(|l| l.version >= *high);
+        below || above
+    }
+}
+
+/// A pinned version: exact (`v4.1.0`), or a floating tag (`v4`, `v4.1`)
+/// standing for every release from `low` up to, not including, `high`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Pinned {
+    Exact(Version),
+    Span(Version, Version),
+}
+
+impl Pinned {
+    /// True if `release` is one the pinned tag can point to.
+    pub(crate) fn matches(&self, release: &Version) -> bool {
+        match self {
+            Pinned::Exact(v) => v == release,
+            Pinned::Span(low, high) => low <= release && release < high,
+        }
+    }
+
+    /// The oldest release the pinned tag can point to.
+    pub(crate) fn floor(&self) -> &Version {
+        match self {
+            Pinned::Exact(v) | Pinned::Span(v, _) => v,
+        }
+    }
+}
+
+/// `Some(true)` if `version` is within `range`, `Some(false)` if it is
+/// outside, and `None` when either can't be parsed. A floating major or
+/// minor tag is `None` when only some of the releases it can point to are
+/// affected.
+pub fn applies(range: &str, version: &str) -> Option<bool> {
+    let intervals = parse_range(range)?;
+    match parse_pinned(version)? {
+        Pinned::Exact(v) => Some(intervals.iter().any(|i| i.contains(&v))),
+        Pinned::Span(low, high) => {
+            if intervals.iter().any(|i| i.covers(&low, &high)) {
+                Some(true)
+            } else if intervals.iter().all(|i| i.disjoint(&low, &high)) {
+                Some(false)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn parse_range(range: &str) -> Option<Vec<Interval>> {
+    let mut intervals = Vec::new();
+    let mut open: Option<Interval> = None;
+    for part in range.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (op, version) = split_operator(part);
+        let version = parse_version(version)?;
+        match op {
+            ">=" | ">" => {
+                intervals.extend(open.take());
+                open = Some(Interval {
+                    lower: Some(Bound {
+                        version,
+                        inclusive: op == ">=",
+                    }),
+                    upper: None,
+                });
+            }
+            "<" | "<=" => {
+                let mut interval = open.take().unwrap_or_default();
+                interval.upper = Some(Bound {
+                    version,
+                    inclusive: op == "<=",
+                });
+                intervals.push(interval);
+            }
+            _ => {
+                intervals.extend(open.take());
+                let exact = Bound {
+                    version,
+                    inclusive: true,
+                };
+                intervals.push(Interval {
+                    lower: Some(exact.clone()),
+                    upper: Some(exact),
+                });
+            }
+        }
+    }
+    intervals.extend(open);
+    (!intervals.is_empty()).then_some(intervals)
+}
+
+/// `>= 1.0` → (`>=`, `1.0`). A bare version is an exact match (`=`).
+fn split_operator(comparator: &str) -> (&str, &str) {
+    for op in [">=", "<=", ">", "<", "="] {
+        if let Some(rest) = comparator.strip_prefix(op) {
+            return (op, rest.trim());
+        }
+    }
+    ("=", comparator)
+}
+
+/// A version, with a missing minor or patch taken as 0 (`1.0` → `1.0.0`).
+fn parse_version(s: &str) -> Option<Version> {
+    let s = s.trim().trim_start_matches('v');
+    if let Ok(v) = Version::parse(s) {
+        return Some(v);
+    }
+    let parts = numeric_parts(s)?;
+    Some(Version::new(
+        parts[0],
+        parts.get(1).copied().unwrap_or(0),
+        parts.get(2).copied().unwrap_or(0),
+    ))
+}
+
+pub(crate) fn parse_pinned(s: &str) -> Option<Pinned> {
+    let s = s.trim().trim_start_matches('v');
+    if let Ok(v) = Version::parse(s) {
+        return Some(Pinned::Exact(v));
+    }
+    match *numeric_parts(s)?.as_slice() {
+        [major] => Some(Pinned::Span(
+            Version::new(major, 0, 0),
+            Version::new(major.checked_add(1)?, 0, 0),
+        )),
+        [major, minor] => Some(Pinned::Span(
+            Version::new(major, minor, 0),
+            Version::new(major, minor.checked_add(1)?, 0),
+        )),
+        [major, minor, patch] => Some(Pinned::Exact(Version::new(major, minor, patch))),
+        _ => None,
+    }
+}
+
+/// A full, non-prerelease release tag such as `v4.2.0`. Floating tags like
+/// `v4` and prereleases are `None`.
+pub(crate) fn parse_release(tag: &str) -> Option<Version> {
+    Version::parse(tag.trim().trim_start_matches('v'))
+        .ok()
+        .filter(|v| v.pre.is_empty())
+}
+
+/// `4.1` → `[4, 1]`; `None` unless one to three dot-separated numbers.
+fn numeric_parts(s: &str) -> Option<Vec<u64>> {
+    let parts = s
+        .split('.')
+        .map(|p| p.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    (1..=3).contains(&parts.len()).then_some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_versions() {
+        assert_eq!(applies(">= 6.0.0, < 8.3.1", "8.0.0"), Some(true));
+        assert_eq!(applies(">= 6.0.0, < 8.3.1", "v8.3.1"), Some(false));
+        assert_eq!(applies(">= 6.0.0, < 8.3.1", "5.9.9"), Some(false));
+        assert_eq!(applies("<= 3.0.0", "3.0.0"), Some(true));
+        assert_eq!(applies(">= 1.0", "1.0.0"), Some(true));
+        assert_eq!(applies("= 1.2.3", "1.2.4"), Some(false));
+        assert_eq!(applies("<4.17.21", "4.17.20"), Some(true));
+    }
+
+    #[test]
+    fn several_intervals() {
+        let range = "< 1.2.0, >= 2.0.0, < 2.1.0";
+        assert_eq!(applies(range, "1.1.0"), Some(true));
+        assert_eq!(applies(range, "1.5.0"), Some(false));
+        assert_eq!(applies(range, "2.0.5"), Some(true));
+        assert_eq!(applies(range, "2.1.0"), Some(false));
+        // Open-ended last interval.
+        assert_eq!(applies("< 1.0.0, >= 3.0.0", "4.0.0"), Some(true));
+    }
+
+    #[test]
+    fn floating_tags() {
+        assert_eq!(applies("< 46.0.1", "35"), Some(true));
+        assert_eq!(applies("< 46.0.1", "47"), Some(false));
+        // v46 may point at 46.0.0 (affected) or a later fix.
+        assert_eq!(applies("< 46.0.1", "46"), None);
+        assert_eq!(applies(">= 2.0.0, < 2.1.0", "2.1"), Some(false));
+        assert_eq!(applies(">= 2.0.0, < 2.1.0", "2.0"), Some(true));
+    }
+
+    #[test]
+    fn unparseable_is_unknown() {
+        assert_eq!(applies("< 2.0.0", "main"), None);
+        assert_eq!(applies("< 2.0.0", "4-beta"), None);
+        assert_eq!(applies("^1.2", "1.2.3"), None);
+        assert_eq!(applies("", "1.2.3"), None);
+        assert_eq!(applies(">= 2.0.0-rc.1", "2.0.0-rc.2"), Some(true));
+    }
+
+    #[test]
+    fn releases_and_pinned_spans() {
+        assert_eq!(parse_release("v4.2.0"), Some(Version::new(4, 2, 0)));
+        assert_eq!(parse_release("v4"), None);
+        assert_eq!(parse_release("v5.0.0-beta.1"), None);
+
+        let v4 = parse_pinned("4").unwrap();
+        assert!(v4.matches(&Version::new(4, 9, 1)));
+        assert!(!v4.matches(&Version::new(5, 0, 0)));
+        assert_eq!(v4.floor(), &Version::new(4, 0, 0));
+        assert!(
+            parse_pinned("4.1.0")
+                .unwrap()
+                .matches(&Version::new(4, 1, 0))
+        );
+        assert!(parse_pinned(&u64::MAX.to_string()).is_none());
+        assert!(parse_pinned(&format!("4.{}", u64::MAX)).is_none());
+    }
+}
@@ -0,0 +1,814 @@
+//! OpenVEX documents: machine-readable claims about whether a product is
+//! affected by a vulnerability. See <https://github.com/openvex/spec>.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::action_ref::ActionRef;
+use crate::advisory::Advisory;
+use crate::output::AuditNode;
+use crate::stages::Ecosystem;
+
+pub const OPENVEX_CONTEXT: &str = "https://openvex.dev/ns/v0.2.0";
+
+/// Why a product is not affected, from the OpenVEX status justifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Justification {
+    ComponentNotPresent,
+    VulnerableCodeNotPresent,
+    VulnerableCodeNotInExecutePath,
+    VulnerableCodeCannotBeControlledByAdversary,
+    InlineMitigationsAlreadyExist,
+}
+
+impl fmt::Display for Justification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Justification::ComponentNotPresent => "component_not_present",
+            Justification::VulnerableCodeNotPresent => "vulnerable_code_not_present",
+            Justification::VulnerableCodeNotInExecutePath => "vulnerable_code_not_in_execute_path",
+            Justification::VulnerableCodeCannotBeControlledByAdversary => {
+                "vulnerable_code_cannot_be_controlled_by_adversary"
+            }
+            Justification::InlineMitigationsAlreadyExist => "inline_mitigations_already_exist",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VexStatus {
+    NotAffected,
+    Affected,
+    Fixed,
+    UnderInvestigation,
+}
+
+impl fmt::Display for VexStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            VexStatus::NotAffected => "not_affected",
+            VexStatus::Affected => "affected",
+            VexStatus::Fixed => "fixed",
+            VexStatus::UnderInvestigation => "under_investigation",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "VulnerabilityRepr")]
+pub struct VexVulnerability {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+/// OpenVEX before v0.2.0 gave the vulnerability as a bare string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VulnerabilityRepr {
+    Name(String),
+    Object {
+        name: String,
+        #[serde(default)]
+        aliases: Vec<String>,
+    },
+}
+
+impl From<VulnerabilityRepr> for VexVulnerability {
+    fn from(repr: VulnerabilityRepr) -> Self {
+        match repr {
+            VulnerabilityRepr::Name(name) => Self {
+                name,
+                aliases: vec![],
+            },
+            VulnerabilityRepr::Object { name, aliases } => Self { name, aliases },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VexProduct {
+    /// Package URL, e.g. `pkg:npm/lodash@4.17.20`.
+    #[serde(rename = "@id")]
+    pub id: String,
+    /// Alternative identifiers, e.g. `purl`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub identifiers: BTreeMap<String, String>,
+    /// Components of the product the statement is about instead, e.g. an
+    /// action's npm dependency.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subcomponents: Vec<VexProduct>,
+}
+
+impl VexProduct {
+    /// The `@id` and any `purl` identifier, parsed as package URLs.
+    fn purls(&self) -> impl Iterator<Item = Purl> {
+        std::iter::once(self.id.as_str())
+            .chain(self.identifiers.get("purl").map(String::as_str))
+            .filter_map(Purl::parse)
+    }
+
+    /// True if this product, without subcomponents, is `action`.
+    fn is_action(&self, action: &ActionRef, resolved_sha: Option<&str>) -> bool {
+        self.subcomponents.is_empty() && self.purls().any(|p| p.is_action(action, resolved_sha))
+    }
+
+    /// True if this product is the dependency `package`, or is `action` with
+    /// `package` among its subcomponents.
+    fn is_dependency(
+        &self,
+        package: &Purl,
+        action: &ActionRef,
+        resolved_sha: Option<&str>,
+    ) -> bool {
+        if self.subcomponents.is_empty() {
+            self.purls().any(|p| p.covers(package))
+        } else {
+            self.purls().any(|p| p.is_action(action, resolved_sha))
+                && self
+                    .subcomponents
+                    .iter()
+                    .any(|s| s.purls().any(|p| p.covers(package)))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VexStatement {
+    pub vulnerability: VexVulnerability,
+    pub products: Vec<VexProduct>,
+    pub status: VexStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<Justification>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact_statement: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VexDocument {
+    #[serde(rename = "@context")]
+    pub context: String,
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    pub version: u32,
+    pub statements: Vec<VexStatement>,
+}
+
+/// A VEX statement's verdict on one advisory, attached by [`apply`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VexAssessment {
+    pub status: VexStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<Justification>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact_statement: Option<String>,
+    /// `@id` of the document the statement came from.
+    pub document: String,
+}
+
+impl VexAssessment {
+    /// True when the advisory should not fail a run: the product is not
+    /// affected, or runs a fixed version.
+    pub fn suppresses(&self) -> bool {
+        matches!(self.status, VexStatus::NotAffected | VexStatus::Fixed)
+    }
+}
+
+impl fmt::Display for VexAssessment {
+    /// e.g. `not_affected (component_not_present): only used in tests`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(justification) = self.justification {
+            write!(f, " ({justification})")?;
+        }
+        if let Some(statement) = &self.impact_statement {
+            write!(f, ": {statement}")?;
+        }
+        Ok(())
+    }
+}
+
+impl VexDocument {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("invalid OpenVEX document")
+    }
+
+    /// Read a document from a local path or an `http(s)://` URL.
+    pub async fn load(source: &str) -> Result<Self> {
+        let json = if source.starts_with("https://") || source.starts_with("http://") {
+            reqwest::get(source)
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .with_context(|| format!("failed to fetch VEX document: {source}"))?
+                .text()
+                .await
+                .with_context(|| format!("failed to read VEX document: {source}"))?
+        } else {
+            std::fs::read_to_string(source)
+                .with_context(|| format!("failed to read VEX document: {source}"))?
+        };
+        Self::from_json(&json).with_context(|| source.to_string())
+    }
+
+    /// A document authored by ghss. Its `@id` is derived from the statements,
+    /// so the same claims always get the same id.
+    pub fn new(statements: Vec<VexStatement>, timestamp: DateTime<Utc>) -> Self {
+        let digest = Sha256::digest(serde_json::to_vec(&statements).unwrap_or_default());
+        Self {
+            context: OPENVEX_CONTEXT.to_string(),
+            id: format!(
+                "https://openvex.dev/docs/public/ghss-{}",
+                &hex::encode(digest)[..16]
+            ),
+            author: "ghss".to_string(),
+            timestamp,
+            version: 1,
+            statements,
+        }
+    }
+
+    /// `not_affected` statements for every dependency advisory suppressed by
+    /// an override anywhere in the tree.
+    pub fn from_suppressions(nodes: &[AuditNode], timestamp: DateTime<Utc>) -> Self {
+        fn visit(node: &AuditNode, statements: &mut Vec<VexStatement>) {
+            for dep in &node.entry.dep_vulnerabilities {
+                let product = VexProduct {
+                    id: purl(dep.ecosystem, &dep.package, &dep.version),
+                    identifiers: BTreeMap::new(),
+                    subcomponents: vec![],
+                };
+                for s in &dep.not_affected {
+                    let statement = VexStatement {
+                        vulnerability: VexVulnerability {
+                            name: s.advisory.id.clone(),
+                            aliases: s.advisory.aliases.clone(),
+                        },
+                        products: vec![product.clone()],
+                        status: VexStatus::NotAffected,
+                        justification: Some(s.justification),
+                        impact_statement: s.statement.clone(),
+                    };
+                    if !statements.contains(&statement) {
+                        statements.push(statement);
+                    }
+                }
+            }
+            for child in &node.children {
+                visit(child, statements);
+            }
+        }
+
+        let mut statements = Vec::new();
+        for node in nodes {
+            visit(node, &mut statements);
+        }
+        Self::new(statements, timestamp)
+    }
+}
+
+/// Package URL for an action at the ref the workflow uses.
+fn action_purl(action: &ActionRef) -> String {
+    format!(
+        "pkg:githubactions/{}/{}@{}",
+        action.owner,
+        action.repo,
+        action.git_ref.replace('/', "%2F")
+    )
+}
+
+/// A statement on one advisory, from the `--vex` assessment applied to it
+/// if any. Otherwise the advisory is `not_affected` when the pinned version
+/// is outside its range, and `affected` or `under_investigation` as
+/// `in_range` says.
+fn audit_statement(advisory: &Advisory, product: VexProduct, in_range: bool) -> VexStatement {
+    let (status, justification, impact_statement) = match &advisory.vex {
+        Some(vex) => (vex.status, vex.justification, vex.impact_statement.clone()),
+        None if advisory.is_not_applicable() => (
+            VexStatus::NotAffected,
+            Some(Justification::VulnerableCodeNotPresent),
+            advisory
+                .affected_range
+                .as_ref()
+                .map(|range| format!("pinned version is outside the affected range {range}")),
+        ),
+        None if in_range => (VexStatus::Affected, None, None),
+        None => (VexStatus::UnderInvestigation, None, None),
+    };
+    VexStatement {
+        vulnerability: VexVulnerability {
+            name: advisory.id.clone(),
+            aliases: advisory.aliases.clone(),
+        },
+        products: vec![product],
+        status,
+        justification,
+        impact_statement,
+    }
+}
+
+impl VexDocument {
+    /// A statement for every advisory in the audited tree (`--format
+    /// openvex`). Actions are products at the ref the workflow uses, and
+    /// dependencies subcomponents of the action that bundles them.
+    ///
+    /// An action advisory is `affected` when the pinned version is known to
+    /// be in its range, and `under_investigation` when ghss could not tell.
+    /// Dependency advisories were matched on the installed version, so they
+    /// are `affected`, or `not_affected` when an override says so. Statuses
+    /// from `--vex` documents are kept.
+    pub fn from_audit(nodes: &[AuditNode], timestamp: DateTime<Utc>) -> Self {
+        fn push(statements: &mut Vec<VexStatement>, statement: VexStatement) {
+            if !statements.contains(&statement) {
+                statements.push(statement);
+            }
+        }
+
+        fn visit(node: &AuditNode, statements: &mut Vec<VexStatement>) {
+            let entry = &node.entry;
+            let product = VexProduct {
+                id: action_purl(&entry.action),
+                identifiers: BTreeMap::new(),
+                subcomponents: vec![],
+            };
+            for adv in &entry.advisories {
+                let in_range = adv.applicable == Some(true);
+                push(statements, audit_statement(adv, product.clone(), in_range));
+            }
+            for dep in &entry.dep_vulnerabilities {
+                let product = VexProduct {
+                    subcomponents: vec![VexProduct {
+                        id: purl(dep.ecosystem, &dep.package, &dep.version),
+                        identifiers: BTreeMap::new(),
+                        subcomponents: vec![],
+                    }],
+                    ..product.clone()
+                };
+                for adv in &dep.advisories {
+                    push(statements, audit_statement(adv, product.clone(), true));
+                }
+                for s in &dep.not_affected {
+                    push(
+                        statements,
+                        VexStatement {
+                            vulnerability: VexVulnerability {
+                                name: s.advisory.id.clone(),
+                                aliases: s.advisory.aliases.clone(),
+                            },
+                            products: vec![product.clone()],
+                            status: VexStatus::NotAffected,
+                            justification: Some(s.justification),
+                            impact_statement: s.statement.clone(),
+                        },
+                    );
+                }
+            }
+            for child in &node.children {
+                visit(child, statements);
+            }
+        }
+
+        let mut statements = Vec::new();
+        for node in nodes {
+            visit(node, &mut statements);
+        }
+        Self::new(statements, timestamp)
+    }
+}
+
+/// Attach the matching statement from `documents` to every advisory in the
+/// tree, on actions and their dependencies. A statement matches when it
+/// names the advisory's ID or an alias and lists the action
+/// (`pkg:githubactions/owner/repo`) or dependency package URL as a product,
+/// or the action with the dependency as a subcomponent; a product without a
+/// version covers every version. Later statements win.
+/// Returns how many advisories got an assessment.
+pub fn apply(documents: &[VexDocument], nodes: &mut [AuditNode]) -> usize {
+    let mut applied = 0;
+    for node in nodes {
+        let action = &node.entry.action;
+        let resolved = node.entry.resolved_sha.as_deref();
+        for adv in &mut node.entry.advisories {
+            let assessment = assess(documents, adv, |p| p.is_action(action, resolved));
+            applied += usize::from(assessment.is_some());
+            adv.vex = assessment;
+        }
+        for dep in &mut node.entry.dep_vulnerabilities {
+            let Some(expected) = Purl::parse(&purl(dep.ecosystem, &dep.package, &dep.version))
+            else {
+                continue;
+            };
+            for adv in &mut dep.advisories {
+                let assessment = assess(documents, adv, |p| {
+                    p.is_dependency(&expected, action, resolved)
+                });
+                applied += usize::from(assessment.is_some());
+                adv.vex = assessment;
+            }
+        }
+        applied += apply(documents, &mut node.children);
+    }
+    applied
+}
+
+fn assess(
+    documents: &[VexDocument],
+    advisory: &Advisory,
+    product_matches: impl Fn(&VexProduct) -> bool,
+) -> Option<VexAssessment> {
+    let names_advisory = |v: &VexVulnerability| {
+        std::iter::once(&v.name)
+            .chain(&v.aliases)
+            .any(|n| *n == advisory.id || advisory.aliases.contains(n))
+    };
+    documents
+        .iter()
+        .flat_map(|doc| doc.statements.iter().map(move |s| (doc, s)))
+        .filter(|(_, s)| names_advisory(&s.vulnerability))
+        .filter(|(_, s)| s.products.iter().any(&product_matches))
+        .last()
+        .map(|(doc, s)| VexAssessment {
+            status: s.status,
+            justification: s.justification,
+            impact_statement: s.impact_statement.clone(),
+            document: doc.id.clone(),
+        })
+}
+
+/// The parts of a package URL that VEX matching compares. Qualifiers and
+/// subpaths are ignored.
+#[derive(Debug, PartialEq, Eq)]
+struct Purl {
+    kind: String,
+    name: String,
+    version: Option<String>,
+}
+
+impl Purl {
+    fn parse(s: &str) -> Option<Self> {
+        let rest = s.strip_prefix("pkg:")?;
+        let rest = rest.split(['?', '#']).next()?;
+        let (kind, rest) = rest.split_once('/')?;
+        let (name, version) = match rest.rfind('@') {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+        Some(Self {
+            kind: kind.to_ascii_lowercase(),
+            name: name.replace("%40", "@").replace("%2F", "/"),
+            version: version.map(|v| v.replace("%2F", "/")),
+        })
+    }
+
+    /// True if this product names `other`'s package, at its version or at
+    /// any version.
+    fn covers(&self, other: &Purl) -> bool {
+        self.kind == other.kind
+            && self.name == other.name
+            && self
+                .version
+                .as_ref()
+                .is_none_or(|v| Some(v) == other.version.as_ref())
+    }
+
+    /// True if this product is `action`'s repository at its ref, its
+    /// resolved commit, or any version.
+    fn is_action(&self, action: &ActionRef, resolved_sha: Option<&str>) -> bool {
+        matches!(self.kind.as_str(), "githubactions" | "github")
+            && self
+                .name
+                .eq_ignore_ascii_case(&format!("{}/{}", action.owner, action.repo))
+            && self
+                .version
+                .as_deref()
+                .is_none_or(|v| v == action.git_ref || Some(v) == resolved_sha)
+    }
+}
+
+/// Package URL for a dependency. Manifest range operators are dropped from
+/// the version; a version that is not concrete is left out.
+pub fn purl(ecosystem: Ecosystem, package: &str, version: &str) -> String {
+    let kind = match ecosystem {
+        Ecosystem::Npm => "npm",
+        Ecosystem::Cargo => "cargo",
+        Ecosystem::Go => "golang",
+        Ecosystem::Pip => "pypi",
+        Ecosystem::Maven | Ecosystem::Gradle => "maven",
+        Ecosystem::RubyGems => "gem",
+        Ecosystem::Composer => "composer",
+        Ecosystem::Docker => "docker",
+    };
+    let name = package.replace('@', "%40");
+    let version = version.trim_start_matches(['^', '~', '=', '>', '<', 'v', ' ']);
+    if version.starts_with(|c: char| c.is_ascii_digit()) && !version.contains(' ') {
+        format!("pkg:{kind}/{name}@{version}")
+    } else {
+        format!("pkg:{kind}/{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purl_strips_range_and_encodes_scope() {
+        assert_eq!(
+            purl(Ecosystem::Npm, "@actions/core", "^1.10.0"),
+            "pkg:npm/%40actions/core@1.10.0"
+        );
+        assert_eq!(
+            purl(Ecosystem::Go, "golang.org/x/net", "v0.17.0"),
+            "pkg:golang/golang.org/x/net@0.17.0"
+        );
+        assert_eq!(purl(Ecosystem::Npm, "left-pad", "*"), "pkg:npm/left-pad");
+    }
+
+    #[test]
+    fn document_serializes_openvex_fields() {
+        let statement = VexStatement {
+            vulnerability: VexVulnerability {
+                name: "GHSA-35jh-r3h4-6jhm".to_string(),
+                aliases: vec!["CVE-2021-23337".to_string()],
+            },
+            products: vec![VexProduct {
+                id: "pkg:npm/lodash@4.17.20".to_string(),
+                identifiers: BTreeMap::new(),
+                subcomponents: vec![],
+            }],
+            status: VexStatus::NotAffected,
+            justification: Some(Justification::VulnerableCodeNotInExecutePath),
+            impact_statement: None,
+        };
+        let timestamp = "2025-01-01T00:00:00Z".parse().unwrap();
+        let doc = VexDocument::new(vec![statement.clone()], timestamp);
+        assert_eq!(doc, VexDocument::new(vec![statement], timestamp));
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["@context"], OPENVEX_CONTEXT);
+        assert!(
+            json["@id"]
+                .as_str()
+                .unwrap()
+                .starts_with("https://openvex.dev/docs/public/ghss-")
+        );
+        let s = &json["statements"][0];
+        assert_eq!(s["status"], "not_affected");
+        assert_eq!(s["justification"], "vulnerable_code_not_in_execute_path");
+        assert_eq!(s["products"][0]["@id"], "pkg:npm/lodash@4.17.20");
+    }
+
+    const DOCUMENT: &str = r#"{
+        "@context": "https://openvex.dev/ns/v0.2.0",
+        "@id": "https://example.com/vex/1",
+        "author": "Example Security",
+        "timestamp": "2025-03-01T00:00:00Z",
+        "version": 1,
+        "statements": [
+            {
+                "vulnerability": {"name": "CVE-2025-0001"},
+                "products": [{"@id": "pkg:githubactions/example/deploy@v2"}],
+                "status": "not_affected",
+                "justification": "vulnerable_code_not_present"
+            },
+            {
+                "vulnerability": {"name": "GHSA-aaaa-bbbb-cccc"},
+                "products": [{"@id": "urn:example:x", "identifiers": {"purl": "pkg:github/example/deploy"}}],
+                "status": "fixed"
+            },
+            {
+                "vulnerability": {"name": "GHSA-aaaa-bbbb-cccc"},
+                "products": [{"@id": "pkg:githubactions/example/deploy@v1"}],
+                "status": "affected"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn parses_products_with_scopes_and_qualifiers() {
+        assert_eq!(
+            Purl::parse("pkg:npm/%40actions/core@1.10.0?arch=x64"),
+            Some(Purl {
+                kind: "npm".to_string(),
+                name: "@actions/core".to_string(),
+                version: Some("1.10.0".to_string()),
+            })
+        );
+        assert!(Purl::parse("urn:example:x").is_none());
+        let any = Purl::parse("pkg:npm/lodash").unwrap();
+        assert!(any.covers(&Purl::parse("pkg:npm/lodash@4.17.20").unwrap()));
+        assert!(!any.covers(&Purl::parse("pkg:pypi/lodash@1.0").unwrap()));
+    }
+
+    #[test]
+    fn apply_matches_aliases_products_and_later_statements() {
+        let doc = VexDocument::from_json(DOCUMENT).unwrap();
+        let mut nodes = vec![
+            AuditNode::with_advisories(
+                "example/deploy@v2",
+                vec![
+                    Advisory::for_test("GHSA-xxxx-yyyy-zzzz", &["CVE-2025-0001"]),
+                    Advisory::for_test("GHSA-aaaa-bbbb-cccc", &[]),
+                ],
+            ),
+            AuditNode::with_advisories(
+                "example/deploy@v1",
+                vec![Advisory::for_test("GHSA-aaaa-bbbb-cccc", &[])],
+            ),
+            AuditNode::with_advisories(
+                "other/tool@v1",
+                vec![Advisory::for_test("CVE-2025-0001", &[])],
+            ),
+        ];
+        assert_eq!(apply(&[doc], &mut nodes), 3);
+
+        let v2 = &nodes[0].entry.advisories;
+        assert_eq!(
+            v2[0].vex.as_ref().unwrap().to_string(),
+            "not_affected (vulnerable_code_not_present)"
+        );
+        assert!(v2[0].is_vex_suppressed());
+        assert_eq!(v2[1].vex.as_ref().unwrap().status, VexStatus::Fixed);
+
+        // The versioned `affected` statement comes last and wins for v1.
+        let v1 = &nodes[1].entry.advisories[0];
+        assert_eq!(v1.vex.as_ref().unwrap().status, VexStatus::Affected);
+        assert!(!v1.is_vex_suppressed());
+        assert_eq!(
+            v1.vex.as_ref().unwrap().document,
+            "https://example.com/vex/1"
+        );
+
+        assert!(nodes[2].entry.advisories[0].vex.is_none());
+    }
+
+    #[test]
+    fn apply_matches_dependency_purls() {
+        use crate::stages::dependency::DependencyReport;
+
+        let doc = VexDocument::from_json(&DOCUMENT.replace(
+            "pkg:githubactions/example/deploy@v2",
+            "pkg:npm/lodash@4.17.20",
+        ))
+        .unwrap();
+        let mut nodes = vec![AuditNode::with_advisories("example/deploy@v2", vec![])];
+        nodes[0].entry.dep_vulnerabilities = vec![DependencyReport {
+            package: "lodash".to_string(),
+            version: "^4.17.20".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![Advisory::for_test(
+                "GHSA-35jh-r3h4-6jhm",
+                &["CVE-2025-0001"],
+            )],
+            maintenance: None,
+            not_affected: vec![],
+        }];
+        assert_eq!(apply(&[doc], &mut nodes), 1);
+        assert!(nodes[0].entry.dep_vulnerabilities[0].advisories[0].is_vex_suppressed());
+    }
+
+    #[test]
+    fn apply_matches_dependencies_as_action_subcomponents() {
+        use crate::stages::dependency::DependencyReport;
+
+        // v0.0.1 style: the vulnerability is a bare string.
+        let doc = VexDocument::from_json(
+            r#"{
+                "@context": "https://openvex.dev/ns/v0.0.1",
+                "@id": "https://example.com/vex/2",
+                "author": "Example Security",
+                "timestamp": "2025-03-01T00:00:00Z",
+                "version": 1,
+                "statements": [{
+                    "vulnerability": "CVE-2025-0001",
+                    "products": [{
+                        "@id": "pkg:githubactions/example/deploy",
+                        "subcomponents": [{"@id": "pkg:npm/lodash"}]
+                    }],
+                    "status": "not_affected",
+                    "justification": "vulnerable_code_not_in_execute_path"
+                }]
+            }"#,
+        )
+        .unwrap();
+        let dependency = DependencyReport {
+            package: "lodash".to_string(),
+            version: "4.17.20".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![Advisory::for_test(
+                "GHSA-35jh-r3h4-6jhm",
+                &["CVE-2025-0001"],
+            )],
+            maintenance: None,
+            not_affected: vec![],
+        };
+        let mut nodes = vec![
+            AuditNode::with_advisories(
+                "example/deploy@v2",
+                vec![Advisory::for_test(
+                    "GHSA-xxxx-yyyy-zzzz",
+                    &["CVE-2025-0001"],
+                )],
+            ),
+            AuditNode::with_advisories("other/tool@v1", vec![]),
+        ];
+        nodes[0].entry.dep_vulnerabilities = vec![dependency.clone()];
+        nodes[1].entry.dep_vulnerabilities = vec![dependency];
+
+        // Only lodash inside example/deploy: not the action itself, and not
+        // lodash inside another action.
+        assert_eq!(apply(&[doc], &mut nodes), 1);
+        assert!(nodes[0].entry.dep_vulnerabilities[0].advisories[0].is_vex_suppressed());
+        assert!(nodes[0].entry.advisories[0].vex.is_none());
+        assert!(
+            nodes[1].entry.dep_vulnerabilities[0].advisories[0]
+                .vex
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn from_audit_states_each_advisory() {
+        use crate::stages::dependency::DependencyReport;
+
+        let mut in_range = Advisory::for_test("GHSA-aaaa-bbbb-cccc", &["CVE-2025-0001"]);
+        in_range.applicable = Some(true);
+        let mut out_of_range = Advisory::for_test("GHSA-dddd-eeee-ffff", &[]);
+        out_of_range.applicable = Some(false);
+        out_of_range.affected_range = Some("< 2.1.0".to_string());
+        let mut vexed = Advisory::for_test("GHSA-gggg-hhhh-iiii", &[]);
+        vexed.vex = Some(VexAssessment {
+            status: VexStatus::Fixed,
+            justification: None,
+            impact_statement: None,
+            document: "https://example.com/vex/1".to_string(),
+        });
+
+        let mut nodes = vec![AuditNode::with_advisories(
+            "example/deploy@release/v2",
+            vec![
+                in_range,
+                out_of_range,
+                vexed,
+                Advisory::for_test("GHSA-jjjj-kkkk-llll", &[]),
+            ],
+        )];
+        nodes[0].entry.dep_vulnerabilities = vec![DependencyReport {
+            package: "lodash".to_string(),
+            version: "^4.17.20".to_string(),
+            ecosystem: Ecosystem::Npm,
+            advisories: vec![Advisory::for_test("GHSA-35jh-r3h4-6jhm", &[])],
+            maintenance: None,
+            not_affected: vec![],
+        }];
+        let timestamp = "2025-01-01T00:00:00Z".parse().unwrap();
+        let doc = VexDocument::from_audit(&nodes, timestamp);
+
+        let statuses: Vec<_> = doc
+            .statements
+            .iter()
+            .map(|s| (s.vulnerability.name.as_str(), s.status.to_string()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("GHSA-aaaa-bbbb-cccc", "affected".to_string()),
+                ("GHSA-dddd-eeee-ffff", "not_affected".to_string()),
+                ("GHSA-gggg-hhhh-iiii", "fixed".to_string()),
+                ("GHSA-jjjj-kkkk-llll", "under_investigation".to_string()),
+                ("GHSA-35jh-r3h4-6jhm", "affected".to_string()),
+            ]
+        );
+        let product = &doc.statements[0].products[0];
+        assert_eq!(product.id, "pkg:githubactions/example/deploy@release%2Fv2");
+        assert_eq!(
+            doc.statements[1].impact_statement.as_deref(),
+            Some("pinned version is outside the affected range < 2.1.0")
+        );
+        assert_eq!(
+            doc.statements[4].products[0].subcomponents[0].id,
+            "pkg:npm/lodash@4.17.20"
+        );
+
+        // The document's own statements match the same advisories again.
+        for adv in &mut nodes[0].entry.advisories {
+            adv.vex = None;
+        }
+        assert_eq!(apply(&[doc], &mut nodes), 5);
+        assert!(
+            nodes[0].entry.dep_vulnerabilities[0].advisories[0]
+                .vex
+                .is_some()
+        );
+    }
+}
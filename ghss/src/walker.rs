@@ -1,32 +1,101 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use anyhow::bail;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
 use tokio::sync::Semaphore;
-use tracing::{debug, instrument};
+use tracing::{debug, info, instrument, warn};
 
 use crate::action_ref::ActionRef;
+use crate::checkpoint::{self, Checkpoint, Queued};
+use crate::concurrency::AdaptiveConcurrency;
 use crate::context::AuditContext;
+use crate::github::GitHubClient;
 use crate::output::AuditNode;
-use crate::pipeline::Pipeline;
+use crate::pipeline::{self, Pipeline};
+use crate::stages::Phase;
 
 #[cfg(test)]
 type VisitLog = Arc<std::sync::Mutex<Vec<(ActionRef, usize, Option<ActionRef>)>>>;
 
+/// Minimum time between checkpoints written in the middle of a BFS level.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Drives breadth-first traversal of the action dependency graph.
 ///
-/// The Walker takes a `Pipeline` and processes each BFS frontier concurrently
-/// (bounded by `max_concurrency`), tracks visited nodes to prevent cycles,
-/// and produces a `Vec<AuditNode>` tree.
+/// The walk has two passes. The first runs the pipeline's
+/// [`Phase::Expand`] stages on each BFS frontier concurrently (bounded by
+/// `max_concurrency`), tracking visited nodes to prevent cycles. Once the
+/// whole tree is known, the second runs each [`Phase::Enrich`] stage over
+/// every unique node, handing them all to batched stages at once. The
+/// result is a `Vec<AuditNode>` tree.
 pub struct Walker {
     pipeline: Pipeline,
     max_depth: Option<usize>,
     max_concurrency: usize,
+    adaptive: Option<Arc<AdaptiveConcurrency>>,
+    checkpoint_path: Option<PathBuf>,
+    resume: Option<Checkpoint>,
 }
 
-/// Internal record for a node that has been processed by the pipeline.
-struct ProcessedNode {
-    key: ActionRef,
-    context: AuditContext,
+/// Walk state between passes; converted to and from a [`Checkpoint`].
+struct WalkState {
+    fingerprint: String,
+    frontier: Vec<Queued>,
+    expanded: Vec<AuditContext>,
+    root_keys: Vec<ActionRef>,
+    children_order: HashMap<ActionRef, Vec<ActionRef>>,
+    enriched_stages: usize,
+}
+
+impl WalkState {
+    fn from_checkpoint(checkpoint: Checkpoint) -> Self {
+        Self {
+            fingerprint: checkpoint.fingerprint,
+            frontier: checkpoint.frontier,
+            expanded: checkpoint.expanded,
+            root_keys: checkpoint.root_keys,
+            children_order: checkpoint.children_order.into_iter().collect(),
+            enriched_stages: checkpoint.enriched_stages,
+        }
+    }
+
+    /// The state as a checkpoint, with `level` (the BFS level in progress)
+    /// split into the nodes done so far, whose children are queued, and
+    /// those to redo on resume.
+    fn to_checkpoint(
+        &self,
+        level: &[(Queued, Option<AuditContext>)],
+        max_depth: Option<usize>,
+        complete: bool,
+    ) -> Checkpoint {
+        let mut checkpoint = Checkpoint::new(self.fingerprint.clone(), vec![]);
+        checkpoint.expanded = self.expanded.clone();
+        let mut children = Vec::new();
+        for (queued, done) in level {
+            match done {
+                Some(ctx) => {
+                    children.extend(queue_children(ctx, max_depth));
+                    checkpoint.expanded.push(ctx.clone());
+                }
+                None => checkpoint.frontier.push(queued.clone()),
+            }
+        }
+        checkpoint.frontier.extend(self.frontier.iter().cloned());
+        checkpoint.frontier.extend(children);
+        checkpoint.root_keys = self.root_keys.clone();
+        checkpoint.children_order = self
+            .children_order
+            .iter()
+            .map(|(parent, children)| (parent.clone(), children.clone()))
+            .collect();
+        checkpoint.enriched_stages = self.enriched_stages;
+        checkpoint.complete = complete;
+        checkpoint
+    }
 }
 
 impl Walker {
@@ -35,143 +104,302 @@ impl Walker {
             pipeline,
             max_depth,
             max_concurrency,
+            adaptive: None,
+            checkpoint_path: None,
+            resume: None,
         }
     }
 
+    /// Treat `max_concurrency` as a ceiling and lower the number of nodes in
+    /// flight as `client`'s rate-limit headroom runs out.
+    pub fn with_adaptive_concurrency(mut self, client: GitHubClient) -> Self {
+        self.adaptive = Some(Arc::new(AdaptiveConcurrency::new(
+            client,
+            self.max_concurrency,
+        )));
+        self
+    }
+
+    /// Write the walk's progress to `path` periodically during expansion,
+    /// after every enrichment stage, and when the walk finishes.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint_path = Some(path.into());
+        self
+    }
+
+    /// Continue from `checkpoint` instead of starting over. Fails if it was
+    /// written by a walk with different roots, depth limit or stages.
+    pub fn resume(
+        mut self,
+        checkpoint: Checkpoint,
+        root_actions: &[ActionRef],
+    ) -> anyhow::Result<Self> {
+        if checkpoint.fingerprint != self.fingerprint(root_actions) {
+            bail!(
+                "checkpoint was written by a different walk (workflow, depth, or pipeline options changed)"
+            );
+        }
+        self.resume = Some(checkpoint);
+        Ok(self)
+    }
+
+    fn fingerprint(&self, root_actions: &[ActionRef]) -> String {
+        let stages: Vec<&str> = self
+            .pipeline
+            .stages(Phase::Expand)
+            .chain(self.pipeline.stages(Phase::Enrich))
+            .map(|stage| stage.name())
+            .collect();
+        checkpoint::fingerprint(root_actions, self.max_depth, &stages)
+    }
+
     /// Perform a breadth-first walk of the action dependency graph starting
     /// from `root_actions`. Returns a tree of `AuditNode` values.
     #[instrument(skip(self, root_actions), fields(root_count = root_actions.len(), max_depth = ?self.max_depth))]
     pub async fn walk(&self, root_actions: Vec<ActionRef>) -> Vec<AuditNode> {
-        let mut visited: HashSet<ActionRef> = HashSet::new();
         let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+        let mut state = match &self.resume {
+            Some(checkpoint) => {
+                info!(
+                    nodes = checkpoint.node_count(),
+                    queued = checkpoint.frontier.len(),
+                    enriched_stages = checkpoint.enriched_stages,
+                    "resuming walk from checkpoint"
+                );
+                WalkState::from_checkpoint(checkpoint.clone())
+            }
+            None => WalkState::from_checkpoint(Checkpoint::new(
+                self.fingerprint(&root_actions),
+                root_actions,
+            )),
+        };
 
-        // Queue entries: (action, depth, parent_key)
-        let mut frontier: VecDeque<(ActionRef, usize, Option<ActionRef>)> = VecDeque::new();
-        for action in root_actions {
-            frontier.push_back((action, 0, None));
-        }
+        self.expand(&mut state, &semaphore).await;
+        self.enrich(&mut state, &semaphore).await;
+        self.save_checkpoint(&state, &[], true);
+
+        // Build the tree: convert all contexts to AuditNodes, then
+        // attach children to parents using a recursive traversal.
+        let mut all_nodes: HashMap<ActionRef, AuditContext> = state
+            .expanded
+            .into_iter()
+            .map(|ctx| (ctx.action.clone(), ctx))
+            .collect();
+        build_tree(&mut all_nodes, &state.root_keys, &state.children_order)
+    }
 
-        // All processed nodes, keyed by their ActionRef
-        let mut all_nodes: HashMap<ActionRef, ProcessedNode> = HashMap::new();
-        // Track insertion order of root keys for final output ordering
-        let mut root_keys: Vec<ActionRef> = Vec::new();
-        // Track child ordering per parent
-        let mut children_order: HashMap<ActionRef, Vec<ActionRef>> = HashMap::new();
+    /// First pass: BFS over the frontier, running expansion stages on each
+    /// new node and queueing its children.
+    async fn expand(&self, state: &mut WalkState, semaphore: &Arc<Semaphore>) {
+        let mut visited: HashSet<ActionRef> = state
+            .expanded
+            .iter()
+            .map(|ctx| ctx.action.clone())
+            .collect();
+        let mut last_saved = Instant::now();
 
-        while !frontier.is_empty() {
+        while !state.frontier.is_empty() {
             // Drain the current frontier (all nodes at the same depth level)
-            let current_level: Vec<(ActionRef, usize, Option<ActionRef>)> =
-                frontier.drain(..).collect();
+            let current_level: Vec<Queued> = std::mem::take(&mut state.frontier);
 
             // Filter out already-visited actions, mark new ones as visited
-            let mut to_process: Vec<(ActionRef, usize, Option<ActionRef>)> = Vec::new();
-            for (action, depth, parent_key) in current_level {
-                if visited.contains(&action) {
-                    debug!(action = %action, "skipping already-visited action");
+            let mut to_process: Vec<Queued> = Vec::new();
+            for queued in current_level {
+                if !visited.insert(queued.action.clone()) {
+                    debug!(action = %queued.action, "skipping already-visited action");
                     continue;
                 }
-                visited.insert(action.clone());
-                to_process.push((action, depth, parent_key));
+                to_process.push(queued);
             }
 
             if to_process.is_empty() {
                 continue;
             }
 
-            // Track which keys are roots vs children
-            for (action, depth, parent_key) in &to_process {
-                if *depth == 0 {
-                    root_keys.push(action.clone());
+            // Track which keys are roots vs children. A resumed level may
+            // already have been registered before the interruption.
+            for queued in &to_process {
+                if queued.depth == 0 && !state.root_keys.contains(&queued.action) {
+                    state.root_keys.push(queued.action.clone());
                 }
-                if let Some(pk) = parent_key {
-                    children_order
-                        .entry(pk.clone())
-                        .or_default()
-                        .push(action.clone());
+                if let Some(pk) = &queued.parent {
+                    let siblings = state.children_order.entry(pk.clone()).or_default();
+                    if !siblings.contains(&queued.action) {
+                        siblings.push(queued.action.clone());
+                    }
                 }
             }
 
-            // Process all nodes in this frontier concurrently, bounded by semaphore.
+            // Expand all nodes in this frontier concurrently, bounded by semaphore.
             // We clone the pipeline (cheap — stages are Arc'd) and use tokio::spawn
             // so each task owns its data and satisfies 'static.
-            let mut handles = Vec::new();
-            for (action, depth, parent_key) in to_process {
-                let sem = Arc::clone(&semaphore);
+            let mut tasks = FuturesUnordered::new();
+            for (index, queued) in to_process.iter().cloned().enumerate() {
+                let sem = Arc::clone(semaphore);
+                let adaptive = self.adaptive.clone();
                 let pipeline = self.pipeline.clone();
-                let key = action.clone();
-                handles.push(tokio::spawn(async move {
-                    let _permit = sem.acquire().await.expect("semaphore closed unexpectedly");
+                tasks.push(tokio::spawn(async move {
+                    let _permit = match &adaptive {
+                        Some(limiter) => (None, Some(limiter.acquire().await)),
+                        None => (
+                            Some(sem.acquire().await.expect("semaphore closed unexpectedly")),
+                            None,
+                        ),
+                    };
 
                     let mut ctx = AuditContext {
-                        action,
-                        depth,
-                        parent: parent_key,
+                        action: queued.action,
+                        depth: queued.depth,
+                        parent: queued.parent,
                         children: vec![],
                         resolved_ref: None,
                         advisories: vec![],
                         scan: None,
                         dependencies: vec![],
+                        update_hygiene: None,
+                        project: None,
+                        releases: None,
+                        recommended_ref: None,
+                        repository: None,
+                        reputation: None,
+                        scorecard: None,
+                        provenance: None,
+                        signatures: None,
+                        license: None,
+                        tag_protection: None,
+                        repo_hygiene: None,
+                        runtime: None,
+                        findings: vec![],
                         errors: vec![],
                     };
 
-                    pipeline.run_one(&mut ctx).await;
+                    pipeline.run_phase(&mut ctx, Phase::Expand).await;
+                    if let Some(limiter) = &adaptive {
+                        limiter.observe();
+                    }
 
                     debug!(
                         action = %ctx.action,
-                        depth,
+                        depth = ctx.depth,
                         child_count = ctx.children.len(),
-                        "node processed"
+                        "node expanded"
                     );
 
-                    ProcessedNode { key, context: ctx }
+                    (index, ctx)
                 }));
             }
 
-            let results: Vec<ProcessedNode> = futures::future::join_all(handles)
-                .await
+            // Collect results in frontier order so the tree doesn't depend
+            // on which request finished first.
+            let mut level: Vec<(Queued, Option<AuditContext>)> = to_process
                 .into_iter()
-                .map(|r| r.expect("walker task panicked"))
+                .map(|queued| (queued, None))
                 .collect();
-            for processed in results {
-                let depth = processed.context.depth;
-                let children_actions: Vec<ActionRef> = processed.context.children.clone();
-                let node_key = processed.key.clone();
-
-                all_nodes.insert(processed.key.clone(), processed);
-
-                // Enqueue children for the next frontier if depth allows
-                let should_expand = match self.max_depth {
-                    Some(max) => depth < max,
-                    None => true,
-                };
-
-                if should_expand {
-                    for child_action in children_actions {
-                        frontier.push_back((child_action, depth + 1, Some(node_key.clone())));
-                    }
+            while let Some(result) = tasks.next().await {
+                let (index, ctx) = result.expect("walker task panicked");
+                level[index].1 = Some(ctx);
+                if self.checkpoint_path.is_some() && last_saved.elapsed() >= CHECKPOINT_INTERVAL {
+                    self.save_checkpoint(state, &level, false);
+                    last_saved = Instant::now();
                 }
             }
+
+            for (_, ctx) in level {
+                let ctx = ctx.expect("every expansion task returned");
+                state.frontier.extend(queue_children(&ctx, self.max_depth));
+                state.expanded.push(ctx);
+            }
+            self.save_checkpoint(state, &[], false);
+            last_saved = Instant::now();
         }
+    }
 
-        // Build the tree: convert all contexts to AuditNodes, then
-        // attach children to parents using a recursive traversal.
-        build_tree(&mut all_nodes, &root_keys, &children_order)
+    /// Second pass: run each enrichment stage over every unique node, in
+    /// one call for batched stages, otherwise per node with the walk's
+    /// concurrency limit. Stages a resumed checkpoint already ran are
+    /// skipped.
+    #[instrument(skip_all, fields(node_count = state.expanded.len()))]
+    async fn enrich(&self, state: &mut WalkState, semaphore: &Semaphore) {
+        let stages: Vec<_> = self.pipeline.stages(Phase::Enrich).collect();
+        for stage in stages.into_iter().skip(state.enriched_stages) {
+            let contexts = &mut state.expanded;
+            if stage.batched() {
+                debug!(stage = stage.name(), "running batched stage");
+                stage.run_batch(contexts).await;
+            } else {
+                futures::stream::iter(contexts.iter_mut())
+                    .for_each_concurrent(None, |ctx| async move {
+                        let _permit = match &self.adaptive {
+                            Some(limiter) => (None, Some(limiter.acquire().await)),
+                            None => (
+                                Some(
+                                    semaphore
+                                        .acquire()
+                                        .await
+                                        .expect("semaphore closed unexpectedly"),
+                                ),
+                                None,
+                            ),
+                        };
+                        pipeline::run_stage(stage, ctx).await;
+                        if let Some(limiter) = &self.adaptive {
+                            limiter.observe();
+                        }
+                    })
+                    .await;
+            }
+            state.enriched_stages += 1;
+            self.save_checkpoint(state, &[], false);
+        }
+    }
+
+    fn save_checkpoint(
+        &self,
+        state: &WalkState,
+        level: &[(Queued, Option<AuditContext>)],
+        complete: bool,
+    ) {
+        let Some(path) = &self.checkpoint_path else {
+            return;
+        };
+        match state
+            .to_checkpoint(level, self.max_depth, complete)
+            .save(path)
+        {
+            Ok(()) => debug!(path = %path.display(), "checkpoint saved"),
+            Err(e) => warn!(error = %format!("{e:#}"), "failed to save checkpoint"),
+        }
+    }
+}
+
+/// The children of `ctx` to expand next, if depth allows.
+fn queue_children(ctx: &AuditContext, max_depth: Option<usize>) -> Vec<Queued> {
+    if max_depth.is_some_and(|max| ctx.depth >= max) {
+        return vec![];
     }
+    ctx.children
+        .iter()
+        .map(|child| Queued {
+            action: child.clone(),
+            depth: ctx.depth + 1,
+            parent: Some(ctx.action.clone()),
+        })
+        .collect()
 }
 
-/// Recursively build `AuditNode` trees from the flat processed node map.
+/// Recursively build `AuditNode` trees from the flat node map.
 fn build_tree(
-    nodes: &mut HashMap<ActionRef, ProcessedNode>,
+    nodes: &mut HashMap<ActionRef, AuditContext>,
     keys: &[ActionRef],
     children_order: &HashMap<ActionRef, Vec<ActionRef>>,
 ) -> Vec<AuditNode> {
     let mut result = Vec::new();
     for key in keys {
-        if let Some(processed) = nodes.remove(key) {
+        if let Some(context) = nodes.remove(key) {
             let child_keys = children_order.get(key).cloned().unwrap_or_default();
             let children = build_tree(nodes, &child_keys, children_order);
 
-            let mut node = AuditNode::from(processed.context);
+            let mut node = AuditNode::from(context);
             node.children = children;
             result.push(node);
         }
@@ -218,6 +446,10 @@ mod tests {
         fn name(&self) -> &'static str {
             "mock-child"
         }
+
+        fn phase(&self) -> Phase {
+            Phase::Expand
+        }
     }
 
     fn make_walker(
@@ -529,4 +761,214 @@ mod tests {
         assert!(result.is_empty());
         assert!(log.lock().unwrap().is_empty());
     }
+
+    /// Records which actions each call saw: one entry per `run`, or one per
+    /// `run_batch` when batched.
+    struct EnrichStage {
+        batched: bool,
+        calls: Arc<StdMutex<Vec<Vec<String>>>>,
+    }
+
+    #[async_trait]
+    impl Stage for EnrichStage {
+        async fn run(&self, ctx: &mut AuditContext) -> anyhow::Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(vec![ctx.action.to_string()]);
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "enrich"
+        }
+
+        fn batched(&self) -> bool {
+            self.batched
+        }
+
+        async fn run_batch(&self, ctxs: &mut [AuditContext]) {
+            let actions = ctxs.iter().map(|c| c.action.to_string()).collect();
+            self.calls.lock().unwrap().push(actions);
+        }
+    }
+
+    /// Diamond A->[B,C], B->[D], C->[D]: enrichment runs after the whole
+    /// tree is expanded, once per unique node, and batched stages get every
+    /// node in one call.
+    #[tokio::test]
+    async fn enrichment_runs_once_per_unique_node_after_expansion() {
+        let mut child_map = HashMap::new();
+        child_map.insert(
+            action("owner/A@v1"),
+            vec![action("owner/B@v1"), action("owner/C@v1")],
+        );
+        child_map.insert(action("owner/B@v1"), vec![action("owner/D@v1")]);
+        child_map.insert(action("owner/C@v1"), vec![action("owner/D@v1")]);
+
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        let per_node = Arc::new(StdMutex::new(Vec::new()));
+        let batched = Arc::new(StdMutex::new(Vec::new()));
+        let pipeline = PipelineBuilder::new()
+            .stage(EnrichStage {
+                batched: false,
+                calls: Arc::clone(&per_node),
+            })
+            .stage(MockChildStage {
+                child_map,
+                visit_log: Arc::clone(&log),
+            })
+            .stage(EnrichStage {
+                batched: true,
+                calls: Arc::clone(&batched),
+            })
+            .build();
+        let walker = Walker::new(pipeline, None, 1);
+
+        let result = walker.walk(vec![action("owner/A@v1")]).await;
+        assert_eq!(result[0].children.len(), 2);
+        assert_eq!(log.lock().unwrap().len(), 4);
+
+        let per_node: Vec<String> = per_node.lock().unwrap().concat();
+        assert_eq!(
+            per_node,
+            ["owner/A@v1", "owner/B@v1", "owner/C@v1", "owner/D@v1"]
+        );
+        assert_eq!(
+            *batched.lock().unwrap(),
+            [["owner/A@v1", "owner/B@v1", "owner/C@v1", "owner/D@v1"]]
+        );
+    }
+
+    fn checkpoint_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "ghss-checkpoint-{}-{name}.json",
+            std::process::id()
+        ))
+    }
+
+    fn diamond() -> HashMap<ActionRef, Vec<ActionRef>> {
+        let mut child_map = HashMap::new();
+        child_map.insert(
+            action("owner/A@v1"),
+            vec![action("owner/B@v1"), action("owner/C@v1")],
+        );
+        child_map.insert(action("owner/B@v1"), vec![action("owner/D@v1")]);
+        child_map.insert(action("owner/C@v1"), vec![action("owner/D@v1")]);
+        child_map
+    }
+
+    fn tree_shape(nodes: &[AuditNode]) -> Vec<(String, usize)> {
+        let mut shape = Vec::new();
+        for node in nodes {
+            shape.push((node.entry.action.to_string(), node.children.len()));
+            shape.extend(tree_shape(&node.children));
+        }
+        shape
+    }
+
+    /// A finished walk leaves a complete checkpoint; resuming from it runs
+    /// no stages and rebuilds the same tree.
+    #[tokio::test]
+    async fn resume_from_complete_checkpoint_runs_nothing() {
+        let path = checkpoint_path("complete");
+        let roots = vec![action("owner/A@v1")];
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        let walker = make_walker(diamond(), Arc::clone(&log), None).with_checkpoint(&path);
+        let first = walker.walk(roots.clone()).await;
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert!(checkpoint.is_complete());
+        assert_eq!(checkpoint.node_count(), 4);
+
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        let walker = make_walker(diamond(), Arc::clone(&log), None)
+            .resume(checkpoint, &roots)
+            .unwrap();
+        let second = walker.walk(roots).await;
+        assert!(log.lock().unwrap().is_empty());
+        assert_eq!(tree_shape(&first), tree_shape(&second));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// A checkpoint taken with D still queued and no enrichment done
+    /// expands only D and then enriches every node.
+    #[tokio::test]
+    async fn resume_continues_from_frontier() {
+        let path = checkpoint_path("partial");
+        let roots = vec![action("owner/A@v1")];
+        let calls = Arc::new(StdMutex::new(Vec::new()));
+        let pipeline = |log: VisitLog, calls: Arc<StdMutex<Vec<Vec<String>>>>| {
+            PipelineBuilder::new()
+                .stage(MockChildStage {
+                    child_map: diamond(),
+                    visit_log: log,
+                })
+                .stage(EnrichStage {
+                    batched: true,
+                    calls,
+                })
+                .build()
+        };
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        Walker::new(pipeline(Arc::clone(&log), Arc::clone(&calls)), None, 1)
+            .with_checkpoint(&path)
+            .walk(roots.clone())
+            .await;
+
+        // Roll the checkpoint back to just before D was expanded.
+        let mut checkpoint = Checkpoint::load(&path).unwrap();
+        let d = checkpoint.expanded.pop().unwrap();
+        assert_eq!(d.action, action("owner/D@v1"));
+        checkpoint.frontier = vec![Queued {
+            action: d.action,
+            depth: d.depth,
+            parent: d.parent,
+        }];
+        checkpoint.enriched_stages = 0;
+        checkpoint.complete = false;
+
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        let calls = Arc::new(StdMutex::new(Vec::new()));
+        let result = Walker::new(pipeline(Arc::clone(&log), Arc::clone(&calls)), None, 1)
+            .resume(checkpoint, &roots)
+            .unwrap()
+            .walk(roots)
+            .await;
+
+        let visited: Vec<ActionRef> = log.lock().unwrap().iter().map(|v| v.0.clone()).collect();
+        assert_eq!(visited, [action("owner/D@v1")]);
+        assert_eq!(calls.lock().unwrap()[0].len(), 4);
+        assert_eq!(
+            tree_shape(&result),
+            [
+                ("owner/A@v1".to_string(), 2),
+                ("owner/B@v1".to_string(), 1),
+                ("owner/D@v1".to_string(), 0),
+                ("owner/C@v1".to_string(), 0),
+            ]
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn resume_rejects_checkpoint_from_other_walk() {
+        let path = checkpoint_path("mismatch");
+        let log = Arc::new(StdMutex::new(Vec::new()));
+        make_walker(diamond(), Arc::clone(&log), None)
+            .with_checkpoint(&path)
+            .walk(vec![action("owner/A@v1")])
+            .await;
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let walker = make_walker(diamond(), Arc::clone(&log), Some(1));
+        let err = walker
+            .resume(checkpoint.clone(), &[action("owner/A@v1")])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("different walk"), "{err}");
+        let walker = make_walker(diamond(), log, None);
+        assert!(walker.resume(checkpoint, &[action("owner/B@v1")]).is_err());
+    }
 }
@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use crate::action_ref::ActionRef;
+use crate::location::{LocationIndex, Occurrence, SourceLocation};
 
 /// A classified `uses:` reference from a GitHub Actions workflow or composite action.
 #[derive(Debug, Clone, PartialEq)]
@@ -17,13 +19,23 @@ pub enum UsesRef {
     Docker(String),
     /// Third-party action (parsed into ActionRef)
     ThirdParty(ActionRef),
+    /// Reference built from a `${{ }}` expression; unknown until run time
+    Dynamic {
+        raw: String,
+        location: Option<SourceLocation>,
+    },
 }
 
 impl FromStr for UsesRef {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("./") {
+        if s.contains("${{") {
+            Ok(UsesRef::Dynamic {
+                raw: s.to_string(),
+                location: None,
+            })
+        } else if s.starts_with("./") {
             Ok(UsesRef::Local(s.to_string()))
         } else if s.starts_with("docker://") {
             Ok(UsesRef::Docker(s.to_string()))
@@ -45,7 +57,7 @@ impl UsesRef {
 impl fmt::Display for UsesRef {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Local(s) | Self::Docker(s) => f.write_str(s),
+            Self::Local(s) | Self::Docker(s) | Self::Dynamic { raw: s, .. } => f.write_str(s),
             Self::ThirdParty(ar) => write!(f, "{ar}"),
         }
     }
@@ -55,7 +67,87 @@ impl fmt::Display for UsesRef {
 /// Shared between workflow steps and composite action steps.
 #[derive(Debug, Deserialize)]
 pub(crate) struct Step {
+    #[serde(default)]
+    pub name: Option<String>,
     pub uses: Option<String>,
+    #[serde(default)]
+    pub with: Option<serde_yaml::Mapping>,
+    /// Shell script of a `run:` step.
+    #[serde(default)]
+    pub run: Option<String>,
+    #[serde(default)]
+    pub env: Option<serde_yaml::Mapping>,
+}
+
+impl Step {
+    /// The action the step uses, if it has a `uses:` that parses.
+    pub fn action(&self) -> Option<UsesRef> {
+        self.uses.as_deref()?.parse().ok()
+    }
+
+    /// Whether the step uses third-party action `name` (`owner/repo[/path]`).
+    pub fn uses_action(&self, name: &str) -> bool {
+        matches!(self.action(), Some(UsesRef::ThirdParty(a)) if a.package_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the step runs code that can read files in the workspace: a
+    /// `run:` script, a local `./` action, or `actions/github-script`.
+    pub fn runs_code(&self) -> bool {
+        self.run.is_some()
+            || matches!(self.action(), Some(UsesRef::Local(_)))
+            || self.uses_action("actions/github-script")
+    }
+}
+
+/// A raw `uses:` value with the job and step it appears in.
+#[derive(Debug)]
+pub(crate) struct UsesSite {
+    pub job: Option<String>,
+    /// The step's `name:`, if it has one.
+    pub step: Option<String>,
+    pub uses: String,
+    /// What a job-level reusable workflow call passes.
+    pub call: Option<CallArgs>,
+    /// A step's string-valued `with:` inputs.
+    pub with: BTreeMap<String, String>,
+}
+
+/// The `with:` and `secrets:` a job passes to the reusable workflow it calls.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallArgs {
+    /// Input names under `with:`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub secrets: CallSecrets,
+    /// The caller's secrets referenced by the `secrets:` values, e.g.
+    /// `NPM_TOKEN` for `${{ secrets.NPM_TOKEN }}`. Sorted and deduplicated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exposed: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallSecrets {
+    /// `secrets: inherit`: every secret the caller can see.
+    Inherit,
+    /// Secret names under `secrets:`, empty if none are passed.
+    #[serde(untagged)]
+    Named(Vec<String>),
+}
+
+impl Default for CallSecrets {
+    fn default() -> Self {
+        CallSecrets::Named(Vec::new())
+    }
+}
+
+/// The `on.workflow_call` interface of a reusable workflow: declared inputs
+/// and secrets, each mapped to whether it is required.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkflowCallDef {
+    pub inputs: BTreeMap<String, bool>,
+    pub secrets: BTreeMap<String, bool>,
 }
 
 // ─── Workflow schema ───
@@ -65,13 +157,35 @@ pub(crate) struct Job {
     #[serde(default)]
     pub uses: Option<String>,
     #[serde(default)]
+    pub with: Option<serde_yaml::Mapping>,
+    #[serde(default)]
+    pub secrets: Option<serde_yaml::Value>,
+    #[serde(default)]
     pub steps: Option<Vec<Step>>,
+    /// `GITHUB_TOKEN` permissions for this job, overriding the workflow's.
+    #[serde(default)]
+    pub permissions: Option<serde_yaml::Value>,
+    /// Runner labels: a string, a list, or a `group`/`labels` mapping.
+    #[serde(default, rename = "runs-on")]
+    pub runs_on: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub env: Option<serde_yaml::Mapping>,
 }
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Workflow {
+    /// Triggers. A string, a list, or a mapping.
+    #[serde(default)]
+    on: serde_yaml::Value,
+    /// Default `GITHUB_TOKEN` permissions for every job.
     #[serde(default)]
-    jobs: HashMap<String, serde_yaml::Value>,
+    pub permissions: Option<serde_yaml::Value>,
+    /// Environment variables for every job.
+    #[serde(default)]
+    pub env: Option<serde_yaml::Mapping>,
+    /// Kept as a `Mapping` to preserve document order of jobs.
+    #[serde(default)]
+    jobs: serde_yaml::Mapping,
 }
 
 // ─── Composite action schema ───
@@ -94,14 +208,14 @@ pub(crate) struct ActionYaml {
 impl FromStr for Workflow {
     type Err = serde_yaml::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        serde_yaml::from_str(s)
+        from_yaml_with_merges(s)
     }
 }
 
 impl FromStr for ActionYaml {
     type Err = serde_yaml::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        serde_yaml::from_str(s)
+        from_yaml_with_merges(s)
     }
 }
 
@@ -114,17 +228,82 @@ impl TryFrom<serde_yaml::Value> for Job {
 
 // ─── Methods ───
 
+/// Keys of a YAML mapping that are strings.
+fn string_keys(mapping: &serde_yaml::Mapping) -> Vec<String> {
+    mapping
+        .keys()
+        .filter_map(|k| k.as_str().map(String::from))
+        .collect()
+}
+
+/// Secret names read as `secrets.NAME` in an expression.
+pub(crate) fn secret_refs(value: &str) -> impl Iterator<Item = String> + '_ {
+    value.match_indices("secrets.").filter_map(|(i, m)| {
+        let preceded = value[..i]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        let name: String = value[i + m.len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        (!preceded && !name.is_empty()).then_some(name)
+    })
+}
+
+/// Entries of a `with:` mapping whose key and value are both strings.
+pub(crate) fn string_values(with: Option<&serde_yaml::Mapping>) -> BTreeMap<String, String> {
+    with.into_iter()
+        .flatten()
+        .filter_map(|(k, v)| Some((k.as_str()?.to_string(), v.as_str()?.to_string())))
+        .collect()
+}
+
 impl Job {
-    /// All raw `uses:` values from this job (job-level + step-level).
-    pub fn uses_strings(self) -> Vec<String> {
+    /// All raw `uses:` values from this job (job-level + step-level), tagged
+    /// with `job` and the step name where there is one.
+    pub fn uses_sites(self, job: Option<String>) -> Vec<UsesSite> {
         let mut refs = Vec::new();
         if let Some(uses) = self.uses {
-            refs.push(uses);
+            let (secrets, exposed) = match &self.secrets {
+                Some(serde_yaml::Value::String(s)) if s == "inherit" => {
+                    (CallSecrets::Inherit, Vec::new())
+                }
+                Some(serde_yaml::Value::Mapping(m)) => {
+                    let exposed: BTreeSet<String> = m
+                        .values()
+                        .filter_map(|v| v.as_str())
+                        .flat_map(secret_refs)
+                        .collect();
+                    (
+                        CallSecrets::Named(string_keys(m)),
+                        exposed.into_iter().collect(),
+                    )
+                }
+                _ => (CallSecrets::default(), Vec::new()),
+            };
+            refs.push(UsesSite {
+                job: job.clone(),
+                step: None,
+                uses,
+                call: Some(CallArgs {
+                    inputs: self.with.as_ref().map(string_keys).unwrap_or_default(),
+                    secrets,
+                    exposed,
+                }),
+                with: BTreeMap::new(),
+            });
         }
         if let Some(steps) = self.steps {
             for step in steps {
                 if let Some(uses) = step.uses {
-                    refs.push(uses);
+                    refs.push(UsesSite {
+                        job: job.clone(),
+                        step: step.name,
+                        uses,
+                        call: None,
+                        with: string_values(step.with.as_ref()),
+                    });
                 }
             }
         }
@@ -133,14 +312,16 @@ impl Job {
 }
 
 impl Workflow {
-    /// All raw `uses:` values. Malformed jobs warn and skip.
+    /// All raw `uses:` values with their job and step. Malformed jobs warn and skip.
     /// Consumes self to avoid cloning serde_yaml::Value.
-    pub fn uses_strings(self) -> Vec<String> {
+    pub fn uses_strings(self) -> Vec<UsesSite> {
         let mut refs = Vec::new();
         for (job_name, job_value) in self.jobs {
+            let job_name = job_name.as_str().map(str::to_string);
             match Job::try_from(job_value) {
-                Ok(job) => refs.extend(job.uses_strings()),
+                Ok(job) => refs.extend(job.uses_sites(job_name.clone())),
                 Err(e) => {
+                    let job_name = job_name.as_deref().unwrap_or("<non-string key>");
                     warn!(job = %job_name, error = %e, "failed to parse job");
                 }
             }
@@ -149,6 +330,77 @@ impl Workflow {
     }
 }
 
+impl Workflow {
+    /// Jobs by name, in document order. Malformed jobs are skipped;
+    /// [`Workflow::uses_strings`] warns about them.
+    pub fn jobs(&self) -> Vec<(String, Job)> {
+        self.jobs
+            .iter()
+            .filter_map(|(name, job)| {
+                Some((name.as_str()?.to_string(), Job::try_from(job.clone()).ok()?))
+            })
+            .collect()
+    }
+
+    /// Names of the events that trigger the workflow, e.g. `push`.
+    pub fn events(&self) -> Vec<String> {
+        match &self.on {
+            serde_yaml::Value::String(s) => vec![s.clone()],
+            serde_yaml::Value::Sequence(events) => events
+                .iter()
+                .filter_map(|e| e.as_str().map(String::from))
+                .collect(),
+            serde_yaml::Value::Mapping(events) => string_keys(events),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The workflows named under `on.workflow_run.workflows`, whose runs
+    /// trigger this one.
+    pub fn workflow_run_sources(&self) -> Vec<String> {
+        self.on
+            .get("workflow_run")
+            .and_then(|run| run.get("workflows"))
+            .and_then(|w| w.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|w| w.as_str().map(String::from))
+            .collect()
+    }
+
+    /// The `on.workflow_call` interface, or None if the workflow cannot be called.
+    pub fn workflow_call(&self) -> Option<WorkflowCallDef> {
+        let declared = |call: &serde_yaml::Value, key: &str| -> BTreeMap<String, bool> {
+            call.get(key)
+                .and_then(|v| v.as_mapping())
+                .into_iter()
+                .flatten()
+                .filter_map(|(name, spec)| {
+                    let required = spec.get("required").and_then(|r| r.as_bool());
+                    Some((name.as_str()?.to_string(), required.unwrap_or(false)))
+                })
+                .collect()
+        };
+        match &self.on {
+            serde_yaml::Value::String(s) if s == "workflow_call" => {
+                Some(WorkflowCallDef::default())
+            }
+            serde_yaml::Value::Sequence(events) => events
+                .iter()
+                .any(|e| e.as_str() == Some("workflow_call"))
+                .then(WorkflowCallDef::default),
+            serde_yaml::Value::Mapping(events) => {
+                let call = events.get("workflow_call")?;
+                Some(WorkflowCallDef {
+                    inputs: declared(call, "inputs"),
+                    secrets: declared(call, "secrets"),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 impl ActionYaml {
     /// Returns composite steps, or None if not a composite action.
     /// Returns Some(vec![]) if composite but has no steps.
@@ -160,16 +412,60 @@ impl ActionYaml {
 
 // ─── Helpers ───
 
-/// Classify raw `uses:` strings into UsesRef variants. Warns and skips unparseable refs.
-fn classify_uses(raw: impl IntoIterator<Item = String>) -> Vec<UsesRef> {
+/// Deserialize YAML after resolving `<<:` merge keys. serde_yaml expands
+/// `*alias` references on its own but leaves merge keys as literal `<<`
+/// entries, which would drop any `steps:` or `uses:` pulled in through them.
+fn from_yaml_with_merges<T: DeserializeOwned>(s: &str) -> Result<T, serde_yaml::Error> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(s)?;
+    value.apply_merge()?;
+    serde_yaml::from_value(value)
+}
+
+/// Classify raw `uses:` strings (with their job and step) into UsesRef variants.
+/// Warns and skips unparseable refs. Third-party and dynamic refs are tagged
+/// with their position under `root` in `yaml` (and `file`, if given);
+/// third-party refs also get a single [`Occurrence`].
+fn classify_uses(
+    raw: impl IntoIterator<Item = UsesSite>,
+    yaml: &str,
+    root: &str,
+    file: Option<&str>,
+) -> Vec<UsesRef> {
+    let mut locations = LocationIndex::for_key(yaml, root, "uses");
     raw.into_iter()
-        .filter_map(|s| match s.parse::<UsesRef>() {
-            Ok(r) => Some(r),
-            Err(e) => {
-                warn!(uses = %s, error = %e, "failed to parse uses reference");
-                None
-            }
-        })
+        .filter_map(
+            |UsesSite {
+                 job,
+                 step,
+                 uses: s,
+                 call,
+                 with,
+             }| {
+                let location = locations.take(&s).map(|loc| match file {
+                    Some(f) => loc.with_file(f),
+                    None => loc,
+                });
+                match s.parse::<UsesRef>() {
+                    Ok(UsesRef::ThirdParty(mut ar)) => {
+                        ar.location = location;
+                        ar.occurrences = vec![Occurrence {
+                            job,
+                            step,
+                            location: ar.location.clone(),
+                            call,
+                            with,
+                        }];
+                        Some(UsesRef::ThirdParty(ar))
+                    }
+                    Ok(UsesRef::Dynamic { raw, .. }) => Some(UsesRef::Dynamic { raw, location }),
+                    Ok(r) => Some(r),
+                    Err(e) => {
+                        warn!(uses = %s, error = %e, "failed to parse uses reference");
+                        None
+                    }
+                }
+            },
+        )
         .collect()
 }
 
@@ -179,7 +475,25 @@ fn classify_uses(raw: impl IntoIterator<Item = String>) -> Vec<UsesRef> {
 /// Malformed jobs warn and skip. Unparseable third-party refs warn and skip.
 pub fn parse_workflow(yaml: &str) -> anyhow::Result<Vec<UsesRef>> {
     let workflow: Workflow = yaml.parse()?;
-    Ok(classify_uses(workflow.uses_strings()))
+    Ok(classify_uses(workflow.uses_strings(), yaml, "jobs", None))
+}
+
+/// Like [`parse_workflow`], but records `file` in each third-party ref's location.
+pub fn parse_workflow_file(yaml: &str, file: &str) -> anyhow::Result<Vec<UsesRef>> {
+    let workflow: Workflow = yaml.parse()?;
+    Ok(classify_uses(
+        workflow.uses_strings(),
+        yaml,
+        "jobs",
+        Some(file),
+    ))
+}
+
+/// Parse the `on.workflow_call` interface of a reusable workflow. Returns
+/// None if the workflow has no `workflow_call` trigger.
+pub fn parse_workflow_call(yaml: &str) -> anyhow::Result<Option<WorkflowCallDef>> {
+    let workflow: Workflow = yaml.parse()?;
+    Ok(workflow.workflow_call())
 }
 
 /// Parse a workflow YAML and return only third-party ActionRefs.
@@ -194,18 +508,61 @@ pub fn parse_workflow_refs(yaml: &str) -> anyhow::Result<Vec<ActionRef>> {
 /// Parse a composite action YAML.
 /// Returns None if not composite. Returns Some(refs) with third-party ActionRefs if composite.
 pub fn parse_composite_action(yaml: &str) -> anyhow::Result<Option<Vec<ActionRef>>> {
+    Ok(composite_uses(yaml, None)?.map(|refs| {
+        refs.into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .collect()
+    }))
+}
+
+/// Like [`parse_composite_action`], but returns every classified uses ref
+/// (including local ones) and records `file` in their locations.
+pub fn parse_composite_action_file(yaml: &str, file: &str) -> anyhow::Result<Option<Vec<UsesRef>>> {
+    composite_uses(yaml, Some(file))
+}
+
+/// Events an outside contributor can trigger that run in the base
+/// repository's context: with its secrets, a token that can write, and the
+/// default branch's cache scope.
+pub const UNTRUSTED_EVENTS: &[&str] = &[
+    "pull_request_target",
+    "workflow_run",
+    "issue_comment",
+    "issues",
+    "discussion",
+    "discussion_comment",
+];
+
+/// `runs.using` values GitHub Actions has retired.
+pub const EOL_RUNTIMES: &[&str] = &["node12", "node16"];
+
+/// An action's `runs.using`, e.g. `node20` or `composite`.
+pub fn action_runtime(yaml: &str) -> anyhow::Result<Option<String>> {
+    let action: ActionYaml = yaml.parse()?;
+    Ok(action.runs.map(|runs| runs.using))
+}
+
+fn composite_uses(yaml: &str, file: Option<&str>) -> anyhow::Result<Option<Vec<UsesRef>>> {
     let action: ActionYaml = yaml.parse()?;
 
     let Some(steps) = action.into_composite_steps() else {
         return Ok(None);
     };
 
-    let children = classify_uses(steps.into_iter().filter_map(|step| step.uses))
-        .into_iter()
-        .filter_map(UsesRef::into_third_party)
-        .collect();
-
-    Ok(Some(children))
+    Ok(Some(classify_uses(
+        steps.into_iter().filter_map(|step| {
+            step.uses.map(|uses| UsesSite {
+                job: None,
+                step: step.name,
+                uses,
+                call: None,
+                with: string_values(step.with.as_ref()),
+            })
+        }),
+        yaml,
+        "runs",
+        file,
+    )))
 }
 
 #[cfg(test)]
@@ -234,6 +591,33 @@ mod tests {
         assert_eq!(r, UsesRef::Docker("docker://node:18".to_string()));
     }
 
+    #[test]
+    fn uses_ref_parses_dynamic() {
+        let r: UsesRef = "${{ matrix.action }}".parse().unwrap();
+        assert_eq!(
+            r,
+            UsesRef::Dynamic {
+                raw: "${{ matrix.action }}".to_string(),
+                location: None,
+            }
+        );
+        let r: UsesRef = "actions/${{ inputs.name }}@v1".parse().unwrap();
+        assert!(matches!(r, UsesRef::Dynamic { .. }));
+    }
+
+    #[test]
+    fn parse_workflow_file_locates_dynamic_refs() {
+        let yaml = "jobs:\n  build:\n    steps:\n      - uses: ${{ matrix.action }}\n";
+        let refs = parse_workflow_file(yaml, "ci.yml").unwrap();
+        assert_eq!(
+            refs,
+            [UsesRef::Dynamic {
+                raw: "${{ matrix.action }}".to_string(),
+                location: Some(SourceLocation::new(4, 15).with_file("ci.yml")),
+            }]
+        );
+    }
+
     #[test]
     fn uses_ref_parses_third_party() {
         let r: UsesRef = "actions/checkout@v4".parse().unwrap();
@@ -302,6 +686,272 @@ mod tests {
         assert_eq!(refs.len(), 4);
     }
 
+    #[test]
+    fn parse_workflow_records_uses_locations() {
+        let yaml = r#"
+name: CI
+on: push
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - run: make
+      - uses: actions/setup-node@v4
+  call:
+    uses: org/shared/.github/workflows/ci.yml@v1
+"#;
+        let refs = parse_workflow_file(yaml, ".github/workflows/ci.yml").unwrap();
+        let locations: Vec<String> = refs
+            .into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .map(|ar| ar.location.expect("location recorded").to_string())
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                ".github/workflows/ci.yml:8:15",
+                ".github/workflows/ci.yml:10:15",
+                ".github/workflows/ci.yml:12:11",
+            ]
+        );
+    }
+
+    #[test]
+    fn anchored_step_reused_in_two_jobs_is_located_per_job() {
+        let yaml = r#"
+on: push
+x-checkout: &co {uses: actions/checkout@v4}
+jobs:
+  a:
+    runs-on: ubuntu-latest
+    steps:
+      - *co
+  b:
+    runs-on: ubuntu-latest
+    steps:
+      - *co
+"#;
+        let refs = parse_workflow_file(yaml, "ci.yml").unwrap();
+        let sites: Vec<(Option<String>, String)> = refs
+            .into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .map(|ar| {
+                let occurrence = &ar.occurrences[0];
+                (
+                    occurrence.job.clone(),
+                    occurrence.location.as_ref().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            sites,
+            vec![
+                (Some("a".to_string()), "ci.yml:8:9".to_string()),
+                (Some("b".to_string()), "ci.yml:12:9".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_workflow_duplicate_refs_get_distinct_locations() {
+        let refs = parse_workflow(&read_fixture("sample-workflow.yml")).unwrap();
+        let mut lines: Vec<usize> = refs
+            .into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .filter(|ar| ar.to_string() == "actions/checkout@v4")
+            .map(|ar| ar.location.unwrap().line)
+            .collect();
+        lines.dedup();
+        assert_eq!(lines.len(), 3);
+    }
+
+    // ─── YAML anchors and merge keys ───
+
+    #[test]
+    fn parse_workflow_expands_anchored_step_lists() {
+        let yaml = r#"
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps: &setup
+      - uses: actions/checkout@v4
+      - uses: actions/setup-node@v4
+  test:
+    runs-on: ubuntu-latest
+    steps: *setup
+"#;
+        let refs = parse_workflow(yaml).unwrap();
+        let raw: Vec<String> = refs.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            raw,
+            [
+                "actions/checkout@v4",
+                "actions/setup-node@v4",
+                "actions/checkout@v4",
+                "actions/setup-node@v4",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_workflow_expands_aliased_steps() {
+        let yaml = r#"
+jobs:
+  build:
+    steps:
+      - &checkout
+        uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - uses: actions/setup-node@v4
+  lint:
+    steps:
+      - *checkout
+      - uses: github/super-linter@v5
+"#;
+        let refs = parse_workflow(yaml).unwrap();
+        let raw: Vec<String> = refs.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            raw,
+            [
+                "actions/checkout@v4",
+                "actions/setup-node@v4",
+                "actions/checkout@v4",
+                "github/super-linter@v5",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_workflow_resolves_merge_keys_in_jobs() {
+        let yaml = r#"
+x-defaults: &defaults
+  runs-on: ubuntu-latest
+  steps:
+    - uses: actions/checkout@v4
+jobs:
+  build:
+    <<: *defaults
+  deploy:
+    <<: *defaults
+    steps:
+      - uses: actions/deploy-pages@v4
+"#;
+        let refs = parse_workflow(yaml).unwrap();
+        let raw: Vec<String> = refs.iter().map(ToString::to_string).collect();
+        // Keys set on the job itself win over merged ones.
+        assert_eq!(raw, ["actions/checkout@v4", "actions/deploy-pages@v4"]);
+    }
+
+    #[test]
+    fn parse_workflow_anchored_refs_get_alias_locations() {
+        let yaml = r#"
+jobs:
+  build:
+    steps: &setup
+      - uses: actions/checkout@v4
+  test:
+    steps: *setup
+"#;
+        let lines: Vec<usize> = parse_workflow(yaml)
+            .unwrap()
+            .into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .map(|ar| ar.location.unwrap().line)
+            .collect();
+        assert_eq!(lines, [5, 7]);
+    }
+
+    #[test]
+    fn parse_composite_action_resolves_merge_keys() {
+        let yaml = r#"
+x-runs: &runs
+  using: composite
+  steps:
+    - uses: actions/cache@v4
+runs:
+  <<: *runs
+"#;
+        let children = parse_composite_action(yaml).unwrap().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].to_string(), "actions/cache@v4");
+    }
+
+    // ─── Reusable workflow calls ───
+
+    #[test]
+    fn parse_workflow_call_reads_interface() {
+        let def = parse_workflow_call(
+            "on:\n  push:\n  workflow_call:\n    inputs:\n      env:\n        required: true\n      debug: {}\n    secrets:\n      token:\n",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            def.inputs,
+            BTreeMap::from([("debug".to_string(), false), ("env".to_string(), true)])
+        );
+        assert_eq!(def.secrets, BTreeMap::from([("token".to_string(), false)]));
+
+        assert_eq!(
+            parse_workflow_call("on: workflow_call\njobs: {}\n").unwrap(),
+            Some(WorkflowCallDef::default())
+        );
+        assert!(
+            parse_workflow_call("on: [push, workflow_call]\n")
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(parse_workflow_call("on: push\n").unwrap(), None);
+    }
+
+    #[test]
+    fn job_level_calls_record_with_and_secrets() {
+        let yaml = r#"
+jobs:
+  deploy:
+    uses: org/shared/.github/workflows/deploy.yml@v1
+    with:
+      env: prod
+    secrets: inherit
+  release:
+    uses: org/shared/.github/workflows/release.yml@v1
+    secrets:
+      token: ${{ secrets.TOKEN }}
+      npm: ${{ secrets.NPM_TOKEN || secrets.TOKEN }}
+      plain: ${{ inputs.secrets.X }}
+  build:
+    steps:
+      - uses: actions/checkout@v4
+"#;
+        let calls: Vec<Option<CallArgs>> = parse_workflow(yaml)
+            .unwrap()
+            .into_iter()
+            .filter_map(UsesRef::into_third_party)
+            .map(|ar| ar.occurrences[0].call.clone())
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                Some(CallArgs {
+                    inputs: vec!["env".to_string()],
+                    secrets: CallSecrets::Inherit,
+                    exposed: vec![],
+                }),
+                Some(CallArgs {
+                    inputs: vec![],
+                    secrets: CallSecrets::Named(vec![
+                        "token".to_string(),
+                        "npm".to_string(),
+                        "plain".to_string()
+                    ]),
+                    exposed: vec!["NPM_TOKEN".to_string(), "TOKEN".to_string()],
+                }),
+                None,
+            ]
+        );
+    }
+
     // ─── parse_workflow_refs tests (migrated from workflow_expand.rs) ───
 
     #[test]
@@ -0,0 +1,98 @@
+//! Third-party actions in workflows that `workflow_run` triggers.
+//!
+//! A `workflow_run` workflow runs from the default branch with its secrets
+//! and a token that can write, even when the run that triggered it built a
+//! fork's pull request. Every third-party action in it therefore runs
+//! inside that trust boundary, often fed data from the untrusted run.
+//! Actions owned by `actions` and `github` are maintained by GitHub and not
+//! reported.
+
+use std::collections::BTreeSet;
+
+use crate::finding::{Finding, WORKFLOW_RUN_ACTION};
+use crate::workflow::{self, UsesRef, Workflow, secret_refs};
+
+/// Owners whose actions GitHub maintains.
+const GITHUB_OWNERS: &[&str] = &["actions", "github"];
+
+/// Raise [`WORKFLOW_RUN_ACTION`] for each third-party action or reusable
+/// workflow used by a `workflow_run` workflow, located at its `uses:`.
+pub fn check_workflow_run_actions(yaml: &str, file: &str) -> anyhow::Result<Vec<Finding>> {
+    let workflow: Workflow = yaml.parse()?;
+    if !workflow.events().iter().any(|e| e == "workflow_run") {
+        return Ok(Vec::new());
+    }
+    let sources = workflow.workflow_run_sources();
+    let trigger = if sources.is_empty() {
+        "workflow_run".to_string()
+    } else {
+        format!("runs of {}", sources.join(", "))
+    };
+
+    let mut findings = Vec::new();
+    for action in workflow::parse_workflow_file(yaml, file)?
+        .into_iter()
+        .filter_map(UsesRef::into_third_party)
+    {
+        if GITHUB_OWNERS
+            .iter()
+            .any(|o| o.eq_ignore_ascii_case(&action.owner))
+        {
+            continue;
+        }
+        for occurrence in &action.occurrences {
+            let job = occurrence.job.as_deref().unwrap_or("<unnamed>");
+            let mut secrets: BTreeSet<String> = occurrence
+                .with
+                .values()
+                .flat_map(|v| secret_refs(v))
+                .collect();
+            if let Some(call) = &occurrence.call {
+                secrets.extend(call.exposed.iter().cloned());
+            }
+            let mut message = format!(
+                "{action} in job `{job}` runs with the default branch's secrets and token in a workflow triggered by {trigger}"
+            );
+            if !secrets.is_empty() {
+                let names: Vec<String> = secrets.into_iter().collect();
+                message.push_str(&format!(", and is passed {}", names.join(", ")));
+            }
+            findings.push(
+                Finding::new(&WORKFLOW_RUN_ACTION, message)
+                    .with_location(occurrence.location.clone()),
+            );
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(yaml: &str) -> Vec<String> {
+        check_workflow_run_actions(yaml, "report.yml")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn flags_third_party_actions_with_what_they_are_passed() {
+        let yaml = "on:\n  workflow_run:\n    workflows: [CI, Tests]\n    types: [completed]\njobs:\n  report:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/download-artifact@v4\n      - uses: marocchino/sticky-pull-request-comment@v2\n        with:\n          GITHUB_TOKEN: ${{ secrets.BOT_TOKEN }}\n      - uses: github/codeql-action/upload-sarif@v3\n  notify:\n    uses: org/shared/.github/workflows/notify.yml@main\n    secrets:\n      webhook: ${{ secrets.SLACK_WEBHOOK }}\n";
+        assert_eq!(
+            check(yaml),
+            [
+                "workflow-run-action (medium): marocchino/sticky-pull-request-comment@v2 in job `report` runs with the default branch's secrets and token in a workflow triggered by runs of CI, Tests, and is passed BOT_TOKEN\n    at report.yml:10:15",
+                "workflow-run-action (medium): org/shared/.github/workflows/notify.yml@main in job `notify` runs with the default branch's secrets and token in a workflow triggered by runs of CI, Tests, and is passed SLACK_WEBHOOK\n    at report.yml:15:11",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_other_triggers() {
+        let yaml = "on: push\njobs:\n  report:\n    steps:\n      - uses: marocchino/sticky-pull-request-comment@v2\n";
+        assert!(check(yaml).is_empty());
+    }
+}